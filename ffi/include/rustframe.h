@@ -0,0 +1,85 @@
+/*
+ * rustframe.h - C API of RustFrame's region capture
+ *
+ * Link against rustframe_ffi.dll.lib and ship rustframe_ffi.dll next to the
+ * application. Pixels are BGRA, one uint32_t per pixel, rows without padding.
+ *
+ *   RfSession *session = rf_session_create(100, 100, 1280, 720, true, 60);
+ *   if (rf_session_start(session) == RF_OK) {
+ *       RfFrame frame;
+ *       while (rf_session_get_frame(session, 1000, &frame) == RF_OK) {
+ *           // frame.pixels stays valid until the next call for this session
+ *       }
+ *       rf_session_stop(session);
+ *   } else {
+ *       printf("%s\n", rf_last_error());
+ *   }
+ *   rf_session_destroy(session);
+ *
+ * A session must only be used by one thread at a time.
+ */
+
+#ifndef RUSTFRAME_H
+#define RUSTFRAME_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct RfSession RfSession;
+
+typedef enum RfResult {
+    RF_OK = 0,
+    /* rf_session_get_frame: no new frame within the timeout */
+    RF_NO_FRAME = 1,
+    RF_ERROR_INVALID_ARGUMENT = -1,
+    /* rf_session_get_frame / rf_session_stop before rf_session_start */
+    RF_ERROR_NOT_STARTED = -2,
+    /* Windows.Graphics.Capture is not available (Windows 10 1903 or later) */
+    RF_ERROR_UNSUPPORTED = -3,
+    /* GPU reset or removed - stop and start the session again */
+    RF_ERROR_DEVICE_LOST = -4,
+    RF_ERROR_INVALID_REGION = -5,
+    RF_ERROR_PERMISSION_DENIED = -6,
+    /* Other Windows API failure - see rf_last_error() */
+    RF_ERROR_BACKEND = -7,
+    RF_ERROR_PANIC = -8
+} RfResult;
+
+typedef struct RfFrame {
+    const uint32_t *pixels;
+    uint32_t width;
+    uint32_t height;
+    /* Frames delivered by the session so far (1 for the first frame) */
+    uint64_t number;
+} RfFrame;
+
+/* Create a session for a screen region (virtual screen coordinates).
+ * fps = 0 delivers every captured frame. Returns NULL for an empty region. */
+RfSession *rf_session_create(int32_t x, int32_t y, uint32_t width, uint32_t height,
+                             bool cursor, uint32_t fps);
+
+/* Start capturing (restarts a running session) */
+RfResult rf_session_start(RfSession *session);
+
+/* Wait up to timeout_ms for the next frame */
+RfResult rf_session_get_frame(RfSession *session, uint32_t timeout_ms, RfFrame *frame);
+
+/* Stop capturing; the session can be started again */
+RfResult rf_session_stop(RfSession *session);
+
+/* Stop and free the session (NULL is ignored) */
+void rf_session_destroy(RfSession *session);
+
+/* Message of the last error on this thread (UTF-8, empty if none); valid
+ * until the next call on this thread */
+const char *rf_last_error(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* RUSTFRAME_H */
@@ -0,0 +1,192 @@
+// lib.rs - C API of RustFrame's Region Capture
+//
+// The functions declared in include/rustframe.h, on top of the library's
+// CaptureSession (see src/capture.rs in the main crate). A session is created
+// with its settings and captures between rf_session_start and
+// rf_session_stop; frames are copied into the session and stay valid until
+// the next call. Errors become RfResult codes, their messages are kept per
+// thread for rf_last_error. Panics are caught at the boundary.
+
+#![allow(clippy::missing_safety_doc)]
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::time::Duration;
+
+use rustframe::capture::{CaptureError, CaptureFrame, CaptureRect, CaptureSession};
+
+/// Result codes (RfResult in rustframe.h)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RfResult {
+    Ok = 0,
+    NoFrame = 1,
+    InvalidArgument = -1,
+    NotStarted = -2,
+    Unsupported = -3,
+    DeviceLost = -4,
+    InvalidRegion = -5,
+    PermissionDenied = -6,
+    Backend = -7,
+    Panic = -8,
+}
+
+/// A frame as seen from C (RfFrame in rustframe.h)
+#[repr(C)]
+pub struct RfFrame {
+    pub pixels: *const u32,
+    pub width: u32,
+    pub height: u32,
+    pub number: u64,
+}
+
+/// Settings and, while started, the running capture
+pub struct RfSession {
+    region: CaptureRect,
+    cursor: bool,
+    fps: u32,
+    session: Option<CaptureSession>,
+    /// The frame handed out last (kept until the next call)
+    frame: CaptureFrame,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+fn set_last_error(message: &str) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = message);
+}
+
+fn error_code(error: &CaptureError) -> RfResult {
+    set_last_error(&error.to_string());
+    match error {
+        CaptureError::Unsupported => RfResult::Unsupported,
+        CaptureError::DeviceLost => RfResult::DeviceLost,
+        CaptureError::InvalidRegion(_) => RfResult::InvalidRegion,
+        CaptureError::PermissionDenied => RfResult::PermissionDenied,
+        // Sessions do not encode; reported like any other Windows failure
+        CaptureError::Encoder(..) | CaptureError::Backend(..) => RfResult::Backend,
+    }
+}
+
+/// Run `f`, turning a panic into RF_ERROR_PANIC
+fn guard(f: impl FnOnce() -> RfResult) -> RfResult {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|_| {
+        set_last_error("Internal error (panic) in rustframe_ffi");
+        RfResult::Panic
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn rf_session_create(
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    cursor: bool,
+    fps: u32,
+) -> *mut RfSession {
+    if width == 0 || height == 0 {
+        set_last_error("The capture region is empty");
+        return std::ptr::null_mut();
+    }
+    Box::into_raw(Box::new(RfSession {
+        region: CaptureRect {
+            x,
+            y,
+            width,
+            height,
+        },
+        cursor,
+        fps,
+        session: None,
+        frame: CaptureFrame::default(),
+    }))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rf_session_start(session: *mut RfSession) -> RfResult {
+    let Some(session) = session.as_mut() else {
+        set_last_error("session is NULL");
+        return RfResult::InvalidArgument;
+    };
+    guard(|| {
+        // Restarting: stop the running capture first
+        session.session = None;
+        let built = CaptureSession::builder()
+            .region(session.region)
+            .cursor(session.cursor)
+            .fps(session.fps)
+            .build();
+        match built {
+            Ok(capture) => {
+                session.session = Some(capture);
+                RfResult::Ok
+            }
+            Err(e) => error_code(&e),
+        }
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rf_session_get_frame(
+    session: *mut RfSession,
+    timeout_ms: u32,
+    frame: *mut RfFrame,
+) -> RfResult {
+    let (Some(session), Some(out)) = (session.as_mut(), frame.as_mut()) else {
+        set_last_error("session or frame is NULL");
+        return RfResult::InvalidArgument;
+    };
+    guard(|| {
+        let Some(capture) = session.session.as_mut() else {
+            set_last_error("The session is not started");
+            return RfResult::NotStarted;
+        };
+        match capture.next_frame(Duration::from_millis(timeout_ms.into())) {
+            Ok(Some(frame)) => {
+                // Copied, so the pixels stay valid while the capture goes on
+                session.frame.clone_from(frame);
+                *out = RfFrame {
+                    pixels: session.frame.pixels.as_ptr(),
+                    width: session.frame.width,
+                    height: session.frame.height,
+                    number: session.frame.number,
+                };
+                RfResult::Ok
+            }
+            Ok(None) => RfResult::NoFrame,
+            Err(e) => error_code(&e),
+        }
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rf_session_stop(session: *mut RfSession) -> RfResult {
+    let Some(session) = session.as_mut() else {
+        set_last_error("session is NULL");
+        return RfResult::InvalidArgument;
+    };
+    guard(|| match session.session.take() {
+        Some(_) => RfResult::Ok,
+        None => {
+            set_last_error("The session is not started");
+            RfResult::NotStarted
+        }
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rf_session_destroy(session: *mut RfSession) {
+    if !session.is_null() {
+        let _ = catch_unwind(AssertUnwindSafe(|| drop(Box::from_raw(session))));
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn rf_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ptr())
+}
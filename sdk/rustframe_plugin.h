@@ -0,0 +1,53 @@
+/*
+ * rustframe_plugin.h - RustFrame Plugin Interface
+ *
+ * Build a DLL that exports rustframe_plugin_entry and copy it to
+ * %APPDATA%\RustFrame\plugins. See docs/plugins.md.
+ *
+ * Frames are one uint32_t per pixel, 0xAARRGGBB (BGRA in memory), rows
+ * without padding. All hooks are called on RustFrame's UI thread and return
+ * 0 on success; a hook that returns anything else is not called again.
+ */
+
+#ifndef RUSTFRAME_PLUGIN_H
+#define RUSTFRAME_PLUGIN_H
+
+#include <stdint.h>
+
+#define RUSTFRAME_PLUGIN_ABI_VERSION 1
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct RustFramePlugin {
+    /* RUSTFRAME_PLUGIN_ABI_VERSION the plugin was built with */
+    uint32_t abi_version;
+    /* Display name and version (UTF-8) */
+    const char *name;
+    const char *version;
+    /* Passed back to every hook */
+    void *user;
+
+    /* Modify the output frame in place (NULL = no filter) */
+    int32_t (*filter_frame)(void *user, uint32_t *pixels, uint32_t width, uint32_t height);
+    /* Receive every output frame, e.g. to stream it (NULL = no sink) */
+    int32_t (*sink_frame)(void *user, const uint32_t *pixels, uint32_t width, uint32_t height);
+    /* Text for the plugin's panel in Settings -> Plugins, valid until the
+     * next call (NULL = no panel) */
+    const char *(*panel_text)(void *user);
+    /* Called before the DLL is unloaded (may be NULL) */
+    void (*shutdown)(void *user);
+} RustFramePlugin;
+
+/*
+ * Return the plugin descriptor (valid until shutdown), or NULL if the plugin
+ * does not support host_abi_version.
+ */
+__declspec(dllexport) const RustFramePlugin *rustframe_plugin_entry(uint32_t host_abi_version);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
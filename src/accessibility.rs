@@ -0,0 +1,95 @@
+// accessibility.rs - Windows Accessibility Settings
+//
+// RustFrame follows two settings from Settings → Accessibility in Windows:
+// - Contrast themes (high contrast): the overlay, the help text and the
+//   settings dialog use the colors of the contrast theme instead of the
+//   RustFrame palette (see theme.rs)
+// - Animation effects off ("reduce motion"): output transitions become cuts
+//   and click highlights no longer grow, they just fade
+//
+// Both are read with SystemParametersInfo. The drawing code asks on every
+// frame, so the answer is kept for REFRESH_MS - a change in Windows shows up
+// within a second without reading the settings sixty times a second.
+
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::constants::accessibility;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct State {
+    high_contrast: bool,
+    reduce_motion: bool,
+}
+
+static CACHE: Mutex<Option<(Instant, State)>> = Mutex::new(None);
+
+/// Whether a Windows contrast theme (high contrast) is on
+pub fn high_contrast() -> bool {
+    state().high_contrast
+}
+
+/// Whether Windows animation effects are off
+pub fn reduce_motion() -> bool {
+    state().reduce_motion
+}
+
+/// Read the settings again on the next call (Windows reported a change)
+pub fn refresh() {
+    *CACHE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+fn state() -> State {
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    match *cache {
+        Some((read, state)) if read.elapsed().as_millis() < accessibility::REFRESH_MS as u128 => {
+            state
+        }
+        _ => {
+            let state = read_state();
+            *cache = Some((Instant::now(), state));
+            state
+        }
+    }
+}
+
+#[cfg(windows)]
+fn read_state() -> State {
+    use windows::Win32::UI::Accessibility::{HCF_HIGHCONTRASTON, HIGHCONTRASTW};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        SystemParametersInfoW, SPI_GETCLIENTAREAANIMATION, SPI_GETHIGHCONTRAST,
+        SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
+    };
+
+    let mut contrast = HIGHCONTRASTW {
+        cbSize: std::mem::size_of::<HIGHCONTRASTW>() as u32,
+        ..Default::default()
+    };
+    let mut animations = windows::core::BOOL(1);
+    unsafe {
+        let high_contrast = SystemParametersInfoW(
+            SPI_GETHIGHCONTRAST,
+            contrast.cbSize,
+            Some(&mut contrast as *mut HIGHCONTRASTW as *mut std::ffi::c_void),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+        .is_ok()
+            && contrast.dwFlags.contains(HCF_HIGHCONTRASTON);
+        // Unknown = animations on
+        let _ = SystemParametersInfoW(
+            SPI_GETCLIENTAREAANIMATION,
+            0,
+            Some(&mut animations as *mut windows::core::BOOL as *mut std::ffi::c_void),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        );
+        State {
+            high_contrast,
+            reduce_motion: !animations.as_bool(),
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn read_state() -> State {
+    State::default()
+}
@@ -0,0 +1,224 @@
+// annotation.rs - Live Annotation Layer
+//
+// Holds the shapes sketched in draw mode (arrows, rectangles, ellipses and
+// freehand strokes). Coordinates are relative to the top-left corner of the
+// capture region, so the same shapes can be rasterized into:
+// 1. The captured frames (renderer) - what the recording / shared window shows
+// 2. The overlay window (window_manager) - local feedback while drawing
+//
+// Freehand strokes drawn with a pen keep the pressure of every point, so the
+// line gets thicker where the pen is pressed harder.
+
+use crate::bitmap_font::Canvas;
+use crate::constants::annotation;
+use crate::drawing;
+
+/// Shape drawn by a mouse drag in draw mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnnotationTool {
+    Freehand,
+    Arrow,
+    Rectangle,
+    Ellipse,
+}
+
+impl AnnotationTool {
+    /// Short label for logs and titles
+    pub fn label(self) -> &'static str {
+        match self {
+            AnnotationTool::Freehand => "Freehand",
+            AnnotationTool::Arrow => "Arrow",
+            AnnotationTool::Rectangle => "Rectangle",
+            AnnotationTool::Ellipse => "Ellipse",
+        }
+    }
+}
+
+/// A single finished (or in-progress) shape
+#[derive(Debug, Clone)]
+struct Shape {
+    tool: AnnotationTool,
+    color: u32,
+    width: u32,
+    /// Freehand: every sampled point. Other tools: start and end point.
+    points: Vec<(i32, i32)>,
+    /// Pen pressure (0.0-1.0) per freehand point; empty for mouse and finger input
+    pressures: Vec<f32>,
+}
+
+impl Shape {
+    fn draw(&self, canvas: &mut Canvas, offset_x: i32, offset_y: i32) {
+        let Some(&(sx, sy)) = self.points.first() else {
+            return;
+        };
+        let &(ex, ey) = self.points.last().unwrap_or(&(sx, sy));
+        let (sx, sy) = (sx + offset_x, sy + offset_y);
+        let (ex, ey) = (ex + offset_x, ey + offset_y);
+
+        match self.tool {
+            AnnotationTool::Freehand => {
+                if self.points.len() == 1 {
+                    let radius = self.width_at(0) as i32 / 2;
+                    drawing::fill_circle(canvas, sx, sy, radius, self.color);
+                }
+                for (i, pair) in self.points.windows(2).enumerate() {
+                    drawing::draw_line(
+                        canvas,
+                        pair[0].0 + offset_x,
+                        pair[0].1 + offset_y,
+                        pair[1].0 + offset_x,
+                        pair[1].1 + offset_y,
+                        self.width_at(i + 1),
+                        self.color,
+                    );
+                }
+            }
+            AnnotationTool::Arrow => {
+                drawing::draw_arrow(canvas, sx, sy, ex, ey, self.width, self.color)
+            }
+            AnnotationTool::Rectangle => {
+                drawing::draw_rect(canvas, sx, sy, ex, ey, self.width, self.color)
+            }
+            AnnotationTool::Ellipse => {
+                drawing::draw_ellipse(canvas, sx, sy, ex, ey, self.width, self.color)
+            }
+        }
+    }
+
+    /// Stroke width at a point - scaled by the pen pressure when there is one
+    /// (half pressure = the selected width)
+    fn width_at(&self, index: usize) -> u32 {
+        match self.pressures.get(index) {
+            Some(&pressure) => {
+                let scale = pressure.clamp(0.0, 1.0) * annotation::PEN_PRESSURE_SCALE;
+                ((self.width as f32 * scale).round() as u32).max(annotation::MIN_WIDTH)
+            }
+            None => self.width,
+        }
+    }
+}
+
+/// All annotations for the current capture session plus the active tool state
+pub struct AnnotationLayer {
+    shapes: Vec<Shape>,
+    active: Option<Shape>,
+    tool: AnnotationTool,
+    color: u32,
+    width: u32,
+    /// Bumped on every visible change so the renderer knows when to recomposite
+    revision: u64,
+}
+
+impl AnnotationLayer {
+    pub fn new() -> Self {
+        Self {
+            shapes: Vec::new(),
+            active: None,
+            tool: AnnotationTool::Freehand,
+            color: annotation::PALETTE[0],
+            width: annotation::DEFAULT_WIDTH,
+            revision: 0,
+        }
+    }
+
+    /// Start a new shape at the given point (capture-region coordinates)
+    /// `pressure` is the pen pressure (0.0-1.0), None for mouse and finger input
+    pub fn begin_stroke(&mut self, x: i32, y: i32, pressure: Option<f32>) {
+        self.active = Some(Shape {
+            tool: self.tool,
+            color: self.color,
+            width: self.width,
+            points: vec![(x, y)],
+            pressures: pressure.into_iter().collect(),
+        });
+        self.revision += 1;
+    }
+
+    /// Extend the active shape to the given point
+    pub fn extend_stroke(&mut self, x: i32, y: i32, pressure: Option<f32>) {
+        if let Some(shape) = &mut self.active {
+            if shape.tool == AnnotationTool::Freehand || shape.points.len() < 2 {
+                shape.points.push((x, y));
+                // Pressure is kept only for strokes that started with a pen
+                if let Some(pressure) = pressure.filter(|_| !shape.pressures.is_empty()) {
+                    shape.pressures.push(pressure);
+                }
+            } else {
+                shape.points[1] = (x, y);
+            }
+            self.revision += 1;
+        }
+    }
+
+    /// Finish the active shape and keep it on the layer
+    pub fn end_stroke(&mut self) {
+        if let Some(shape) = self.active.take() {
+            self.shapes.push(shape);
+            self.revision += 1;
+        }
+    }
+
+    /// Whether a drag is currently in progress
+    pub fn is_drawing(&self) -> bool {
+        self.active.is_some()
+    }
+
+    /// Remove the most recent shape
+    pub fn undo(&mut self) {
+        if self.shapes.pop().is_some() {
+            self.revision += 1;
+        }
+    }
+
+    /// Remove all shapes
+    pub fn clear(&mut self) {
+        if !self.is_empty() {
+            self.shapes.clear();
+            self.active = None;
+            self.revision += 1;
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shapes.is_empty() && self.active.is_none()
+    }
+
+    pub fn tool(&self) -> AnnotationTool {
+        self.tool
+    }
+
+    pub fn set_tool(&mut self, tool: AnnotationTool) {
+        self.tool = tool;
+    }
+
+    /// Select a color from the palette (index 0-5)
+    pub fn set_color_index(&mut self, index: usize) {
+        if let Some(&color) = annotation::PALETTE.get(index) {
+            self.color = color;
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Grow or shrink the stroke width, clamped to the allowed range
+    pub fn adjust_width(&mut self, delta: i32) {
+        self.width = (self.width as i32 + delta)
+            .clamp(annotation::MIN_WIDTH as i32, annotation::MAX_WIDTH as i32)
+            as u32;
+    }
+
+    /// Revision counter - changes whenever the rendered result would change
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Rasterize all shapes onto a canvas, shifted by the given offset
+    /// (the overlay window draws with an offset equal to its border width)
+    pub fn draw(&self, canvas: &mut Canvas, offset_x: i32, offset_y: i32) {
+        for shape in self.shapes.iter().chain(self.active.iter()) {
+            shape.draw(canvas, offset_x, offset_y);
+        }
+    }
+}
@@ -0,0 +1,267 @@
+// app_settings.rs - Settings of the RustFrame App
+//
+// Everything the Settings dialog edits and settings.json stores (see
+// settings_file.rs). The settings of the capture itself - cursor, border,
+// frame rate - are the library's capture::CaptureSettings, flattened in, so
+// settings.json keeps one level of fields.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::capture::CaptureSettings;
+use crate::theme::ThemeMode;
+
+/// What ESC does when no mode (drawing, measuring, countdown, ...) takes it
+/// Stopping a running capture is always confirmed first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EscAction {
+    /// Stop the capture; while selecting, exit RustFrame
+    #[default]
+    Stop,
+    /// Hide the windows to the tray (a running capture continues)
+    Minimize,
+    /// Like Stop, but exiting RustFrame is confirmed too
+    Ask,
+}
+
+impl EscAction {
+    pub const ALL: [EscAction; 3] = [EscAction::Stop, EscAction::Minimize, EscAction::Ask];
+
+    /// Name shown in the settings dialog
+    pub fn label(self) -> &'static str {
+        match self {
+            EscAction::Stop => "Stop capture / exit",
+            EscAction::Minimize => "Minimize to tray",
+            EscAction::Ask => "Ask first",
+        }
+    }
+}
+
+/// Where the control toolbar (see toolbar.rs) is shown during a capture
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolbarPlacement {
+    /// No toolbar
+    Off,
+    /// Above (or below) the capture region, follows it
+    #[default]
+    Border,
+    /// Wherever it was dragged, top of the monitor at first
+    Floating,
+}
+
+impl ToolbarPlacement {
+    pub const ALL: [ToolbarPlacement; 3] = [
+        ToolbarPlacement::Off,
+        ToolbarPlacement::Border,
+        ToolbarPlacement::Floating,
+    ];
+
+    /// Name shown in the settings dialog
+    pub fn label(self) -> &'static str {
+        match self {
+            ToolbarPlacement::Off => "Off",
+            ToolbarPlacement::Border => "At the capture border",
+            ToolbarPlacement::Floating => "Floating",
+        }
+    }
+}
+
+/// Settings of the app (stored in settings.json, see settings_file.rs)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    /// Cursor, border, frame rate (see capture.rs)
+    #[serde(flatten)]
+    pub capture: CaptureSettings,
+    /// Seconds to count down after pressing Enter before capture starts (0 = off)
+    pub countdown_seconds: u32,
+    /// Show the countdown over the whole monitor instead of only the selected region
+    pub countdown_fullscreen: bool,
+    /// Seconds the last good frame is held while the source is interrupted
+    /// (captured application minimized, UAC prompt) - 0 = off
+    pub hold_seconds: u32,
+    /// Countdown length of the on-screen timer in seconds, 0 = stopwatch
+    /// (tray → Timer, see timer.rs)
+    pub timer_seconds: u64,
+    /// Corner of the output the on-screen timer is drawn in
+    pub timer_corner: crate::timer::TimerCorner,
+    /// Text size of the on-screen timer
+    pub timer_size: crate::timer::TimerSize,
+    /// Placeholder shown while the output is paused (see pause_screen.rs)
+    pub pause_style: crate::pause_screen::PauseStyle,
+    /// Region positions (top-left corner) the auto pan moves through (see pan.rs)
+    pub pan_path: Vec<(i32, i32)>,
+    /// Duration of the auto pan in seconds
+    pub pan_seconds: u32,
+    /// Duration of output transitions in milliseconds (0 = cut)
+    pub transition_ms: u32,
+    /// Transition used for scene switches and freeze / pause toggles
+    pub transition_kind: crate::transition::TransitionKind,
+    /// "#RRGGBB" the fade to color transition passes through
+    pub transition_color: String,
+    /// Frame rate while the captured content does not change (0 = off)
+    pub idle_fps: u32,
+    /// Milliseconds without a change before idle_fps is used
+    pub idle_after_ms: u32,
+    /// Cap the frame rate while running on battery (see power.rs)
+    pub low_power_on_battery: bool,
+    /// Output frame rate limit on battery
+    pub low_power_fps: u32,
+    /// Scheduling priority of the capture thread (see thread_priority.rs)
+    pub thread_priority: crate::thread_priority::ThreadPriority,
+    /// Logical processors the capture thread runs on (empty = any)
+    pub cpu_cores: Vec<u32>,
+    /// Image file or slideshow folder of the image source (empty = none)
+    pub image_path: String,
+    /// Time per slide of a slideshow folder in seconds (0 = manual)
+    pub slide_seconds: u32,
+    /// Process file names whose windows are blacked out in the output (e.g. "Slack.exe")
+    pub redacted_apps: Vec<String>,
+    /// Black out Windows toast notifications in the output while capturing
+    pub hide_notifications: bool,
+    /// Drive capture with an XInput game controller (see gamepad.rs)
+    pub gamepad: bool,
+    /// Drive RustFrame with a MIDI controller (see midi.rs)
+    pub midi_enabled: bool,
+    /// MIDI input device name (empty = the first device)
+    pub midi_device: String,
+    /// MIDI mapping: action id → "note 36" / "cc 7" (see midi.rs)
+    pub midi_mappings: BTreeMap<String, String>,
+    /// Accept Stream Deck / remote control connections (see stream_deck.rs)
+    pub stream_deck: bool,
+    /// WebSocket port of the remote control server (localhost only)
+    pub stream_deck_port: u16,
+    /// Token remote clients identify with (generated on first start)
+    pub stream_deck_token: String,
+    /// Accept OSC messages from control surfaces (see osc.rs)
+    pub osc: bool,
+    /// UDP port of the OSC listener
+    pub osc_port: u16,
+    /// Address the OSC listener binds to ("0.0.0.0" = all network interfaces)
+    pub osc_bind: String,
+    /// IP addresses of other devices allowed to send OSC (this computer always is)
+    pub osc_allowed_sources: Vec<String>,
+    /// Minimizing a RustFrame window hides it to the tray (the capture keeps running)
+    pub minimize_to_tray: bool,
+    /// Closing a RustFrame window hides it to the tray instead of exiting
+    pub close_to_tray: bool,
+    /// What ESC does (see EscAction)
+    pub esc_action: EscAction,
+    /// Light / dark / high contrast UI (see theme.rs)
+    pub theme: ThemeMode,
+    /// "#RRGGBB" for the border and the overlay accents, empty = RustFrame blue
+    pub accent_color: String,
+    /// Control toolbar during a capture (see ToolbarPlacement)
+    pub toolbar: ToolbarPlacement,
+    /// Start RustFrame when the user signs in (see autostart.rs)
+    pub auto_start: bool,
+    /// Start hidden in the tray (like --minimized)
+    pub start_minimized: bool,
+    /// "Capture with RustFrame" in the desktop right-click menu (see shell_menu.rs)
+    pub shell_menu: bool,
+    /// Enabled frame filters in the order they run (see filter.rs)
+    pub filters: Vec<String>,
+    /// Shortcuts that differ from the defaults: action id → "Ctrl+Alt+S"
+    /// (empty = unbound, see hotkeys.rs)
+    pub hotkeys: BTreeMap<String, String>,
+    /// Plugin DLLs that are loaded (file names, see plugin.rs) - DLLs not
+    /// listed here stay off
+    pub enabled_plugins: Vec<String>,
+    /// URLs notified on recording start/stop, stream drops and errors
+    /// (settings.json only, see webhook.rs)
+    pub webhooks: Vec<crate::webhook::Webhook>,
+    /// What happens with a screenshot after it was saved
+    /// (settings.json only, see post_actions.rs)
+    pub post_actions: crate::post_actions::PostActions,
+    /// Upload target for tray → Upload Last Screenshot
+    /// (settings.json only, see upload.rs)
+    pub upload: crate::upload::UploadSettings,
+    /// Write the keyboard and mouse input of each capture to a file
+    /// (see input_recording.rs)
+    pub record_input: bool,
+    /// Per-monitor / per-application overrides applied when a capture starts
+    /// (settings.json only, see profile.rs)
+    pub profiles: Vec<crate::profile::SettingsProfile>,
+    /// Saved scenes by slot, None = empty (tray → Scenes → Save, see scene.rs)
+    pub scenes: Vec<Option<crate::scene::Scene>>,
+}
+
+impl Default for AppSettings {
+    /// Default settings for PRODUCTION mode
+    fn default() -> Self {
+        Self {
+            capture: CaptureSettings::default(),
+            countdown_seconds: crate::constants::capture::DEFAULT_COUNTDOWN_SECONDS,
+            countdown_fullscreen: false,
+            hold_seconds: crate::constants::capture::DEFAULT_HOLD_SECONDS,
+            timer_seconds: 0,
+            timer_corner: Default::default(),
+            timer_size: Default::default(),
+            pause_style: Default::default(),
+            pan_path: Vec::new(),
+            pan_seconds: crate::constants::capture::DEFAULT_PAN_SECONDS,
+            transition_ms: crate::constants::capture::DEFAULT_TRANSITION_MS,
+            transition_kind: crate::transition::TransitionKind::default(),
+            transition_color: crate::constants::transition::DEFAULT_FADE_COLOR.to_string(),
+            idle_fps: crate::constants::capture::DEFAULT_IDLE_FPS,
+            idle_after_ms: crate::constants::capture::DEFAULT_IDLE_AFTER_MS,
+            low_power_on_battery: true,
+            low_power_fps: crate::constants::power::DEFAULT_FPS,
+            thread_priority: Default::default(),
+            cpu_cores: Vec::new(),
+            image_path: String::new(),
+            slide_seconds: crate::constants::capture::DEFAULT_SLIDE_SECONDS,
+            redacted_apps: default_redacted_apps(),
+            hide_notifications: true,
+            gamepad: false,
+            midi_enabled: false,
+            midi_device: String::new(),
+            midi_mappings: BTreeMap::new(),
+            stream_deck: false,
+            stream_deck_port: crate::constants::stream_deck::DEFAULT_PORT,
+            stream_deck_token: String::new(),
+            osc: false,
+            osc_port: crate::constants::osc::DEFAULT_PORT,
+            osc_bind: crate::constants::osc::DEFAULT_BIND.to_string(),
+            osc_allowed_sources: Vec::new(),
+            minimize_to_tray: false,
+            close_to_tray: false,
+            esc_action: EscAction::default(),
+            theme: ThemeMode::default(),
+            accent_color: String::new(),
+            toolbar: ToolbarPlacement::default(),
+            auto_start: false,
+            start_minimized: false,
+            shell_menu: false,
+            filters: crate::filter::default_order(),
+            hotkeys: BTreeMap::new(),
+            enabled_plugins: Vec::new(),
+            webhooks: Vec::new(),
+            post_actions: Default::default(),
+            upload: Default::default(),
+            record_input: false,
+            profiles: Vec::new(),
+            scenes: Vec::new(),
+        }
+    }
+}
+
+/// Applications redacted out of the box (password managers)
+fn default_redacted_apps() -> Vec<String> {
+    crate::constants::window_mask::DEFAULT_APPS
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+impl AppSettings {
+    /// Development mode settings - destination window visible beside overlay
+    pub fn for_development() -> Self {
+        Self {
+            capture: CaptureSettings::for_development(),
+            ..Self::default()
+        }
+    }
+}
@@ -0,0 +1,70 @@
+// autostart.rs - Start with Windows
+//
+// With "Start with Windows" on, RustFrame registers itself in the current
+// user's Run key, so it starts (minimized to the tray, with --minimized) when
+// the user signs in:
+//
+//   HKCU\Software\Microsoft\Windows\CurrentVersion\Run
+//     RustFrame = "C:\...\RustFrame.exe" --minimized
+//
+// The entry is written when the setting is turned on and again at every
+// start, so it follows the executable if it was moved; it is removed when the
+// setting is turned off. No administrator rights are needed.
+
+use anyhow::Result;
+
+#[cfg(windows)]
+const RUN_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Run";
+/// Value name in the Run key
+#[cfg(windows)]
+const VALUE_NAME: &str = "RustFrame";
+
+/// Add or remove the Run key entry
+#[cfg(windows)]
+pub fn set_enabled(enabled: bool) -> Result<()> {
+    use anyhow::{anyhow, Context};
+    use windows::core::PCWSTR;
+    use windows::Win32::System::Registry::{
+        RegCloseKey, RegDeleteValueW, RegOpenKeyExW, HKEY, HKEY_CURRENT_USER, KEY_SET_VALUE,
+    };
+
+    if enabled {
+        let exe = std::env::current_exe().context("Failed to locate RustFrame.exe")?;
+        let command = format!("\"{}\" --minimized", exe.display());
+        crate::utils::set_user_registry_string(RUN_KEY, Some(VALUE_NAME), &command)?;
+        log::info!("Start with Windows: {}", command);
+        return Ok(());
+    }
+
+    let subkey = crate::utils::wide_string(RUN_KEY);
+    let name = crate::utils::wide_string(VALUE_NAME);
+    unsafe {
+        let mut key = HKEY::default();
+        RegOpenKeyExW(
+            HKEY_CURRENT_USER,
+            PCWSTR(subkey.as_ptr()),
+            Some(0),
+            KEY_SET_VALUE,
+            &mut key,
+        )
+        .ok()
+        .context("Failed to open the Run key")?;
+
+        let result = RegDeleteValueW(key, PCWSTR(name.as_ptr()));
+        let _ = RegCloseKey(key);
+        // Nothing to remove is fine
+        if result.is_ok() || result == windows::Win32::Foundation::ERROR_FILE_NOT_FOUND {
+            log::info!("Start with Windows: off");
+            Ok(())
+        } else {
+            Err(anyhow!("Failed to remove the Run key entry: {:?}", result))
+        }
+    }
+}
+
+#[cfg(not(windows))]
+pub fn set_enabled(_enabled: bool) -> Result<()> {
+    Err(anyhow::anyhow!(
+        "Start with Windows is only supported on Windows"
+    ))
+}
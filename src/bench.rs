@@ -0,0 +1,254 @@
+// bench.rs - Capture Benchmark
+//
+// `RustFrame bench --region 0,0,1920,1080 --seconds 10` captures a region
+// for a fixed time and reports what the capture path achieves on this
+// machine, to compare machines and backends and to catch performance
+// regressions between releases:
+//
+// - fps: frames delivered per second
+// - latency: from the moment Windows captured a frame (its SystemRelativeTime)
+//   until its pixels are in memory - average and 99th percentile
+// - copy: GPU → CPU copy and crop of a frame (the staging texture round trip
+//   every frame takes), and the bandwidth it reaches
+// - conversion: BGRA → RGBA of a frame, the step of every image export
+//
+// Windows.Graphics.Capture only delivers frames when the screen changes, so
+// the region should show something moving (a video, an animation) - a still
+// screen measures the desktop, not the capture. The numbers are for the one
+// backend RustFrame has; the report names it and the GPU, so reports from
+// other backends or machines can be put side by side.
+
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+use crate::capture::{CaptureRect, CaptureSession, Result};
+use crate::constants::bench;
+use crate::record::parse_region;
+
+/// Name of the capture backend in the report
+pub const BACKEND: &str = "Windows.Graphics.Capture";
+
+/// A parsed `bench` command line
+#[derive(Debug, Clone)]
+pub struct BenchArgs {
+    pub region: CaptureRect,
+    pub duration: Duration,
+    pub cursor: bool,
+    /// Print the report as JSON
+    pub json: bool,
+}
+
+impl BenchArgs {
+    /// Parse the options after `bench`; the error is a message for the user
+    pub fn parse(args: &[String]) -> std::result::Result<Self, String> {
+        let mut region = None;
+        let mut seconds = bench::DEFAULT_SECONDS;
+        let mut cursor = false;
+        let mut json = false;
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .map(|v| v.trim())
+                    .ok_or_else(|| format!("Missing value for {}", arg))
+            };
+            match arg.as_str() {
+                "--region" => {
+                    let text = value()?;
+                    region = Some(
+                        parse_region(text).ok_or_else(|| format!("Invalid region '{}'", text))?,
+                    );
+                }
+                "--seconds" => {
+                    let text = value()?;
+                    seconds = text
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|s| s.is_finite() && *s > 0.0)
+                        .ok_or_else(|| format!("Invalid --seconds '{}'", text))?;
+                }
+                "--cursor" => cursor = true,
+                "--json" => json = true,
+                other => return Err(format!("Unknown option '{}'", other)),
+            }
+        }
+
+        Ok(Self {
+            region: region.ok_or("Missing --region")?,
+            duration: Duration::from_secs_f64(seconds),
+            cursor,
+            json,
+        })
+    }
+}
+
+/// Average and 99th percentile of a measurement (milliseconds)
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct Timing {
+    pub average_ms: f64,
+    pub p99_ms: f64,
+}
+
+impl Timing {
+    fn of(mut samples: Vec<f64>) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        samples.sort_by(f64::total_cmp);
+        let p99 = ((samples.len() * 99 + 99) / 100).max(1) - 1;
+        Self {
+            average_ms: samples.iter().sum::<f64>() / samples.len() as f64,
+            p99_ms: samples[p99],
+        }
+    }
+}
+
+/// What a benchmark run measured
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    pub backend: &'static str,
+    /// Name of the GPU the capture ran on
+    pub adapter: String,
+    pub version: &'static str,
+    /// Size of the captured frames
+    pub width: u32,
+    pub height: u32,
+    pub seconds: f64,
+    pub frames: u64,
+    pub fps: f64,
+    /// Captured by Windows → pixels in memory (frames without a capture time
+    /// are left out)
+    pub latency: Timing,
+    /// GPU → CPU copy and crop
+    pub copy: Timing,
+    /// BGRA → RGBA
+    pub conversion: Timing,
+    /// Bytes copied per second of copy time (MB/s)
+    pub copy_bandwidth_mb_s: f64,
+}
+
+impl std::fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(
+            f,
+            "RustFrame {} - {} on {}",
+            self.version, self.backend, self.adapter
+        )?;
+        writeln!(
+            f,
+            "Region:      {}x{} for {:.1} s",
+            self.width, self.height, self.seconds
+        )?;
+        writeln!(f, "Frames:      {} ({:.1} fps)", self.frames, self.fps)?;
+        let timing = |f: &mut std::fmt::Formatter, name: &str, timing: &Timing| {
+            writeln!(
+                f,
+                "{:<12} {:.2} ms average, {:.2} ms 99th percentile",
+                name, timing.average_ms, timing.p99_ms
+            )
+        };
+        timing(f, "Latency:", &self.latency)?;
+        timing(f, "Copy:", &self.copy)?;
+        timing(f, "Conversion:", &self.conversion)?;
+        write!(f, "Bandwidth:   {:.0} MB/s", self.copy_bandwidth_mb_s)
+    }
+}
+
+/// Capture `region` for `duration` and measure every frame
+pub fn run(region: CaptureRect, duration: Duration, cursor: bool) -> Result<BenchReport> {
+    let mut session = CaptureSession::builder()
+        .region(region)
+        .cursor(cursor)
+        .build()?;
+    let adapter = adapter_name(&session);
+    let poll = Duration::from_millis(bench::POLL_MS);
+
+    let mut latency = Vec::new();
+    let mut copy = Vec::new();
+    let mut conversion = Vec::new();
+    let mut copied = Duration::ZERO;
+    let mut bytes = 0u64;
+    let mut size = (0, 0);
+    let mut rgba = Vec::new();
+
+    let started = Instant::now();
+    while started.elapsed() < duration {
+        let before = Instant::now();
+        let Some(frame) = session.try_next_frame()? else {
+            std::thread::sleep(poll);
+            continue;
+        };
+        let read = before.elapsed();
+        let received = now_100ns();
+
+        copied += read;
+        copy.push(read.as_secs_f64() * 1000.0);
+        bytes += frame.pixels.len() as u64 * 4;
+        size = (frame.width, frame.height);
+        if frame.capture_time > 0 && received >= frame.capture_time {
+            latency.push((received - frame.capture_time) as f64 / 10_000.0);
+        }
+
+        let before = Instant::now();
+        rgba.clear();
+        rgba.extend(
+            frame
+                .pixels
+                .iter()
+                .flat_map(|&p| [(p >> 16) as u8, (p >> 8) as u8, p as u8, 0xFF]),
+        );
+        conversion.push(before.elapsed().as_secs_f64() * 1000.0);
+    }
+
+    let seconds = started.elapsed().as_secs_f64();
+    let frames = copy.len() as u64;
+    Ok(BenchReport {
+        backend: BACKEND,
+        adapter,
+        version: env!("CARGO_PKG_VERSION"),
+        width: size.0,
+        height: size.1,
+        seconds,
+        frames,
+        fps: frames as f64 / seconds,
+        latency: Timing::of(latency),
+        copy: Timing::of(copy),
+        conversion: Timing::of(conversion),
+        copy_bandwidth_mb_s: if copied.is_zero() {
+            0.0
+        } else {
+            bytes as f64 / copied.as_secs_f64() / 1_000_000.0
+        },
+    })
+}
+
+/// QueryPerformanceCounter time in 100 ns units (the clock of SystemRelativeTime)
+#[cfg(windows)]
+pub(crate) fn now_100ns() -> i64 {
+    use windows::Win32::System::Performance::{QueryPerformanceCounter, QueryPerformanceFrequency};
+
+    let (mut counter, mut frequency) = (0i64, 0i64);
+    unsafe {
+        if QueryPerformanceCounter(&mut counter).is_err()
+            || QueryPerformanceFrequency(&mut frequency).is_err()
+            || frequency == 0
+        {
+            return 0;
+        }
+    }
+    (counter as i128 * 10_000_000 / frequency as i128) as i64
+}
+
+#[cfg(not(windows))]
+pub(crate) fn now_100ns() -> i64 {
+    0
+}
+
+/// Description of the GPU behind the session's D3D11 device
+pub(crate) fn adapter_name(session: &CaptureSession) -> String {
+    session
+        .engine()
+        .gpu()
+        .map_or_else(|| "unknown GPU".to_string(), |gpu| gpu.name)
+}
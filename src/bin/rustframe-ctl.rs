@@ -0,0 +1,239 @@
+// rustframe-ctl.rs - Command Line Control of a Running RustFrame
+//
+// A small console program for shell scripts:
+//
+//   rustframe-ctl start-recording --scene 2 --duration 60
+//   rustframe-ctl screenshot --out demo.png
+//   rustframe-ctl stop-recording
+//
+// Commands go to the running instance through its named pipe (see
+// instance.rs) as rustframe:// links (see deep_link.rs), so no network port
+// is involved. The pipe only accepts commands, it does not answer - a
+// command that was delivered is queued, not necessarily done. screenshot
+// --out waits until the file was written, so the next line of the script can
+// use it.
+//
+// `record` is the exception: it records a region to an MP4 file by itself
+// (rustframe::record), without a running RustFrame or any window.
+//
+// Exit codes are the documented ones of rustframe::cli (0 = delivered,
+// 9 = RustFrame is not running, ...); --json-errors prints the error as JSON.
+
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant, SystemTime};
+
+use rustframe::cli::{self, CliError, FailureKind};
+
+/// Pipe connection attempts while the running instance serves another client
+const CONNECT_ATTEMPTS: u32 = 10;
+const CONNECT_RETRY_MS: u64 = 100;
+/// How long screenshot --out waits for the file
+const SCREENSHOT_TIMEOUT_MS: u64 = 10000;
+const SCREENSHOT_POLL_MS: u64 = 100;
+
+const USAGE: &str = "\
+Control a running RustFrame
+
+Usage: rustframe-ctl <command> [options]
+
+Commands:
+  start-recording [--scene <1-4>] [--duration <seconds>]
+                          Start capture (with the countdown)
+  stop-recording          Stop capture or cancel the countdown
+  toggle                  Start / stop capture
+  pause                   Show / hide the pause screen
+  scene <1-4>             Switch to a scene
+  marker                  Drop a marker
+  screenshot [--out <file.png>]
+                          Save a screenshot (waits for the file with --out)
+  show                    Bring RustFrame back from the tray
+  link <rustframe://...>  Send any rustframe:// link
+
+Without a running RustFrame:
+  record --region <x,y,width,height> --duration <seconds> --out <file.mp4>
+         [--fps <n>] [--bitrate <bits per second>] [--no-cursor] [--quality]
+                          Record a region to an MP4 file (no window,
+                          same as RustFrame record); --quality also
+                          reports PSNR / SSIM against the captured frames
+
+Options:
+  --json-errors           Print errors as one JSON line on stderr
+
+Exit codes: 0 ok, 1 error, 2 invalid region, 3 capture unsupported,
+4 encoder failure, 5 permission denied, 6 device lost, 7 invalid arguments,
+8 output not written, 9 RustFrame is not running (see docs/cli.md)";
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let json_errors = cli::take_json_errors(&mut args);
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => ExitCode::from(e.report(json_errors)),
+    }
+}
+
+fn run(args: &[String]) -> Result<(), CliError> {
+    let Some(command) = args.first() else {
+        return Err(CliError::usage("Missing command", USAGE));
+    };
+    let options = &args[1..];
+
+    match command.as_str() {
+        "start-recording" | "start" => {
+            let mut query = Vec::new();
+            if let Some(scene) = option(options, "--scene")? {
+                query.push(format!("scene={}", encode(&scene)));
+            }
+            if let Some(duration) = option(options, "--duration")? {
+                query.push(format!("duration={}", encode(&duration)));
+            }
+            let link = if query.is_empty() {
+                "rustframe://record".to_string()
+            } else {
+                format!("rustframe://record?{}", query.join("&"))
+            };
+            send(&link)
+        }
+        "stop-recording" | "stop" => send("rustframe://stop"),
+        "toggle" => send("rustframe://toggle"),
+        "pause" => send("rustframe://pause"),
+        "scene" => {
+            let slot = options
+                .first()
+                .ok_or_else(|| CliError::usage("Missing scene number", USAGE))?;
+            send(&format!("rustframe://scene?slot={}", encode(slot)))
+        }
+        "marker" => send("rustframe://marker"),
+        "screenshot" => match option(options, "--out")? {
+            Some(out) => screenshot_to(out),
+            None => send("rustframe://screenshot"),
+        },
+        "show" => send("rustframe://show"),
+        "record" => record(options),
+        "link" => {
+            let link = options
+                .first()
+                .ok_or_else(|| CliError::usage("Missing link", USAGE))?;
+            send(link)
+        }
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        other => Err(CliError::usage(
+            format!("Unknown command '{}'", other),
+            USAGE,
+        )),
+    }
+}
+
+/// Ask for a screenshot in `out` and wait until it was written
+fn screenshot_to(out: String) -> Result<(), CliError> {
+    // RustFrame runs in another directory - relative paths are ours
+    let path = std::env::current_dir()
+        .map(|dir| dir.join(&out))
+        .unwrap_or_else(|_| PathBuf::from(&out));
+    let requested = SystemTime::now();
+    send(&format!(
+        "rustframe://screenshot?out={}",
+        encode(&path.to_string_lossy())
+    ))?;
+
+    let deadline = Instant::now() + Duration::from_millis(SCREENSHOT_TIMEOUT_MS);
+    while Instant::now() < deadline {
+        let written = path
+            .metadata()
+            .and_then(|m| m.modified())
+            .is_ok_and(|modified| modified >= requested);
+        if written {
+            println!("{}", path.display());
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_millis(SCREENSHOT_POLL_MS));
+    }
+    Err(CliError::new(
+        FailureKind::Output,
+        format!(
+            "No screenshot was written to {} - is RustFrame capturing?",
+            path.display()
+        ),
+    ))
+}
+
+/// Record a region headlessly (see record.rs in the library)
+fn record(options: &[String]) -> Result<(), CliError> {
+    let args =
+        rustframe::record::RecordArgs::parse(options).map_err(|e| CliError::usage(e, USAGE))?;
+    let summary = rustframe::record(args.region, args.duration, &args.out, &args.options)
+        .map_err(|e| CliError::capture("Recording failed", &e))?;
+    println!(
+        "{} ({}x{}, {} frames{})",
+        args.out.display(),
+        summary.width,
+        summary.height,
+        summary.frames,
+        if summary.interrupted { ", stopped early" } else { "" }
+    );
+    if let Some(quality) = summary.quality {
+        println!("Quality: {}", quality);
+    }
+    Ok(())
+}
+
+/// Value of `--name <value>`
+fn option(options: &[String], name: &str) -> Result<Option<String>, CliError> {
+    match options.iter().position(|o| o == name) {
+        Some(i) => options
+            .get(i + 1)
+            .cloned()
+            .map(Some)
+            .ok_or_else(|| CliError::usage(format!("Missing value for {}", name), USAGE)),
+        None => Ok(None),
+    }
+}
+
+/// Write a link to the running instance's pipe (same name as in instance.rs)
+fn send(link: &str) -> Result<(), CliError> {
+    let user = std::env::var("USERNAME").unwrap_or_default();
+    let name = format!(r"\\.\pipe\RustFrame-{}", user);
+    for _ in 0..CONNECT_ATTEMPTS {
+        match std::fs::OpenOptions::new().write(true).open(&name) {
+            Ok(mut pipe) => {
+                return pipe
+                    .write_all(format!("{}\n", link).as_bytes())
+                    .map_err(|e| {
+                        CliError::new(
+                            FailureKind::Other,
+                            format!("Failed to send the command: {}", e),
+                        )
+                    });
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                return Err(CliError::new(
+                    FailureKind::NotRunning,
+                    "RustFrame is not running",
+                ))
+            }
+            Err(_) => std::thread::sleep(Duration::from_millis(CONNECT_RETRY_MS)),
+        }
+    }
+    Err(CliError::new(
+        FailureKind::Other,
+        "The running RustFrame instance does not respond",
+    ))
+}
+
+/// Percent-encode a query value
+fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
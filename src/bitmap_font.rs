@@ -1,13 +1,13 @@
 // bitmap_font.rs - Simple Bitmap Font for Pixel-Based Text Rendering
 //
 // This module provides a 5x7 pixel bitmap font for rendering text directly
-// to pixel buffers. This is needed for layered windows with alpha transparency,
-// where GDI text rendering doesn't work properly.
+// to pixel buffers. It draws the text composited into the captured frames
+// (timer, pause screen, test pattern, click labels) on every frame, where
+// it is the cheapest option. The overlay windows use scalable GDI text
+// instead (see text.rs).
 //
 // Each character is 5 pixels wide and 7 pixels tall, stored as bit patterns.
 
-use crate::constants::colors;
-
 /// 5x7 bitmap font data (ASCII 32-122)
 /// Each character is stored as 7 bytes, where each byte represents one row
 /// and the lower 5 bits represent the pixel columns.
@@ -178,113 +178,7 @@ pub fn text_width(text: &str, scale: i32) -> i32 {
     text.len() as i32 * (CHAR_WIDTH + CHAR_SPACING) * scale
 }
 
-/// Draw centered help text for the selection overlay
-/// Shows current settings state (cursor, border, mode)
-pub fn draw_help_text(pixels: &mut [u32], width: i32, height: i32, 
-                      show_cursor: bool, show_border: bool, exclude_from_capture: bool) {
-    let mut canvas = Canvas {
-        pixels,
-        width,
-        height,
-    };
-
-    // Format settings status text
-    let cursor_status = if show_cursor { "ON" } else { "OFF" };
-    let border_status = if show_border { "ON" } else { "OFF" };
-    let mode_status = if exclude_from_capture { "PROD" } else { "DEV" };
-    
-    // Build dynamic text lines
-    let cursor_line = format!("[C] Cursor: {}", cursor_status);
-    let border_line = format!("[B] Border: {}", border_status);
-    let mode_line = format!("[E] Mode: {}", mode_status);
-
-    // Help text content with color and scale
-    // Using owned Strings for dynamic content
-    let lines: Vec<(&str, u32, i32)> = vec![
-        ("RustFrame", colors::TEXT_BLUE, 2),
-        ("", colors::TEXT_WHITE, 1),
-        ("Drag borders to resize", colors::TEXT_GRAY, 1),
-        ("Drag center to move", colors::TEXT_GRAY, 1),
-        ("", colors::TEXT_WHITE, 1),
-        ("ENTER - Start capture", colors::TEXT_WHITE, 1),
-        ("ESC   - Stop / Exit", colors::TEXT_WHITE, 1),
-        ("", colors::TEXT_WHITE, 1),
-        ("[S] Settings", colors::TEXT_GRAY, 1),
-        ("", colors::TEXT_WHITE, 1),
-        ("by Salih Cantekin", colors::TEXT_GRAY, 1),
-    ];
-
-    // Calculate line heights
-    const LINE_HEIGHT: i32 = 16;
-    const TITLE_HEIGHT: i32 = 28;
-    const EMPTY_LINE_HEIGHT: i32 = 8;
-
-    // Calculate total height including dynamic settings lines
-    let settings_lines_height = LINE_HEIGHT * 3; // 3 settings lines
-    let total_height: i32 = lines
-        .iter()
-        .map(|(text, _, scale)| {
-            if text.is_empty() {
-                EMPTY_LINE_HEIGHT
-            } else if *scale > 1 {
-                TITLE_HEIGHT
-            } else {
-                LINE_HEIGHT
-            }
-        })
-        .sum::<i32>() + settings_lines_height;
-
-    let mut y = (height - total_height) / 2;
-
-    // Draw static lines up to settings section
-    for (i, (text, color, scale)) in lines.iter().enumerate() {
-        if text.is_empty() {
-            y += EMPTY_LINE_HEIGHT;
-            continue;
-        }
-
-        let style = TextStyle {
-            color: *color,
-            scale: *scale,
-        };
-        let text_w = text_width(text, *scale);
-        let x = (width - text_w) / 2;
-
-        draw_text(&mut canvas, x, y, text, &style);
-
-        y += if *scale > 1 {
-            TITLE_HEIGHT
-        } else {
-            LINE_HEIGHT
-        };
-
-        // Insert dynamic settings lines after "ESC - Stop / Exit" (index 6)
-        if i == 6 {
-            y += EMPTY_LINE_HEIGHT; // Add spacing before settings
-            
-            // Draw cursor setting (green if ON, red if OFF)
-            let cursor_color = if show_cursor { colors::TEXT_GREEN } else { colors::TEXT_RED };
-            let cursor_style = TextStyle { color: cursor_color, scale: 1 };
-            let text_w = text_width(&cursor_line, 1);
-            let x = (width - text_w) / 2;
-            draw_text(&mut canvas, x, y, &cursor_line, &cursor_style);
-            y += LINE_HEIGHT;
-            
-            // Draw border setting
-            let border_color = if show_border { colors::TEXT_GREEN } else { colors::TEXT_RED };
-            let border_style = TextStyle { color: border_color, scale: 1 };
-            let text_w = text_width(&border_line, 1);
-            let x = (width - text_w) / 2;
-            draw_text(&mut canvas, x, y, &border_line, &border_style);
-            y += LINE_HEIGHT;
-            
-            // Draw mode setting
-            let mode_color = if exclude_from_capture { colors::TEXT_BLUE } else { colors::TEXT_YELLOW };
-            let mode_style = TextStyle { color: mode_color, scale: 1 };
-            let text_w = text_width(&mode_line, 1);
-            let x = (width - text_w) / 2;
-            draw_text(&mut canvas, x, y, &mode_line, &mode_style);
-            y += LINE_HEIGHT;
-        }
-    }
+/// Height of a line of text at a given scale
+pub fn text_height(scale: i32) -> i32 {
+    CHAR_HEIGHT * scale
 }
@@ -15,28 +15,40 @@
 // 3. Create a Direct3D11CaptureFramePool (manages texture buffers)
 // 4. Create a GraphicsCaptureSession and start it
 // 5. Handle FrameArrived events to get new frames
+//
+// On Windows 11 24H2 and later the session also reports which parts of each
+// frame changed (dirty regions); the renderer then uploads only those parts
+// and CaptureFrame::dirty hands them to library users.
 
-use anyhow::{anyhow, Context, Result};
 use log::{info, warn};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+
+use crate::game_capture::{self, BlackCause, BlackDetector, DesktopDuplication, Notice};
+use crate::source::FrameSource;
 use windows::{
     Foundation::TypedEventHandler,
     Graphics::{
-        Capture::{Direct3D11CaptureFramePool, GraphicsCaptureItem, GraphicsCaptureSession},
+        Capture::{
+            Direct3D11CaptureFramePool, GraphicsCaptureDirtyRegionMode, GraphicsCaptureItem,
+            GraphicsCaptureSession,
+        },
         DirectX::{
             Direct3D11::{IDirect3DDevice, IDirect3DSurface},
             DirectXPixelFormat,
         },
     },
     Win32::{
-        Foundation::RECT,
+        Foundation::{E_POINTER, RECT},
         Graphics::{
-            Direct3D::D3D_DRIVER_TYPE_HARDWARE,
+            Direct3D::{D3D_DRIVER_TYPE_HARDWARE, D3D_DRIVER_TYPE_UNKNOWN},
             Direct3D11::{
-                D3D11CreateDevice, ID3D11Device, ID3D11DeviceContext,
-                D3D11_CREATE_DEVICE_BGRA_SUPPORT, D3D11_SDK_VERSION,
+                D3D11CreateDevice, ID3D11Device, ID3D11DeviceContext, ID3D11Texture2D,
+                D3D11_BIND_FLAG, D3D11_CPU_ACCESS_READ, D3D11_CREATE_DEVICE_BGRA_SUPPORT,
+                D3D11_MAPPED_SUBRESOURCE, D3D11_MAP_READ, D3D11_RESOURCE_MISC_FLAG,
+                D3D11_SDK_VERSION, D3D11_TEXTURE2D_DESC, D3D11_USAGE_STAGING,
             },
-            Dxgi::IDXGIDevice,
+            Dxgi::{IDXGIAdapter, IDXGIDevice},
             Gdi::{GetMonitorInfoW, MonitorFromPoint, MONITORINFO, MONITOR_DEFAULTTONEAREST},
         },
         System::{
@@ -47,6 +59,84 @@ use windows::{
     },
 };
 
+/// Why capturing failed
+#[derive(Debug, thiserror::Error)]
+pub enum CaptureError {
+    /// Windows.Graphics.Capture is not available (Windows 10 before 1903,
+    /// Server editions without the desktop experience, some VMs)
+    #[error("Screen capture is not supported on this system (Windows 10 version 1903 or later is required)")]
+    Unsupported,
+    /// The GPU was reset or removed (driver update, GPU switch)
+    #[error("The graphics device was lost (driver update or GPU reset) - start the capture again")]
+    DeviceLost,
+    #[error("Invalid capture region: {0}")]
+    InvalidRegion(String),
+    /// Windows or a policy refused the capture
+    #[error("Windows denied the screen capture - check Settings → Privacy & security and any screen capture policy")]
+    PermissionDenied,
+    /// Media Foundation failed to encode a recording (the call, the OS error)
+    #[error("{0} failed: {1}")]
+    Encoder(&'static str, #[source] windows::core::Error),
+    /// Any other failure of a Windows API call (the call, the OS error)
+    #[error("{0} failed: {1}")]
+    Backend(&'static str, #[source] windows::core::Error),
+}
+
+impl CaptureError {
+    /// Classify the error of the Windows API call `call`
+    fn backend(call: &'static str, error: windows::core::Error) -> Self {
+        use windows::Win32::Foundation::{E_ACCESSDENIED, E_NOTIMPL, REGDB_E_CLASSNOTREG};
+        use windows::Win32::Graphics::Dxgi::{
+            DXGI_ERROR_DEVICE_HUNG, DXGI_ERROR_DEVICE_REMOVED, DXGI_ERROR_DEVICE_RESET,
+        };
+
+        match error.code() {
+            code if code == DXGI_ERROR_DEVICE_REMOVED
+                || code == DXGI_ERROR_DEVICE_RESET
+                || code == DXGI_ERROR_DEVICE_HUNG =>
+            {
+                Self::DeviceLost
+            }
+            code if code == E_ACCESSDENIED => Self::PermissionDenied,
+            code if code == REGDB_E_CLASSNOTREG || code == E_NOTIMPL => Self::Unsupported,
+            _ => Self::Backend(call, error),
+        }
+    }
+
+    /// Classifier for `map_err` at a named call
+    #[doc(hidden)]
+    pub fn at(call: &'static str) -> impl FnOnce(windows::core::Error) -> Self {
+        move |error| Self::backend(call, error)
+    }
+
+    /// Classifier for `map_err` at a named encoder call (only a lost device
+    /// is told apart - access denied there is about the file, not the screen)
+    pub(crate) fn encoder(call: &'static str) -> impl FnOnce(windows::core::Error) -> Self {
+        use windows::Win32::Graphics::Dxgi::{
+            DXGI_ERROR_DEVICE_HUNG, DXGI_ERROR_DEVICE_REMOVED, DXGI_ERROR_DEVICE_RESET,
+        };
+
+        move |error| match error.code() {
+            code if code == DXGI_ERROR_DEVICE_REMOVED
+                || code == DXGI_ERROR_DEVICE_RESET
+                || code == DXGI_ERROR_DEVICE_HUNG =>
+            {
+                Self::DeviceLost
+            }
+            _ => Self::Encoder(call, error),
+        }
+    }
+}
+
+impl From<windows::core::Error> for CaptureError {
+    fn from(error: windows::core::Error) -> Self {
+        Self::backend("Windows.Graphics.Capture", error)
+    }
+}
+
+/// Result of the capture API
+pub type Result<T> = std::result::Result<T, CaptureError>;
+
 /// Represents a rectangular region on the screen
 #[derive(Debug, Clone, Copy)]
 pub struct CaptureRect {
@@ -56,8 +146,9 @@ pub struct CaptureRect {
     pub height: u32,
 }
 
-/// Settings for the capture session
-#[derive(Debug, Clone)]
+/// Settings of a capture (the app stores them in settings.json)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CaptureSettings {
     /// Whether to show the mouse cursor in the capture
     pub show_cursor: bool,
@@ -67,7 +158,14 @@ pub struct CaptureSettings {
     pub border_width: u32,
     /// Whether to exclude destination from screen capture (prevents infinite mirror)
     /// Note: If true, Google Meet "window share" will show black. Use "screen share" instead.
+    /// Not stored - follows production / development mode
+    #[serde(skip)]
     pub exclude_from_capture: bool,
+    /// Capture exclusive-fullscreen games with Desktop Duplication when they
+    /// leave the region black (see game_capture.rs)
+    pub game_capture: bool,
+    /// Output frame rate limit (0 = render every captured frame)
+    pub target_fps: u32,
 }
 
 impl Default for CaptureSettings {
@@ -78,6 +176,8 @@ impl Default for CaptureSettings {
             show_border: true,
             border_width: crate::constants::capture::DEFAULT_BORDER_WIDTH,
             exclude_from_capture: true,
+            game_capture: true,
+            target_fps: crate::constants::capture::DEFAULT_TARGET_FPS,
         }
     }
 }
@@ -86,10 +186,8 @@ impl CaptureSettings {
     /// Development mode settings - destination window visible beside overlay
     pub fn for_development() -> Self {
         Self {
-            show_cursor: true,
-            show_border: true,
-            border_width: crate::constants::capture::DEFAULT_BORDER_WIDTH,
             exclude_from_capture: false,
+            ..Self::default()
         }
     }
 }
@@ -105,6 +203,19 @@ impl From<CaptureRect> for RECT {
     }
 }
 
+/// A GPU as DXGI describes it - the renderer looks for the same one, so
+/// captured frames do not travel between two GPUs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GpuAdapter {
+    pub name: String,
+    /// PCI vendor and device ids
+    pub vendor_id: u32,
+    pub device_id: u32,
+}
+
+/// Callback of CaptureEngine::set_frame_callback, shared with the FrameArrived handler
+type FrameCallback = Arc<std::sync::Mutex<Option<Box<dyn Fn() + Send>>>>;
+
 /// The main capture engine that wraps Windows.Graphics.Capture
 pub struct CaptureEngine {
     /// Direct3D11 device (COM object) - this is the GPU device
@@ -140,6 +251,36 @@ pub struct CaptureEngine {
 
     /// Flag indicating a new frame is ready
     frame_ready: Arc<std::sync::atomic::AtomicBool>,
+
+    /// Called on the capture thread whenever a frame arrives (see set_frame_callback)
+    frame_callback: FrameCallback,
+
+    /// When the newest frame taken from the pool was captured
+    /// (SystemRelativeTime: QueryPerformanceCounter time in 100 ns units)
+    latest_frame_time: std::sync::atomic::AtomicI64,
+
+    /// Whether the session reports dirty regions (Windows 11 24H2 or later)
+    dirty_regions: bool,
+
+    /// Dirty regions of the newest frame taken from the pool, in captured
+    /// surface coordinates (None = unknown)
+    latest_dirty: std::sync::Mutex<Option<Vec<CaptureRect>>>,
+
+    /// The dirty regions of the next frame do not describe what changed inside
+    /// the region (it is the first frame, or the region moved)
+    dirty_unknown: bool,
+
+    /// Switch to Desktop Duplication for exclusive-fullscreen games
+    game_capture: bool,
+
+    /// Watches for a region that stays black
+    black: BlackDetector,
+
+    /// Desktop Duplication while an exclusive-fullscreen game leaves WGC black
+    duplication: Option<DesktopDuplication>,
+
+    /// Why the region turned black, until the app takes it
+    notice: Option<Notice>,
 }
 
 impl CaptureEngine {
@@ -168,10 +309,7 @@ impl CaptureEngine {
                 let code = hr.0;
                 // Ignore RPC_E_CHANGED_MODE - COM already initialized
                 if code != 0x80010106u32 as i32 {
-                    return Err(anyhow!(
-                        "Failed to initialize COM: HRESULT 0x{:08X}",
-                        code as u32
-                    ));
+                    return Err(CaptureError::backend("CoInitializeEx", hr.into()));
                 }
                 info!("COM already initialized (different apartment type)");
             } else {
@@ -179,9 +317,23 @@ impl CaptureEngine {
             }
         }
 
+        // Windows.Graphics.Capture needs Windows 10 1903 or later
+        if !GraphicsCaptureSession::IsSupported().unwrap_or(false) {
+            return Err(CaptureError::Unsupported);
+        }
+
         // STEP 2: Create Direct3D11 Device
-        // This is the GPU device that will handle all graphics operations
-        let (d3d_device, d3d_context) = Self::create_d3d_device()?;
+        // This is the GPU device that will handle all graphics operations - on
+        // the GPU driving the captured monitor, so Windows does not have to copy
+        // every frame over from another GPU (hybrid graphics laptops)
+        let adapter = Self::adapter_for_monitor(overlay_position);
+        let (d3d_device, d3d_context) = match Self::create_d3d_device(adapter.as_ref()) {
+            Err(e) if adapter.is_some() => {
+                warn!("Failed to use the monitor's GPU ({}), using the default GPU", e);
+                Self::create_d3d_device(None)?
+            }
+            created => created?,
+        };
         info!("D3D11 device created");
 
         // STEP 3: Create WinRT Direct3D device wrapper
@@ -214,13 +366,26 @@ impl CaptureEngine {
         capture_session.SetIsCursorCaptureEnabled(settings.show_cursor)?;
         info!("Cursor capture enabled: {}", settings.show_cursor);
 
+        // Report which parts of each frame changed (fails before Windows 11 24H2)
+        let dirty_regions = capture_session
+            .SetDirtyRegionMode(GraphicsCaptureDirtyRegionMode::ReportOnly)
+            .is_ok();
+        info!("Dirty regions reported: {}", dirty_regions);
+
         // STEP 7: Set up frame arrival event handler
         // This is called every time a new frame is ready
         let frame_ready = Arc::new(std::sync::atomic::AtomicBool::new(false));
         let frame_ready_clone = Arc::clone(&frame_ready);
+        let frame_callback: FrameCallback = Arc::default();
+        let frame_callback_clone = Arc::clone(&frame_callback);
 
         frame_pool.FrameArrived(&TypedEventHandler::new(move |_pool, _args| {
             frame_ready_clone.store(true, std::sync::atomic::Ordering::Release);
+            if let Ok(callback) = frame_callback_clone.lock() {
+                if let Some(callback) = callback.as_ref() {
+                    callback();
+                }
+            }
             Ok(())
         }))?;
         info!("Frame arrival event handler registered");
@@ -239,21 +404,59 @@ impl CaptureEngine {
             capture_region: region,
             monitor_origin,
             frame_ready,
+            frame_callback,
+            latest_frame_time: std::sync::atomic::AtomicI64::new(0),
+            dirty_regions,
+            latest_dirty: std::sync::Mutex::new(None),
+            dirty_unknown: true,
+            game_capture: settings.game_capture,
+            black: BlackDetector::new(),
+            duplication: None,
+            notice: None,
         })
     }
 
-    /// Create a Direct3D11 device
+    /// The GPU whose output shows the monitor at `point` (None = not found)
+    fn adapter_for_monitor(point: (i32, i32)) -> Option<IDXGIAdapter> {
+        use windows::Win32::Graphics::Dxgi::{CreateDXGIFactory1, IDXGIFactory1};
+
+        let monitor = unsafe {
+            MonitorFromPoint(POINT { x: point.0, y: point.1 }, MONITOR_DEFAULTTONEAREST)
+        };
+        let factory: IDXGIFactory1 = unsafe { CreateDXGIFactory1() }.ok()?;
+        // EnumAdapters fails past the last adapter, EnumOutputs past the last output
+        for index in 0.. {
+            let adapter = unsafe { factory.EnumAdapters(index) }.ok()?;
+            let drives_monitor = (0..)
+                .map_while(|output| unsafe { adapter.EnumOutputs(output) }.ok())
+                .any(|output| unsafe { output.GetDesc() }.is_ok_and(|d| d.Monitor == monitor));
+            if drives_monitor {
+                return Some(adapter);
+            }
+        }
+        None
+    }
+
+    /// Create a Direct3D11 device on `adapter` (None = the default GPU)
     /// This is the GPU device that will handle all rendering and capture
-    fn create_d3d_device() -> Result<(ID3D11Device, ID3D11DeviceContext)> {
+    fn create_d3d_device(
+        adapter: Option<&IDXGIAdapter>,
+    ) -> Result<(ID3D11Device, ID3D11DeviceContext)> {
         let mut device = None;
         let mut context = None;
+        // A given adapter needs the "unknown" driver type
+        let driver_type = if adapter.is_some() {
+            D3D_DRIVER_TYPE_UNKNOWN
+        } else {
+            D3D_DRIVER_TYPE_HARDWARE
+        };
 
         // SAFETY: This is a standard D3D11 device creation call
         // We're using hardware acceleration (GPU) and BGRA support for better compatibility
         unsafe {
             D3D11CreateDevice(
-                None,                                           // Use default adapter (primary GPU)
-                D3D_DRIVER_TYPE_HARDWARE,                       // Use hardware acceleration
+                adapter,                                        // The monitor's GPU (or the default one)
+                driver_type,                                    // Use hardware acceleration
                 windows::Win32::Foundation::HMODULE::default(), // No software rasterizer
                 D3D11_CREATE_DEVICE_BGRA_SUPPORT, // Enable BGRA format (needed for WGC)
                 None,                             // Use default feature levels
@@ -262,13 +465,13 @@ impl CaptureEngine {
                 None,                             // Don't care about feature level
                 Some(&mut context),               // Output context
             )
-            .context("D3D11CreateDevice failed")?;
+            .map_err(CaptureError::at("D3D11CreateDevice"))?;
         }
 
-        Ok((
-            device.ok_or_else(|| anyhow!("Device creation returned null"))?,
-            context.ok_or_else(|| anyhow!("Context creation returned null"))?,
-        ))
+        match (device, context) {
+            (Some(device), Some(context)) => Ok((device, context)),
+            _ => Err(CaptureError::backend("D3D11CreateDevice", E_POINTER.into())),
+        }
     }
 
     /// Create a WinRT Direct3D device from a D3D11 device
@@ -281,22 +484,20 @@ impl CaptureEngine {
         // Cast the D3D11 device to a DXGI device
         let dxgi_device: IDXGIDevice = d3d_device
             .cast()
-            .context("Failed to cast ID3D11Device to IDXGIDevice")?;
+            .map_err(CaptureError::at("ID3D11Device to IDXGIDevice cast"))?;
 
         // Manually load CreateDirect3D11DeviceFromDXGIDevice from d3d11.dll
         // This function is not exposed in windows 0.58 but exists in the DLL
         unsafe {
             // Load d3d11.dll
             let dll_name = windows::core::w!("d3d11.dll");
-            let module =
-                LoadLibraryW(PCWSTR(dll_name.as_ptr())).context("Failed to load d3d11.dll")?;
+            let module = LoadLibraryW(PCWSTR(dll_name.as_ptr()))
+                .map_err(CaptureError::at("Loading d3d11.dll"))?;
 
             // Get the function pointer (ANSI name for GetProcAddress)
             let func_name = windows::core::s!("CreateDirect3D11DeviceFromDXGIDevice");
             let func_ptr = GetProcAddress(module, windows::core::PCSTR(func_name.as_ptr()))
-                .ok_or_else(|| {
-                    anyhow!("CreateDirect3D11DeviceFromDXGIDevice not found in d3d11.dll")
-                })?;
+                .ok_or(CaptureError::Unsupported)?;
 
             // Define the function signature
             type CreateDirect3D11DeviceFromDXGIDeviceFn =
@@ -312,15 +513,16 @@ impl CaptureEngine {
             let hr = create_fn(dxgi_device.as_raw() as *mut _, &mut result_ptr);
 
             if hr.is_err() {
-                return Err(anyhow!(
-                    "CreateDirect3D11DeviceFromDXGIDevice failed: HRESULT 0x{:08X}",
-                    hr.0 as u32
+                return Err(CaptureError::backend(
+                    "CreateDirect3D11DeviceFromDXGIDevice",
+                    hr.into(),
                 ));
             }
 
             if result_ptr.is_null() {
-                return Err(anyhow!(
-                    "CreateDirect3D11DeviceFromDXGIDevice returned null"
+                return Err(CaptureError::backend(
+                    "CreateDirect3D11DeviceFromDXGIDevice",
+                    E_POINTER.into(),
                 ));
             }
 
@@ -343,7 +545,10 @@ impl CaptureEngine {
         let monitor = unsafe { MonitorFromPoint(pt, MONITOR_DEFAULTTONEAREST) };
 
         if monitor.is_invalid() {
-            return Err(anyhow!("Failed to get monitor for point {:?}", point));
+            return Err(CaptureError::InvalidRegion(format!(
+                "no monitor at {:?}",
+                point
+            )));
         }
         info!("Detected monitor for point {:?}", point);
 
@@ -360,7 +565,10 @@ impl CaptureEngine {
 
         let info_ok = unsafe { GetMonitorInfoW(monitor, &mut monitor_info) }.as_bool();
         if !info_ok {
-            return Err(anyhow!("GetMonitorInfoW failed for primary monitor"));
+            return Err(CaptureError::InvalidRegion(format!(
+                "no monitor information for the monitor at {:?}",
+                point
+            )));
         }
 
         let item = unsafe { interop.CreateForMonitor(monitor)? };
@@ -375,6 +583,8 @@ impl CaptureEngine {
     pub fn update_region(&mut self, new_region: CaptureRect) -> Result<()> {
         info!("Updating capture region to {:?}", new_region);
         self.capture_region = new_region;
+        self.dirty_unknown = true;
+        self.black.reset();
 
         // Note: WGC captures the entire item (monitor/window)
         // Cropping happens in the rendering stage
@@ -384,6 +594,14 @@ impl CaptureEngine {
         Ok(())
     }
 
+    /// Call `callback` (on a capture thread) whenever a new frame arrives, so
+    /// an event loop can sleep until there is something to render
+    pub fn set_frame_callback(&self, callback: impl Fn() + Send + 'static) {
+        if let Ok(mut slot) = self.frame_callback.lock() {
+            *slot = Some(Box::new(callback));
+        }
+    }
+
     /// Update cursor visibility in the capture
     pub fn update_cursor_visibility(&self, show_cursor: bool) -> Result<()> {
         info!("Updating cursor visibility to: {}", show_cursor);
@@ -397,6 +615,25 @@ impl CaptureEngine {
         &self.d3d_device
     }
 
+    /// The GPU the capture runs on (None if DXGI does not tell)
+    pub fn gpu(&self) -> Option<GpuAdapter> {
+        use windows::core::Interface;
+
+        let description = self
+            .d3d_device
+            .cast::<IDXGIDevice>()
+            .and_then(|device| unsafe { device.GetAdapter() })
+            .and_then(|adapter| unsafe { adapter.GetDesc() })
+            .ok()?;
+        let name = &description.Description;
+        let len = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+        Some(GpuAdapter {
+            name: String::from_utf16_lossy(&name[..len]),
+            vendor_id: description.VendorId,
+            device_id: description.DeviceId,
+        })
+    }
+
     /// Get the D3D11 device context (needed by renderer)
     pub fn get_d3d_context(&self) -> &ID3D11DeviceContext {
         &self.d3d_context
@@ -412,6 +649,16 @@ impl CaptureEngine {
                     Ok(surface) => {
                         self.frame_ready
                             .store(false, std::sync::atomic::Ordering::Release);
+                        if let Ok(time) = frame.SystemRelativeTime() {
+                            self.latest_frame_time
+                                .store(time.Duration, std::sync::atomic::Ordering::Relaxed);
+                        }
+                        if self.dirty_regions {
+                            let dirty = Self::dirty_regions_of(&frame);
+                            if let Ok(mut latest) = self.latest_dirty.lock() {
+                                *latest = dirty;
+                            }
+                        }
                         return Some(surface);
                     }
                     Err(e) => {
@@ -426,6 +673,234 @@ impl CaptureEngine {
         None
     }
 
+    /// Dirty regions of a captured frame (None if Windows did not report them)
+    fn dirty_regions_of(
+        frame: &windows::Graphics::Capture::Direct3D11CaptureFrame,
+    ) -> Option<Vec<CaptureRect>> {
+        let regions = frame.DirtyRegions().ok()?;
+        (0..regions.Size().ok()?)
+            .map(|i| {
+                regions.GetAt(i).ok().map(|r| CaptureRect {
+                    x: r.X,
+                    y: r.Y,
+                    width: r.Width.max(0) as u32,
+                    height: r.Height.max(0) as u32,
+                })
+            })
+            .collect()
+    }
+
+    /// Parts of the region that changed with the newest frame read, relative to
+    /// the region (None = unknown: no dirty regions reported, or the region
+    /// moved since the previous frame)
+    pub fn take_dirty_rects(&mut self) -> Option<Vec<CaptureRect>> {
+        let dirty = self.latest_dirty.get_mut().ok()?.take();
+        if std::mem::take(&mut self.dirty_unknown) {
+            return None;
+        }
+        // The region in captured surface coordinates (as in copy_cropped_texture)
+        let left = (self.capture_region.x - self.monitor_origin.0).max(0);
+        let top = (self.capture_region.y - self.monitor_origin.1).max(0);
+        let right = left + self.capture_region.width as i32;
+        let bottom = top + self.capture_region.height as i32;
+        Some(
+            dirty?
+                .into_iter()
+                .filter_map(|r| {
+                    let x = r.x.max(left);
+                    let y = r.y.max(top);
+                    let width = (r.x + r.width as i32).min(right) - x;
+                    let height = (r.y + r.height as i32).min(bottom) - y;
+                    (width > 0 && height > 0).then_some(CaptureRect {
+                        x: x - left,
+                        y: y - top,
+                        width: width as u32,
+                        height: height as u32,
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    /// Cast WinRT IDirect3DSurface to COM ID3D11Texture2D using DXGI as bridge
+    /// This properly handles the WinRT↔COM interface conversion
+    fn cast_surface_to_texture(
+        surface: &windows::Graphics::DirectX::Direct3D11::IDirect3DSurface,
+    ) -> Result<ID3D11Texture2D> {
+        use windows::core::Interface;
+        use windows::Win32::System::WinRT::Direct3D11::IDirect3DDxgiInterfaceAccess;
+
+        // The correct way to get the underlying DXGI/D3D11 interface from a WinRT IDirect3DSurface
+        // is through IDirect3DDxgiInterfaceAccess::GetInterface()
+        unsafe {
+            // Cast the WinRT surface to the interop interface
+            let interop: IDirect3DDxgiInterfaceAccess = surface
+                .cast()
+                .map_err(CaptureError::at("IDirect3DSurface to IDirect3DDxgiInterfaceAccess cast"))?;
+
+            // Get the underlying D3D11 texture
+            let texture: ID3D11Texture2D = interop
+                .GetInterface()
+                .map_err(CaptureError::at("IDirect3DDxgiInterfaceAccess::GetInterface"))?;
+
+            Ok(texture)
+        }
+    }
+
+    /// Copy the cropped region of a captured texture into `frame` and return its size
+    ///
+    /// This is the bridge between Windows.Graphics.Capture (D3D11) and wgpu (DX12/Vulkan).
+    /// The process:
+    /// 1. Create a staging texture in D3D11 (CPU-readable)
+    /// 2. Copy the captured texture to the staging texture
+    /// 3. Map the staging texture and read the cropped pixel data to CPU
+    ///
+    /// The renderer uploads the pixels (or only their dirty regions) after compositing.
+    ///
+    /// WHY: wgpu and D3D11 don't share memory directly without using HAL (Hardware Abstraction Layer)
+    /// This is the simplest approach but involves a CPU roundtrip.
+    ///
+    /// PERFORMANCE: This is not ideal for real-time capture (adds latency and CPU overhead)
+    /// For production, you'd want to use:
+    /// - Direct3D12 interop with wgpu's DX12 backend
+    /// - wgpu HAL for zero-copy texture sharing
+    fn copy_cropped_texture(
+        &self,
+        d3d11_texture: &ID3D11Texture2D,
+        frame: &mut Vec<u32>,
+        log_crop: bool,
+    ) -> Result<(u32, u32)> {
+        let d3d_device = &self.d3d_device;
+        let d3d_context = &self.d3d_context;
+        let crop_region = self.capture_region;
+        let monitor_origin = self.monitor_origin;
+
+        // STEP 1: Get the texture description
+        let mut desc = D3D11_TEXTURE2D_DESC::default();
+        unsafe {
+            d3d11_texture.GetDesc(&mut desc);
+        }
+
+        // STEP 2: Create a staging texture (CPU-readable)
+        // This is necessary because the captured texture is on the GPU
+        let staging_desc = D3D11_TEXTURE2D_DESC {
+            Width: desc.Width,
+            Height: desc.Height,
+            MipLevels: 1,
+            ArraySize: 1,
+            Format: desc.Format, // Keep same format (should be BGRA8)
+            SampleDesc: windows::Win32::Graphics::Dxgi::Common::DXGI_SAMPLE_DESC {
+                Count: 1,
+                Quality: 0,
+            },
+            Usage: D3D11_USAGE_STAGING, // Staging = CPU-readable
+            BindFlags: D3D11_BIND_FLAG(0).0 as u32,
+            CPUAccessFlags: D3D11_CPU_ACCESS_READ.0 as u32, // Allow CPU reads
+            MiscFlags: D3D11_RESOURCE_MISC_FLAG(0).0 as u32,
+        };
+
+        let mut staging_texture: Option<ID3D11Texture2D> = None;
+        unsafe {
+            d3d_device
+                .CreateTexture2D(&staging_desc, None, Some(&mut staging_texture))
+                .map_err(CaptureError::at("Creating the staging texture"))?;
+        }
+
+        let staging_texture =
+            staging_texture.ok_or_else(|| CaptureError::backend("Creating the staging texture", E_POINTER.into()))?;
+
+        // STEP 3: Copy from captured texture to staging texture (GPU -> GPU)
+        unsafe {
+            d3d_context.CopyResource(&staging_texture, d3d11_texture);
+        }
+
+        // STEP 4: Map the staging texture (GPU -> CPU)
+        let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
+        unsafe {
+            d3d_context
+                .Map(
+                    &staging_texture,
+                    0,              // Subresource index
+                    D3D11_MAP_READ, // Read-only access
+                    0,              // No flags
+                    Some(&mut mapped),
+                )
+                .map_err(CaptureError::at("Mapping the staging texture"))?;
+        }
+
+        // STEP 5: Read pixel data from CPU memory
+        let row_pitch = mapped.RowPitch as usize;
+
+        // Compute crop window relative to captured surface (monitor origin aware)
+        let origin_x = (crop_region.x - monitor_origin.0).max(0) as usize;
+        let origin_y = (crop_region.y - monitor_origin.1).max(0) as usize;
+
+        let crop_width = crop_region
+            .width
+            .min(desc.Width.saturating_sub(origin_x as u32)) as usize;
+        let crop_height = crop_region
+            .height
+            .min(desc.Height.saturating_sub(origin_y as u32)) as usize;
+
+        // Log crop calculation for debugging (only first frame)
+        if log_crop {
+            info!(
+                "Crop calculation: monitor_origin=({},{}), crop_region=({},{} {}x{})",
+                monitor_origin.0,
+                monitor_origin.1,
+                crop_region.x,
+                crop_region.y,
+                crop_region.width,
+                crop_region.height
+            );
+            info!(
+                "Computed: origin=({},{}) size={}x{}, texture_size={}x{}",
+                origin_x, origin_y, crop_width, crop_height, desc.Width, desc.Height
+            );
+        }
+
+        if crop_width == 0 || crop_height == 0 {
+            unsafe {
+                d3d_context.Unmap(&staging_texture, 0);
+            }
+            return Err(CaptureError::InvalidRegion(
+                "the region is outside the captured monitor".to_string(),
+            ));
+        }
+
+        // Reuse the frame buffer between frames
+        // Assuming BGRA8 format (one u32 per pixel)
+        frame.resize(crop_width * crop_height, 0);
+
+        unsafe {
+            let src_ptr = mapped.pData as *const u8;
+            let dst_ptr = frame.as_mut_ptr() as *mut u8;
+
+            // Copy only the cropped region row by row (texture rows may have padding)
+            for y in 0..crop_height {
+                let src_offset = (origin_y + y) * row_pitch + origin_x * 4;
+                let dst_offset = y * crop_width * 4;
+
+                std::ptr::copy_nonoverlapping(
+                    src_ptr.add(src_offset),
+                    dst_ptr.add(dst_offset),
+                    crop_width * 4,
+                );
+            }
+
+            // STEP 6: Unmap the staging texture
+            d3d_context.Unmap(&staging_texture, 0);
+        }
+
+        Ok((crop_width as u32, crop_height as u32))
+    }
+
+    /// When the newest frame taken from the pool was captured (QueryPerformanceCounter
+    /// time in 100 ns units, 0 before the first frame)
+    pub fn get_latest_frame_time(&self) -> i64 {
+        self.latest_frame_time.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
     /// Get the capture region (for cropping in the renderer)
     pub fn get_capture_region(&self) -> CaptureRect {
         self.capture_region
@@ -437,7 +912,554 @@ impl CaptureEngine {
     }
 }
 
-// SAFETY: These are COM objects that are thread-safe
-// We need to implement Send to use CaptureEngine across threads
+impl CaptureEngine {
+    /// Copy the cropped region of the newest frame into `frame` and return its
+    /// size, or None when no frame arrived since the last call
+    fn read_newest_frame(&mut self, frame: &mut Vec<u32>) -> Result<Option<(u32, u32)>> {
+        if self.duplication.is_some() {
+            return self.read_duplicated_frame(frame);
+        }
+        let Some(surface) = self.get_latest_frame_surface() else {
+            if let Some(cause) = self.black.no_frame() {
+                self.region_black(cause);
+            }
+            return Ok(None);
+        };
+        let texture = Self::cast_surface_to_texture(&surface)?;
+        // Log the crop calculation for the first frame only
+        let size = self.copy_cropped_texture(&texture, frame, frame.is_empty())?;
+        if let Some(cause) = self.black.frame(frame) {
+            self.region_black(cause);
+        }
+        Ok(Some(size))
+    }
+
+    /// The region stayed black: tell protected content apart, and switch to
+    /// Desktop Duplication for an exclusive-fullscreen game
+    fn region_black(&mut self, mut cause: BlackCause) {
+        let center = (
+            self.capture_region.x + (self.capture_region.width / 2) as i32,
+            self.capture_region.y + (self.capture_region.height / 2) as i32,
+        );
+        let mut fallback = false;
+        match cause {
+            BlackCause::ExclusiveFullscreen if self.game_capture => {
+                match DesktopDuplication::new(&self.d3d_device, center) {
+                    Ok(duplication) => {
+                        self.duplication = Some(duplication);
+                        self.dirty_unknown = true;
+                        fallback = true;
+                    }
+                    Err(e) => warn!("Desktop Duplication is not available: {}", e),
+                }
+            }
+            BlackCause::Unknown if game_capture::protected_content(&self.d3d_device, center) => {
+                cause = BlackCause::ProtectedContent;
+                self.black.set_cause(cause);
+            }
+            _ => {}
+        }
+        let notice = Notice { cause, fallback };
+        warn!("{}", notice.message());
+        self.notice = Some(notice);
+    }
+
+    /// Read the region from Desktop Duplication; back to WGC once the game
+    /// left exclusive fullscreen
+    fn read_duplicated_frame(&mut self, frame: &mut Vec<u32>) -> Result<Option<(u32, u32)>> {
+        if !self.black.exclusive_fullscreen() {
+            info!("Exclusive fullscreen ended, capturing with Windows.Graphics.Capture again");
+            self.duplication = None;
+            self.black.reset();
+            self.dirty_unknown = true;
+            return Ok(None);
+        }
+        let Some(mut duplication) = self.duplication.take() else {
+            return Ok(None);
+        };
+        let result = match duplication.acquire(0) {
+            Ok(Some(duplicated)) => {
+                self.latest_frame_time
+                    .store(duplicated.time, std::sync::atomic::Ordering::Relaxed);
+                let size = self.copy_cropped_texture(&duplicated.texture, frame, false);
+                duplication.release();
+                size.map(Some)
+            }
+            other => other.map(|_| None),
+        };
+        self.duplication = Some(duplication);
+        // Desktop Duplication's dirty rectangles are not passed on
+        self.dirty_unknown = true;
+        result
+    }
+
+    /// Why the region turned black since the last call (see game_capture.rs)
+    pub fn take_notice(&mut self) -> Option<Notice> {
+        self.notice.take()
+    }
+
+    /// Short state of the black region detection for status lines: the
+    /// Desktop Duplication fallback, or why the region is black right now
+    pub fn black_status(&self) -> Option<&'static str> {
+        let cause = self.black.cause()?;
+        Some(
+            Notice {
+                cause,
+                fallback: self.duplication.is_some(),
+            }
+            .status(),
+        )
+    }
+}
+
+/// The main region, additional regions (see composite.rs) and CaptureSession
+/// all read their frames this way: the cropped region of the newest frame
+impl FrameSource for CaptureEngine {
+    fn read_frame(&mut self, frame: &mut Vec<u32>) -> anyhow::Result<Option<(u32, u32)>> {
+        Ok(self.read_newest_frame(frame)?)
+    }
+
+    fn dirty_rects(&mut self) -> Option<Vec<CaptureRect>> {
+        self.take_dirty_rects()
+    }
+}
+
+// SAFETY: the engine owns its COM objects (the D3D11 device is free-threaded,
+// the capture objects are agile), so it can be moved to another thread -
+// CaptureSession runs it on a capture thread. It is deliberately not Sync:
+// the immediate device context must not be used from two threads at once.
 unsafe impl Send for CaptureEngine {}
-unsafe impl Sync for CaptureEngine {}
+
+/// A frame delivered by `CaptureSession` and `FrameStream`
+#[derive(Debug, Clone, Default)]
+pub struct CaptureFrame {
+    /// BGRA pixels, one u32 per pixel, row by row without padding
+    pub pixels: Vec<u32>,
+    pub width: u32,
+    pub height: u32,
+    /// Frames delivered by the session so far (1 for the first frame)
+    pub number: u64,
+    /// When Windows captured the frame (QueryPerformanceCounter time in
+    /// 100 ns units, 0 = unknown)
+    pub capture_time: i64,
+    /// Rectangles that changed since the previous frame (frame coordinates),
+    /// so an encoder can update only those; None = unknown, treat the whole
+    /// frame as changed (always None for the first frame and before Windows 11
+    /// 24H2)
+    pub dirty: Option<Vec<CaptureRect>>,
+}
+
+/// Options of a `CaptureSession` (see `CaptureSession::builder`)
+pub struct CaptureSessionBuilder {
+    region: Option<CaptureRect>,
+    show_cursor: bool,
+    fps: u32,
+}
+
+impl CaptureSessionBuilder {
+    /// Screen region to capture (virtual screen coordinates)
+    pub fn region(mut self, region: CaptureRect) -> Self {
+        self.region = Some(region);
+        self
+    }
+
+    /// Include the mouse cursor in the frames (default: off)
+    pub fn cursor(mut self, show: bool) -> Self {
+        self.show_cursor = show;
+        self
+    }
+
+    /// Deliver at most this many frames per second (default: 0 = every
+    /// captured frame)
+    pub fn fps(mut self, fps: u32) -> Self {
+        self.fps = fps;
+        self
+    }
+
+    /// Start capturing
+    pub fn build(self) -> Result<CaptureSession> {
+        let region = self
+            .region
+            .ok_or_else(|| CaptureError::InvalidRegion("no region set".to_string()))?;
+        if region.width == 0 || region.height == 0 {
+            return Err(CaptureError::InvalidRegion("the region is empty".to_string()));
+        }
+        let settings = CaptureSettings {
+            show_cursor: self.show_cursor,
+            ..Default::default()
+        };
+        // The monitor that contains the center of the region is captured
+        let center = (
+            region.x + (region.width / 2) as i32,
+            region.y + (region.height / 2) as i32,
+        );
+        let engine = CaptureEngine::new(region, &settings, center)?;
+        let interval = (self.fps > 0)
+            .then(|| std::time::Duration::from_secs_f64(1.0 / self.fps as f64));
+
+        Ok(CaptureSession {
+            engine,
+            interval,
+            last_frame: None,
+            frame: CaptureFrame::default(),
+        })
+    }
+}
+
+/// Captures a screen region and hands out its frames - the engine lifecycle
+/// (COM, D3D11 device, WGC session, cropping) in one object for other
+/// applications. Capturing stops when the session is dropped.
+///
+/// ```no_run
+/// use rustframe::capture::{CaptureRect, CaptureSession};
+/// use std::time::Duration;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let mut session = CaptureSession::builder()
+///     .region(CaptureRect { x: 100, y: 100, width: 1280, height: 720 })
+///     .cursor(true)
+///     .fps(60)
+///     .build()?;
+/// while let Some(frame) = session.next_frame(Duration::from_secs(1))? {
+///     println!("#{}: {}x{}", frame.number, frame.width, frame.height);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct CaptureSession {
+    engine: CaptureEngine,
+    /// Shortest time between two delivered frames (None = no limit)
+    interval: Option<std::time::Duration>,
+    last_frame: Option<std::time::Instant>,
+    /// The last delivered frame (its buffer is reused)
+    frame: CaptureFrame,
+}
+
+impl CaptureSession {
+    pub fn builder() -> CaptureSessionBuilder {
+        CaptureSessionBuilder {
+            region: None,
+            show_cursor: false,
+            fps: 0,
+        }
+    }
+
+    /// The newest frame, or None when there is no new one yet (or the frame
+    /// rate limit was reached) - does not wait
+    pub fn try_next_frame(&mut self) -> Result<Option<&CaptureFrame>> {
+        let now = std::time::Instant::now();
+        if let (Some(interval), Some(last)) = (self.interval, self.last_frame) {
+            if now.duration_since(last) < interval {
+                return Ok(None);
+            }
+        }
+        let Some((width, height)) = self.engine.read_newest_frame(&mut self.frame.pixels)? else {
+            return Ok(None);
+        };
+        let dirty = self.engine.take_dirty_rects();
+        self.frame.dirty = dirty.filter(|_| {
+            self.frame.number > 0 && (self.frame.width, self.frame.height) == (width, height)
+        });
+        self.frame.width = width;
+        self.frame.height = height;
+        self.frame.number += 1;
+        self.frame.capture_time = self.engine.get_latest_frame_time();
+        self.last_frame = Some(now);
+        Ok(Some(&self.frame))
+    }
+
+    /// Wait up to `timeout` for the next frame (None = timed out; the screen
+    /// only delivers frames when something changes)
+    pub fn next_frame(&mut self, timeout: std::time::Duration) -> Result<Option<&CaptureFrame>> {
+        let deadline = std::time::Instant::now() + timeout;
+        let poll = std::time::Duration::from_millis(crate::constants::capture::SESSION_POLL_MS);
+        loop {
+            // Checked without borrowing the frame, so the loop can continue
+            if self.try_next_frame()?.is_some() {
+                return Ok(Some(&self.frame));
+            }
+            if std::time::Instant::now() >= deadline {
+                return Ok(None);
+            }
+            std::thread::sleep(poll);
+        }
+    }
+
+    /// The last delivered frame again (None before the first one)
+    pub fn last_frame(&self) -> Option<&CaptureFrame> {
+        (self.frame.number > 0).then_some(&self.frame)
+    }
+
+    pub fn region(&self) -> CaptureRect {
+        self.engine.get_capture_region()
+    }
+
+    /// Move or resize the captured region (on the same monitor)
+    pub fn set_region(&mut self, region: CaptureRect) -> Result<()> {
+        self.engine.update_region(region)
+    }
+
+    /// Show or hide the mouse cursor in the following frames
+    pub fn set_cursor(&self, show: bool) -> Result<()> {
+        self.engine.update_cursor_visibility(show)
+    }
+
+    /// The underlying engine, e.g. for its D3D11 device
+    pub fn engine(&self) -> &CaptureEngine {
+        &self.engine
+    }
+
+    /// Turn the session into an async stream of frames
+    ///
+    /// The session moves to its own thread, which reads the frames and queues
+    /// up to `capacity` of them. While the queue is full no more frames are
+    /// read (the screen keeps only the newest ones), so a slow consumer gets
+    /// fewer frames instead of a growing backlog - see `frames_with_policy`
+    /// for the other drop policies. The stream ends after an error; dropping
+    /// it stops the capture.
+    ///
+    /// ```no_run
+    /// # async fn run(session: rustframe::capture::CaptureSession) {
+    /// use futures_core::Stream;
+    /// use std::pin::Pin;
+    ///
+    /// let mut frames = session.frames(2);
+    /// // Or StreamExt::next() from futures / tokio-stream
+    /// while let Some(Ok(frame)) =
+    ///     std::future::poll_fn(|cx| Pin::new(&mut frames).poll_next(cx)).await
+    /// {
+    ///     println!("#{}: {}x{}", frame.number, frame.width, frame.height);
+    /// }
+    /// # }
+    /// ```
+    pub fn frames(self, capacity: usize) -> FrameStream {
+        FrameStream::start(self, capacity, DropPolicy::Wait)
+    }
+
+    /// Like `frames`, with a choice of what happens to new frames while the
+    /// queue is full (dropped frames are counted in `FrameStream::stats`)
+    pub fn frames_with_policy(self, capacity: usize, policy: DropPolicy) -> FrameStream {
+        FrameStream::start(self, capacity, policy)
+    }
+}
+
+/// What a `FrameStream` does with a new frame while its queue is full
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DropPolicy {
+    /// Stop reading until the consumer takes a frame - the screen keeps only
+    /// the newest frame, so the ones in between are never read
+    #[default]
+    Wait,
+    /// Drop the oldest queued frame - the consumer always gets the newest
+    /// frames (lowest latency)
+    DropOldest,
+    /// Drop the new frame - the consumer gets the queued frames in order
+    DropNewest,
+}
+
+/// Counters of a `FrameStream` (see `FrameStream::stats`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StreamStats {
+    /// Frames taken by the consumer
+    pub delivered: u64,
+    /// Frames dropped because the queue was full (always 0 with `DropPolicy::Wait`)
+    pub dropped: u64,
+    /// Frames waiting in the queue right now
+    pub queued: usize,
+}
+
+/// Async stream of captured frames (see `CaptureSession::frames`)
+pub struct FrameStream {
+    shared: Arc<StreamShared>,
+}
+
+/// The bounded queue between the capture thread and the consumer
+struct StreamQueue {
+    frames: std::collections::VecDeque<Result<CaptureFrame>>,
+    capacity: usize,
+    policy: DropPolicy,
+    delivered: u64,
+    dropped: u64,
+    /// A frame was dropped - the dirty rectangles of the next one are incomplete
+    dirty_lost: bool,
+    /// The capture thread ended (after an error, or the stream was dropped)
+    finished: bool,
+}
+
+/// State shared by the stream and its capture thread
+struct StreamShared {
+    queue: std::sync::Mutex<StreamQueue>,
+    /// Signalled when the consumer takes a frame or the stream is dropped
+    taken: std::sync::Condvar,
+    /// Task waiting for the next frame
+    waker: std::sync::Mutex<Option<std::task::Waker>>,
+    stopped: std::sync::atomic::AtomicBool,
+}
+
+impl StreamShared {
+    fn wake(&self) {
+        if let Some(waker) = self.waker.lock().ok().and_then(|mut w| w.take()) {
+            waker.wake();
+        }
+    }
+
+    /// Queue a frame following the drop policy; false once the stream was dropped
+    fn push(&self, mut frame: Result<CaptureFrame>, stop_check: std::time::Duration) -> bool {
+        use std::sync::atomic::Ordering;
+
+        let Ok(mut queue) = self.queue.lock() else {
+            return false;
+        };
+        while queue.frames.len() >= queue.capacity {
+            if self.stopped.load(Ordering::Acquire) {
+                return false;
+            }
+            // An error always gets into the queue, so the stream ends with it
+            match (queue.policy, frame.is_err()) {
+                (DropPolicy::Wait, _) => {
+                    // Backpressure: wait for the consumer (checking for the end)
+                    queue = match self.taken.wait_timeout(queue, stop_check) {
+                        Ok((queue, _)) => queue,
+                        Err(_) => return false,
+                    };
+                }
+                (DropPolicy::DropNewest, false) => {
+                    queue.dropped += 1;
+                    queue.dirty_lost = true;
+                    return true;
+                }
+                _ => {
+                    queue.frames.pop_front();
+                    queue.dropped += 1;
+                    match queue.frames.front_mut() {
+                        Some(Ok(next)) => next.dirty = None,
+                        _ => queue.dirty_lost = true,
+                    }
+                }
+            }
+        }
+        if let Ok(frame) = &mut frame {
+            if std::mem::take(&mut queue.dirty_lost) {
+                frame.dirty = None;
+            }
+        }
+        queue.frames.push_back(frame);
+        true
+    }
+
+    /// Mark the end of the stream
+    fn finish(&self) -> StreamStats {
+        let Ok(mut queue) = self.queue.lock() else {
+            return StreamStats::default();
+        };
+        queue.finished = true;
+        StreamStats {
+            delivered: queue.delivered,
+            dropped: queue.dropped,
+            queued: queue.frames.len(),
+        }
+    }
+}
+
+impl FrameStream {
+    fn start(mut session: CaptureSession, capacity: usize, policy: DropPolicy) -> Self {
+        use std::sync::atomic::Ordering;
+
+        let shared = Arc::new(StreamShared {
+            queue: std::sync::Mutex::new(StreamQueue {
+                frames: std::collections::VecDeque::new(),
+                capacity: capacity.max(1),
+                policy,
+                delivered: 0,
+                dropped: 0,
+                dirty_lost: false,
+                finished: false,
+            }),
+            taken: std::sync::Condvar::new(),
+            waker: std::sync::Mutex::new(None),
+            stopped: std::sync::atomic::AtomicBool::new(false),
+        });
+        let thread_shared = Arc::clone(&shared);
+        let stop_check =
+            std::time::Duration::from_millis(crate::constants::capture::STREAM_STOP_CHECK_MS);
+
+        std::thread::spawn(move || {
+            let shared = thread_shared;
+            while !shared.stopped.load(Ordering::Acquire) {
+                let next = match session.next_frame(stop_check) {
+                    Ok(Some(frame)) => Ok(frame.clone()),
+                    Ok(None) => continue,
+                    Err(e) => Err(e),
+                };
+                let failed = next.is_err();
+                if !shared.push(next, stop_check) {
+                    break;
+                }
+                shared.wake();
+                if failed {
+                    break;
+                }
+            }
+            let stats = shared.finish();
+            // Let a waiting task see the end of the stream
+            shared.wake();
+            info!(
+                "Frame stream stopped ({} frames delivered, {} dropped)",
+                stats.delivered, stats.dropped
+            );
+        });
+
+        Self { shared }
+    }
+
+    /// Frames delivered and dropped so far, and the current queue length
+    pub fn stats(&self) -> StreamStats {
+        match self.shared.queue.lock() {
+            Ok(queue) => StreamStats {
+                delivered: queue.delivered,
+                dropped: queue.dropped,
+                queued: queue.frames.len(),
+            },
+            Err(_) => StreamStats::default(),
+        }
+    }
+}
+
+impl futures_core::Stream for FrameStream {
+    type Item = Result<CaptureFrame>;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        use std::task::Poll;
+
+        // Register before checking, so a frame queued in between still wakes us
+        if let Ok(mut waker) = self.shared.waker.lock() {
+            *waker = Some(cx.waker().clone());
+        }
+        let Ok(mut queue) = self.shared.queue.lock() else {
+            return Poll::Ready(None);
+        };
+        match queue.frames.pop_front() {
+            Some(frame) => {
+                queue.delivered += 1;
+                drop(queue);
+                // Room for the next frame (DropPolicy::Wait)
+                self.shared.taken.notify_one();
+                Poll::Ready(Some(frame))
+            }
+            None if queue.finished => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl Drop for FrameStream {
+    fn drop(&mut self) {
+        self.shared
+            .stopped
+            .store(true, std::sync::atomic::Ordering::Release);
+        self.shared.taken.notify_one();
+    }
+}
@@ -0,0 +1,289 @@
+// check_config.rs - Settings File Validation
+//
+// `RustFrame check-config [path]` checks a settings.json before it is
+// deployed - a profile shared by a team, a file written by a script - and
+// lists every problem instead of stopping at the first:
+//
+//   RustFrame check-config team-settings.json
+//   team-settings.json: 2 problems
+//     target_fps: 500 is above the maximum of 240
+//     hotkeys: Start / stop capture and Take a screenshot use the same shortcut
+//
+// - JSON syntax and types (the same parsing RustFrame does when it starts)
+// - Unknown fields: RustFrame ignores them, so a typo silently does nothing
+// - Values outside the ranges of the Settings dialog (border width,
+//   countdown, frame rate, ...), ports of enabled servers
+// - Names: filters, shortcut and MIDI action ids, MIDI triggers, webhook
+//   events and URLs
+// - Shortcuts that do not parse or are used twice (see settings_file.rs)
+//
+// Without a path the settings.json RustFrame uses is checked. The exit code
+// is 0 for a valid file and 10 when problems were found (see cli.rs); --json
+// prints { "path": ..., "problems": [{ "field": ..., "message": ... }] }.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::net::IpAddr;
+use std::path::Path;
+
+use crate::app_settings::AppSettings;
+use crate::constants::capture as limits;
+use crate::filter::FilterKind;
+use crate::webhook::WebhookEvent;
+use crate::{hotkeys, midi, theme};
+
+/// Something wrong in the file
+#[derive(Debug, Clone, Serialize)]
+pub struct Problem {
+    /// Where, e.g. "profiles[1].target_fps" (empty = the whole file)
+    pub field: String,
+    pub message: String,
+}
+
+impl Problem {
+    fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Check the file at `path`; an error means it could not be read at all
+pub fn check_file(path: &Path) -> std::io::Result<Vec<Problem>> {
+    let text = std::fs::read_to_string(path)?;
+    Ok(check(&text))
+}
+
+/// Every problem of a settings.json text
+pub fn check(text: &str) -> Vec<Problem> {
+    let input: Value = match serde_json::from_str(text) {
+        Ok(value) => value,
+        Err(e) => return vec![Problem::new("", format!("Invalid JSON: {}", e))],
+    };
+    let settings: AppSettings = match serde_json::from_value(input.clone()) {
+        Ok(settings) => settings,
+        Err(e) => return vec![Problem::new("", e.to_string())],
+    };
+
+    let mut problems = Vec::new();
+    // Everything RustFrame reads comes back when the settings are written
+    // again - what does not was ignored
+    if let Ok(known) = serde_json::to_value(&settings) {
+        unknown_fields(&input, &known, "", &mut problems);
+    }
+    check_values(&settings, &mut problems);
+    problems
+}
+
+fn unknown_fields(input: &Value, known: &Value, field: &str, problems: &mut Vec<Problem>) {
+    match (input, known) {
+        (Value::Object(input), Value::Object(known)) => {
+            for (key, value) in input {
+                let path = if field.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", field, key)
+                };
+                match known.get(key) {
+                    Some(known) => unknown_fields(value, known, &path, problems),
+                    // Fields only written when set (moved secrets) may be empty
+                    None if value.as_str() == Some("") || value.is_null() => {}
+                    None => {
+                        problems.push(Problem::new(path, "Unknown field (ignored by RustFrame)"))
+                    }
+                }
+            }
+        }
+        (Value::Array(input), Value::Array(known)) => {
+            for (i, (value, known)) in input.iter().zip(known).enumerate() {
+                unknown_fields(value, known, &format!("{}[{}]", field, i), problems);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_values(settings: &AppSettings, problems: &mut Vec<Problem>) {
+    let mut range = |field: &str, value: u32, min: u32, max: u32| {
+        if value < min {
+            problems.push(Problem::new(
+                field,
+                format!("{} is below the minimum of {}", value, min),
+            ));
+        } else if value > max {
+            problems.push(Problem::new(
+                field,
+                format!("{} is above the maximum of {}", value, max),
+            ));
+        }
+    };
+    range(
+        "border_width",
+        settings.capture.border_width,
+        limits::MIN_BORDER_WIDTH,
+        limits::MAX_BORDER_WIDTH,
+    );
+    range(
+        "countdown_seconds",
+        settings.countdown_seconds,
+        0,
+        limits::MAX_COUNTDOWN_SECONDS,
+    );
+    range(
+        "hold_seconds",
+        settings.hold_seconds,
+        0,
+        limits::MAX_HOLD_SECONDS,
+    );
+    range(
+        "pan_seconds",
+        settings.pan_seconds,
+        1,
+        limits::MAX_PAN_SECONDS,
+    );
+    range(
+        "transition_ms",
+        settings.transition_ms,
+        0,
+        limits::MAX_TRANSITION_MS,
+    );
+    range(
+        "target_fps",
+        settings.capture.target_fps,
+        0,
+        limits::MAX_TARGET_FPS,
+    );
+    range("idle_fps", settings.idle_fps, 0, limits::MAX_TARGET_FPS);
+    range("low_power_fps", settings.low_power_fps, 0, limits::MAX_TARGET_FPS);
+    range(
+        "idle_after_ms",
+        settings.idle_after_ms,
+        0,
+        limits::MAX_IDLE_AFTER_MS,
+    );
+    range(
+        "slide_seconds",
+        settings.slide_seconds,
+        0,
+        limits::MAX_SLIDE_SECONDS,
+    );
+    for (i, profile) in settings.profiles.iter().enumerate() {
+        if let Some(fps) = profile.target_fps {
+            range(
+                &format!("profiles[{}].target_fps", i),
+                fps,
+                0,
+                limits::MAX_TARGET_FPS,
+            );
+        }
+        if let Some(width) = profile.border_width {
+            range(
+                &format!("profiles[{}].border_width", i),
+                width,
+                limits::MIN_BORDER_WIDTH,
+                limits::MAX_BORDER_WIDTH,
+            );
+        }
+    }
+
+    if settings.stream_deck && settings.stream_deck_port == 0 {
+        problems.push(Problem::new(
+            "stream_deck_port",
+            "Port 0 with stream_deck enabled",
+        ));
+    }
+    if settings.osc && settings.osc_port == 0 {
+        problems.push(Problem::new("osc_port", "Port 0 with osc enabled"));
+    }
+    if settings.osc_bind.trim().parse::<IpAddr>().is_err() {
+        problems.push(Problem::new(
+            "osc_bind",
+            format!("Not an IP address: {:?}", settings.osc_bind),
+        ));
+    }
+    for (i, source) in settings.osc_allowed_sources.iter().enumerate() {
+        if source.trim().parse::<IpAddr>().is_err() {
+            problems.push(Problem::new(
+                format!("osc_allowed_sources[{}]", i),
+                format!("Not an IP address: {:?}", source),
+            ));
+        }
+    }
+    let color = &settings.transition_color;
+    if theme::parse_accent(color).is_none() {
+        problems.push(Problem::new(
+            "transition_color",
+            format!("Invalid color '{}' (\"#RRGGBB\")", color),
+        ));
+    }
+
+    check_filters("filters", &settings.filters, problems);
+    for (i, profile) in settings.profiles.iter().enumerate() {
+        if let Some(filters) = &profile.filters {
+            check_filters(&format!("profiles[{}].filters", i), filters, problems);
+        }
+    }
+
+    for (id, text) in &settings.hotkeys {
+        if hotkeys::HotkeyAction::from_id(id).is_none() {
+            problems.push(Problem::new(
+                format!("hotkeys.{}", id),
+                "Unknown shortcut action",
+            ));
+        } else if !text.trim().is_empty() {
+            if let Err(e) = hotkeys::parse_hotkey(text) {
+                problems.push(Problem::new(format!("hotkeys.{}", id), e.to_string()));
+            }
+        }
+    }
+    let bindings = hotkeys::resolve(&settings.hotkeys);
+    for (a, b) in hotkeys::conflicts(&bindings) {
+        problems.push(Problem::new(
+            "hotkeys",
+            format!("{} and {} use the same shortcut", a.label(), b.label()),
+        ));
+    }
+
+    let midi_actions: Vec<String> = midi::MidiAction::all()
+        .into_iter()
+        .map(|a| a.id())
+        .collect();
+    for (id, trigger) in &settings.midi_mappings {
+        let field = format!("midi_mappings.{}", id);
+        if !midi_actions.contains(id) {
+            problems.push(Problem::new(field, "Unknown MIDI action"));
+        } else if !trigger.trim().is_empty() && midi::MidiTrigger::parse(trigger).is_none() {
+            problems.push(Problem::new(
+                field,
+                format!("Invalid trigger '{}' (\"note 36\" or \"cc 7\")", trigger),
+            ));
+        }
+    }
+
+    for (i, webhook) in settings.webhooks.iter().enumerate() {
+        let url = webhook.url.trim();
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            problems.push(Problem::new(
+                format!("webhooks[{}].url", i),
+                "Not an http:// or https:// URL",
+            ));
+        }
+        for event in &webhook.events {
+            if WebhookEvent::from_id(event).is_none() {
+                problems.push(Problem::new(
+                    format!("webhooks[{}].events", i),
+                    format!("Unknown event '{}'", event),
+                ));
+            }
+        }
+    }
+}
+
+fn check_filters(field: &str, names: &[String], problems: &mut Vec<Problem>) {
+    for name in names {
+        if FilterKind::from_name(name).is_none() {
+            problems.push(Problem::new(field, format!("Unknown filter '{}'", name)));
+        }
+    }
+}
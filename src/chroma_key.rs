@@ -0,0 +1,205 @@
+// chroma_key.rs - Chroma Key Filter
+//
+// Makes a key color (green screen, blue screen...) transparent in the output.
+// The keying itself runs on the GPU in the fragment shader (shader.wgsl); this
+// module only holds the user-facing settings and converts them into the
+// shader's uniform block.
+//
+// Pixels are compared with the key color in the CbCr (chroma) plane, so
+// shadows and highlights on a green backdrop are keyed as well:
+// - distance < similarity                        -> fully transparent
+// - similarity <= distance < similarity + smooth -> partially transparent
+// - otherwise                                    -> opaque
+//
+// The destination window is an opaque swapchain surface that screen sharing
+// tools capture without alpha, so keyed areas are shown over a checkerboard
+// there. Screenshots run the same keying on the CPU (Keyer) and keep the
+// result as the PNG alpha channel.
+
+use serde::{Deserialize, Serialize};
+
+use crate::constants::chroma_key;
+
+/// Color that is keyed out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyColor {
+    Green,
+    Blue,
+    Magenta,
+}
+
+impl KeyColor {
+    pub fn label(self) -> &'static str {
+        match self {
+            KeyColor::Green => "Green",
+            KeyColor::Blue => "Blue",
+            KeyColor::Magenta => "Magenta",
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            KeyColor::Green => KeyColor::Blue,
+            KeyColor::Blue => KeyColor::Magenta,
+            KeyColor::Magenta => KeyColor::Green,
+        }
+    }
+
+    /// sRGB color as 0xRRGGBB
+    fn rgb(self) -> u32 {
+        match self {
+            KeyColor::Green => chroma_key::GREEN,
+            KeyColor::Blue => chroma_key::BLUE,
+            KeyColor::Magenta => chroma_key::MAGENTA,
+        }
+    }
+}
+
+/// Uniform block consumed by shader.wgsl (must match `struct ChromaKey` there)
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ChromaKeyUniform {
+    /// Key color in linear RGB (the frame texture is sampled as sRGB -> linear)
+    key_color: [f32; 4],
+    similarity: f32,
+    smoothness: f32,
+    /// 1 = keying enabled, 0 = passthrough
+    enabled: u32,
+    /// Checkerboard cell size in pixels (destination window preview)
+    checker_size: f32,
+}
+
+/// Chroma key settings for the session (saved with scenes)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChromaKey {
+    enabled: bool,
+    color: KeyColor,
+    /// Index into SIMILARITY_PRESETS
+    similarity: usize,
+    /// Index into SMOOTHNESS_PRESETS
+    smoothness: usize,
+}
+
+impl ChromaKey {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            color: KeyColor::Green,
+            similarity: chroma_key::DEFAULT_SIMILARITY,
+            smoothness: chroma_key::DEFAULT_SMOOTHNESS,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn color(&self) -> KeyColor {
+        self.color
+    }
+
+    pub fn cycle_color(&mut self) {
+        self.color = self.color.next();
+    }
+
+    /// Similarity in percent (size of the fully transparent range)
+    pub fn similarity_percent(&self) -> u32 {
+        // A scene from settings.json may have an index out of range
+        let presets = chroma_key::SIMILARITY_PRESETS;
+        presets
+            .get(self.similarity)
+            .copied()
+            .unwrap_or(presets[chroma_key::DEFAULT_SIMILARITY])
+    }
+
+    pub fn cycle_similarity(&mut self) {
+        self.similarity = (self.similarity + 1) % chroma_key::SIMILARITY_PRESETS.len();
+    }
+
+    /// Smoothness in percent (width of the partially transparent edge)
+    pub fn smoothness_percent(&self) -> u32 {
+        let presets = chroma_key::SMOOTHNESS_PRESETS;
+        presets
+            .get(self.smoothness)
+            .copied()
+            .unwrap_or(presets[chroma_key::DEFAULT_SMOOTHNESS])
+    }
+
+    pub fn cycle_smoothness(&mut self) {
+        self.smoothness = (self.smoothness + 1) % chroma_key::SMOOTHNESS_PRESETS.len();
+    }
+
+    /// Shader parameters for the current settings
+    pub fn uniform(&self) -> ChromaKeyUniform {
+        let rgb = self.color.rgb();
+        let channel = |shift: u32| srgb_to_linear(((rgb >> shift) & 0xFF) as f32 / 255.0);
+        ChromaKeyUniform {
+            key_color: [channel(16), channel(8), channel(0), 1.0],
+            similarity: self.similarity_percent() as f32 / 100.0,
+            smoothness: self.smoothness_percent() as f32 / 100.0,
+            enabled: self.enabled as u32,
+            checker_size: chroma_key::CHECKER_SIZE,
+        }
+    }
+
+    /// CPU keyer for the current settings (None while keying is off)
+    pub fn keyer(&self) -> Option<Keyer> {
+        if !self.enabled {
+            return None;
+        }
+        let uniform = self.uniform();
+        let mut linear = [0.0; 256];
+        for (i, value) in linear.iter_mut().enumerate() {
+            *value = srgb_to_linear(i as f32 / 255.0);
+        }
+        let key = uniform.key_color;
+        Some(Keyer {
+            linear,
+            key_cbcr: rgb_to_cbcr([key[0], key[1], key[2]]),
+            similarity: uniform.similarity,
+            smoothness: uniform.smoothness,
+        })
+    }
+}
+
+/// CPU version of chroma_alpha in shader.wgsl, used for screenshot alpha
+pub struct Keyer {
+    /// sRGB byte -> linear lookup table
+    linear: [f32; 256],
+    key_cbcr: [f32; 2],
+    similarity: f32,
+    smoothness: f32,
+}
+
+impl Keyer {
+    /// Alpha for an ARGB pixel: 0 = matches the key color, 255 = keep
+    pub fn alpha(&self, pixel: u32) -> u8 {
+        let channel = |shift: u32| self.linear[((pixel >> shift) & 0xFF) as usize];
+        let cbcr = rgb_to_cbcr([channel(16), channel(8), channel(0)]);
+        let (db, dr) = (cbcr[0] - self.key_cbcr[0], cbcr[1] - self.key_cbcr[1]);
+        let distance = (db * db + dr * dr).sqrt();
+        // smoothstep(similarity, similarity + smoothness, distance)
+        let t = ((distance - self.similarity) / self.smoothness).clamp(0.0, 1.0);
+        (t * t * (3.0 - 2.0 * t) * 255.0).round() as u8
+    }
+}
+
+/// Blue-difference and red-difference chroma (BT.709), as in shader.wgsl
+fn rgb_to_cbcr(rgb: [f32; 3]) -> [f32; 2] {
+    let y = rgb[0] * 0.2126 + rgb[1] * 0.7152 + rgb[2] * 0.0722;
+    [(rgb[2] - y) * 0.5389, (rgb[0] - y) * 0.6350]
+}
+
+/// sRGB transfer function -> linear
+pub fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
@@ -0,0 +1,126 @@
+// cli.rs - Exit Codes and Errors of the Command Line
+//
+// `RustFrame record / shot / list-monitors / list-windows` and rustframe-ctl
+// end with a documented exit code, so a script wrapping them can react to
+// the kind of failure instead of parsing the message:
+//
+//   0  success                 5  permission denied
+//   1  any other error         6  graphics device lost
+//   2  invalid region          7  invalid arguments
+//   3  capture unsupported     8  output file not written
+//   4  encoder failure         9  RustFrame is not running (rustframe-ctl)
+//                             10  the configuration has problems (check-config)
+//
+// With --json-errors the error goes to stderr as one JSON line instead of
+// text (stdout keeps the normal output):
+//
+//   {"error":{"code":2,"kind":"invalid_region","message":"Invalid capture region: ..."}}
+//
+// The codes are part of the command line interface (docs/cli.md): a code
+// never changes its meaning, new kinds of failures get new numbers.
+
+use crate::capture::CaptureError;
+use serde::Serialize;
+
+/// Kind of failure; the discriminant is the exit code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    Other = 1,
+    /// Empty or off-screen region, unknown monitor or window
+    InvalidRegion = 2,
+    /// Windows.Graphics.Capture is not available
+    Unsupported = 3,
+    /// Media Foundation could not encode the video
+    Encoder = 4,
+    PermissionDenied = 5,
+    DeviceLost = 6,
+    /// Unknown command or option, missing or malformed value
+    Usage = 7,
+    /// The output file could not be written
+    Output = 8,
+    /// rustframe-ctl found no running RustFrame
+    NotRunning = 9,
+    /// RustFrame check-config found problems in the settings file
+    InvalidConfig = 10,
+}
+
+impl FailureKind {
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+
+    /// Kind of a capture API error
+    pub fn of(error: &CaptureError) -> Self {
+        match error {
+            CaptureError::Unsupported => Self::Unsupported,
+            CaptureError::DeviceLost => Self::DeviceLost,
+            CaptureError::InvalidRegion(_) => Self::InvalidRegion,
+            CaptureError::PermissionDenied => Self::PermissionDenied,
+            CaptureError::Encoder(..) => Self::Encoder,
+            CaptureError::Backend(..) => Self::Other,
+        }
+    }
+}
+
+/// A failed command: the kind of failure and the message for the user
+#[derive(Debug, Clone)]
+pub struct CliError {
+    pub kind: FailureKind,
+    pub message: String,
+    /// Usage text printed after the message (not in JSON)
+    pub usage: Option<&'static str>,
+}
+
+impl CliError {
+    pub fn new(kind: FailureKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            usage: None,
+        }
+    }
+
+    /// Invalid arguments, followed by the usage text
+    pub fn usage(message: impl Into<String>, usage: &'static str) -> Self {
+        Self {
+            usage: Some(usage),
+            ..Self::new(FailureKind::Usage, message)
+        }
+    }
+
+    /// A capture API error, e.g. `CliError::capture("Recording failed", &e)`
+    pub fn capture(context: &str, error: &CaptureError) -> Self {
+        Self::new(FailureKind::of(error), format!("{}: {}", context, error))
+    }
+
+    pub fn code(&self) -> u8 {
+        self.kind.code()
+    }
+
+    /// Print the error to stderr (as JSON with `json`) and return the exit code
+    pub fn report(&self, json: bool) -> u8 {
+        if json {
+            let error = serde_json::json!({
+                "error": {
+                    "code": self.code(),
+                    "kind": self.kind,
+                    "message": self.message,
+                }
+            });
+            eprintln!("{}", error);
+        } else if let Some(usage) = self.usage {
+            eprintln!("{}\n\n{}", self.message, usage);
+        } else {
+            eprintln!("{}", self.message);
+        }
+        self.code()
+    }
+}
+
+/// Remove --json-errors from the arguments; true if it was there
+pub fn take_json_errors(args: &mut Vec<String>) -> bool {
+    let before = args.len();
+    args.retain(|arg| arg != "--json-errors");
+    args.len() != before
+}
@@ -0,0 +1,254 @@
+// click_highlight.rs - Click and Drag Highlights
+//
+// Makes mouse actions visible to viewers: every click leaves an expanding
+// ring (blue = left, orange = right, green = middle button) and every drag
+// leaves its path with an arrow head at the release point. Both disappear
+// after a moment. The path of a drag in progress is drawn live.
+//
+// What exactly was pressed is visible too:
+// - A double click shows two concentric rings
+// - With modifier keys held the ring is outlined and labelled ("Ctrl+click",
+//   "Shift+drag", "Ctrl+double-click")
+//
+// The gestures come from the low-level mouse hook (see mouse_hook.rs) in
+// screen coordinates and are drawn relative to the capture region.
+
+use std::time::Instant;
+
+use crate::accessibility;
+use crate::bitmap_font::{self, Canvas, TextStyle};
+use crate::constants::click_highlight;
+use crate::drawing;
+use crate::mouse_hook::{MouseButton, MouseGesture};
+
+/// Recent clicks and drags drawn into the output
+pub struct ClickHighlight {
+    enabled: bool,
+    /// Released gestures that are still fading out
+    recent: Vec<MouseGesture>,
+    /// Gesture while a button is held
+    current: Option<MouseGesture>,
+    /// Screen position of the capture region's top-left corner
+    origin: (i32, i32),
+    /// Bumped on every visible change so the renderer knows when to recomposite
+    revision: u64,
+}
+
+impl ClickHighlight {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            recent: Vec::new(),
+            current: None,
+            origin: (0, 0),
+            revision: 0,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if self.enabled != enabled {
+            self.enabled = enabled;
+            self.recent.clear();
+            self.current = None;
+            self.revision += 1;
+        }
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Take the newest gestures from the hook and drop the faded ones
+    /// `origin`: screen position of the capture region
+    pub fn update(
+        &mut self,
+        finished: Vec<MouseGesture>,
+        current: Option<MouseGesture>,
+        origin: (i32, i32),
+    ) {
+        let was_visible = self.is_active();
+        let moved = self.origin != origin;
+        let current_changed = match (&self.current, &current) {
+            (None, None) => false,
+            (Some(a), Some(b)) => a.points.len() != b.points.len(),
+            _ => true,
+        };
+
+        self.recent.extend(finished);
+        self.recent
+            .retain(|g| g.end().at.elapsed().as_millis() < click_highlight::FADE_MS as u128);
+        self.current = current;
+        self.origin = origin;
+
+        // Rings keep expanding while anything is visible, so every frame changes
+        if was_visible || self.is_active() || moved || current_changed {
+            self.revision += 1;
+        }
+    }
+
+    /// True if something would be drawn
+    pub fn is_active(&self) -> bool {
+        self.enabled && (!self.recent.is_empty() || self.current.is_some())
+    }
+
+    /// Draw the rings and drag paths into the frame
+    pub fn draw(&self, canvas: &mut Canvas) {
+        if !self.enabled {
+            return;
+        }
+        let now = Instant::now();
+        for gesture in self.recent.iter().chain(self.current.iter()) {
+            let color = button_color(gesture.button);
+            if gesture.is_drag() {
+                self.draw_path(canvas, gesture, color);
+            } else if gesture.released {
+                // Ring grows from the click point while the highlight fades
+                // (full size right away with Windows animation effects off)
+                let age = now.duration_since(gesture.end().at).as_millis() as i32;
+                let progress = if accessibility::reduce_motion() {
+                    click_highlight::FADE_MS as i32
+                } else {
+                    age.min(click_highlight::FADE_MS as i32)
+                };
+                let radius = click_highlight::RING_START_RADIUS
+                    + (click_highlight::RING_END_RADIUS - click_highlight::RING_START_RADIUS)
+                        * progress
+                        / click_highlight::FADE_MS as i32;
+                let (x, y) = self.to_canvas(gesture.start().x, gesture.start().y);
+                let outlined = gesture.modifiers.any();
+                draw_ring(canvas, x, y, radius, color, outlined);
+                if gesture.clicks >= 2 {
+                    let inner = radius * click_highlight::DOUBLE_CLICK_INNER_PERCENT / 100;
+                    draw_ring(canvas, x, y, inner, color, outlined);
+                }
+            } else {
+                // Button held without moving yet
+                let (x, y) = self.to_canvas(gesture.start().x, gesture.start().y);
+                drawing::fill_circle(canvas, x, y, click_highlight::RING_START_RADIUS / 2, color);
+            }
+
+            if let Some(text) = describe(gesture) {
+                let (x, y) = self.to_canvas(gesture.start().x, gesture.start().y);
+                draw_label(canvas, x + click_highlight::RING_END_RADIUS + 6, y, &text);
+            }
+        }
+    }
+
+    /// Path of a drag, with an arrow head at the release point
+    fn draw_path(&self, canvas: &mut Canvas, gesture: &MouseGesture, color: u32) {
+        let points: Vec<(i32, i32)> = gesture
+            .points
+            .iter()
+            .map(|p| self.to_canvas(p.x, p.y))
+            .collect();
+        let width = click_highlight::LINE_WIDTH;
+        for pair in points.windows(2) {
+            drawing::draw_line(
+                canvas, pair[0].0, pair[0].1, pair[1].0, pair[1].1, width, color,
+            );
+        }
+        let (x, y) = points[0];
+        drawing::fill_circle(canvas, x, y, width as i32 * 2, color);
+        if gesture.released && points.len() >= 2 {
+            // Short arrow along the last stretch of the path
+            let end = points[points.len() - 1];
+            let from = points
+                .iter()
+                .rev()
+                .find(|p| (p.0 - end.0).abs() + (p.1 - end.1).abs() > click_highlight::ARROW_LENGTH)
+                .unwrap_or(&points[0]);
+            drawing::draw_arrow(canvas, from.0, from.1, end.0, end.1, width, color);
+        }
+    }
+
+    fn to_canvas(&self, x: i32, y: i32) -> (i32, i32) {
+        (x - self.origin.0, y - self.origin.1)
+    }
+}
+
+/// Ring around a point; `outlined` adds a dark edge on both sides
+fn draw_ring(canvas: &mut Canvas, x: i32, y: i32, radius: i32, color: u32, outlined: bool) {
+    let width = click_highlight::LINE_WIDTH;
+    if outlined {
+        let outline = width + 2 * click_highlight::OUTLINE_WIDTH;
+        drawing::draw_ellipse(
+            canvas,
+            x - radius,
+            y - radius,
+            x + radius,
+            y + radius,
+            outline,
+            click_highlight::OUTLINE_COLOR,
+        );
+    }
+    drawing::draw_ellipse(
+        canvas,
+        x - radius,
+        y - radius,
+        x + radius,
+        y + radius,
+        width,
+        color,
+    );
+}
+
+/// Label for gestures a viewer could not tell from a plain click / drag
+/// ("Ctrl+click", "double-click", "Shift+drag") - None for plain ones
+fn describe(gesture: &MouseGesture) -> Option<String> {
+    let multi_click = gesture.clicks >= 2 && !gesture.is_drag();
+    if !gesture.modifiers.any() && !multi_click {
+        return None;
+    }
+    let action = match (gesture.is_drag(), gesture.clicks) {
+        (true, _) => "drag",
+        (false, 1) => "click",
+        (false, 2) => "double-click",
+        (false, _) => "triple-click",
+    };
+    let button = match gesture.button {
+        MouseButton::Left => "",
+        MouseButton::Right => "right-",
+        MouseButton::Middle => "middle-",
+    };
+    let action = format!("{}{}", button, action);
+    if gesture.modifiers.any() {
+        Some(format!("{}+{}", gesture.modifiers.label(), action))
+    } else {
+        Some(action)
+    }
+}
+
+/// Text on a box in the theme colors, vertically centered on `y`
+fn draw_label(canvas: &mut Canvas, x: i32, y: i32, text: &str) {
+    let colors = crate::theme::palette();
+    let scale = click_highlight::LABEL_SCALE;
+    let padding = 4;
+    let width = bitmap_font::text_width(text, scale);
+    let height = bitmap_font::text_height(scale);
+    let top = y - height / 2;
+    drawing::fill_rect(
+        canvas,
+        x - padding,
+        top - padding,
+        width + 2 * padding,
+        height + 2 * padding,
+        colors.text_bg,
+    );
+    let style = TextStyle {
+        color: colors.text,
+        scale,
+    };
+    bitmap_font::draw_text(canvas, x, top, text, &style);
+}
+
+fn button_color(button: MouseButton) -> u32 {
+    match button {
+        MouseButton::Left => click_highlight::LEFT_COLOR,
+        MouseButton::Right => click_highlight::RIGHT_COLOR,
+        MouseButton::Middle => click_highlight::MIDDLE_COLOR,
+    }
+}
@@ -0,0 +1,166 @@
+// color_picker.rs - Eyedropper (Pixel Color Picker)
+//
+// In color picker mode the overlay shows the color of the captured pixel
+// under the cursor: a zoomed view of the pixels around it with the picked
+// pixel outlined, a swatch and the value as hex and RGB. A click copies the
+// hex value ("#1E90FF") to the clipboard.
+//
+// The color comes from the captured frame (the same pixels the output
+// shows), not from reading the screen - so it works over applications that
+// block ordinary color pickers, and is exactly the color viewers see.
+//
+// Like measurements, the readout is local feedback only: it is drawn on the
+// overlay window (excluded from capture) and never composited into the output.
+
+use crate::bitmap_font::Canvas;
+use crate::constants::color_picker;
+use crate::drawing;
+use crate::text::{self, TextStyle};
+
+/// Pixel under the cursor and its neighborhood (coordinates relative to the capture region)
+pub struct ColorPicker {
+    cursor: Option<(i32, i32)>,
+    /// ZOOM_PIXELS x ZOOM_PIXELS colors around the cursor (0xRRGGBB, None outside the frame)
+    pixels: Vec<Option<u32>>,
+    /// The shown color was just copied
+    copied: bool,
+}
+
+impl ColorPicker {
+    pub fn new() -> Self {
+        Self {
+            cursor: None,
+            pixels: Vec::new(),
+            copied: false,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.cursor = None;
+        self.pixels.clear();
+        self.copied = false;
+    }
+
+    /// Read the pixels around (x, y) from a captured frame of `width` x `height`
+    pub fn sample(&mut self, frame: &[u32], width: i32, height: i32, x: i32, y: i32) {
+        let half = color_picker::ZOOM_PIXELS / 2;
+        self.pixels.clear();
+        for py in y - half..=y + half {
+            for px in x - half..=x + half {
+                let inside = px >= 0 && py >= 0 && px < width && py < height;
+                let color = inside
+                    .then(|| frame.get((py * width + px) as usize))
+                    .flatten()
+                    .map(|c| c & 0x00FF_FFFF);
+                self.pixels.push(color);
+            }
+        }
+        self.cursor = Some((x, y));
+        self.copied = false;
+    }
+
+    /// Color of the pixel under the cursor (0xRRGGBB)
+    pub fn color(&self) -> Option<u32> {
+        let center = self.pixels.len() / 2;
+        self.pixels.get(center).copied().flatten()
+    }
+
+    /// Note that the color was copied (shown in the readout until the cursor moves)
+    pub fn set_copied(&mut self) {
+        self.copied = true;
+    }
+
+    /// Readout text, e.g. "#1E90FF  |  30, 144, 255"
+    pub fn readout(&self) -> Option<String> {
+        let color = self.color()?;
+        let (r, g, b) = ((color >> 16) & 0xFF, (color >> 8) & 0xFF, color & 0xFF);
+        let prefix = if self.copied { "Copied " } else { "" };
+        Some(format!("{}{}  |  {}, {}, {}", prefix, hex(color), r, g, b))
+    }
+
+    /// Draw the zoomed pixels, swatch and readout next to the cursor at the
+    /// given offset (the overlay border width)
+    /// `scale`: scale factor of the overlay window, for the sizes and the text
+    pub fn draw(&self, canvas: &mut Canvas, offset_x: i32, offset_y: i32, scale: f32) {
+        let (Some((x, y)), Some(color), Some(readout)) =
+            (self.cursor, self.color(), self.readout())
+        else {
+            return;
+        };
+        let (x, y) = (x + offset_x, y + offset_y);
+
+        // STEP 1: Size of the panel - zoomed pixels on the left, swatch and
+        // readout on the right
+        let cell = text::scaled(color_picker::ZOOM_CELL, scale);
+        let zoom = cell * color_picker::ZOOM_PIXELS;
+        let padding = text::scaled(color_picker::PADDING, scale);
+        let swatch = text::scaled(color_picker::SWATCH_SIZE, scale);
+        let style = TextStyle {
+            color: color_picker::LABEL_TEXT,
+            size: text::scaled(color_picker::TEXT_SIZE, scale),
+            bold: false,
+        };
+        let (text_w, text_h) = text::text_size(&readout, &style);
+        let panel_w = padding * 3 + zoom + text_w.max(swatch);
+        let panel_h = padding * 2 + zoom.max(swatch + padding + text_h);
+
+        // STEP 2: Place it next to the cursor, kept inside the canvas
+        let offset = text::scaled(color_picker::OFFSET, scale);
+        let mut px = x + offset;
+        let mut py = y + offset;
+        if px + panel_w > canvas.width {
+            px = x - offset - panel_w;
+        }
+        if py + panel_h > canvas.height {
+            py = y - offset - panel_h;
+        }
+        let px = px.max(0);
+        let py = py.max(0);
+        drawing::fill_rect(
+            canvas,
+            px,
+            py,
+            panel_w,
+            panel_h,
+            color_picker::LABEL_BACKGROUND,
+        );
+
+        // STEP 3: Zoomed pixels with the picked one outlined
+        let (zx, zy) = (px + padding, py + padding);
+        for (i, pixel) in self.pixels.iter().enumerate() {
+            let col = i as i32 % color_picker::ZOOM_PIXELS;
+            let row = i as i32 / color_picker::ZOOM_PIXELS;
+            let fill = pixel.map_or(color_picker::OUTSIDE, |c| 0xFF00_0000 | c);
+            drawing::fill_rect(canvas, zx + col * cell, zy + row * cell, cell, cell, fill);
+        }
+        let center = color_picker::ZOOM_PIXELS / 2 * cell;
+        drawing::draw_rect(
+            canvas,
+            zx + center - 1,
+            zy + center - 1,
+            zx + center + cell,
+            zy + center + cell,
+            1,
+            color_picker::CENTER,
+        );
+
+        // STEP 4: Swatch and readout
+        let (sx, sy) = (zx + zoom + padding, zy);
+        drawing::fill_rect(canvas, sx, sy, swatch, swatch, 0xFF00_0000 | color);
+        drawing::draw_rect(
+            canvas,
+            sx,
+            sy,
+            sx + swatch - 1,
+            sy + swatch - 1,
+            1,
+            color_picker::CENTER,
+        );
+        text::draw_text(canvas, sx, sy + swatch + padding, &readout, &style);
+    }
+}
+
+/// "#RRGGBB" for a 0xRRGGBB color
+pub fn hex(color: u32) -> String {
+    format!("#{:06X}", color & 0x00FF_FFFF)
+}
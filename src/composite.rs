@@ -0,0 +1,552 @@
+// composite.rs - Multi-Region Composite Layouts
+//
+// Besides the main capture region, additional regions can be added during
+// capture (tray → Capture Regions → Add Region). Each one gets its own hollow
+// border and its own capture session; the renderer places all region frames on
+// one output canvas (e.g. terminal on the left, browser on the right).
+//
+// Frames are placed top-left aligned in their cells, so the main region always
+// starts at (0, 0) and overlays positioned in its coordinates (annotations,
+// privacy regions, spotlight...) stay where they were drawn.
+//
+// Layout templates (tray → Layout Templates) are ready-made layouts that scale
+// the other sources (regions, webcam, image) relative to the main region:
+// - Split 50/50:        every source fitted into an equal cell, left to right
+// - Picture in Picture: the other sources small in the bottom-right corner
+// - Vertical (9:16):    a phone-shaped canvas, the other sources below the
+//                       main region, the rest filled with a blurred copy of it
+//
+// The custom layout is arranged by hand in the layout editor (see
+// layout_editor.rs): every source has its own rectangle on the canvas, a crop
+// and a z-order. The rectangles are stored per source (SourceId), so a source
+// that is switched off and on again comes back to its place.
+
+use serde::{Deserialize, Serialize};
+
+use crate::constants::composite;
+
+/// Where a frame goes on the canvas: x, y, width, height (scaled if the size
+/// differs from the frame's)
+pub type Cell = (u32, u32, u32, u32);
+
+/// How the region frames are arranged on the output canvas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompositeLayout {
+    /// Left to right
+    SideBySide,
+    /// Top to bottom
+    Stacked,
+    /// Rows of equally sized cells
+    Grid,
+    /// Equal cells of the main region's size, left to right
+    Split,
+    /// Other sources scaled down into the bottom-right corner of the main region
+    PictureInPicture,
+    /// 9:16 canvas with the other sources below the main region on a blurred fill
+    Vertical,
+    /// Placed by hand in the layout editor
+    Custom,
+}
+
+impl CompositeLayout {
+    pub fn label(self) -> &'static str {
+        match self {
+            CompositeLayout::SideBySide => "Side by Side",
+            CompositeLayout::Stacked => "Stacked",
+            CompositeLayout::Grid => "Grid",
+            CompositeLayout::Split => "Split 50/50",
+            CompositeLayout::PictureInPicture => "Picture in Picture",
+            CompositeLayout::Vertical => "Vertical (9:16)",
+            CompositeLayout::Custom => "Custom",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            CompositeLayout::SideBySide => CompositeLayout::Stacked,
+            CompositeLayout::Stacked => CompositeLayout::Grid,
+            CompositeLayout::Grid => CompositeLayout::Split,
+            CompositeLayout::Split => CompositeLayout::PictureInPicture,
+            CompositeLayout::PictureInPicture => CompositeLayout::Vertical,
+            CompositeLayout::Vertical => CompositeLayout::Custom,
+            CompositeLayout::Custom => CompositeLayout::SideBySide,
+        }
+    }
+
+    /// Layouts offered as one-click templates (tray → Layout Templates)
+    pub const TEMPLATES: [CompositeLayout; 3] = [
+        CompositeLayout::Split,
+        CompositeLayout::PictureInPicture,
+        CompositeLayout::Vertical,
+    ];
+
+    /// Whether a lone main frame is shown as it is (the vertical layout
+    /// always puts it on a 9:16 canvas)
+    pub fn passes_single_frame(self) -> bool {
+        self != CompositeLayout::Vertical
+    }
+
+    /// Canvas size and the rectangle (x, y, width, height) of each frame
+    /// `custom`: the custom layout's rectangles in the same order as `sizes`
+    /// (see custom_rects), used by CompositeLayout::Custom only
+    pub fn arrange(self, sizes: &[(u32, u32)], custom: &[SourceRect]) -> ((u32, u32), Vec<Cell>) {
+        let gap = composite::GAP;
+        let mut positions = Vec::with_capacity(sizes.len());
+        let (mut width, mut height) = (0u32, 0u32);
+
+        match self {
+            CompositeLayout::SideBySide => {
+                let mut x = 0;
+                for &(w, h) in sizes {
+                    positions.push((x, 0, w, h));
+                    width = x + w;
+                    height = height.max(h);
+                    x += w + gap;
+                }
+            }
+            CompositeLayout::Stacked => {
+                let mut y = 0;
+                for &(w, h) in sizes {
+                    positions.push((0, y, w, h));
+                    width = width.max(w);
+                    height = y + h;
+                    y += h + gap;
+                }
+            }
+            CompositeLayout::Grid => {
+                // As many columns as rows (2 regions = 2x1, 4 = 2x2, 5 = 3x2)
+                let columns = (1..).find(|c| c * c >= sizes.len()).unwrap_or(1) as u32;
+                let cell_w = sizes.iter().map(|s| s.0).max().unwrap_or(0);
+                let cell_h = sizes.iter().map(|s| s.1).max().unwrap_or(0);
+                for (i, &(w, h)) in sizes.iter().enumerate() {
+                    let (column, row) = (i as u32 % columns, i as u32 / columns);
+                    let (x, y) = (column * (cell_w + gap), row * (cell_h + gap));
+                    positions.push((x, y, w, h));
+                    width = width.max(x + w);
+                    height = height.max(y + h);
+                }
+            }
+            CompositeLayout::Split => {
+                // Cells of the main region's size (the main frame fills its cell)
+                let (cell_w, cell_h) = sizes.first().copied().unwrap_or((0, 0));
+                for (i, &size) in sizes.iter().enumerate() {
+                    let (w, h) = fit(size, (cell_w, cell_h));
+                    let x = i as u32 * (cell_w + gap) + (cell_w - w) / 2;
+                    positions.push((x, (cell_h - h) / 2, w, h));
+                    width = i as u32 * (cell_w + gap) + cell_w;
+                }
+                height = cell_h;
+            }
+            CompositeLayout::PictureInPicture => {
+                let (main_w, main_h) = sizes.first().copied().unwrap_or((0, 0));
+                (width, height) = (main_w, main_h);
+                positions.extend(sizes.first().map(|_| (0, 0, main_w, main_h)));
+
+                // Right to left along the bottom edge
+                let bounds = (
+                    main_w * composite::PIP_SCALE_PERCENT / 100,
+                    main_h * composite::PIP_SCALE_PERCENT / 100,
+                );
+                let mut right = main_w;
+                for &size in sizes.iter().skip(1) {
+                    let (w, h) = fit(size, bounds);
+                    let x = right.saturating_sub(composite::PIP_MARGIN + w);
+                    let y = main_h.saturating_sub(composite::PIP_MARGIN + h);
+                    positions.push((x, y, w, h));
+                    right = x;
+                }
+            }
+            CompositeLayout::Vertical => {
+                let (main_w, main_h) = sizes.first().copied().unwrap_or((0, 0));
+                positions.extend(sizes.first().map(|_| (0, 0, main_w, main_h)));
+
+                // The other sources at the full canvas width, top to bottom
+                let mut y = main_h;
+                for &(w, h) in sizes.iter().skip(1) {
+                    let scaled_h = (h * main_w).checked_div(w).unwrap_or(0);
+                    positions.push((0, y + gap, main_w, scaled_h));
+                    y += gap + scaled_h;
+                }
+                let (aspect_w, aspect_h) = composite::VERTICAL_ASPECT;
+                width = main_w;
+                height = y.max(main_w * aspect_h / aspect_w);
+            }
+            CompositeLayout::Custom => {
+                // Sources without a rectangle go to the right of the others
+                for (i, &(w, h)) in sizes.iter().enumerate() {
+                    let cell = match custom.get(i) {
+                        Some(rect) => rect.cell(),
+                        None if i == 0 => (0, 0, w, h),
+                        None => (width + gap, 0, w, h),
+                    };
+                    positions.push(cell);
+                    width = width.max(cell.0 + cell.2);
+                    height = height.max(cell.1 + cell.3);
+                }
+            }
+        }
+
+        ((width, height), positions)
+    }
+
+    /// Place the frames (ARGB pixels, width, height) on one canvas
+    pub fn compose(
+        self,
+        frames: &[(&[u32], u32, u32)],
+        custom: &[SourceRect],
+    ) -> (Vec<u32>, u32, u32) {
+        let sizes: Vec<(u32, u32)> = frames.iter().map(|&(_, w, h)| (w, h)).collect();
+        let ((width, height), positions) = self.arrange(&sizes, custom);
+        let custom = if self == CompositeLayout::Custom {
+            custom
+        } else {
+            &[]
+        };
+        let mut canvas = match frames.first() {
+            Some(&(pixels, w, h))
+                if self == CompositeLayout::Vertical && pixels.len() >= (w * h) as usize =>
+            {
+                blurred_fill(pixels, (w, h), width, height)
+            }
+            _ => vec![composite::BACKGROUND; (width * height) as usize],
+        };
+
+        // Lowest z first, so higher ones end up on top (equal z: in order)
+        let mut order: Vec<usize> = (0..frames.len().min(positions.len())).collect();
+        order.sort_by_key(|&i| custom.get(i).map_or(0, |rect| rect.z));
+
+        for i in order {
+            let (pixels, w, h) = frames[i];
+            let (x, y, cell_w, cell_h) = positions[i];
+            if w == 0 || h == 0 || pixels.len() < (w * h) as usize {
+                continue;
+            }
+            let (crop_x, crop_y, crop_w, crop_h) = match custom.get(i) {
+                Some(rect) => rect.crop_window((w, h)),
+                None => (0, 0, w, h),
+            };
+            if (crop_x, crop_y, crop_w, crop_h) == (0, 0, w, h) && (cell_w, cell_h) == (w, h) {
+                for row in 0..h {
+                    let src = (row * w) as usize;
+                    let dst = ((y + row) * width + x) as usize;
+                    canvas[dst..dst + w as usize].copy_from_slice(&pixels[src..src + w as usize]);
+                }
+                continue;
+            }
+
+            // Scaled by a template or the custom layout, cropped by the
+            // custom layout (nearest neighbor, like the output sampler for
+            // pixel-exact captures)
+            for row in 0..cell_h {
+                let src_row = (crop_y + row * crop_h / cell_h) * w + crop_x;
+                let dst = ((y + row) * width + x) as usize;
+                for column in 0..cell_w {
+                    canvas[dst + column as usize] =
+                        pixels[(src_row + column * crop_w / cell_w) as usize];
+                }
+            }
+        }
+
+        (canvas, width, height)
+    }
+}
+
+/// A source of the output, to find its place in the custom layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceId {
+    /// The main capture region
+    Screen,
+    /// An additional capture region (0 = the first one added)
+    Region(usize),
+    Webcam,
+    Image,
+}
+
+impl SourceId {
+    pub fn label(self) -> String {
+        match self {
+            SourceId::Screen => "Screen".to_string(),
+            SourceId::Region(i) => format!("Region {}", i + 2),
+            SourceId::Webcam => "Webcam".to_string(),
+            SourceId::Image => "Image".to_string(),
+        }
+    }
+}
+
+/// Where a source goes in the custom layout
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SourceRect {
+    pub source: SourceId,
+    /// Rectangle on the canvas - the shown part of the frame is scaled to it
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    /// Share of the frame cut off at the left, top, right and bottom (0-1)
+    pub crop: [f32; 4],
+    /// Sources with a higher z are drawn on top of the others
+    pub z: i32,
+}
+
+impl SourceRect {
+    /// The whole frame in a cell
+    pub fn new(source: SourceId, (x, y, width, height): Cell) -> Self {
+        Self {
+            source,
+            x,
+            y,
+            width,
+            height,
+            crop: [0.0; 4],
+            z: 0,
+        }
+    }
+
+    pub fn cell(&self) -> Cell {
+        (self.x, self.y, self.width.max(1), self.height.max(1))
+    }
+
+    /// The shown part (x, y, width, height) of a frame of `size`
+    pub fn crop_window(&self, (w, h): (u32, u32)) -> Cell {
+        let [left, top, right, bottom] = self.crop.map(|c| c.clamp(0.0, 1.0));
+        let span = |size: u32, start: f32, end: f32| {
+            let first = ((start * size as f32) as u32).min(size.saturating_sub(1));
+            let last = (((1.0 - end) * size as f32) as u32).clamp(first + 1, size.max(1));
+            (first, last - first)
+        };
+        let (x, width) = span(w, left, right);
+        let (y, height) = span(h, top, bottom);
+        (x, y, width, height)
+    }
+}
+
+/// The custom layout's rectangles for the sources in output order: the saved
+/// place of each source, the others at their own size to the right
+pub fn custom_rects(layout: &[SourceRect], sources: &[(SourceId, (u32, u32))]) -> Vec<SourceRect> {
+    // The screen stays at the top-left corner at its own size, where the
+    // overlays are drawn (see layout_editor.rs) - only its z-order is kept
+    let saved: Vec<Option<SourceRect>> = sources
+        .iter()
+        .map(|&(id, (w, h))| {
+            let saved = layout.iter().find(|r| r.source == id).copied();
+            if id == SourceId::Screen {
+                Some(SourceRect {
+                    z: saved.map_or(0, |r| r.z),
+                    ..SourceRect::new(id, (0, 0, w, h))
+                })
+            } else {
+                saved
+            }
+        })
+        .collect();
+    let mut right = saved
+        .iter()
+        .flatten()
+        .map(|r| r.x + r.width + composite::GAP)
+        .max()
+        .unwrap_or(0);
+    sources
+        .iter()
+        .zip(saved)
+        .map(|(&(id, (w, h)), saved)| {
+            saved.unwrap_or_else(|| {
+                let rect = SourceRect::new(id, (right, 0, w, h));
+                right += w + composite::GAP;
+                rect
+            })
+        })
+        .collect()
+}
+
+/// Largest size with the aspect ratio of `size` that fits into `bounds`
+fn fit((w, h): (u32, u32), (max_w, max_h): (u32, u32)) -> (u32, u32) {
+    if w == 0 || h == 0 {
+        return (0, 0);
+    }
+    let scale = (max_w as f32 / w as f32).min(max_h as f32 / h as f32);
+    (
+        ((w as f32 * scale) as u32).clamp(1, max_w.max(1)),
+        ((h as f32 * scale) as u32).clamp(1, max_h.max(1)),
+    )
+}
+
+/// Canvas filled with a blurred, darkened copy of the frame scaled to cover it
+///
+/// The frame is averaged down to a coarse grid (BLUR_COLUMNS wide), which is
+/// then stretched over the canvas with bilinear filtering.
+fn blurred_fill(pixels: &[u32], (w, h): (u32, u32), width: u32, height: u32) -> Vec<u32> {
+    let mut canvas = vec![composite::BACKGROUND; (width * height) as usize];
+    if w == 0 || h == 0 || width == 0 || height == 0 {
+        return canvas;
+    }
+
+    // STEP 1: Average the frame into the grid (cover scaling, centered)
+    let columns = composite::BLUR_COLUMNS;
+    let rows = ((columns * height + width - 1) / width).max(1);
+    let scale = (width as f32 / w as f32).max(height as f32 / h as f32);
+    let offset_x = (w as f32 * scale - width as f32) / 2.0;
+    let offset_y = (h as f32 * scale - height as f32) / 2.0;
+    let samples = composite::BLUR_SAMPLES;
+    let mut grid = vec![[0f32; 3]; (columns * rows) as usize];
+    for gy in 0..rows {
+        for gx in 0..columns {
+            let mut sum = [0f32; 3];
+            for sy in 0..samples {
+                for sx in 0..samples {
+                    let cx = (gx as f32 + (sx as f32 + 0.5) / samples as f32) * width as f32
+                        / columns as f32;
+                    let cy = (gy as f32 + (sy as f32 + 0.5) / samples as f32) * height as f32
+                        / rows as f32;
+                    let px = (((cx + offset_x) / scale) as u32).min(w - 1);
+                    let py = (((cy + offset_y) / scale) as u32).min(h - 1);
+                    let p = pixels[(py * w + px) as usize];
+                    sum[0] += ((p >> 16) & 0xFF) as f32;
+                    sum[1] += ((p >> 8) & 0xFF) as f32;
+                    sum[2] += (p & 0xFF) as f32;
+                }
+            }
+            let dim = composite::BLUR_BRIGHTNESS_PERCENT as f32 / 100.0;
+            let count = (samples * samples) as f32;
+            grid[(gy * columns + gx) as usize] = sum.map(|c| c / count * dim);
+        }
+    }
+
+    // STEP 2: Stretch the grid over the canvas (bilinear)
+    let cell_w = width as f32 / columns as f32;
+    let cell_h = height as f32 / rows as f32;
+    for y in 0..height {
+        let fy = ((y as f32 + 0.5) / cell_h - 0.5).clamp(0.0, (rows - 1) as f32);
+        let (y0, ty) = (fy as u32, fy.fract());
+        let y1 = (y0 + 1).min(rows - 1);
+        for x in 0..width {
+            let fx = ((x as f32 + 0.5) / cell_w - 0.5).clamp(0.0, (columns - 1) as f32);
+            let (x0, tx) = (fx as u32, fx.fract());
+            let x1 = (x0 + 1).min(columns - 1);
+            let at = |gx: u32, gy: u32| grid[(gy * columns + gx) as usize];
+            let (a, b, c, d) = (at(x0, y0), at(x1, y0), at(x0, y1), at(x1, y1));
+            let mut argb = 0xFF00_0000;
+            for i in 0..3 {
+                let top = a[i] + (b[i] - a[i]) * tx;
+                let bottom = c[i] + (d[i] - c[i]) * tx;
+                let value = (top + (bottom - top) * ty) as u32;
+                argb |= value.min(255) << (16 - 8 * i);
+            }
+            canvas[(y * width + x) as usize] = argb;
+        }
+    }
+    canvas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAP: u32 = composite::GAP;
+
+    #[test]
+    fn side_by_side_places_frames_left_to_right() {
+        let (canvas, cells) = CompositeLayout::SideBySide.arrange(&[(100, 50), (40, 80)], &[]);
+        assert_eq!(canvas, (100 + GAP + 40, 80));
+        assert_eq!(cells, vec![(0, 0, 100, 50), (100 + GAP, 0, 40, 80)]);
+    }
+
+    #[test]
+    fn stacked_places_frames_top_to_bottom() {
+        let (canvas, cells) = CompositeLayout::Stacked.arrange(&[(100, 50), (40, 80)], &[]);
+        assert_eq!(canvas, (100, 50 + GAP + 80));
+        assert_eq!(cells, vec![(0, 0, 100, 50), (0, 50 + GAP, 40, 80)]);
+    }
+
+    #[test]
+    fn grid_uses_as_many_columns_as_rows() {
+        let sizes = [(100, 50), (40, 80), (30, 30)];
+        let (canvas, cells) = CompositeLayout::Grid.arrange(&sizes, &[]);
+        assert_eq!(cells[1], (100 + GAP, 0, 40, 80));
+        assert_eq!(cells[2], (0, 80 + GAP, 30, 30));
+        assert_eq!(canvas, (100 + GAP + 40, 80 + GAP + 30));
+    }
+
+    #[test]
+    fn picture_in_picture_fits_sources_into_the_corner() {
+        let (canvas, cells) =
+            CompositeLayout::PictureInPicture.arrange(&[(400, 200), (100, 100)], &[]);
+        assert_eq!(canvas, (400, 200));
+        assert_eq!(cells[0], (0, 0, 400, 200));
+        // 25 % of the main region is 100x50, the square source becomes 50x50
+        let margin = composite::PIP_MARGIN;
+        assert_eq!(cells[1], (400 - margin - 50, 200 - margin - 50, 50, 50));
+    }
+
+    #[test]
+    fn vertical_canvas_is_at_least_nine_by_sixteen() {
+        let (canvas, cells) = CompositeLayout::Vertical.arrange(&[(90, 40), (30, 10)], &[]);
+        assert_eq!(cells[1], (0, 40 + GAP, 90, 30));
+        assert_eq!(canvas, (90, 160));
+    }
+
+    #[test]
+    fn custom_places_sources_without_a_rectangle_to_the_right() {
+        let custom = [SourceRect::new(SourceId::Screen, (0, 0, 4, 4))];
+        let (canvas, cells) = CompositeLayout::Custom.arrange(&[(4, 4), (2, 2)], &custom);
+        assert_eq!(cells, vec![(0, 0, 4, 4), (4 + GAP, 0, 2, 2)]);
+        assert_eq!(canvas, (4 + GAP + 2, 4));
+    }
+
+    #[test]
+    fn arrange_without_frames_is_empty() {
+        for layout in [CompositeLayout::SideBySide, CompositeLayout::Custom] {
+            let (canvas, cells) = layout.arrange(&[], &[]);
+            assert_eq!(canvas, (0, 0));
+            assert!(cells.is_empty());
+        }
+    }
+
+    #[test]
+    fn compose_copies_frames_onto_the_background() {
+        let main = [1, 2, 3, 4];
+        let other = [9];
+        let (canvas, width, height) =
+            CompositeLayout::SideBySide.compose(&[(&main, 2, 2), (&other, 1, 1)], &[]);
+        assert_eq!((width, height), (2 + GAP + 1, 2));
+        assert_eq!(&canvas[..2], &[1, 2]);
+        assert_eq!(&canvas[width as usize..width as usize + 2], &[3, 4]);
+        assert_eq!(canvas[(2 + GAP) as usize], 9);
+        assert_eq!(canvas[2], composite::BACKGROUND);
+        assert_eq!(canvas[(width * 2 - 1) as usize], composite::BACKGROUND);
+    }
+
+    #[test]
+    fn compose_scales_and_crops_custom_cells() {
+        let frame = [1, 2, 3, 4];
+        let scaled = [SourceRect::new(SourceId::Screen, (0, 0, 4, 4))];
+        let (canvas, width, _) = CompositeLayout::Custom.compose(&[(&frame, 2, 2)], &scaled);
+        assert_eq!(width, 4);
+        assert_eq!(&canvas[..4], &[1, 1, 2, 2]);
+        assert_eq!(&canvas[12..], &[3, 3, 4, 4]);
+
+        // The left half cut off: only the right column is shown
+        let cropped = [SourceRect {
+            crop: [0.5, 0.0, 0.0, 0.0],
+            ..SourceRect::new(SourceId::Screen, (0, 0, 1, 2))
+        }];
+        let (canvas, _, _) = CompositeLayout::Custom.compose(&[(&frame, 2, 2)], &cropped);
+        assert_eq!(canvas, vec![2, 4]);
+    }
+
+    #[test]
+    fn compose_draws_higher_z_on_top() {
+        let (bottom, top) = ([1], [2]);
+        let custom = [
+            SourceRect {
+                z: 1,
+                ..SourceRect::new(SourceId::Screen, (0, 0, 1, 1))
+            },
+            SourceRect::new(SourceId::Webcam, (0, 0, 1, 1)),
+        ];
+        let (canvas, _, _) =
+            CompositeLayout::Custom.compose(&[(&bottom, 1, 1), (&top, 1, 1)], &custom);
+        assert_eq!(canvas, vec![1]);
+    }
+}
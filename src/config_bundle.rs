@@ -0,0 +1,660 @@
+// config_bundle.rs - Configuration Bundles
+//
+// Tray → Export Configuration writes the whole setup into one .zip file that
+// can be handed to a colleague; tray → Import Configuration reads it back:
+//
+//   rustframe-bundle.json   format version and the exporting RustFrame version
+//   settings.json           all settings (shortcuts, MIDI mappings, profiles,
+//                           filters, webhooks, uploads, ...)
+//   scripts/*.rhai          the scripts folder (see scripting.rs)
+//   images/*                the image / slideshow of the image source
+//
+// Machine-specific values are not shared: the remote control token is left
+// out of the export, and Start with Windows / the desktop menu keep their
+// local values on import. Plugins are DLLs and are not bundled.
+//
+// Import modes:
+// - Merge: values from the bundle win, everything else stays; lists (profiles,
+//   webhooks, redacted apps, ...) get the bundle's entries appended, scripts
+//   with the same name are overwritten
+// - Replace: settings.json is replaced and the scripts folder emptied first
+//
+// Parts of a bundle run code or send data somewhere: scripts, the
+// post-capture command, webhooks and upload targets. inspect() lists them and
+// they are only imported when the user agrees; otherwise they are stripped
+// and the local ones stay. Entries larger than MAX_ENTRY_SIZE (unpacked) are
+// rejected.
+
+use anyhow::{anyhow, Context, Result};
+use log::info;
+use serde_json::Value;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::app_settings::AppSettings;
+use crate::constants::config_bundle;
+use crate::{image_source, scripting, settings_file};
+
+/// How an imported bundle is combined with the current setup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    Merge,
+    Replace,
+}
+
+/// What a bundle contained
+#[derive(Debug, Default)]
+pub struct BundleSummary {
+    pub scripts: usize,
+    pub images: usize,
+}
+
+/// The parts of a bundle that run code or send data (see inspect)
+#[derive(Debug, Default)]
+pub struct ActiveContent {
+    /// post_actions.command
+    pub command: Option<String>,
+    /// File names of scripts/*.rhai
+    pub scripts: Vec<String>,
+    /// Webhook URLs
+    pub webhooks: Vec<String>,
+    /// upload.target
+    pub upload_target: Option<String>,
+}
+
+impl ActiveContent {
+    pub fn is_empty(&self) -> bool {
+        self.command.is_none()
+            && self.scripts.is_empty()
+            && self.webhooks.is_empty()
+            && self.upload_target.is_none()
+    }
+
+    /// One line per item, for the question before importing
+    pub fn describe(&self) -> String {
+        let mut lines = Vec::new();
+        if let Some(command) = &self.command {
+            lines.push(format!("Command after each capture: {}", command));
+        }
+        for script in &self.scripts {
+            lines.push(format!("Script: {}", script));
+        }
+        for url in &self.webhooks {
+            lines.push(format!("Webhook: {}", url));
+        }
+        if let Some(target) = &self.upload_target {
+            lines.push(format!("Screenshot uploads to: {}", target));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Write the current setup into a bundle
+pub fn export(settings: &AppSettings, path: &Path) -> Result<BundleSummary> {
+    let file =
+        std::fs::File::create(path).with_context(|| format!("Failed to create {:?}", path))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    let mut summary = BundleSummary::default();
+
+    let manifest = serde_json::json!({
+        "format": config_bundle::FORMAT_VERSION,
+        "rustframe": env!("CARGO_PKG_VERSION"),
+    });
+    zip.start_file(config_bundle::MANIFEST, options)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+
+    // The image source travels as images/... and is found there again on import
+    let mut images = Vec::new();
+    let image_path = PathBuf::from(settings.image_path.trim());
+    let mut bundled_image_path = String::new();
+    if image_path.is_dir() {
+        for entry in std::fs::read_dir(&image_path)? {
+            let path = entry?.path();
+            if image_source::is_image(&path) {
+                images.push(path);
+            }
+        }
+        bundled_image_path = config_bundle::IMAGES_DIR.to_string();
+    } else if image_path.is_file() {
+        if let Some(name) = image_path.file_name() {
+            bundled_image_path =
+                format!("{}/{}", config_bundle::IMAGES_DIR, name.to_string_lossy());
+        }
+        images.push(image_path);
+    }
+    for image in &images {
+        let Some(name) = image.file_name() else {
+            continue;
+        };
+        let data = std::fs::read(image).with_context(|| format!("Failed to read {:?}", image))?;
+        zip.start_file(
+            format!("{}/{}", config_bundle::IMAGES_DIR, name.to_string_lossy()),
+            options,
+        )?;
+        zip.write_all(&data)?;
+        summary.images += 1;
+    }
+
+    let mut value = serde_json::to_value(settings)?;
+    if let Value::Object(map) = &mut value {
+        map.remove("stream_deck_token");
+        map.insert("image_path".into(), Value::String(bundled_image_path));
+    }
+    zip.start_file(config_bundle::SETTINGS, options)?;
+    zip.write_all(serde_json::to_string_pretty(&value)?.as_bytes())?;
+
+    if let Some(dir) = scripting::dir() {
+        for path in script_files(&dir) {
+            let Some(name) = path.file_name() else {
+                continue;
+            };
+            let text =
+                std::fs::read(&path).with_context(|| format!("Failed to read {:?}", path))?;
+            zip.start_file(
+                format!("{}/{}", config_bundle::SCRIPTS_DIR, name.to_string_lossy()),
+                options,
+            )?;
+            zip.write_all(&text)?;
+            summary.scripts += 1;
+        }
+    }
+
+    zip.finish()?;
+    info!(
+        "Exported configuration to {:?} ({} script(s), {} image(s))",
+        path, summary.scripts, summary.images
+    );
+    Ok(summary)
+}
+
+/// List the scripts, post-capture command, webhooks and upload target of a
+/// bundle, to ask before they are imported
+pub fn inspect(path: &Path) -> Result<ActiveContent> {
+    let mut zip = open(path)?;
+    let incoming: Value = serde_json::from_str(&read_text(&mut zip, config_bundle::SETTINGS)?)
+        .context("Invalid settings.json in the bundle")?;
+
+    let text = |value: &Value| {
+        value
+            .as_str()
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .map(str::to_string)
+    };
+    let mut content = ActiveContent {
+        command: text(&incoming["post_actions"]["command"]),
+        upload_target: text(&incoming["upload"]["target"]),
+        ..Default::default()
+    };
+    if let Some(webhooks) = incoming["webhooks"].as_array() {
+        content.webhooks = webhooks.iter().filter_map(|w| text(&w["url"])).collect();
+    }
+    for index in 0..zip.len() {
+        let entry = zip.by_index(index)?;
+        let Some(name) = entry.enclosed_name() else {
+            continue;
+        };
+        if let Ok(file) = name.strip_prefix(config_bundle::SCRIPTS_DIR) {
+            if !entry.is_dir() {
+                content.scripts.push(file.to_string_lossy().into_owned());
+            }
+        }
+    }
+    Ok(content)
+}
+
+/// Read a bundle into settings.json, the scripts folder and the images folder
+/// The new settings.json is checked before anything is written. Without
+/// `include_active` the bundle's scripts, post-capture command, webhooks and
+/// upload target are left out (see inspect).
+pub fn import(
+    current: &AppSettings,
+    path: &Path,
+    mode: ImportMode,
+    include_active: bool,
+) -> Result<BundleSummary> {
+    let mut zip = open(path)?;
+
+    let manifest: Value = serde_json::from_str(&read_text(&mut zip, config_bundle::MANIFEST)?)
+        .context("Invalid bundle manifest")?;
+    let format = manifest["format"].as_u64().unwrap_or(0);
+    if format > config_bundle::FORMAT_VERSION {
+        return Err(anyhow!(
+            "The bundle was made by a newer RustFrame ({}) - update RustFrame to import it",
+            manifest["rustframe"].as_str().unwrap_or("unknown version")
+        ));
+    }
+
+    let config_dir = settings_file::dir().ok_or_else(|| anyhow!("%APPDATA% is not set"))?;
+    let mut incoming: Value = serde_json::from_str(&read_text(&mut zip, config_bundle::SETTINGS)?)
+        .context("Invalid settings.json in the bundle")?;
+    if !include_active {
+        strip_active(&mut incoming);
+    }
+    let incoming_scenes = incoming
+        .as_object_mut()
+        .and_then(|map| map.remove("scenes"));
+    let mut settings = match mode {
+        ImportMode::Merge => serde_json::to_value(current)?,
+        ImportMode::Replace => serde_json::to_value(AppSettings::default())?,
+    };
+    merge(&mut settings, incoming);
+    if let Some(Value::Array(scenes)) = incoming_scenes {
+        merge_scenes(&mut settings, scenes);
+    }
+    let local = serde_json::to_value(current)?;
+    if let Value::Object(map) = &mut settings {
+        // Machine-specific values stay as they are here, and so do the
+        // active parts the user did not accept
+        let mut keep: Vec<&str> = config_bundle::LOCAL_KEYS.to_vec();
+        if !include_active {
+            keep.extend(config_bundle::ACTIVE_KEYS);
+        }
+        for key in keep {
+            if let Some(value) = local.get(key) {
+                map.insert(key.to_string(), value.clone());
+            }
+        }
+        if !include_active {
+            if let (Some(post_actions), Some(command)) = (
+                map.get_mut("post_actions").and_then(Value::as_object_mut),
+                local["post_actions"].get("command"),
+            ) {
+                post_actions.insert("command".into(), command.clone());
+            }
+        }
+        // Bundled images are unpacked next to settings.json
+        if let Some(Value::String(image)) = map.get_mut("image_path") {
+            if let Some(relative) = image.strip_prefix(config_bundle::IMAGES_DIR) {
+                let local = config_dir
+                    .join(config_bundle::IMAGES_DIR)
+                    .join(relative.trim_start_matches('/'));
+                *image = local.to_string_lossy().trim_end_matches('\\').to_string();
+            }
+        }
+    }
+    let parsed: AppSettings =
+        serde_json::from_value(settings.clone()).context("Invalid settings in the bundle")?;
+    settings_file::validate(&parsed).context("Invalid settings in the bundle")?;
+
+    // Scripts not accepted: the local ones stay untouched
+    let scripts_dir = scripting::dir().filter(|_| include_active);
+    if mode == ImportMode::Replace {
+        if let Some(dir) = &scripts_dir {
+            for path in script_files(dir) {
+                std::fs::remove_file(&path)
+                    .with_context(|| format!("Failed to remove {:?}", path))?;
+            }
+        }
+    }
+
+    let mut summary = BundleSummary::default();
+    for index in 0..zip.len() {
+        let mut entry = zip.by_index(index)?;
+        // enclosed_name rejects absolute paths and ".." components
+        let Some(name) = entry.enclosed_name() else {
+            continue;
+        };
+        let (target, count) = if let Ok(file) = name.strip_prefix(config_bundle::SCRIPTS_DIR) {
+            match &scripts_dir {
+                Some(dir) => (dir.join(file), &mut summary.scripts),
+                None => continue,
+            }
+        } else if let Ok(file) = name.strip_prefix(config_bundle::IMAGES_DIR) {
+            (
+                config_dir.join(config_bundle::IMAGES_DIR).join(file),
+                &mut summary.images,
+            )
+        } else {
+            continue;
+        };
+        if entry.is_dir() {
+            continue;
+        }
+        if let Some(dir) = target.parent() {
+            std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+        }
+        let data = read_entry(&mut entry)?;
+        std::fs::write(&target, data).with_context(|| format!("Failed to write {:?}", target))?;
+        *count += 1;
+    }
+
+    let settings_path = settings_file::path().ok_or_else(|| anyhow!("%APPDATA% is not set"))?;
+    std::fs::create_dir_all(&config_dir)
+        .with_context(|| format!("Failed to create {:?}", config_dir))?;
+    std::fs::write(&settings_path, serde_json::to_string_pretty(&settings)?)
+        .with_context(|| format!("Failed to write {:?}", settings_path))?;
+    info!(
+        "Imported configuration from {:?} ({:?}, {} script(s), {} image(s))",
+        path, mode, summary.scripts, summary.images
+    );
+    Ok(summary)
+}
+
+/// Remove what runs code or sends data from incoming settings
+fn strip_active(incoming: &mut Value) {
+    let Some(map) = incoming.as_object_mut() else {
+        return;
+    };
+    for key in config_bundle::ACTIVE_KEYS {
+        map.remove(key);
+    }
+    if let Some(post_actions) = map.get_mut("post_actions").and_then(Value::as_object_mut) {
+        post_actions.remove("command");
+    }
+}
+
+/// Scenes are slots, not a list: a scene from the bundle replaces the one in
+/// the same slot, its empty slots leave the local scene alone
+fn merge_scenes(settings: &mut Value, incoming: Vec<Value>) {
+    let Some(map) = settings.as_object_mut() else {
+        return;
+    };
+    let slots = map
+        .entry("scenes")
+        .or_insert_with(|| Value::Array(Vec::new()));
+    let Value::Array(slots) = slots else {
+        return;
+    };
+    for (slot, scene) in incoming.into_iter().enumerate() {
+        if scene.is_null() {
+            continue;
+        }
+        if slots.len() <= slot {
+            slots.resize(slot + 1, Value::Null);
+        }
+        slots[slot] = scene;
+    }
+}
+
+/// Combine settings: values from `incoming` win, objects are combined key by
+/// key and lists get the entries they do not have yet
+fn merge(base: &mut Value, incoming: Value) {
+    match (base, incoming) {
+        (Value::Object(base), Value::Object(incoming)) => {
+            for (key, value) in incoming {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Array(base), Value::Array(incoming)) => {
+            for value in incoming {
+                if !base.contains(&value) {
+                    base.push(value);
+                }
+            }
+        }
+        (base, incoming) => *base = incoming,
+    }
+}
+
+fn open(path: &Path) -> Result<zip::ZipArchive<std::fs::File>> {
+    let file = std::fs::File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+    zip::ZipArchive::new(file).with_context(|| format!("{:?} is not a zip file", path))
+}
+
+fn read_text(zip: &mut zip::ZipArchive<std::fs::File>, name: &str) -> Result<String> {
+    let mut entry = zip
+        .by_name(name)
+        .map_err(|_| anyhow!("Not a RustFrame configuration bundle (no {})", name))?;
+    let data = read_entry(&mut entry)?;
+    String::from_utf8(data).with_context(|| format!("{} is not UTF-8 text", name))
+}
+
+/// Unpack an entry, up to MAX_ENTRY_SIZE bytes (the size in the zip header
+/// is not trusted)
+fn read_entry(entry: &mut zip::read::ZipFile) -> Result<Vec<u8>> {
+    let limit = config_bundle::MAX_ENTRY_SIZE;
+    let name = entry.name().to_string();
+    let too_large = || anyhow!("{} is larger than {} MB", name, limit / (1024 * 1024));
+    if entry.size() > limit {
+        return Err(too_large());
+    }
+    let mut data = Vec::new();
+    entry.by_ref().take(limit + 1).read_to_end(&mut data)?;
+    if data.len() as u64 > limit {
+        return Err(too_large());
+    }
+    Ok(data)
+}
+
+/// The *.rhai files of the scripts folder
+fn script_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("rhai"))
+        })
+        .collect()
+}
+
+/// Ask where to save a bundle (None = cancelled)
+#[cfg(windows)]
+pub fn pick_export_path() -> Option<PathBuf> {
+    pick_file(true)
+}
+
+/// Ask which bundle to import (None = cancelled)
+#[cfg(windows)]
+pub fn pick_import_path() -> Option<PathBuf> {
+    pick_file(false)
+}
+
+/// Ask whether to import the active parts of a bundle (None = cancelled)
+#[cfg(windows)]
+pub fn ask_include_active(content: &ActiveContent) -> Option<bool> {
+    use windows::core::PCWSTR;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        MessageBoxW, IDNO, IDYES, MB_DEFBUTTON2, MB_ICONWARNING, MB_SETFOREGROUND, MB_YESNOCANCEL,
+    };
+
+    let text = crate::utils::wide_string(&format!(
+        "The bundle runs programs or sends data when it is used:\n\n{}\n\n\
+         Yes: import these too - only if you trust who made the bundle\n\
+         No: import the bundle without them and keep your own",
+        content.describe()
+    ));
+    let caption = crate::utils::wide_string("Import Configuration");
+    let answer = unsafe {
+        MessageBoxW(
+            None,
+            PCWSTR(text.as_ptr()),
+            PCWSTR(caption.as_ptr()),
+            MB_YESNOCANCEL | MB_ICONWARNING | MB_DEFBUTTON2 | MB_SETFOREGROUND,
+        )
+    };
+    match answer {
+        IDYES => Some(true),
+        IDNO => Some(false),
+        _ => None,
+    }
+}
+
+/// Ask whether to merge or replace (None = cancelled)
+#[cfg(windows)]
+pub fn ask_import_mode() -> Option<ImportMode> {
+    use windows::core::PCWSTR;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        MessageBoxW, IDNO, IDYES, MB_ICONQUESTION, MB_SETFOREGROUND, MB_YESNOCANCEL,
+    };
+
+    let text = crate::utils::wide_string(
+        "Merge the bundle into your current setup?\n\n\
+         Yes: the bundle's values win, your other settings and scripts stay\n\
+         No: replace your settings and scripts with the bundle",
+    );
+    let caption = crate::utils::wide_string("Import Configuration");
+    let answer = unsafe {
+        MessageBoxW(
+            None,
+            PCWSTR(text.as_ptr()),
+            PCWSTR(caption.as_ptr()),
+            MB_YESNOCANCEL | MB_ICONQUESTION | MB_SETFOREGROUND,
+        )
+    };
+    match answer {
+        IDYES => Some(ImportMode::Merge),
+        IDNO => Some(ImportMode::Replace),
+        _ => None,
+    }
+}
+
+/// Common file dialog for *.zip bundles
+#[cfg(windows)]
+fn pick_file(save: bool) -> Option<PathBuf> {
+    use windows::core::{w, Interface};
+    use windows::Win32::System::Com::{CoCreateInstance, CoTaskMemFree, CLSCTX_INPROC_SERVER};
+    use windows::Win32::UI::Shell::Common::COMDLG_FILTERSPEC;
+    use windows::Win32::UI::Shell::{
+        FileOpenDialog, FileSaveDialog, IFileDialog, IFileOpenDialog, IFileSaveDialog,
+        SIGDN_FILESYSPATH,
+    };
+
+    unsafe {
+        let dialog: IFileDialog = if save {
+            CoCreateInstance::<_, IFileSaveDialog>(&FileSaveDialog, None, CLSCTX_INPROC_SERVER)
+                .ok()?
+                .cast()
+                .ok()?
+        } else {
+            CoCreateInstance::<_, IFileOpenDialog>(&FileOpenDialog, None, CLSCTX_INPROC_SERVER)
+                .ok()?
+                .cast()
+                .ok()?
+        };
+        let filters = [COMDLG_FILTERSPEC {
+            pszName: w!("RustFrame configuration (*.zip)"),
+            pszSpec: w!("*.zip"),
+        }];
+        dialog.SetFileTypes(&filters).ok()?;
+        dialog.SetDefaultExtension(w!("zip")).ok()?;
+        if save {
+            dialog.SetFileName(w!("RustFrame configuration.zip")).ok()?;
+            dialog.SetTitle(w!("Export Configuration")).ok()?;
+        } else {
+            dialog.SetTitle(w!("Import Configuration")).ok()?;
+        }
+        // Fails when the user cancels
+        dialog.Show(None).ok()?;
+        let item = dialog.GetResult().ok()?;
+        let name = item.GetDisplayName(SIGDN_FILESYSPATH).ok()?;
+        let path = name.to_string().ok();
+        CoTaskMemFree(Some(name.0 as *const std::ffi::c_void));
+        path.map(PathBuf::from)
+    }
+}
+
+#[cfg(not(windows))]
+pub fn pick_export_path() -> Option<PathBuf> {
+    None
+}
+
+#[cfg(not(windows))]
+pub fn pick_import_path() -> Option<PathBuf> {
+    None
+}
+
+#[cfg(not(windows))]
+pub fn ask_import_mode() -> Option<ImportMode> {
+    None
+}
+
+#[cfg(not(windows))]
+pub fn ask_include_active(_content: &ActiveContent) -> Option<bool> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn merge_lets_incoming_values_win_and_keeps_the_rest() {
+        let mut base = json!({
+            "fps": 30,
+            "capture": {"show_border": true, "border_width": 4},
+            "keep": "local",
+        });
+        merge(
+            &mut base,
+            json!({"fps": 60, "capture": {"border_width": 8}, "new": [1]}),
+        );
+        assert_eq!(
+            base,
+            json!({
+                "fps": 60,
+                "capture": {"show_border": true, "border_width": 8},
+                "keep": "local",
+                "new": [1],
+            })
+        );
+    }
+
+    #[test]
+    fn merge_appends_only_missing_list_entries() {
+        let mut base = json!({"redacted_apps": ["a.exe", "b.exe"]});
+        merge(&mut base, json!({"redacted_apps": ["b.exe", "c.exe"]}));
+        assert_eq!(base, json!({"redacted_apps": ["a.exe", "b.exe", "c.exe"]}));
+    }
+
+    #[test]
+    fn merge_replaces_values_of_another_type() {
+        let mut base = json!({"upload": null});
+        merge(&mut base, json!({"upload": {"target": "s3"}}));
+        assert_eq!(base, json!({"upload": {"target": "s3"}}));
+    }
+
+    #[test]
+    fn merge_scenes_keeps_slots_the_bundle_leaves_empty() {
+        let mut settings = json!({"scenes": [{"name": "a"}, {"name": "b"}]});
+        merge_scenes(
+            &mut settings,
+            vec![Value::Null, json!({"name": "x"}), json!({"name": "y"})],
+        );
+        assert_eq!(
+            settings,
+            json!({"scenes": [{"name": "a"}, {"name": "x"}, {"name": "y"}]})
+        );
+    }
+
+    #[test]
+    fn strip_active_removes_what_runs_or_sends() {
+        let mut incoming = json!({
+            "fps": 60,
+            "webhooks": [{"url": "https://example.com"}],
+            "upload": {"target": "s3"},
+            "post_actions": {"command": "calc.exe", "copy_path": true},
+        });
+        strip_active(&mut incoming);
+        assert_eq!(
+            incoming,
+            json!({"fps": 60, "post_actions": {"copy_path": true}})
+        );
+    }
+
+    #[test]
+    fn active_content_lists_every_item() {
+        let content = ActiveContent {
+            command: Some("calc.exe".to_string()),
+            scripts: vec!["a.rhai".to_string()],
+            ..Default::default()
+        };
+        assert!(!content.is_empty());
+        assert_eq!(
+            content.describe(),
+            "Command after each capture: calc.exe\nScript: a.rhai"
+        );
+        assert!(ActiveContent::default().is_empty());
+    }
+}
@@ -3,7 +3,7 @@
 // Centralized constants for colors, dimensions, and other magic numbers.
 // This makes the code more maintainable and consistent.
 
-/// Overlay window colors (ARGB format)
+/// Overlay window colors (ARGB format) of the dark theme (see theme.rs)
 pub mod colors {
     /// Bright blue border color
     pub const BORDER: u32 = 0xFF00A8FF;
@@ -27,6 +27,46 @@ pub mod colors {
     pub const TEXT_RED: u32 = 0xFFFF4444;
     /// Yellow text (for dev mode indicator)
     pub const TEXT_YELLOW: u32 = 0xFFFFCC00;
+    /// Keyboard focus outline in the overlay help text
+    pub const FOCUS: u32 = 0xFFFFFFFF;
+    /// Settings dialog background
+    pub const WINDOW_BG: u32 = 0xFF202020;
+
+    /// Colors that differ in the light theme
+    pub mod light {
+        /// Light gray background for text box
+        pub const TEXT_BG: u32 = 0xF0F3F3F3;
+        /// Almost black text
+        pub const TEXT: u32 = 0xFF1A1A1A;
+        /// Darker blue title (readable on the light background)
+        pub const TITLE: u32 = 0xFF0067C0;
+        /// Secondary info
+        pub const SECONDARY: u32 = 0xFF5A5A5A;
+        /// Enabled settings
+        pub const ENABLED: u32 = 0xFF107C10;
+        /// Disabled settings
+        pub const DISABLED: u32 = 0xFFC42B1C;
+        /// Dev mode indicator
+        pub const WARNING: u32 = 0xFF9D5D00;
+        /// Settings dialog background
+        pub const WINDOW_BG: u32 = 0xFFF3F3F3;
+    }
+
+    /// The RustFrame high contrast theme (a Windows contrast theme uses its own colors)
+    pub mod high_contrast {
+        /// Black background
+        pub const BACKGROUND: u32 = 0xFF000000;
+        /// White text
+        pub const TEXT: u32 = 0xFFFFFFFF;
+        /// Yellow border, corners and title
+        pub const ACCENT: u32 = 0xFFFFFF00;
+        /// Cyan secondary info
+        pub const SECONDARY: u32 = 0xFF00FFFF;
+        /// Bright green enabled settings
+        pub const ENABLED: u32 = 0xFF00FF00;
+        /// Light red disabled settings
+        pub const DISABLED: u32 = 0xFFFF8080;
+    }
 }
 
 /// Overlay window dimensions
@@ -48,14 +88,39 @@ pub mod overlay {
     pub const RESIZE_MARGIN: i32 = 8;
 }
 
+/// Keyboard operation of the selection overlay
+pub mod keyboard {
+    /// Arrow keys move / Shift+Arrow resizes the region by this many pixels
+    pub const STEP: i32 = 10;
+    /// Step with Ctrl held (fine adjustment)
+    pub const FINE_STEP: i32 = 1;
+    /// Number of overlay settings lines reachable with Tab (cursor, border, mode)
+    pub const FOCUSABLE_OPTIONS: usize = 3;
+}
+
 /// Text box dimensions for help text
 pub mod text_box {
     /// Fixed width of the help text box
     pub const WIDTH: i32 = 280;
-    /// Fixed height of the help text box  
-    pub const HEIGHT: i32 = 260;
+    /// Fixed height of the help text box (both at 100 % scaling)
+    pub const HEIGHT: i32 = 280;
     /// Border width of text box
     pub const BORDER_WIDTH: i32 = 2;
+    /// Font height of the title and of the other lines (pixels at 100 % scaling)
+    pub const TITLE_SIZE: i32 = 22;
+    pub const LINE_SIZE: i32 = 13;
+    /// Height of an empty line between groups
+    pub const EMPTY_LINE_HEIGHT: i32 = 8;
+    /// Space between a focused line and its outline
+    pub const FOCUS_PADDING: i32 = 4;
+}
+
+/// Scalable text in the overlay windows (see text.rs)
+pub mod text {
+    /// Font face
+    pub const FONT_NAME: &str = "Segoe UI";
+    /// Font sizes kept per thread before the oldest is released
+    pub const MAX_FONTS: usize = 8;
 }
 
 /// Settings dialog dimensions
@@ -63,9 +128,31 @@ pub mod dialog {
     /// Dialog width in pixels
     pub const WIDTH: i32 = 420;
     /// Dialog height in dev mode (with production mode option)
-    pub const HEIGHT_DEV: i32 = 320;
+    pub const HEIGHT_DEV: i32 = 1012;
     /// Dialog height in production mode
-    pub const HEIGHT_PROD: i32 = 280;
+    pub const HEIGHT_PROD: i32 = 972;
+    /// Space around the tab control
+    pub const TAB_MARGIN: i32 = 8;
+    /// Height of the tab strip (General / Shortcuts / MIDI / Plugins)
+    pub const TAB_HEIGHT: i32 = 28;
+    /// Top of the tab pages
+    pub const PAGE_TOP: i32 = 40;
+    /// Height of the action list on the Shortcuts tab
+    pub const SHORTCUT_LIST_HEIGHT: i32 = 300;
+    /// Height of the conflict / warning text on the Shortcuts tab
+    pub const SHORTCUT_STATUS_HEIGHT: i32 = 60;
+    /// Height of the action list on the MIDI tab
+    pub const MIDI_LIST_HEIGHT: i32 = 220;
+    /// Height of the learn / device status text on the MIDI tab
+    pub const MIDI_STATUS_HEIGHT: i32 = 60;
+    /// Edits kept for Undo
+    pub const UNDO_LIMIT: usize = 100;
+    /// Interval of the live preview while the dialog is open (~30 FPS output)
+    pub const PREVIEW_TICK_MS: u32 = 33;
+    /// Height of the plugin list on the Plugins tab
+    pub const PLUGIN_LIST_HEIGHT: i32 = 220;
+    /// Height of the selected plugin's panel on the Plugins tab
+    pub const PLUGIN_PANEL_HEIGHT: i32 = 200;
 }
 
 /// Default capture settings
@@ -76,4 +163,911 @@ pub mod capture {
     pub const MIN_BORDER_WIDTH: u32 = 1;
     /// Maximum allowed border width
     pub const MAX_BORDER_WIDTH: u32 = 50;
+    /// Default countdown before capture starts (seconds, 0 = start immediately)
+    pub const DEFAULT_COUNTDOWN_SECONDS: u32 = 0;
+    /// Longest allowed countdown
+    pub const MAX_COUNTDOWN_SECONDS: u32 = 10;
+    /// Default grace period the last good frame is held during interruptions
+    /// (source minimized, UAC prompt) - 0 = never hold
+    pub const DEFAULT_HOLD_SECONDS: u32 = 0;
+    /// Longest allowed hold grace period
+    pub const MAX_HOLD_SECONDS: u32 = 60;
+    /// Default duration of output transitions (milliseconds, 0 = cut)
+    pub const DEFAULT_TRANSITION_MS: u32 = 300;
+    /// Longest allowed transition
+    pub const MAX_TRANSITION_MS: u32 = 5000;
+    /// Default output frame rate limit (0 = render every captured frame)
+    pub const DEFAULT_TARGET_FPS: u32 = 0;
+    /// Highest selectable output frame rate
+    pub const MAX_TARGET_FPS: u32 = 240;
+    /// Longest sleep of the event loop while capturing (milliseconds) - new
+    /// captured frames wake it earlier; overlays, webcams, images and
+    /// transitions are updated at least this often
+    pub const FRAME_WAIT_MS: u64 = 8;
+    /// Default frame rate while the captured content does not change (0 = off)
+    pub const DEFAULT_IDLE_FPS: u32 = 5;
+    /// Default time without a change before the idle frame rate is used
+    pub const DEFAULT_IDLE_AFTER_MS: u32 = 2000;
+    /// Longest allowed time before the idle frame rate is used
+    pub const MAX_IDLE_AFTER_MS: u32 = 60_000;
+    /// Default time per slide of a folder slideshow (seconds, 0 = manual)
+    pub const DEFAULT_SLIDE_SECONDS: u32 = 10;
+    /// Longest allowed time per slide
+    pub const MAX_SLIDE_SECONDS: u32 = 3600;
+    /// Default duration of the auto pan (seconds, see pan.rs)
+    pub const DEFAULT_PAN_SECONDS: u32 = 20;
+    /// Longest allowed auto pan
+    pub const MAX_PAN_SECONDS: u32 = 600;
+    /// How often CaptureSession::next_frame checks for a new frame (milliseconds)
+    pub const SESSION_POLL_MS: u64 = 1;
+    /// How often the FrameStream thread checks whether the stream was dropped
+    /// while no frames arrive (milliseconds)
+    pub const STREAM_STOP_CHECK_MS: u64 = 100;
+}
+
+/// Headless recording to a video file (see record.rs)
+pub mod record {
+    /// Default frame rate of recorded videos
+    pub const DEFAULT_FPS: u32 = 30;
+    /// Default H.264 bitrate (bits per second)
+    pub const DEFAULT_BITRATE: u32 = 8_000_000;
+    /// How long to wait for the first captured frame (milliseconds)
+    pub const FIRST_FRAME_TIMEOUT_MS: u64 = 2000;
+    /// How often the recording checks whether the encoder keeps up (milliseconds)
+    pub const OVERLOAD_CHECK_MS: u64 = 1000;
+    /// Frames later than this (and getting later) mean the encoder falls behind
+    pub const OVERLOAD_LATE_MS: u64 = 250;
+    /// More samples waiting in the encoder mean it falls behind
+    pub const OVERLOAD_QUEUED_SAMPLES: u64 = 8;
+    /// Time with headroom before the recording steps back up (milliseconds)
+    pub const RESTORE_MS: u64 = 5000;
+    /// Seconds between keyframes; every keyframe starts a new fragment of
+    /// the MP4 file, so a crash loses at most this much
+    pub const FRAGMENT_SECONDS: u32 = 2;
+    /// Journal of running recordings, under %APPDATA%\RustFrame (see recovery.rs)
+    pub const JOURNAL_DIR_NAME: &str = "recordings";
+    /// How long closing the console waits for the file to be finished
+    /// (Windows ends the process after about 5 seconds anyway)
+    pub const CLOSE_WAIT_MS: u64 = 4500;
+    /// Shown when `RustFrame record` gets invalid options
+    pub const USAGE: &str = "Usage: RustFrame record --region <x,y,width,height> --duration <seconds> \
+                             --out <file.mp4> [--fps <n>] [--bitrate <bits per second>] [--no-cursor] \
+                             [--quality] [--json-errors]";
+}
+
+/// Video quality measurement of recordings (see quality.rs)
+pub mod quality {
+    /// Seconds between two sampled source frames (doubled when the memory
+    /// budget is full)
+    pub const SAMPLE_SECONDS: u64 = 1;
+    /// Memory for the luma of the sampled frames (bytes)
+    pub const SAMPLE_MEMORY_BYTES: usize = 256 * 1024 * 1024;
+    /// PSNR reported for identical frames (dB)
+    pub const MAX_PSNR_DB: f64 = 100.0;
+    /// Side of the blocks SSIM compares (pixels)
+    pub const SSIM_BLOCK: usize = 8;
+}
+
+/// One-shot screenshots (RustFrame shot)
+pub mod shot {
+    /// How long to wait for the captured frame (milliseconds)
+    pub const FRAME_TIMEOUT_MS: u64 = 2000;
+    /// Time for a window brought to the front to draw itself (milliseconds)
+    pub const WINDOW_SETTLE_MS: u64 = 300;
+    /// Shown when `RustFrame shot` gets invalid options
+    pub const USAGE: &str = "Usage: RustFrame shot [--monitor <n|primary> | --window <title> | \
+                             --region <x,y,width,height>] [--out <file.png>] [--cursor] [--json-errors]";
+}
+
+/// Capture benchmark (RustFrame bench)
+pub mod bench {
+    /// Length of a run without --seconds
+    pub const DEFAULT_SECONDS: f64 = 10.0;
+    /// Sleep between two polls for a new frame (milliseconds)
+    pub const POLL_MS: u64 = 1;
+    /// Shown when `RustFrame bench` gets invalid options
+    pub const USAGE: &str = "Usage: RustFrame bench --region <x,y,width,height> [--seconds <n>] [--cursor] \
+                             [--json] [--json-errors]";
+}
+
+/// End-to-end latency test (RustFrame latency)
+pub mod latency {
+    /// Flashes measured without --samples
+    pub const DEFAULT_SAMPLES: u32 = 30;
+    pub const MAX_SAMPLES: u32 = 1000;
+    /// Width and height of the flashing test window (pixels)
+    pub const WINDOW_SIZE: i32 = 64;
+    /// Pause between two flashes (milliseconds), plus a varying part below
+    /// GAP_JITTER_MS so the flashes do not line up with the refresh
+    pub const GAP_MS: u64 = 100;
+    pub const GAP_JITTER_MS: u64 = 37;
+    /// How long a flash may take to come back before it counts as missed
+    pub const SAMPLE_TIMEOUT_MS: u64 = 1000;
+    /// Shown when `RustFrame latency` gets invalid options
+    pub const USAGE: &str = "Usage: RustFrame latency [--samples <n>] [--json] [--json-errors]";
+}
+
+/// Diagnostic reports (RustFrame diagnose)
+pub mod diagnose {
+    /// Lines of rustframe.log included in the report
+    pub const LOG_LINES: usize = 200;
+    /// Replaces redacted values
+    pub const REDACTED: &str = "<redacted>";
+    /// settings.json keys whose values are left out (matched as part of the key)
+    pub const SECRET_KEYS: &[&str] = &[
+        "secret", "token", "password", "passphrase", "access_key", "key_id", "client_id", "bucket",
+    ];
+    /// settings.json keys holding URLs - only the scheme and host are kept
+    pub const URL_KEYS: &[&str] = &["url", "endpoint"];
+    /// File names inside a .zip report
+    pub const REPORT_NAME: &str = "report.txt";
+    pub const SETTINGS_NAME: &str = "settings.json";
+    pub const LOG_NAME: &str = "rustframe.log";
+    /// Shown when `RustFrame diagnose` gets invalid options
+    pub const USAGE: &str = "Usage: RustFrame diagnose [--out <report.txt|report.zip>] [--json-errors]";
+}
+
+/// Settings file validation (RustFrame check-config)
+pub mod check_config {
+    /// Shown when `RustFrame check-config` gets invalid options
+    pub const USAGE: &str = "Usage: RustFrame check-config [<settings.json>] [--json] [--json-errors]";
+}
+
+/// Monitor and window lists (RustFrame list-monitors / list-windows)
+pub mod list {
+    /// DPI reported when Windows does not know it (100 % scaling)
+    pub const DEFAULT_DPI: u32 = 96;
+}
+
+/// Annotation (draw mode) settings
+pub mod annotation {
+    /// Selectable stroke colors (keys 1-6 in draw mode)
+    pub const PALETTE: [u32; 6] = [
+        0xFFFF3B30, // Red
+        0xFF34C759, // Green
+        0xFF00A8FF, // Blue
+        0xFFFFCC00, // Yellow
+        0xFFFFFFFF, // White
+        0xFF000000, // Black
+    ];
+    /// Default stroke width in pixels
+    pub const DEFAULT_WIDTH: u32 = 4;
+    /// Minimum stroke width
+    pub const MIN_WIDTH: u32 = 1;
+    /// Maximum stroke width
+    pub const MAX_WIDTH: u32 = 32;
+    /// Stroke width at full pen pressure, relative to the selected width
+    pub const PEN_PRESSURE_SCALE: f32 = 2.0;
+    /// Border color shown around the overlay while draw mode is active
+    pub const BORDER: u32 = 0xFFFFCC00;
+    /// Interior fill while drawing - alpha 1 so the layered window receives
+    /// mouse input without visibly tinting the content underneath
+    pub const INTERIOR: u32 = 0x01000000;
+}
+
+/// Presentation spotlight settings
+pub mod spotlight {
+    /// Brightness kept outside the focus area (percent)
+    pub const DIM_PERCENT: u32 = 35;
+    /// Default focus area half-width in pixels
+    pub const DEFAULT_RADIUS_X: i32 = 220;
+    /// Default focus area half-height in pixels
+    pub const DEFAULT_RADIUS_Y: i32 = 150;
+    /// Smallest allowed half-extent
+    pub const MIN_RADIUS: i32 = 40;
+    /// Largest allowed half-extent
+    pub const MAX_RADIUS: i32 = 2000;
+    /// Resize step per hotkey press
+    pub const RESIZE_STEP: i32 = 20;
+    /// Width of the soft edge between focus area and dimmed area
+    pub const FEATHER: i32 = 24;
+}
+
+/// Magnifier (zoom lens) settings
+pub mod magnifier {
+    /// Zoom factor when the magnifier is first enabled
+    pub const DEFAULT_ZOOM: u32 = 2;
+    /// Smallest zoom factor
+    pub const MIN_ZOOM: u32 = 2;
+    /// Largest zoom factor
+    pub const MAX_ZOOM: u32 = 8;
+    /// Lens width and height in pixels
+    pub const LENS_SIZE: i32 = 240;
+    /// Distance between the cursor and the lens
+    pub const CURSOR_OFFSET: i32 = 24;
+    /// Lens border thickness
+    pub const BORDER_WIDTH: i32 = 2;
+    /// Lens border color
+    pub const BORDER: u32 = 0xFF00A8FF;
+    /// Fill for lens pixels that fall outside the captured frame
+    pub const OUTSIDE: u32 = 0xFF202020;
+}
+
+/// On-screen timer / stopwatch settings
+pub mod timer {
+    /// Countdown durations offered by the mode menu (seconds)
+    pub const COUNTDOWN_PRESETS: [u64; 3] = [60, 300, 600];
+    /// Distance between the timer box and the frame edge
+    pub const MARGIN: i32 = 16;
+    /// Padding between the timer text and its box
+    pub const PADDING: i32 = 8;
+    /// Timer box background
+    pub const BACKGROUND: u32 = 0xFF181818;
+    /// Timer text color while running or paused
+    pub const TEXT: u32 = 0xFFFFFFFF;
+    /// Timer text color when a countdown has reached zero
+    pub const EXPIRED: u32 = 0xFFFF4444;
+}
+
+/// Pre-capture countdown display
+pub mod countdown {
+    /// Dimmed background behind the countdown number (premultiplied ARGB)
+    pub const BACKGROUND: u32 = 0xB0000000;
+    /// Countdown number color
+    pub const TEXT: u32 = 0xFFFFFFFF;
+}
+
+/// Pause ("be right back") screen settings
+pub mod pause_screen {
+    /// Card background
+    pub const CARD_BACKGROUND: u32 = 0xFF181818;
+    /// Card accent bar color
+    pub const CARD_ACCENT: u32 = 0xFF00A8FF;
+    /// Card text color
+    pub const CARD_TEXT: u32 = 0xFFFFFFFF;
+    /// Card headline
+    pub const CARD_TITLE: &str = "BE RIGHT BACK";
+    /// Solid color placeholder
+    pub const SOLID_COLOR: u32 = 0xFF000000;
+    /// Custom image file name (looked up next to the executable, then in the current directory)
+    pub const IMAGE_FILE: &str = "pause.png";
+}
+
+/// Composition grid settings
+pub mod grid {
+    /// Columns of the even grid style
+    pub const COLUMNS: u32 = 4;
+    /// Rows of the even grid style
+    pub const ROWS: u32 = 4;
+    /// Guide line thickness in pixels
+    pub const LINE_WIDTH: i32 = 1;
+    /// Guide line color on the selection overlay (premultiplied ARGB)
+    pub const OVERLAY_COLOR: u32 = 0x80808080;
+    /// Guide line color blended into the output
+    pub const OUTPUT_COLOR: u32 = 0xFFFFFFFF;
+}
+
+/// Ruler / measure mode settings (drawn on the overlay only)
+pub mod measure {
+    /// Measured rectangle outline
+    pub const BOX: u32 = 0xFF00D4FF;
+    /// Distance line and end point markers
+    pub const LINE: u32 = 0xFFFFCC00;
+    /// End point marker size in pixels
+    pub const MARKER_SIZE: i32 = 5;
+    /// Readout font height (pixels at 100 % scaling)
+    pub const TEXT_SIZE: i32 = 16;
+    /// Padding between readout text and its background
+    pub const LABEL_PADDING: i32 = 4;
+    /// Distance between the end point and the readout label
+    pub const LABEL_OFFSET: i32 = 12;
+    /// Readout background
+    pub const LABEL_BACKGROUND: u32 = 0xF0181818;
+    /// Readout text color
+    pub const LABEL_TEXT: u32 = 0xFFFFFFFF;
+    /// Border color shown around the overlay while measure mode is active
+    pub const BORDER: u32 = 0xFF00D4FF;
+}
+
+/// Color picker (eyedropper) settings (drawn on the overlay only)
+pub mod color_picker {
+    /// Pixels shown around the picked one, per side of the zoomed view (odd)
+    pub const ZOOM_PIXELS: i32 = 9;
+    /// Size of one zoomed pixel (pixels at 100 % scaling)
+    pub const ZOOM_CELL: i32 = 8;
+    /// Color swatch size (pixels at 100 % scaling)
+    pub const SWATCH_SIZE: i32 = 24;
+    /// Readout font height (pixels at 100 % scaling)
+    pub const TEXT_SIZE: i32 = 16;
+    /// Padding inside the panel and between its parts
+    pub const PADDING: i32 = 6;
+    /// Distance between the cursor and the panel
+    pub const OFFSET: i32 = 16;
+    /// Panel background
+    pub const LABEL_BACKGROUND: u32 = 0xF0181818;
+    /// Readout text color
+    pub const LABEL_TEXT: u32 = 0xFFFFFFFF;
+    /// Outline of the picked pixel and the swatch
+    pub const CENTER: u32 = 0xFFFFFFFF;
+    /// Zoomed pixels outside the capture region
+    pub const OUTSIDE: u32 = 0xFF404040;
+    /// Border color shown around the overlay while the color picker is active
+    pub const BORDER: u32 = 0xFFFF4FD8;
+}
+
+/// Privacy blur / pixelate regions
+pub mod privacy {
+    /// Pixelation block size in pixels
+    pub const BLOCK_SIZE: i32 = 16;
+    /// Box blur radius in pixels (applied in three passes)
+    pub const BLUR_RADIUS: i32 = 8;
+    /// Regions smaller than this (either side) are discarded when created
+    pub const MIN_SIZE: i32 = 8;
+    /// Region outline on the overlay
+    pub const OUTLINE: u32 = 0xFFFF3B30;
+    /// Font height of the labels on the overlay (pixels at 100 % scaling)
+    pub const LABEL_SIZE: i32 = 13;
+    /// Outline of the selected region
+    pub const SELECTED: u32 = 0xFFFFCC00;
+    /// Border color shown around the overlay while redact mode is active
+    pub const BORDER: u32 = 0xFFFF3B30;
+    /// Default censor brush radius in pixels
+    pub const BRUSH_RADIUS: i32 = 24;
+    /// Smallest / largest brush radius
+    pub const BRUSH_MIN_RADIUS: i32 = 8;
+    pub const BRUSH_MAX_RADIUS: i32 = 128;
+    /// Radius change per [ / ] key press
+    pub const BRUSH_STEP: i32 = 8;
+    /// Painted areas on the overlay (premultiplied, ~25% red)
+    pub const BRUSH_TINT: u32 = 0x40400F0C;
+}
+
+/// Persisted settings
+pub mod settings_file {
+    /// Folder under %APPDATA%
+    pub const DIR_NAME: &str = "RustFrame";
+    /// Settings file name
+    pub const FILE_NAME: &str = "settings.json";
+    /// File next to RustFrame.exe that turns on portable mode
+    pub const PORTABLE_MARKER: &str = "portable.txt";
+    /// Folder next to RustFrame.exe used instead of %APPDATA%\RustFrame in portable mode
+    pub const PORTABLE_DIR_NAME: &str = "config";
+    /// Log file in the portable config folder
+    pub const PORTABLE_LOG_NAME: &str = "rustframe.log";
+    /// How often settings.json is checked for changes made in a text editor
+    pub const WATCH_POLL_MS: u64 = 500;
+}
+
+/// Configuration bundles (tray → Export / Import Configuration)
+pub mod config_bundle {
+    /// Bumped when a bundle can no longer be read by older versions
+    pub const FORMAT_VERSION: u64 = 1;
+    /// Entry names inside the zip file
+    pub const MANIFEST: &str = "rustframe-bundle.json";
+    pub const SETTINGS: &str = "settings.json";
+    pub const SCRIPTS_DIR: &str = "scripts";
+    /// Also the folder next to settings.json that imported images go to
+    pub const IMAGES_DIR: &str = "images";
+    /// Settings that belong to this machine and keep their value on import
+    pub const LOCAL_KEYS: [&str; 3] = ["stream_deck_token", "auto_start", "shell_menu"];
+    /// Settings that send data elsewhere; only imported when the user agrees
+    /// (like the scripts and the post-capture command)
+    pub const ACTIVE_KEYS: [&str; 2] = ["webhooks", "upload"];
+    /// Larger entries are rejected (bytes, unpacked)
+    pub const MAX_ENTRY_SIZE: u64 = 64 * 1024 * 1024;
+}
+
+/// Automatic redaction of listed applications
+pub mod window_mask {
+    /// Applications redacted by default (process file names, case-insensitive)
+    pub const DEFAULT_APPS: [&str; 4] = [
+        "KeePass.exe",
+        "KeePassXC.exe",
+        "1Password.exe",
+        "Bitwarden.exe",
+    ];
+    /// How often window positions are re-scanned (milliseconds)
+    pub const REFRESH_INTERVAL_MS: u64 = 50;
+    /// Extra pixels masked around each window to cover movement between scans
+    pub const MARGIN: i32 = 16;
+    /// Fill color for masked windows
+    pub const COLOR: u32 = 0xFF000000;
+    /// Processes that show toast notifications and shell popups
+    /// (notification center, volume / network flyouts)
+    pub const NOTIFICATION_PROCESSES: [&str; 1] = ["ShellExperienceHost.exe"];
+}
+
+/// Frame filter chain
+pub mod filter {
+    /// Filters enabled by default, in the order they run (see FilterKind::name)
+    /// Masking and redaction come first so nothing later can reveal hidden pixels
+    pub const DEFAULT_ORDER: [&str; 8] = [
+        "window-mask",
+        "privacy",
+        "spotlight",
+        "annotations",
+        "clicks",
+        "magnifier",
+        "grid",
+        "timer",
+    ];
+}
+
+/// Chroma key filter settings
+pub mod chroma_key {
+    /// Green screen key color (sRGB 0xRRGGBB)
+    pub const GREEN: u32 = 0x00B140;
+    /// Blue screen key color
+    pub const BLUE: u32 = 0x0047BB;
+    /// Magenta key color
+    pub const MAGENTA: u32 = 0xFF00FF;
+    /// Similarity values offered by the tray menu (percent)
+    pub const SIMILARITY_PRESETS: [u32; 4] = [20, 30, 40, 50];
+    /// Default similarity (index into SIMILARITY_PRESETS)
+    pub const DEFAULT_SIMILARITY: usize = 2;
+    /// Smoothness values offered by the tray menu (percent)
+    pub const SMOOTHNESS_PRESETS: [u32; 4] = [2, 5, 8, 15];
+    /// Default smoothness (index into SMOOTHNESS_PRESETS)
+    pub const DEFAULT_SMOOTHNESS: usize = 2;
+    /// Checkerboard cell size shown behind keyed areas in the destination window
+    pub const CHECKER_SIZE: f32 = 16.0;
+}
+
+/// Sharpen / denoise output stages
+pub mod enhance {
+    /// Unsharp mask strength (how much of the edge detail is added back)
+    pub const SHARPEN_AMOUNT: f32 = 0.6;
+    /// Per-pixel change (linear RGB distance) below which a change counts as noise
+    pub const DENOISE_THRESHOLD: f32 = 0.04;
+    /// Share of the previous frame blended into pixels that only changed by noise
+    pub const DENOISE_STRENGTH: f32 = 0.5;
+}
+
+/// Output rendering (see renderer.rs)
+pub mod renderer {
+    /// Size of the staging buffers frames are uploaded through (bytes; a
+    /// larger frame gets a buffer of its own size)
+    pub const UPLOAD_CHUNK_BYTES: u64 = 16 * 1024 * 1024;
+    /// Frame textures kept for reuse (the shown frame, the previous one for
+    /// temporal denoise, the one a transition fades out)
+    pub const FRAME_TEXTURE_POOL: usize = 3;
+    /// Changed rectangles remembered per frame texture before it gets the
+    /// whole frame again
+    pub const MAX_DIRTY_RECTS: usize = 64;
+    /// Above this share of the frame (percent) the changed rectangles are not
+    /// uploaded one by one but the whole frame at once
+    pub const DIRTY_AREA_PERCENT: u64 = 50;
+}
+
+/// Low-power mode on battery (see power.rs)
+pub mod power {
+    /// Default output frame rate limit on battery
+    pub const DEFAULT_FPS: u32 = 30;
+    /// Time without a change before the idle frame rate is used on battery
+    pub const IDLE_AFTER_MS: u32 = 500;
+    /// How often the power source is checked (milliseconds)
+    pub const POLL_MS: u64 = 5000;
+}
+
+/// Holding the last frame during capture interruptions
+pub mod frame_hold {
+    /// How often the source window and the input desktop are checked (milliseconds)
+    pub const CHECK_INTERVAL_MS: u64 = 50;
+}
+
+/// Black regions and the Desktop Duplication fallback (see game_capture.rs)
+pub mod game_capture {
+    /// How long the region has to stay black before the cause is looked for
+    pub const BLACK_AFTER_MS: u64 = 3000;
+    /// How often the exclusive-fullscreen state is asked for
+    pub const CHECK_INTERVAL_MS: u64 = 500;
+    /// Pixels sampled per frame for the black check
+    pub const SAMPLES: usize = 1024;
+    /// How long the protected content check waits for a desktop image
+    pub const PROBE_TIMEOUT_MS: u32 = 100;
+}
+
+/// Elevated application windows (see elevation.rs)
+pub mod elevation {
+    /// How often the foreground window is checked during a capture (milliseconds)
+    pub const CHECK_INTERVAL_MS: u64 = 1000;
+    /// Argument of the elevated process started by a restart: the instance
+    /// that started it is still exiting and must not be handed the link
+    pub const RESTARTED_FLAG: &str = "--restarted";
+}
+
+/// Frame comparison against a reference frame (see frame_diff.rs)
+pub mod frame_diff {
+    /// Largest red, green or blue difference that still counts as unchanged
+    /// (0 = every difference counts)
+    pub const TOLERANCE: u8 = 0;
+    /// Time the blink view shows each frame (milliseconds)
+    pub const BLINK_INTERVAL_MS: u64 = 500;
+    /// Brightness of unchanged pixels in the heatmap view (percent)
+    pub const DIM_PERCENT: u32 = 35;
+    /// Readout text scale (bitmap font)
+    pub const TEXT_SCALE: i32 = 2;
+    /// Distance between the readout box and the frame edge
+    pub const MARGIN: i32 = 16;
+    /// Padding between the readout text and its box
+    pub const PADDING: i32 = 8;
+    /// Readout box background
+    pub const BACKGROUND: u32 = 0xFF181818;
+    /// Readout text color
+    pub const TEXT: u32 = 0xFFFFFFFF;
+}
+
+/// QR codes and links in the captured frame (see frame_scan.rs)
+pub mod frame_scan {
+    /// Characters of a result shown in tray → Scan Results (the rest is cut
+    /// off; Copy and Open use the whole text)
+    pub const MENU_TEXT_CHARS: usize = 60;
+}
+
+/// Automated pan along a path (see pan.rs)
+pub mod pan {
+    /// Durations the tray item cycles through (seconds)
+    pub const DURATIONS: [u32; 6] = [5, 10, 20, 30, 60, 120];
+    /// Most points a path can have
+    pub const MAX_POINTS: usize = 16;
+}
+
+/// Scenes (saved region + output setups)
+pub mod scene {
+    /// Number of scene slots (Ctrl+Alt+1 .. Ctrl+Alt+4)
+    pub const SLOTS: usize = 4;
+}
+
+/// Compositing multiple capture regions into one output
+pub mod composite {
+    /// Most regions that can be added besides the main capture region
+    pub const MAX_EXTRA_REGIONS: usize = 3;
+    /// Space between region frames on the output canvas (pixels)
+    pub const GAP: u32 = 8;
+    /// Canvas color around and between the region frames (ARGB)
+    pub const BACKGROUND: u32 = 0xFF000000;
+    /// Offset of a new region from the previous one (pixels)
+    pub const NEW_REGION_OFFSET: i32 = 40;
+    /// Picture in Picture: largest size of a small source (% of the main region)
+    pub const PIP_SCALE_PERCENT: u32 = 25;
+    /// Picture in Picture: space to the canvas edges and between small sources
+    pub const PIP_MARGIN: u32 = 16;
+    /// Vertical layout canvas aspect ratio (width, height)
+    pub const VERTICAL_ASPECT: (u32, u32) = (9, 16);
+    /// Vertical layout blurred fill: grid columns the frame is averaged into
+    pub const BLUR_COLUMNS: u32 = 24;
+    /// Vertical layout blurred fill: samples per grid cell and axis
+    pub const BLUR_SAMPLES: u32 = 4;
+    /// Vertical layout blurred fill brightness (% of the frame)
+    pub const BLUR_BRIGHTNESS_PERCENT: u32 = 60;
+}
+
+/// Layout editor (custom composite layout on the overlay)
+pub mod layout_editor {
+    /// Border color shown around the overlay while the editor is open
+    pub const BORDER: u32 = 0xFF34C759;
+    /// Source outline
+    pub const OUTLINE: u32 = 0xFF34C759;
+    /// Outline and handles of the selected source
+    pub const SELECTED: u32 = 0xFFFFCC00;
+    /// Source fill (premultiplied, ~20% green)
+    pub const FILL: u32 = 0x330A2812;
+    /// Snap guide lines
+    pub const GUIDE: u32 = 0xFFFF2D55;
+    /// Side of the square resize / crop handles (pixels at 100 % scaling)
+    pub const HANDLE_SIZE: i32 = 10;
+    /// Edges closer than this snap together (overlay pixels)
+    pub const SNAP_DISTANCE: i32 = 8;
+    /// Smallest source on the canvas (pixels)
+    pub const MIN_SIZE: u32 = 32;
+    /// Smallest share of a frame that a crop leaves visible
+    pub const MIN_VISIBLE: f32 = 0.1;
+    /// Smallest scale the canvas is shown at on the overlay
+    pub const MIN_VIEW_SCALE: f32 = 0.05;
+    /// Font height of the labels (pixels at 100 % scaling)
+    pub const LABEL_SIZE: i32 = 13;
+    /// Arrow key step with Shift held (pixels; 1 without)
+    pub const NUDGE_STEP: u32 = 10;
+}
+
+/// Output transitions (scene switch, freeze, pause)
+pub mod transition {
+    /// Default color the fade to color transition passes through ("#RRGGBB")
+    pub const DEFAULT_FADE_COLOR: &str = "#000000";
+}
+
+/// Synthetic test pattern source
+pub mod test_pattern {
+    /// Frames generated per second
+    pub const FPS: u32 = 60;
+    /// SMPTE 75% bars: gray, yellow, cyan, green, magenta, red, blue (ARGB)
+    pub const BARS: [u32; 7] = [
+        0xFFBFBFBF, 0xFFBFBF00, 0xFF00BFBF, 0xFF00BF00, 0xFFBF00BF, 0xFFBF0000, 0xFF0000BF,
+    ];
+    /// Castellation row below the bars: blue, black, magenta, black, cyan, black, gray
+    pub const CASTELLATIONS: [u32; 7] = [
+        0xFF0000BF, 0xFF131313, 0xFFBF00BF, 0xFF131313, 0xFF00BFBF, 0xFF131313, 0xFFBFBFBF,
+    ];
+    /// Bottom row: -I, white, +Q, black
+    pub const BOTTOM_ROW: [u32; 4] = [0xFF00214C, 0xFFFFFFFF, 0xFF32006A, 0xFF131313];
+    /// PLUGE bars: below black, black, above black
+    pub const PLUGE: [u32; 3] = [0xFF090909, 0xFF131313, 0xFF1D1D1D];
+    /// Checkerboard square size (pixels)
+    pub const SQUARE_SIZE: i32 = 64;
+    /// Checkerboard movement per frame (pixels)
+    pub const CHECKER_SPEED: i32 = 4;
+    pub const CHECKER_DARK: u32 = 0xFF202020;
+    pub const CHECKER_LIGHT: u32 = 0xFFE0E0E0;
+    /// Frame counter text
+    pub const TEXT: u32 = 0xFFFFFFFF;
+    pub const TEXT_BACKGROUND: u32 = 0xFF000000;
+    pub const TEXT_SCALE: i32 = 3;
+    pub const TEXT_PADDING: i32 = 8;
+    /// Distance of the frame counter from the frame edge
+    pub const TEXT_MARGIN: i32 = 16;
+}
+
+/// Image and slideshow source
+pub mod image_source {
+    /// File extensions picked up from a slideshow folder (lowercase)
+    pub const EXTENSIONS: [&str; 3] = ["png", "jpg", "jpeg"];
+    /// Larger images are downscaled to fit (width, height)
+    pub const MAX_SIZE: (u32, u32) = (3840, 2160);
+    /// Letterbox color around images with another aspect ratio
+    pub const BACKGROUND: u32 = 0xFF000000;
+}
+
+/// Webcam source
+pub mod webcam {
+    /// How long closing the webcam waits for the capture thread to stop
+    pub const STOP_TIMEOUT_MS: u64 = 1000;
+}
+
+/// Touch gestures on the overlay and border (see touch.rs)
+pub mod touch {
+    /// A finger this close to an edge (pixels) drags the edge instead of moving
+    pub const EDGE_REACH: i32 = 32;
+}
+
+/// Gamepad control (see gamepad.rs)
+pub mod gamepad {
+    /// How often the controller is polled while waiting in selection mode
+    pub const POLL_INTERVAL_MS: u64 = 50;
+    /// How often empty XInput slots are scanned for a new controller
+    pub const RESCAN_INTERVAL_MS: u64 = 3000;
+}
+
+/// Capture markers (see markers.rs)
+pub mod markers {
+    /// Marker file name (in the settings folder)
+    pub const FILE_NAME: &str = "markers.txt";
+}
+
+/// Output screenshots
+pub mod screenshot {
+    /// Folder in the user's Pictures folder
+    pub const DIR_NAME: &str = "RustFrame";
+    /// Screenshots listed in tray → Recent Screenshots
+    pub const RECENT_COUNT: usize = 5;
+}
+
+/// OSC control (see osc.rs)
+pub mod osc {
+    /// Default UDP port
+    pub const DEFAULT_PORT: u16 = 39218;
+    /// Default address the listener binds to (this computer only)
+    pub const DEFAULT_BIND: &str = "127.0.0.1";
+    /// Addresses RustFrame reacts to start with this
+    pub const ADDRESS_PREFIX: &str = "/rustframe";
+    /// Largest packet read (bytes)
+    pub const MAX_PACKET: usize = 4096;
+    /// How long a read waits before the thread checks whether to stop
+    pub const RECEIVE_TIMEOUT_MS: u64 = 200;
+    /// How often OSC actions are polled while waiting in selection mode
+    pub const POLL_INTERVAL_MS: u64 = 50;
+}
+
+/// Control over stdin / stdout (see control_stdio.rs)
+pub mod control_stdio {
+    /// How often commands are polled while waiting in selection mode
+    pub const POLL_INTERVAL_MS: u64 = 50;
+}
+
+/// Scripts (see scripting.rs)
+pub mod scripting {
+    /// Folder next to settings.json that holds the *.rhai scripts
+    pub const DIR_NAME: &str = "scripts";
+    /// Operations a single script call may run before it is aborted,
+    /// so an endless loop cannot freeze RustFrame
+    pub const MAX_OPERATIONS: u64 = 1_000_000;
+    /// How often on_frame_stats is called while capturing
+    pub const FRAME_STATS_INTERVAL_MS: u64 = 1000;
+}
+
+/// Plugins (see plugin.rs)
+pub mod plugin {
+    /// Folder next to settings.json that holds the plugin DLLs
+    pub const DIR_NAME: &str = "plugins";
+    /// Function every plugin DLL exports (null-terminated for GetProcAddress)
+    pub const ENTRY_SYMBOL: &[u8] = b"rustframe_plugin_entry\0";
+}
+
+/// Outgoing HTTP requests (see http.rs)
+pub mod http {
+    /// User-Agent header of every request
+    pub const USER_AGENT: &str = "RustFrame";
+    /// Request bodies are sent in chunks of this size (progress steps)
+    pub const CHUNK_SIZE: usize = 64 * 1024;
+    /// Longer responses are cut off (bytes)
+    pub const MAX_RESPONSE: usize = 1024 * 1024;
+}
+
+/// Stored secrets (see credentials.rs)
+pub mod credentials {
+    /// Credential Manager target names start with this
+    pub const TARGET_PREFIX: &str = "RustFrame/";
+    /// User name shown in the Credential Manager
+    pub const USER_NAME: &str = "RustFrame";
+}
+
+/// Uploads (see upload.rs)
+pub mod upload {
+    /// Connect/send/receive timeout of each upload request
+    pub const TIMEOUT_MS: u64 = 120_000;
+    /// How long "Upload done" / "Upload failed" stays in the tray status
+    pub const RESULT_SHOWN_MS: u64 = 5000;
+    /// Region used when the S3 settings leave it empty
+    pub const S3_DEFAULT_REGION: &str = "us-east-1";
+    pub const GOOGLE_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+    pub const GOOGLE_UPLOAD_URL: &str =
+        "https://www.googleapis.com/upload/drive/v3/files?uploadType=multipart&fields=id,webViewLink";
+    pub const GOOGLE_FILES_URL: &str = "https://www.googleapis.com/drive/v3/files";
+    pub const IMGUR_UPLOAD_URL: &str = "https://api.imgur.com/3/image";
+    /// Separates the parts of multipart bodies
+    pub const MULTIPART_BOUNDARY: &str = "RustFrameUploadBoundary7MA4YWxkTrZu0gW";
+}
+
+/// Input recording and replay (see input_recording.rs)
+pub mod input_recording {
+    /// Format version written to the file
+    pub const FILE_VERSION: u32 = 1;
+    /// Added to the session's screenshot-style name
+    pub const FILE_SUFFIX: &str = " input.json";
+    /// How far back frame times are kept to number late-reported events
+    pub const FRAME_HISTORY_MS: u64 = 10_000;
+    /// How often a replay checks whether it was stopped while waiting
+    pub const REPLAY_POLL_MS: u64 = 10;
+}
+
+/// Webhook notifications (see webhook.rs)
+pub mod webhook {
+    /// Connect/send/receive timeout for a single request
+    pub const TIMEOUT_MS: u64 = 5000;
+    /// Stream drops and errors are sent at most this often
+    pub const COOLDOWN_MS: u64 = 60_000;
+    /// Body sent when a webhook has no template
+    pub const DEFAULT_TEMPLATE: &str = r#"{"app":"RustFrame","event":"{{event}}","message":"{{message}}","timestamp":{{timestamp}}}"#;
+}
+
+/// Shutting down with Windows (see session_end.rs)
+pub mod session_end {
+    /// How long the end of the session waits for the shutdown (milliseconds)
+    pub const SHUTDOWN_WAIT_MS: u64 = 4000;
+    /// How long the shutdown waits for webhooks still being sent (milliseconds)
+    pub const WEBHOOK_DRAIN_MS: u64 = 2000;
+}
+
+/// Windows accessibility settings (see accessibility.rs)
+pub mod accessibility {
+    /// How long the contrast and animation settings are cached (milliseconds)
+    pub const REFRESH_MS: u64 = 1000;
+}
+
+/// Commands from a second instance (see instance.rs)
+pub mod instance {
+    /// Pipe buffer size (bytes)
+    pub const BUFFER_SIZE: u32 = 4096;
+    /// Longer messages are dropped (bytes)
+    pub const MAX_MESSAGE: usize = 64 * 1024;
+    /// How long a client may take to write its commands and close the pipe
+    pub const READ_TIMEOUT_MS: u64 = 2000;
+    /// How often the pipe thread checks whether the app is shutting down
+    pub const STOP_POLL_MS: u32 = 100;
+    /// Connection attempts while the running instance serves another client
+    pub const CONNECT_ATTEMPTS: u32 = 10;
+    /// Pause between connection attempts
+    pub const CONNECT_RETRY_MS: u64 = 100;
+    /// How often commands are checked for while waiting in selection mode
+    pub const POLL_INTERVAL_MS: u64 = 200;
+}
+
+/// Capture targets picked from the desktop menu (see shell_menu.rs)
+pub mod shell_menu {
+    /// The window picker gives up if nothing was clicked within this time
+    pub const PICK_TIMEOUT_MS: u64 = 15000;
+    /// How often the window picker checks for the click
+    pub const PICK_POLL_MS: u64 = 50;
+}
+
+/// Re-acquiring a picked window after it restarts
+pub mod window_tracker {
+    /// How often the tracked window is checked (and searched for once it is gone)
+    pub const CHECK_INTERVAL_MS: u64 = 1000;
+}
+
+/// MIDI controller input
+pub mod midi {
+    /// How often the controller is polled while waiting in selection mode
+    pub const POLL_INTERVAL_MS: u64 = 20;
+    /// How often Settings → MIDI checks for a message in learn mode
+    pub const LEARN_POLL_MS: u32 = 30;
+}
+
+/// Low-level mouse hook (see mouse_hook.rs)
+pub mod mouse_hook {
+    /// Moving further than this (pixels) while pressed turns a click into a drag
+    pub const DRAG_THRESHOLD: i32 = 4;
+    /// Moves closer than this to the last recorded point are skipped
+    pub const MIN_POINT_DISTANCE: i32 = 2;
+    /// Longest recorded path of one gesture
+    pub const MAX_POINTS: usize = 10_000;
+}
+
+/// Click and drag highlights (see click_highlight.rs)
+pub mod click_highlight {
+    /// How long a click ring / drag path stays visible
+    pub const FADE_MS: u64 = 600;
+    /// Ring radius right after the click
+    pub const RING_START_RADIUS: i32 = 10;
+    /// Ring radius when it disappears
+    pub const RING_END_RADIUS: i32 = 36;
+    /// Ring and path line width
+    pub const LINE_WIDTH: u32 = 3;
+    /// Length of the arrow at the end of a drag path
+    pub const ARROW_LENGTH: i32 = 40;
+    /// Inner ring of a double click (percent of the outer ring)
+    pub const DOUBLE_CLICK_INNER_PERCENT: i32 = 55;
+    /// Dark edge around rings of clicks with modifier keys
+    pub const OUTLINE_WIDTH: u32 = 2;
+    pub const OUTLINE_COLOR: u32 = 0xFF101010;
+    /// Text scale of the "Ctrl+click" labels
+    pub const LABEL_SCALE: i32 = 2;
+    /// Left button (blue)
+    pub const LEFT_COLOR: u32 = 0xFF00A8FF;
+    /// Right button (orange)
+    pub const RIGHT_COLOR: u32 = 0xFFFF8C00;
+    /// Middle button (green)
+    pub const MIDDLE_COLOR: u32 = 0xFF00DD66;
+}
+
+/// Stream Deck / remote control server (see stream_deck.rs)
+pub mod stream_deck {
+    /// Default WebSocket port (ws://127.0.0.1:39217)
+    pub const DEFAULT_PORT: u16 = 39217;
+    /// Random bytes of a generated remote token (hex encoded, 64 digits)
+    pub const TOKEN_BYTES: usize = 32;
+    /// How often remote actions are polled while waiting in selection mode
+    pub const POLL_INTERVAL_MS: u64 = 50;
+    /// How often the server checks for new connections
+    pub const ACCEPT_POLL_MS: u64 = 100;
+    /// How long a connection waits for a message before sending state changes
+    pub const CLIENT_POLL_MS: u64 = 100;
+    /// How long a new connection may take to send its request head
+    pub const HTTP_HEAD_TIMEOUT_MS: u64 = 2000;
+    /// Larger request heads are rejected (bytes)
+    pub const HTTP_HEAD_MAX: usize = 8192;
+    /// Connections served at the same time - more are closed right away
+    pub const MAX_CLIENTS: usize = 16;
+    /// How long a WebSocket client may take to identify with the token
+    pub const IDENTIFY_TIMEOUT_MS: u64 = 5000;
+    /// How long sending to a client may block before it is dropped
+    pub const WRITE_TIMEOUT_MS: u64 = 2000;
+}
+
+/// Control toolbar during a capture (see toolbar.rs), sizes at 100 % scaling
+pub mod toolbar {
+    /// Height of the bar and size of its buttons
+    pub const HEIGHT: i32 = 36;
+    pub const BUTTON_SIZE: i32 = 28;
+    /// Space around and between the buttons
+    pub const PADDING: i32 = 4;
+    /// Drag handle at the left end
+    pub const GRIP_WIDTH: i32 = 10;
+    /// Recording dot and elapsed time at the right end
+    pub const TIMER_WIDTH: i32 = 74;
+    pub const TEXT_SIZE: i32 = 14;
+    /// Distance from the capture border (and from the monitor edge)
+    pub const GAP: i32 = 6;
+    /// Stop icon and recording dot
+    pub const RECORDING_COLOR: u32 = 0xFFFF3B30;
 }
@@ -0,0 +1,263 @@
+// control_stdio.rs - Control over stdin / stdout
+//
+// Started with --control-stdio, RustFrame reads commands from stdin and
+// writes events to stdout, one JSON object per line, so another program can
+// run it as a child process (an Electron app, a test harness, ...):
+//
+//   → {"id": 1, "command": "set-region", "x": 100, "y": 100, "width": 1280, "height": 720}
+//   ← {"event": "ok", "id": 1, "command": "set-region"}
+//   → {"id": 2, "command": "start"}
+//   ← {"event": "ok", "id": 2, "command": "start"}
+//   ← {"event": "state", "capturing": true, ...}
+//
+// Commands: start, stop, set-region, screenshot (optional "out"), action
+// (any remote action id, e.g. "scene_2"), status and quit. "id" is optional
+// and copied into the answer. The protocol is documented in
+// docs/control-stdio.md.
+//
+// Events: ready (once at startup), ok / error (the answer to every command),
+// state (whenever the remote state changes, see stream_deck.rs) and
+// screenshot (the path of every screenshot saved). The log stays on stderr.
+//
+// When stdin is closed - the parent program exited - RustFrame exits too.
+// stdin is read on its own thread; commands reach the app through a channel
+// polled from about_to_wait (like the hotkeys).
+
+use serde::Serialize;
+use serde_json::Value;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, Sender};
+
+use crate::capture::CaptureRect;
+use crate::stream_deck::{RemoteAction, RemoteState};
+
+/// What a command asks for
+#[derive(Debug, Clone)]
+pub enum StdioCommand {
+    /// Start capture (with the countdown)
+    Start,
+    /// Stop capture (or cancel the countdown)
+    Stop,
+    /// Move the capture region (virtual screen coordinates)
+    SetRegion(CaptureRect),
+    /// Save a screenshot (`out`: the file to write instead of Pictures\RustFrame)
+    Screenshot {
+        out: Option<PathBuf>,
+    },
+    Action(RemoteAction),
+    /// Send the state now
+    Status,
+    Quit,
+}
+
+impl StdioCommand {
+    /// Name used in the protocol
+    pub fn name(&self) -> &'static str {
+        match self {
+            StdioCommand::Start => "start",
+            StdioCommand::Stop => "stop",
+            StdioCommand::SetRegion(_) => "set-region",
+            StdioCommand::Screenshot { .. } => "screenshot",
+            StdioCommand::Action(_) => "action",
+            StdioCommand::Status => "status",
+            StdioCommand::Quit => "quit",
+        }
+    }
+}
+
+/// A command and the id to answer with
+#[derive(Debug, Clone)]
+pub struct StdioRequest {
+    pub id: Option<Value>,
+    pub command: StdioCommand,
+}
+
+/// What arrived on stdin
+#[derive(Debug, Clone)]
+pub enum StdioInput {
+    Request(StdioRequest),
+    /// stdin was closed
+    Closed,
+}
+
+/// Messages written to stdout
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Event<'a> {
+    Ready {
+        app: &'static str,
+        version: &'static str,
+    },
+    Ok {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<&'a Value>,
+        command: &'a str,
+    },
+    Error {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<&'a Value>,
+        error: &'a str,
+    },
+    State(&'a RemoteState),
+    Screenshot {
+        path: &'a str,
+    },
+}
+
+/// The stdin reader and the stdout writer
+pub struct StdioControl {
+    receiver: Receiver<StdioInput>,
+    /// Last state sent (without the frame count, which changes every frame)
+    last_state: Option<RemoteState>,
+}
+
+impl StdioControl {
+    /// Start reading stdin and announce that commands are accepted
+    pub fn start() -> Self {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || read_loop(sender));
+        log::info!("Control over stdin / stdout enabled");
+        write_event(&Event::Ready {
+            app: "RustFrame",
+            version: env!("CARGO_PKG_VERSION"),
+        });
+        Self {
+            receiver,
+            last_state: None,
+        }
+    }
+
+    /// Next command (or the end of stdin), if any
+    pub fn poll(&self) -> Option<StdioInput> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Answer a command that was carried out
+    pub fn reply_ok(&self, request: &StdioRequest) {
+        write_event(&Event::Ok {
+            id: request.id.as_ref(),
+            command: request.command.name(),
+        });
+    }
+
+    /// Answer a command that could not be carried out
+    pub fn reply_error(&self, request: &StdioRequest, error: &str) {
+        write_event(&Event::Error {
+            id: request.id.as_ref(),
+            error,
+        });
+    }
+
+    /// Send the state if it changed since the last call (or always with `force`)
+    pub fn publish(&mut self, state: &RemoteState, force: bool) {
+        let compared = RemoteState {
+            frames: 0,
+            ..state.clone()
+        };
+        if force || self.last_state.as_ref() != Some(&compared) {
+            write_event(&Event::State(state));
+            self.last_state = Some(compared);
+        }
+    }
+
+    /// Report a saved screenshot
+    pub fn screenshot_saved(&self, path: &std::path::Path) {
+        write_event(&Event::Screenshot {
+            path: &path.to_string_lossy(),
+        });
+    }
+}
+
+/// Read commands until stdin is closed; invalid lines are answered here
+fn read_loop(sender: Sender<StdioInput>) {
+    let stdin = std::io::stdin();
+    for line in stdin.lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse(&line) {
+            Ok(request) => {
+                if sender.send(StdioInput::Request(request)).is_err() {
+                    return;
+                }
+            }
+            Err((id, error)) => write_event(&Event::Error {
+                id: id.as_ref(),
+                error: &error,
+            }),
+        }
+    }
+    log::info!("stdin closed");
+    let _ = sender.send(StdioInput::Closed);
+}
+
+/// Parse one line; the error carries the id (if there was one) and a message
+fn parse(line: &str) -> Result<StdioRequest, (Option<Value>, String)> {
+    let value: Value =
+        serde_json::from_str(line).map_err(|e| (None, format!("Invalid JSON: {}", e)))?;
+    let id = value.get("id").cloned();
+    let fail = |error: String| (id.clone(), error);
+    let name = value
+        .get("command")
+        .and_then(Value::as_str)
+        .ok_or_else(|| fail("Missing \"command\"".to_string()))?;
+
+    let command = match name {
+        "start" => StdioCommand::Start,
+        "stop" => StdioCommand::Stop,
+        "set-region" | "set_region" => {
+            let number = |key: &str| {
+                value
+                    .get(key)
+                    .and_then(Value::as_i64)
+                    .ok_or_else(|| fail(format!("Missing or invalid \"{}\"", key)))
+            };
+            let (x, y, width, height) = (
+                number("x")?,
+                number("y")?,
+                number("width")?,
+                number("height")?,
+            );
+            if width <= 0 || height <= 0 {
+                return Err(fail("The region is empty".to_string()));
+            }
+            StdioCommand::SetRegion(CaptureRect {
+                x: x as i32,
+                y: y as i32,
+                width: width as u32,
+                height: height as u32,
+            })
+        }
+        "screenshot" => StdioCommand::Screenshot {
+            out: value.get("out").and_then(Value::as_str).map(PathBuf::from),
+        },
+        "action" => {
+            let action = value
+                .get("action")
+                .and_then(Value::as_str)
+                .ok_or_else(|| fail("Missing \"action\"".to_string()))?;
+            StdioCommand::Action(
+                RemoteAction::from_id(action)
+                    .ok_or_else(|| fail(format!("Unknown action '{}'", action)))?,
+            )
+        }
+        "status" => StdioCommand::Status,
+        "quit" | "exit" => StdioCommand::Quit,
+        other => return Err(fail(format!("Unknown command '{}'", other))),
+    };
+    Ok(StdioRequest { id, command })
+}
+
+/// Write one event line (stdout errors are ignored - the parent may be gone)
+fn write_event(event: &Event) {
+    let Ok(line) = serde_json::to_string(event) else {
+        return;
+    };
+    let mut stdout = std::io::stdout().lock();
+    let _ = writeln!(stdout, "{}", line);
+    let _ = stdout.flush();
+}
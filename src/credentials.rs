@@ -0,0 +1,83 @@
+// credentials.rs - Stored Secrets
+//
+// Upload keys and tokens are kept in the Windows Credential Manager (Control
+// Panel → Credential Manager → Windows Credentials, "RustFrame/..."), not in
+// settings.json. Secrets entered in settings.json are moved there on the
+// next start (see upload::store_secrets).
+
+use anyhow::Result;
+
+/// Credential Manager target name for `name`
+#[cfg(windows)]
+fn target(name: &str) -> Vec<u16> {
+    use crate::constants::credentials::TARGET_PREFIX;
+
+    crate::utils::wide_string(&format!("{}{}", TARGET_PREFIX, name))
+}
+
+/// Read a stored secret (None if it was never stored)
+#[cfg(windows)]
+pub fn get(name: &str) -> Option<String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Security::Credentials::{
+        CredFree, CredReadW, CREDENTIALW, CRED_TYPE_GENERIC,
+    };
+
+    let target = target(name);
+    unsafe {
+        let mut credential: *mut CREDENTIALW = std::ptr::null_mut();
+        CredReadW(
+            PCWSTR(target.as_ptr()),
+            CRED_TYPE_GENERIC,
+            None,
+            &mut credential,
+        )
+        .ok()?;
+        let blob = std::slice::from_raw_parts(
+            (*credential).CredentialBlob,
+            (*credential).CredentialBlobSize as usize,
+        );
+        let value = String::from_utf8_lossy(blob).into_owned();
+        CredFree(credential as *const std::ffi::c_void);
+        Some(value)
+    }
+}
+
+/// Store a secret, replacing an older value
+#[cfg(windows)]
+pub fn set(name: &str, value: &str) -> Result<()> {
+    use windows::core::PWSTR;
+    use windows::Win32::Security::Credentials::{
+        CredWriteW, CREDENTIALW, CRED_PERSIST_LOCAL_MACHINE, CRED_TYPE_GENERIC,
+    };
+
+    use crate::constants::credentials::USER_NAME;
+
+    let mut target = target(name);
+    let mut user = crate::utils::wide_string(USER_NAME);
+    let mut blob = value.as_bytes().to_vec();
+    let credential = CREDENTIALW {
+        Type: CRED_TYPE_GENERIC,
+        TargetName: PWSTR(target.as_mut_ptr()),
+        UserName: PWSTR(user.as_mut_ptr()),
+        CredentialBlobSize: blob.len() as u32,
+        CredentialBlob: blob.as_mut_ptr(),
+        Persist: CRED_PERSIST_LOCAL_MACHINE,
+        ..Default::default()
+    };
+    unsafe { CredWriteW(&credential, 0) }
+        .map_err(|e| anyhow::anyhow!("Failed to store the {} credential: {}", name, e))
+}
+
+#[cfg(not(windows))]
+pub fn get(_name: &str) -> Option<String> {
+    None
+}
+
+#[cfg(not(windows))]
+pub fn set(name: &str, _value: &str) -> Result<()> {
+    Err(anyhow::anyhow!(
+        "Storing the {} credential is only supported on Windows",
+        name
+    ))
+}
@@ -0,0 +1,404 @@
+// deep_link.rs - rustframe:// Links
+//
+// RustFrame registers the rustframe:// URL scheme for the current user, so a
+// link in a script, a document or the Run dialog controls it:
+//
+//   rustframe://record?region=scene1&duration=60   start capturing scene 1, stop after 60 s
+//   rustframe://stop                               stop capture
+//   rustframe://toggle                             start / stop capture
+//   rustframe://pause                              show / hide the pause screen
+//   rustframe://scene?slot=2                       switch to scene 2
+//   rustframe://marker                             drop a marker
+//   rustframe://screenshot                         save a screenshot
+//   rustframe://screenshot?out=C%3A%5Cdemo.png     save a screenshot to a file
+//                                                  (rustframe-ctl only, see LinkSource)
+//   rustframe://show                               bring RustFrame back from the tray
+//   rustframe://window                             capture the next window clicked
+//   rustframe://monitor                            capture the monitor under the mouse
+//   rustframe://frame?x=0&y=0&width=1280&height=720&start=1
+//                                                  put the frame there (and start capturing)
+//
+// Windows starts RustFrame.exe with the link as its argument. If RustFrame is
+// already running, the new process hands the link to it (see instance.rs) and
+// exits; otherwise it starts normally and runs the link once it is up.
+//
+// Any web page or document can launch a link, so launched links are trusted
+// less than the ones rustframe-ctl writes to the instance pipe: the new
+// process marks them with LAUNCH_PREFIX, and a launched link that would start
+// a capture asks the user first (see LinkSource).
+//
+// The scheme is registered under HKCU\Software\Classes\rustframe at every
+// start (no administrator rights needed), so it follows the executable if it
+// was moved.
+
+use anyhow::Result;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::constants::scene;
+
+/// URL scheme (without "://")
+pub const SCHEME: &str = "rustframe";
+
+/// Marks a launched link handed to the running instance
+pub const LAUNCH_PREFIX: &str = "launch:";
+
+/// Where a link came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkSource {
+    /// RustFrame.exe was started with the link (a browser, a document, the
+    /// Run dialog)
+    Launch,
+    /// Written to the instance pipe by rustframe-ctl or another program of
+    /// the same user
+    Local,
+}
+
+/// Source and link of a command received on the instance pipe
+pub fn split_source(command: &str) -> (LinkSource, &str) {
+    match command.strip_prefix(LAUNCH_PREFIX) {
+        Some(link) => (LinkSource::Launch, link),
+        None => (LinkSource::Local, command),
+    }
+}
+
+/// What a link asks for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLink {
+    /// Start capture (with the countdown), optionally in a scene and for a limited time
+    Start {
+        scene: Option<usize>,
+        duration: Option<Duration>,
+    },
+    Stop,
+    /// Start capture (with the countdown), cancel the countdown, or stop capture
+    Toggle,
+    TogglePause,
+    SwitchScene(usize),
+    DropMarker,
+    /// Save a screenshot (`out`: the file to write instead of Pictures\RustFrame,
+    /// ignored for launched links)
+    Screenshot {
+        out: Option<PathBuf>,
+    },
+    /// Bring RustFrame back from the tray
+    Show,
+    /// Capture the next window clicked (desktop menu, see shell_menu.rs)
+    PickWindow,
+    /// Capture the monitor under the mouse
+    CaptureMonitor,
+    /// Put the frame around a screen region, optionally starting the capture
+    /// (restart as administrator, see elevation.rs)
+    Frame {
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+        start: bool,
+    },
+}
+
+impl DeepLink {
+    /// Parse a rustframe:// link
+    pub fn parse(url: &str) -> Result<Self, String> {
+        let rest = url
+            .trim()
+            .split_once("://")
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case(SCHEME))
+            .map(|(_, rest)| rest)
+            .ok_or_else(|| format!("Not a {}:// link: {}", SCHEME, url))?;
+        let (action, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let action = action.trim_end_matches('/').to_ascii_lowercase();
+        let params: Vec<(String, String)> = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (decode(key).to_ascii_lowercase(), decode(value))
+            })
+            .collect();
+        let param = |names: &[&str]| {
+            params
+                .iter()
+                .find(|(key, _)| names.contains(&key.as_str()))
+                .map(|(_, value)| value.as_str())
+        };
+
+        match action.as_str() {
+            "record" | "capture" | "start" => {
+                let scene = param(&["region", "scene", "preset"])
+                    .map(parse_slot)
+                    .transpose()?;
+                let duration = param(&["duration"])
+                    .map(|value| {
+                        value
+                            .trim()
+                            .parse::<u64>()
+                            .ok()
+                            .filter(|&secs| secs > 0)
+                            .map(Duration::from_secs)
+                            .ok_or_else(|| format!("Invalid duration (seconds): {}", value))
+                    })
+                    .transpose()?;
+                Ok(DeepLink::Start { scene, duration })
+            }
+            "stop" => Ok(DeepLink::Stop),
+            "toggle" => Ok(DeepLink::Toggle),
+            "pause" => Ok(DeepLink::TogglePause),
+            "scene" => {
+                let slot = param(&["slot", "region", "scene", "preset"])
+                    .ok_or_else(|| "Missing scene (scene?slot=1)".to_string())?;
+                Ok(DeepLink::SwitchScene(parse_slot(slot)?))
+            }
+            "marker" => Ok(DeepLink::DropMarker),
+            "screenshot" => Ok(DeepLink::Screenshot {
+                out: param(&["out"]).map(PathBuf::from),
+            }),
+            "show" | "open" | "" => Ok(DeepLink::Show),
+            "window" => Ok(DeepLink::PickWindow),
+            "monitor" => Ok(DeepLink::CaptureMonitor),
+            "frame" => {
+                let number = |name: &str| {
+                    let value = param(&[name]).ok_or_else(|| {
+                        format!("Missing {} (frame?x=0&y=0&width=1280&height=720)", name)
+                    })?;
+                    value
+                        .trim()
+                        .parse::<i64>()
+                        .map_err(|_| format!("Invalid {}: {}", name, value))
+                };
+                let size = |name: &str| {
+                    number(name)?
+                        .try_into()
+                        .ok()
+                        .filter(|&n: &u32| n > 0)
+                        .ok_or_else(|| format!("Invalid {} (pixels above 0)", name))
+                };
+                let position = |name: &str| {
+                    number(name)?
+                        .try_into()
+                        .map_err(|_| format!("Invalid {}", name))
+                };
+                Ok(DeepLink::Frame {
+                    x: position("x")?,
+                    y: position("y")?,
+                    width: size("width")?,
+                    height: size("height")?,
+                    start: matches!(param(&["start"]), Some("1" | "true" | "yes")),
+                })
+            }
+            _ => Err(format!("Unknown action in {}", url)),
+        }
+    }
+
+    /// Whether the link starts a capture (Toggle: if none is running)
+    pub fn starts_capture(&self) -> bool {
+        matches!(
+            self,
+            DeepLink::Start { .. }
+                | DeepLink::Toggle
+                | DeepLink::CaptureMonitor
+                | DeepLink::Frame { start: true, .. }
+        )
+    }
+}
+
+/// Scene slot from "2", "scene2" or "preset2" (the slot starts at 1)
+fn parse_slot(value: &str) -> Result<usize, String> {
+    let lower = value.trim().to_ascii_lowercase();
+    let number = lower
+        .trim_start_matches("scene")
+        .trim_start_matches("preset")
+        .trim();
+    number
+        .parse::<usize>()
+        .ok()
+        .filter(|&n| (1..=scene::SLOTS).contains(&n))
+        .map(|n| n - 1)
+        .ok_or_else(|| format!("Invalid scene (1-{}): {}", scene::SLOTS, value))
+}
+
+/// Undo %XX escapes and "+" for spaces
+fn decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+                match u8::from_str_radix(hex, 16) {
+                    Ok(byte) => {
+                        decoded.push(byte);
+                        i += 3;
+                        continue;
+                    }
+                    Err(_) => decoded.push(b'%'),
+                }
+            }
+            b'+' => decoded.push(b' '),
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// The rustframe:// link among the command line arguments, if any
+/// (never one with line breaks, which would add commands on the pipe)
+pub fn from_args(args: &[String]) -> Option<String> {
+    let prefix = format!("{}://", SCHEME);
+    args.iter()
+        .skip(1)
+        .find(|arg| {
+            arg.get(..prefix.len())
+                .is_some_and(|p| p.eq_ignore_ascii_case(&prefix))
+                && !arg.chars().any(char::is_control)
+        })
+        .cloned()
+}
+
+/// Register the URL scheme for the current user
+#[cfg(windows)]
+pub fn register_url_scheme() -> Result<()> {
+    use crate::utils::set_user_registry_string;
+    use anyhow::Context;
+
+    let exe = std::env::current_exe().context("Failed to locate RustFrame.exe")?;
+    let key = format!(r"Software\Classes\{}", SCHEME);
+    set_user_registry_string(&key, None, "URL:RustFrame")?;
+    set_user_registry_string(&key, Some("URL Protocol"), "")?;
+    set_user_registry_string(
+        &format!(r"{}\DefaultIcon", key),
+        None,
+        &format!("\"{}\",0", exe.display()),
+    )?;
+    set_user_registry_string(
+        &format!(r"{}\shell\open\command", key),
+        None,
+        &format!("\"{}\" \"%1\"", exe.display()),
+    )?;
+    log::info!("Registered the {}:// URL scheme", SCHEME);
+    Ok(())
+}
+
+#[cfg(not(windows))]
+pub fn register_url_scheme() -> Result<()> {
+    Err(anyhow::anyhow!(
+        "{}:// links are only supported on Windows",
+        SCHEME
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_record_with_scene_and_duration() {
+        assert_eq!(
+            DeepLink::parse("rustframe://record?region=scene2&duration=60"),
+            Ok(DeepLink::Start {
+                scene: Some(1),
+                duration: Some(Duration::from_secs(60)),
+            })
+        );
+        assert_eq!(
+            DeepLink::parse("rustframe://start"),
+            Ok(DeepLink::Start {
+                scene: None,
+                duration: None,
+            })
+        );
+        assert!(DeepLink::parse("rustframe://record?duration=0").is_err());
+    }
+
+    #[test]
+    fn scheme_and_action_ignore_case_and_trailing_slash() {
+        assert_eq!(DeepLink::parse("RustFrame://Stop/"), Ok(DeepLink::Stop));
+        assert_eq!(DeepLink::parse(" rustframe:// "), Ok(DeepLink::Show));
+    }
+
+    #[test]
+    fn scene_slot_must_exist() {
+        assert_eq!(
+            DeepLink::parse("rustframe://scene?slot=2"),
+            Ok(DeepLink::SwitchScene(1))
+        );
+        let past_last = format!("rustframe://scene?slot={}", scene::SLOTS + 1);
+        assert!(DeepLink::parse(&past_last).is_err());
+        assert!(DeepLink::parse("rustframe://scene?slot=0").is_err());
+        assert!(DeepLink::parse("rustframe://scene").is_err());
+    }
+
+    #[test]
+    fn screenshot_path_is_decoded() {
+        assert_eq!(
+            DeepLink::parse("rustframe://screenshot?out=C%3A%5Cdemo+1.png"),
+            Ok(DeepLink::Screenshot {
+                out: Some(PathBuf::from("C:\\demo 1.png")),
+            })
+        );
+    }
+
+    #[test]
+    fn frame_needs_a_position_and_a_size() {
+        assert_eq!(
+            DeepLink::parse("rustframe://frame?x=-10&y=20&width=1280&height=720&start=1"),
+            Ok(DeepLink::Frame {
+                x: -10,
+                y: 20,
+                width: 1280,
+                height: 720,
+                start: true,
+            })
+        );
+        assert!(DeepLink::parse("rustframe://frame?x=0&y=0&width=0&height=720").is_err());
+        assert!(DeepLink::parse("rustframe://frame?x=0&y=0&width=1280").is_err());
+    }
+
+    #[test]
+    fn rejects_other_schemes_and_unknown_actions() {
+        assert!(DeepLink::parse("https://example.com/stop").is_err());
+        assert!(DeepLink::parse("rustframe-stop").is_err());
+        assert!(DeepLink::parse("rustframe://format").is_err());
+    }
+
+    #[test]
+    fn starts_capture_only_for_starting_links() {
+        assert!(DeepLink::Toggle.starts_capture());
+        assert!(DeepLink::parse("rustframe://record")
+            .unwrap()
+            .starts_capture());
+        assert!(
+            !DeepLink::parse("rustframe://frame?x=0&y=0&width=10&height=10")
+                .unwrap()
+                .starts_capture()
+        );
+        assert!(!DeepLink::Stop.starts_capture());
+    }
+
+    #[test]
+    fn launched_links_are_marked() {
+        let launched = format!("{}rustframe://stop", LAUNCH_PREFIX);
+        assert_eq!(
+            split_source(&launched),
+            (LinkSource::Launch, "rustframe://stop")
+        );
+        assert_eq!(
+            split_source("rustframe://stop"),
+            (LinkSource::Local, "rustframe://stop")
+        );
+    }
+
+    #[test]
+    fn from_args_skips_links_with_line_breaks() {
+        let args = |link: &str| vec!["RustFrame.exe".to_string(), link.to_string()];
+        assert_eq!(
+            from_args(&args("RUSTFRAME://show")),
+            Some("RUSTFRAME://show".to_string())
+        );
+        assert_eq!(from_args(&args("rustframe://show\nrustframe://stop")), None);
+        assert_eq!(from_args(&args("--restarted")), None);
+    }
+}
@@ -0,0 +1,481 @@
+// diagnose.rs - Diagnostic Report for Bug Reports
+//
+// `RustFrame diagnose` collects what is needed to understand a capture
+// problem on someone else's machine into one report to attach to an issue:
+//
+//   RustFrame diagnose --out report.zip
+//
+// - RustFrame version and mode (installed / portable)
+// - Windows edition, version and build
+// - GPUs: name, vendor / device id, video memory, driver version
+// - Whether Windows.Graphics.Capture is supported, and whether Desktop
+//   Duplication works on each output - RustFrame captures with WGC, but a
+//   failing duplication points at the driver rather than at WGC
+// - Monitors with their rectangles and DPI (see list.rs)
+// - settings.json and the last lines of rustframe.log (RustFrame writes a log
+//   file in portable mode only)
+//
+// Everything is redacted before it is written: the user name, profile folder
+// and computer name are replaced in every line, secrets in settings.json
+// (tokens, keys, passwords, bucket names) are left out and URLs keep only
+// their scheme and host. Without --out the text is printed; a .zip holds the
+// text, settings.json and the log as separate files.
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::io::Write;
+use std::path::Path;
+
+use crate::constants::{diagnose, settings_file as settings_constants};
+use crate::{list, settings_file};
+
+/// The collected (and redacted) report
+pub struct Report {
+    pub text: String,
+    /// settings.json (None when there is none)
+    pub settings: Option<String>,
+    /// The last lines of rustframe.log (None outside portable mode)
+    pub log: Option<String>,
+}
+
+/// A GPU as DXGI lists it
+struct Adapter {
+    name: String,
+    vendor_id: u32,
+    device_id: u32,
+    memory_mb: u64,
+    driver: Option<String>,
+    software: bool,
+    /// Output device names and whether Desktop Duplication works on them
+    outputs: Vec<(String, std::result::Result<(), String>)>,
+}
+
+/// Collect the report
+pub fn collect() -> Report {
+    let settings = settings_file::path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .map(|text| match serde_json::from_str::<Value>(&text) {
+            Ok(mut value) => {
+                redact_json(&mut value);
+                serde_json::to_string_pretty(&value).unwrap_or_default()
+            }
+            Err(e) => format!("settings.json does not parse: {}", e),
+        });
+    let log = settings_file::portable_dir()
+        .and_then(|dir| {
+            std::fs::read_to_string(dir.join(settings_constants::PORTABLE_LOG_NAME)).ok()
+        })
+        .map(|text| {
+            let lines: Vec<&str> = text.lines().collect();
+            let start = lines.len().saturating_sub(diagnose::LOG_LINES);
+            lines[start..].join("\n")
+        });
+
+    let mut text = String::new();
+    let mut line = |s: String| {
+        text.push_str(&s);
+        text.push('\n');
+    };
+
+    line("RustFrame diagnostic report".to_string());
+    line(format!("Created: {}", crate::screenshot::timestamp()));
+    line(String::new());
+
+    line("== RustFrame ==".to_string());
+    line(format!("Version: {}", env!("CARGO_PKG_VERSION")));
+    line(format!(
+        "Mode: {}",
+        if settings_file::portable_dir().is_some() {
+            "portable"
+        } else {
+            "installed"
+        }
+    ));
+    if let Ok(exe) = std::env::current_exe() {
+        line(format!("Executable: {}", exe.display()));
+    }
+    if let Some(dir) = settings_file::dir() {
+        line(format!("Settings folder: {}", dir.display()));
+    }
+    line(String::new());
+
+    line("== Windows ==".to_string());
+    line(windows_version());
+    line(String::new());
+
+    line("== Capture ==".to_string());
+    line(format!(
+        "Windows.Graphics.Capture: {}",
+        if wgc_supported() {
+            "supported"
+        } else {
+            "not supported"
+        }
+    ));
+    let adapters = adapters();
+    for (output, duplication) in adapters.iter().flat_map(|a| &a.outputs) {
+        line(format!(
+            "Desktop Duplication on {}: {}",
+            output,
+            match duplication {
+                Ok(()) => "works".to_string(),
+                Err(e) => format!("fails ({})", e),
+            }
+        ));
+    }
+    line(String::new());
+
+    line("== GPUs ==".to_string());
+    if adapters.is_empty() {
+        line("No adapters found".to_string());
+    }
+    for (i, adapter) in adapters.iter().enumerate() {
+        line(format!(
+            "{}. {}{} - vendor 0x{:04X}, device 0x{:04X}, {} MB, driver {}",
+            i + 1,
+            adapter.name,
+            if adapter.software { " (software)" } else { "" },
+            adapter.vendor_id,
+            adapter.device_id,
+            adapter.memory_mb,
+            adapter.driver.as_deref().unwrap_or("unknown")
+        ));
+    }
+    line(String::new());
+
+    line("== Monitors ==".to_string());
+    for monitor in list::monitors() {
+        line(format!(
+            "{}. {} at {},{} {}x{}, {} DPI ({} %){}",
+            monitor.index,
+            monitor.name,
+            monitor.x,
+            monitor.y,
+            monitor.width,
+            monitor.height,
+            monitor.dpi,
+            monitor.dpi * 100 / crate::constants::list::DEFAULT_DPI,
+            if monitor.primary { ", primary" } else { "" }
+        ));
+    }
+    line(String::new());
+
+    line("== settings.json ==".to_string());
+    line(
+        settings
+            .clone()
+            .unwrap_or_else(|| "none (defaults)".to_string()),
+    );
+    line(String::new());
+
+    line(format!(
+        "== rustframe.log (last {} lines) ==",
+        diagnose::LOG_LINES
+    ));
+    line(
+        log.clone().unwrap_or_else(|| {
+            "none (RustFrame writes a log file in portable mode only)".to_string()
+        }),
+    );
+
+    // Redacted once, at the end - a placeholder must not be replaced again
+    Report {
+        text: redact_text(&text),
+        settings: settings.map(|s| redact_text(&s)),
+        log: log.map(|l| redact_text(&l)),
+    }
+}
+
+/// Print the report, or write it to a .txt / .zip file
+pub fn write(report: &Report, out: Option<&Path>) -> Result<()> {
+    let Some(path) = out else {
+        print!("{}", report.text);
+        return Ok(());
+    };
+    let zip = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("zip"));
+    if !zip {
+        return std::fs::write(path, &report.text)
+            .with_context(|| format!("Failed to write {:?}", path));
+    }
+
+    let file =
+        std::fs::File::create(path).with_context(|| format!("Failed to create {:?}", path))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    zip.start_file(diagnose::REPORT_NAME, options)?;
+    zip.write_all(report.text.as_bytes())?;
+    if let Some(settings) = &report.settings {
+        zip.start_file(diagnose::SETTINGS_NAME, options)?;
+        zip.write_all(settings.as_bytes())?;
+    }
+    if let Some(log) = &report.log {
+        zip.start_file(diagnose::LOG_NAME, options)?;
+        zip.write_all(log.as_bytes())?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+/// Replace the profile folder, user name and computer name
+fn redact_text(text: &str) -> String {
+    let mut text = text.to_string();
+    // The profile folder first - it contains the user name
+    for (variable, placeholder) in [
+        ("USERPROFILE", "%USERPROFILE%"),
+        ("USERNAME", "<user>"),
+        ("COMPUTERNAME", "<computer>"),
+    ] {
+        let Ok(value) = std::env::var(variable) else {
+            continue;
+        };
+        if value.len() < 2 {
+            continue;
+        }
+        text = replace_ignore_case(&text, &value, placeholder);
+    }
+    text
+}
+
+/// Replace every occurrence of `from` regardless of (ASCII) case
+fn replace_ignore_case(text: &str, from: &str, to: &str) -> String {
+    let lower = text.to_ascii_lowercase();
+    let from_lower = from.to_ascii_lowercase();
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+    for (start, _) in lower.match_indices(&from_lower) {
+        result.push_str(&text[last..start]);
+        result.push_str(to);
+        last = start + from.len();
+    }
+    result.push_str(&text[last..]);
+    result
+}
+
+/// Leave out secrets and cut URLs down to their host
+fn redact_json(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let key = key.to_ascii_lowercase();
+                if diagnose::SECRET_KEYS.iter().any(|k| key.contains(k)) {
+                    if !value.is_null() && value.as_str() != Some("") {
+                        *value = Value::String(diagnose::REDACTED.to_string());
+                    }
+                } else if diagnose::URL_KEYS.iter().any(|k| key.contains(k)) {
+                    if let Some(url) = value.as_str() {
+                        *value = Value::String(url_host(url));
+                    }
+                } else {
+                    redact_json(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+/// "https://hooks.example.com/services/T0/B1/x" -> "https://hooks.example.com/<redacted>"
+fn url_host(url: &str) -> String {
+    if url.is_empty() {
+        return String::new();
+    }
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return diagnose::REDACTED.to_string();
+    };
+    // Credentials before the host (user:password@host) go too
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or("");
+    let host = authority.rsplit('@').next().unwrap_or("");
+    if rest.len() > authority.len() || authority.contains('@') {
+        format!("{}://{}/{}", scheme, host, diagnose::REDACTED)
+    } else {
+        format!("{}://{}", scheme, host)
+    }
+}
+
+/// Edition, version and build from the registry
+#[cfg(windows)]
+fn windows_version() -> String {
+    const KEY: &str = r"SOFTWARE\Microsoft\Windows NT\CurrentVersion";
+    let product = registry_string(KEY, "ProductName").unwrap_or_else(|| "Windows".to_string());
+    let build = registry_string(KEY, "CurrentBuild").unwrap_or_default();
+    // Windows 11 still reports "Windows 10" as its product name
+    let product = match build.parse::<u32>() {
+        Ok(number) if number >= 22000 => product.replacen("Windows 10", "Windows 11", 1),
+        _ => product,
+    };
+    let version = registry_string(KEY, "DisplayVersion")
+        .or_else(|| registry_string(KEY, "ReleaseId"))
+        .unwrap_or_default();
+    let revision = registry_dword(KEY, "UBR")
+        .map(|ubr| format!(".{}", ubr))
+        .unwrap_or_default();
+    format!("{} {} (build {}{})", product, version, build, revision)
+}
+
+#[cfg(not(windows))]
+fn windows_version() -> String {
+    std::env::consts::OS.to_string()
+}
+
+#[cfg(windows)]
+fn registry_string(key: &str, name: &str) -> Option<String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY_LOCAL_MACHINE, RRF_RT_REG_SZ};
+
+    let key = crate::utils::wide_string(key);
+    let name = crate::utils::wide_string(name);
+    let mut buffer = [0u16; 256];
+    let mut size = std::mem::size_of_val(&buffer) as u32;
+    let result = unsafe {
+        RegGetValueW(
+            HKEY_LOCAL_MACHINE,
+            PCWSTR(key.as_ptr()),
+            PCWSTR(name.as_ptr()),
+            RRF_RT_REG_SZ,
+            None,
+            Some(buffer.as_mut_ptr() as *mut std::ffi::c_void),
+            Some(&mut size),
+        )
+    };
+    if result.is_err() {
+        return None;
+    }
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    Some(String::from_utf16_lossy(&buffer[..len]))
+}
+
+#[cfg(windows)]
+fn registry_dword(key: &str, name: &str) -> Option<u32> {
+    use windows::core::PCWSTR;
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY_LOCAL_MACHINE, RRF_RT_REG_DWORD};
+
+    let key = crate::utils::wide_string(key);
+    let name = crate::utils::wide_string(name);
+    let mut value = 0u32;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    let result = unsafe {
+        RegGetValueW(
+            HKEY_LOCAL_MACHINE,
+            PCWSTR(key.as_ptr()),
+            PCWSTR(name.as_ptr()),
+            RRF_RT_REG_DWORD,
+            None,
+            Some(&mut value as *mut u32 as *mut std::ffi::c_void),
+            Some(&mut size),
+        )
+    };
+    result.is_ok().then_some(value)
+}
+
+#[cfg(windows)]
+fn wgc_supported() -> bool {
+    windows::Graphics::Capture::GraphicsCaptureSession::IsSupported().unwrap_or(false)
+}
+
+#[cfg(not(windows))]
+fn wgc_supported() -> bool {
+    false
+}
+
+/// Every adapter DXGI lists, with a Desktop Duplication test per output
+#[cfg(windows)]
+fn adapters() -> Vec<Adapter> {
+    use windows::core::Interface;
+    use windows::Win32::Graphics::Direct3D::D3D_DRIVER_TYPE_UNKNOWN;
+    use windows::Win32::Graphics::Direct3D11::{
+        D3D11CreateDevice, D3D11_CREATE_DEVICE_BGRA_SUPPORT, D3D11_SDK_VERSION,
+    };
+    use windows::Win32::Graphics::Dxgi::{
+        CreateDXGIFactory1, IDXGIDevice, IDXGIFactory1, IDXGIOutput1, DXGI_ADAPTER_FLAG_SOFTWARE,
+    };
+
+    let Ok(factory) = (unsafe { CreateDXGIFactory1::<IDXGIFactory1>() }) else {
+        return Vec::new();
+    };
+    let mut adapters = Vec::new();
+    let mut index = 0;
+    while let Ok(adapter) = unsafe { factory.EnumAdapters1(index) } {
+        index += 1;
+        let Ok(desc) = (unsafe { adapter.GetDesc1() }) else {
+            continue;
+        };
+        let len = desc
+            .Description
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(desc.Description.len());
+        // The user mode driver version, e.g. 31.0.15.3699
+        let driver = unsafe { adapter.CheckInterfaceSupport(&IDXGIDevice::IID) }
+            .ok()
+            .map(|v| {
+                format!(
+                    "{}.{}.{}.{}",
+                    (v >> 48) & 0xFFFF,
+                    (v >> 32) & 0xFFFF,
+                    (v >> 16) & 0xFFFF,
+                    v & 0xFFFF
+                )
+            });
+
+        // One device per adapter for the duplication test
+        let mut device = None;
+        let created = unsafe {
+            D3D11CreateDevice(
+                &adapter,
+                D3D_DRIVER_TYPE_UNKNOWN,
+                windows::Win32::Foundation::HMODULE::default(),
+                D3D11_CREATE_DEVICE_BGRA_SUPPORT,
+                None,
+                D3D11_SDK_VERSION,
+                Some(&mut device),
+                None,
+                None,
+            )
+        };
+        let mut outputs = Vec::new();
+        let mut output_index = 0;
+        while let Ok(output) = unsafe { adapter.EnumOutputs(output_index) } {
+            output_index += 1;
+            let name = unsafe { output.GetDesc() }
+                .map(|d| {
+                    let len = d
+                        .DeviceName
+                        .iter()
+                        .position(|&c| c == 0)
+                        .unwrap_or(d.DeviceName.len());
+                    String::from_utf16_lossy(&d.DeviceName[..len])
+                })
+                .unwrap_or_else(|_| format!("output {}", output_index));
+            let duplication = match (&created, &device) {
+                (Ok(()), Some(device)) => output
+                    .cast::<IDXGIOutput1>()
+                    .and_then(|output| unsafe { output.DuplicateOutput(device) })
+                    .map(|_| ())
+                    .map_err(|e| e.message().to_string()),
+                (Err(e), _) => Err(format!("no Direct3D 11 device: {}", e.message())),
+                _ => Err("no Direct3D 11 device".to_string()),
+            };
+            outputs.push((name, duplication));
+        }
+
+        adapters.push(Adapter {
+            name: String::from_utf16_lossy(&desc.Description[..len]),
+            vendor_id: desc.VendorId,
+            device_id: desc.DeviceId,
+            memory_mb: desc.DedicatedVideoMemory as u64 / (1024 * 1024),
+            driver,
+            software: desc.Flags & DXGI_ADAPTER_FLAG_SOFTWARE.0 as u32 != 0,
+            outputs,
+        });
+    }
+    adapters
+}
+
+#[cfg(not(windows))]
+fn adapters() -> Vec<Adapter> {
+    Vec::new()
+}
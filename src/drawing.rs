@@ -0,0 +1,171 @@
+// drawing.rs - Pixel Drawing Primitives
+//
+// Simple shape rasterization on top of bitmap_font::Canvas.
+// Used to draw annotations both onto the overlay window (local preview) and
+// into the captured frames before they are uploaded to the GPU.
+//
+// All colors are ARGB u32 values. Captured frames are BGRA bytes, which read
+// as 0xAARRGGBB on little-endian, so the same values work for both targets.
+
+use crate::bitmap_font::Canvas;
+
+/// Set a single pixel, ignoring coordinates outside the canvas
+pub fn put_pixel(canvas: &mut Canvas, x: i32, y: i32, color: u32) {
+    if x >= 0 && x < canvas.width && y >= 0 && y < canvas.height {
+        let idx = (y * canvas.width + x) as usize;
+        if idx < canvas.pixels.len() {
+            canvas.pixels[idx] = color;
+        }
+    }
+}
+
+/// Fill an axis-aligned rectangle (clipped to the canvas)
+pub fn fill_rect(canvas: &mut Canvas, x: i32, y: i32, width: i32, height: i32, color: u32) {
+    let left = x.max(0);
+    let top = y.max(0);
+    let right = (x + width).min(canvas.width);
+    let bottom = (y + height).min(canvas.height);
+
+    for py in top..bottom {
+        let row = (py * canvas.width) as usize;
+        for px in left..right {
+            canvas.pixels[row + px as usize] = color;
+        }
+    }
+}
+
+/// Fill a solid disc - used as the "brush" for thick lines
+pub fn fill_circle(canvas: &mut Canvas, cx: i32, cy: i32, radius: i32, color: u32) {
+    if radius <= 0 {
+        put_pixel(canvas, cx, cy, color);
+        return;
+    }
+
+    let r2 = radius * radius;
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            if dx * dx + dy * dy <= r2 {
+                put_pixel(canvas, cx + dx, cy + dy, color);
+            }
+        }
+    }
+}
+
+/// Draw a line with the given thickness (Bresenham, stamping a disc per step)
+pub fn draw_line(
+    canvas: &mut Canvas,
+    x0: i32,
+    y0: i32,
+    x1: i32,
+    y1: i32,
+    width: u32,
+    color: u32,
+) {
+    let radius = (width as i32 - 1) / 2;
+
+    let dx = (x1 - x0).abs();
+    let dy = -(y1 - y0).abs();
+    let step_x = if x0 < x1 { 1 } else { -1 };
+    let step_y = if y0 < y1 { 1 } else { -1 };
+    let mut err = dx + dy;
+    let (mut x, mut y) = (x0, y0);
+
+    loop {
+        fill_circle(canvas, x, y, radius, color);
+        if x == x1 && y == y1 {
+            break;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += step_x;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += step_y;
+        }
+    }
+}
+
+/// Draw a rectangle outline between two corner points
+pub fn draw_rect(
+    canvas: &mut Canvas,
+    x0: i32,
+    y0: i32,
+    x1: i32,
+    y1: i32,
+    width: u32,
+    color: u32,
+) {
+    draw_line(canvas, x0, y0, x1, y0, width, color);
+    draw_line(canvas, x1, y0, x1, y1, width, color);
+    draw_line(canvas, x1, y1, x0, y1, width, color);
+    draw_line(canvas, x0, y1, x0, y0, width, color);
+}
+
+/// Draw an ellipse outline inscribed in the box spanned by two corner points
+pub fn draw_ellipse(
+    canvas: &mut Canvas,
+    x0: i32,
+    y0: i32,
+    x1: i32,
+    y1: i32,
+    width: u32,
+    color: u32,
+) {
+    let cx = (x0 + x1) as f32 / 2.0;
+    let cy = (y0 + y1) as f32 / 2.0;
+    let rx = (x1 - x0).abs() as f32 / 2.0;
+    let ry = (y1 - y0).abs() as f32 / 2.0;
+
+    // Approximate with line segments - roughly one segment per 4px of perimeter
+    let perimeter = std::f32::consts::PI * (rx + ry);
+    let segments = ((perimeter / 4.0) as i32).clamp(16, 720);
+
+    let point = |i: i32| {
+        let t = i as f32 / segments as f32 * std::f32::consts::TAU;
+        (
+            (cx + rx * t.cos()).round() as i32,
+            (cy + ry * t.sin()).round() as i32,
+        )
+    };
+
+    let mut prev = point(0);
+    for i in 1..=segments {
+        let next = point(i);
+        draw_line(canvas, prev.0, prev.1, next.0, next.1, width, color);
+        prev = next;
+    }
+}
+
+/// Draw an arrow from (x0, y0) to (x1, y1) with the head at the end point
+pub fn draw_arrow(
+    canvas: &mut Canvas,
+    x0: i32,
+    y0: i32,
+    x1: i32,
+    y1: i32,
+    width: u32,
+    color: u32,
+) {
+    draw_line(canvas, x0, y0, x1, y1, width, color);
+
+    let dx = (x1 - x0) as f32;
+    let dy = (y1 - y0) as f32;
+    let length = (dx * dx + dy * dy).sqrt();
+    if length < 1.0 {
+        return;
+    }
+
+    // Head size scales with stroke width but never exceeds half the shaft
+    let head = (12.0 + width as f32 * 3.0).min(length / 2.0).max(4.0);
+    let angle = dy.atan2(dx);
+    let spread = 0.5; // ~28 degrees
+
+    for side in [-spread, spread] {
+        let a = angle + std::f32::consts::PI + side;
+        let hx = x1 + (head * a.cos()).round() as i32;
+        let hy = y1 + (head * a.sin()).round() as i32;
+        draw_line(canvas, x1, y1, hx, hy, width, color);
+    }
+}
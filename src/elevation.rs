@@ -0,0 +1,235 @@
+// elevation.rs - Elevated (Administrator) Application Windows
+//
+// Windows keeps a normal process from reaching into an elevated one (User
+// Interface Privilege Isolation). While an application running as
+// administrator has the focus, RustFrame's low-level mouse and keyboard hooks
+// get none of its input: click highlighting, keystroke display, gestures and
+// the window picker stop working over it. The picture itself is still
+// captured.
+//
+// HOW IT WORKS:
+// 1. At a short interval during a capture, the foreground window is checked
+//    if it overlaps the capture region (a picked window is checked at once)
+// 2. A window whose process token is elevated - or whose token RustFrame is
+//    not even allowed to open - counts as elevated
+// 3. Once per capture, RustFrame explains this and offers to restart as
+//    administrator. The restart passes a rustframe://frame link (see
+//    deep_link.rs) to the new process, so the frame comes back at the same
+//    place and the capture starts again if it was running.
+// 4. The restart is not offered while plugins, scripts or a post-capture
+//    command are set up: the elevated instance would run them as
+//    administrator. The box then only explains what does not work.
+//
+// Nothing is checked when RustFrame itself is elevated.
+
+use anyhow::{bail, Result};
+use log::info;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use crate::capture::CaptureRect;
+use crate::constants::elevation;
+use crate::window_mask;
+
+/// Watches for elevated windows in front of the capture region
+pub struct ElevationWatch {
+    /// The elevated window found (reported once per capture)
+    found: Option<isize>,
+    last_check: Option<Instant>,
+}
+
+impl ElevationWatch {
+    pub fn new() -> Self {
+        Self {
+            found: None,
+            last_check: None,
+        }
+    }
+
+    /// Forget the window found (capture started or stopped)
+    pub fn reset(&mut self) {
+        self.found = None;
+        self.last_check = None;
+    }
+
+    /// Check the foreground window if the check interval has passed
+    /// Returns the name of an elevated application the first time one is found.
+    pub fn update(&mut self, region: CaptureRect) -> Option<String> {
+        if self.found.is_some() || is_elevated() {
+            return None;
+        }
+        let interval = Duration::from_millis(elevation::CHECK_INTERVAL_MS);
+        if self.last_check.is_some_and(|t| t.elapsed() < interval) {
+            return None;
+        }
+        self.last_check = Some(Instant::now());
+
+        let (hwnd, pid) = foreground_window()?;
+        let rect = window_mask::window_rect(hwnd)?;
+        let overlaps = rect.x < region.x + region.width as i32
+            && region.x < rect.x + rect.width as i32
+            && rect.y < region.y + region.height as i32
+            && region.y < rect.y + rect.height as i32;
+        if !overlaps {
+            return None;
+        }
+        self.check(hwnd, pid)
+    }
+
+    /// Check a window right away (picked with the window picker)
+    pub fn check(&mut self, hwnd: isize, pid: u32) -> Option<String> {
+        if self.found.is_some() || is_elevated() || !process_elevated(pid) {
+            return None;
+        }
+        self.found = Some(hwnd);
+        let name = window_mask::process_name(pid).unwrap_or_else(|| format!("Process {}", pid));
+        info!(
+            "{} runs as administrator, input hooks are blocked over it",
+            name
+        );
+        Some(name)
+    }
+}
+
+/// Text of the box offering the restart for an elevated application
+pub fn restart_question(name: &str) -> String {
+    format!(
+        "{} runs as administrator.\n\nWindows keeps RustFrame from seeing the mouse and keyboard \
+         while it has the focus: click highlighting, keystroke display and the window picker \
+         do not work over it. The picture is still captured.\n\nRestart RustFrame as \
+         administrator? The frame and the capture are restored.",
+        name
+    )
+}
+
+/// Text of the box shown instead of the restart when `code` (plugins,
+/// scripts, ...) would run elevated
+pub fn restart_refused(name: &str, code: &[&str]) -> String {
+    format!(
+        "{} runs as administrator.\n\nWindows keeps RustFrame from seeing the mouse and keyboard \
+         while it has the focus: click highlighting, keystroke display and the window picker \
+         do not work over it. The picture is still captured.\n\nRestarting RustFrame as \
+         administrator is not offered because it would run {} as administrator too. Turn \
+         them off to get the restart.",
+        name,
+        code.join(", ")
+    )
+}
+
+/// True if RustFrame itself runs elevated
+pub fn is_elevated() -> bool {
+    static ELEVATED: OnceLock<bool> = OnceLock::new();
+    *ELEVATED.get_or_init(|| token_elevated(None).unwrap_or(false))
+}
+
+/// True if a process runs elevated
+/// A process whose token cannot be opened runs at a higher integrity level
+/// than RustFrame, which for an application of the same user means elevated.
+fn process_elevated(pid: u32) -> bool {
+    token_elevated(Some(pid)).unwrap_or(true)
+}
+
+/// Elevation of a process token (None: the current process); None if the
+/// token cannot be opened
+#[cfg(windows)]
+fn token_elevated(pid: Option<u32>) -> Option<bool> {
+    use windows::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows::Win32::Security::{
+        GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY,
+    };
+    use windows::Win32::System::Threading::{
+        GetCurrentProcess, OpenProcess, OpenProcessToken, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    unsafe {
+        let process = match pid {
+            Some(pid) => OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?,
+            None => GetCurrentProcess(),
+        };
+        let mut token = HANDLE::default();
+        let opened = OpenProcessToken(process, TOKEN_QUERY, &mut token);
+        if pid.is_some() {
+            let _ = CloseHandle(process);
+        }
+        opened.ok()?;
+
+        let mut elevation = TOKEN_ELEVATION::default();
+        let mut size = 0u32;
+        let result = GetTokenInformation(
+            token,
+            TokenElevation,
+            Some(&mut elevation as *mut TOKEN_ELEVATION as *mut std::ffi::c_void),
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut size,
+        );
+        let _ = CloseHandle(token);
+        result.ok()?;
+        Some(elevation.TokenIsElevated != 0)
+    }
+}
+
+#[cfg(not(windows))]
+fn token_elevated(_pid: Option<u32>) -> Option<bool> {
+    Some(false)
+}
+
+/// Foreground window as (HWND, process ID)
+#[cfg(windows)]
+fn foreground_window() -> Option<(isize, u32)> {
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.is_invalid() {
+            return None;
+        }
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        Some((hwnd.0 as isize, pid))
+    }
+}
+
+#[cfg(not(windows))]
+fn foreground_window() -> Option<(isize, u32)> {
+    None
+}
+
+/// Start RustFrame again as administrator with a rustframe:// link to run
+/// The caller exits once this succeeds; the UAC prompt answered with No is an error.
+#[cfg(windows)]
+pub fn restart_elevated(link: &str) -> Result<()> {
+    use anyhow::Context;
+    use windows::core::PCWSTR;
+    use windows::Win32::UI::Shell::ShellExecuteW;
+    use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+    let exe = std::env::current_exe().context("Failed to locate RustFrame.exe")?;
+    let operation = crate::utils::wide_string("runas");
+    let file = crate::utils::wide_string(&exe.to_string_lossy());
+    let parameters =
+        crate::utils::wide_string(&format!("{} \"{}\"", elevation::RESTARTED_FLAG, link));
+    // Values above 32 mean success
+    let result = unsafe {
+        ShellExecuteW(
+            None,
+            PCWSTR(operation.as_ptr()),
+            PCWSTR(file.as_ptr()),
+            PCWSTR(parameters.as_ptr()),
+            None,
+            SW_SHOWNORMAL,
+        )
+    };
+    if result.0 as usize <= 32 {
+        bail!(
+            "Restart as administrator cancelled or failed ({})",
+            result.0 as usize
+        );
+    }
+    info!("Restarting as administrator with {}", link);
+    Ok(())
+}
+
+#[cfg(not(windows))]
+pub fn restart_elevated(_link: &str) -> Result<()> {
+    bail!("Restarting as administrator is only supported on Windows")
+}
@@ -0,0 +1,57 @@
+// enhance.rs - Sharpen and Denoise Output Filters
+//
+// Two GPU stages that run in the fragment shader (shader.wgsl) after the CPU
+// filter chain has composited the frame:
+// - Sharpen: unsharp mask at output resolution. When a 4K region is scaled down
+//   to a 1080p window, text gets soft; adding back the difference between each
+//   pixel and its blurred neighborhood restores the edges.
+// - Denoise: light temporal denoise. Pixels that changed only slightly since the
+//   previous frame are blended with it, which calms capture noise and dithering
+//   without smearing real motion (large changes are passed through untouched).
+//
+// Each output keeps its own flags, so e.g. a recording can stay untouched while
+// the shared window is sharpened.
+
+use serde::{Deserialize, Serialize};
+
+use crate::constants::enhance;
+
+/// Enable flags of the enhancement stages for one output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputEnhance {
+    pub sharpen: bool,
+    pub denoise: bool,
+}
+
+/// Uniform block consumed by shader.wgsl (must match `struct Enhance` there)
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct EnhanceUniform {
+    /// Strength of the unsharp mask (0 = off)
+    sharpen_amount: f32,
+    /// Largest per-pixel change (linear RGB distance) treated as noise (0 = off)
+    denoise_threshold: f32,
+    /// How much of the previous frame is blended into noise-only pixels
+    denoise_strength: f32,
+    _padding: f32,
+}
+
+impl OutputEnhance {
+    /// Shader parameters for the current flags
+    pub fn uniform(&self) -> EnhanceUniform {
+        EnhanceUniform {
+            sharpen_amount: if self.sharpen {
+                enhance::SHARPEN_AMOUNT
+            } else {
+                0.0
+            },
+            denoise_threshold: if self.denoise {
+                enhance::DENOISE_THRESHOLD
+            } else {
+                0.0
+            },
+            denoise_strength: enhance::DENOISE_STRENGTH,
+            _padding: 0.0,
+        }
+    }
+}
@@ -0,0 +1,352 @@
+// filter.rs - Frame Filter Chain
+//
+// Every effect that modifies the captured pixels before they reach the outputs
+// is a FrameFilter. The FilterChain decides which filters run and in which
+// order, so new effects only need to implement the trait and get a FilterKind -
+// the renderer never has to know about them individually.
+//
+// The chain only stores the order and the enabled flags; the filters themselves
+// stay owned by the app (hotkeys and menus mutate them directly) and are looked
+// up by kind when a frame is composited (see FrameOverlays in renderer.rs).
+//
+// The redaction filters (window-mask, privacy) are not FrameFilters: they work
+// in the coordinates of one capture region, so they run on each source frame
+// before the frames are composited (see Redaction in renderer.rs) - always
+// ahead of the other filters, whatever their place in the list.
+//
+// The order is configured in Settings as a comma-separated list of filter names.
+// Listed filters run in the given order; filters left out are disabled.
+// Leaving out a redaction filter (window-mask, privacy) is allowed but logged,
+// since it silently turns off masking that is still configured elsewhere.
+
+use log::warn;
+
+use crate::annotation::AnnotationLayer;
+use crate::bitmap_font::Canvas;
+use crate::click_highlight::ClickHighlight;
+use crate::constants::filter;
+use crate::grid::CompositionGrid;
+use crate::magnifier::Magnifier;
+use crate::spotlight::Spotlight;
+use crate::timer::OverlayTimer;
+
+/// An effect applied to every output frame
+pub trait FrameFilter {
+    /// Counter that only ever increases and changes whenever the result of
+    /// apply() would change (lets the renderer skip unchanged frames)
+    fn revision(&self) -> u64;
+
+    /// True if apply() would modify the frame
+    fn is_active(&self) -> bool;
+
+    /// Modify the captured frame in place
+    fn apply(&self, canvas: &mut Canvas);
+}
+
+/// Identifies a filter in the chain (and in the settings)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterKind {
+    WindowMask,
+    Privacy,
+    Spotlight,
+    Annotations,
+    Clicks,
+    Magnifier,
+    Grid,
+    Timer,
+}
+
+impl FilterKind {
+    /// Every filter, in the default order
+    pub const ALL: [FilterKind; 8] = [
+        FilterKind::WindowMask,
+        FilterKind::Privacy,
+        FilterKind::Spotlight,
+        FilterKind::Annotations,
+        FilterKind::Clicks,
+        FilterKind::Magnifier,
+        FilterKind::Grid,
+        FilterKind::Timer,
+    ];
+
+    /// Name used in the settings
+    pub fn name(self) -> &'static str {
+        match self {
+            FilterKind::WindowMask => "window-mask",
+            FilterKind::Privacy => "privacy",
+            FilterKind::Spotlight => "spotlight",
+            FilterKind::Annotations => "annotations",
+            FilterKind::Clicks => "clicks",
+            FilterKind::Magnifier => "magnifier",
+            FilterKind::Grid => "grid",
+            FilterKind::Timer => "timer",
+        }
+    }
+
+    /// True for the filters that hide content (window mask, privacy regions)
+    pub fn redacts(self) -> bool {
+        matches!(self, FilterKind::WindowMask | FilterKind::Privacy)
+    }
+
+    /// Whether the overlay opacity applies (redaction always covers fully)
+    pub fn fades(self) -> bool {
+        !self.redacts()
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|k| k.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// Ordered list of filters with an enabled flag each
+pub struct FilterChain {
+    filters: Vec<(FilterKind, bool)>,
+    /// Bumped whenever the order or an enabled flag changes
+    revision: u64,
+}
+
+impl FilterChain {
+    /// Chain with the given enabled filters (by name, in order)
+    pub fn new(names: &[String]) -> Self {
+        let mut chain = Self {
+            filters: Vec::new(),
+            revision: 0,
+        };
+        chain.set_order(names);
+        chain
+    }
+
+    /// Replace the order: listed filters are enabled in the given order,
+    /// the others are disabled (kept at the end in their default order)
+    pub fn set_order(&mut self, names: &[String]) {
+        let mut filters: Vec<(FilterKind, bool)> = Vec::new();
+        for name in names {
+            match FilterKind::from_name(name) {
+                Some(kind) if !filters.iter().any(|&(k, _)| k == kind) => {
+                    filters.push((kind, true))
+                }
+                Some(_) => {}
+                None => warn!("Unknown filter '{}' ignored", name),
+            }
+        }
+        for kind in FilterKind::ALL {
+            if !filters.iter().any(|&(k, _)| k == kind) {
+                if kind.redacts() {
+                    warn!(
+                        "Filter '{}' is not in the filter list - it will not redact anything",
+                        kind.name()
+                    );
+                }
+                filters.push((kind, false));
+            }
+        }
+
+        if filters != self.filters {
+            self.filters = filters;
+            self.revision += 1;
+        }
+    }
+
+    /// Enabled filters in the order they run
+    pub fn enabled(&self) -> impl Iterator<Item = FilterKind> + '_ {
+        self.filters.iter().filter(|&&(_, on)| on).map(|&(k, _)| k)
+    }
+
+    pub fn is_enabled(&self, kind: FilterKind) -> bool {
+        self.enabled().any(|k| k == kind)
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+}
+
+/// How strongly drawn overlays cover the frame (set with a MIDI fader)
+pub struct OverlayOpacity {
+    percent: u32,
+    revision: u64,
+}
+
+impl OverlayOpacity {
+    pub fn new() -> Self {
+        Self {
+            percent: 100,
+            revision: 0,
+        }
+    }
+
+    pub fn percent(&self) -> u32 {
+        self.percent
+    }
+
+    pub fn set(&mut self, percent: u32) {
+        let percent = percent.min(100);
+        if percent != self.percent {
+            self.percent = percent;
+            self.revision += 1;
+        }
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Mix the filtered pixels with the pixels from before the filter ran
+    pub fn blend(&self, pixels: &mut [u32], before: &[u32]) {
+        let alpha = self.percent;
+        let mix = |after: u32, before: u32, shift: u32| {
+            let a = (after >> shift) & 0xFF;
+            let b = (before >> shift) & 0xFF;
+            ((a * alpha + b * (100 - alpha)) / 100) << shift
+        };
+        for (pixel, &old) in pixels.iter_mut().zip(before) {
+            if *pixel != old {
+                *pixel = (*pixel & 0xFF00_0000)
+                    | mix(*pixel, old, 16)
+                    | mix(*pixel, old, 8)
+                    | mix(*pixel, old, 0);
+            }
+        }
+    }
+}
+
+/// Filter names enabled out of the box, in order
+pub fn default_order() -> Vec<String> {
+    filter::DEFAULT_ORDER
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// FrameFilter implementations for the built-in overlays
+// (inherent methods take precedence, so these simply forward to them)
+// ---------------------------------------------------------------------------
+
+impl FrameFilter for Spotlight {
+    fn revision(&self) -> u64 {
+        self.revision()
+    }
+
+    fn is_active(&self) -> bool {
+        self.is_enabled()
+    }
+
+    fn apply(&self, canvas: &mut Canvas) {
+        self.apply(canvas)
+    }
+}
+
+impl FrameFilter for AnnotationLayer {
+    fn revision(&self) -> u64 {
+        self.revision()
+    }
+
+    fn is_active(&self) -> bool {
+        !self.is_empty()
+    }
+
+    fn apply(&self, canvas: &mut Canvas) {
+        self.draw(canvas, 0, 0)
+    }
+}
+
+impl FrameFilter for ClickHighlight {
+    fn revision(&self) -> u64 {
+        self.revision()
+    }
+
+    fn is_active(&self) -> bool {
+        self.is_active()
+    }
+
+    fn apply(&self, canvas: &mut Canvas) {
+        self.draw(canvas);
+    }
+}
+
+impl FrameFilter for Magnifier {
+    fn revision(&self) -> u64 {
+        self.revision()
+    }
+
+    fn is_active(&self) -> bool {
+        self.draws_into_output()
+    }
+
+    fn apply(&self, canvas: &mut Canvas) {
+        self.apply(canvas)
+    }
+}
+
+impl FrameFilter for CompositionGrid {
+    fn revision(&self) -> u64 {
+        self.revision()
+    }
+
+    fn is_active(&self) -> bool {
+        self.draws_into_output()
+    }
+
+    fn apply(&self, canvas: &mut Canvas) {
+        self.apply(canvas)
+    }
+}
+
+impl FrameFilter for OverlayTimer {
+    fn revision(&self) -> u64 {
+        self.revision()
+    }
+
+    fn is_active(&self) -> bool {
+        self.is_visible()
+    }
+
+    fn apply(&self, canvas: &mut Canvas) {
+        self.draw(canvas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn listed_filters_run_in_the_given_order() {
+        let chain = FilterChain::new(&names(&["privacy", "timer", "spotlight"]));
+        let enabled: Vec<FilterKind> = chain.enabled().collect();
+        assert_eq!(
+            enabled,
+            vec![
+                FilterKind::Privacy,
+                FilterKind::Timer,
+                FilterKind::Spotlight
+            ]
+        );
+        assert!(!chain.is_enabled(FilterKind::Grid));
+        assert_eq!(chain.filters.len(), FilterKind::ALL.len());
+    }
+
+    #[test]
+    fn unknown_and_repeated_names_are_ignored() {
+        let chain = FilterChain::new(&names(&["Grid", "grid", "bogus", " timer "]));
+        let enabled: Vec<FilterKind> = chain.enabled().collect();
+        assert_eq!(enabled, vec![FilterKind::Grid, FilterKind::Timer]);
+    }
+
+    #[test]
+    fn revision_changes_only_with_the_order() {
+        let mut chain = FilterChain::new(&default_order());
+        let revision = chain.revision();
+        chain.set_order(&default_order());
+        assert_eq!(chain.revision(), revision);
+        chain.set_order(&names(&["grid"]));
+        assert_eq!(chain.revision(), revision + 1);
+    }
+}
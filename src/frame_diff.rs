@@ -0,0 +1,222 @@
+// frame_diff.rs - Frame Comparison (Visual Diff)
+//
+// For visual testing: hold a reference frame and compare every captured frame
+// against it, so a changed pixel after a rebuild or a settings change is easy
+// to spot.
+//
+// HOW IT WORKS:
+// 1. Set Reference Frame (tray → Compare Frames, or Ctrl+Alt+D) keeps a copy
+//    of the captured frame as it is now
+// 2. Each output frame is compared with it pixel by pixel. The output shows
+//    one of two views:
+//    - Heatmap: unchanged pixels dimmed to gray, changed pixels from yellow
+//      (slightly different) to red (very different)
+//    - Blink: the reference and the current frame take turns, so a shifted
+//      or recolored element visibly jumps
+// 3. A readout in the corner shows the share of changed pixels
+//
+// The comparison runs on the captured pixels before the overlays, so
+// annotations, the grid and the timer are drawn on top and never count as
+// changes. A reference only matches frames of the same size - after the
+// region is resized a new one has to be set.
+
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+use crate::bitmap_font::{self, Canvas, TextStyle};
+use crate::constants::frame_diff;
+use crate::drawing;
+
+/// How the difference is shown in the output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffView {
+    Heatmap,
+    Blink,
+}
+
+impl DiffView {
+    pub fn label(self) -> &'static str {
+        match self {
+            DiffView::Heatmap => "Heatmap",
+            DiffView::Blink => "Blink",
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            DiffView::Heatmap => DiffView::Blink,
+            DiffView::Blink => DiffView::Heatmap,
+        }
+    }
+}
+
+/// Reference frame and the view of the comparison
+pub struct FrameDiff {
+    /// (pixels, width, height) - None while not comparing
+    reference: Option<(Vec<u32>, i32, i32)>,
+    view: DiffView,
+    /// Blink view: the reference is on screen, and since when
+    showing_reference: bool,
+    blink_since: Instant,
+    /// (changed pixels, compared pixels) of the last composited frame
+    changed: Cell<Option<(usize, usize)>>,
+    /// Bumped on every visible change so the renderer knows when to recomposite
+    revision: u64,
+}
+
+impl FrameDiff {
+    pub fn new() -> Self {
+        Self {
+            reference: None,
+            view: DiffView::Heatmap,
+            showing_reference: false,
+            blink_since: Instant::now(),
+            changed: Cell::new(None),
+            revision: 0,
+        }
+    }
+
+    /// True while a reference frame is held
+    pub fn is_active(&self) -> bool {
+        self.reference.is_some()
+    }
+
+    /// Keep a captured frame (0xAARRGGBB) as the reference and start comparing
+    pub fn set_reference(&mut self, frame: &[u32], width: u32, height: u32) {
+        self.reference = Some((frame.to_vec(), width as i32, height as i32));
+        self.showing_reference = false;
+        self.blink_since = Instant::now();
+        self.changed.set(None);
+        self.revision += 1;
+    }
+
+    /// Stop comparing and drop the reference
+    pub fn clear(&mut self) {
+        if self.reference.take().is_some() {
+            self.changed.set(None);
+            self.revision += 1;
+        }
+    }
+
+    pub fn view(&self) -> DiffView {
+        self.view
+    }
+
+    pub fn cycle_view(&mut self) {
+        self.view = self.view.next();
+        self.showing_reference = false;
+        self.blink_since = Instant::now();
+        self.revision += 1;
+    }
+
+    /// Share of changed pixels in the last composited frame (0-100)
+    /// None while not comparing or when the frame size differs from the reference.
+    pub fn changed_percent(&self) -> Option<f64> {
+        let (changed, total) = self.changed.get()?;
+        Some(changed as f64 * 100.0 / total.max(1) as f64)
+    }
+
+    /// Call once per loop iteration - switches the blink view between the
+    /// reference and the current frame
+    pub fn tick(&mut self) {
+        if self.reference.is_none() || self.view != DiffView::Blink {
+            return;
+        }
+        if self.blink_since.elapsed() >= Duration::from_millis(frame_diff::BLINK_INTERVAL_MS) {
+            self.showing_reference = !self.showing_reference;
+            self.blink_since = Instant::now();
+            self.revision += 1;
+        }
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Replace the frame with the comparison view and draw the readout
+    pub fn apply(&self, canvas: &mut Canvas) {
+        let Some((reference, width, height)) = &self.reference else {
+            return;
+        };
+        if (*width, *height) != (canvas.width, canvas.height)
+            || reference.len() != canvas.pixels.len()
+        {
+            self.changed.set(None);
+            let text = format!("Reference is {}x{} - set a new one", width, height);
+            draw_readout(canvas, &text);
+            return;
+        }
+
+        // STEP 1: Count the changed pixels (and paint the heatmap on the way)
+        let heatmap = self.view == DiffView::Heatmap;
+        let mut changed = 0;
+        for (pixel, &before) in canvas.pixels.iter_mut().zip(reference.iter()) {
+            let difference = channel_difference(*pixel, before);
+            if difference > frame_diff::TOLERANCE {
+                changed += 1;
+                if heatmap {
+                    *pixel = heat_color(difference);
+                }
+            } else if heatmap {
+                *pixel = dimmed(*pixel);
+            }
+        }
+        self.changed.set(Some((changed, reference.len())));
+
+        // STEP 2: Blink view - show the reference every other interval
+        if !heatmap && self.showing_reference {
+            canvas.pixels.copy_from_slice(reference);
+        }
+
+        // STEP 3: Readout
+        let percent = self.changed_percent().unwrap_or(0.0);
+        let mut text = format!("Changed: {:.2}% ({} px)", percent, changed);
+        if !heatmap {
+            let shown = if self.showing_reference {
+                "Reference"
+            } else {
+                "Current"
+            };
+            text = format!("{} - {}", shown, text);
+        }
+        draw_readout(canvas, &text);
+    }
+}
+
+/// Largest difference of the red, green and blue values of two pixels
+fn channel_difference(a: u32, b: u32) -> u8 {
+    [16, 8, 0]
+        .into_iter()
+        .map(|shift| (((a >> shift) & 0xFF) as i32 - ((b >> shift) & 0xFF) as i32).unsigned_abs())
+        .max()
+        .unwrap_or(0) as u8
+}
+
+/// Yellow for a small difference to red for the largest
+fn heat_color(difference: u8) -> u32 {
+    let green = 255 - difference as u32;
+    0xFFFF_0000 | (green << 8)
+}
+
+/// Unchanged pixel as dark gray, so the changes stand out
+fn dimmed(pixel: u32) -> u32 {
+    let (r, g, b) = ((pixel >> 16) & 0xFF, (pixel >> 8) & 0xFF, pixel & 0xFF);
+    let luma = (r * 299 + g * 587 + b * 114) / 1000;
+    let gray = luma * frame_diff::DIM_PERCENT / 100;
+    0xFF00_0000 | (gray << 16) | (gray << 8) | gray
+}
+
+/// Text in a box in the top-left corner of the frame
+fn draw_readout(canvas: &mut Canvas, text: &str) {
+    let scale = frame_diff::TEXT_SCALE;
+    let padding = frame_diff::PADDING;
+    let box_w = bitmap_font::text_width(text, scale) + padding * 2;
+    let box_h = bitmap_font::text_height(scale) + padding * 2;
+    let (x, y) = (frame_diff::MARGIN, frame_diff::MARGIN);
+    drawing::fill_rect(canvas, x, y, box_w, box_h, frame_diff::BACKGROUND);
+    let style = TextStyle {
+        color: frame_diff::TEXT,
+        scale,
+    };
+    bitmap_font::draw_text(canvas, x + padding, y + padding, text, &style);
+}
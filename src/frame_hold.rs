@@ -0,0 +1,171 @@
+// frame_hold.rs - Hold the Last Good Frame During Interruptions
+//
+// Some interruptions should never reach the audience:
+// - The application under the capture region is minimized (the output would
+//   suddenly show the desktop or whatever is behind it)
+// - The secure desktop is active (UAC prompt, Ctrl+Alt+Del) - the capture shows
+//   black or stalls
+//
+// While such an interruption lasts, the output keeps showing the last good frame
+// (like freeze-frame), for at most the configured grace period. If the
+// interruption is still there after the grace period, the live capture is shown
+// again so the output never stays stuck.
+//
+// HOW IT WORKS:
+// 1. At a short interval the topmost window under the center of the capture
+//    region is remembered as the "watched" application window
+// 2. If the watched window becomes minimized, or the input desktop cannot be
+//    opened (secure desktop), an interruption starts
+// 3. The renderer is frozen while the interruption lasts and the grace period
+//    has not run out
+
+use log::info;
+use std::time::{Duration, Instant};
+
+use crate::capture::CaptureRect;
+use crate::constants::frame_hold;
+use crate::window_mask;
+
+/// Detects interruptions of the captured content
+pub struct FrameHold {
+    /// Longest time a frame is held (zero = feature off)
+    grace: Duration,
+    /// Top-level window under the capture region (HWND value)
+    watched: Option<isize>,
+    /// When the current interruption started
+    interrupted_since: Option<Instant>,
+    last_check: Option<Instant>,
+}
+
+impl FrameHold {
+    pub fn new(grace_seconds: u32) -> Self {
+        Self {
+            grace: Duration::from_secs(grace_seconds as u64),
+            watched: None,
+            interrupted_since: None,
+            last_check: None,
+        }
+    }
+
+    /// Change the grace period (0 = never hold)
+    pub fn set_grace_seconds(&mut self, seconds: u32) {
+        self.grace = Duration::from_secs(seconds as u64);
+    }
+
+    /// Forget the watched window and any interruption (capture stopped)
+    pub fn reset(&mut self) {
+        self.watched = None;
+        self.interrupted_since = None;
+        self.last_check = None;
+    }
+
+    /// Re-check for interruptions if the check interval has passed
+    pub fn update(&mut self, region: CaptureRect) {
+        if self.grace.is_zero() {
+            self.interrupted_since = None;
+            return;
+        }
+        let interval = Duration::from_millis(frame_hold::CHECK_INTERVAL_MS);
+        if self.last_check.is_some_and(|t| t.elapsed() < interval) {
+            return;
+        }
+        self.last_check = Some(Instant::now());
+
+        // STEP 1: Is the watched application minimized, or the secure desktop up?
+        let mut minimized = self.watched.is_some_and(is_minimized);
+        if minimized
+            && self
+                .interrupted_since
+                .is_some_and(|t| t.elapsed() >= self.grace)
+        {
+            // Still minimized after the grace period - accept what is shown now
+            // and watch the window that took its place
+            self.watched = None;
+            minimized = false;
+        }
+        let interrupted = minimized || secure_desktop_active();
+
+        // STEP 2: Track the window under the region while everything is normal
+        if !interrupted {
+            self.watched = window_under(region);
+        }
+
+        match (interrupted, self.interrupted_since) {
+            (true, None) => {
+                info!(
+                    "Capture interrupted ({}), holding last frame for up to {}s",
+                    if minimized {
+                        "application minimized"
+                    } else {
+                        "secure desktop"
+                    },
+                    self.grace.as_secs()
+                );
+                self.interrupted_since = Some(Instant::now());
+            }
+            (false, Some(_)) => {
+                info!("Capture interruption over, showing live content again");
+                self.interrupted_since = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// True while the last good frame should be shown instead of the capture
+    pub fn is_holding(&self) -> bool {
+        self.interrupted_since
+            .is_some_and(|t| t.elapsed() < self.grace)
+    }
+}
+
+/// Topmost window (not ours) containing the center of the capture region
+fn window_under(region: CaptureRect) -> Option<isize> {
+    let cx = region.x + region.width as i32 / 2;
+    let cy = region.y + region.height as i32 / 2;
+    let own_pid = std::process::id();
+
+    // EnumWindows reports windows in z-order, topmost first
+    window_mask::visible_windows()
+        .into_iter()
+        .find(|w| w.pid != own_pid && cx >= w.left && cx < w.right && cy >= w.top && cy < w.bottom)
+        .map(|w| w.hwnd)
+}
+
+/// True if the window is minimized
+#[cfg(windows)]
+fn is_minimized(hwnd: isize) -> bool {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::IsIconic;
+
+    unsafe { IsIconic(HWND(hwnd as *mut std::ffi::c_void)).as_bool() }
+}
+
+#[cfg(not(windows))]
+fn is_minimized(_hwnd: isize) -> bool {
+    false
+}
+
+/// True if the secure desktop (UAC prompt, Ctrl+Alt+Del screen) has the input
+/// Normal processes cannot open the secure desktop, so failing to open the
+/// input desktop means it is active.
+#[cfg(windows)]
+fn secure_desktop_active() -> bool {
+    use windows::Win32::System::StationsAndDesktops::{
+        CloseDesktop, OpenInputDesktop, DESKTOP_CONTROL_FLAGS, DESKTOP_READOBJECTS,
+    };
+
+    unsafe {
+        match OpenInputDesktop(DESKTOP_CONTROL_FLAGS(0), false, DESKTOP_READOBJECTS) {
+            Ok(desktop) => {
+                let _ = CloseDesktop(desktop);
+                false
+            }
+            Err(_) => true,
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn secure_desktop_active() -> bool {
+    false
+}
@@ -0,0 +1,209 @@
+// frame_scan.rs - QR Codes and Links in the Captured Frame
+//
+// Someone shows a QR code or a link in a meeting that is being captured -
+// instead of reaching for a phone or typing it off the screen, RustFrame reads
+// it from the captured frame (tray → Scan for QR Codes and Links, or
+// Ctrl+Alt+Q):
+// - QR codes are decoded with rqrr from a grayscale copy of the frame
+// - Links are found in the text Windows OCR (Windows.Media.Ocr, the languages
+//   of the user profile) reads from the frame: http://, https:// and www.
+//
+// The scan runs on its own thread (OCR takes a moment on large regions). The
+// results are listed in tray → Scan Results, each with Open (web links only)
+// and Copy.
+
+use anyhow::Result;
+use log::{info, warn};
+
+/// Where a result was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FoundKind {
+    QrCode,
+    Link,
+}
+
+impl FoundKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            FoundKind::QrCode => "QR code",
+            FoundKind::Link => "Link",
+        }
+    }
+}
+
+/// A QR code's content or a link read from the frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Found {
+    pub kind: FoundKind,
+    pub text: String,
+}
+
+impl Found {
+    /// The web address to open, if the text is one ("www." gets https://)
+    pub fn url(&self) -> Option<String> {
+        let text = self.text.trim();
+        let lower = text.to_ascii_lowercase();
+        if lower.starts_with("http://") || lower.starts_with("https://") {
+            Some(text.to_string())
+        } else if lower.starts_with("www.") {
+            Some(format!("https://{}", text))
+        } else {
+            None
+        }
+    }
+}
+
+/// Scan a captured frame (0xAARRGGBB pixels) for QR codes and links
+pub fn scan(frame: &[u32], width: u32, height: u32) -> Vec<Found> {
+    let mut found: Vec<Found> = qr_codes(frame, width, height)
+        .into_iter()
+        .map(|text| Found {
+            kind: FoundKind::QrCode,
+            text,
+        })
+        .collect();
+    match ocr_text(frame, width, height) {
+        Ok(text) => {
+            for link in find_links(&text) {
+                // A link that is also in a QR code is only listed once
+                if !found.iter().any(|f| f.text == link) {
+                    found.push(Found {
+                        kind: FoundKind::Link,
+                        text: link,
+                    });
+                }
+            }
+        }
+        Err(e) => warn!("Text recognition unavailable: {:#}", e),
+    }
+    info!("Frame scan: {} result(s)", found.len());
+    found
+}
+
+/// Contents of the QR codes in the frame
+fn qr_codes(frame: &[u32], width: u32, height: u32) -> Vec<String> {
+    let (width, height) = (width as usize, height as usize);
+    if frame.len() < width * height {
+        return Vec::new();
+    }
+    let mut image = rqrr::PreparedImage::prepare_from_greyscale(width, height, |x, y| {
+        let pixel = frame[y * width + x];
+        let (r, g, b) = ((pixel >> 16) & 0xFF, (pixel >> 8) & 0xFF, pixel & 0xFF);
+        // Rec. 601 luma
+        ((r * 299 + g * 587 + b * 114) / 1000) as u8
+    });
+    let mut contents: Vec<String> = Vec::new();
+    for grid in image.detect_grids() {
+        match grid.decode() {
+            Ok((_, content)) if !contents.contains(&content) => contents.push(content),
+            Ok(_) => {}
+            Err(e) => info!("QR code found but not readable: {}", e),
+        }
+    }
+    contents
+}
+
+/// Links in OCR text: words starting with http://, https:// or www.
+/// Punctuation around them (brackets, quotes, a full stop) is dropped.
+pub fn find_links(text: &str) -> Vec<String> {
+    let mut links: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        let word = word.trim_start_matches(['(', '[', '<', '"', '\'']);
+        let lower = word.to_ascii_lowercase();
+        let is_link = ["http://", "https://", "www."]
+            .iter()
+            .any(|prefix| lower.starts_with(prefix) && lower.len() > prefix.len());
+        if !is_link {
+            continue;
+        }
+        let link = word.trim_end_matches(['.', ',', ';', ':', ')', ']', '>', '"', '\'', '!', '?']);
+        if !links.iter().any(|l| l == link) {
+            links.push(link.to_string());
+        }
+    }
+    links
+}
+
+/// Text Windows OCR reads from the frame, one line per text line
+#[cfg(windows)]
+fn ocr_text(frame: &[u32], width: u32, height: u32) -> Result<String> {
+    use anyhow::Context;
+    use windows::Graphics::Imaging::{BitmapAlphaMode, BitmapPixelFormat, SoftwareBitmap};
+    use windows::Media::Ocr::OcrEngine;
+    use windows::Storage::Streams::DataWriter;
+    use windows::Win32::System::Com::{CoInitializeEx, COINIT_MULTITHREADED};
+
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+    }
+    let engine = OcrEngine::TryCreateFromUserProfileLanguages()
+        .context("No OCR language installed for the user profile languages")?;
+
+    // Larger images are not accepted - shrink by a whole factor to fit
+    let max = OcrEngine::MaxImageDimension()?.max(1);
+    let step = ((width.max(height) + max - 1) / max).max(1) as usize;
+    let (w, h) = (width as usize / step, height as usize / step);
+    let mut bytes = Vec::with_capacity(w * h * 4);
+    for y in 0..h {
+        for x in 0..w {
+            let pixel = frame
+                .get(y * step * width as usize + x * step)
+                .copied()
+                .unwrap_or(0);
+            bytes.extend_from_slice(&(pixel | 0xFF00_0000).to_le_bytes());
+        }
+    }
+
+    let writer = DataWriter::new()?;
+    writer.WriteBytes(&bytes)?;
+    let buffer = writer.DetachBuffer()?;
+    let bitmap = SoftwareBitmap::CreateCopyWithAlphaFromBuffer(
+        &buffer,
+        BitmapPixelFormat::Bgra8,
+        w as i32,
+        h as i32,
+        BitmapAlphaMode::Premultiplied,
+    )?;
+    let result = engine.RecognizeAsync(&bitmap)?.join()?;
+    let mut text = String::new();
+    for line in result.Lines()? {
+        text.push_str(&line.Text()?.to_string_lossy());
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+#[cfg(not(windows))]
+fn ocr_text(_frame: &[u32], _width: u32, _height: u32) -> Result<String> {
+    anyhow::bail!("Text recognition is only supported on Windows")
+}
+
+/// Open a web address in the default browser
+#[cfg(windows)]
+pub fn open_url(url: &str) {
+    use windows::core::PCWSTR;
+    use windows::Win32::UI::Shell::ShellExecuteW;
+    use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+    let operation = crate::utils::wide_string("open");
+    let file = crate::utils::wide_string(url);
+    // Values above 32 mean success
+    let result = unsafe {
+        ShellExecuteW(
+            None,
+            PCWSTR(operation.as_ptr()),
+            PCWSTR(file.as_ptr()),
+            None,
+            None,
+            SW_SHOWNORMAL,
+        )
+    };
+    if result.0 as usize <= 32 {
+        warn!("Failed to open {}", url);
+    }
+}
+
+#[cfg(not(windows))]
+pub fn open_url(url: &str) {
+    warn!("Opening {} is only supported on Windows", url);
+}
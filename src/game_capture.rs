@@ -0,0 +1,340 @@
+// game_capture.rs - Capturing Exclusive-Fullscreen Games
+//
+// Windows.Graphics.Capture captures what the desktop compositor (DWM) shows.
+// A game in exclusive fullscreen bypasses DWM and presents straight to the
+// display, so WGC gets black frames - or no frames at all - for as long as the
+// game owns the monitor. Without help the output just turns black.
+//
+// DETECTION: the capture engine watches for a region that stays black (or
+// stops delivering frames while an exclusive-fullscreen application runs) for
+// BLACK_AFTER_MS, then finds out why:
+// - SHQueryUserNotificationState reports a Direct3D exclusive-fullscreen
+//   application
+// - Desktop Duplication marks frames whose protected (DRM) content is masked out
+// - Otherwise the content really is black, or its application keeps itself
+//   out of screen captures (SetWindowDisplayAffinity)
+// The engine logs the reason and hands it to the app as a Notice.
+//
+// FALLBACK: for exclusive fullscreen the engine switches to Desktop
+// Duplication (IDXGIOutputDuplication) of the output that contains the region,
+// which sees the image that is scanned out, and back to WGC once the game
+// leaves fullscreen. Desktop Duplication does not draw the mouse cursor, so the
+// cursor is missing from the output meanwhile.
+//
+// A shared-texture hook (a DLL injected into the game that copies its back
+// buffer, like the game capture of OBS) is not done: injecting code into other
+// processes trips anti-cheat systems and needs 32- and 64-bit helpers.
+
+use std::time::Instant;
+
+use windows::core::Interface;
+use windows::Win32::Foundation::{E_POINTER, POINT};
+use windows::Win32::Graphics::Direct3D11::{ID3D11Device, ID3D11Texture2D};
+use windows::Win32::Graphics::Dxgi::{
+    IDXGIDevice, IDXGIOutput1, IDXGIOutputDuplication, IDXGIResource, DXGI_ERROR_ACCESS_LOST,
+    DXGI_ERROR_WAIT_TIMEOUT, DXGI_OUTDUPL_FRAME_INFO,
+};
+use windows::Win32::Graphics::Gdi::{MonitorFromPoint, MONITOR_DEFAULTTONEAREST};
+
+use crate::capture::{CaptureError, Result};
+use crate::constants::game_capture;
+
+/// Why the captured region is black
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlackCause {
+    /// A game (or video player) owns the monitor in exclusive fullscreen
+    ExclusiveFullscreen,
+    /// Protected (DRM) video is masked out by Windows
+    ProtectedContent,
+    /// Windows does not tell: black content, or an application that keeps
+    /// itself out of screen captures
+    Unknown,
+}
+
+/// What the capture engine found out about a black region
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Notice {
+    pub cause: BlackCause,
+    /// The engine switched to Desktop Duplication
+    pub fallback: bool,
+}
+
+impl Notice {
+    /// Explanation for the user
+    pub fn message(&self) -> &'static str {
+        match (self.cause, self.fallback) {
+            (BlackCause::ExclusiveFullscreen, true) => {
+                "A game is running in exclusive fullscreen, which Windows.Graphics.Capture cannot see. RustFrame captures the monitor with Desktop Duplication until the game leaves fullscreen (without the mouse cursor)."
+            }
+            (BlackCause::ExclusiveFullscreen, false) => {
+                "The region is black because a game is running in exclusive fullscreen, which Windows.Graphics.Capture cannot see. Run the game in borderless windowed mode, or turn on the game capture fallback (Settings → Advanced)."
+            }
+            (BlackCause::ProtectedContent, _) => {
+                "The region is black because it shows protected (DRM) video, which Windows keeps out of every screen capture."
+            }
+            (BlackCause::Unknown, _) => {
+                "The region has been black for a while. If it should not be, the application shown there may keep itself out of screen captures."
+            }
+        }
+    }
+
+    /// Short form for the tray status line
+    pub fn status(&self) -> &'static str {
+        match (self.cause, self.fallback) {
+            (BlackCause::ExclusiveFullscreen, true) => "Desktop Duplication",
+            (BlackCause::ExclusiveFullscreen, false) => "Black: fullscreen game",
+            (BlackCause::ProtectedContent, _) => "Black: protected content",
+            (BlackCause::Unknown, _) => "Black",
+        }
+    }
+}
+
+/// Watches the frames of a capture for a region that stays black
+pub struct BlackDetector {
+    /// When the region turned black (None while it shows something)
+    black_since: Option<Instant>,
+    /// Cause found for the current black period (found once per period)
+    cause: Option<BlackCause>,
+    /// Last answer of SHQueryUserNotificationState and when it was asked
+    fullscreen: bool,
+    last_check: Option<Instant>,
+}
+
+impl BlackDetector {
+    pub fn new() -> Self {
+        Self {
+            black_since: None,
+            cause: None,
+            fullscreen: false,
+            last_check: None,
+        }
+    }
+
+    /// Start over (the region moved, or the capture method changed)
+    pub fn reset(&mut self) {
+        self.black_since = None;
+        self.cause = None;
+    }
+
+    /// A frame was read: the cause, once the region has been black for
+    /// BLACK_AFTER_MS
+    pub fn frame(&mut self, pixels: &[u32]) -> Option<BlackCause> {
+        if !is_black(pixels) {
+            self.reset();
+            return None;
+        }
+        self.black_since.get_or_insert_with(Instant::now);
+        self.check()
+    }
+
+    /// No new frame: the screen did not change - or the capture stalls under
+    /// an exclusive-fullscreen application, which counts as black
+    pub fn no_frame(&mut self) -> Option<BlackCause> {
+        if self.black_since.is_none() && self.exclusive_fullscreen() {
+            self.black_since = Some(Instant::now());
+        }
+        self.check()
+    }
+
+    /// The cause of the current black period (None while the region is not black)
+    pub fn cause(&self) -> Option<BlackCause> {
+        self.cause
+    }
+
+    /// Correct the cause found by `frame` / `no_frame`
+    pub fn set_cause(&mut self, cause: BlackCause) {
+        self.cause = Some(cause);
+    }
+
+    /// Whether an exclusive-fullscreen Direct3D application runs (asked at
+    /// most every CHECK_INTERVAL_MS)
+    pub fn exclusive_fullscreen(&mut self) -> bool {
+        let interval = std::time::Duration::from_millis(game_capture::CHECK_INTERVAL_MS);
+        if !self.last_check.is_some_and(|t| t.elapsed() < interval) {
+            self.fullscreen = exclusive_fullscreen();
+            self.last_check = Some(Instant::now());
+        }
+        self.fullscreen
+    }
+
+    fn check(&mut self) -> Option<BlackCause> {
+        let since = self.black_since?;
+        if self.cause.is_some()
+            || since.elapsed().as_millis() < game_capture::BLACK_AFTER_MS as u128
+        {
+            return None;
+        }
+        let cause = if self.exclusive_fullscreen() {
+            BlackCause::ExclusiveFullscreen
+        } else {
+            BlackCause::Unknown
+        };
+        self.cause = Some(cause);
+        Some(cause)
+    }
+}
+
+/// Whether every sampled pixel is pure black (what WGC delivers for content it
+/// cannot see - dark content is hardly ever exactly 0)
+pub fn is_black(pixels: &[u32]) -> bool {
+    let step = (pixels.len() / game_capture::SAMPLES).max(1);
+    !pixels.is_empty() && pixels.iter().step_by(step).all(|&p| p & 0x00FF_FFFF == 0)
+}
+
+/// Whether a Direct3D application runs in exclusive fullscreen
+pub fn exclusive_fullscreen() -> bool {
+    use windows::Win32::UI::Shell::{SHQueryUserNotificationState, QUNS_RUNNING_D3D_FULL_SCREEN};
+
+    unsafe { SHQueryUserNotificationState() }
+        .is_ok_and(|state| state == QUNS_RUNNING_D3D_FULL_SCREEN)
+}
+
+/// Whether Desktop Duplication of the monitor at `point` masks out protected content
+pub fn protected_content(device: &ID3D11Device, point: (i32, i32)) -> bool {
+    let Ok(mut duplication) = DesktopDuplication::new(device, point) else {
+        return false;
+    };
+    let protected = matches!(
+        duplication.acquire(game_capture::PROBE_TIMEOUT_MS),
+        Ok(Some(frame)) if frame.protected
+    );
+    duplication.release();
+    protected
+}
+
+/// A desktop image acquired from Desktop Duplication
+pub struct DuplicatedFrame {
+    pub texture: ID3D11Texture2D,
+    /// When it was presented (QueryPerformanceCounter time in 100 ns units)
+    pub time: i64,
+    /// Protected content was masked out
+    pub protected: bool,
+}
+
+/// Desktop Duplication of the output that shows a point
+/// Mode changes (a game entering or leaving exclusive fullscreen) end a
+/// duplication; it is created again on the next acquire.
+pub struct DesktopDuplication {
+    device: ID3D11Device,
+    output: IDXGIOutput1,
+    duplication: Option<IDXGIOutputDuplication>,
+    /// A frame is acquired and has to be released
+    acquired: bool,
+}
+
+impl DesktopDuplication {
+    /// Duplicate the output of the capture GPU that shows `point`
+    pub fn new(device: &ID3D11Device, point: (i32, i32)) -> Result<Self> {
+        let monitor = unsafe {
+            MonitorFromPoint(
+                POINT {
+                    x: point.0,
+                    y: point.1,
+                },
+                MONITOR_DEFAULTTONEAREST,
+            )
+        };
+        let adapter = device
+            .cast::<IDXGIDevice>()
+            .and_then(|device| unsafe { device.GetAdapter() })
+            .map_err(CaptureError::at("IDXGIDevice::GetAdapter"))?;
+        // EnumOutputs fails past the last output
+        let output = (0..)
+            .map_while(|index| unsafe { adapter.EnumOutputs(index) }.ok())
+            .find(|output| unsafe { output.GetDesc() }.is_ok_and(|d| d.Monitor == monitor))
+            .ok_or_else(|| {
+                CaptureError::InvalidRegion(
+                    "the monitor is not connected to the capture GPU".to_string(),
+                )
+            })?
+            .cast::<IDXGIOutput1>()
+            .map_err(CaptureError::at("IDXGIOutput to IDXGIOutput1 cast"))?;
+
+        let mut duplication = Self {
+            device: device.clone(),
+            output,
+            duplication: None,
+            acquired: false,
+        };
+        duplication.duplicate()?;
+        Ok(duplication)
+    }
+
+    fn duplicate(&mut self) -> Result<()> {
+        let duplication = unsafe { self.output.DuplicateOutput(&self.device) }
+            .map_err(CaptureError::at("IDXGIOutput1::DuplicateOutput"))?;
+        self.duplication = Some(duplication);
+        Ok(())
+    }
+
+    /// The newest desktop image, None when it did not change within
+    /// `timeout_ms` - `release` it once it is copied
+    pub fn acquire(&mut self, timeout_ms: u32) -> Result<Option<DuplicatedFrame>> {
+        self.release();
+        if self.duplication.is_none() {
+            // Recreating fails while the mode change is still going on
+            if self.duplicate().is_err() {
+                return Ok(None);
+            }
+        }
+        let Some(duplication) = &self.duplication else {
+            return Ok(None);
+        };
+
+        let mut info = DXGI_OUTDUPL_FRAME_INFO::default();
+        let mut resource: Option<IDXGIResource> = None;
+        match unsafe { duplication.AcquireNextFrame(timeout_ms, &mut info, &mut resource) } {
+            Ok(()) => {}
+            Err(e) if e.code() == DXGI_ERROR_WAIT_TIMEOUT => return Ok(None),
+            Err(e) if e.code() == DXGI_ERROR_ACCESS_LOST => {
+                log::info!("Desktop Duplication lost (display mode changed), duplicating again");
+                self.duplication = None;
+                return Ok(None);
+            }
+            Err(e) => return Err(CaptureError::at("IDXGIOutputDuplication::AcquireNextFrame")(e)),
+        }
+        self.acquired = true;
+
+        // Only the mouse moved
+        if info.LastPresentTime == 0 {
+            return Ok(None);
+        }
+        let texture = resource
+            .ok_or_else(|| {
+                CaptureError::at("IDXGIOutputDuplication::AcquireNextFrame")(E_POINTER.into())
+            })?
+            .cast::<ID3D11Texture2D>()
+            .map_err(CaptureError::at("IDXGIResource to ID3D11Texture2D cast"))?;
+        Ok(Some(DuplicatedFrame {
+            texture,
+            time: qpc_to_100ns(info.LastPresentTime),
+            protected: info.ProtectedContentMaskedOut.as_bool(),
+        }))
+    }
+
+    /// Hand the acquired frame back to Windows
+    pub fn release(&mut self) {
+        if std::mem::take(&mut self.acquired) {
+            if let Some(duplication) = &self.duplication {
+                let _ = unsafe { duplication.ReleaseFrame() };
+            }
+        }
+    }
+}
+
+impl Drop for DesktopDuplication {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// QueryPerformanceCounter ticks -> 100 ns units (the unit of WGC frame times)
+fn qpc_to_100ns(ticks: i64) -> i64 {
+    use windows::Win32::System::Performance::QueryPerformanceFrequency;
+
+    let mut frequency = 0;
+    match unsafe { QueryPerformanceFrequency(&mut frequency) } {
+        Ok(()) if frequency > 0 => (ticks as i128 * 10_000_000 / frequency as i128) as i64,
+        _ => 0,
+    }
+}
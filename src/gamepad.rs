@@ -0,0 +1,125 @@
+// gamepad.rs - Gamepad Control (XInput)
+//
+// Lets a presenter walking around with a game controller drive RustFrame
+// without touching the keyboard. Buttons of the first connected XInput
+// controller (Xbox and compatible pads) are mapped to:
+// - START: start capture (with the countdown) / stop capture
+// - B:     pause screen on/off
+// - RB/LB: next / previous saved scene
+// - X:     drop a marker (see markers.rs)
+//
+// The controller is polled from about_to_wait. Polling an empty XInput slot is
+// slow, so while no controller is connected the slots are only scanned every
+// few seconds.
+
+use log::info;
+use std::time::{Duration, Instant};
+
+use crate::constants::gamepad;
+
+/// Actions triggered by gamepad buttons
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamepadAction {
+    /// Start capture (or cancel the countdown), stop capture while capturing
+    StartStop,
+    TogglePause,
+    NextScene,
+    PreviousScene,
+    DropMarker,
+}
+
+/// Button → action mapping (XINPUT_GAMEPAD_* button flags)
+#[cfg(windows)]
+const BUTTONS: [(u16, GamepadAction); 5] = {
+    use windows::Win32::UI::Input::XboxController::{
+        XINPUT_GAMEPAD_B, XINPUT_GAMEPAD_LEFT_SHOULDER, XINPUT_GAMEPAD_RIGHT_SHOULDER,
+        XINPUT_GAMEPAD_START, XINPUT_GAMEPAD_X,
+    };
+    [
+        (XINPUT_GAMEPAD_START.0, GamepadAction::StartStop),
+        (XINPUT_GAMEPAD_B.0, GamepadAction::TogglePause),
+        (XINPUT_GAMEPAD_RIGHT_SHOULDER.0, GamepadAction::NextScene),
+        (XINPUT_GAMEPAD_LEFT_SHOULDER.0, GamepadAction::PreviousScene),
+        (XINPUT_GAMEPAD_X.0, GamepadAction::DropMarker),
+    ]
+};
+
+/// Polls one XInput controller and reports newly pressed buttons
+pub struct Gamepad {
+    /// XInput user index of the connected controller
+    slot: Option<u32>,
+    /// Buttons held down at the last poll
+    buttons: u16,
+    /// When the empty slots were last scanned
+    last_scan: Option<Instant>,
+}
+
+impl Gamepad {
+    pub fn new() -> Self {
+        info!("Gamepad control enabled");
+        Self {
+            slot: None,
+            buttons: 0,
+            last_scan: None,
+        }
+    }
+
+    /// Actions for the buttons pressed since the last poll
+    #[cfg(windows)]
+    pub fn poll(&mut self) -> Vec<GamepadAction> {
+        use windows::Win32::UI::Input::XboxController::XUSER_MAX_COUNT;
+
+        // STEP 1: Find a controller (rate-limited while none is connected)
+        if self.slot.is_none() {
+            if self
+                .last_scan
+                .is_some_and(|t| t.elapsed() < Duration::from_millis(gamepad::RESCAN_INTERVAL_MS))
+            {
+                return Vec::new();
+            }
+            self.last_scan = Some(Instant::now());
+            self.slot = (0..XUSER_MAX_COUNT).find(|&slot| read_buttons(slot).is_some());
+            match self.slot {
+                Some(slot) => {
+                    info!("Gamepad connected (XInput slot {})", slot);
+                    // Buttons already held when connecting do not fire
+                    self.buttons = read_buttons(slot).unwrap_or(0);
+                }
+                None => return Vec::new(),
+            }
+        }
+
+        // STEP 2: Read the buttons
+        let Some(buttons) = self.slot.and_then(read_buttons) else {
+            info!("Gamepad disconnected");
+            self.slot = None;
+            self.buttons = 0;
+            return Vec::new();
+        };
+
+        // STEP 3: Report buttons that went down since the last poll
+        let pressed = buttons & !self.buttons;
+        self.buttons = buttons;
+        BUTTONS
+            .iter()
+            .filter(|(flag, _)| pressed & flag != 0)
+            .map(|&(_, action)| action)
+            .collect()
+    }
+
+    #[cfg(not(windows))]
+    pub fn poll(&mut self) -> Vec<GamepadAction> {
+        Vec::new()
+    }
+}
+
+/// Button flags of the controller in a slot (None if no controller is connected)
+#[cfg(windows)]
+fn read_buttons(slot: u32) -> Option<u16> {
+    use windows::Win32::Foundation::ERROR_SUCCESS;
+    use windows::Win32::UI::Input::XboxController::{XInputGetState, XINPUT_STATE};
+
+    let mut state = XINPUT_STATE::default();
+    let result = unsafe { XInputGetState(slot, &mut state) };
+    (result == ERROR_SUCCESS.0).then_some(state.Gamepad.wButtons.0)
+}
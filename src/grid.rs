@@ -0,0 +1,167 @@
+// grid.rs - Composition Grid Overlay
+//
+// Draws guide lines (rule of thirds, an even grid or a center cross) to help
+// frame the capture region. The grid is always shown on the selection overlay
+// while it is enabled, and can optionally be burned into the output as well -
+// useful when the recording will be cropped or matted later.
+
+use serde::{Deserialize, Serialize};
+
+use crate::bitmap_font::Canvas;
+use crate::constants::grid;
+
+/// Which guide lines to draw
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GridStyle {
+    Off,
+    /// Two lines each way, splitting the area into thirds
+    Thirds,
+    /// Even grid with the given number of columns and rows
+    Grid {
+        columns: u32,
+        rows: u32,
+    },
+    /// One line each way through the center
+    CenterCross,
+}
+
+impl GridStyle {
+    /// Short label for the tray menu and logs
+    pub fn label(self) -> String {
+        match self {
+            GridStyle::Off => "Off".to_string(),
+            GridStyle::Thirds => "Rule of Thirds".to_string(),
+            GridStyle::Grid { columns, rows } => format!("{}x{}", columns, rows),
+            GridStyle::CenterCross => "Center Cross".to_string(),
+        }
+    }
+
+    /// Next style in the cycle Off -> Thirds -> Grid -> Center Cross -> Off
+    pub fn next(self) -> Self {
+        match self {
+            GridStyle::Off => GridStyle::Thirds,
+            GridStyle::Thirds => GridStyle::Grid {
+                columns: grid::COLUMNS,
+                rows: grid::ROWS,
+            },
+            GridStyle::Grid { .. } => GridStyle::CenterCross,
+            GridStyle::CenterCross => GridStyle::Off,
+        }
+    }
+
+    /// Number of (columns, rows) the area is divided into
+    fn divisions(self) -> (u32, u32) {
+        match self {
+            GridStyle::Off => (1, 1),
+            GridStyle::Thirds => (3, 3),
+            GridStyle::Grid { columns, rows } => (columns.max(1), rows.max(1)),
+            GridStyle::CenterCross => (2, 2),
+        }
+    }
+
+    /// Per-pixel masks of which columns and rows are covered by a guide line
+    /// Returns (column mask of `width` entries, row mask of `height` entries)
+    pub fn line_masks(self, width: i32, height: i32) -> (Vec<bool>, Vec<bool>) {
+        let (columns, rows) = self.divisions();
+        (
+            Self::axis_mask(width, columns),
+            Self::axis_mask(height, rows),
+        )
+    }
+
+    fn axis_mask(length: i32, divisions: u32) -> Vec<bool> {
+        let mut mask = vec![false; length.max(0) as usize];
+        for i in 1..divisions as i64 {
+            let center = (length as i64 * i / divisions as i64) as i32;
+            // Lines are LINE_WIDTH pixels wide, centered on the division point
+            let start = center - grid::LINE_WIDTH / 2;
+            for p in start..start + grid::LINE_WIDTH {
+                if p >= 0 && p < length {
+                    mask[p as usize] = true;
+                }
+            }
+        }
+        mask
+    }
+}
+
+/// Grid state for the current session
+pub struct CompositionGrid {
+    style: GridStyle,
+    /// Also draw the grid into the captured output
+    in_output: bool,
+    /// Bumped on every visible change so the renderer knows when to recomposite
+    revision: u64,
+}
+
+impl CompositionGrid {
+    pub fn new() -> Self {
+        Self {
+            style: GridStyle::Off,
+            in_output: false,
+            revision: 0,
+        }
+    }
+
+    pub fn style(&self) -> GridStyle {
+        self.style
+    }
+
+    /// Advance to the next grid style
+    pub fn cycle_style(&mut self) {
+        self.style = self.style.next();
+        self.revision += 1;
+    }
+
+    pub fn set_style(&mut self, style: GridStyle) {
+        if self.style != style {
+            self.style = style;
+            self.revision += 1;
+        }
+    }
+
+    pub fn in_output(&self) -> bool {
+        self.in_output
+    }
+
+    pub fn set_in_output(&mut self, in_output: bool) {
+        if self.in_output != in_output {
+            self.in_output = in_output;
+            self.revision += 1;
+        }
+    }
+
+    /// True if the grid should be drawn into the output frame
+    pub fn draws_into_output(&self) -> bool {
+        self.in_output && self.style != GridStyle::Off
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Draw the grid into the captured frame
+    /// Lines are blended 50/50 with the frame so they stay visible on both
+    /// light and dark content without hiding it.
+    pub fn apply(&self, canvas: &mut Canvas) {
+        if !self.draws_into_output() {
+            return;
+        }
+
+        let (columns, rows) = self.style.line_masks(canvas.width, canvas.height);
+        for y in 0..canvas.height {
+            let row_line = rows[y as usize];
+            for x in 0..canvas.width {
+                if row_line || columns[x as usize] {
+                    let idx = (y * canvas.width + x) as usize;
+                    let p = canvas.pixels[idx];
+                    // Average each channel with the line color
+                    let blended =
+                        ((p >> 1) & 0x007F7F7F) + ((grid::OUTPUT_COLOR >> 1) & 0x007F7F7F);
+                    canvas.pixels[idx] = 0xFF000000 | blended;
+                }
+            }
+        }
+    }
+}
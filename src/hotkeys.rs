@@ -0,0 +1,523 @@
+// hotkeys.rs - System-Wide Hotkeys
+//
+// The overlay only receives keyboard input while it has focus, which is rarely
+// the case during capture (the user is working in the captured application).
+// Actions that must work at any time are registered as global hotkeys instead.
+//
+// Events are delivered through GlobalHotKeyEvent::receiver() and polled from
+// about_to_wait, the same way tray menu events are handled.
+//
+// Every action can be rebound in Settings → Shortcuts. Only the bindings that
+// differ from the defaults are stored in settings.json ("hotkeys": action id →
+// "Ctrl+Alt+S", an empty string unbinds the action).
+
+use anyhow::{anyhow, Result};
+use global_hotkey::{
+    hotkey::{Code, HotKey, Modifiers},
+    GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState,
+};
+use log::{info, warn};
+use std::collections::BTreeMap;
+
+/// Actions that can be triggered by a global hotkey
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyAction {
+    /// Enter/leave annotation draw mode (Ctrl+Shift+D)
+    ToggleDrawMode,
+    /// Remove all annotations (Ctrl+Shift+X)
+    ClearAnnotations,
+    /// Enter/leave ruler measure mode (Ctrl+Shift+M)
+    ToggleMeasureMode,
+    /// Enter/leave privacy region redact mode (Ctrl+Shift+R)
+    ToggleRedactMode,
+    /// Enter/leave the color picker (Ctrl+Shift+E)
+    ToggleColorPicker,
+    /// Exclude the window under the cursor from the output, or include it again (Ctrl+Shift+W)
+    ToggleExcludeWindow,
+    /// Turn the presentation spotlight on/off (Ctrl+Alt+S)
+    ToggleSpotlight,
+    /// Pin the spotlight in place / let it follow the cursor (Ctrl+Alt+P)
+    ToggleSpotlightPin,
+    /// Switch spotlight shape between ellipse and rectangle (Ctrl+Alt+O)
+    ToggleSpotlightShape,
+    /// Enlarge the spotlight focus area (Ctrl+Alt+Up)
+    GrowSpotlight,
+    /// Shrink the spotlight focus area (Ctrl+Alt+Down)
+    ShrinkSpotlight,
+    /// Turn click and drag highlights on/off (Ctrl+Alt+K)
+    ToggleClickHighlight,
+    /// Show the cursor in the output while the keys are held (Ctrl+Alt+C)
+    HoldToShowCursor,
+    /// Record keyboard and mouse input during captures on/off (Ctrl+Alt+I)
+    ToggleInputRecording,
+    /// Turn the magnifier on/off (Ctrl+Alt+M)
+    ToggleMagnifier,
+    /// Switch the magnifier between local window and output (Ctrl+Alt+L)
+    ToggleMagnifierMode,
+    /// Increase magnifier zoom (Ctrl+Alt+=)
+    ZoomIn,
+    /// Decrease magnifier zoom (Ctrl+Alt+-)
+    ZoomOut,
+    /// Start/pause the on-screen timer (Ctrl+Alt+T)
+    ToggleTimer,
+    /// Reset and hide the on-screen timer (Ctrl+Alt+R)
+    ResetTimer,
+    /// Pause the output and show the pause screen (Ctrl+Alt+B)
+    TogglePause,
+    /// Freeze the output on the current frame (Ctrl+Alt+F)
+    ToggleFreeze,
+    /// Scan the captured frame for QR codes and links (Ctrl+Alt+Q)
+    ScanFrame,
+    /// Compare the output with a reference frame / stop comparing (Ctrl+Alt+D)
+    ToggleFrameDiff,
+    /// Switch to a saved scene (Ctrl+Alt+1 .. Ctrl+Alt+4)
+    SwitchScene(usize),
+    /// Save the current setup as a scene (Ctrl+Alt+Shift+1 .. Ctrl+Alt+Shift+4)
+    SaveScene(usize),
+}
+
+impl HotkeyAction {
+    /// All actions, in registration order
+    pub const ALL: &'static [HotkeyAction] = &[
+        HotkeyAction::ToggleDrawMode,
+        HotkeyAction::ClearAnnotations,
+        HotkeyAction::ToggleMeasureMode,
+        HotkeyAction::ToggleRedactMode,
+        HotkeyAction::ToggleColorPicker,
+        HotkeyAction::ToggleExcludeWindow,
+        HotkeyAction::ToggleSpotlight,
+        HotkeyAction::ToggleSpotlightPin,
+        HotkeyAction::ToggleSpotlightShape,
+        HotkeyAction::GrowSpotlight,
+        HotkeyAction::ShrinkSpotlight,
+        HotkeyAction::ToggleClickHighlight,
+        HotkeyAction::HoldToShowCursor,
+        HotkeyAction::ToggleInputRecording,
+        HotkeyAction::ToggleMagnifier,
+        HotkeyAction::ToggleMagnifierMode,
+        HotkeyAction::ZoomIn,
+        HotkeyAction::ZoomOut,
+        HotkeyAction::ToggleTimer,
+        HotkeyAction::ResetTimer,
+        HotkeyAction::TogglePause,
+        HotkeyAction::ToggleFreeze,
+        HotkeyAction::ScanFrame,
+        HotkeyAction::ToggleFrameDiff,
+        HotkeyAction::SwitchScene(0),
+        HotkeyAction::SwitchScene(1),
+        HotkeyAction::SwitchScene(2),
+        HotkeyAction::SwitchScene(3),
+        HotkeyAction::SaveScene(0),
+        HotkeyAction::SaveScene(1),
+        HotkeyAction::SaveScene(2),
+        HotkeyAction::SaveScene(3),
+    ];
+
+    /// Stable name in settings.json, e.g. "toggle_spotlight" or "switch_scene_2"
+    pub fn id(self) -> String {
+        match self {
+            HotkeyAction::ToggleDrawMode => "toggle_draw_mode".to_string(),
+            HotkeyAction::ClearAnnotations => "clear_annotations".to_string(),
+            HotkeyAction::ToggleMeasureMode => "toggle_measure_mode".to_string(),
+            HotkeyAction::ToggleRedactMode => "toggle_redact_mode".to_string(),
+            HotkeyAction::ToggleColorPicker => "toggle_color_picker".to_string(),
+            HotkeyAction::ToggleExcludeWindow => "toggle_exclude_window".to_string(),
+            HotkeyAction::ToggleSpotlight => "toggle_spotlight".to_string(),
+            HotkeyAction::ToggleSpotlightPin => "toggle_spotlight_pin".to_string(),
+            HotkeyAction::ToggleSpotlightShape => "toggle_spotlight_shape".to_string(),
+            HotkeyAction::GrowSpotlight => "grow_spotlight".to_string(),
+            HotkeyAction::ShrinkSpotlight => "shrink_spotlight".to_string(),
+            HotkeyAction::ToggleClickHighlight => "toggle_click_highlight".to_string(),
+            HotkeyAction::HoldToShowCursor => "hold_to_show_cursor".to_string(),
+            HotkeyAction::ToggleInputRecording => "toggle_input_recording".to_string(),
+            HotkeyAction::ToggleMagnifier => "toggle_magnifier".to_string(),
+            HotkeyAction::ToggleMagnifierMode => "toggle_magnifier_mode".to_string(),
+            HotkeyAction::ZoomIn => "zoom_in".to_string(),
+            HotkeyAction::ZoomOut => "zoom_out".to_string(),
+            HotkeyAction::ToggleTimer => "toggle_timer".to_string(),
+            HotkeyAction::ResetTimer => "reset_timer".to_string(),
+            HotkeyAction::TogglePause => "toggle_pause".to_string(),
+            HotkeyAction::ToggleFreeze => "toggle_freeze".to_string(),
+            HotkeyAction::ScanFrame => "scan_frame".to_string(),
+            HotkeyAction::ToggleFrameDiff => "toggle_frame_diff".to_string(),
+            HotkeyAction::SwitchScene(slot) => format!("switch_scene_{}", slot + 1),
+            HotkeyAction::SaveScene(slot) => format!("save_scene_{}", slot + 1),
+        }
+    }
+
+    /// Action for a settings.json name (see `id`)
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|a| a.id().eq_ignore_ascii_case(id.trim()))
+    }
+
+    /// Name shown in Settings → Shortcuts
+    pub fn label(self) -> String {
+        match self {
+            HotkeyAction::ToggleDrawMode => "Draw mode".to_string(),
+            HotkeyAction::ClearAnnotations => "Clear annotations".to_string(),
+            HotkeyAction::ToggleMeasureMode => "Measure mode".to_string(),
+            HotkeyAction::ToggleRedactMode => "Redact mode".to_string(),
+            HotkeyAction::ToggleColorPicker => "Color picker".to_string(),
+            HotkeyAction::ToggleExcludeWindow => "Exclude window".to_string(),
+            HotkeyAction::ToggleSpotlight => "Spotlight".to_string(),
+            HotkeyAction::ToggleSpotlightPin => "Pin spotlight".to_string(),
+            HotkeyAction::ToggleSpotlightShape => "Spotlight shape".to_string(),
+            HotkeyAction::GrowSpotlight => "Grow spotlight".to_string(),
+            HotkeyAction::ShrinkSpotlight => "Shrink spotlight".to_string(),
+            HotkeyAction::ToggleClickHighlight => "Click highlights".to_string(),
+            HotkeyAction::HoldToShowCursor => "Hold to show cursor".to_string(),
+            HotkeyAction::ToggleInputRecording => "Record input".to_string(),
+            HotkeyAction::ToggleMagnifier => "Magnifier".to_string(),
+            HotkeyAction::ToggleMagnifierMode => "Magnifier mode".to_string(),
+            HotkeyAction::ZoomIn => "Zoom in".to_string(),
+            HotkeyAction::ZoomOut => "Zoom out".to_string(),
+            HotkeyAction::ToggleTimer => "Start / pause timer".to_string(),
+            HotkeyAction::ResetTimer => "Reset timer".to_string(),
+            HotkeyAction::TogglePause => "Pause output".to_string(),
+            HotkeyAction::ToggleFreeze => "Freeze output".to_string(),
+            HotkeyAction::ScanFrame => "Scan for QR codes and links".to_string(),
+            HotkeyAction::ToggleFrameDiff => "Compare frames".to_string(),
+            HotkeyAction::SwitchScene(slot) => format!("Switch to scene {}", slot + 1),
+            HotkeyAction::SaveScene(slot) => format!("Save scene {}", slot + 1),
+        }
+    }
+
+    /// Default key combination for this action
+    pub fn default_hotkey(self) -> HotKey {
+        let ctrl_shift = Some(Modifiers::CONTROL | Modifiers::SHIFT);
+        let ctrl_alt = Some(Modifiers::CONTROL | Modifiers::ALT);
+        let ctrl_alt_shift = Some(Modifiers::CONTROL | Modifiers::ALT | Modifiers::SHIFT);
+        let scene_keys = [Code::Digit1, Code::Digit2, Code::Digit3, Code::Digit4];
+        match self {
+            HotkeyAction::ToggleDrawMode => HotKey::new(ctrl_shift, Code::KeyD),
+            HotkeyAction::ClearAnnotations => HotKey::new(ctrl_shift, Code::KeyX),
+            HotkeyAction::ToggleMeasureMode => HotKey::new(ctrl_shift, Code::KeyM),
+            HotkeyAction::ToggleRedactMode => HotKey::new(ctrl_shift, Code::KeyR),
+            HotkeyAction::ToggleColorPicker => HotKey::new(ctrl_shift, Code::KeyE),
+            HotkeyAction::ToggleExcludeWindow => HotKey::new(ctrl_shift, Code::KeyW),
+            HotkeyAction::ToggleSpotlight => HotKey::new(ctrl_alt, Code::KeyS),
+            HotkeyAction::ToggleSpotlightPin => HotKey::new(ctrl_alt, Code::KeyP),
+            HotkeyAction::ToggleSpotlightShape => HotKey::new(ctrl_alt, Code::KeyO),
+            HotkeyAction::GrowSpotlight => HotKey::new(ctrl_alt, Code::ArrowUp),
+            HotkeyAction::ShrinkSpotlight => HotKey::new(ctrl_alt, Code::ArrowDown),
+            HotkeyAction::ToggleClickHighlight => HotKey::new(ctrl_alt, Code::KeyK),
+            HotkeyAction::HoldToShowCursor => HotKey::new(ctrl_alt, Code::KeyC),
+            HotkeyAction::ToggleInputRecording => HotKey::new(ctrl_alt, Code::KeyI),
+            HotkeyAction::ToggleMagnifier => HotKey::new(ctrl_alt, Code::KeyM),
+            HotkeyAction::ToggleMagnifierMode => HotKey::new(ctrl_alt, Code::KeyL),
+            HotkeyAction::ZoomIn => HotKey::new(ctrl_alt, Code::Equal),
+            HotkeyAction::ZoomOut => HotKey::new(ctrl_alt, Code::Minus),
+            HotkeyAction::ToggleTimer => HotKey::new(ctrl_alt, Code::KeyT),
+            HotkeyAction::ResetTimer => HotKey::new(ctrl_alt, Code::KeyR),
+            HotkeyAction::TogglePause => HotKey::new(ctrl_alt, Code::KeyB),
+            HotkeyAction::ToggleFreeze => HotKey::new(ctrl_alt, Code::KeyF),
+            HotkeyAction::ScanFrame => HotKey::new(ctrl_alt, Code::KeyQ),
+            HotkeyAction::ToggleFrameDiff => HotKey::new(ctrl_alt, Code::KeyD),
+            HotkeyAction::SwitchScene(slot) => HotKey::new(ctrl_alt, scene_keys[slot]),
+            HotkeyAction::SaveScene(slot) => HotKey::new(ctrl_alt_shift, scene_keys[slot]),
+        }
+    }
+
+    /// Actions that last while the keys are held (their release is reported too)
+    pub fn is_held(self) -> bool {
+        matches!(self, HotkeyAction::HoldToShowCursor)
+    }
+}
+
+/// Key combination of every action: the defaults with the overrides from
+/// settings.json applied (None = unbound)
+/// An override that cannot be parsed is ignored with a warning
+pub fn resolve(overrides: &BTreeMap<String, String>) -> Vec<(HotkeyAction, Option<HotKey>)> {
+    HotkeyAction::ALL
+        .iter()
+        .map(|&action| {
+            let hotkey = match overrides.get(&action.id()) {
+                None => Some(action.default_hotkey()),
+                Some(text) if text.trim().is_empty() => None,
+                Some(text) => match parse_hotkey(text) {
+                    Ok(hotkey) => Some(hotkey),
+                    Err(e) => {
+                        warn!("Ignoring shortcut for {}: {}", action.id(), e);
+                        Some(action.default_hotkey())
+                    }
+                },
+            };
+            (action, hotkey)
+        })
+        .collect()
+}
+
+/// The bindings that differ from the defaults, as stored in settings.json
+pub fn overrides(bindings: &[(HotkeyAction, Option<HotKey>)]) -> BTreeMap<String, String> {
+    bindings
+        .iter()
+        .filter(|(action, hotkey)| *hotkey != Some(action.default_hotkey()))
+        .map(|(action, hotkey)| (action.id(), hotkey.map(format_hotkey).unwrap_or_default()))
+        .collect()
+}
+
+/// Pairs of actions bound to the same key combination
+pub fn conflicts(bindings: &[(HotkeyAction, Option<HotKey>)]) -> Vec<(HotkeyAction, HotkeyAction)> {
+    let mut pairs = Vec::new();
+    for (i, (action, hotkey)) in bindings.iter().enumerate() {
+        let Some(hotkey) = hotkey else {
+            continue;
+        };
+        for (other, other_hotkey) in &bindings[i + 1..] {
+            if other_hotkey.as_ref() == Some(hotkey) {
+                pairs.push((*action, *other));
+            }
+        }
+    }
+    pairs
+}
+
+/// "Ctrl+Alt+S" style text for settings.json and Settings → Shortcuts
+pub fn format_hotkey(hotkey: HotKey) -> String {
+    let mut text = String::new();
+    for (modifier, name) in [
+        (Modifiers::CONTROL, "Ctrl+"),
+        (Modifiers::ALT, "Alt+"),
+        (Modifiers::SHIFT, "Shift+"),
+        (Modifiers::SUPER, "Win+"),
+    ] {
+        if hotkey.mods.contains(modifier) {
+            text.push_str(name);
+        }
+    }
+
+    // Code names are "KeyS", "Digit1", "ArrowUp"... - show the plain key
+    let key = match hotkey.key {
+        Code::Equal => "=".to_string(),
+        Code::Minus => "-".to_string(),
+        code => {
+            let name = code.to_string();
+            ["Key", "Digit", "Arrow"]
+                .iter()
+                .find_map(|prefix| name.strip_prefix(prefix).map(str::to_string))
+                .unwrap_or(name)
+        }
+    };
+    text.push_str(&key);
+    text
+}
+
+/// Parse "Ctrl+Alt+S" (the global-hotkey form "control+alt+KeyS" works too)
+pub fn parse_hotkey(text: &str) -> Result<HotKey> {
+    let text = text
+        .split('+')
+        .map(|token| {
+            if token.trim().eq_ignore_ascii_case("win") {
+                "super"
+            } else {
+                token.trim()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("+");
+    text.parse()
+        .map_err(|e| anyhow!("Invalid shortcut \"{}\": {}", text, e))
+}
+
+/// Windows virtual-key codes of the keys that can be bound
+/// (used by the hotkey control in Settings → Shortcuts)
+const VIRTUAL_KEYS: &[(u16, Code)] = &[
+    (0x41, Code::KeyA),
+    (0x42, Code::KeyB),
+    (0x43, Code::KeyC),
+    (0x44, Code::KeyD),
+    (0x45, Code::KeyE),
+    (0x46, Code::KeyF),
+    (0x47, Code::KeyG),
+    (0x48, Code::KeyH),
+    (0x49, Code::KeyI),
+    (0x4A, Code::KeyJ),
+    (0x4B, Code::KeyK),
+    (0x4C, Code::KeyL),
+    (0x4D, Code::KeyM),
+    (0x4E, Code::KeyN),
+    (0x4F, Code::KeyO),
+    (0x50, Code::KeyP),
+    (0x51, Code::KeyQ),
+    (0x52, Code::KeyR),
+    (0x53, Code::KeyS),
+    (0x54, Code::KeyT),
+    (0x55, Code::KeyU),
+    (0x56, Code::KeyV),
+    (0x57, Code::KeyW),
+    (0x58, Code::KeyX),
+    (0x59, Code::KeyY),
+    (0x5A, Code::KeyZ),
+    (0x30, Code::Digit0),
+    (0x31, Code::Digit1),
+    (0x32, Code::Digit2),
+    (0x33, Code::Digit3),
+    (0x34, Code::Digit4),
+    (0x35, Code::Digit5),
+    (0x36, Code::Digit6),
+    (0x37, Code::Digit7),
+    (0x38, Code::Digit8),
+    (0x39, Code::Digit9),
+    (0x70, Code::F1),
+    (0x71, Code::F2),
+    (0x72, Code::F3),
+    (0x73, Code::F4),
+    (0x74, Code::F5),
+    (0x75, Code::F6),
+    (0x76, Code::F7),
+    (0x77, Code::F8),
+    (0x78, Code::F9),
+    (0x79, Code::F10),
+    (0x7A, Code::F11),
+    (0x7B, Code::F12),
+    (0x7C, Code::F13),
+    (0x7D, Code::F14),
+    (0x7E, Code::F15),
+    (0x7F, Code::F16),
+    (0x80, Code::F17),
+    (0x81, Code::F18),
+    (0x82, Code::F19),
+    (0x83, Code::F20),
+    (0x84, Code::F21),
+    (0x85, Code::F22),
+    (0x86, Code::F23),
+    (0x87, Code::F24),
+    (0x60, Code::Numpad0),
+    (0x61, Code::Numpad1),
+    (0x62, Code::Numpad2),
+    (0x63, Code::Numpad3),
+    (0x64, Code::Numpad4),
+    (0x65, Code::Numpad5),
+    (0x66, Code::Numpad6),
+    (0x67, Code::Numpad7),
+    (0x68, Code::Numpad8),
+    (0x69, Code::Numpad9),
+    (0x6A, Code::NumpadMultiply),
+    (0x6B, Code::NumpadAdd),
+    (0x6D, Code::NumpadSubtract),
+    (0x6E, Code::NumpadDecimal),
+    (0x6F, Code::NumpadDivide),
+    (0x25, Code::ArrowLeft),
+    (0x26, Code::ArrowUp),
+    (0x27, Code::ArrowRight),
+    (0x28, Code::ArrowDown),
+    (0x24, Code::Home),
+    (0x23, Code::End),
+    (0x21, Code::PageUp),
+    (0x22, Code::PageDown),
+    (0x2D, Code::Insert),
+    (0x2E, Code::Delete),
+    (0x20, Code::Space),
+    (0x0D, Code::Enter),
+    (0x09, Code::Tab),
+    (0x1B, Code::Escape),
+    (0x08, Code::Backspace),
+    (0x13, Code::Pause),
+    (0x2C, Code::PrintScreen),
+    (0x91, Code::ScrollLock),
+    (0xBA, Code::Semicolon),
+    (0xBB, Code::Equal),
+    (0xBC, Code::Comma),
+    (0xBD, Code::Minus),
+    (0xBE, Code::Period),
+    (0xBF, Code::Slash),
+    (0xC0, Code::Backquote),
+    (0xDB, Code::BracketLeft),
+    (0xDC, Code::Backslash),
+    (0xDD, Code::BracketRight),
+    (0xDE, Code::Quote),
+];
+
+/// Key for a Windows virtual-key code
+pub fn code_from_vk(vk: u16) -> Option<Code> {
+    VIRTUAL_KEYS
+        .iter()
+        .find(|(v, _)| *v == vk)
+        .map(|(_, code)| *code)
+}
+
+/// Windows virtual-key code for a key
+pub fn vk_from_code(code: Code) -> Option<u16> {
+    VIRTUAL_KEYS
+        .iter()
+        .find(|(_, c)| *c == code)
+        .map(|(vk, _)| *vk)
+}
+
+/// Owns the registered hotkeys and maps incoming events back to actions
+pub struct HotkeyManager {
+    /// Must stay alive - dropping it unregisters every hotkey
+    manager: GlobalHotKeyManager,
+    bindings: Vec<(HotKey, HotkeyAction)>,
+}
+
+impl HotkeyManager {
+    /// Create the manager and register the hotkeys (see resolve)
+    /// A hotkey that is already taken by another application is skipped with a warning
+    pub fn new(hotkeys: &[(HotkeyAction, Option<HotKey>)]) -> Result<Self> {
+        let manager = GlobalHotKeyManager::new()
+            .map_err(|e| anyhow!("Failed to create hotkey manager: {}", e))?;
+
+        let mut bindings = Vec::new();
+        for &(action, hotkey) in hotkeys {
+            let Some(hotkey) = hotkey else {
+                info!("No hotkey for {:?}", action);
+                continue;
+            };
+            if bindings.iter().any(|(h, _)| *h == hotkey) {
+                warn!(
+                    "Hotkey {} for {:?} is already used by another action",
+                    format_hotkey(hotkey),
+                    action
+                );
+                continue;
+            }
+            match manager.register(hotkey) {
+                Ok(()) => {
+                    info!(
+                        "Registered hotkey {} for {:?}",
+                        format_hotkey(hotkey),
+                        action
+                    );
+                    bindings.push((hotkey, action));
+                }
+                Err(e) => {
+                    warn!(
+                        "Could not register hotkey {} for {:?}: {}",
+                        format_hotkey(hotkey),
+                        action,
+                        e
+                    );
+                }
+            }
+        }
+
+        Ok(Self { manager, bindings })
+    }
+
+    /// Return the next hotkey action, if any: (action, true) when pressed,
+    /// (action, false) when released
+    /// Releases are only reported for held actions (see HotkeyAction::is_held)
+    pub fn poll(&self) -> Option<(HotkeyAction, bool)> {
+        while let Ok(event) = GlobalHotKeyEvent::receiver().try_recv() {
+            let Some((_, action)) = self.bindings.iter().find(|(h, _)| h.id() == event.id()) else {
+                continue;
+            };
+            let pressed = event.state() == HotKeyState::Pressed;
+            if pressed || action.is_held() {
+                return Some((*action, pressed));
+            }
+        }
+        None
+    }
+}
+
+impl Drop for HotkeyManager {
+    fn drop(&mut self) {
+        let hotkeys: Vec<HotKey> = self.bindings.iter().map(|(h, _)| *h).collect();
+        let _ = self.manager.unregister_all(&hotkeys);
+    }
+}
@@ -0,0 +1,247 @@
+// http.rs - Outgoing HTTP Requests
+//
+// A small blocking client on top of WinHTTP, so https works with the system
+// certificates and proxy settings and without extra dependencies. Used by the
+// webhooks (webhook.rs) and the upload targets (upload.rs) - always from a
+// background thread.
+
+use anyhow::{anyhow, Result};
+
+/// A received response
+pub struct Response {
+    pub status: u32,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// The body as text (for JSON answers and error messages)
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    /// Turn a non-2xx answer into an error that includes the start of the body
+    pub fn error_for_status(self) -> Result<Self> {
+        if self.is_success() {
+            return Ok(self);
+        }
+        let text = self.text();
+        let text: String = text.trim().chars().take(200).collect();
+        Err(anyhow!("HTTP {}: {}", self.status, text))
+    }
+}
+
+/// Parts of an http(s) URL
+pub struct Url<'a> {
+    pub secure: bool,
+    pub host: &'a str,
+    pub port: Option<u16>,
+    /// Path and query, starting with '/'
+    pub path: &'a str,
+}
+
+impl<'a> Url<'a> {
+    pub fn parse(url: &'a str) -> Result<Self> {
+        let (secure, rest) = if let Some(rest) = url.strip_prefix("https://") {
+            (true, rest)
+        } else if let Some(rest) = url.strip_prefix("http://") {
+            (false, rest)
+        } else {
+            return Err(anyhow!("Only http:// and https:// URLs are supported"));
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (
+                host,
+                Some(
+                    port.parse()
+                        .map_err(|_| anyhow!("Invalid port in {}", url))?,
+                ),
+            ),
+            None => (authority, None),
+        };
+        if host.is_empty() {
+            return Err(anyhow!("No host in {}", url));
+        }
+        Ok(Self {
+            secure,
+            host,
+            port,
+            path,
+        })
+    }
+
+    /// Host header value (with the port if it is not the default one)
+    pub fn host_header(&self) -> String {
+        match self.port {
+            Some(port) => format!("{}:{}", self.host, port),
+            None => self.host.to_string(),
+        }
+    }
+}
+
+/// Percent-encode everything except the unreserved characters (RFC 3986)
+/// `keep_slash`: leave '/' as is (object keys, paths)
+pub fn encode(value: &str, keep_slash: bool) -> String {
+    let mut out = String::new();
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                out.push(byte as char)
+            }
+            b'/' if keep_slash => out.push('/'),
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+/// Send a request and wait for the whole response
+/// `progress` is called with the number of body bytes sent so far.
+#[cfg(windows)]
+pub fn request(
+    method: &str,
+    url: &str,
+    headers: &[(&str, String)],
+    body: &[u8],
+    timeout_ms: u64,
+    progress: &mut dyn FnMut(u64),
+) -> Result<Response> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Networking::WinHttp::*;
+
+    use crate::constants::http;
+    use crate::utils::wide_string;
+
+    /// Closes a WinHTTP handle when dropped
+    struct Handle(*mut std::ffi::c_void);
+    impl Drop for Handle {
+        fn drop(&mut self) {
+            if !self.0.is_null() {
+                let _ = unsafe { WinHttpCloseHandle(self.0) };
+            }
+        }
+    }
+
+    let parsed = Url::parse(url)?;
+    let port = parsed.port.unwrap_or(if parsed.secure {
+        INTERNET_DEFAULT_HTTPS_PORT
+    } else {
+        INTERNET_DEFAULT_HTTP_PORT
+    });
+    let agent = wide_string(http::USER_AGENT);
+    let host = wide_string(parsed.host);
+    let verb = wide_string(method);
+    let path = wide_string(parsed.path);
+    let header_text: String = headers
+        .iter()
+        .map(|(name, value)| format!("{}: {}\r\n", name, value))
+        .collect();
+    let header_text: Vec<u16> = header_text.encode_utf16().collect();
+    let timeout = timeout_ms.min(i32::MAX as u64) as i32;
+
+    unsafe {
+        let session = Handle(WinHttpOpen(
+            PCWSTR(agent.as_ptr()),
+            WINHTTP_ACCESS_TYPE_AUTOMATIC_PROXY,
+            PCWSTR::null(),
+            PCWSTR::null(),
+            0,
+        ));
+        if session.0.is_null() {
+            return Err(windows::core::Error::from_thread().into());
+        }
+        WinHttpSetTimeouts(session.0, timeout, timeout, timeout, timeout)?;
+        let connection = Handle(WinHttpConnect(session.0, PCWSTR(host.as_ptr()), port, 0));
+        if connection.0.is_null() {
+            return Err(windows::core::Error::from_thread().into());
+        }
+        let flags = if parsed.secure {
+            WINHTTP_FLAG_SECURE
+        } else {
+            WINHTTP_OPEN_REQUEST_FLAGS(0)
+        };
+        let request = Handle(WinHttpOpenRequest(
+            connection.0,
+            PCWSTR(verb.as_ptr()),
+            PCWSTR(path.as_ptr()),
+            PCWSTR::null(),
+            PCWSTR::null(),
+            std::ptr::null(),
+            flags,
+        ));
+        if request.0.is_null() {
+            return Err(windows::core::Error::from_thread().into());
+        }
+
+        // The body is written in chunks so the caller can show progress
+        let headers_arg = (!header_text.is_empty()).then_some(header_text.as_slice());
+        WinHttpSendRequest(request.0, headers_arg, None, 0, body.len() as u32, 0)?;
+        let mut sent = 0usize;
+        for chunk in body.chunks(http::CHUNK_SIZE) {
+            let mut written = 0u32;
+            WinHttpWriteData(
+                request.0,
+                Some(chunk.as_ptr() as *const std::ffi::c_void),
+                chunk.len() as u32,
+                &mut written,
+            )?;
+            sent += chunk.len();
+            progress(sent as u64);
+        }
+        WinHttpReceiveResponse(request.0, std::ptr::null_mut())?;
+
+        let mut status = 0u32;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        WinHttpQueryHeaders(
+            request.0,
+            WINHTTP_QUERY_STATUS_CODE | WINHTTP_QUERY_FLAG_NUMBER,
+            PCWSTR::null(),
+            Some(&mut status as *mut u32 as *mut std::ffi::c_void),
+            &mut size,
+            std::ptr::null_mut(),
+        )?;
+
+        let mut response = Vec::new();
+        loop {
+            let mut available = 0u32;
+            WinHttpQueryDataAvailable(request.0, &mut available)?;
+            if available == 0 || response.len() >= http::MAX_RESPONSE {
+                break;
+            }
+            let start = response.len();
+            response.resize(start + available as usize, 0);
+            let mut read = 0u32;
+            WinHttpReadData(
+                request.0,
+                response[start..].as_mut_ptr() as *mut std::ffi::c_void,
+                available,
+                &mut read,
+            )?;
+            response.truncate(start + read as usize);
+        }
+
+        Ok(Response {
+            status,
+            body: response,
+        })
+    }
+}
+
+#[cfg(not(windows))]
+pub fn request(
+    method: &str,
+    url: &str,
+    _headers: &[(&str, String)],
+    _body: &[u8],
+    _timeout_ms: u64,
+    _progress: &mut dyn FnMut(u64),
+) -> Result<Response> {
+    Err(anyhow!("{} {} is only supported on Windows", method, url))
+}
@@ -0,0 +1,204 @@
+// http_api.rs - REST Control Endpoint
+//
+// Plain HTTP on the remote control port (see stream_deck.rs), for curl and
+// scripts that do not want to keep a WebSocket open:
+//
+//   curl -H "Authorization: Bearer <token>" http://127.0.0.1:39217/status
+//   curl -X POST -H "Authorization: Bearer <token>" http://127.0.0.1:39217/record/start
+//
+// Endpoints:
+// - GET  /status          the remote state as JSON (like the WebSocket "state" event)
+// - POST /record/start    start capture (with the countdown)
+// - POST /record/stop     stop capture (or cancel the countdown)
+// - POST /screenshot      save a screenshot of the output
+// - POST /action/<id>     any remote action, e.g. /action/scene_2
+//
+// The server tells the two apart by the request head: WebSocket handshakes
+// (Upgrade: websocket) go to tungstenite, everything else is answered here.
+// Every request needs the remote token; every connection serves one request.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
+
+use crate::constants::stream_deck;
+use crate::stream_deck::{token_matches, RemoteAction, RemoteState};
+
+/// A parsed request head
+struct Request {
+    method: String,
+    path: String,
+    /// Token from "Authorization: Bearer <token>"
+    token: Option<String>,
+}
+
+/// Body of the answer to an action
+#[derive(Serialize)]
+struct ActionReply {
+    ok: bool,
+    action: String,
+}
+
+#[derive(Serialize)]
+struct ErrorReply<'a> {
+    ok: bool,
+    error: &'a str,
+}
+
+/// Whether the connection starts with a WebSocket handshake
+/// (looks at the request head without consuming it)
+pub fn is_websocket(stream: &TcpStream) -> Result<bool> {
+    let head = peek_head(stream)?;
+    let head = String::from_utf8_lossy(&head).to_ascii_lowercase();
+    Ok(head
+        .lines()
+        .any(|line| line.starts_with("upgrade:") && line.contains("websocket")))
+}
+
+/// Answer one HTTP request
+pub fn serve(
+    mut stream: TcpStream,
+    sender: &Sender<RemoteAction>,
+    state: &RemoteState,
+    token: &str,
+) -> Result<()> {
+    let head = peek_head(&stream)?;
+    // Consume the head that was only peeked at (a body, if any, is ignored)
+    let mut consumed = vec![0u8; head.len()];
+    stream.read_exact(&mut consumed)?;
+
+    let Some(request) = parse_request(&String::from_utf8_lossy(&head)) else {
+        return reply_error(&mut stream, "400 Bad Request", "Malformed request");
+    };
+    if !token_matches(request.token.as_deref().unwrap_or(""), token) {
+        return reply_error(
+            &mut stream,
+            "401 Unauthorized",
+            "Send the remote token as Authorization: Bearer <token>",
+        );
+    }
+
+    let path = request.path.trim_end_matches('/');
+    if path == "/status" {
+        return match request.method.as_str() {
+            "GET" => reply(&mut stream, "200 OK", &serde_json::to_string(state)?),
+            _ => reply_error(&mut stream, "405 Method Not Allowed", "Use GET"),
+        };
+    }
+
+    let action = match path {
+        "/record/start" => Some(RemoteAction::Start),
+        "/record/stop" => Some(RemoteAction::Stop),
+        "/screenshot" => Some(RemoteAction::Screenshot),
+        _ => path
+            .strip_prefix("/action/")
+            .and_then(RemoteAction::from_id),
+    };
+    let Some(action) = action else {
+        return reply_error(&mut stream, "404 Not Found", "Unknown endpoint");
+    };
+    if request.method != "POST" {
+        return reply_error(&mut stream, "405 Method Not Allowed", "Use POST");
+    }
+
+    log::info!("HTTP action: {:?}", action);
+    let _ = sender.send(action);
+    let body = serde_json::to_string(&ActionReply {
+        ok: true,
+        action: action.id(),
+    })?;
+    reply(&mut stream, "200 OK", &body)
+}
+
+/// Bytes up to and including the blank line after the headers, without
+/// consuming them
+fn peek_head(stream: &TcpStream) -> Result<Vec<u8>> {
+    let deadline = Instant::now() + Duration::from_millis(stream_deck::HTTP_HEAD_TIMEOUT_MS);
+    let mut buffer = vec![0u8; stream_deck::HTTP_HEAD_MAX];
+    loop {
+        let len = stream.peek(&mut buffer)?;
+        if let Some(end) = buffer[..len].windows(4).position(|w| w == b"\r\n\r\n") {
+            return Ok(buffer[..end + 4].to_vec());
+        }
+        if len == 0 || len == buffer.len() || Instant::now() >= deadline {
+            return Err(anyhow::anyhow!("Incomplete request head"));
+        }
+        // The rest of the head is still on its way
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+fn parse_request(head: &str) -> Option<Request> {
+    let mut lines = head.lines();
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_ascii_uppercase();
+    // The query string is not used
+    let path = request_line.next()?.split('?').next()?.to_string();
+    let token = lines.find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        if !name.trim().eq_ignore_ascii_case("authorization") {
+            return None;
+        }
+        let (scheme, token) = value.trim().split_once(' ')?;
+        scheme
+            .eq_ignore_ascii_case("bearer")
+            .then(|| token.trim().to_string())
+    });
+    Some(Request {
+        method,
+        path,
+        token,
+    })
+}
+
+fn reply_error(stream: &mut TcpStream, status: &str, error: &str) -> Result<()> {
+    let body = serde_json::to_string(&ErrorReply { ok: false, error })?;
+    reply(stream, status, &body)
+}
+
+fn reply(stream: &mut TcpStream, status: &str, body: &str) -> Result<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes())?;
+    stream.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_method_path_and_bearer_token() {
+        let request = parse_request(
+            "post /record/start?now=1 HTTP/1.1\r\nHost: 127.0.0.1\r\nauthorization:  Bearer  abc123 \r\n\r\n",
+        )
+        .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/record/start");
+        assert_eq!(request.token.as_deref(), Some("abc123"));
+    }
+
+    #[test]
+    fn other_authorization_schemes_are_no_token() {
+        let request =
+            parse_request("GET /status HTTP/1.1\r\nAuthorization: Basic dXNlcjpwdw==\r\n\r\n")
+                .unwrap();
+        assert_eq!(request.token, None);
+        let request = parse_request("GET /status HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(request.token, None);
+    }
+
+    #[test]
+    fn rejects_a_request_line_without_a_path() {
+        assert!(parse_request("").is_none());
+        assert!(parse_request("GET\r\n\r\n").is_none());
+    }
+}
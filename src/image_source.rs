@@ -0,0 +1,243 @@
+// image_source.rs - Static Image and Slideshow Source
+//
+// Shows a PNG / JPEG image in the output - a title card, a "starting soon"
+// screen or a set of slides - either instead of the captured region or
+// composited next to it (like the webcam, see source.rs):
+// - A file: the image is shown as long as the source is on
+// - A folder: its images are shown in name order as a slideshow, advancing
+//   every "Slide every" seconds (Settings) or with tray → Image → Next Image
+//
+// Images are letterboxed to the size of the capture region, so switching
+// between a title card and the live region keeps the output size.
+//
+// Images are decoded on a background thread - a large JPEG never stalls the
+// render loop; the previous image stays up until the next one is ready. An
+// image that fails to load is skipped at the next slide change instead of
+// being retried every frame, and a slideshow where no image loads stops
+// advancing.
+
+use anyhow::{anyhow, Context, Result};
+use image::imageops::FilterType;
+use image::GenericImageView;
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::time::{Duration, Instant};
+
+use crate::constants::image_source;
+use crate::source::FrameSource;
+
+/// Decoded image: ARGB pixels, width, height
+type Decoded = (Vec<u32>, u32, u32);
+
+/// Image or slideshow source
+pub struct ImageSource {
+    /// Images in slideshow order (one entry for a single file)
+    paths: Vec<PathBuf>,
+    index: usize,
+    /// Time per slide (None = no automatic advance)
+    interval: Option<Duration>,
+    /// Decoded current image (ARGB) and its size
+    image: Option<(Vec<u32>, (u32, u32))>,
+    shown_at: Instant,
+    /// Output frame size (the capture region size)
+    size: (u32, u32),
+    /// The next read has to deliver a new frame (new image or size)
+    dirty: bool,
+    /// Decode of the current image in progress
+    loading: Option<Receiver<Result<Decoded>>>,
+    /// Images that failed to load in a row
+    failures: usize,
+}
+
+impl ImageSource {
+    /// Open an image file, or a folder as a slideshow (slide_seconds 0 = manual)
+    pub fn open(path: &str, slide_seconds: u32) -> Result<Self> {
+        if path.trim().is_empty() {
+            return Err(anyhow!("No image set - choose one in Settings → Image"));
+        }
+        let path = PathBuf::from(path.trim());
+        let paths = if path.is_dir() {
+            let mut paths: Vec<PathBuf> = std::fs::read_dir(&path)
+                .with_context(|| format!("Failed to read image folder {:?}", path))?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|p| is_image(p))
+                .collect();
+            paths.sort();
+            paths
+        } else {
+            vec![path.clone()]
+        };
+        if paths.is_empty() {
+            return Err(anyhow!("No PNG / JPEG images in {:?}", path));
+        }
+        info!("Image source: {:?} ({} image(s))", path, paths.len());
+
+        let mut source = Self {
+            paths,
+            index: 0,
+            interval: (slide_seconds > 0).then(|| Duration::from_secs(slide_seconds as u64)),
+            image: None,
+            shown_at: Instant::now(),
+            size: (0, 0),
+            dirty: true,
+            loading: None,
+            failures: 0,
+        };
+        source.load_current();
+        Ok(source)
+    }
+
+    /// Render at this size from the next frame on (the capture region size)
+    pub fn set_size(&mut self, width: u32, height: u32) {
+        if self.size != (width, height) {
+            self.size = (width, height);
+            self.dirty = true;
+        }
+    }
+
+    /// Show the next image of the slideshow (wraps around)
+    pub fn next_image(&mut self) {
+        if self.paths.len() < 2 || self.loading.is_some() {
+            return;
+        }
+        self.index = (self.index + 1) % self.paths.len();
+        self.load_current();
+    }
+
+    /// Start decoding the current image on a background thread
+    fn load_current(&mut self) {
+        let path = self.paths[self.index].clone();
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = sender.send(load_argb(&path));
+        });
+        self.loading = Some(receiver);
+        self.shown_at = Instant::now();
+    }
+
+    /// Pick up a finished decode
+    fn poll_loading(&mut self) {
+        let Some(receiver) = &self.loading else {
+            return;
+        };
+        let result = match receiver.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => Err(anyhow!("Image decoder stopped")),
+        };
+        self.loading = None;
+        // The slide time starts once the image is up (or has failed)
+        self.shown_at = Instant::now();
+
+        match result {
+            Ok((pixels, width, height)) => {
+                info!(
+                    "Showing image {:?} ({}x{})",
+                    self.paths[self.index], width, height
+                );
+                self.image = Some((pixels, (width, height)));
+                self.dirty = true;
+                self.failures = 0;
+            }
+            Err(e) => {
+                warn!("{:#}", e);
+                self.failures += 1;
+                if self.failures >= self.paths.len() && self.interval.take().is_some() {
+                    warn!("No image of the slideshow could be loaded - stopped advancing");
+                }
+            }
+        }
+    }
+}
+
+impl FrameSource for ImageSource {
+    fn read_frame(&mut self, frame: &mut Vec<u32>) -> Result<Option<(u32, u32)>> {
+        self.poll_loading();
+        if self.interval.is_some_and(|i| self.shown_at.elapsed() >= i) {
+            self.next_image();
+        }
+
+        let (width, height) = self.size;
+        let Some((pixels, image_size)) = &self.image else {
+            return Ok(None);
+        };
+        if !self.dirty || width == 0 || height == 0 {
+            return Ok(None);
+        }
+        self.dirty = false;
+
+        *frame = letterbox(
+            pixels,
+            *image_size,
+            width as i32,
+            height as i32,
+            image_source::BACKGROUND,
+        );
+        Ok(Some((width, height)))
+    }
+}
+
+/// True for file names with a supported image extension
+pub fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| image_source::EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+/// Decode an image file to ARGB pixels (downscaled to at most 4K)
+pub fn load_argb(path: &Path) -> Result<(Vec<u32>, u32, u32)> {
+    let img = image::open(path).with_context(|| format!("Failed to load image {:?}", path))?;
+
+    // Keep memory bounded for huge images - frames are rarely larger than 4K
+    let (max_w, max_h) = image_source::MAX_SIZE;
+    let img = if img.width() > max_w || img.height() > max_h {
+        img.resize(max_w, max_h, FilterType::Triangle)
+    } else {
+        img
+    };
+    let (width, height) = img.dimensions();
+
+    // RGBA bytes -> ARGB u32 (same layout as captured BGRA frames)
+    let pixels = img
+        .to_rgba8()
+        .pixels()
+        .map(|p| {
+            let [r, g, b, _] = p.0;
+            0xFF00_0000 | (r as u32) << 16 | (g as u32) << 8 | b as u32
+        })
+        .collect();
+    Ok((pixels, width, height))
+}
+
+/// Scale an image to fit a width x height frame (aspect ratio kept), centered
+/// on the background color
+pub fn letterbox(
+    pixels: &[u32],
+    (src_w, src_h): (u32, u32),
+    width: i32,
+    height: i32,
+    background: u32,
+) -> Vec<u32> {
+    let mut out = vec![background; (width * height) as usize];
+
+    // Fit inside the frame
+    let scale = (width as f32 / src_w as f32).min(height as f32 / src_h as f32);
+    let target_w = ((src_w as f32 * scale) as i32).max(1);
+    let target_h = ((src_h as f32 * scale) as i32).max(1);
+    let offset_x = (width - target_w) / 2;
+    let offset_y = (height - target_h) / 2;
+
+    for y in 0..target_h {
+        let sy = (y as u32 * src_h / target_h as u32).min(src_h - 1);
+        for x in 0..target_w {
+            let sx = (x as u32 * src_w / target_w as u32).min(src_w - 1);
+            let px = offset_x + x;
+            let py = offset_y + y;
+            if px >= 0 && px < width && py >= 0 && py < height {
+                out[(py * width + px) as usize] = pixels[(sy * src_w + sx) as usize];
+            }
+        }
+    }
+    out
+}
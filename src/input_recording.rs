@@ -0,0 +1,520 @@
+// input_recording.rs - Input Recording and Replay
+//
+// While "Record Input" is on (tray, Ctrl+Alt+I), every capture session writes
+// the keyboard and mouse input to a JSON file next to the screenshots:
+//
+//   Pictures\RustFrame\RustFrame 2026-10-16 14-03-27 input.json
+//
+// Each event has the time since the capture started and the number of output
+// frames presented before it, so it can be matched to a recording of the
+// output frame by frame (keystroke overlays added in editing, regression
+// demos). Mouse positions are relative to the capture region; the region
+// itself is stored in the file.
+//
+// Sources: the mouse hook for button presses and releases (see
+// mouse_hook.rs), the cursor position once per frame for moves, and a
+// keyboard hook (see key_hook.rs).
+//
+// tray → Replay Last Input Recording plays the newest file back with
+// SendInput, relative to the current capture region (or the recorded one
+// while not capturing), with the original timing. Keys and buttons still held
+// when the replay is stopped are released.
+//
+// Note: the file contains everything typed during the capture, passwords
+// included.
+
+use anyhow::{anyhow, Context, Result};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::capture::CaptureRect;
+use crate::constants::input_recording;
+use crate::key_hook::{KeyEvent, KeyHook};
+use crate::mouse_hook::{MouseButton, MouseGesture};
+use crate::screenshot;
+
+/// What happened
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InputKind {
+    KeyDown {
+        key: String,
+        vk: u32,
+        scan_code: u32,
+        extended: bool,
+    },
+    KeyUp {
+        key: String,
+        vk: u32,
+        scan_code: u32,
+        extended: bool,
+    },
+    MouseDown {
+        button: String,
+        x: i32,
+        y: i32,
+        clicks: u32,
+    },
+    MouseUp {
+        button: String,
+        x: i32,
+        y: i32,
+    },
+    MouseMove {
+        x: i32,
+        y: i32,
+    },
+}
+
+/// One recorded event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputEvent {
+    /// Milliseconds since the capture started
+    pub t_ms: u64,
+    /// Output frames presented before the event
+    pub frame: u32,
+    #[serde(flatten)]
+    pub kind: InputKind,
+}
+
+/// Capture region of the recording (screen coordinates)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRegion {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Contents of an input.json file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputRecording {
+    pub version: u32,
+    /// Unix time of the capture start in milliseconds
+    pub started_unix_ms: u64,
+    pub region: RecordedRegion,
+    pub events: Vec<InputEvent>,
+}
+
+/// Records the input of one capture session
+pub struct InputRecorder {
+    started: Instant,
+    file_stem: String,
+    recording: InputRecording,
+    keys: Option<KeyHook>,
+    /// (time, frames presented) of the recent frames
+    frames: VecDeque<(Instant, u32)>,
+    /// Press time of the gesture whose press was already recorded
+    pressed: Option<Instant>,
+    last_cursor: Option<(i32, i32)>,
+}
+
+impl InputRecorder {
+    /// Start recording (the keyboard hook is installed here)
+    pub fn start(region: CaptureRect) -> Self {
+        let keys = match KeyHook::install() {
+            Ok(hook) => Some(hook),
+            Err(e) => {
+                log::warn!("Keys are not recorded: {:#}", e);
+                None
+            }
+        };
+        let started_unix_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        info!("Input recording started");
+        Self {
+            started: Instant::now(),
+            file_stem: format!("RustFrame {}", screenshot::timestamp()),
+            recording: InputRecording {
+                version: input_recording::FILE_VERSION,
+                started_unix_ms,
+                region: RecordedRegion {
+                    x: region.x,
+                    y: region.y,
+                    width: region.width,
+                    height: region.height,
+                },
+                events: Vec::new(),
+            },
+            keys,
+            frames: VecDeque::new(),
+            pressed: None,
+            last_cursor: None,
+        }
+    }
+
+    /// Record the presses and releases reported by the mouse hook
+    pub fn add_gestures(&mut self, finished: &[MouseGesture], current: Option<&MouseGesture>) {
+        for gesture in finished {
+            if self.pressed != Some(gesture.start().at) {
+                self.push_press(gesture);
+            }
+            let end = gesture.end();
+            let (x, y) = self.relative(end.x, end.y);
+            let kind = InputKind::MouseUp {
+                button: button_name(gesture.button).to_string(),
+                x,
+                y,
+            };
+            self.push(end.at, kind);
+            self.pressed = None;
+        }
+        if let Some(gesture) = current {
+            if self.pressed != Some(gesture.start().at) {
+                self.push_press(gesture);
+                self.pressed = Some(gesture.start().at);
+            }
+        }
+    }
+
+    /// Once per frame: note the frame count, the keys and the cursor position
+    pub fn update(&mut self, frames: u32, cursor: Option<(i32, i32)>) {
+        let now = Instant::now();
+        self.frames.push_back((now, frames));
+        let history = Duration::from_millis(input_recording::FRAME_HISTORY_MS);
+        while self
+            .frames
+            .front()
+            .is_some_and(|(at, _)| now.duration_since(*at) > history)
+        {
+            self.frames.pop_front();
+        }
+
+        let keys = self
+            .keys
+            .as_ref()
+            .map(|hook| hook.take_events())
+            .unwrap_or_default();
+        for key in keys {
+            self.push(key.at, key_kind(&key));
+        }
+
+        if let Some((x, y)) = cursor {
+            let position = self.relative(x, y);
+            if self.last_cursor != Some(position) {
+                self.last_cursor = Some(position);
+                self.push(
+                    now,
+                    InputKind::MouseMove {
+                        x: position.0,
+                        y: position.1,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Stop recording and write the file
+    pub fn finish(mut self) -> Result<PathBuf> {
+        self.keys = None;
+        let dir =
+            screenshot::folder().ok_or_else(|| anyhow!("The Pictures folder was not found"))?;
+        std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {:?}", dir))?;
+        let path = dir.join(format!(
+            "{}{}",
+            self.file_stem,
+            input_recording::FILE_SUFFIX
+        ));
+
+        // Presses are sometimes reported after later moves
+        self.recording.events.sort_by_key(|e| e.t_ms);
+        // Keys still down at the end (the shortcut that ended the recording)
+        // would only trigger it again on replay
+        // (index of the last press, vk) of the keys that are down
+        let mut held: Vec<(usize, u32)> = Vec::new();
+        for (i, event) in self.recording.events.iter().enumerate() {
+            match &event.kind {
+                InputKind::KeyDown { vk, .. } => {
+                    held.retain(|(_, v)| v != vk);
+                    held.push((i, *vk));
+                }
+                InputKind::KeyUp { vk, .. } => held.retain(|(_, v)| v != vk),
+                _ => {}
+            }
+        }
+        for (i, _) in held.into_iter().rev() {
+            self.recording.events.remove(i);
+        }
+        let json = serde_json::to_string_pretty(&self.recording)?;
+        std::fs::write(&path, json).with_context(|| format!("Failed to write {:?}", path))?;
+        info!(
+            "Input recording saved: {:?} ({} events)",
+            path,
+            self.recording.events.len()
+        );
+        Ok(path)
+    }
+
+    fn push_press(&mut self, gesture: &MouseGesture) {
+        let start = gesture.start();
+        let (x, y) = self.relative(start.x, start.y);
+        let kind = InputKind::MouseDown {
+            button: button_name(gesture.button).to_string(),
+            x,
+            y,
+            clicks: gesture.clicks,
+        };
+        self.push(start.at, kind);
+    }
+
+    fn push(&mut self, at: Instant, kind: InputKind) {
+        let t_ms = at.saturating_duration_since(self.started).as_millis() as u64;
+        let frame = self
+            .frames
+            .iter()
+            .rev()
+            .find(|(time, _)| *time <= at)
+            .or(self.frames.front())
+            .map_or(0, |(_, frames)| *frames);
+        self.recording.events.push(InputEvent { t_ms, frame, kind });
+    }
+
+    fn relative(&self, x: i32, y: i32) -> (i32, i32) {
+        (x - self.recording.region.x, y - self.recording.region.y)
+    }
+}
+
+fn button_name(button: MouseButton) -> &'static str {
+    match button {
+        MouseButton::Left => "left",
+        MouseButton::Right => "right",
+        MouseButton::Middle => "middle",
+    }
+}
+
+fn key_kind(key: &KeyEvent) -> InputKind {
+    let name = key_name(key);
+    if key.down {
+        InputKind::KeyDown {
+            key: name,
+            vk: key.vk,
+            scan_code: key.scan_code,
+            extended: key.extended,
+        }
+    } else {
+        InputKind::KeyUp {
+            key: name,
+            vk: key.vk,
+            scan_code: key.scan_code,
+            extended: key.extended,
+        }
+    }
+}
+
+/// Name of the key on the current keyboard layout, e.g. "A", "Enter", "Right Ctrl"
+#[cfg(windows)]
+fn key_name(key: &KeyEvent) -> String {
+    use windows::Win32::UI::Input::KeyboardAndMouse::GetKeyNameTextW;
+
+    let lparam = ((key.scan_code & 0xFF) << 16) | (u32::from(key.extended) << 24);
+    let mut buffer = [0u16; 64];
+    let len = unsafe { GetKeyNameTextW(lparam as i32, &mut buffer) };
+    if len > 0 {
+        String::from_utf16_lossy(&buffer[..len as usize])
+    } else {
+        format!("VK {}", key.vk)
+    }
+}
+
+#[cfg(not(windows))]
+fn key_name(key: &KeyEvent) -> String {
+    format!("VK {}", key.vk)
+}
+
+/// The newest input recording in Pictures\RustFrame
+pub fn latest() -> Option<PathBuf> {
+    let entries = std::fs::read_dir(screenshot::folder()?).ok()?;
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|n| n.to_string_lossy().ends_with(input_recording::FILE_SUFFIX))
+        })
+        .filter_map(|path| {
+            let modified = path.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, path))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+/// A running replay (stopped when dropped)
+pub struct InputReplay {
+    stop: Arc<AtomicBool>,
+    done: Arc<AtomicBool>,
+}
+
+impl InputReplay {
+    /// Play `path` back; mouse positions are relative to `origin`
+    /// (None = the region stored in the file)
+    pub fn start(path: &Path, origin: Option<(i32, i32)>) -> Result<Self> {
+        let text =
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+        let recording: InputRecording = serde_json::from_str(&text)
+            .with_context(|| format!("Invalid input recording {:?}", path))?;
+        let origin = origin.unwrap_or((recording.region.x, recording.region.y));
+        info!("Replaying {:?} ({} events)", path, recording.events.len());
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let done = Arc::new(AtomicBool::new(false));
+        {
+            let stop = stop.clone();
+            let done = done.clone();
+            std::thread::spawn(move || {
+                replay(&recording.events, origin, &stop);
+                done.store(true, Ordering::Relaxed);
+                info!("Input replay finished");
+            });
+        }
+        Ok(Self { stop, done })
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.done.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for InputReplay {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Send the events with their original timing until done or stopped
+fn replay(events: &[InputEvent], origin: (i32, i32), stop: &AtomicBool) {
+    let started = Instant::now();
+    // Keys (vk, scan code, extended) and buttons that are down right now
+    let mut held_keys: Vec<(u32, u32, bool)> = Vec::new();
+    let mut held_buttons: Vec<String> = Vec::new();
+
+    for event in events {
+        let due = started + Duration::from_millis(event.t_ms);
+        while Instant::now() < due {
+            if stop.load(Ordering::Relaxed) {
+                break;
+            }
+            let wait = due.saturating_duration_since(Instant::now());
+            std::thread::sleep(wait.min(Duration::from_millis(input_recording::REPLAY_POLL_MS)));
+        }
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+        match &event.kind {
+            InputKind::KeyDown {
+                vk,
+                scan_code,
+                extended,
+                ..
+            } => {
+                send_key(*vk, *scan_code, *extended, true);
+                if !held_keys.contains(&(*vk, *scan_code, *extended)) {
+                    held_keys.push((*vk, *scan_code, *extended));
+                }
+            }
+            InputKind::KeyUp {
+                vk,
+                scan_code,
+                extended,
+                ..
+            } => {
+                send_key(*vk, *scan_code, *extended, false);
+                held_keys.retain(|k| *k != (*vk, *scan_code, *extended));
+            }
+            InputKind::MouseDown { button, x, y, .. } => {
+                move_cursor(origin.0 + x, origin.1 + y);
+                send_button(button, true);
+                held_buttons.push(button.clone());
+            }
+            InputKind::MouseUp { button, x, y } => {
+                move_cursor(origin.0 + x, origin.1 + y);
+                send_button(button, false);
+                held_buttons.retain(|b| b != button);
+            }
+            InputKind::MouseMove { x, y } => move_cursor(origin.0 + x, origin.1 + y),
+        }
+    }
+
+    // Nothing stays pressed after a stopped replay
+    for (vk, scan_code, extended) in held_keys {
+        send_key(vk, scan_code, extended, false);
+    }
+    for button in held_buttons {
+        send_button(&button, false);
+    }
+}
+
+#[cfg(windows)]
+fn send_key(vk: u32, scan_code: u32, extended: bool, down: bool) {
+    use windows::Win32::UI::Input::KeyboardAndMouse::*;
+
+    let mut flags = KEYBD_EVENT_FLAGS(0);
+    if extended {
+        flags |= KEYEVENTF_EXTENDEDKEY;
+    }
+    if !down {
+        flags |= KEYEVENTF_KEYUP;
+    }
+    let input = INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: VIRTUAL_KEY(vk as u16),
+                wScan: scan_code as u16,
+                dwFlags: flags,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    };
+    unsafe { SendInput(&[input], std::mem::size_of::<INPUT>() as i32) };
+}
+
+#[cfg(windows)]
+fn send_button(button: &str, down: bool) {
+    use windows::Win32::UI::Input::KeyboardAndMouse::*;
+
+    let flags = match (button, down) {
+        ("right", true) => MOUSEEVENTF_RIGHTDOWN,
+        ("right", false) => MOUSEEVENTF_RIGHTUP,
+        ("middle", true) => MOUSEEVENTF_MIDDLEDOWN,
+        ("middle", false) => MOUSEEVENTF_MIDDLEUP,
+        (_, true) => MOUSEEVENTF_LEFTDOWN,
+        (_, false) => MOUSEEVENTF_LEFTUP,
+    };
+    let input = INPUT {
+        r#type: INPUT_MOUSE,
+        Anonymous: INPUT_0 {
+            mi: MOUSEINPUT {
+                dx: 0,
+                dy: 0,
+                mouseData: 0,
+                dwFlags: flags,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    };
+    unsafe { SendInput(&[input], std::mem::size_of::<INPUT>() as i32) };
+}
+
+#[cfg(windows)]
+fn move_cursor(x: i32, y: i32) {
+    let _ = unsafe { windows::Win32::UI::WindowsAndMessaging::SetCursorPos(x, y) };
+}
+
+#[cfg(not(windows))]
+fn send_key(_vk: u32, _scan_code: u32, _extended: bool, _down: bool) {}
+
+#[cfg(not(windows))]
+fn send_button(_button: &str, _down: bool) {}
+
+#[cfg(not(windows))]
+fn move_cursor(_x: i32, _y: i32) {}
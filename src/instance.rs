@@ -0,0 +1,383 @@
+// instance.rs - Running Instance Channel
+//
+// A second RustFrame process (e.g. started by a rustframe:// link) or
+// rustframe-ctl (see src/bin/rustframe-ctl.rs) hands its command to the
+// instance that is already running instead of opening a second overlay. The running instance listens on a named pipe of the current user:
+//
+//   \\.\pipe\RustFrame-<user name>
+//
+// Every connection writes one or more commands, one per line, and closes the
+// pipe. Commands reach the app through a channel polled from about_to_wait
+// (like the hotkeys); what a command means is up to the caller (see
+// deep_link.rs).
+//
+// Only the current user can open the pipe (its DACL grants access to the
+// user's SID alone) and only from this machine (PIPE_REJECT_REMOTE_CLIENTS).
+// Reads are overlapped: a client gets READ_TIMEOUT_MS to write its commands
+// and MAX_MESSAGE bytes at most, and shutting down cancels a pending wait.
+//
+// Only the first instance owns the pipe - a later instance that starts
+// anyway (a normal launch) just runs without it.
+
+use anyhow::Result;
+use std::sync::mpsc::Receiver;
+
+/// Pipe name of the current user
+fn pipe_name() -> String {
+    let user = std::env::var("USERNAME").unwrap_or_default();
+    format!(r"\\.\pipe\RustFrame-{}", user)
+}
+
+/// Send commands to the running instance
+/// Returns false if no instance is running
+pub fn send_to_running(commands: &[String]) -> Result<bool> {
+    use std::io::{ErrorKind, Write};
+
+    let name = pipe_name();
+    // The pipe is busy for a moment while the instance serves another client
+    for _ in 0..crate::constants::instance::CONNECT_ATTEMPTS {
+        match std::fs::OpenOptions::new().write(true).open(&name) {
+            Ok(mut pipe) => {
+                let text: String = commands.iter().map(|c| format!("{}\n", c)).collect();
+                pipe.write_all(text.as_bytes())?;
+                return Ok(true);
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(false),
+            Err(_) => std::thread::sleep(std::time::Duration::from_millis(
+                crate::constants::instance::CONNECT_RETRY_MS,
+            )),
+        }
+    }
+    Err(anyhow::anyhow!(
+        "The running RustFrame instance does not respond"
+    ))
+}
+
+/// Pipe server of the running instance (stops when dropped)
+pub struct InstanceServer {
+    receiver: Receiver<String>,
+    #[cfg(windows)]
+    stop: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    #[cfg(windows)]
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl InstanceServer {
+    /// Own the pipe and listen on it
+    /// Fails if another instance already owns it
+    #[cfg(windows)]
+    pub fn start() -> Result<Self> {
+        use std::sync::atomic::AtomicBool;
+        use std::sync::Arc;
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        // Created here, so a second instance fails right away
+        let pipe = create_pipe()?;
+        let event = match unsafe {
+            windows::Win32::System::Threading::CreateEventW(None, true, false, None)
+        } {
+            Ok(event) => event,
+            Err(e) => {
+                unsafe {
+                    let _ = windows::Win32::Foundation::CloseHandle(pipe);
+                }
+                return Err(e.into());
+            }
+        };
+
+        let thread = {
+            let stop = stop.clone();
+            // A HANDLE is not Send - the thread gets the raw values
+            let (raw_pipe, raw_event) = (pipe.0 as usize, event.0 as usize);
+            std::thread::spawn(move || {
+                use windows::Win32::Foundation::HANDLE;
+
+                serve(
+                    HANDLE(raw_pipe as *mut _),
+                    HANDLE(raw_event as *mut _),
+                    sender,
+                    stop,
+                )
+            })
+        };
+        log::info!("Listening for commands on {}", pipe_name());
+
+        Ok(Self {
+            receiver,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    #[cfg(not(windows))]
+    pub fn start() -> Result<Self> {
+        Err(anyhow::anyhow!(
+            "The instance pipe is only supported on Windows"
+        ))
+    }
+
+    /// Next command sent by another process, if any
+    pub fn poll(&self) -> Option<String> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+impl Drop for InstanceServer {
+    fn drop(&mut self) {
+        #[cfg(windows)]
+        {
+            use std::sync::atomic::Ordering;
+
+            // The thread notices within STOP_POLL_MS and cancels its wait
+            self.stop.store(true, Ordering::Relaxed);
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
+
+/// Create the pipe (fails if it already exists)
+#[cfg(windows)]
+fn create_pipe() -> Result<windows::Win32::Foundation::HANDLE> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::{LocalFree, HLOCAL};
+    use windows::Win32::Security::Authorization::{
+        ConvertStringSecurityDescriptorToSecurityDescriptorW, SDDL_REVISION_1,
+    };
+    use windows::Win32::Security::{PSECURITY_DESCRIPTOR, SECURITY_ATTRIBUTES};
+    use windows::Win32::Storage::FileSystem::{
+        FILE_FLAG_FIRST_PIPE_INSTANCE, FILE_FLAG_OVERLAPPED, PIPE_ACCESS_INBOUND,
+    };
+    use windows::Win32::System::Pipes::{
+        CreateNamedPipeW, PIPE_READMODE_BYTE, PIPE_REJECT_REMOTE_CLIENTS, PIPE_TYPE_BYTE, PIPE_WAIT,
+    };
+
+    // Protected DACL with a single entry: full access for the current user
+    let sddl = crate::utils::wide_string(&format!("D:P(A;;GA;;;{})", current_user_sid()?));
+    let mut descriptor = PSECURITY_DESCRIPTOR::default();
+    unsafe {
+        ConvertStringSecurityDescriptorToSecurityDescriptorW(
+            PCWSTR(sddl.as_ptr()),
+            SDDL_REVISION_1,
+            &mut descriptor,
+            None,
+        )?;
+    }
+    let attributes = SECURITY_ATTRIBUTES {
+        nLength: std::mem::size_of::<SECURITY_ATTRIBUTES>() as u32,
+        lpSecurityDescriptor: descriptor.0,
+        bInheritHandle: false.into(),
+    };
+
+    let name = crate::utils::wide_string(&pipe_name());
+    let buffer = crate::constants::instance::BUFFER_SIZE;
+    let pipe = unsafe {
+        CreateNamedPipeW(
+            PCWSTR(name.as_ptr()),
+            PIPE_ACCESS_INBOUND | FILE_FLAG_FIRST_PIPE_INSTANCE | FILE_FLAG_OVERLAPPED,
+            PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+            // One instance, reused for client after client
+            1,
+            buffer,
+            buffer,
+            0,
+            Some(&attributes),
+        )
+    };
+    unsafe {
+        LocalFree(Some(HLOCAL(descriptor.0)));
+    }
+    if pipe.is_invalid() {
+        return Err(anyhow::anyhow!(
+            "Failed to create {} (is another RustFrame running?): {}",
+            pipe_name(),
+            windows::core::Error::from_thread()
+        ));
+    }
+    Ok(pipe)
+}
+
+/// SID of the user RustFrame runs as, in string form ("S-1-5-21-...")
+#[cfg(windows)]
+fn current_user_sid() -> Result<String> {
+    use windows::Win32::Foundation::{CloseHandle, LocalFree, HANDLE, HLOCAL};
+    use windows::Win32::Security::Authorization::ConvertSidToStringSidW;
+    use windows::Win32::Security::{GetTokenInformation, TokenUser, TOKEN_QUERY, TOKEN_USER};
+    use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    unsafe {
+        let mut token = HANDLE::default();
+        OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token)?;
+
+        // First call for the size, second for the data (a SID follows the struct)
+        let mut size = 0u32;
+        let _ = GetTokenInformation(token, TokenUser, None, 0, &mut size);
+        let mut data = vec![0u64; (size as usize + 7) / 8];
+        let result = GetTokenInformation(
+            token,
+            TokenUser,
+            Some(data.as_mut_ptr() as *mut std::ffi::c_void),
+            size,
+            &mut size,
+        );
+        let _ = CloseHandle(token);
+        result?;
+
+        let user = &*(data.as_ptr() as *const TOKEN_USER);
+        let mut text = windows::core::PWSTR::null();
+        ConvertSidToStringSidW(user.User.Sid, &mut text)?;
+        let sid = text.to_string();
+        LocalFree(Some(HLOCAL(text.0 as *mut _)));
+        Ok(sid?)
+    }
+}
+
+/// Accept one client after another and pass on their lines
+#[cfg(windows)]
+fn serve(
+    pipe: windows::Win32::Foundation::HANDLE,
+    event: windows::Win32::Foundation::HANDLE,
+    sender: std::sync::mpsc::Sender<String>,
+    stop: std::sync::Arc<std::sync::atomic::AtomicBool>,
+) {
+    use windows::Win32::Foundation::{CloseHandle, ERROR_IO_PENDING, ERROR_PIPE_CONNECTED};
+    use windows::Win32::System::Pipes::{ConnectNamedPipe, DisconnectNamedPipe};
+    use windows::Win32::System::IO::OVERLAPPED;
+
+    loop {
+        // Wait for a client (already connected in between is fine too)
+        let mut overlapped = OVERLAPPED {
+            hEvent: event,
+            ..Default::default()
+        };
+        let connected = match unsafe { ConnectNamedPipe(pipe, Some(&mut overlapped)) } {
+            Ok(()) => true,
+            Err(e) if e.code() == ERROR_PIPE_CONNECTED.to_hresult() => true,
+            Err(e) if e.code() == ERROR_IO_PENDING.to_hresult() => {
+                match unsafe { finish_io(pipe, &overlapped, &stop, None) } {
+                    Some(result) => result.is_ok(),
+                    None => break,
+                }
+            }
+            Err(e) => {
+                log::warn!("Instance pipe: {}", e);
+                false
+            }
+        };
+
+        if connected {
+            match unsafe { read_message(pipe, event, &stop) } {
+                Ok(data) => {
+                    for line in String::from_utf8_lossy(&data).lines() {
+                        let line = line.trim();
+                        if !line.is_empty() {
+                            log::info!("Command from another process: {}", line);
+                            let _ = sender.send(line.to_string());
+                        }
+                    }
+                }
+                Err(e) => log::warn!("Ignored a message on the instance pipe: {}", e),
+            }
+        }
+
+        unsafe {
+            let _ = DisconnectNamedPipe(pipe);
+        }
+        if stop.load(std::sync::atomic::Ordering::Relaxed) {
+            break;
+        }
+    }
+
+    unsafe {
+        let _ = CloseHandle(event);
+        let _ = CloseHandle(pipe);
+    }
+}
+
+/// Read what the connected client writes until it closes the pipe
+#[cfg(windows)]
+unsafe fn read_message(
+    pipe: windows::Win32::Foundation::HANDLE,
+    event: windows::Win32::Foundation::HANDLE,
+    stop: &std::sync::atomic::AtomicBool,
+) -> Result<Vec<u8>> {
+    use crate::constants::instance;
+    use std::time::{Duration, Instant};
+    use windows::Win32::Foundation::{ERROR_BROKEN_PIPE, ERROR_IO_PENDING};
+    use windows::Win32::Storage::FileSystem::ReadFile;
+    use windows::Win32::System::IO::OVERLAPPED;
+
+    let deadline = Instant::now() + Duration::from_millis(instance::READ_TIMEOUT_MS);
+    let mut data = Vec::new();
+    let mut buffer = [0u8; 1024];
+    loop {
+        let mut overlapped = OVERLAPPED {
+            hEvent: event,
+            ..Default::default()
+        };
+        if let Err(e) = ReadFile(pipe, Some(&mut buffer), None, Some(&mut overlapped)) {
+            if e.code() == ERROR_BROKEN_PIPE.to_hresult() {
+                return Ok(data);
+            }
+            if e.code() != ERROR_IO_PENDING.to_hresult() {
+                return Err(e.into());
+            }
+        }
+        let read = match finish_io(pipe, &overlapped, stop, Some(deadline)) {
+            Some(Ok(read)) => read,
+            // The client closed the pipe - the message is complete
+            Some(Err(e)) if e.code() == ERROR_BROKEN_PIPE.to_hresult() => return Ok(data),
+            Some(Err(e)) => return Err(e.into()),
+            None => return Err(anyhow::anyhow!("the client did not close the pipe in time")),
+        };
+        if read == 0 {
+            return Ok(data);
+        }
+        data.extend_from_slice(&buffer[..read as usize]);
+        if data.len() > instance::MAX_MESSAGE {
+            return Err(anyhow::anyhow!(
+                "longer than {} bytes",
+                instance::MAX_MESSAGE
+            ));
+        }
+    }
+}
+
+/// Wait for an overlapped operation on the pipe in short steps; cancels it
+/// when the app shuts down or the deadline passes (None)
+#[cfg(windows)]
+unsafe fn finish_io(
+    pipe: windows::Win32::Foundation::HANDLE,
+    overlapped: &windows::Win32::System::IO::OVERLAPPED,
+    stop: &std::sync::atomic::AtomicBool,
+    deadline: Option<std::time::Instant>,
+) -> Option<windows::core::Result<u32>> {
+    use std::sync::atomic::Ordering;
+    use windows::core::HRESULT;
+    use windows::Win32::Foundation::WAIT_TIMEOUT;
+    use windows::Win32::System::IO::{CancelIoEx, GetOverlappedResult, GetOverlappedResultEx};
+
+    loop {
+        let mut transferred = 0u32;
+        match GetOverlappedResultEx(
+            pipe,
+            overlapped,
+            &mut transferred,
+            crate::constants::instance::STOP_POLL_MS,
+            false,
+        ) {
+            Ok(()) => return Some(Ok(transferred)),
+            Err(e) if e.code() == HRESULT::from_win32(WAIT_TIMEOUT.0) => {}
+            Err(e) => return Some(Err(e)),
+        }
+        if stop.load(Ordering::Relaxed) || deadline.is_some_and(|d| std::time::Instant::now() >= d)
+        {
+            // The operation has to be over before its OVERLAPPED and buffer go away
+            let _ = CancelIoEx(pipe, Some(overlapped));
+            let _ = GetOverlappedResult(pipe, overlapped, &mut transferred, true);
+            return None;
+        }
+    }
+}
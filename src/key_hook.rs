@@ -0,0 +1,161 @@
+// key_hook.rs - Low-Level Keyboard Hook
+//
+// Reports every key press and release anywhere on the desktop, with a
+// timestamp, for the input recording (see input_recording.rs). Like the mouse
+// hook, the WH_KEYBOARD_LL hook runs on its own thread with its own message
+// loop and hands the events over through a channel. Keys injected by another
+// program (including RustFrame's own replay) are left out.
+
+use anyhow::Result;
+use std::sync::mpsc::Receiver;
+use std::time::Instant;
+
+/// A key went down or up
+#[derive(Debug, Clone, Copy)]
+pub struct KeyEvent {
+    /// Virtual-key code (VK_*)
+    pub vk: u32,
+    pub scan_code: u32,
+    /// Extended key (right Ctrl/Alt, arrows, ...)
+    pub extended: bool,
+    pub down: bool,
+    pub at: Instant,
+}
+
+/// The installed hook (removed when dropped)
+pub struct KeyHook {
+    events: Receiver<KeyEvent>,
+    #[cfg(windows)]
+    thread_id: u32,
+    #[cfg(windows)]
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl KeyHook {
+    /// Install the hook on its own thread
+    #[cfg(windows)]
+    pub fn install() -> Result<Self> {
+        let (sender, events) = std::sync::mpsc::channel();
+        let (ready_sender, ready) = std::sync::mpsc::channel();
+
+        let thread = std::thread::spawn(move || hook_thread(sender, ready_sender));
+        let thread_id = ready
+            .recv()
+            .map_err(|_| anyhow::anyhow!("Keyboard hook thread exited"))?
+            .map_err(|e| anyhow::anyhow!("Failed to install keyboard hook: {}", e))?;
+        log::info!("Keyboard hook installed");
+
+        Ok(Self {
+            events,
+            thread_id,
+            thread: Some(thread),
+        })
+    }
+
+    #[cfg(not(windows))]
+    pub fn install() -> Result<Self> {
+        Err(anyhow::anyhow!(
+            "Keyboard hook is only supported on Windows"
+        ))
+    }
+
+    /// Key events since the last call, oldest first
+    pub fn take_events(&self) -> Vec<KeyEvent> {
+        self.events.try_iter().collect()
+    }
+}
+
+impl Drop for KeyHook {
+    fn drop(&mut self) {
+        #[cfg(windows)]
+        unsafe {
+            use windows::Win32::Foundation::{LPARAM, WPARAM};
+            use windows::Win32::UI::WindowsAndMessaging::{PostThreadMessageW, WM_QUIT};
+
+            let _ = PostThreadMessageW(self.thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+            log::info!("Keyboard hook removed");
+        }
+    }
+}
+
+#[cfg(windows)]
+thread_local! {
+    static SENDER: std::cell::RefCell<Option<std::sync::mpsc::Sender<KeyEvent>>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Install the hook and pump messages until WM_QUIT
+#[cfg(windows)]
+fn hook_thread(
+    sender: std::sync::mpsc::Sender<KeyEvent>,
+    ready: std::sync::mpsc::Sender<windows::core::Result<u32>>,
+) {
+    use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+    use windows::Win32::System::Threading::GetCurrentThreadId;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetMessageW, SetWindowsHookExW, UnhookWindowsHookEx, MSG, WH_KEYBOARD_LL,
+    };
+
+    unsafe {
+        SENDER.with(|s| *s.borrow_mut() = Some(sender));
+
+        let module = match GetModuleHandleW(None) {
+            Ok(module) => module,
+            Err(e) => {
+                let _ = ready.send(Err(e));
+                return;
+            }
+        };
+        let hook = match SetWindowsHookExW(WH_KEYBOARD_LL, Some(hook_proc), Some(module.into()), 0)
+        {
+            Ok(hook) => hook,
+            Err(e) => {
+                let _ = ready.send(Err(e));
+                return;
+            }
+        };
+        let _ = ready.send(Ok(GetCurrentThreadId()));
+
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, None, 0, 0).as_bool() {}
+
+        let _ = UnhookWindowsHookEx(hook);
+    }
+}
+
+/// WH_KEYBOARD_LL callback: forward presses and releases
+#[cfg(windows)]
+unsafe extern "system" fn hook_proc(
+    code: i32,
+    wparam: windows::Win32::Foundation::WPARAM,
+    lparam: windows::Win32::Foundation::LPARAM,
+) -> windows::Win32::Foundation::LRESULT {
+    use windows::Win32::UI::WindowsAndMessaging::*;
+
+    if code == HC_ACTION as i32 {
+        let info = &*(lparam.0 as *const KBDLLHOOKSTRUCT);
+        let down = match wparam.0 as u32 {
+            WM_KEYDOWN | WM_SYSKEYDOWN => Some(true),
+            WM_KEYUP | WM_SYSKEYUP => Some(false),
+            _ => None,
+        };
+        if let (Some(down), false) = (down, info.flags.contains(LLKHF_INJECTED)) {
+            let event = KeyEvent {
+                vk: info.vkCode,
+                scan_code: info.scanCode,
+                extended: info.flags.contains(LLKHF_EXTENDED),
+                down,
+                at: Instant::now(),
+            };
+            SENDER.with(|s| {
+                if let Some(sender) = s.borrow().as_ref() {
+                    let _ = sender.send(event);
+                }
+            });
+        }
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
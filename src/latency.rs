@@ -0,0 +1,383 @@
+// latency.rs - End-to-End Latency Test
+//
+// `RustFrame latency --samples 50` measures how long a change on the screen
+// takes to come back through the capture pipeline, so "low latency" can be
+// checked on each machine instead of taken on trust:
+//
+// 1. A small topmost test window is opened in the middle of the primary
+//    monitor and captured like any region
+// 2. For every sample it flips between black and white; the time it was
+//    painted is taken from QueryPerformanceCounter
+// 3. The captured frames are watched until the new color shows up
+//
+// Two distributions are reported (min, average, median, 95th / 99th
+// percentile, max):
+//
+// - glass to capture: painted → the frame Windows captured with the change
+//   (the frame's SystemRelativeTime) - DWM composition and WGC
+// - glass to memory: painted → the pixels are readable by RustFrame (what the
+//   preview and every export start from) - adds the GPU → CPU copy
+//
+// The wgpu preview adds about one refresh interval of the destination
+// window's monitor on top; it is not measured here because the test runs
+// without the app's windows. RustFrame has no virtual camera output.
+
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+use crate::bench::{adapter_name, now_100ns, BACKEND};
+use crate::capture::{CaptureError, CaptureRect, CaptureSession, Result};
+use crate::constants::latency;
+
+/// A parsed `latency` command line
+#[derive(Debug, Clone)]
+pub struct LatencyArgs {
+    pub samples: u32,
+    /// Print the report as JSON
+    pub json: bool,
+}
+
+impl LatencyArgs {
+    /// Parse the options after `latency`; the error is a message for the user
+    pub fn parse(args: &[String]) -> std::result::Result<Self, String> {
+        let mut samples = latency::DEFAULT_SAMPLES;
+        let mut json = false;
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--samples" => {
+                    let text = args
+                        .next()
+                        .map(|v| v.trim())
+                        .ok_or_else(|| format!("Missing value for {}", arg))?;
+                    samples = text
+                        .parse()
+                        .ok()
+                        .filter(|n| (1..=latency::MAX_SAMPLES).contains(n))
+                        .ok_or_else(|| format!("Invalid --samples '{}'", text))?;
+                }
+                "--json" => json = true,
+                other => return Err(format!("Unknown option '{}'", other)),
+            }
+        }
+        Ok(Self { samples, json })
+    }
+}
+
+/// Spread of a latency (milliseconds)
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct Distribution {
+    pub min_ms: f64,
+    pub average_ms: f64,
+    pub median_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl Distribution {
+    fn of(mut samples: Vec<f64>) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        samples.sort_by(f64::total_cmp);
+        let at = |percent: usize| samples[((samples.len() * percent + 99) / 100).max(1) - 1];
+        Self {
+            min_ms: samples[0],
+            average_ms: samples.iter().sum::<f64>() / samples.len() as f64,
+            median_ms: at(50),
+            p95_ms: at(95),
+            p99_ms: at(99),
+            max_ms: samples[samples.len() - 1],
+        }
+    }
+}
+
+/// What a latency test measured
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencyReport {
+    pub backend: &'static str,
+    /// Name of the GPU the capture ran on
+    pub adapter: String,
+    pub version: &'static str,
+    /// Flashes measured
+    pub samples: u32,
+    /// Flashes that did not come back within the timeout
+    pub missed: u32,
+    /// Painted → captured by Windows (frames without a capture time are left out)
+    pub glass_to_capture: Distribution,
+    /// Painted → pixels in memory
+    pub glass_to_memory: Distribution,
+}
+
+impl std::fmt::Display for LatencyReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(
+            f,
+            "RustFrame {} - {} on {}",
+            self.version, self.backend, self.adapter
+        )?;
+        writeln!(
+            f,
+            "Samples:           {} ({} missed)",
+            self.samples, self.missed
+        )?;
+        let line = |f: &mut std::fmt::Formatter, name: &str, d: &Distribution| {
+            writeln!(
+                f,
+                "{:<18} min {:.1} / avg {:.1} / median {:.1} / p95 {:.1} / p99 {:.1} / max {:.1} ms",
+                name, d.min_ms, d.average_ms, d.median_ms, d.p95_ms, d.p99_ms, d.max_ms
+            )
+        };
+        line(f, "Glass to capture:", &self.glass_to_capture)?;
+        line(f, "Glass to memory:", &self.glass_to_memory)
+    }
+}
+
+/// Flash the test window `samples` times and time every flash
+pub fn run(samples: u32) -> Result<LatencyReport> {
+    let window = TestWindow::open()?;
+    let mut session = CaptureSession::builder()
+        .region(window.rect())
+        .cursor(false)
+        .build()?;
+    let adapter = adapter_name(&session);
+    let timeout = Duration::from_millis(latency::SAMPLE_TIMEOUT_MS);
+
+    // Start from a captured black window
+    let mut white = false;
+    window.fill(white);
+    if wait_for(&mut session, &window, white, timeout)?.is_none() {
+        return Err(CaptureError::Backend(
+            "Waiting for the test window",
+            windows::Win32::Foundation::ERROR_TIMEOUT.into(),
+        ));
+    }
+
+    let mut to_capture = Vec::new();
+    let mut to_memory = Vec::new();
+    let mut missed = 0;
+    for sample in 0..samples {
+        // A changing gap, so the flashes do not line up with the refresh
+        let gap = latency::GAP_MS + (sample as u64 * 7) % latency::GAP_JITTER_MS;
+        pump_until(&window, Instant::now() + Duration::from_millis(gap));
+
+        white = !white;
+        window.fill(white);
+        let painted = now_100ns();
+        match wait_for(&mut session, &window, white, timeout)? {
+            Some((captured, received)) => {
+                if captured > painted {
+                    to_capture.push((captured - painted) as f64 / 10_000.0);
+                }
+                to_memory.push((received - painted) as f64 / 10_000.0);
+            }
+            None => missed += 1,
+        }
+    }
+
+    Ok(LatencyReport {
+        backend: BACKEND,
+        adapter,
+        version: env!("CARGO_PKG_VERSION"),
+        samples,
+        missed,
+        glass_to_capture: Distribution::of(to_capture),
+        glass_to_memory: Distribution::of(to_memory),
+    })
+}
+
+/// Poll the frames until the center pixel has the expected color; returns the
+/// capture time and the time the pixels were read (None = timed out)
+fn wait_for(
+    session: &mut CaptureSession,
+    window: &TestWindow,
+    white: bool,
+    timeout: Duration,
+) -> Result<Option<(i64, i64)>> {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        window.pump();
+        // Polled without sleeping - a sleep can take a whole timer tick
+        let Some(frame) = session.try_next_frame()? else {
+            std::thread::yield_now();
+            continue;
+        };
+        let received = now_100ns();
+        let center = (frame.height / 2 * frame.width + frame.width / 2) as usize;
+        let bright = frame
+            .pixels
+            .get(center)
+            .is_some_and(|p| (p >> 8) & 0xFF > 0x80);
+        if bright == white {
+            return Ok(Some((frame.capture_time, received)));
+        }
+    }
+    Ok(None)
+}
+
+/// Keep the test window responsive until `until`
+fn pump_until(window: &TestWindow, until: Instant) {
+    while Instant::now() < until {
+        window.pump();
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+/// The small topmost window that flashes
+struct TestWindow {
+    hwnd: isize,
+}
+
+#[cfg(windows)]
+impl TestWindow {
+    fn open() -> Result<Self> {
+        use windows::core::PCWSTR;
+        use windows::Win32::Foundation::E_FAIL;
+        use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+        use windows::Win32::UI::WindowsAndMessaging::{
+            CreateWindowExW, GetSystemMetrics, RegisterClassExW, ShowWindow, SM_CXSCREEN,
+            SM_CYSCREEN, SW_SHOWNOACTIVATE, WNDCLASSEXW, WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW,
+            WS_EX_TOPMOST, WS_POPUP,
+        };
+
+        let size = latency::WINDOW_SIZE;
+        unsafe {
+            let instance = GetModuleHandleW(None)
+                .map_err(CaptureError::at("GetModuleHandleW"))?
+                .into();
+            let class_name =
+                crate::utils::wide_string(&format!("RustFrameLatency_{}", std::process::id()));
+            let class = WNDCLASSEXW {
+                cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
+                lpfnWndProc: Some(test_window_proc),
+                hInstance: instance,
+                lpszClassName: PCWSTR(class_name.as_ptr()),
+                ..Default::default()
+            };
+            RegisterClassExW(&class);
+
+            let title = crate::utils::wide_string("RustFrame latency test");
+            let hwnd = CreateWindowExW(
+                WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE,
+                PCWSTR(class_name.as_ptr()),
+                PCWSTR(title.as_ptr()),
+                WS_POPUP,
+                (GetSystemMetrics(SM_CXSCREEN) - size) / 2,
+                (GetSystemMetrics(SM_CYSCREEN) - size) / 2,
+                size,
+                size,
+                None,
+                None,
+                Some(instance),
+                None,
+            )
+            .map_err(CaptureError::at("Creating the test window"))?;
+            if hwnd.is_invalid() {
+                return Err(CaptureError::Backend(
+                    "Creating the test window",
+                    E_FAIL.into(),
+                ));
+            }
+            let _ = ShowWindow(hwnd, SW_SHOWNOACTIVATE);
+            Ok(Self {
+                hwnd: hwnd.0 as isize,
+            })
+        }
+    }
+
+    fn handle(&self) -> windows::Win32::Foundation::HWND {
+        windows::Win32::Foundation::HWND(self.hwnd as *mut std::ffi::c_void)
+    }
+
+    /// Screen rectangle of the window
+    fn rect(&self) -> CaptureRect {
+        crate::window_mask::window_rect(self.hwnd).unwrap_or(CaptureRect {
+            x: 0,
+            y: 0,
+            width: latency::WINDOW_SIZE as u32,
+            height: latency::WINDOW_SIZE as u32,
+        })
+    }
+
+    /// Paint the whole window black or white
+    fn fill(&self, white: bool) {
+        use windows::Win32::Foundation::{COLORREF, RECT};
+        use windows::Win32::Graphics::Gdi::{
+            CreateSolidBrush, DeleteObject, FillRect, GdiFlush, GetDC, ReleaseDC,
+        };
+
+        let size = latency::WINDOW_SIZE;
+        let color = if white { 0x00FF_FFFF } else { 0 };
+        unsafe {
+            let dc = GetDC(Some(self.handle()));
+            let brush = CreateSolidBrush(COLORREF(color));
+            let rect = RECT {
+                left: 0,
+                top: 0,
+                right: size,
+                bottom: size,
+            };
+            FillRect(dc, &rect, brush);
+            let _ = GdiFlush();
+            let _ = DeleteObject(brush.into());
+            ReleaseDC(Some(self.handle()), dc);
+        }
+    }
+
+    /// Handle the window's pending messages
+    fn pump(&self) {
+        use windows::Win32::UI::WindowsAndMessaging::{
+            DispatchMessageW, PeekMessageW, TranslateMessage, MSG, PM_REMOVE,
+        };
+
+        let mut msg = MSG::default();
+        unsafe {
+            while PeekMessageW(&mut msg, Some(self.handle()), 0, 0, PM_REMOVE).as_bool() {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        }
+    }
+}
+
+/// The test window is painted from outside; everything else is the default
+#[cfg(windows)]
+unsafe extern "system" fn test_window_proc(
+    hwnd: windows::Win32::Foundation::HWND,
+    msg: u32,
+    wparam: windows::Win32::Foundation::WPARAM,
+    lparam: windows::Win32::Foundation::LPARAM,
+) -> windows::Win32::Foundation::LRESULT {
+    windows::Win32::UI::WindowsAndMessaging::DefWindowProcW(hwnd, msg, wparam, lparam)
+}
+
+#[cfg(windows)]
+impl Drop for TestWindow {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = windows::Win32::UI::WindowsAndMessaging::DestroyWindow(self.handle());
+        }
+    }
+}
+
+#[cfg(not(windows))]
+impl TestWindow {
+    fn open() -> Result<Self> {
+        Err(CaptureError::Unsupported)
+    }
+
+    fn rect(&self) -> CaptureRect {
+        CaptureRect {
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 0,
+        }
+    }
+
+    fn fill(&self, _white: bool) {}
+
+    fn pump(&self) {}
+}
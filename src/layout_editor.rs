@@ -0,0 +1,461 @@
+// layout_editor.rs - Layout Editor (Custom Composite Layout)
+//
+// Arranges the sources of the output by hand (tray → Capture Regions → Edit
+// Layout). The overlay shows every source as a box - scaled down when the
+// canvas is larger than the capture region - while the output shows the
+// result live:
+// - Drag a source to move it
+// - Drag a corner handle to scale it (the aspect ratio is kept)
+// - Drag an edge handle to crop it (dragging back outwards uncrops)
+// - Page Up / Page Down bring the selected source forward / send it back,
+//   Tab selects the next source, the arrows move it (Shift = 10 pixels)
+//
+// Edges snap to the canvas origin and to the edges and centers of the other
+// sources; guide lines show what a source snapped to.
+//
+// The screen (main capture region) stays at the top-left corner at its own
+// size: annotations, privacy regions and the other overlays are drawn in its
+// coordinates, and moving it would move a redaction away from what it hides.
+// Move and resize the capture region itself instead - its z-order can still be
+// changed.
+//
+// The rectangles are kept per source in the custom layout (see composite.rs),
+// which is saved with scenes.
+
+use std::cell::Cell;
+
+use crate::bitmap_font::Canvas;
+use crate::composite::{SourceId, SourceRect};
+use crate::constants::layout_editor;
+use crate::drawing;
+use crate::text::{self, TextStyle};
+
+/// Mouse drag in progress
+#[derive(Debug, Clone, Copy)]
+enum Drag {
+    /// Moving source `index`; `grab` is the cursor offset inside it
+    Move { index: usize, grab: (i32, i32) },
+    /// Scaling source `index` by the corner on the `right` / `bottom` side
+    Scale {
+        index: usize,
+        right: bool,
+        bottom: bool,
+    },
+    /// Cropping source `index` at an edge (index into SourceRect::crop)
+    Crop { index: usize, edge: usize },
+}
+
+/// The sources being arranged and the state of the mouse
+pub struct LayoutEditor {
+    /// Rectangles in output order (canvas coordinates)
+    rects: Vec<SourceRect>,
+    selected: Option<usize>,
+    drag: Option<Drag>,
+    /// Snap guides of the current drag: a vertical line at x, a horizontal at y
+    guides: (Option<i32>, Option<i32>),
+    /// Overlay pixels per canvas pixel and the handle size, from the last drawing
+    view_scale: Cell<f32>,
+    handle_size: Cell<i32>,
+    /// Bumped on every change of the rectangles
+    revision: u64,
+}
+
+impl LayoutEditor {
+    pub fn new() -> Self {
+        Self {
+            rects: Vec::new(),
+            selected: None,
+            drag: None,
+            guides: (None, None),
+            view_scale: Cell::new(1.0),
+            handle_size: Cell::new(layout_editor::HANDLE_SIZE),
+            revision: 0,
+        }
+    }
+
+    /// Start editing the rectangles of the current sources (see custom_rects)
+    pub fn load(&mut self, rects: Vec<SourceRect>) {
+        self.selected = self.selected.filter(|&i| i < rects.len());
+        self.rects = rects;
+        self.drag = None;
+        self.guides = (None, None);
+    }
+
+    pub fn rects(&self) -> &[SourceRect] {
+        &self.rects
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    /// Overlay (frame) coordinates -> canvas coordinates
+    fn to_canvas(&self, x: i32, y: i32) -> (i32, i32) {
+        let scale = self.view_scale.get();
+        ((x as f32 / scale) as i32, (y as f32 / scale) as i32)
+    }
+
+    /// The screen keeps its place (see the top of this file)
+    fn is_movable(&self, index: usize) -> bool {
+        self.rects
+            .get(index)
+            .is_some_and(|r| r.source != SourceId::Screen)
+    }
+
+    /// Indices from the bottom to the top (compose draws in this order)
+    fn z_order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.rects.len()).collect();
+        order.sort_by_key(|&i| self.rects[i].z);
+        order
+    }
+
+    /// Topmost source under the point
+    fn source_at(&self, x: i32, y: i32) -> Option<usize> {
+        self.z_order().into_iter().rev().find(|&i| {
+            let r = &self.rects[i];
+            let (x0, y0) = (r.x as i32, r.y as i32);
+            x >= x0 && x < x0 + r.width as i32 && y >= y0 && y < y0 + r.height as i32
+        })
+    }
+
+    /// Handles of a source: the four corners (scale), then the middle of the
+    /// left, top, right and bottom edge (crop)
+    fn handles(r: &SourceRect) -> [(i32, i32); 8] {
+        let (x0, y0) = (r.x as i32, r.y as i32);
+        let (x1, y1) = (x0 + r.width as i32, y0 + r.height as i32);
+        let (cx, cy) = ((x0 + x1) / 2, (y0 + y1) / 2);
+        [
+            (x0, y0),
+            (x1, y0),
+            (x0, y1),
+            (x1, y1),
+            (x0, cy),
+            (cx, y0),
+            (x1, cy),
+            (cx, y1),
+        ]
+    }
+
+    /// Mouse button pressed: grab a handle of the selected source, or select
+    /// and grab the source under the cursor
+    pub fn begin_drag(&mut self, x: i32, y: i32) {
+        let (x, y) = self.to_canvas(x, y);
+        let reach = (self.handle_size.get() as f32 / self.view_scale.get()) as i32;
+
+        if let Some(index) = self.selected.filter(|&i| self.is_movable(i)) {
+            let handle = Self::handles(&self.rects[index])
+                .iter()
+                .position(|&(hx, hy)| (x - hx).abs() <= reach && (y - hy).abs() <= reach);
+            if let Some(handle) = handle {
+                self.drag = Some(match handle {
+                    0..=3 => Drag::Scale {
+                        index,
+                        right: handle % 2 == 1,
+                        bottom: handle >= 2,
+                    },
+                    _ => Drag::Crop {
+                        index,
+                        edge: handle - 4,
+                    },
+                });
+                return;
+            }
+        }
+
+        self.selected = self.source_at(x, y);
+        self.drag = self.selected.filter(|&i| self.is_movable(i)).map(|index| {
+            let r = &self.rects[index];
+            Drag::Move {
+                index,
+                grab: (x - r.x as i32, y - r.y as i32),
+            }
+        });
+        self.revision += 1;
+    }
+
+    /// Mouse moved with the button held
+    pub fn update_drag(&mut self, x: i32, y: i32) {
+        let (x, y) = self.to_canvas(x, y);
+        match self.drag {
+            Some(Drag::Move { index, grab }) => {
+                let r = self.rects[index];
+                let (w, h) = (r.width as i32, r.height as i32);
+                let (mut left, mut top) = ((x - grab.0).max(0), (y - grab.1).max(0));
+                let snap_x = self.snap(index, false, &[left, left + w / 2, left + w]);
+                let snap_y = self.snap(index, true, &[top, top + h / 2, top + h]);
+                left = (left + snap_x.map_or(0, |s| s.0)).max(0);
+                top = (top + snap_y.map_or(0, |s| s.0)).max(0);
+                self.guides = (snap_x.map(|s| s.1), snap_y.map(|s| s.1));
+                let r = &mut self.rects[index];
+                (r.x, r.y) = (left as u32, top as u32);
+            }
+            Some(Drag::Scale {
+                index,
+                right,
+                bottom,
+            }) => {
+                let snap_x = self.snap(index, false, &[x]);
+                let x = x + snap_x.map_or(0, |s| s.0);
+                self.guides = (snap_x.map(|s| s.1), None);
+                scale_to(&mut self.rects[index], right, bottom, (x, y));
+            }
+            Some(Drag::Crop { index, edge }) => {
+                let vertical = edge % 2 == 1;
+                let position = if vertical { y } else { x };
+                let snap = self.snap(index, vertical, &[position]);
+                let position = position + snap.map_or(0, |s| s.0);
+                self.guides = if vertical {
+                    (None, snap.map(|s| s.1))
+                } else {
+                    (snap.map(|s| s.1), None)
+                };
+                crop_to(&mut self.rects[index], edge, position);
+            }
+            None => return,
+        }
+        self.revision += 1;
+    }
+
+    /// Mouse button released
+    pub fn end_drag(&mut self) {
+        if self.drag.take().is_some() {
+            self.guides = (None, None);
+            self.revision += 1;
+        }
+    }
+
+    /// The closest snap of `values` (x or y coordinates of source `index`):
+    /// (shift to apply, guide position), None if nothing is close enough
+    fn snap(&self, index: usize, vertical: bool, values: &[i32]) -> Option<(i32, i32)> {
+        let tolerance =
+            (layout_editor::SNAP_DISTANCE as f32 / self.view_scale.get()).round() as i32;
+        let mut targets = vec![0];
+        let others = self.rects.iter().enumerate().filter(|&(i, _)| i != index);
+        for r in others.map(|(_, r)| r) {
+            let (start, size) = if vertical {
+                (r.y as i32, r.height as i32)
+            } else {
+                (r.x as i32, r.width as i32)
+            };
+            targets.extend([start, start + size / 2, start + size]);
+        }
+        values
+            .iter()
+            .flat_map(|&value| targets.iter().map(move |&target| (target - value, target)))
+            .filter(|(shift, _)| shift.abs() <= tolerance)
+            .min_by_key(|(shift, _)| shift.abs())
+    }
+
+    /// Draw the selected source above all others (Page Up)
+    pub fn bring_forward(&mut self) {
+        let Some(index) = self.selected else {
+            return;
+        };
+        let top = self.rects.iter().map(|r| r.z).max().unwrap_or(0);
+        if self.z_order().last() != Some(&index) {
+            self.rects[index].z = top + 1;
+            self.revision += 1;
+        }
+    }
+
+    /// Draw the selected source below all others (Page Down)
+    pub fn send_backward(&mut self) {
+        let Some(index) = self.selected else {
+            return;
+        };
+        let bottom = self.rects.iter().map(|r| r.z).min().unwrap_or(0);
+        if self.z_order().first() != Some(&index) {
+            self.rects[index].z = bottom - 1;
+            self.revision += 1;
+        }
+    }
+
+    /// Select the next source (Tab)
+    pub fn select_next(&mut self) {
+        if !self.rects.is_empty() {
+            self.selected = Some(self.selected.map_or(0, |i| (i + 1) % self.rects.len()));
+            self.revision += 1;
+        }
+    }
+
+    /// Move the selected source by a few pixels (arrow keys)
+    pub fn nudge(&mut self, dx: i32, dy: i32) {
+        if let Some(index) = self.selected.filter(|&i| self.is_movable(i)) {
+            let r = &mut self.rects[index];
+            r.x = (r.x as i32 + dx).max(0) as u32;
+            r.y = (r.y as i32 + dy).max(0) as u32;
+            self.revision += 1;
+        }
+    }
+
+    /// Draw the sources, the handles of the selected one and the snap guides
+    /// on the overlay; `scale`: scale factor of the overlay window
+    pub fn draw(&self, canvas: &mut Canvas, offset_x: i32, offset_y: i32, scale: f32) {
+        // The whole canvas has to fit into the capture region. The scale is
+        // kept during a drag - the canvas grows with the dragged source, and
+        // a shrinking view would push it further away from the cursor.
+        let (area_w, area_h) = (canvas.width - 2 * offset_x, canvas.height - 2 * offset_y);
+        if self.drag.is_none() {
+            let canvas_w = self.rects.iter().map(|r| r.x + r.width).max().unwrap_or(1);
+            let canvas_h = self.rects.iter().map(|r| r.y + r.height).max().unwrap_or(1);
+            let view = (area_w as f32 / canvas_w.max(1) as f32)
+                .min(area_h as f32 / canvas_h.max(1) as f32)
+                .clamp(layout_editor::MIN_VIEW_SCALE, 1.0);
+            self.view_scale.set(view);
+        }
+        let view = self.view_scale.get();
+        let handle = text::scaled(layout_editor::HANDLE_SIZE, scale);
+        self.handle_size.set(handle);
+        let at = |value: u32| (value as f32 * view) as i32;
+
+        let style = TextStyle {
+            color: layout_editor::OUTLINE,
+            size: text::scaled(layout_editor::LABEL_SIZE, scale),
+            bold: false,
+        };
+        let padding = text::scaled(4, scale);
+
+        for index in self.z_order() {
+            let r = &self.rects[index];
+            let selected = self.selected == Some(index);
+            let (x0, y0) = (offset_x + at(r.x), offset_y + at(r.y));
+            let (x1, y1) = (offset_x + at(r.x + r.width), offset_y + at(r.y + r.height));
+            let color = if selected {
+                layout_editor::SELECTED
+            } else {
+                layout_editor::OUTLINE
+            };
+            drawing::fill_rect(canvas, x0, y0, x1 - x0, y1 - y0, layout_editor::FILL);
+            drawing::draw_rect(canvas, x0, y0, x1, y1, 2, color);
+
+            let mut label = format!("{} ({}x{})", r.source.label(), r.width, r.height);
+            if r.crop.iter().any(|&c| c > 0.0) {
+                label.push_str(" cropped");
+            }
+            if r.source == SourceId::Screen {
+                label.push_str(" - fixed");
+            }
+            text::draw_text(canvas, x0 + padding, y0 + padding, &label, &style);
+
+            if selected && self.is_movable(index) {
+                for (hx, hy) in Self::handles(r) {
+                    let (hx, hy) = (offset_x + at(hx as u32), offset_y + at(hy as u32));
+                    drawing::fill_rect(
+                        canvas,
+                        hx - handle / 2,
+                        hy - handle / 2,
+                        handle,
+                        handle,
+                        layout_editor::SELECTED,
+                    );
+                }
+            }
+        }
+
+        if let Some(x) = self.guides.0 {
+            let x = offset_x + at(x.max(0) as u32);
+            drawing::draw_line(
+                canvas,
+                x,
+                offset_y,
+                x,
+                offset_y + area_h,
+                1,
+                layout_editor::GUIDE,
+            );
+        }
+        if let Some(y) = self.guides.1 {
+            let y = offset_y + at(y.max(0) as u32);
+            drawing::draw_line(
+                canvas,
+                offset_x,
+                y,
+                offset_x + area_w,
+                y,
+                1,
+                layout_editor::GUIDE,
+            );
+        }
+    }
+}
+
+/// Scale a source by its corner on the `right` / `bottom` side to the point,
+/// keeping the aspect ratio and the opposite corner in place
+fn scale_to(r: &mut SourceRect, right: bool, bottom: bool, (x, y): (i32, i32)) {
+    let anchor_x = if right { r.x } else { r.x + r.width } as i32;
+    let anchor_y = if bottom { r.y } else { r.y + r.height } as i32;
+    let aspect = r.width.max(1) as f32 / r.height.max(1) as f32;
+
+    // The larger of the two distances decides, so dragging either way works
+    let mut width = ((x - anchor_x).abs() as f32).max((y - anchor_y).abs() as f32 * aspect);
+    // Keep the canvas origin as the limit on the left / top
+    if !right {
+        width = width.min(anchor_x as f32);
+    }
+    if !bottom {
+        width = width.min(anchor_y as f32 * aspect);
+    }
+    let min = layout_editor::MIN_SIZE as f32;
+    let width = width.max(min).max(min * aspect);
+    let (width, height) = (width.round() as u32, (width / aspect).round() as u32);
+
+    r.x = if right {
+        anchor_x as u32
+    } else {
+        (anchor_x - width as i32).max(0) as u32
+    };
+    r.y = if bottom {
+        anchor_y as u32
+    } else {
+        (anchor_y - height as i32).max(0) as u32
+    };
+    (r.width, r.height) = (width, height);
+}
+
+/// Move a crop edge (0 left, 1 top, 2 right, 3 bottom) of a source to
+/// `position` on the canvas; the rest of the source stays where it is
+fn crop_to(r: &mut SourceRect, edge: usize, position: i32) {
+    let vertical = edge % 2 == 1;
+    let start_edge = edge < 2;
+    let (start, size) = if vertical {
+        (r.y as i32, r.height as i32)
+    } else {
+        (r.x as i32, r.width as i32)
+    };
+    let opposite = (edge + 2) % 4;
+    let visible = 1.0 - r.crop[edge] - r.crop[opposite];
+    // Share of the frame per canvas pixel
+    let per_pixel = visible / size.max(1) as f32;
+
+    // Pixels cut off by this drag (negative: brought back)
+    let cut = if start_edge {
+        position - start
+    } else {
+        start + size - position
+    };
+    let most = ((visible - layout_editor::MIN_VISIBLE) / per_pixel)
+        .min((size - layout_editor::MIN_SIZE as i32) as f32)
+        .max(0.0) as i32;
+    let mut least = -((r.crop[edge] / per_pixel) as i32);
+    if start_edge {
+        // Not past the canvas origin
+        least = least.max(-start);
+    }
+    let cut = cut.clamp(least.min(0), most);
+
+    r.crop[edge] = (r.crop[edge] + cut as f32 * per_pixel).max(0.0);
+    let (start, size) = if start_edge {
+        (start + cut, size - cut)
+    } else {
+        (start, size - cut)
+    };
+    if vertical {
+        (r.y, r.height) = (start as u32, size as u32);
+    } else {
+        (r.x, r.width) = (start as u32, size as u32);
+    }
+}
@@ -0,0 +1,46 @@
+// lib.rs - RustFrame as a Library
+//
+// The capture engine and the modules it needs, for embedding region capture
+// in another application without the RustFrame window, tray and hotkeys.
+// The RustFrame app itself (main.rs) uses the same modules.
+//
+// The entry point is `capture::CaptureSession`:
+//
+//   let mut session = CaptureSession::builder()
+//       .region(CaptureRect { x: 100, y: 100, width: 1280, height: 720 })
+//       .cursor(true)
+//       .fps(60)
+//       .build()?;
+//   while let Some(frame) = session.next_frame(Duration::from_secs(1))? {
+//       // frame.pixels: BGRA, frame.width x frame.height
+//   }
+//
+// `rustframe::record(region, duration, path, &options)` records a region
+// straight to an MP4 file (see record.rs).
+//
+// `source::FrameSource` is the one trait every frame producer implements
+// (capture regions, webcams, images), and `capture::CaptureFrame` the frame
+// type of the session and its async stream. Everything else is shared with
+// the app and has no stable API.
+
+// The engine's state types are created with new(), like in main.rs
+#![allow(clippy::new_without_default)]
+
+pub mod capture;
+pub mod record;
+pub mod source;
+
+pub use record::{record, QualityReport, RecordOptions, RecordSummary};
+
+#[doc(hidden)]
+pub mod cli;
+#[doc(hidden)]
+pub mod constants;
+#[doc(hidden)]
+pub mod game_capture;
+#[doc(hidden)]
+pub mod quality;
+#[doc(hidden)]
+pub mod recovery;
+#[doc(hidden)]
+pub mod utils;
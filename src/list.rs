@@ -0,0 +1,250 @@
+// list.rs - Monitor and Window Lists from the Command Line
+//
+// `RustFrame list-monitors` and `RustFrame list-windows` print what a script
+// needs to compute a region before it starts a capture:
+//
+//   RustFrame list-monitors
+//   #  Name           X     Y     Width  Height  DPI  Primary
+//   1  \\.\DISPLAY1   0     0     2560   1440    144  yes
+//
+// With --json the same list is printed as a JSON array:
+//
+//   [{ "index": 1, "name": "\\\\.\\DISPLAY1", "x": 0, "y": 0,
+//      "width": 2560, "height": 1440, "dpi": 144, "primary": true }]
+//
+// Rectangles are physical pixels in virtual screen coordinates - the values
+// --region of `RustFrame record` / `shot` takes. Monitor numbers are the
+// ones `shot --monitor` takes; windows are listed in z-order, topmost first.
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::constants::list;
+
+/// A connected monitor
+#[derive(Debug, Clone, Serialize)]
+pub struct Monitor {
+    /// 1-based, in the order Windows lists the monitors
+    pub index: usize,
+    /// Device name, e.g. "\\.\DISPLAY1"
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    /// Effective DPI (96 = 100 % scaling)
+    pub dpi: u32,
+    pub primary: bool,
+}
+
+/// A visible top-level window
+#[derive(Debug, Clone, Serialize)]
+pub struct Window {
+    /// 1-based, topmost first
+    pub index: usize,
+    pub title: String,
+    /// Process file name, e.g. "Code.exe"
+    pub process: String,
+    pub pid: u32,
+    pub hwnd: isize,
+    /// Visible bounds (without the invisible resize borders)
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub dpi: u32,
+}
+
+/// `list-monitors [--json]`
+pub fn print_monitors(json: bool) -> Result<()> {
+    let monitors = monitors();
+    if json {
+        println!("{}", serde_json::to_string_pretty(&monitors)?);
+        return Ok(());
+    }
+    let rows = monitors
+        .iter()
+        .map(|m| {
+            vec![
+                m.index.to_string(),
+                m.name.clone(),
+                m.x.to_string(),
+                m.y.to_string(),
+                m.width.to_string(),
+                m.height.to_string(),
+                m.dpi.to_string(),
+                if m.primary { "yes" } else { "" }.to_string(),
+            ]
+        })
+        .collect();
+    print_table(
+        &["#", "Name", "X", "Y", "Width", "Height", "DPI", "Primary"],
+        rows,
+    );
+    Ok(())
+}
+
+/// `list-windows [--json]`
+pub fn print_windows(json: bool) -> Result<()> {
+    let windows = windows();
+    if json {
+        println!("{}", serde_json::to_string_pretty(&windows)?);
+        return Ok(());
+    }
+    let rows = windows
+        .iter()
+        .map(|w| {
+            vec![
+                w.index.to_string(),
+                format!("0x{:X}", w.hwnd),
+                w.process.clone(),
+                w.x.to_string(),
+                w.y.to_string(),
+                w.width.to_string(),
+                w.height.to_string(),
+                w.dpi.to_string(),
+                w.title.clone(),
+            ]
+        })
+        .collect();
+    print_table(
+        &[
+            "#", "HWND", "Process", "X", "Y", "Width", "Height", "DPI", "Title",
+        ],
+        rows,
+    );
+    Ok(())
+}
+
+/// Columns padded to their widest cell
+fn print_table(header: &[&str], rows: Vec<Vec<String>>) {
+    let mut widths: Vec<usize> = header.iter().map(|h| h.chars().count()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |cells: Vec<String>| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<1$}", cell, width))
+            .collect();
+        println!("{}", padded.join("  ").trim_end());
+    };
+    line(header.iter().map(|h| h.to_string()).collect());
+    for row in rows {
+        line(row);
+    }
+}
+
+/// All monitors in the order Windows lists them
+#[cfg(windows)]
+pub fn monitors() -> Vec<Monitor> {
+    use windows::core::BOOL;
+    use windows::Win32::Foundation::{LPARAM, RECT};
+    use windows::Win32::Graphics::Gdi::{
+        EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITORINFO, MONITORINFOEXW,
+    };
+    use windows::Win32::UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
+    use windows::Win32::UI::WindowsAndMessaging::MONITORINFOF_PRIMARY;
+
+    unsafe extern "system" fn enum_proc(
+        monitor: HMONITOR,
+        _hdc: HDC,
+        _rect: *mut RECT,
+        lparam: LPARAM,
+    ) -> BOOL {
+        let monitors = &mut *(lparam.0 as *mut Vec<Monitor>);
+        let mut info = MONITORINFOEXW::default();
+        info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+        if !GetMonitorInfoW(
+            monitor,
+            &mut info as *mut MONITORINFOEXW as *mut MONITORINFO,
+        )
+        .as_bool()
+        {
+            return BOOL(1);
+        }
+        // X and Y are always the same
+        let (mut dpi, mut dpi_y) = (0u32, 0u32);
+        if GetDpiForMonitor(monitor, MDT_EFFECTIVE_DPI, &mut dpi, &mut dpi_y).is_err() {
+            dpi = list::DEFAULT_DPI;
+        }
+        let len = info
+            .szDevice
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(info.szDevice.len());
+        let rect = info.monitorInfo.rcMonitor;
+        monitors.push(Monitor {
+            index: monitors.len() + 1,
+            name: String::from_utf16_lossy(&info.szDevice[..len]),
+            x: rect.left,
+            y: rect.top,
+            width: (rect.right - rect.left) as u32,
+            height: (rect.bottom - rect.top) as u32,
+            dpi,
+            primary: info.monitorInfo.dwFlags & MONITORINFOF_PRIMARY != 0,
+        });
+        BOOL(1)
+    }
+
+    let mut monitors: Vec<Monitor> = Vec::new();
+    unsafe {
+        let _ = EnumDisplayMonitors(
+            None,
+            None,
+            Some(enum_proc),
+            LPARAM(&mut monitors as *mut Vec<Monitor> as isize),
+        );
+    }
+    monitors
+}
+
+#[cfg(not(windows))]
+pub fn monitors() -> Vec<Monitor> {
+    Vec::new()
+}
+
+/// Visible top-level windows with a title, topmost first
+#[cfg(windows)]
+pub fn windows() -> Vec<Window> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::HiDpi::GetDpiForWindow;
+    use windows::Win32::UI::WindowsAndMessaging::GetWindowTextW;
+
+    crate::window_mask::visible_windows()
+        .into_iter()
+        .filter_map(|w| {
+            let hwnd = HWND(w.hwnd as *mut std::ffi::c_void);
+            let mut buffer = [0u16; 512];
+            let len = unsafe { GetWindowTextW(hwnd, &mut buffer) };
+            if len <= 0 {
+                return None;
+            }
+            Some(Window {
+                index: 0,
+                title: String::from_utf16_lossy(&buffer[..len as usize]),
+                process: crate::window_mask::process_name(w.pid).unwrap_or_default(),
+                pid: w.pid,
+                hwnd: w.hwnd,
+                x: w.left,
+                y: w.top,
+                width: (w.right - w.left).max(0) as u32,
+                height: (w.bottom - w.top).max(0) as u32,
+                dpi: unsafe { GetDpiForWindow(hwnd) },
+            })
+        })
+        .enumerate()
+        .map(|(i, window)| Window {
+            index: i + 1,
+            ..window
+        })
+        .collect()
+}
+
+#[cfg(not(windows))]
+pub fn windows() -> Vec<Window> {
+    Vec::new()
+}
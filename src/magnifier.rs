@@ -0,0 +1,203 @@
+// magnifier.rs - Zoom Lens
+//
+// Shows a 2x-8x zoom of the area around the cursor, either:
+// 1. Composited into the output frames (viewers see the lens), or
+// 2. In a local-only window next to the cursor (excluded from capture,
+//    so only the presenter sees it)
+//
+// The lens samples the cropped capture frame, so cursor coordinates are
+// relative to the capture region just like annotations and the spotlight.
+
+use crate::bitmap_font::Canvas;
+use crate::constants::magnifier;
+use crate::drawing;
+
+/// Where the zoomed image is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MagnifierMode {
+    /// Separate window next to the cursor, visible only on this screen
+    Local,
+    /// Drawn into the captured frames
+    Output,
+}
+
+impl MagnifierMode {
+    /// Short label for logs and titles
+    pub fn label(self) -> &'static str {
+        match self {
+            MagnifierMode::Local => "Local",
+            MagnifierMode::Output => "Output",
+        }
+    }
+}
+
+/// Magnifier state for the current capture session
+pub struct Magnifier {
+    enabled: bool,
+    mode: MagnifierMode,
+    zoom: u32,
+    cursor: (i32, i32),
+    /// Bumped on every visible change so the renderer knows when to recomposite
+    revision: u64,
+}
+
+impl Magnifier {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            mode: MagnifierMode::Local,
+            zoom: magnifier::DEFAULT_ZOOM,
+            cursor: (0, 0),
+            revision: 0,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if self.enabled != enabled {
+            self.enabled = enabled;
+            self.revision += 1;
+        }
+    }
+
+    /// Whether the lens is composited into the captured frames
+    pub fn draws_into_output(&self) -> bool {
+        self.enabled && self.mode == MagnifierMode::Output
+    }
+
+    pub fn mode(&self) -> MagnifierMode {
+        self.mode
+    }
+
+    /// Switch between local-only window and output compositing
+    pub fn toggle_mode(&mut self) {
+        self.mode = match self.mode {
+            MagnifierMode::Local => MagnifierMode::Output,
+            MagnifierMode::Output => MagnifierMode::Local,
+        };
+        self.revision += 1;
+    }
+
+    pub fn zoom(&self) -> u32 {
+        self.zoom
+    }
+
+    /// Change the zoom factor by the given number of steps (clamped to 2x-8x)
+    pub fn adjust_zoom(&mut self, delta: i32) {
+        let zoom = (self.zoom as i32 + delta)
+            .clamp(magnifier::MIN_ZOOM as i32, magnifier::MAX_ZOOM as i32)
+            as u32;
+        if zoom != self.zoom {
+            self.zoom = zoom;
+            self.revision += 1;
+        }
+    }
+
+    /// Feed the current cursor position (capture-region coordinates)
+    pub fn update_cursor(&mut self, x: i32, y: i32) {
+        if self.cursor != (x, y) {
+            self.cursor = (x, y);
+            self.revision += 1;
+        }
+    }
+
+    /// Revision counter - changes whenever the rendered result would change
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Top-left corner of the lens, relative to the cursor
+    /// The lens sits below-right of the cursor and flips when it would leave the bounds
+    pub fn lens_position(&self, bounds_width: i32, bounds_height: i32) -> (i32, i32) {
+        let size = magnifier::LENS_SIZE;
+        let offset = magnifier::CURSOR_OFFSET;
+        let (cx, cy) = self.cursor;
+
+        let x = if cx + offset + size > bounds_width {
+            cx - offset - size
+        } else {
+            cx + offset
+        };
+        let y = if cy + offset + size > bounds_height {
+            cy - offset - size
+        } else {
+            cy + offset
+        };
+        (x, y)
+    }
+
+    /// Render the zoomed area around the cursor (plus border) into `out`
+    /// `source` is the cropped capture frame (ARGB, `source_width` x `source_height`)
+    pub fn render_lens(
+        &self,
+        source: &[u32],
+        source_width: i32,
+        source_height: i32,
+        out: &mut Canvas,
+    ) {
+        let zoom = self.zoom.max(1) as i32;
+        let (cx, cy) = self.cursor;
+
+        for y in 0..out.height {
+            // Nearest-neighbor sampling keeps pixels crisp - that's the point of a zoom lens
+            let sy = cy + (y - out.height / 2).div_euclid(zoom);
+            for x in 0..out.width {
+                let sx = cx + (x - out.width / 2).div_euclid(zoom);
+                let inside = sx >= 0 && sx < source_width && sy >= 0 && sy < source_height;
+                out.pixels[(y * out.width + x) as usize] = if inside {
+                    source[(sy * source_width + sx) as usize] | 0xFF00_0000
+                } else {
+                    magnifier::OUTSIDE
+                };
+            }
+        }
+
+        // Border
+        let b = magnifier::BORDER_WIDTH;
+        let (w, h) = (out.width, out.height);
+        drawing::fill_rect(out, 0, 0, w, b, magnifier::BORDER);
+        drawing::fill_rect(out, 0, h - b, w, b, magnifier::BORDER);
+        drawing::fill_rect(out, 0, 0, b, h, magnifier::BORDER);
+        drawing::fill_rect(out, w - b, 0, b, h, magnifier::BORDER);
+    }
+
+    /// Composite the lens into a captured frame (Output mode only)
+    pub fn apply(&self, canvas: &mut Canvas) {
+        if !self.draws_into_output() {
+            return;
+        }
+
+        // Sample from a copy - the lens overlaps the area it magnifies
+        let source = canvas.pixels.to_vec();
+        let size = magnifier::LENS_SIZE;
+        let mut lens = vec![0u32; (size * size) as usize];
+        self.render_lens(
+            &source,
+            canvas.width,
+            canvas.height,
+            &mut Canvas {
+                pixels: &mut lens,
+                width: size,
+                height: size,
+            },
+        );
+
+        let (lx, ly) = self.lens_position(canvas.width, canvas.height);
+        for y in 0..size {
+            let py = ly + y;
+            if py < 0 || py >= canvas.height {
+                continue;
+            }
+            for x in 0..size {
+                let px = lx + x;
+                if px >= 0 && px < canvas.width {
+                    canvas.pixels[(py * canvas.width + px) as usize] =
+                        lens[(y * size + x) as usize];
+                }
+            }
+        }
+    }
+}
@@ -10,40 +10,234 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use anyhow::Result;
-use log::{error, info};
-use std::time::Instant;
+use log::{error, info, warn};
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::time::{Duration, Instant};
 use winit::application::ApplicationHandler;
-use winit::dpi::PhysicalSize;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
 use winit::event::WindowEvent;
-use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
+use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop, EventLoopProxy};
 use winit::window::WindowId;
 
 // Tray icon and menu
-use muda::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem};
-use tray_icon::{Icon, TrayIcon, TrayIconBuilder};
+use muda::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu};
+use tray_icon::{Icon, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent};
 
 // Image loading for tray icon
 use image::GenericImageView;
 
+mod accessibility;
+mod annotation;
+mod app_settings;
+mod autostart;
+mod bench;
 mod bitmap_font;
-mod capture;
-mod constants;
+mod check_config;
+mod chroma_key;
+mod click_highlight;
+mod color_picker;
+mod composite;
+mod config_bundle;
+mod control_stdio;
+mod credentials;
+mod deep_link;
+mod diagnose;
+mod drawing;
+mod elevation;
+mod enhance;
+mod filter;
+mod frame_diff;
+mod frame_hold;
+mod frame_scan;
+mod gamepad;
+mod grid;
+mod hotkeys;
+mod http;
+mod http_api;
+mod image_source;
+mod input_recording;
+mod instance;
+mod key_hook;
+mod latency;
+mod layout_editor;
+mod list;
+mod magnifier;
+mod markers;
+mod measure;
+mod midi;
+mod mouse_hook;
+mod osc;
+mod pan;
+mod pause_screen;
+mod plugin;
+mod post_actions;
+mod power;
+mod privacy;
+mod profile;
 mod renderer;
+mod scene;
+mod screenshot;
+mod scripting;
+mod session_end;
 mod settings_dialog;
-mod utils;
+mod settings_file;
+mod shell_menu;
+mod shot;
+mod spotlight;
+mod stream_deck;
+mod test_pattern;
+mod text;
+mod theme;
+mod thread_priority;
+mod timer;
+mod toolbar;
+mod touch;
+mod transition;
+mod upload;
+mod webcam;
+mod webhook;
 mod window_manager;
+mod window_mask;
+mod window_tracker;
 
+// Shared with the library (see lib.rs)
+use rustframe::{capture, cli, constants, game_capture, record, source, utils};
+
+use annotation::{AnnotationLayer, AnnotationTool};
+use app_settings::{AppSettings, EscAction, ToolbarPlacement};
 use capture::{CaptureEngine, CaptureSettings};
-use renderer::Renderer;
-use window_manager::{DestinationWindow, OverlayWindow};
+use cli::{CliError, FailureKind};
+use filter::{FilterChain, OverlayOpacity};
+use elevation::ElevationWatch;
+use frame_hold::FrameHold;
+use gamepad::{Gamepad, GamepadAction};
+use chroma_key::ChromaKey;
+use click_highlight::ClickHighlight;
+use composite::{CompositeLayout, SourceId, SourceRect};
+use control_stdio::{StdioCommand, StdioControl, StdioInput};
+use deep_link::{DeepLink, LinkSource};
+use enhance::OutputEnhance;
+use hotkeys::{HotkeyAction, HotkeyManager};
+use layout_editor::LayoutEditor;
+use magnifier::{Magnifier, MagnifierMode};
+use markers::MarkerLog;
+use midi::{MidiAction, MidiEvent, MidiInput};
+use mouse_hook::MouseHook;
+use osc::OscListener;
+use grid::CompositionGrid;
+use color_picker::ColorPicker;
+use measure::Measurement;
+use pan::Pan;
+use frame_diff::FrameDiff;
+use pause_screen::PauseScreen;
+use plugin::PluginHost;
+use privacy::{PrivacyRegions, RedactTool};
+use renderer::{FrameOverlays, Redaction, Renderer};
+use scene::{Scene, SceneList};
+use scripting::{ScriptCommand, ScriptHost};
+use source::{FrameSource, Placement};
+use spotlight::Spotlight;
+use stream_deck::{RemoteAction, RemoteState, StreamDeckServer};
+use image_source::ImageSource;
+use input_recording::{InputRecorder, InputReplay};
+use instance::InstanceServer;
+use window_tracker::TrackedWindow;
+use test_pattern::{PatternKind, TestPattern};
+use toolbar::{Toolbar, ToolbarButton, ToolbarView};
+use touch::TouchGestures;
+use timer::{OverlayTimer, TimerMode};
+use upload::Uploader;
+use webcam::Webcam;
+use webhook::{WebhookEvent, WebhookSender};
+use window_manager::{DestinationWindow, OverlayWindow, PopupWindow};
+use window_mask::WindowMasker;
 
 /// Menu item IDs for tray icon context menu
 mod menu_ids {
+    pub const STATUS: &str = "status";
+    pub const START_STOP_CAPTURE: &str = "start_stop_capture";
+    pub const SCREENSHOT: &str = "screenshot";
+    /// Prefix - the index in the recent screenshot list is appended
+    pub const RECENT_SCREENSHOT: &str = "recent_screenshot_";
+    pub const UPLOAD_SCREENSHOT: &str = "upload_screenshot";
+    pub const RECORD_INPUT: &str = "record_input";
+    pub const REPLAY_INPUT: &str = "replay_input";
+    pub const SCAN_FRAME: &str = "scan_frame";
+    /// Prefixes - the index in the scan result list is appended
+    pub const SCAN_OPEN: &str = "scan_open_";
+    pub const SCAN_COPY: &str = "scan_copy_";
+    pub const OPEN_SCREENSHOTS: &str = "open_screenshots";
     pub const TOGGLE_CURSOR: &str = "toggle_cursor";
     pub const TOGGLE_BORDER: &str = "toggle_border";
     pub const TOGGLE_EXCLUDE: &str = "toggle_exclude";
+    pub const TOGGLE_PAUSE: &str = "toggle_pause";
+    pub const TOGGLE_FREEZE: &str = "toggle_freeze";
+    pub const PAUSE_STYLE: &str = "pause_style";
+    pub const DIFF_REFERENCE: &str = "diff_reference";
+    pub const DIFF_VIEW: &str = "diff_view";
+    pub const DIFF_STOP: &str = "diff_stop";
+    pub const TIMER_MODE: &str = "timer_mode";
+    pub const TIMER_POSITION: &str = "timer_position";
+    pub const TIMER_SIZE: &str = "timer_size";
+    pub const GRID_STYLE: &str = "grid_style";
+    pub const GRID_IN_OUTPUT: &str = "grid_in_output";
+    pub const CLEAR_EXCLUDED_WINDOWS: &str = "clear_excluded_windows";
+    pub const CHROMA_KEY_ENABLED: &str = "chroma_key_enabled";
+    pub const CHROMA_KEY_COLOR: &str = "chroma_key_color";
+    pub const CHROMA_KEY_SIMILARITY: &str = "chroma_key_similarity";
+    pub const CHROMA_KEY_SMOOTHNESS: &str = "chroma_key_smoothness";
+    pub const TOGGLE_SHARPEN: &str = "toggle_sharpen";
+    pub const TOGGLE_DENOISE: &str = "toggle_denoise";
+    pub const TOGGLE_SECOND_SHARPEN: &str = "toggle_second_sharpen";
+    pub const TOGGLE_SECOND_DENOISE: &str = "toggle_second_denoise";
+    /// Prefixes - the scene slot number is appended
+    pub const SWITCH_SCENE: &str = "switch_scene_";
+    pub const SAVE_SCENE: &str = "save_scene_";
+    pub const TRANSITION: &str = "transition";
+    pub const PAN_START: &str = "pan_start";
+    pub const PAN_ADD_POINT: &str = "pan_add_point";
+    pub const PAN_CLEAR: &str = "pan_clear";
+    pub const PAN_DURATION: &str = "pan_duration";
+    pub const ADD_REGION: &str = "add_region";
+    pub const REMOVE_REGION: &str = "remove_region";
+    pub const REGION_LAYOUT: &str = "region_layout";
+    pub const REGIONS_SEPARATE: &str = "regions_separate";
+    pub const EDIT_LAYOUT: &str = "edit_layout";
+    /// Prefix - the index in CompositeLayout::TEMPLATES is appended
+    pub const LAYOUT_TEMPLATE: &str = "layout_template_";
+    pub const WEBCAM_MODE: &str = "webcam_mode";
+    pub const WEBCAM_DEVICE: &str = "webcam_device";
+    pub const TEST_PATTERN: &str = "test_pattern";
+    pub const IMAGE_MODE: &str = "image_mode";
+    pub const NEXT_IMAGE: &str = "next_image";
     pub const SETTINGS: &str = "settings";
+    pub const EXPORT_CONFIG: &str = "export_config";
+    pub const IMPORT_CONFIG: &str = "import_config";
     pub const EXIT: &str = "exit";
+    /// Items that work without the windows (daemon mode does not create them;
+    /// prefixes, like RECENT_SCREENSHOT)
+    pub const WINDOWLESS: &[&str] = &[
+        STATUS,
+        RECENT_SCREENSHOT,
+        SCAN_OPEN,
+        SCAN_COPY,
+        OPEN_SCREENSHOTS,
+        SETTINGS,
+        EXPORT_CONFIG,
+        IMPORT_CONFIG,
+        EXIT,
+    ];
+}
+
+/// What a confirmation box asks for (see RustFrameApp::confirm)
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ConfirmAction {
+    StopCapture,
+    Exit,
+    /// Restart as administrator for an elevated application (see elevation.rs)
+    RestartElevated,
+    /// Run a launched rustframe:// link that starts a capture
+    RunLink(DeepLink),
 }
 
 /// Main application state
@@ -62,7 +256,7 @@ struct RustFrameApp {
     renderer: Option<Renderer>,
 
     /// Capture settings (cursor, border, etc.)
-    settings: CaptureSettings,
+    settings: AppSettings,
 
     /// Track if we're in "selection mode" or "capture mode"
     is_selecting: bool,
@@ -76,27 +270,404 @@ struct RustFrameApp {
     /// System tray icon
     tray_icon: Option<TrayIcon>,
 
+    /// Status line, start / stop and screenshot items (updated from about_to_wait)
+    menu_status: Option<MenuItem>,
+    menu_capture: Option<MenuItem>,
+    menu_screenshot: Option<MenuItem>,
+    /// Recent Screenshots submenu and the files it lists
+    menu_recent: Option<Submenu>,
+    menu_record_input: Option<CheckMenuItem>,
+    menu_replay_input: Option<MenuItem>,
+    recent_screenshots: Vec<std::path::PathBuf>,
+    /// Scan item, Scan Results submenu and the results it lists (see frame_scan.rs)
+    menu_scan: Option<MenuItem>,
+    menu_scan_results: Option<Submenu>,
+    scan_results: Vec<frame_scan::Found>,
+    /// Where the results of a running scan arrive
+    pending_scan: Option<Receiver<Vec<frame_scan::Found>>>,
+
+    /// Where the last screenshot went (after the post-capture actions)
+    last_screenshot: Option<std::path::PathBuf>,
+
+    /// Uploads of screenshots (see upload.rs)
+    uploader: Uploader,
+    /// Keyboard/mouse recording of the running capture (see input_recording.rs)
+    input_recorder: Option<InputRecorder>,
+    input_replay: Option<InputReplay>,
+    /// Status text currently shown in the tray
+    tray_status: String,
+
+    /// Windows are hidden to the tray (Some(true) if the destination window
+    /// was hidden as well, see hide_to_tray)
+    hidden_to_tray: Option<bool>,
+
     /// Menu items for updating check state
     menu_cursor: Option<CheckMenuItem>,
     menu_border: Option<CheckMenuItem>,
     menu_exclude: Option<CheckMenuItem>,
+    menu_pause: Option<CheckMenuItem>,
+    menu_freeze: Option<CheckMenuItem>,
+    menu_pause_style: Option<MenuItem>,
+    menu_diff_reference: Option<MenuItem>,
+    menu_diff_view: Option<MenuItem>,
+    menu_diff_stop: Option<MenuItem>,
+    menu_timer_mode: Option<MenuItem>,
+    menu_timer_position: Option<MenuItem>,
+    menu_timer_size: Option<MenuItem>,
+    menu_grid_style: Option<MenuItem>,
+    menu_grid_output: Option<CheckMenuItem>,
+    menu_clear_excluded: Option<MenuItem>,
+    menu_chroma_enabled: Option<CheckMenuItem>,
+    menu_chroma_color: Option<MenuItem>,
+    menu_chroma_similarity: Option<MenuItem>,
+    menu_chroma_smoothness: Option<MenuItem>,
+    menu_sharpen: Option<CheckMenuItem>,
+    menu_denoise: Option<CheckMenuItem>,
+    menu_second_sharpen: Option<CheckMenuItem>,
+    menu_second_denoise: Option<CheckMenuItem>,
+    menu_scenes: Vec<MenuItem>,
+    menu_transition: Option<MenuItem>,
+    menu_remove_region: Option<MenuItem>,
+    menu_region_layout: Option<MenuItem>,
+    menu_regions_separate: Option<CheckMenuItem>,
+    menu_webcam_mode: Option<MenuItem>,
+    menu_webcam_device: Option<MenuItem>,
+    menu_test_pattern: Option<MenuItem>,
+    menu_image_mode: Option<MenuItem>,
+    menu_pan_start: Option<MenuItem>,
+    menu_pan_add: Option<MenuItem>,
+    menu_pan_clear: Option<MenuItem>,
+    menu_pan_duration: Option<MenuItem>,
+
+    /// Automated pan of the capture region (see pan.rs)
+    pan: Option<Pan>,
+
+    /// Global hotkeys (work while other applications have focus)
+    hotkeys: Option<HotkeyManager>,
+
+    /// XInput controller polling (None while gamepad control is off)
+    gamepad: Option<Gamepad>,
+
+    /// MIDI controller input (None while MIDI control is off)
+    midi: Option<MidiInput>,
+
+    /// Stream Deck / remote control server (None while it is off)
+    stream_deck: Option<StreamDeckServer>,
+
+    /// OSC listener for control surfaces (None while it is off)
+    osc: Option<OscListener>,
+
+    /// Commands on stdin / events on stdout (--control-stdio)
+    stdio: Option<StdioControl>,
+
+    /// Scripts from the scripts folder (None if there are none)
+    scripts: Option<ScriptHost>,
+
+    /// Plugin DLLs from the plugins folder (frame filters and sinks)
+    plugins: PluginHost,
+
+    /// Sends the configured webhooks (settings.json)
+    webhooks: WebhookSender,
+
+    /// The capture source failed on the last frame (stream_drop is sent once)
+    stream_dropped: bool,
+
+    /// The captured content is static - output limited to settings.idle_fps
+    idle: bool,
+
+    /// Whether the computer runs on battery (low-power mode)
+    power: power::PowerWatcher,
+
+    /// Wakes the event loop when a capture engine has a new frame or a
+    /// confirmation was answered
+    waker: EventLoopProxy<()>,
+
+    /// Confirmation box on screen and where its answer arrives
+    pending_confirm: Option<(ConfirmAction, Receiver<bool>)>,
+
+    /// When the running capture started (elapsed time for remotes)
+    capture_started: Option<Instant>,
+
+    /// Stop the capture after this long (rustframe://record?duration=...)
+    capture_duration: Option<Duration>,
+
+    /// Commands from a second RustFrame process, e.g. rustframe:// links
+    /// (None if another instance owns the pipe)
+    instance: Option<InstanceServer>,
+
+    /// rustframe:// link from the command line, run once the windows exist
+    pending_link: Option<String>,
+
+    /// File for the requested screenshot (rustframe-ctl screenshot --out)
+    screenshot_out: Option<std::path::PathBuf>,
+
+    /// Waiting for the click on the window to capture (desktop menu): the
+    /// mouse hook that sees the click and when the picker started
+    window_picker: Option<(MouseHook, Instant)>,
+
+    /// The picked window, found again if its application restarts
+    tracked_window: Option<TrackedWindow>,
+
+    /// Markers dropped during the capture session (gamepad X, MIDI)
+    markers: MarkerLog,
+
+    /// Notices edits of settings.json made while RustFrame runs
+    settings_watcher: settings_file::Watcher,
+
+    /// How strongly drawn overlays cover the output (MIDI fader, not stored)
+    overlay_opacity: OverlayOpacity,
+
+    /// Annotations drawn over the capture (composited into the output)
+    annotations: AnnotationLayer,
+
+    /// Track if annotation draw mode is active (capture mode only)
+    is_drawing_mode: bool,
+
+    /// Track if ruler measure mode is active (capture mode only)
+    is_measuring: bool,
+
+    /// Current ruler measurement (shown on the overlay only)
+    measurement: Measurement,
+
+    /// Track if the color picker is active (capture mode only)
+    is_picking_color: bool,
+
+    /// Pixel under the cursor in color picker mode (shown on the overlay only)
+    color_picker: ColorPicker,
+
+    /// Touch gestures on the overlay / border (move, edge drag, pinch)
+    touch: TouchGestures,
+
+    /// Track if privacy region redact mode is active (capture mode only)
+    is_redacting: bool,
+
+    /// Blurred / pixelated regions (composited into the output)
+    privacy: PrivacyRegions,
+
+    /// Track if the layout editor is open (capture mode only)
+    is_editing_layout: bool,
+
+    /// Source rectangles being arranged in the layout editor
+    layout_editor: LayoutEditor,
+
+    /// Editor revision last taken over into custom_layout
+    layout_revision: u64,
+
+    /// Blacks out windows of redacted applications (composited into the output)
+    window_mask: WindowMasker,
+
+    /// Last cursor position over the overlay window (client coordinates)
+    overlay_cursor_pos: (f64, f64),
+
+    /// Presentation spotlight (dims everything outside a focus area)
+    spotlight: Spotlight,
+
+    /// Click rings and drag paths in the output
+    click_highlight: ClickHighlight,
+
+    /// The hold-to-show-cursor keys are held (the cursor is captured even
+    /// with show_cursor off)
+    cursor_held: bool,
+
+    /// Mouse button gestures anywhere on the desktop (only while capturing
+    /// with click highlights on)
+    mouse_hook: Option<MouseHook>,
+
+    /// Zoom lens around the cursor
+    magnifier: Magnifier,
+
+    /// Local-only window for the magnifier (excluded from capture)
+    magnifier_popup: Option<PopupWindow>,
+
+    /// Renderer frame and magnifier revision last shown in the magnifier window
+    magnifier_shown: (u32, u64),
+
+    /// On-screen timer / stopwatch composited into the output
+    timer: OverlayTimer,
+
+    /// Pause ("be right back") screen shown instead of the captured content
+    pause_screen: PauseScreen,
+
+    /// Freeze-frame: the output keeps showing the last captured frame
+    is_output_frozen: bool,
+
+    /// Comparison of the output with a reference frame (see frame_diff.rs)
+    frame_diff: FrameDiff,
+
+    /// Holds the last good frame while the source is interrupted (minimized, UAC)
+    frame_hold: FrameHold,
+
+    /// Elevated application in front of the capture region (see elevation.rs)
+    elevation: ElevationWatch,
+    elevated_app: Option<String>,
+
+    /// Composition grid (selection overlay, optionally the output too)
+    grid: CompositionGrid,
+
+    /// Order and enabled state of the frame filters (see filter.rs)
+    filters: FilterChain,
+
+    /// Chroma key stage of the output shader (key color becomes transparent)
+    chroma_key: ChromaKey,
+
+    /// Sharpen / denoise flags of the destination window output
+    enhance: OutputEnhance,
+
+    /// Sharpen / denoise flags of the second output
+    second_enhance: OutputEnhance,
+
+    /// Saved scenes (region + output setups) switchable via hotkeys / tray
+    scenes: SceneList,
+
+    /// Additional capture regions (hollow borders) composited next to the main one
+    region_overlays: Vec<OverlayWindow>,
+
+    /// Capture sessions of the additional regions (same order as region_overlays)
+    region_captures: Vec<CaptureEngine>,
+
+    /// How the additional regions are arranged in the output
+    composite_layout: CompositeLayout,
+
+    /// Place of each source in the custom layout (kept for sources that are
+    /// switched off, so they come back to the same place)
+    custom_layout: Vec<SourceRect>,
+
+    /// Show the additional regions in their own window instead of the main output
+    regions_separate: bool,
+
+    /// Second destination window (additional regions, when shown separately)
+    second_destination: Option<DestinationWindow>,
+
+    /// Renderer for the second destination window
+    second_renderer: Option<Renderer>,
+
+    /// Open webcam (only while capturing with a webcam mode other than Off)
+    webcam: Option<Webcam>,
+
+    /// Where the webcam is shown in the output
+    webcam_mode: Placement,
+
+    /// Index of the selected video capture device
+    webcam_device: usize,
+
+    /// Synthetic source shown instead of the screen capture (None = screen)
+    test_pattern: Option<TestPattern>,
+
+    /// Open image / slideshow (only while capturing with a mode other than Off)
+    image_source: Option<ImageSource>,
+
+    /// Where the image is shown in the output
+    image_mode: Placement,
+
+    /// Local-only window for the pre-capture countdown
+    countdown_popup: Option<PopupWindow>,
+
+    /// Control toolbar during a capture and its local-only window
+    toolbar: Toolbar,
+    toolbar_popup: Option<PopupWindow>,
+
+    /// Whether this capture already explained a region that turned black
+    black_warned: bool,
+
+    /// When the pre-capture countdown started (None when no countdown is running)
+    countdown_started: Option<Instant>,
+
+    /// Number currently shown by the countdown popup
+    countdown_shown: u32,
+
+    /// When the output was last rendered (for the target frame rate)
+    last_render: Option<Instant>,
 
     /// Development mode flag (shows extra options)
     dev_mode: bool,
 
+    /// Hide to the tray as soon as the windows exist (--minimized or the
+    /// start_minimized setting)
+    start_minimized: bool,
+
+    /// --daemon: the windows are created when an action needs them and
+    /// released again when hidden to the tray while idle
+    daemon: bool,
+
+    /// Capture region of the released windows (daemon mode)
+    released_region: Option<capture::CaptureRect>,
+
     /// Startup time - used to ignore Enter key for first 500ms
     startup_time: Instant,
+
+    /// Keyboard modifiers held down (Shift = resize, Ctrl = fine step)
+    modifiers: winit::keyboard::ModifiersState,
+
+    /// Overlay settings line with keyboard focus (Tab / Shift+Tab, Space toggles)
+    focused_option: Option<usize>,
 }
 
 impl RustFrameApp {
-    fn new(dev_mode: bool) -> Self {
-        let settings = if dev_mode {
+    fn new(
+        dev_mode: bool,
+        minimized_flag: bool,
+        daemon: bool,
+        control_stdio: bool,
+        link: Option<String>,
+        waker: EventLoopProxy<()>,
+    ) -> Self {
+        if dev_mode {
             info!("Starting in DEVELOPMENT mode (destination window visible)");
-            CaptureSettings::for_development()
         } else {
             info!("Starting in PRODUCTION mode (destination hidden)");
-            CaptureSettings::default()
-        };
+        }
+        let mut settings = settings_file::load(dev_mode);
+        if settings.stream_deck_token.is_empty() {
+            settings.stream_deck_token = stream_deck::new_token();
+            if let Err(e) = settings_file::save(&settings) {
+                error!("Failed to save settings: {:#}", e);
+            }
+        }
+        // Upload secrets do not stay in settings.json
+        if upload::store_secrets(&mut settings.upload) {
+            if let Err(e) = settings_file::save(&settings) {
+                error!("Failed to save settings: {:#}", e);
+            }
+        }
+        // Capture, compositing and presenting run on this (the event loop) thread
+        thread_priority::apply_to_current_thread(settings.thread_priority, &settings.cpu_cores);
+        theme::apply(settings.theme, &settings.accent_color);
+        let mut window_mask = WindowMasker::new(&settings.redacted_apps);
+        window_mask.set_hide_notifications(settings.hide_notifications);
+        let filters = FilterChain::new(&settings.filters);
+        let frame_hold = FrameHold::new(settings.hold_seconds);
+        let timer = OverlayTimer::new(
+            TimerMode::from_seconds(settings.timer_seconds),
+            settings.timer_corner,
+            settings.timer_size,
+        );
+        let pause_screen = PauseScreen::new(settings.pause_style);
+        let scenes = SceneList::from_slots(settings.scenes.clone());
+        let gamepad = settings.gamepad.then(Gamepad::new);
+        let midi = open_midi(&settings);
+        let stream_deck = open_stream_deck(&settings);
+        let osc = open_osc(&settings);
+        let start_minimized = minimized_flag || settings.start_minimized;
+
+        // Rewrite the Run key entry in case the executable was moved
+        if settings.auto_start {
+            if let Err(e) = autostart::set_enabled(true) {
+                warn!("{:#}", e);
+            }
+        }
+        if let Err(e) = deep_link::register_url_scheme() {
+            warn!("{:#}", e);
+        }
+        // Rewrite the desktop menu entries in case the executable was moved
+        if settings.shell_menu {
+            if let Err(e) = shell_menu::set_enabled(true) {
+                warn!("{:#}", e);
+            }
+        }
+        let instance = InstanceServer::start()
+            .map_err(|e| warn!("rustframe:// links go to the other instance: {:#}", e))
+            .ok();
+        let plugins = PluginHost::load(&settings.enabled_plugins);
 
         Self {
             overlay_window: None,
@@ -108,29 +679,201 @@ impl RustFrameApp {
             is_dragging: false,
             last_mouse_pos: None,
             tray_icon: None,
+            menu_status: None,
+            menu_capture: None,
+            menu_screenshot: None,
+            menu_recent: None,
+            menu_record_input: None,
+            menu_replay_input: None,
+            recent_screenshots: Vec::new(),
+            menu_scan: None,
+            menu_scan_results: None,
+            scan_results: Vec::new(),
+            pending_scan: None,
+            last_screenshot: None,
+            uploader: Uploader::new(),
+            input_recorder: None,
+            input_replay: None,
+            tray_status: String::new(),
+            hidden_to_tray: None,
             menu_cursor: None,
             menu_border: None,
             menu_exclude: None,
+            menu_pause: None,
+            menu_freeze: None,
+            menu_pause_style: None,
+            menu_diff_reference: None,
+            menu_diff_view: None,
+            menu_diff_stop: None,
+            menu_timer_mode: None,
+            menu_timer_position: None,
+            menu_timer_size: None,
+            menu_grid_style: None,
+            menu_grid_output: None,
+            menu_clear_excluded: None,
+            menu_chroma_enabled: None,
+            menu_chroma_color: None,
+            menu_chroma_similarity: None,
+            menu_chroma_smoothness: None,
+            menu_sharpen: None,
+            menu_denoise: None,
+            menu_second_sharpen: None,
+            menu_second_denoise: None,
+            menu_scenes: Vec::new(),
+            menu_transition: None,
+            menu_remove_region: None,
+            menu_region_layout: None,
+            menu_regions_separate: None,
+            menu_webcam_mode: None,
+            menu_webcam_device: None,
+            menu_test_pattern: None,
+            menu_image_mode: None,
+            menu_pan_start: None,
+            menu_pan_add: None,
+            menu_pan_clear: None,
+            menu_pan_duration: None,
+            pan: None,
+            hotkeys: None,
+            gamepad,
+            midi,
+            stream_deck,
+            osc,
+            stdio: control_stdio.then(StdioControl::start),
+            scripts: ScriptHost::load(),
+            plugins,
+            webhooks: WebhookSender::new(),
+            stream_dropped: false,
+            idle: false,
+            power: power::PowerWatcher::new(),
+            waker,
+            pending_confirm: None,
+            capture_started: None,
+            capture_duration: None,
+            instance,
+            pending_link: link,
+            screenshot_out: None,
+            window_picker: None,
+            tracked_window: None,
+            markers: MarkerLog::new(),
+            settings_watcher: settings_file::Watcher::new(),
+            overlay_opacity: OverlayOpacity::new(),
+            annotations: AnnotationLayer::new(),
+            is_drawing_mode: false,
+            is_measuring: false,
+            measurement: Measurement::new(),
+            is_picking_color: false,
+            color_picker: ColorPicker::new(),
+            touch: TouchGestures::new(),
+            is_redacting: false,
+            privacy: PrivacyRegions::new(),
+            is_editing_layout: false,
+            layout_editor: LayoutEditor::new(),
+            layout_revision: 0,
+            window_mask,
+            overlay_cursor_pos: (0.0, 0.0),
+            spotlight: Spotlight::new(),
+            click_highlight: ClickHighlight::new(),
+            cursor_held: false,
+            mouse_hook: None,
+            magnifier: Magnifier::new(),
+            magnifier_popup: None,
+            magnifier_shown: (0, 0),
+            timer,
+            pause_screen,
+            frame_diff: FrameDiff::new(),
+            is_output_frozen: false,
+            frame_hold,
+            elevation: ElevationWatch::new(),
+            elevated_app: None,
+            grid: CompositionGrid::new(),
+            filters,
+            chroma_key: ChromaKey::new(),
+            enhance: OutputEnhance::default(),
+            second_enhance: OutputEnhance::default(),
+            scenes,
+            region_overlays: Vec::new(),
+            region_captures: Vec::new(),
+            composite_layout: CompositeLayout::SideBySide,
+            regions_separate: false,
+            second_destination: None,
+            second_renderer: None,
+            webcam: None,
+            webcam_mode: Placement::Off,
+            webcam_device: 0,
+            custom_layout: Vec::new(),
+            test_pattern: None,
+            image_source: None,
+            image_mode: Placement::Off,
+            countdown_popup: None,
+            toolbar: Toolbar::new(),
+            toolbar_popup: None,
+            black_warned: false,
+            countdown_started: None,
+            last_render: None,
+            countdown_shown: 0,
             dev_mode,
+            start_minimized,
+            daemon,
+            released_region: None,
             startup_time: Instant::now(),
+            modifiers: winit::keyboard::ModifiersState::empty(),
+            focused_option: None,
         }
     }
 
     /// Create and show the system tray icon with context menu
     fn create_tray_icon(&mut self) {
+        // Live actions - the status line is informational only, the texts
+        // follow the capture state (see refresh_tray_status)
+        self.tray_status = self.status_text();
+        let menu_status = MenuItem::with_id(menu_ids::STATUS, &self.tray_status, false, None);
+        let menu_capture = MenuItem::with_id(
+            menu_ids::START_STOP_CAPTURE,
+            self.capture_action_label(),
+            true,
+            None,
+        );
+        let menu_screenshot = MenuItem::with_id(
+            menu_ids::SCREENSHOT,
+            "Take Screenshot",
+            !self.is_selecting,
+            None,
+        );
+        let menu_recent = Submenu::new("Recent Screenshots", true);
+        let menu_scan = MenuItem::with_id(
+            menu_ids::SCAN_FRAME,
+            "Scan for QR Codes and Links",
+            !self.is_selecting,
+            None,
+        );
+        let menu_scan_results = Submenu::new("Scan Results", true);
+        let menu_record_input = CheckMenuItem::with_id(
+            menu_ids::RECORD_INPUT,
+            "Record Input",
+            true,
+            self.settings.record_input,
+            None,
+        );
+        let menu_replay_input = MenuItem::with_id(
+            menu_ids::REPLAY_INPUT,
+            "Replay Last Input Recording",
+            true,
+            None,
+        );
+
         // Create menu items
         let menu_cursor = CheckMenuItem::with_id(
             menu_ids::TOGGLE_CURSOR,
             "Show Cursor",
             true,
-            self.settings.show_cursor,
+            self.settings.capture.show_cursor,
             None,
         );
         let menu_border = CheckMenuItem::with_id(
             menu_ids::TOGGLE_BORDER,
             "Show Border",
             true,
-            self.settings.show_border,
+            self.settings.capture.show_border,
             None,
         );
 
@@ -140,18 +883,342 @@ impl RustFrameApp {
                 menu_ids::TOGGLE_EXCLUDE,
                 "Production Mode (Single Window)",
                 true,
-                self.settings.exclude_from_capture,
+                self.settings.capture.exclude_from_capture,
                 None,
             ))
         } else {
             None
         };
 
+        // Pause output - the style item cycles through the placeholder types
+        let menu_pause = CheckMenuItem::with_id(
+            menu_ids::TOGGLE_PAUSE,
+            "Pause Output",
+            true,
+            self.pause_screen.is_paused(),
+            None,
+        );
+        let menu_freeze = CheckMenuItem::with_id(
+            menu_ids::TOGGLE_FREEZE,
+            "Freeze Output",
+            true,
+            self.is_output_frozen,
+            None,
+        );
+        let menu_pause_style = MenuItem::with_id(
+            menu_ids::PAUSE_STYLE,
+            format!("Pause Screen: {}", self.pause_screen.style().label()),
+            true,
+            None,
+        );
+
+        // Comparison with a reference frame (see frame_diff.rs)
+        let menu_diff_reference = MenuItem::with_id(
+            menu_ids::DIFF_REFERENCE,
+            self.diff_reference_label(),
+            !self.is_selecting,
+            None,
+        );
+        let menu_diff_view = MenuItem::with_id(
+            menu_ids::DIFF_VIEW,
+            format!("View: {}", self.frame_diff.view().label()),
+            true,
+            None,
+        );
+        let menu_diff_stop = MenuItem::with_id(
+            menu_ids::DIFF_STOP,
+            "Stop Comparing",
+            self.frame_diff.is_active(),
+            None,
+        );
+        let menu_diff = Submenu::new("Compare Frames", true);
+        let _ = menu_diff.append(&menu_diff_reference);
+        let _ = menu_diff.append(&menu_diff_view);
+        let _ = menu_diff.append(&menu_diff_stop);
+
+        // Timer options - each item cycles through its values when clicked
+        let menu_timer_mode = MenuItem::with_id(
+            menu_ids::TIMER_MODE,
+            format!("Mode: {}", self.timer.mode().label()),
+            true,
+            None,
+        );
+        let menu_timer_position = MenuItem::with_id(
+            menu_ids::TIMER_POSITION,
+            format!("Position: {}", self.timer.corner().label()),
+            true,
+            None,
+        );
+        let menu_timer_size = MenuItem::with_id(
+            menu_ids::TIMER_SIZE,
+            format!("Size: {}", self.timer.size().label()),
+            true,
+            None,
+        );
+        let menu_timer = Submenu::new("Timer", true);
+        let _ = menu_timer.append(&menu_timer_mode);
+        let _ = menu_timer.append(&menu_timer_position);
+        let _ = menu_timer.append(&menu_timer_size);
+
+        // Composition grid - the style item cycles through the grid types
+        let menu_grid_style = MenuItem::with_id(
+            menu_ids::GRID_STYLE,
+            format!("Grid: {}", self.grid.style().label()),
+            true,
+            None,
+        );
+        let menu_grid_output = CheckMenuItem::with_id(
+            menu_ids::GRID_IN_OUTPUT,
+            "Show Grid in Output",
+            true,
+            self.grid.in_output(),
+            None,
+        );
+
+        // Windows excluded with Ctrl+Shift+W - disabled while there are none
+        let menu_clear_excluded = MenuItem::with_id(
+            menu_ids::CLEAR_EXCLUDED_WINDOWS,
+            "Clear Excluded Windows",
+            self.window_mask.excluded_count() > 0,
+            None,
+        );
+
+        // Chroma key - the value items cycle through their presets when clicked
+        let menu_chroma_enabled = CheckMenuItem::with_id(
+            menu_ids::CHROMA_KEY_ENABLED,
+            "Enabled",
+            true,
+            self.chroma_key.is_enabled(),
+            None,
+        );
+        let menu_chroma_color = MenuItem::with_id(
+            menu_ids::CHROMA_KEY_COLOR,
+            format!("Key Color: {}", self.chroma_key.color().label()),
+            true,
+            None,
+        );
+        let menu_chroma_similarity = MenuItem::with_id(
+            menu_ids::CHROMA_KEY_SIMILARITY,
+            format!("Similarity: {}%", self.chroma_key.similarity_percent()),
+            true,
+            None,
+        );
+        let menu_chroma_smoothness = MenuItem::with_id(
+            menu_ids::CHROMA_KEY_SMOOTHNESS,
+            format!("Smoothness: {}%", self.chroma_key.smoothness_percent()),
+            true,
+            None,
+        );
+        let menu_chroma = Submenu::new("Chroma Key", true);
+        let _ = menu_chroma.append(&menu_chroma_enabled);
+        let _ = menu_chroma.append(&menu_chroma_color);
+        let _ = menu_chroma.append(&menu_chroma_similarity);
+        let _ = menu_chroma.append(&menu_chroma_smoothness);
+
+        // Sharpen / denoise stages of the destination window output
+        let menu_sharpen = CheckMenuItem::with_id(
+            menu_ids::TOGGLE_SHARPEN,
+            "Sharpen",
+            true,
+            self.enhance.sharpen,
+            None,
+        );
+        let menu_denoise = CheckMenuItem::with_id(
+            menu_ids::TOGGLE_DENOISE,
+            "Denoise",
+            true,
+            self.enhance.denoise,
+            None,
+        );
+        let menu_second_sharpen = CheckMenuItem::with_id(
+            menu_ids::TOGGLE_SECOND_SHARPEN,
+            "Sharpen Second Output",
+            true,
+            self.second_enhance.sharpen,
+            None,
+        );
+        let menu_second_denoise = CheckMenuItem::with_id(
+            menu_ids::TOGGLE_SECOND_DENOISE,
+            "Denoise Second Output",
+            true,
+            self.second_enhance.denoise,
+            None,
+        );
+        let menu_enhance = Submenu::new("Enhance Output", true);
+        let _ = menu_enhance.append(&menu_sharpen);
+        let _ = menu_enhance.append(&menu_denoise);
+        let _ = menu_enhance.append(&PredefinedMenuItem::separator());
+        let _ = menu_enhance.append(&menu_second_sharpen);
+        let _ = menu_enhance.append(&menu_second_denoise);
+
+        // Scenes - one item per slot to switch to it, plus one to save into it
+        let menu_scene = Submenu::new("Scenes", true);
+        let mut menu_scenes = Vec::new();
+        for slot in 0..self.scenes.len() {
+            let item = MenuItem::with_id(
+                format!("{}{}", menu_ids::SWITCH_SCENE, slot),
+                self.scenes.label(slot),
+                self.scenes.get(slot).is_some(),
+                None,
+            );
+            let _ = menu_scene.append(&item);
+            menu_scenes.push(item);
+        }
+        let _ = menu_scene.append(&PredefinedMenuItem::separator());
+        for slot in 0..self.scenes.len() {
+            let _ = menu_scene.append(&MenuItem::with_id(
+                format!("{}{}", menu_ids::SAVE_SCENE, slot),
+                format!("Save Current as Scene {}", slot + 1),
+                true,
+                None,
+            ));
+        }
+
+        // Transition for live switches - cycles through the kinds when clicked
+        // (the duration is set in Settings)
+        let menu_transition = MenuItem::with_id(
+            menu_ids::TRANSITION,
+            format!("Transition: {}", self.settings.transition_kind.label()),
+            true,
+            None,
+        );
+
+        // Auto pan along the saved path (see pan.rs) - the duration cycles when clicked
+        let menu_pan_start = MenuItem::with_id(
+            menu_ids::PAN_START,
+            self.pan_action_label(),
+            self.pan_available(),
+            None,
+        );
+        let menu_pan_add = MenuItem::with_id(
+            menu_ids::PAN_ADD_POINT,
+            self.pan_add_label(),
+            self.settings.pan_path.len() < constants::pan::MAX_POINTS,
+            None,
+        );
+        let menu_pan_clear = MenuItem::with_id(
+            menu_ids::PAN_CLEAR,
+            "Clear Path",
+            !self.settings.pan_path.is_empty(),
+            None,
+        );
+        let menu_pan_duration = MenuItem::with_id(
+            menu_ids::PAN_DURATION,
+            format!("Duration: {} s", self.settings.pan_seconds),
+            true,
+            None,
+        );
+        let menu_pan = Submenu::new("Auto Pan", true);
+        let _ = menu_pan.append(&menu_pan_start);
+        let _ = menu_pan.append(&menu_pan_add);
+        let _ = menu_pan.append(&menu_pan_clear);
+        let _ = menu_pan.append(&menu_pan_duration);
+
+        // Additional capture regions composited into the same output
+        let menu_add_region =
+            MenuItem::with_id(menu_ids::ADD_REGION, "Add Region", true, None);
+        let menu_remove_region = MenuItem::with_id(
+            menu_ids::REMOVE_REGION,
+            "Remove Last Region",
+            !self.region_overlays.is_empty(),
+            None,
+        );
+        let menu_region_layout = MenuItem::with_id(
+            menu_ids::REGION_LAYOUT,
+            format!("Layout: {}", self.composite_layout.label()),
+            true,
+            None,
+        );
+        let menu_regions_separate = CheckMenuItem::with_id(
+            menu_ids::REGIONS_SEPARATE,
+            "Show in Separate Window",
+            true,
+            self.regions_separate,
+            None,
+        );
+        let menu_edit_layout = MenuItem::with_id(menu_ids::EDIT_LAYOUT, "Edit Layout", true, None);
+        let menu_regions = Submenu::new("Capture Regions", true);
+        let _ = menu_regions.append(&menu_add_region);
+        let _ = menu_regions.append(&menu_remove_region);
+        let _ = menu_regions.append(&menu_region_layout);
+        let _ = menu_regions.append(&menu_edit_layout);
+        let _ = menu_regions.append(&menu_regions_separate);
+
+        // Webcam as an additional source (the device name is filled in once it is opened)
+        let menu_webcam_mode = MenuItem::with_id(
+            menu_ids::WEBCAM_MODE,
+            format!("Mode: {}", self.webcam_mode.label()),
+            true,
+            None,
+        );
+        let menu_webcam_device = MenuItem::with_id(
+            menu_ids::WEBCAM_DEVICE,
+            format!("Camera: {}", self.webcam_device + 1),
+            true,
+            None,
+        );
+        let menu_webcam = Submenu::new("Webcam", true);
+        let _ = menu_webcam.append(&menu_webcam_mode);
+        let _ = menu_webcam.append(&menu_webcam_device);
+
+        // Image / slideshow as an additional source (path set in Settings)
+        let menu_image_mode = MenuItem::with_id(
+            menu_ids::IMAGE_MODE,
+            format!("Mode: {}", self.image_mode.label()),
+            true,
+            None,
+        );
+        let menu_next_image = MenuItem::with_id(menu_ids::NEXT_IMAGE, "Next Image", true, None);
+        let menu_image = Submenu::new("Image", true);
+        let _ = menu_image.append(&menu_image_mode);
+        let _ = menu_image.append(&menu_next_image);
+
+        // Ready-made layouts for all current sources (regions, webcam, image)
+        let menu_templates = Submenu::new("Layout Templates", true);
+        for (i, layout) in CompositeLayout::TEMPLATES.iter().enumerate() {
+            let item = MenuItem::with_id(
+                format!("{}{}", menu_ids::LAYOUT_TEMPLATE, i),
+                layout.label(),
+                true,
+                None,
+            );
+            let _ = menu_templates.append(&item);
+        }
+
+        // Test pattern instead of the screen capture - cycles through the patterns
+        let menu_test_pattern = MenuItem::with_id(
+            menu_ids::TEST_PATTERN,
+            format!("Test Pattern: {}", pattern_label(self.test_pattern.as_ref())),
+            true,
+            None,
+        );
+
         let menu_settings = MenuItem::with_id(menu_ids::SETTINGS, "Settings...", true, None);
+        let menu_export_config = MenuItem::with_id(
+            menu_ids::EXPORT_CONFIG,
+            "Export Configuration...",
+            true,
+            None,
+        );
+        let menu_import_config = MenuItem::with_id(
+            menu_ids::IMPORT_CONFIG,
+            "Import Configuration...",
+            true,
+            None,
+        );
         let menu_exit = MenuItem::with_id(menu_ids::EXIT, "Exit", true, None);
 
         // Build the menu
         let menu = Menu::new();
+        let _ = menu.append(&menu_status);
+        let _ = menu.append(&menu_capture);
+        let _ = menu.append(&menu_screenshot);
+        let _ = menu.append(&menu_recent);
+        let _ = menu.append(&menu_scan);
+        let _ = menu.append(&menu_scan_results);
+        let _ = menu.append(&menu_record_input);
+        let _ = menu.append(&menu_replay_input);
+        let _ = menu.append(&PredefinedMenuItem::separator());
         let _ = menu.append(&menu_cursor);
         let _ = menu.append(&menu_border);
 
@@ -161,14 +1228,77 @@ impl RustFrameApp {
         }
 
         let _ = menu.append(&PredefinedMenuItem::separator());
+        let _ = menu.append(&menu_pause);
+        let _ = menu.append(&menu_freeze);
+        let _ = menu.append(&menu_pause_style);
+        let _ = menu.append(&menu_diff);
+        let _ = menu.append(&menu_timer);
+        let _ = menu.append(&menu_grid_style);
+        let _ = menu.append(&menu_grid_output);
+        let _ = menu.append(&menu_clear_excluded);
+        let _ = menu.append(&menu_chroma);
+        let _ = menu.append(&menu_enhance);
+        let _ = menu.append(&menu_scene);
+        let _ = menu.append(&menu_transition);
+        let _ = menu.append(&menu_pan);
+        let _ = menu.append(&menu_regions);
+        let _ = menu.append(&menu_webcam);
+        let _ = menu.append(&menu_image);
+        let _ = menu.append(&menu_templates);
+        let _ = menu.append(&menu_test_pattern);
         let _ = menu.append(&menu_settings);
+        let _ = menu.append(&menu_export_config);
+        let _ = menu.append(&menu_import_config);
         let _ = menu.append(&PredefinedMenuItem::separator());
         let _ = menu.append(&menu_exit);
 
         // Store menu items for later updates
+        self.menu_status = Some(menu_status);
+        self.menu_capture = Some(menu_capture);
+        self.menu_screenshot = Some(menu_screenshot);
+        self.menu_recent = Some(menu_recent);
+        self.menu_record_input = Some(menu_record_input);
+        self.menu_replay_input = Some(menu_replay_input);
+        self.refresh_recent_screenshots();
+        self.menu_scan = Some(menu_scan);
+        self.menu_scan_results = Some(menu_scan_results);
+        self.refresh_scan_results();
         self.menu_cursor = Some(menu_cursor);
         self.menu_border = Some(menu_border);
         self.menu_exclude = menu_exclude;
+        self.menu_pause = Some(menu_pause);
+        self.menu_freeze = Some(menu_freeze);
+        self.menu_pause_style = Some(menu_pause_style);
+        self.menu_diff_reference = Some(menu_diff_reference);
+        self.menu_diff_view = Some(menu_diff_view);
+        self.menu_diff_stop = Some(menu_diff_stop);
+        self.menu_timer_mode = Some(menu_timer_mode);
+        self.menu_timer_position = Some(menu_timer_position);
+        self.menu_timer_size = Some(menu_timer_size);
+        self.menu_grid_style = Some(menu_grid_style);
+        self.menu_grid_output = Some(menu_grid_output);
+        self.menu_clear_excluded = Some(menu_clear_excluded);
+        self.menu_chroma_enabled = Some(menu_chroma_enabled);
+        self.menu_chroma_color = Some(menu_chroma_color);
+        self.menu_chroma_similarity = Some(menu_chroma_similarity);
+        self.menu_chroma_smoothness = Some(menu_chroma_smoothness);
+        self.menu_sharpen = Some(menu_sharpen);
+        self.menu_denoise = Some(menu_denoise);
+        self.menu_second_sharpen = Some(menu_second_sharpen);
+        self.menu_second_denoise = Some(menu_second_denoise);
+        self.menu_scenes = menu_scenes;
+        self.menu_transition = Some(menu_transition);
+        self.menu_remove_region = Some(menu_remove_region);
+        self.menu_region_layout = Some(menu_region_layout);
+        self.menu_regions_separate = Some(menu_regions_separate);
+        self.menu_webcam_mode = Some(menu_webcam_mode);
+        self.menu_webcam_device = Some(menu_webcam_device);
+        self.menu_test_pattern = Some(menu_test_pattern);
+        self.menu_image_mode = Some(menu_image_mode);
+        self.menu_pan_start = Some(menu_pan_start);
+        self.menu_pan_add = Some(menu_pan_add);
+        self.menu_pan_clear = Some(menu_pan_clear);
+        self.menu_pan_duration = Some(menu_pan_duration);
 
         // Load application icon from icon.ico file
         let icon = load_app_icon().unwrap_or_else(|e| {
@@ -182,6 +1312,8 @@ impl RustFrameApp {
             .with_tooltip("RustFrame - Screen Capture")
             .with_icon(icon)
             .with_menu(Box::new(menu))
+            // Left click restores windows hidden to the tray, right click opens the menu
+            .with_menu_on_left_click(false)
             .build()
         {
             Ok(tray) => {
@@ -195,39 +1327,75 @@ impl RustFrameApp {
     }
 
     /// Handle tray menu events
-    fn handle_menu_event(&mut self, event: &MenuEvent) {
+    fn handle_menu_event(&mut self, event_loop: &ActiveEventLoop, event: &MenuEvent) {
         match event.id().as_ref() {
+            id if id == menu_ids::START_STOP_CAPTURE => self.toggle_capture(),
+            id if id == menu_ids::SCREENSHOT => self.take_screenshot(),
+            id if id == menu_ids::UPLOAD_SCREENSHOT => self.upload_screenshot(),
+            id if id == menu_ids::RECORD_INPUT => {
+                self.set_record_input(!self.settings.record_input)
+            }
+            id if id == menu_ids::REPLAY_INPUT => self.toggle_input_replay(),
+            id if id == menu_ids::OPEN_SCREENSHOTS => {
+                if let Some(dir) = screenshot::folder() {
+                    let _ = std::fs::create_dir_all(&dir);
+                    screenshot::open(&dir);
+                }
+            }
+            id if id.starts_with(menu_ids::RECENT_SCREENSHOT) => {
+                let index = id[menu_ids::RECENT_SCREENSHOT.len()..].parse::<usize>().ok();
+                if let Some(path) = index.and_then(|i| self.recent_screenshots.get(i)) {
+                    screenshot::open(path);
+                }
+            }
+            id if id == menu_ids::SCAN_FRAME => self.scan_frame(),
+            id if id.starts_with(menu_ids::SCAN_OPEN) => {
+                let index = id[menu_ids::SCAN_OPEN.len()..].parse::<usize>().ok();
+                let found = index.and_then(|i| self.scan_results.get(i));
+                if let Some(url) = found.and_then(|f| f.url()) {
+                    frame_scan::open_url(&url);
+                }
+            }
+            id if id.starts_with(menu_ids::SCAN_COPY) => {
+                let index = id[menu_ids::SCAN_COPY.len()..].parse::<usize>().ok();
+                if let Some(found) = index.and_then(|i| self.scan_results.get(i)) {
+                    match utils::set_clipboard_text(&found.text) {
+                        Ok(()) => info!("Copied {}", found.text),
+                        Err(e) => warn!("Failed to copy the scan result: {:#}", e),
+                    }
+                }
+            }
             id if id == menu_ids::TOGGLE_CURSOR => {
-                self.settings.show_cursor = !self.settings.show_cursor;
+                self.settings.capture.show_cursor = !self.settings.capture.show_cursor;
                 if let Some(menu) = &self.menu_cursor {
-                    menu.set_checked(self.settings.show_cursor);
+                    menu.set_checked(self.settings.capture.show_cursor);
                 }
-                info!("Cursor visibility: {}", self.settings.show_cursor);
+                info!("Cursor visibility: {}", self.settings.capture.show_cursor);
                 self.update_overlay_title();
 
                 // Update capture engine cursor visibility if active
                 if !self.is_selecting {
                     if let Some(capture) = &self.capture_engine {
-                        if let Err(e) = capture.update_cursor_visibility(self.settings.show_cursor)
-                        {
+                        let visible = self.settings.capture.show_cursor || self.cursor_held;
+                        if let Err(e) = capture.update_cursor_visibility(visible) {
                             error!("Failed to update cursor visibility: {}", e);
                         }
                     }
                 }
             }
             id if id == menu_ids::TOGGLE_BORDER => {
-                self.settings.show_border = !self.settings.show_border;
+                self.settings.capture.show_border = !self.settings.capture.show_border;
                 if let Some(menu) = &self.menu_border {
-                    menu.set_checked(self.settings.show_border);
+                    menu.set_checked(self.settings.capture.show_border);
                 }
-                info!("Border visibility: {}", self.settings.show_border);
+                info!("Border visibility: {}", self.settings.capture.show_border);
                 self.update_overlay_title();
 
                 // Toggle hollow frame if capture is active
                 if !self.is_selecting {
                     if let Some(overlay) = &self.overlay_window {
-                        if self.settings.show_border {
-                            overlay.make_hollow_frame(self.settings.border_width);
+                        if self.settings.capture.show_border {
+                            overlay.make_hollow_frame(self.settings.capture.border_width);
                             overlay.show();
                         } else {
                             overlay.hide();
@@ -236,13 +1404,14 @@ impl RustFrameApp {
                 }
             }
             id if id == menu_ids::TOGGLE_EXCLUDE => {
-                self.settings.exclude_from_capture = !self.settings.exclude_from_capture;
+                self.settings.capture.exclude_from_capture =
+                    !self.settings.capture.exclude_from_capture;
                 if let Some(menu) = &self.menu_exclude {
-                    menu.set_checked(self.settings.exclude_from_capture);
+                    menu.set_checked(self.settings.capture.exclude_from_capture);
                 }
                 info!(
                     "Production mode (dest behind overlay): {}",
-                    self.settings.exclude_from_capture
+                    self.settings.capture.exclude_from_capture
                 );
                 self.update_overlay_title();
 
@@ -254,7 +1423,7 @@ impl RustFrameApp {
                         let overlay_pos = overlay.get_outer_position();
                         let size = overlay.get_inner_size();
 
-                        if self.settings.exclude_from_capture {
+                        if self.settings.capture.exclude_from_capture {
                             dest.position_offscreen(size);
                         } else {
                             dest.position_beside_overlay(overlay_pos, size);
@@ -262,16 +1431,323 @@ impl RustFrameApp {
                     }
                 }
             }
-            id if id == menu_ids::SETTINGS => {
-                self.show_settings_dialog();
+            id if id == menu_ids::TOGGLE_PAUSE => {
+                self.set_output_paused(!self.pause_screen.is_paused());
             }
-            id if id == menu_ids::EXIT => {
-                info!("Exit requested from tray menu");
-                std::process::exit(0);
+            id if id == menu_ids::TOGGLE_FREEZE => {
+                self.set_output_frozen(!self.is_output_frozen);
             }
-            _ => {}
-        }
-    }
+            id if id == menu_ids::PAUSE_STYLE => {
+                self.pause_screen.cycle_style();
+                self.settings.pause_style = self.pause_screen.style();
+                if let Err(e) = settings_file::save(&self.settings) {
+                    error!("Failed to save settings: {:#}", e);
+                }
+                self.refresh_pause_style_menu();
+            }
+            id if id == menu_ids::DIFF_REFERENCE => self.set_diff_reference(),
+            id if id == menu_ids::DIFF_VIEW => {
+                self.frame_diff.cycle_view();
+                info!("Frame comparison view: {}", self.frame_diff.view().label());
+                self.refresh_diff_menu();
+            }
+            id if id == menu_ids::DIFF_STOP => self.stop_frame_diff(),
+            id if id == menu_ids::TIMER_MODE => {
+                self.timer.cycle_mode();
+                self.save_timer_settings();
+            }
+            id if id == menu_ids::TIMER_POSITION => {
+                self.timer.cycle_corner();
+                self.save_timer_settings();
+            }
+            id if id == menu_ids::TIMER_SIZE => {
+                self.timer.cycle_size();
+                self.save_timer_settings();
+            }
+            id if id == menu_ids::GRID_STYLE => {
+                self.cycle_grid_style();
+            }
+            id if id == menu_ids::GRID_IN_OUTPUT => {
+                self.grid.set_in_output(!self.grid.in_output());
+                info!("Grid in output: {}", self.grid.in_output());
+                if let Some(menu) = &self.menu_grid_output {
+                    menu.set_checked(self.grid.in_output());
+                }
+            }
+            id if id == menu_ids::CLEAR_EXCLUDED_WINDOWS => {
+                self.window_mask.clear_excluded();
+                info!("Cleared excluded windows");
+                if let Some(menu) = &self.menu_clear_excluded {
+                    menu.set_enabled(false);
+                }
+            }
+            id if id == menu_ids::CHROMA_KEY_ENABLED => {
+                self.chroma_key.set_enabled(!self.chroma_key.is_enabled());
+                self.apply_chroma_key();
+            }
+            id if id == menu_ids::CHROMA_KEY_COLOR => {
+                self.chroma_key.cycle_color();
+                self.apply_chroma_key();
+            }
+            id if id == menu_ids::CHROMA_KEY_SIMILARITY => {
+                self.chroma_key.cycle_similarity();
+                self.apply_chroma_key();
+            }
+            id if id == menu_ids::CHROMA_KEY_SMOOTHNESS => {
+                self.chroma_key.cycle_smoothness();
+                self.apply_chroma_key();
+            }
+            id if id == menu_ids::TOGGLE_SHARPEN => {
+                self.enhance.sharpen = !self.enhance.sharpen;
+                self.apply_enhance();
+            }
+            id if id == menu_ids::TOGGLE_DENOISE => {
+                self.enhance.denoise = !self.enhance.denoise;
+                self.apply_enhance();
+            }
+            id if id == menu_ids::TOGGLE_SECOND_SHARPEN => {
+                self.second_enhance.sharpen = !self.second_enhance.sharpen;
+                self.apply_enhance();
+            }
+            id if id == menu_ids::TOGGLE_SECOND_DENOISE => {
+                self.second_enhance.denoise = !self.second_enhance.denoise;
+                self.apply_enhance();
+            }
+            id if id.starts_with(menu_ids::SWITCH_SCENE) => {
+                if let Ok(slot) = id[menu_ids::SWITCH_SCENE.len()..].parse() {
+                    self.switch_scene(slot);
+                }
+            }
+            id if id.starts_with(menu_ids::SAVE_SCENE) => {
+                if let Ok(slot) = id[menu_ids::SAVE_SCENE.len()..].parse() {
+                    self.save_scene(slot);
+                }
+            }
+            id if id == menu_ids::TRANSITION => {
+                self.settings.transition_kind = self.settings.transition_kind.next();
+                info!("Transition: {}", self.settings.transition_kind.label());
+                if let Err(e) = settings_file::save(&self.settings) {
+                    error!("Failed to save settings: {:#}", e);
+                }
+                self.refresh_transition_menu();
+            }
+            id if id == menu_ids::PAN_START => {
+                self.toggle_pan();
+            }
+            id if id == menu_ids::PAN_ADD_POINT => {
+                self.add_pan_point();
+            }
+            id if id == menu_ids::PAN_CLEAR => {
+                self.settings.pan_path.clear();
+                info!("Pan path cleared");
+                self.save_pan_settings();
+            }
+            id if id == menu_ids::PAN_DURATION => {
+                let durations = constants::pan::DURATIONS;
+                self.settings.pan_seconds = durations
+                    .iter()
+                    .position(|&s| s == self.settings.pan_seconds)
+                    .map_or(durations[0], |i| durations[(i + 1) % durations.len()]);
+                info!("Pan duration: {} s", self.settings.pan_seconds);
+                self.save_pan_settings();
+            }
+            id if id == menu_ids::ADD_REGION => {
+                self.add_capture_region(event_loop);
+            }
+            id if id == menu_ids::REMOVE_REGION => {
+                self.remove_capture_region();
+            }
+            id if id == menu_ids::REGION_LAYOUT => {
+                self.composite_layout = self.composite_layout.next();
+                self.apply_composite_layout();
+            }
+            id if id == menu_ids::REGIONS_SEPARATE => {
+                self.set_regions_separate(event_loop, !self.regions_separate);
+            }
+            // Sources are arranged over the live capture
+            id if id == menu_ids::EDIT_LAYOUT && !self.is_selecting => {
+                self.set_layout_editor(!self.is_editing_layout);
+            }
+            id if id.starts_with(menu_ids::LAYOUT_TEMPLATE) => {
+                let index = id[menu_ids::LAYOUT_TEMPLATE.len()..].parse::<usize>();
+                if let Some(&layout) = index.ok().and_then(|i| CompositeLayout::TEMPLATES.get(i)) {
+                    self.apply_layout_template(event_loop, layout);
+                }
+            }
+            id if id == menu_ids::WEBCAM_MODE => {
+                self.set_webcam_mode(self.webcam_mode.next());
+            }
+            id if id == menu_ids::WEBCAM_DEVICE => {
+                self.next_webcam_device();
+            }
+            id if id == menu_ids::IMAGE_MODE => {
+                self.set_image_mode(self.image_mode.next());
+            }
+            id if id == menu_ids::NEXT_IMAGE => {
+                if let Some(image) = &mut self.image_source {
+                    image.next_image();
+                }
+            }
+            id if id == menu_ids::TEST_PATTERN => {
+                let kind = PatternKind::next(self.test_pattern.as_ref().map(|p| p.kind()));
+                self.set_test_pattern(kind);
+            }
+            id if id == menu_ids::SETTINGS => {
+                self.show_settings_dialog();
+            }
+            id if id == menu_ids::EXPORT_CONFIG => {
+                self.export_config();
+            }
+            id if id == menu_ids::IMPORT_CONFIG => {
+                self.import_config();
+            }
+            id if id == menu_ids::EXIT => {
+                info!("Exit requested from tray menu");
+                if self.is_selecting {
+                    event_loop.exit();
+                } else {
+                    self.confirm(ConfirmAction::Exit);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle global hotkey actions
+    fn handle_hotkey(&mut self, action: HotkeyAction) {
+        info!("Hotkey action: {:?}", action);
+        match action {
+            HotkeyAction::ToggleDrawMode => {
+                // Drawing only makes sense while capturing
+                if !self.is_selecting {
+                    self.set_drawing_mode(!self.is_drawing_mode);
+                }
+            }
+            HotkeyAction::ClearAnnotations => {
+                self.annotations.clear();
+                self.refresh_annotation_overlay();
+            }
+            HotkeyAction::ToggleMeasureMode => {
+                // Measuring only makes sense while capturing
+                if !self.is_selecting {
+                    self.set_measure_mode(!self.is_measuring);
+                }
+            }
+            HotkeyAction::ToggleRedactMode => {
+                // Regions are placed over the live capture
+                if !self.is_selecting {
+                    self.set_redact_mode(!self.is_redacting);
+                }
+            }
+            HotkeyAction::ToggleColorPicker => {
+                // Colors are picked from the captured frames
+                if !self.is_selecting {
+                    self.set_color_picker_mode(!self.is_picking_color);
+                }
+            }
+            HotkeyAction::ToggleExcludeWindow => {
+                self.toggle_excluded_window_at_cursor();
+            }
+            HotkeyAction::ScanFrame => self.scan_frame(),
+            HotkeyAction::ToggleFrameDiff => {
+                if self.frame_diff.is_active() {
+                    self.stop_frame_diff();
+                } else {
+                    self.set_diff_reference();
+                }
+            }
+            HotkeyAction::ToggleSpotlight => {
+                self.spotlight.set_enabled(!self.spotlight.is_enabled());
+                info!(
+                    "Spotlight: {} ({})",
+                    self.spotlight.is_enabled(),
+                    self.spotlight.shape().label()
+                );
+            }
+            HotkeyAction::ToggleSpotlightPin => {
+                self.spotlight.toggle_follow_cursor();
+                info!(
+                    "Spotlight follows cursor: {}",
+                    self.spotlight.follows_cursor()
+                );
+            }
+            HotkeyAction::ToggleSpotlightShape => self.spotlight.toggle_shape(),
+            HotkeyAction::GrowSpotlight => self.spotlight.resize(1),
+            HotkeyAction::ShrinkSpotlight => self.spotlight.resize(-1),
+            HotkeyAction::ToggleClickHighlight => {
+                self.click_highlight
+                    .set_enabled(!self.click_highlight.is_enabled());
+                info!("Click highlights: {}", self.click_highlight.is_enabled());
+                self.update_mouse_hook();
+            }
+            HotkeyAction::HoldToShowCursor => self.set_cursor_held(true),
+            HotkeyAction::ToggleInputRecording => {
+                self.set_record_input(!self.settings.record_input)
+            }
+            HotkeyAction::ToggleMagnifier => {
+                self.magnifier.set_enabled(!self.magnifier.is_enabled());
+                info!(
+                    "Magnifier: {} ({}x, {})",
+                    self.magnifier.is_enabled(),
+                    self.magnifier.zoom(),
+                    self.magnifier.mode().label()
+                );
+                self.update_magnifier_window();
+            }
+            HotkeyAction::ToggleMagnifierMode => {
+                self.magnifier.toggle_mode();
+                info!("Magnifier mode: {}", self.magnifier.mode().label());
+                self.update_magnifier_window();
+            }
+            HotkeyAction::ZoomIn => self.magnifier.adjust_zoom(1),
+            HotkeyAction::ZoomOut => self.magnifier.adjust_zoom(-1),
+            HotkeyAction::ToggleTimer => {
+                self.timer.toggle_running();
+                info!(
+                    "Timer {} ({})",
+                    if self.timer.is_running() { "started" } else { "paused" },
+                    self.timer.mode().label()
+                );
+            }
+            HotkeyAction::ResetTimer => {
+                self.timer.reset();
+                info!("Timer reset");
+            }
+            HotkeyAction::TogglePause => {
+                self.set_output_paused(!self.pause_screen.is_paused());
+            }
+            HotkeyAction::ToggleFreeze => {
+                self.set_output_frozen(!self.is_output_frozen);
+            }
+            HotkeyAction::SwitchScene(slot) => self.switch_scene(slot),
+            HotkeyAction::SaveScene(slot) => self.save_scene(slot),
+        }
+    }
+
+    /// Handle the release of a held hotkey (see HotkeyAction::is_held)
+    fn handle_hotkey_release(&mut self, action: HotkeyAction) {
+        if action == HotkeyAction::HoldToShowCursor {
+            self.set_cursor_held(false);
+        }
+    }
+
+    /// Reveal the cursor in the output while the hold-to-show-cursor keys are
+    /// held, hide it again (unless show_cursor is on) when they are released
+    fn set_cursor_held(&mut self, held: bool) {
+        if self.cursor_held == held {
+            return;
+        }
+        self.cursor_held = held;
+        info!("Cursor held visible: {}", held);
+        if self.is_selecting || self.settings.capture.show_cursor {
+            return;
+        }
+        if let Some(capture) = &self.capture_engine {
+            if let Err(e) = capture.update_cursor_visibility(held) {
+                error!("Failed to update cursor visibility: {}", e);
+            }
+        }
+    }
 }
 
 /// Load the application icon from icon.ico file
@@ -329,72 +1805,435 @@ fn create_default_icon() -> Vec<u8> {
 
 impl ApplicationHandler for RustFrameApp {
     /// Called when the application is resumed (Windows-specific lifecycle)
-    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
-        info!("Application resumed");
-
-        // Create the overlay window first (for region selection)
-        if self.overlay_window.is_none() {
-            match OverlayWindow::new(event_loop) {
-                Ok(overlay) => {
-                    info!("Overlay window created successfully");
-                    // Initialize the overlay with current settings state
-                    if let Err(e) = overlay.update_settings_display(
-                        self.settings.show_cursor,
-                        self.settings.show_border,
-                        self.settings.exclude_from_capture
-                    ) {
-                        error!("Failed to initialize overlay settings display: {}", e);
-                    }
-                    self.overlay_window = Some(overlay);
-                    // Set initial title with settings info
-                    self.update_overlay_title();
-                }
-                Err(e) => {
-                    error!("Failed to create overlay window: {}", e);
-                }
+    /// Orderly shutdown for every way out (tray → Exit, ESC, closing the
+    /// window, quit on stdin, logging off): stop the capture, so the input
+    /// recording is saved and the capture session and its frame queue are
+    /// closed; send the queued webhooks; remove the hooks and the tray icon.
+    /// The rest is dropped with the app when main returns.
+    fn exiting(&mut self, _event_loop: &ActiveEventLoop) {
+        info!("Shutting down");
+        if !self.is_selecting {
+            info!("Exiting while capturing, stopping the capture first");
+            self.stop_capture();
+            if let Some(overlay) = &self.overlay_window {
+                overlay.hide();
             }
         }
+        self.webhooks
+            .drain(Duration::from_millis(constants::session_end::WEBHOOK_DRAIN_MS));
+        self.window_picker = None;
+        self.mouse_hook = None;
+        self.hotkeys = None;
+        self.tray_icon = None;
+    }
 
-        // Create the destination window
-        if self.destination_window.is_none() {
-            match DestinationWindow::new(event_loop, self.dev_mode) {
-                Ok(dest) => {
-                    info!("Destination window created successfully");
-                    self.destination_window = Some(dest);
-                }
-                Err(e) => {
-                    error!("Failed to create destination window: {}", e);
-                }
-            }
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        info!("Application resumed");
+
+        // Daemon mode: only the tray icon until the windows are needed
+        if !self.daemon {
+            self.create_windows(event_loop);
         }
 
         // Create tray icon
         if self.tray_icon.is_none() {
             self.create_tray_icon();
         }
+
+        // Launched minimized - only the tray icon is shown
+        if std::mem::take(&mut self.start_minimized) {
+            if let Some(id) = self.overlay_window.as_ref().map(|o| o.window_id()) {
+                self.hide_to_tray(id);
+            }
+        }
+
+        // Register global hotkeys
+        if self.hotkeys.is_none() {
+            self.register_hotkeys();
+        }
+
+        // Launched by a rustframe:// link
+        if let Some(link) = self.pending_link.take() {
+            self.ensure_windows(event_loop);
+            self.handle_deep_link(&link, LinkSource::Launch);
+        }
     }
 
     /// Called when the event loop is about to block waiting for events
     fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        // Logging off / shutting down: the orderly shutdown runs in exiting()
+        if session_end::requested() {
+            event_loop.exit();
+            return;
+        }
+
+        // Answer of the confirmation box, if one is open
+        if let Some((action, receiver)) = &self.pending_confirm {
+            let action = action.clone();
+            match receiver.try_recv() {
+                Ok(answer) => {
+                    self.pending_confirm = None;
+                    info!("{:?} confirmed: {}", action, answer);
+                    match (action, answer) {
+                        (ConfirmAction::StopCapture, true) => self.stop_capture(),
+                        (ConfirmAction::Exit, true) => event_loop.exit(),
+                        (ConfirmAction::RestartElevated, true) => {
+                            self.restart_elevated(event_loop)
+                        }
+                        (ConfirmAction::RunLink(link), true) => self.run_deep_link(link),
+                        (_, false) => {}
+                    }
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => self.pending_confirm = None,
+            }
+        }
+
+        // Results of the frame scan, if one is running
+        if let Some(receiver) = &self.pending_scan {
+            match receiver.try_recv() {
+                Ok(found) => {
+                    self.pending_scan = None;
+                    self.finish_scan(found);
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => self.pending_scan = None,
+            }
+        }
+
         // Check for tray menu events
         if let Ok(event) = MenuEvent::receiver().try_recv() {
-            self.handle_menu_event(&event);
+            if !menu_ids::WINDOWLESS.iter().any(|id| event.id().as_ref().starts_with(id)) {
+                self.ensure_windows(event_loop);
+            }
+            self.handle_menu_event(event_loop, &event);
+        }
+
+        // Left click on the tray icon brings back windows hidden to the tray
+        while let Ok(event) = TrayIconEvent::receiver().try_recv() {
+            if let TrayIconEvent::Click {
+                button: tray_icon::MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                self.ensure_windows(event_loop);
+                self.restore_from_tray();
+            }
+        }
+
+        // Check for global hotkey events
+        if let Some((action, pressed)) = self.hotkeys.as_ref().and_then(|h| h.poll()) {
+            self.ensure_windows(event_loop);
+            if pressed {
+                self.handle_hotkey(action);
+            } else {
+                self.handle_hotkey_release(action);
+            }
+        }
+
+        // Check for gamepad buttons
+        let gamepad_actions = self.gamepad.as_mut().map(|g| g.poll()).unwrap_or_default();
+        for action in gamepad_actions {
+            self.ensure_windows(event_loop);
+            self.handle_gamepad(action);
+        }
+
+        // Check for MIDI notes / CCs
+        while let Some(event) = self.midi.as_ref().and_then(|m| m.poll()) {
+            self.ensure_windows(event_loop);
+            self.handle_midi(event);
+        }
+
+        // Check for Stream Deck actions and update the keys
+        while let Some(action) = self.stream_deck.as_ref().and_then(|s| s.poll()) {
+            self.ensure_windows(event_loop);
+            self.handle_remote(action);
+        }
+        if let Some(server) = &self.stream_deck {
+            server.publish(self.remote_state());
+        }
+
+        // Check for OSC messages from control surfaces
+        while let Some(action) = self.osc.as_ref().and_then(|o| o.poll()) {
+            self.ensure_windows(event_loop);
+            self.handle_remote(action);
+        }
+
+        // Check for commands of the parent program (--control-stdio)
+        while let Some(input) = self.stdio.as_ref().and_then(|s| s.poll()) {
+            let query = match &input {
+                StdioInput::Request(request) => {
+                    matches!(request.command, StdioCommand::Status | StdioCommand::Quit)
+                }
+                StdioInput::Closed => true,
+            };
+            if !query {
+                self.ensure_windows(event_loop);
+            }
+            self.handle_stdio(event_loop, input);
+        }
+        let state = self.stdio.as_ref().map(|_| self.remote_state());
+        if let (Some(stdio), Some(state)) = (&mut self.stdio, state) {
+            stdio.publish(&state, false);
+        }
+
+        // Apply settings.json when it was edited in a text editor
+        if self.settings_watcher.poll() {
+            self.reload_settings_file();
+        }
+
+        // Call the script hooks and run what the scripts asked for
+        let state = self.scripts.as_ref().map(|_| self.remote_state());
+        if let (Some(scripts), Some(state)) = (&mut self.scripts, state) {
+            scripts.update(&state);
+        }
+        while let Some(command) = self.scripts.as_ref().and_then(|s| s.poll()) {
+            self.ensure_windows(event_loop);
+            match command {
+                ScriptCommand::Remote(action) => self.handle_remote(action),
+                ScriptCommand::Hotkey(action) => self.handle_hotkey(action),
+            }
+        }
+
+        // Check for rustframe:// links passed on by a second process
+        while let Some(command) = self.instance.as_ref().and_then(|i| i.poll()) {
+            self.ensure_windows(event_loop);
+            let (source, link) = deep_link::split_source(&command);
+            self.handle_deep_link(link, source);
+        }
+        self.tick_window_picker();
+
+        self.refresh_tray_status();
+
+        // Pre-capture countdown - starts the capture once it runs out
+        if self.countdown_started.is_some() {
+            self.tick_countdown(event_loop);
+            if self.is_selecting {
+                return;
+            }
         }
 
         // During selection mode, just wait for user input
+        // (the gamepad, MIDI input, Stream Deck server, OSC listener, instance
+        // pipe and window picker have to be polled, so keep waking up while
+        // they are on)
         if self.is_selecting {
-            event_loop.set_control_flow(ControlFlow::Wait);
+            let poll_ms = [
+                self.gamepad
+                    .as_ref()
+                    .map(|_| constants::gamepad::POLL_INTERVAL_MS),
+                self.midi.as_ref().map(|_| constants::midi::POLL_INTERVAL_MS),
+                self.stream_deck
+                    .as_ref()
+                    .map(|_| constants::stream_deck::POLL_INTERVAL_MS),
+                self.osc.as_ref().map(|_| constants::osc::POLL_INTERVAL_MS),
+                self.stdio
+                    .as_ref()
+                    .map(|_| constants::control_stdio::POLL_INTERVAL_MS),
+                self.instance
+                    .as_ref()
+                    .map(|_| constants::instance::POLL_INTERVAL_MS),
+                self.window_picker
+                    .as_ref()
+                    .map(|_| constants::shell_menu::PICK_POLL_MS),
+            ]
+            .into_iter()
+            .flatten()
+            .min();
+            match poll_ms {
+                Some(ms) => {
+                    let interval = Duration::from_millis(ms);
+                    event_loop.set_control_flow(ControlFlow::WaitUntil(Instant::now() + interval));
+                }
+                None => event_loop.set_control_flow(ControlFlow::Wait),
+            }
             return;
         }
 
-        // Capture is active - use Poll for continuous rendering
-        event_loop.set_control_flow(ControlFlow::Poll);
+        // Capture started by a link with a duration
+        if let (Some(started), Some(duration)) = (self.capture_started, self.capture_duration) {
+            if started.elapsed() >= duration {
+                info!("Capture duration of {}s reached", duration.as_secs());
+                self.stop_capture();
+                return;
+            }
+        }
+
+        // Capture is active - sleep until a capture engine has a new frame, and
+        // wake up regularly for overlays, the other sources and animations
+        let wait = Duration::from_millis(constants::capture::FRAME_WAIT_MS);
+        event_loop.set_control_flow(ControlFlow::WaitUntil(Instant::now() + wait));
+
+        // Move the spotlight and magnifier with the cursor (capture-region coordinates)
+        if self.spotlight.is_enabled() || self.magnifier.is_enabled() {
+            if let (Some(capture), Some((x, y))) = (&self.capture_engine, utils::get_cursor_pos()) {
+                let region = capture.get_capture_region();
+                if self.spotlight.follows_cursor() {
+                    self.spotlight.update_cursor(x - region.x, y - region.y);
+                }
+                if self.magnifier.is_enabled() {
+                    self.magnifier.update_cursor(x - region.x, y - region.y);
+                }
+            }
+        }
+
+        // Clicks and drags since the last frame (a running replay's own clicks
+        // are not recorded)
+        let replaying = self.input_replay.as_ref().is_some_and(|r| r.is_running());
+        if let (Some(hook), Some(capture)) = (&self.mouse_hook, &self.capture_engine) {
+            let region = capture.get_capture_region();
+            let finished = hook.take_finished();
+            let current = hook.current();
+            if let (Some(recorder), false) = (&mut self.input_recorder, replaying) {
+                recorder.add_gestures(&finished, current.as_ref());
+            }
+            if self.click_highlight.is_enabled() {
+                self.click_highlight
+                    .update(finished, current, (region.x, region.y));
+            }
+        }
+        if self.input_replay.as_ref().is_some_and(|r| !r.is_running()) {
+            self.input_replay = None;
+            self.refresh_replay_label();
+        }
+
+        self.timer.tick();
+        self.frame_diff.tick();
+        self.update_toolbar();
+        self.update_pan();
+
+        // Follow the picked window to its new instance if it restarted
+        if let Some(rect) = self.tracked_window.as_mut().and_then(|t| t.poll()) {
+            self.set_capture_region(rect);
+        }
+
+        // Track windows of redacted applications over the capture regions
+        let mut elevated_app = None;
+        if let Some(capture) = &self.capture_engine {
+            let mut regions = vec![capture.get_capture_region()];
+            regions.extend(self.region_captures.iter().map(|c| c.get_capture_region()));
+            self.window_mask.refresh(&regions);
+
+            // Watch for interruptions (captured application minimized, UAC prompt)
+            self.frame_hold.update(capture.get_capture_region());
+
+            // An elevated application in front blocks the input hooks
+            elevated_app = self.elevation.update(capture.get_capture_region());
+        }
+        if let Some(name) = elevated_app {
+            self.offer_elevated_restart(name);
+        }
+
+        // Explain a region that turned black (see game_capture.rs); a message box
+        // would pull an exclusive-fullscreen game out of fullscreen, so that case
+        // is only logged and shown in the tray status
+        if let Some(notice) = self.capture_engine.as_mut().and_then(|c| c.take_notice()) {
+            if notice.cause != game_capture::BlackCause::ExclusiveFullscreen && !self.black_warned {
+                self.black_warned = true;
+                utils::show_warning(notice.message());
+            }
+        }
+
+        self.power.update();
+        let low_power = self.low_power();
 
-        if let (Some(renderer), Some(capture)) = (&mut self.renderer, &mut self.capture_engine) {
-            if let Err(e) = renderer.render(capture) {
-                error!("Render error in about_to_wait: {}", e);
+        // Drop to the idle frame rate while the captured content does not change;
+        // the first changed frame read at that rate brings the full rate back
+        let idle_after_ms = if low_power {
+            self.settings
+                .idle_after_ms
+                .min(constants::power::IDLE_AFTER_MS)
+        } else {
+            self.settings.idle_after_ms
+        };
+        let idle_after = Duration::from_millis(idle_after_ms as u64);
+        let idle = self.settings.idle_fps > 0
+            && self
+                .renderer
+                .as_ref()
+                .is_some_and(|r| r.unchanged_for() >= idle_after);
+        if idle != self.idle {
+            self.idle = idle;
+            if idle {
+                info!(
+                    "Captured content is static - rendering at {} fps",
+                    self.settings.idle_fps
+                );
+            } else {
+                info!("Captured content changed - back to the full frame rate");
+            }
+        }
+
+        // Limit the output to the target frame rate (0 = every captured frame),
+        // on battery to the low-power frame rate
+        let target = match (low_power, self.settings.capture.target_fps) {
+            (true, 0) => self.settings.low_power_fps,
+            (true, target) => target.min(self.settings.low_power_fps),
+            (false, target) => target,
+        };
+        let fps = match (idle, target) {
+            (true, 0) => self.settings.idle_fps,
+            (true, target) => target.min(self.settings.idle_fps),
+            (false, target) => target,
+        };
+        // Only the main output is throttled - the second output and the
+        // magnifier window below keep following their sources
+        let mut render_main = true;
+        if fps > 0 {
+            let interval = Duration::from_secs_f64(1.0 / fps as f64);
+            match self.last_render.map(|t| t + interval) {
+                Some(next) if next > Instant::now() => {
+                    event_loop.set_control_flow(ControlFlow::WaitUntil(next));
+                    render_main = false;
+                }
+                _ => self.last_render = Some(Instant::now()),
+            }
+        }
+
+        if render_main {
+            self.render_output();
+
+            // Notify once when the captured screen stops delivering frames
+            let source_lost = self.renderer.as_ref().is_some_and(|r| r.source_lost());
+            if source_lost && !self.stream_dropped {
+                self.send_webhook(
+                    WebhookEvent::StreamDrop,
+                    "The capture source stopped delivering frames",
+                );
+            }
+            self.stream_dropped = source_lost;
+
+            // Keys and the cursor position, numbered with the frames presented so far
+            if let (Some(recorder), false) = (&mut self.input_recorder, replaying) {
+                let frames = self.renderer.as_ref().map_or(0, |r| r.frame_count());
+                recorder.update(frames, utils::get_cursor_pos());
+            }
+            self.save_screenshot();
+        }
+
+        // Second output: the additional regions on their own, without overlays
+        // (overlays are positioned in the main region's coordinates), but with
+        // the redacted windows and notifications masked in each region
+        let regions: Vec<Option<usize>> = (1..=self.region_captures.len()).map(Some).collect();
+        if let (Some(renderer), Some((first, rest))) = (
+            &mut self.second_renderer,
+            self.region_captures.split_first_mut(),
+        ) {
+            let mut sources: Vec<&mut dyn FrameSource> =
+                rest.iter_mut().map(|c| c as &mut dyn FrameSource).collect();
+            let redaction = Redaction {
+                filters: &self.filters,
+                window_mask: &self.window_mask,
+                privacy: &self.privacy,
+                regions: &regions,
+            };
+            if let Err(e) = renderer.render(first, &mut sources, &redaction, None) {
+                error!("Render error in second output: {}", e);
             }
         }
+
+        self.update_magnifier_window();
     }
 
     /// Main event dispatcher - routes events to appropriate windows
@@ -406,10 +2245,38 @@ impl ApplicationHandler for RustFrameApp {
     ) {
         // Route events to the correct window
         match event {
+            WindowEvent::CloseRequested
+                if self
+                    .second_destination
+                    .as_ref()
+                    .is_some_and(|d| d.window_id() == window_id) =>
+            {
+                // Closing the second output only puts the regions back into the main output
+                self.set_regions_separate(event_loop, false);
+            }
+            WindowEvent::CloseRequested if self.settings.close_to_tray => {
+                self.hide_to_tray(window_id);
+            }
+            WindowEvent::CloseRequested if !self.is_selecting => {
+                info!("Close requested while capturing");
+                self.confirm(ConfirmAction::Exit);
+            }
             WindowEvent::CloseRequested => {
                 info!("Close requested, shutting down");
                 event_loop.exit();
             }
+            // Minimized (a minimized window is resized to 0x0 on Windows)
+            WindowEvent::Resized(size)
+                if self.settings.minimize_to_tray && size.width == 0 && size.height == 0 =>
+            {
+                self.hide_to_tray(window_id);
+            }
+            // Windows switched between light and dark or to a contrast theme
+            WindowEvent::ThemeChanged(_) => {
+                accessibility::refresh();
+                let accent = self.settings.accent_color.clone();
+                self.apply_theme(self.settings.theme, &accent);
+            }
 
             WindowEvent::RedrawRequested => {
                 // Handle redraw for overlay during selection
@@ -427,10 +2294,55 @@ impl ApplicationHandler for RustFrameApp {
                 if !self.is_selecting {
                     if let Some(dest) = &self.destination_window {
                         if dest.window_id() == window_id {
-                            if let (Some(renderer), Some(capture)) =
-                                (&mut self.renderer, &mut self.capture_engine)
+                            let regions = self.main_output_regions();
+                            let main: Option<&mut dyn FrameSource> = match &mut self.test_pattern
                             {
-                                if let Err(e) = renderer.render(capture) {
+                                Some(pattern) => Some(pattern),
+                                None => self
+                                    .capture_engine
+                                    .as_mut()
+                                    .map(|c| c as &mut dyn FrameSource),
+                            };
+                            if let (Some(renderer), Some(main)) = (&mut self.renderer, main) {
+                                let mut sources: Vec<&mut dyn FrameSource> = Vec::new();
+                                if !self.regions_separate {
+                                    sources.extend(
+                                        self.region_captures
+                                            .iter_mut()
+                                            .map(|c| c as &mut dyn FrameSource),
+                                    );
+                                }
+                                if let Some(webcam) = &mut self.webcam {
+                                    sources.push(webcam);
+                                }
+                                if let Some(image) = &mut self.image_source {
+                                    sources.push(image);
+                                }
+                                let overlays = FrameOverlays {
+                                    filters: &self.filters,
+                                    annotations: &self.annotations,
+                                    clicks: &self.click_highlight,
+                                    spotlight: &self.spotlight,
+                                    magnifier: &self.magnifier,
+                                    timer: &self.timer,
+                                    pause: &self.pause_screen,
+                                    diff: &self.frame_diff,
+                                    grid: &self.grid,
+                                    opacity: &self.overlay_opacity,
+                                    plugins: &self.plugins,
+                                };
+                                let redaction = Redaction {
+                                    filters: &self.filters,
+                                    window_mask: &self.window_mask,
+                                    privacy: &self.privacy,
+                                    regions: &regions,
+                                };
+                                if let Err(e) = renderer.render(
+                                    main,
+                                    &mut sources,
+                                    &redaction,
+                                    Some(&overlays),
+                                ) {
                                     error!("Render error: {}", e);
                                 }
                             }
@@ -454,15 +2366,26 @@ impl ApplicationHandler for RustFrameApp {
                 // If overlay window is resized, update hollow frame, capture region, and destination
                 if let Some(overlay) = &self.overlay_window {
                     if overlay.window_id() == window_id && !self.is_selecting {
-                        // Update the hollow frame region
-                        if self.settings.show_border {
-                            overlay.update_hollow_frame(self.settings.border_width);
+                        // Update the hollow frame region (draw mode uses the full window)
+                        if self.is_drawing_mode {
+                            overlay.draw_annotations(self.annotation_offset(), &self.annotations);
+                        } else if self.is_measuring {
+                            overlay.draw_measurement(self.annotation_offset(), &self.measurement);
+                        } else if self.is_picking_color {
+                            overlay.draw_color_picker(self.annotation_offset(), &self.color_picker);
+                        } else if self.is_redacting {
+                            overlay.draw_privacy_regions(self.annotation_offset(), &self.privacy);
+                        } else if self.is_editing_layout {
+                            overlay
+                                .draw_layout_editor(self.annotation_offset(), &self.layout_editor);
+                        } else if self.settings.capture.show_border {
+                            overlay.update_hollow_frame(self.settings.capture.border_width);
                         }
 
                         // Update capture region (inside border if border is shown)
                         if let Some(capture) = &mut self.capture_engine {
-                            let rect = if self.settings.show_border {
-                                overlay.get_capture_rect_inner(self.settings.border_width)
+                            let rect = if self.settings.capture.show_border {
+                                overlay.get_capture_rect_inner(self.settings.capture.border_width)
                             } else {
                                 overlay.get_capture_rect()
                             };
@@ -473,14 +2396,14 @@ impl ApplicationHandler for RustFrameApp {
 
                         // Resize destination window to match (minus border if shown)
                         if let Some(dest) = &self.destination_window {
-                            let inner_size = if self.settings.show_border {
+                            let inner_size = if self.settings.capture.show_border {
                                 PhysicalSize::new(
                                     new_size
                                         .width
-                                        .saturating_sub(self.settings.border_width * 2),
+                                        .saturating_sub(self.settings.capture.border_width * 2),
                                     new_size
                                         .height
-                                        .saturating_sub(self.settings.border_width * 2),
+                                        .saturating_sub(self.settings.capture.border_width * 2),
                                 )
                             } else {
                                 new_size
@@ -493,6 +2416,26 @@ impl ApplicationHandler for RustFrameApp {
                     }
                 }
 
+                // If an additional region is resized, update its frame and capture region
+                if let Some(index) = self.region_index(window_id) {
+                    let overlay = &self.region_overlays[index];
+                    overlay.update_hollow_frame(self.settings.capture.border_width);
+                    let rect = overlay.get_capture_rect_inner(self.settings.capture.border_width);
+                    if let Err(e) = self.region_captures[index].update_region(rect) {
+                        error!("Failed to update capture region: {}", e);
+                    }
+                }
+
+                // With additional regions the output holds all of them
+                let is_region = self.region_index(window_id).is_some()
+                    || self
+                        .overlay_window
+                        .as_ref()
+                        .is_some_and(|o| o.window_id() == window_id);
+                if is_region && !self.is_selecting && !self.region_overlays.is_empty() {
+                    self.resize_output();
+                }
+
                 // If destination window is resized (by user), resize renderer
                 if let Some(dest) = &self.destination_window {
                     if dest.window_id() == window_id {
@@ -501,6 +2444,13 @@ impl ApplicationHandler for RustFrameApp {
                         }
                     }
                 }
+                if let Some(dest) = &self.second_destination {
+                    if dest.window_id() == window_id {
+                        if let Some(renderer) = &mut self.second_renderer {
+                            renderer.resize(new_size.width, new_size.height);
+                        }
+                    }
+                }
             }
 
             WindowEvent::Moved(new_position) => {
@@ -509,8 +2459,8 @@ impl ApplicationHandler for RustFrameApp {
                     if overlay.window_id() == window_id && !self.is_selecting {
                         // Update capture region with new position (inside border if shown)
                         if let Some(capture) = &mut self.capture_engine {
-                            let rect = if self.settings.show_border {
-                                overlay.get_capture_rect_inner(self.settings.border_width)
+                            let rect = if self.settings.capture.show_border {
+                                overlay.get_capture_rect_inner(self.settings.capture.border_width)
                             } else {
                                 overlay.get_capture_rect()
                             };
@@ -524,29 +2474,68 @@ impl ApplicationHandler for RustFrameApp {
                         );
                     }
                 }
+
+                // Same for the additional capture regions
+                if let Some(index) = self.region_index(window_id) {
+                    let rect =
+                        self.region_overlays[index].get_capture_rect_inner(self.settings.capture.border_width);
+                    if let Err(e) = self.region_captures[index].update_region(rect) {
+                        error!("Failed to update capture region after move: {}", e);
+                    }
+                }
+            }
+
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers.state();
             }
 
             WindowEvent::KeyboardInput { event, .. } => {
                 // Only handle key press events (not release)
                 if event.state == winit::event::ElementState::Pressed {
-                    use std::time::Duration;
                     use winit::keyboard::{KeyCode, PhysicalKey};
 
                     match event.physical_key {
-                        PhysicalKey::Code(KeyCode::Escape) => {
-                            if self.is_selecting {
-                                // In selection/idle mode: exit application
-                                info!("ESC pressed in selection mode, exiting");
-                                event_loop.exit();
+                        PhysicalKey::Code(KeyCode::Escape) if self.is_drawing_mode => {
+                            // In draw mode: clear annotations first, leave draw mode when empty
+                            if self.annotations.is_empty() {
+                                info!("ESC pressed in draw mode, leaving draw mode");
+                                self.set_drawing_mode(false);
                             } else {
-                                // In capture mode: stop capture and return to selection mode
-                                info!("ESC pressed in capture mode, stopping capture");
-                                self.stop_capture();
+                                info!("ESC pressed in draw mode, clearing annotations");
+                                self.annotations.clear();
+                                self.refresh_annotation_overlay();
                             }
                         }
+                        PhysicalKey::Code(KeyCode::Escape) if self.is_measuring => {
+                            info!("ESC pressed in measure mode, leaving measure mode");
+                            self.set_measure_mode(false);
+                        }
+                        PhysicalKey::Code(KeyCode::Escape) if self.is_picking_color => {
+                            info!("ESC pressed in color picker mode, leaving color picker mode");
+                            self.set_color_picker_mode(false);
+                        }
+                        PhysicalKey::Code(KeyCode::Escape) if self.is_redacting => {
+                            // Regions stay active - ESC only stops editing them
+                            info!("ESC pressed in redact mode, leaving redact mode");
+                            self.set_redact_mode(false);
+                        }
+                        PhysicalKey::Code(KeyCode::Escape) if self.is_editing_layout => {
+                            // The layout stays - ESC only closes the editor
+                            info!("ESC pressed in the layout editor, closing it");
+                            self.set_layout_editor(false);
+                        }
+                        PhysicalKey::Code(KeyCode::Escape)
+                            if self.countdown_started.is_some() =>
+                        {
+                            info!("ESC pressed during countdown, cancelling");
+                            self.cancel_countdown();
+                        }
+                        PhysicalKey::Code(KeyCode::Escape) => {
+                            self.handle_escape(event_loop, window_id);
+                        }
                         PhysicalKey::Code(KeyCode::Enter)
                         | PhysicalKey::Code(KeyCode::NumpadEnter)
-                            if self.is_selecting =>
+                            if self.is_selecting && self.countdown_started.is_none() =>
                         {
                             // Ignore Enter for first 500ms after startup
                             // This prevents accidental capture when launching with Enter key
@@ -554,38 +2543,205 @@ impl ApplicationHandler for RustFrameApp {
                                 info!("Enter ignored (startup cooldown)");
                             } else {
                                 info!("Region selection confirmed, starting capture");
-                                self.start_capture();
+                                self.begin_countdown();
                             }
                         }
                         // Settings shortcuts (only during selection mode)
                         PhysicalKey::Code(KeyCode::KeyC) if self.is_selecting => {
-                            self.settings.show_cursor = !self.settings.show_cursor;
-                            info!("Cursor visibility: {}", self.settings.show_cursor);
-                            self.update_overlay_title();
+                            self.toggle_overlay_option(0);
                         }
                         PhysicalKey::Code(KeyCode::KeyB) if self.is_selecting => {
-                            self.settings.show_border = !self.settings.show_border;
-                            info!("Border visibility: {}", self.settings.show_border);
-                            self.update_overlay_title();
+                            self.toggle_overlay_option(1);
                         }
                         PhysicalKey::Code(KeyCode::KeyE) if self.is_selecting => {
-                            self.settings.exclude_from_capture =
-                                !self.settings.exclude_from_capture;
-                            info!(
-                                "Exclude from capture: {}",
-                                self.settings.exclude_from_capture
-                            );
-                            self.update_overlay_title();
+                            self.toggle_overlay_option(2);
+                        }
+                        // Keyboard-only operation: Tab moves the focus between the
+                        // settings lines, Space toggles the focused one
+                        PhysicalKey::Code(KeyCode::Tab) if self.is_selecting => {
+                            self.cycle_overlay_focus(self.modifiers.shift_key());
+                        }
+                        PhysicalKey::Code(KeyCode::Space) if self.is_selecting => {
+                            if let Some(option) = self.focused_option {
+                                self.toggle_overlay_option(option);
+                            }
+                        }
+                        // Arrows move the region, Shift+Arrows resize it (Ctrl = fine step)
+                        PhysicalKey::Code(
+                            code @ (KeyCode::ArrowLeft
+                            | KeyCode::ArrowRight
+                            | KeyCode::ArrowUp
+                            | KeyCode::ArrowDown),
+                        ) if self.countdown_started.is_none()
+                            && !self.is_drawing_mode
+                            && !self.is_measuring
+                            && !self.is_picking_color
+                            && !self.is_redacting
+                            && !self.is_editing_layout =>
+                        {
+                            self.nudge_overlay(code);
                         }
                         PhysicalKey::Code(KeyCode::KeyS) if self.is_selecting => {
                             self.show_settings_dialog();
                         }
+                        PhysicalKey::Code(KeyCode::KeyG) if self.is_selecting => {
+                            self.cycle_grid_style();
+                        }
+                        // Draw mode shortcuts (overlay has focus while drawing)
+                        PhysicalKey::Code(code) if self.is_drawing_mode => {
+                            self.handle_draw_mode_key(code);
+                        }
+                        PhysicalKey::Code(code) if self.is_redacting => {
+                            self.handle_redact_mode_key(code);
+                        }
+                        PhysicalKey::Code(code) if self.is_editing_layout => {
+                            self.handle_layout_editor_key(code);
+                        }
                         _ => {}
                     }
                 }
             }
 
+            WindowEvent::Touch(touch) => {
+                self.handle_touch(window_id, touch);
+            }
+
+            // Mouse input promoted from touch / pen is ignored while a contact
+            // is down - the Touch events above already handle it
+            WindowEvent::MouseInput { .. } | WindowEvent::CursorMoved { .. }
+                if self.touch.is_active() => {}
+
+            // Control toolbar: click a button, or drag the toolbar
+            WindowEvent::MouseInput {
+                state,
+                button: winit::event::MouseButton::Left,
+                ..
+            } if self.is_toolbar(window_id) => {
+                let scale = self.toolbar_scale();
+                if state == winit::event::ElementState::Pressed {
+                    self.toolbar.press(scale);
+                } else if let Some(button) = self.toolbar.release(scale) {
+                    self.handle_toolbar(button);
+                }
+                self.update_toolbar();
+            }
+            WindowEvent::CursorMoved { position, .. } if self.is_toolbar(window_id) => {
+                let scale = self.toolbar_scale();
+                self.toolbar
+                    .move_cursor(position.x, position.y, utils::get_cursor_pos(), scale);
+                self.update_toolbar();
+            }
+            WindowEvent::CursorLeft { .. } if self.is_toolbar(window_id) => {
+                self.toolbar.leave();
+                self.update_toolbar();
+            }
+
             WindowEvent::MouseInput { state, button, .. } => {
+                // Draw mode: left button draws annotation shapes
+                if self.is_drawing_mode {
+                    if let Some(overlay) = &self.overlay_window {
+                        if overlay.window_id() == window_id {
+                            use winit::event::{ElementState, MouseButton};
+
+                            let (x, y) = self.overlay_to_frame(self.overlay_cursor_pos);
+                            match (button, state) {
+                                (MouseButton::Left, ElementState::Pressed) => {
+                                    self.annotations.begin_stroke(x, y, None);
+                                }
+                                (MouseButton::Left, ElementState::Released) => {
+                                    self.annotations.end_stroke();
+                                }
+                                _ => {}
+                            }
+                            self.refresh_annotation_overlay();
+                        }
+                    }
+                }
+
+                // Measure mode: left button drag measures distances and sizes
+                if self.is_measuring {
+                    if let Some(overlay) = &self.overlay_window {
+                        if overlay.window_id() == window_id {
+                            use winit::event::{ElementState, MouseButton};
+
+                            let (x, y) = self.overlay_to_frame(self.overlay_cursor_pos);
+                            match (button, state) {
+                                (MouseButton::Left, ElementState::Pressed) => {
+                                    self.measurement.begin(x, y);
+                                }
+                                (MouseButton::Left, ElementState::Released) => {
+                                    self.measurement.end();
+                                    if let Some(readout) = self.measurement.readout() {
+                                        info!("Measured: {}", readout);
+                                    }
+                                }
+                                _ => {}
+                            }
+                            overlay.draw_measurement(self.annotation_offset(), &self.measurement);
+                        }
+                    }
+                }
+
+                // Color picker mode: a left click copies the color under the cursor
+                if self.is_picking_color {
+                    use winit::event::{ElementState, MouseButton};
+
+                    let on_overlay = self
+                        .overlay_window
+                        .as_ref()
+                        .is_some_and(|o| o.window_id() == window_id);
+                    if on_overlay && (button, state) == (MouseButton::Left, ElementState::Pressed)
+                    {
+                        self.copy_picked_color();
+                    }
+                }
+
+                // Redact mode: left drag creates/moves regions (or paints with the
+                // censor brush / eraser), right click deletes
+                if self.is_redacting {
+                    if let Some(overlay) = &self.overlay_window {
+                        if overlay.window_id() == window_id {
+                            use winit::event::{ElementState, MouseButton};
+
+                            let (x, y) = self.overlay_to_frame(self.overlay_cursor_pos);
+                            match (button, state) {
+                                (MouseButton::Left, ElementState::Pressed) => {
+                                    self.privacy.begin_drag(x, y);
+                                }
+                                (MouseButton::Left, ElementState::Released) => {
+                                    self.privacy.end_drag();
+                                }
+                                (MouseButton::Right, ElementState::Pressed) => {
+                                    self.privacy.remove_at(x, y);
+                                }
+                                _ => {}
+                            }
+                            overlay.draw_privacy_regions(self.annotation_offset(), &self.privacy);
+                        }
+                    }
+                }
+
+                // Layout editor: left drag moves, scales or crops a source
+                if self.is_editing_layout {
+                    if let Some(overlay) = &self.overlay_window {
+                        if overlay.window_id() == window_id {
+                            use winit::event::{ElementState, MouseButton};
+
+                            let (x, y) = self.overlay_to_frame(self.overlay_cursor_pos);
+                            match (button, state) {
+                                (MouseButton::Left, ElementState::Pressed) => {
+                                    self.layout_editor.begin_drag(x, y);
+                                }
+                                (MouseButton::Left, ElementState::Released) => {
+                                    self.layout_editor.end_drag();
+                                }
+                                _ => {}
+                            }
+                            self.apply_edited_layout();
+                        }
+                    }
+                }
+
                 // Handle mouse clicks for dragging the overlay window
                 if self.is_selecting {
                     if let Some(overlay) = &self.overlay_window {
@@ -608,6 +2764,41 @@ impl ApplicationHandler for RustFrameApp {
             }
 
             WindowEvent::CursorMoved { position, .. } => {
+                // Track cursor position over the overlay (used by draw mode)
+                if let Some(overlay) = &self.overlay_window {
+                    if overlay.window_id() == window_id {
+                        self.overlay_cursor_pos = (position.x, position.y);
+
+                        if self.is_drawing_mode && self.annotations.is_drawing() {
+                            let (x, y) = self.overlay_to_frame(self.overlay_cursor_pos);
+                            self.annotations.extend_stroke(x, y, None);
+                            self.refresh_annotation_overlay();
+                        }
+
+                        if self.is_measuring && self.measurement.is_dragging() {
+                            let (x, y) = self.overlay_to_frame(self.overlay_cursor_pos);
+                            self.measurement.update(x, y);
+                            overlay.draw_measurement(self.annotation_offset(), &self.measurement);
+                        }
+
+                        if self.is_redacting && self.privacy.is_dragging() {
+                            let (x, y) = self.overlay_to_frame(self.overlay_cursor_pos);
+                            self.privacy.update_drag(x, y);
+                            overlay.draw_privacy_regions(self.annotation_offset(), &self.privacy);
+                        }
+
+                        if self.is_editing_layout && self.layout_editor.is_dragging() {
+                            let (x, y) = self.overlay_to_frame(self.overlay_cursor_pos);
+                            self.layout_editor.update_drag(x, y);
+                            self.apply_edited_layout();
+                        }
+
+                        if self.is_picking_color {
+                            self.sample_color_at_cursor();
+                        }
+                    }
+                }
+
                 // Handle mouse movement for dragging
                 if self.is_selecting && self.is_dragging {
                     if let Some(overlay) = &mut self.overlay_window {
@@ -631,20 +2822,22 @@ impl ApplicationHandler for RustFrameApp {
 impl RustFrameApp {
     /// Transition from "selection mode" to "capture mode"
     fn start_capture(&mut self) {
+        self.apply_profiles();
+
         if let Some(overlay) = &self.overlay_window {
             let overlay_position = overlay.get_outer_position();
             let full_size = overlay.get_inner_size();
 
             // Calculate capture rect - if border is shown, capture INSIDE the border
-            let (rect, inner_size) = if self.settings.show_border {
-                let r = overlay.get_capture_rect_inner(self.settings.border_width);
+            let (rect, inner_size) = if self.settings.capture.show_border {
+                let r = overlay.get_capture_rect_inner(self.settings.capture.border_width);
                 let s = PhysicalSize::new(
                     full_size
                         .width
-                        .saturating_sub(self.settings.border_width * 2),
+                        .saturating_sub(self.settings.capture.border_width * 2),
                     full_size
                         .height
-                        .saturating_sub(self.settings.border_width * 2),
+                        .saturating_sub(self.settings.capture.border_width * 2),
                 );
                 (r, s)
             } else {
@@ -654,8 +2847,8 @@ impl RustFrameApp {
             info!("Starting capture for region: {:?}", rect);
 
             // Convert overlay to hollow frame (click-through interior)
-            if self.settings.show_border {
-                overlay.make_hollow_frame(self.settings.border_width);
+            if self.settings.capture.show_border {
+                overlay.make_hollow_frame(self.settings.capture.border_width);
             } else {
                 overlay.hide();
             }
@@ -668,7 +2861,7 @@ impl RustFrameApp {
                 // Position based on mode:
                 // - exclude_from_capture=true (prod mode): off-screen, user doesn't see it
                 // - exclude_from_capture=false (dev mode): beside overlay, both visible
-                if self.settings.exclude_from_capture {
+                if self.settings.capture.exclude_from_capture {
                     dest.position_offscreen(inner_size);
                 } else {
                     dest.position_beside_overlay(overlay_position, inner_size);
@@ -678,38 +2871,167 @@ impl RustFrameApp {
             // Initialize Windows.Graphics.Capture engine with settings
             // Pass overlay position for multi-monitor detection
             let overlay_pos = (overlay_position.x, overlay_position.y);
-            match CaptureEngine::new(rect, &self.settings, overlay_pos) {
+            let engine = match CaptureEngine::new(rect, &self.settings.capture, overlay_pos) {
                 Ok(engine) => {
                     info!("Capture engine initialized");
-                    self.capture_engine = Some(engine);
-                    self.is_selecting = false;
-
-                    // Initialize renderer for destination window
-                    if let Some(dest) = &self.destination_window {
-                        match Renderer::new(dest.get_window()) {
-                            Ok(renderer) => {
-                                info!("Renderer initialized");
-                                self.renderer = Some(renderer);
-                            }
-                            Err(e) => {
-                                error!("Failed to initialize renderer: {}", e);
-                            }
-                        }
-                    }
+                    wake_on_frames(&self.waker, &engine);
+                    Some(engine)
+                }
+                Err(e) if self.test_pattern.is_some() => {
+                    // The test pattern does not need WGC (e.g. in a VM without it)
+                    warn!("Screen capture unavailable ({}), showing the test pattern only", e);
+                    None
                 }
                 Err(e) => {
                     error!("Failed to initialize capture engine: {}", e);
+                    self.send_webhook(
+                        WebhookEvent::Error,
+                        &format!("Failed to initialize capture engine: {}", e),
+                    );
+                    return;
+                }
+            };
+            self.capture_engine = engine;
+            self.is_selecting = false;
+            self.markers.start_session();
+            self.capture_started = Some(Instant::now());
+            self.toolbar.reset();
+            self.black_warned = false;
+            self.stream_dropped = false;
+            self.send_webhook(WebhookEvent::CaptureStart, "Capture started");
+            self.update_input_recording();
+            self.update_mouse_hook();
+
+            // Initialize renderer for destination window
+            if let Some(dest) = &self.destination_window {
+                let gpu = self.capture_engine.as_ref().and_then(|c| c.gpu());
+                match Renderer::new(dest.get_window(), gpu.as_ref()) {
+                    Ok(mut renderer) => {
+                        info!("Renderer initialized");
+                        // Freeze may have been toggled before capture started
+                        renderer.set_frozen(self.is_output_frozen);
+                        renderer.set_chroma_key(&self.chroma_key);
+                        renderer.set_enhance(&self.enhance);
+                        renderer.set_composite_layout(self.composite_layout);
+                        self.renderer = Some(renderer);
+                    }
+                    Err(e) => {
+                        error!("Failed to initialize renderer: {}", e);
+                        self.send_webhook(
+                            WebhookEvent::Error,
+                            &format!("Failed to initialize renderer: {}", e),
+                        );
+                    }
                 }
             }
         }
+
+        // Open the webcam and the image if their modes are selected
+        self.update_webcam();
+        self.update_image_source();
     }
     
+    /// Apply the profiles matching the monitor and the application under the
+    /// selected region (see profile.rs)
+    fn apply_profiles(&mut self) {
+        if self.settings.profiles.is_empty() {
+            return;
+        }
+        let Some(overlay) = &self.overlay_window else {
+            return;
+        };
+        let context = profile::Context::of_region(overlay.get_capture_rect());
+        let applied = profile::apply(&mut self.settings, &context);
+        if applied.is_empty() {
+            return;
+        }
+        info!(
+            "Applied profiles {:?} (monitor {:?}, application {:?})",
+            applied,
+            context.monitor.as_ref().map(|m| m.name.as_str()),
+            context.app
+        );
+        if let Some(menu) = &self.menu_cursor {
+            menu.set_checked(self.settings.capture.show_cursor);
+        }
+        if let Some(menu) = &self.menu_border {
+            menu.set_checked(self.settings.capture.show_border);
+        }
+        self.filters.set_order(&self.settings.filters);
+        self.update_overlay_title();
+    }
+
     /// Stop capture and return to selection/idle mode
     fn stop_capture(&mut self) {
         info!("Stopping capture, returning to selection mode");
+        self.markers.end_session();
+        if self.capture_started.is_some() {
+            self.send_webhook(WebhookEvent::CaptureStop, "Capture stopped");
+        }
+        self.capture_started = None;
+        self.update_input_recording();
+        self.capture_duration = None;
+        self.mouse_hook = None;
+        self.cursor_held = false;
+        // The selection overlay is shown again below
+        self.hidden_to_tray = None;
+
+        // Leave draw mode and discard this session's annotations
+        if self.is_drawing_mode {
+            self.set_drawing_mode(false);
+        }
+        self.annotations.clear();
+        self.update_toolbar();
+        self.stop_pan();
+        self.stop_frame_diff();
+
+        // Leave measure and color picker mode as well (nothing is kept)
+        if self.is_measuring {
+            self.set_measure_mode(false);
+        }
+        if self.is_picking_color {
+            self.set_color_picker_mode(false);
+        }
+
+        // Privacy regions are kept for the next capture - only editing stops
+        if self.is_redacting {
+            self.set_redact_mode(false);
+        }
+        if self.is_editing_layout {
+            self.set_layout_editor(false);
+        }
+        self.tracked_window = None;
+
+        // Never start the next capture paused or frozen
+        if self.pause_screen.is_paused() {
+            self.set_output_paused(false);
+        }
+        if self.is_output_frozen {
+            self.set_output_frozen(false);
+        }
+        self.frame_hold.reset();
+        self.elevation.reset();
+
+        // The local magnifier only makes sense over an active capture
+        if let Some(lens) = &self.magnifier_popup {
+            lens.hide();
+        }
         
         // Drop the capture engine to stop capturing
         self.capture_engine = None;
+
+        // Additional regions only live for one capture session
+        self.close_second_output();
+        self.region_captures.clear();
+        self.region_overlays.clear();
+        if let Some(menu) = &self.menu_remove_region {
+            menu.set_enabled(false);
+        }
+
+        // Close the webcam and the image (reopened by the next capture while
+        // their modes are on)
+        self.webcam = None;
+        self.image_source = None;
         
         // Drop the renderer
         self.renderer = None;
@@ -722,6 +3044,7 @@ impl RustFrameApp {
             // Restore to full selection overlay (not hollow frame)
             overlay.restore_selection_mode();
             overlay.show();
+            overlay.focus();
         }
         
         // Hide destination window
@@ -735,160 +3058,3372 @@ impl RustFrameApp {
         info!("Capture stopped, ready for new selection");
     }
 
-    /// Update overlay title and visual display to show current settings
-    fn update_overlay_title(&self) {
-        if let Some(overlay) = &self.overlay_window {
-            let cursor = if self.settings.show_cursor {
-                "ON"
-            } else {
-                "OFF"
-            };
-            let border = if self.settings.show_border {
-                "ON"
-            } else {
-                "OFF"
-            };
-            // E = Production mode: destination window behind overlay (single window view)
-            // OFF = Dev mode: two windows side by side
-            // ON = Prod mode: destination hidden behind overlay
-            let mode = if self.settings.exclude_from_capture {
-                "PROD(single)"
-            } else {
-                "DEV(side-by-side)"
-            };
-
-            let title = format!(
-                "RustFrame | [C]ursor:{} [B]order:{} [E]mode:{} [S]ettings | ENTER=Start ESC=Exit",
-                cursor, border, mode
+    /// Blend from the frame currently shown to the next output (see transition.rs)
+    fn start_transition(&mut self) {
+        // With Windows animation effects off the output switches without one
+        let duration_ms = if accessibility::reduce_motion() {
+            0
+        } else {
+            self.settings.transition_ms
+        };
+        let kind = self.settings.transition_kind;
+        let color = theme::parse_accent(&self.settings.transition_color).unwrap_or_else(|| {
+            warn!(
+                "Invalid transition color '{}' - fading to black",
+                self.settings.transition_color
             );
-            overlay.set_title(&title);
-            
-            // Update the visual display inside the overlay to reflect settings changes
-            if self.is_selecting {
-                if let Err(e) = overlay.update_settings_display(
-                    self.settings.show_cursor,
-                    self.settings.show_border,
-                    self.settings.exclude_from_capture
-                ) {
-                    error!("Failed to update overlay settings display: {}", e);
-                }
-            }
+            0x000000
+        });
+        let renderers = [&mut self.renderer, &mut self.second_renderer];
+        for renderer in renderers.into_iter().flatten() {
+            renderer.start_transition(kind, duration_ms, color);
         }
     }
 
-    /// Show the settings dialog and apply changes
-    fn show_settings_dialog(&mut self) {
-        info!("Opening settings dialog...");
+    /// Show the transition kind in its tray item
+    fn refresh_transition_menu(&self) {
+        if let Some(menu) = &self.menu_transition {
+            let kind = self.settings.transition_kind;
+            menu.set_text(format!("Transition: {}", kind.label()));
+        }
+    }
 
-        if let Some(new_settings) =
-            settings_dialog::show_settings_dialog(&self.settings, self.dev_mode)
-        {
-            info!("Settings changed, applying...");
+    /// Pause/resume the output (paused output shows the pause screen)
+    fn set_output_paused(&mut self, paused: bool) {
+        self.start_transition();
+        self.pause_screen.set_paused(paused);
+        if let Some(menu) = &self.menu_pause {
+            menu.set_checked(paused);
+        }
+        info!(
+            "Output {} ({})",
+            if paused { "paused" } else { "resumed" },
+            self.pause_screen.style().label()
+        );
+    }
 
-            // Update cursor menu checkbox
-            if let Some(menu) = &self.menu_cursor {
-                menu.set_checked(new_settings.show_cursor);
-            }
+    /// Push the chroma key settings to the renderer and refresh the tray menu
+    fn apply_chroma_key(&mut self) {
+        if let Some(renderer) = &mut self.renderer {
+            renderer.set_chroma_key(&self.chroma_key);
+        }
+        if let Some(menu) = &self.menu_chroma_enabled {
+            menu.set_checked(self.chroma_key.is_enabled());
+        }
+        if let Some(menu) = &self.menu_chroma_color {
+            menu.set_text(format!("Key Color: {}", self.chroma_key.color().label()));
+        }
+        if let Some(menu) = &self.menu_chroma_similarity {
+            menu.set_text(format!("Similarity: {}%", self.chroma_key.similarity_percent()));
+        }
+        if let Some(menu) = &self.menu_chroma_smoothness {
+            menu.set_text(format!("Smoothness: {}%", self.chroma_key.smoothness_percent()));
+        }
+        info!(
+            "Chroma key: {} ({}, similarity {}%, smoothness {}%)",
+            if self.chroma_key.is_enabled() { "on" } else { "off" },
+            self.chroma_key.color().label(),
+            self.chroma_key.similarity_percent(),
+            self.chroma_key.smoothness_percent()
+        );
+    }
 
-            // Update border menu checkbox
-            if let Some(menu) = &self.menu_border {
-                menu.set_checked(new_settings.show_border);
+    /// Push the sharpen / denoise flags of both outputs to their renderers and
+    /// refresh the tray menu
+    fn apply_enhance(&mut self) {
+        if let Some(renderer) = &mut self.renderer {
+            renderer.set_enhance(&self.enhance);
+        }
+        if let Some(renderer) = &mut self.second_renderer {
+            renderer.set_enhance(&self.second_enhance);
+        }
+        let checks = [
+            (&self.menu_sharpen, self.enhance.sharpen),
+            (&self.menu_denoise, self.enhance.denoise),
+            (&self.menu_second_sharpen, self.second_enhance.sharpen),
+            (&self.menu_second_denoise, self.second_enhance.denoise),
+        ];
+        for (menu, checked) in checks {
+            if let Some(menu) = menu {
+                menu.set_checked(checked);
             }
+        }
+        info!(
+            "Output enhance: sharpen={}, denoise={}, second output sharpen={}, denoise={}",
+            self.enhance.sharpen,
+            self.enhance.denoise,
+            self.second_enhance.sharpen,
+            self.second_enhance.denoise
+        );
+    }
+
+    /// Add another capture region with its own border and capture session
+    /// Its frames are composited next to the main region (see composite.rs)
+    fn add_capture_region(&mut self, event_loop: &ActiveEventLoop) {
+        if self.is_selecting {
+            info!("Capture regions can only be added during capture");
+            return;
+        }
+        if self.region_overlays.len() >= constants::composite::MAX_EXTRA_REGIONS {
+            info!(
+                "At most {} additional regions are supported",
+                constants::composite::MAX_EXTRA_REGIONS
+            );
+            return;
+        }
 
-            // Update exclude/production mode menu checkbox
-            if let Some(menu) = &self.menu_exclude {
-                menu.set_checked(new_settings.exclude_from_capture);
+        // STEP 1: Create the region frame, offset from the last region
+        let Some(last) = self.region_overlays.last().or(self.overlay_window.as_ref()) else {
+            return;
+        };
+        let position = last.get_outer_position();
+        let size = last.get_inner_size();
+        let overlay = match OverlayWindow::new(event_loop) {
+            Ok(overlay) => overlay,
+            Err(e) => {
+                error!("Failed to create capture region window: {}", e);
+                return;
             }
+        };
+        let offset = constants::composite::NEW_REGION_OFFSET;
+        overlay.set_bounds(
+            (position.x + offset, position.y + offset),
+            (size.width, size.height),
+        );
+        overlay.make_hollow_frame(self.settings.capture.border_width);
 
-            // Store the old settings to detect changes
-            let cursor_changed = self.settings.show_cursor != new_settings.show_cursor;
-            let border_changed = self.settings.show_border != new_settings.show_border;
-            let mode_changed =
-                self.settings.exclude_from_capture != new_settings.exclude_from_capture;
-            let border_width_changed = self.settings.border_width != new_settings.border_width;
+        // STEP 2: Start a capture session for it (the region may be on another monitor)
+        let rect = overlay.get_capture_rect_inner(self.settings.capture.border_width);
+        let position = overlay.get_outer_position();
+        match CaptureEngine::new(rect, &self.settings.capture, (position.x, position.y)) {
+            Ok(engine) => {
+                info!("Added capture region {:?}", rect);
+                wake_on_frames(&self.waker, &engine);
+                self.region_overlays.push(overlay);
+                self.region_captures.push(engine);
+            }
+            Err(e) => {
+                error!("Failed to start capture for the new region: {}", e);
+                return;
+            }
+        }
 
-            // Apply the new settings
-            self.settings = new_settings;
+        if let Some(menu) = &self.menu_remove_region {
+            menu.set_enabled(true);
+        }
+        self.update_second_output(event_loop);
+        self.resize_output();
+    }
 
-            // Update overlay title
-            self.update_overlay_title();
+    /// Remove the most recently added capture region
+    fn remove_capture_region(&mut self) {
+        if self.region_overlays.pop().is_some() {
+            self.region_captures.pop();
+            info!(
+                "Removed capture region ({} additional left)",
+                self.region_overlays.len()
+            );
+        }
+        if let Some(menu) = &self.menu_remove_region {
+            menu.set_enabled(!self.region_overlays.is_empty());
+        }
+        if self.region_captures.is_empty() {
+            self.close_second_output();
+        }
+        self.resize_output();
+    }
 
-            // If capture is active, apply runtime changes
-            if !self.is_selecting {
-                // Handle cursor visibility change
-                if cursor_changed {
-                    if let Some(capture) = &self.capture_engine {
-                        if let Err(e) = capture.update_cursor_visibility(self.settings.show_cursor)
-                        {
-                            error!("Failed to update cursor visibility: {}", e);
-                        }
-                    }
-                }
+    /// Show the additional regions in their own destination window (a second,
+    /// independent share) or composited into the main output
+    fn set_regions_separate(&mut self, event_loop: &ActiveEventLoop, separate: bool) {
+        self.regions_separate = separate;
+        if let Some(menu) = &self.menu_regions_separate {
+            menu.set_checked(separate);
+        }
+        info!(
+            "Additional regions shown {}",
+            if separate {
+                "in a separate window"
+            } else {
+                "in the main output"
+            }
+        );
+        self.update_second_output(event_loop);
+        self.resize_output();
+    }
 
-                // Handle border visibility change
-                if border_changed {
-                    if let Some(overlay) = &self.overlay_window {
-                        if self.settings.show_border {
-                            overlay.make_hollow_frame(self.settings.border_width);
-                            overlay.show();
-                        } else {
-                            overlay.hide();
-                        }
+    /// Open the second output if the additional regions should get their own
+    /// window, close it otherwise
+    fn update_second_output(&mut self, event_loop: &ActiveEventLoop) {
+        if !self.regions_separate || self.is_selecting || self.region_overlays.is_empty() {
+            self.close_second_output();
+            return;
+        }
+        if self.second_renderer.is_some() {
+            return;
+        }
+
+        // STEP 1: Create the window once (a normal window, so it can be shared or watched)
+        if self.second_destination.is_none() {
+            match DestinationWindow::new(event_loop, true) {
+                Ok(dest) => {
+                    dest.set_title("RustFrame - Second Output");
+                    dest.set_dark(theme::palette().dark);
+                    self.second_destination = Some(dest);
+                }
+                Err(e) => {
+                    error!("Failed to create second output window: {}", e);
+                    return;
+                }
+            }
+        }
+
+        // STEP 2: Show it beside the first additional region and start rendering
+        if let Some(dest) = &self.second_destination {
+            let first = &self.region_overlays[0];
+            let rect = first.get_capture_rect_inner(self.settings.capture.border_width);
+            dest.position_beside_overlay(
+                first.get_outer_position(),
+                PhysicalSize::new(rect.width, rect.height),
+            );
+            let gpu = self.region_captures.first().and_then(|c| c.gpu());
+            match Renderer::new(dest.get_window(), gpu.as_ref()) {
+                Ok(mut renderer) => {
+                    info!("Second output renderer initialized");
+                    renderer.set_enhance(&self.second_enhance);
+                    renderer.set_composite_layout(self.composite_layout);
+                    self.second_renderer = Some(renderer);
+                }
+                Err(e) => error!("Failed to initialize second output renderer: {}", e),
+            }
+        }
+    }
+
+    /// Stop rendering the second output and hide its window
+    fn close_second_output(&mut self) {
+        if self.second_renderer.take().is_some() {
+            info!("Second output closed");
+        }
+        if let Some(dest) = &self.second_destination {
+            dest.hide();
+        }
+    }
+
+    /// Push the region layout to the renderer, the tray menu and the output size
+    fn apply_composite_layout(&mut self) {
+        if let Some(renderer) = &mut self.renderer {
+            renderer.set_composite_layout(self.composite_layout);
+        }
+        if let Some(renderer) = &mut self.second_renderer {
+            renderer.set_composite_layout(self.composite_layout);
+        }
+        if let Some(menu) = &self.menu_region_layout {
+            menu.set_text(format!("Layout: {}", self.composite_layout.label()));
+        }
+        info!("Region layout: {}", self.composite_layout.label());
+        self.resize_output();
+    }
+
+    /// Arrange all current sources with a layout template: the additional
+    /// regions come back into the main output and a fullscreen webcam / image
+    /// is placed beside the screen, so every source ends up in the template
+    fn apply_layout_template(&mut self, event_loop: &ActiveEventLoop, layout: CompositeLayout) {
+        info!("Applying layout template: {}", layout.label());
+        self.start_transition();
+        if self.regions_separate {
+            self.set_regions_separate(event_loop, false);
+        }
+        if self.webcam_mode == Placement::Fullscreen {
+            self.set_webcam_mode(Placement::Beside);
+        }
+        if self.image_mode == Placement::Fullscreen {
+            self.set_image_mode(Placement::Beside);
+        }
+        self.composite_layout = layout;
+        self.apply_composite_layout();
+    }
+
+    /// Index of the additional capture region with the given window
+    fn region_index(&self, window_id: WindowId) -> Option<usize> {
+        self.region_overlays
+            .iter()
+            .position(|o| o.window_id() == window_id)
+    }
+
+    /// Size the destination window(s) for the composite of the capture regions
+    fn resize_output(&mut self) {
+        if self.overlay_window.is_none() || self.is_selecting {
+            return;
+        }
+        let sources = self.output_sources();
+        let rects = composite::custom_rects(&self.custom_layout, &sources);
+        let sizes: Vec<(u32, u32)> = sources.iter().map(|&(_, size)| size).collect();
+        let ((width, height), _) = self.composite_layout.arrange(&sizes, &rects);
+        if let Some(dest) = &self.destination_window {
+            dest.resize(PhysicalSize::new(width, height));
+        }
+        // The editor follows sources that come and go and scene switches
+        if self.is_editing_layout
+            && !self.layout_editor.is_dragging()
+            && self.layout_editor.rects() != rects.as_slice()
+        {
+            self.layout_editor.load(rects.clone());
+            if let Some(overlay) = &self.overlay_window {
+                overlay.draw_layout_editor(self.annotation_offset(), &self.layout_editor);
+            }
+        }
+        if let Some(renderer) = &mut self.renderer {
+            renderer.set_custom_layout(rects);
+            renderer.set_source_sizes(sizes);
+            renderer.resize(width, height);
+        }
+
+        // The custom layout is for the main output - a custom layout places
+        // the regions in the second output side by side
+        let extra = self.region_sizes();
+        if self.regions_separate && !extra.is_empty() {
+            let ((width, height), _) = self.composite_layout.arrange(&extra, &[]);
+            if let Some(dest) = &self.second_destination {
+                dest.resize(PhysicalSize::new(width, height));
+            }
+            if let Some(renderer) = &mut self.second_renderer {
+                renderer.set_source_sizes(extra);
+                renderer.resize(width, height);
+            }
+        }
+    }
+
+    /// Frame sizes of the additional capture regions
+    fn region_sizes(&self) -> Vec<(u32, u32)> {
+        let border = self.settings.capture.border_width;
+        self.region_overlays
+            .iter()
+            .map(|o| {
+                let rect = o.get_capture_rect_inner(border);
+                (rect.width, rect.height)
+            })
+            .collect()
+    }
+
+    /// The sources of the main output in composite order, with their frame sizes
+    fn output_sources(&self) -> Vec<(SourceId, (u32, u32))> {
+        let Some(overlay) = &self.overlay_window else {
+            return Vec::new();
+        };
+        let main = if self.settings.capture.show_border {
+            overlay.get_capture_rect_inner(self.settings.capture.border_width)
+        } else {
+            overlay.get_capture_rect()
+        };
+
+        // The main output holds all regions, or only the main one when the
+        // additional regions have their own window, plus the webcam and the
+        // image (which replace the main region in fullscreen mode)
+        let mut sources = Vec::new();
+        if self.screen_visible() {
+            sources.push((SourceId::Screen, (main.width, main.height)));
+        }
+        if !self.regions_separate {
+            let regions = self.region_sizes().into_iter().enumerate();
+            sources.extend(regions.map(|(i, size)| (SourceId::Region(i), size)));
+        }
+        if let Some(webcam) = &self.webcam {
+            sources.push((SourceId::Webcam, webcam.size()));
+        }
+        if self.image_source.is_some() {
+            sources.push((SourceId::Image, (main.width, main.height)));
+        }
+        sources
+    }
+
+    /// Capture region behind the main frame and each additional source of the
+    /// main output, in render order (see renderer::Redaction)
+    fn main_output_regions(&self) -> Vec<Option<usize>> {
+        let mut regions = vec![self.test_pattern.is_none().then_some(0)];
+        if !self.regions_separate {
+            regions.extend((1..=self.region_captures.len()).map(Some));
+        }
+        regions.extend(self.webcam.iter().map(|_| None));
+        regions.extend(self.image_source.iter().map(|_| None));
+        regions
+    }
+
+    /// Show a test pattern instead of the screen capture (None = screen again)
+    fn set_test_pattern(&mut self, kind: Option<PatternKind>) {
+        self.start_transition();
+        self.test_pattern = kind.map(TestPattern::new);
+        if let Some(menu) = &self.menu_test_pattern {
+            menu.set_text(format!(
+                "Test Pattern: {}",
+                pattern_label(self.test_pattern.as_ref())
+            ));
+        }
+        info!("Test pattern: {}", pattern_label(self.test_pattern.as_ref()));
+        if kind.is_none() && self.capture_engine.is_none() && !self.is_selecting {
+            warn!("Screen capture is not running - restart the capture to show the screen");
+        }
+    }
+
+    /// Whether the main capture region is shown (a fullscreen webcam or image
+    /// replaces it)
+    fn screen_visible(&self) -> bool {
+        let webcam = self.webcam_mode == Placement::Fullscreen && self.webcam.is_some();
+        let image = self.image_mode == Placement::Fullscreen && self.image_source.is_some();
+        !webcam && !image
+    }
+
+    /// Change where the webcam is shown (opens / closes the camera as needed)
+    fn set_webcam_mode(&mut self, mode: Placement) {
+        self.webcam_mode = mode;
+        if let Some(menu) = &self.menu_webcam_mode {
+            menu.set_text(format!("Mode: {}", mode.label()));
+        }
+        info!("Webcam mode: {}", mode.label());
+        self.update_webcam();
+    }
+
+    /// Switch to the next video capture device
+    fn next_webcam_device(&mut self) {
+        let devices = webcam::list_devices();
+        if devices.is_empty() {
+            info!("No webcam found");
+            return;
+        }
+        self.webcam_device = (self.webcam_device + 1) % devices.len();
+        if let Some(menu) = &self.menu_webcam_device {
+            menu.set_text(format!("Camera: {}", devices[self.webcam_device]));
+        }
+        info!("Webcam device: {}", devices[self.webcam_device]);
+
+        // Reopen with the new device if the webcam is in use
+        if self.webcam.take().is_some() {
+            self.update_webcam();
+        }
+    }
+
+    /// Open the webcam while capturing with a webcam mode, close it otherwise
+    fn update_webcam(&mut self) {
+        if self.webcam_mode == Placement::Off || self.is_selecting {
+            self.webcam = None;
+        } else if self.webcam.is_none() {
+            match Webcam::open(self.webcam_device) {
+                Ok(webcam) => {
+                    if let Some(menu) = &self.menu_webcam_device {
+                        menu.set_text(format!("Camera: {}", webcam.name()));
+                    }
+                    self.webcam = Some(webcam);
+                }
+                Err(e) => error!("Failed to open webcam: {}", e),
+            }
+        }
+
+        let screen_visible = self.screen_visible();
+        if let Some(renderer) = &mut self.renderer {
+            renderer.set_screen_visible(screen_visible);
+        }
+        self.resize_output();
+    }
+
+    /// Change where the image is shown (loads / drops the image as needed)
+    fn set_image_mode(&mut self, mode: Placement) {
+        self.start_transition();
+        self.image_mode = mode;
+        if let Some(menu) = &self.menu_image_mode {
+            menu.set_text(format!("Mode: {}", mode.label()));
+        }
+        info!("Image mode: {}", mode.label());
+        self.update_image_source();
+    }
+
+    /// Load the image while capturing with an image mode, drop it otherwise
+    fn update_image_source(&mut self) {
+        if self.image_mode == Placement::Off || self.is_selecting {
+            self.image_source = None;
+        } else if self.image_source.is_none() {
+            match ImageSource::open(&self.settings.image_path, self.settings.slide_seconds) {
+                Ok(image) => self.image_source = Some(image),
+                Err(e) => error!("Failed to open image source: {}", e),
+            }
+        }
+
+        let screen_visible = self.screen_visible();
+        if let Some(renderer) = &mut self.renderer {
+            renderer.set_screen_visible(screen_visible);
+        }
+        self.resize_output();
+    }
+
+    /// Store the current region and output setup in a scene slot
+    fn save_scene(&mut self, slot: usize) {
+        let Some(overlay) = &self.overlay_window else {
+            return;
+        };
+        let position = overlay.get_outer_position();
+        let size = overlay.get_inner_size();
+        let scene = Scene {
+            name: format!("Scene {}", slot + 1),
+            position: (position.x, position.y),
+            size: (size.width, size.height),
+            grid_style: self.grid.style(),
+            grid_in_output: self.grid.in_output(),
+            spotlight: self.spotlight.is_enabled(),
+            chroma_key: self.chroma_key.clone(),
+            enhance: self.enhance,
+            second_enhance: self.second_enhance,
+            filters: self.settings.filters.clone(),
+            composite_layout: self.composite_layout,
+            custom_layout: self.custom_layout.clone(),
+            target_fps: self.settings.capture.target_fps,
+            show_cursor: self.settings.capture.show_cursor,
+            show_border: self.settings.capture.show_border,
+            border_width: self.settings.capture.border_width,
+            webcam: self.webcam_mode,
+            image: self.image_mode,
+            privacy_regions: self.privacy.regions().to_vec(),
+        };
+        info!(
+            "Saved {} ({}x{} at {:?})",
+            scene.name, scene.size.0, scene.size.1, scene.position
+        );
+        self.scenes.save(slot, scene);
+        self.settings.scenes = self.scenes.slots().to_vec();
+        if let Err(e) = settings_file::save(&self.settings) {
+            error!("Failed to save settings: {:#}", e);
+        }
+        self.update_scene_menu();
+    }
+
+    /// Switch live to a saved scene (with a transition while capturing)
+    fn switch_scene(&mut self, slot: usize) {
+        let Some(scene) = self.scenes.get(slot).cloned() else {
+            info!("Scene {} is empty - save it first", slot + 1);
+            return;
+        };
+        info!("Switching to {}", scene.name);
+
+        // STEP 1: Transition out of the frame currently shown
+        self.start_transition();
+
+        // STEP 2: Restore the output setup
+        self.grid.set_style(scene.grid_style);
+        self.grid.set_in_output(scene.grid_in_output);
+        if let Some(menu) = &self.menu_grid_style {
+            menu.set_text(format!("Grid: {}", self.grid.style().label()));
+        }
+        if let Some(menu) = &self.menu_grid_output {
+            menu.set_checked(self.grid.in_output());
+        }
+        if let Some(overlay) = &self.overlay_window {
+            if let Err(e) = overlay.set_grid_style(self.grid.style()) {
+                error!("Failed to redraw overlay grid: {}", e);
+            }
+        }
+        self.spotlight.set_enabled(scene.spotlight);
+        self.chroma_key = scene.chroma_key;
+        self.apply_chroma_key();
+        self.enhance = scene.enhance;
+        self.second_enhance = scene.second_enhance;
+        self.apply_enhance();
+        self.filters.set_order(&scene.filters);
+        self.settings.filters = scene.filters;
+        self.composite_layout = scene.composite_layout;
+        self.custom_layout = scene.custom_layout;
+        self.apply_composite_layout();
+        if self.webcam_mode != scene.webcam {
+            self.set_webcam_mode(scene.webcam);
+        }
+        if self.image_mode != scene.image {
+            self.set_image_mode(scene.image);
+        }
+        self.privacy.set_regions(scene.privacy_regions);
+        if self.is_redacting {
+            if let Some(overlay) = &self.overlay_window {
+                overlay.draw_privacy_regions(self.annotation_offset(), &self.privacy);
+            }
+        }
+
+        // STEP 3: Restore the capture settings (frame rate, cursor, border)
+        let cursor_changed = self.settings.capture.show_cursor != scene.show_cursor;
+        let border_changed = self.settings.capture.show_border != scene.show_border;
+        let border_width_changed = self.settings.capture.border_width != scene.border_width;
+        self.settings.capture.target_fps = scene.target_fps;
+        self.settings.capture.show_cursor = scene.show_cursor;
+        self.settings.capture.show_border = scene.show_border;
+        self.settings.capture.border_width = scene.border_width;
+        if let Some(menu) = &self.menu_cursor {
+            menu.set_checked(self.settings.capture.show_cursor);
+        }
+        if let Some(menu) = &self.menu_border {
+            menu.set_checked(self.settings.capture.show_border);
+        }
+        self.update_overlay_title();
+        self.apply_capture_changes(cursor_changed, border_changed, border_width_changed);
+
+        // STEP 4: Move the overlay to the scene's region
+        // (the Moved / Resized events update the capture region and destination)
+        if let Some(overlay) = &self.overlay_window {
+            overlay.set_bounds(scene.position, scene.size);
+        }
+        self.follow_overlay_monitor(scene.position);
+
+        self.scenes.set_active(slot);
+        self.update_scene_menu();
+    }
+
+    /// After the overlay was moved: a region on another monitor needs a
+    /// capture session for that monitor
+    fn follow_overlay_monitor(&mut self, position: (i32, i32)) {
+        let Some(overlay) = &self.overlay_window else {
+            return;
+        };
+        let monitor = overlay.get_monitor_rect().map(|m| (m.x, m.y));
+        if let Some(capture) = &mut self.capture_engine {
+            if monitor.is_some_and(|m| m != capture.get_monitor_origin()) {
+                let rect = if self.settings.capture.show_border {
+                    overlay.get_capture_rect_inner(self.settings.capture.border_width)
+                } else {
+                    overlay.get_capture_rect()
+                };
+                match CaptureEngine::new(rect, &self.settings.capture, position) {
+                    Ok(engine) => {
+                        wake_on_frames(&self.waker, &engine);
+                        *capture = engine
+                    }
+                    Err(e) => error!("Failed to capture the region's monitor: {}", e),
+                }
+            }
+        }
+    }
+
+    /// Move the capture region to `rect` (the border goes around it); a
+    /// running capture follows
+    fn set_capture_region(&mut self, rect: capture::CaptureRect) {
+        let border = if self.settings.capture.show_border {
+            self.settings.capture.border_width
+        } else {
+            0
+        };
+        let position = (rect.x - border as i32, rect.y - border as i32);
+        if let Some(overlay) = &self.overlay_window {
+            info!(
+                "Capture region: {}x{} at {}, {}",
+                rect.width, rect.height, rect.x, rect.y
+            );
+            overlay.set_bounds(position, (rect.width + 2 * border, rect.height + 2 * border));
+        }
+        self.follow_overlay_monitor(position);
+    }
+
+    /// Render the capture and its overlays into the destination window
+    fn render_output(&mut self) {
+        // The test pattern and the image are rendered at the size of the capture region
+        if let Some(overlay) = &self.overlay_window {
+            let rect = if self.settings.capture.show_border {
+                overlay.get_capture_rect_inner(self.settings.capture.border_width)
+            } else {
+                overlay.get_capture_rect()
+            };
+            if let Some(pattern) = &mut self.test_pattern {
+                pattern.set_size(rect.width, rect.height);
+            }
+            if let Some(image) = &mut self.image_source {
+                image.set_size(rect.width, rect.height);
+            }
+        }
+
+        // The test pattern replaces the screen capture as the main source
+        let mut render_error = None;
+        let regions = self.main_output_regions();
+        let main: Option<&mut dyn FrameSource> = match &mut self.test_pattern {
+            Some(pattern) => Some(pattern),
+            None => self.capture_engine.as_mut().map(|c| c as &mut dyn FrameSource),
+        };
+        if let (Some(renderer), Some(main)) = (&mut self.renderer, main) {
+            // Interruptions hold the last good frame just like freeze-frame
+            renderer.set_frozen(self.is_output_frozen || self.frame_hold.is_holding());
+            // Additional regions go into this output unless they have their own
+            // window, the webcam and the image always do
+            let mut sources: Vec<&mut dyn FrameSource> = Vec::new();
+            if !self.regions_separate {
+                sources.extend(
+                    self.region_captures
+                        .iter_mut()
+                        .map(|c| c as &mut dyn FrameSource),
+                );
+            }
+            if let Some(webcam) = &mut self.webcam {
+                sources.push(webcam);
+            }
+            if let Some(image) = &mut self.image_source {
+                sources.push(image);
+            }
+            let overlays = FrameOverlays {
+                filters: &self.filters,
+                annotations: &self.annotations,
+                clicks: &self.click_highlight,
+                spotlight: &self.spotlight,
+                magnifier: &self.magnifier,
+                timer: &self.timer,
+                pause: &self.pause_screen,
+                diff: &self.frame_diff,
+                grid: &self.grid,
+                opacity: &self.overlay_opacity,
+                plugins: &self.plugins,
+            };
+            let redaction = Redaction {
+                filters: &self.filters,
+                window_mask: &self.window_mask,
+                privacy: &self.privacy,
+                regions: &regions,
+            };
+            if let Err(e) = renderer.render(main, &mut sources, &redaction, Some(&overlays)) {
+                error!("Render error: {}", e);
+                render_error = Some(format!("Render error: {}", e));
+            }
+        }
+        if let Some(message) = render_error {
+            self.send_webhook(WebhookEvent::Error, &message);
+        }
+    }
+
+    /// Apply cursor and border changes to a running capture
+    fn apply_capture_changes(
+        &mut self,
+        cursor_changed: bool,
+        border_changed: bool,
+        border_width_changed: bool,
+    ) {
+        if self.is_selecting {
+            return;
+        }
+
+        // Handle cursor visibility change
+        if cursor_changed {
+            if let Some(capture) = &self.capture_engine {
+                let visible = self.settings.capture.show_cursor || self.cursor_held;
+                if let Err(e) = capture.update_cursor_visibility(visible) {
+                    error!("Failed to update cursor visibility: {}", e);
+                }
+            }
+        }
+
+        // Handle border visibility change
+        if border_changed {
+            if let Some(overlay) = &self.overlay_window {
+                if self.settings.capture.show_border {
+                    overlay.make_hollow_frame(self.settings.capture.border_width);
+                    overlay.show();
+                } else {
+                    overlay.hide();
+                }
+            }
+        }
+
+        // Handle border width change
+        if border_width_changed && self.settings.capture.show_border {
+            if let Some(overlay) = &self.overlay_window {
+                overlay.update_hollow_frame(self.settings.capture.border_width);
+            }
+
+            // Update capture region
+            if let (Some(overlay), Some(capture)) = (&self.overlay_window, &mut self.capture_engine)
+            {
+                let rect = overlay.get_capture_rect_inner(self.settings.capture.border_width);
+                if let Err(e) = capture.update_region(rect) {
+                    error!("Failed to update capture region: {}", e);
+                }
+            }
+        }
+
+        // The additional regions always show their border - same width as the main one
+        if border_width_changed && !self.region_overlays.is_empty() {
+            let width = self.settings.capture.border_width;
+            for (overlay, capture) in self.region_overlays.iter().zip(&mut self.region_captures) {
+                overlay.update_hollow_frame(width);
+                if let Err(e) = capture.update_region(overlay.get_capture_rect_inner(width)) {
+                    error!("Failed to update capture region: {}", e);
+                }
+            }
+            self.resize_output();
+        }
+    }
+
+    /// Refresh the scene labels in the tray menu
+    fn update_scene_menu(&self) {
+        for (slot, item) in self.menu_scenes.iter().enumerate() {
+            let active = if self.scenes.active() == Some(slot) {
+                "• "
+            } else {
+                ""
+            };
+            item.set_text(format!("{}{}", active, self.scenes.label(slot)));
+            item.set_enabled(self.scenes.get(slot).is_some());
+        }
+    }
+
+    /// Freeze the output on the current frame, or let it follow the screen again
+    fn set_output_frozen(&mut self, frozen: bool) {
+        self.start_transition();
+        self.is_output_frozen = frozen;
+        if let Some(renderer) = &mut self.renderer {
+            renderer.set_frozen(frozen);
+        }
+        if let Some(menu) = &self.menu_freeze {
+            menu.set_checked(frozen);
+        }
+        info!("Output {}", if frozen { "frozen" } else { "unfrozen" });
+    }
+
+    /// Compare the output with the captured frame as it is now (see frame_diff.rs)
+    /// Setting it again while comparing replaces the reference.
+    fn set_diff_reference(&mut self) {
+        if self.is_selecting {
+            return;
+        }
+        let Some(renderer) = &self.renderer else {
+            return;
+        };
+        let (frame, width, height) = renderer.last_frame();
+        if frame.is_empty() {
+            return;
+        }
+        self.frame_diff.set_reference(frame, width, height);
+        info!(
+            "Comparing frames with a {}x{} reference ({})",
+            width,
+            height,
+            self.frame_diff.view().label()
+        );
+        self.refresh_diff_menu();
+    }
+
+    /// Stop comparing frames and show the captured pixels again
+    fn stop_frame_diff(&mut self) {
+        if self.frame_diff.is_active() {
+            self.frame_diff.clear();
+            info!("Frame comparison stopped");
+            self.refresh_diff_menu();
+        }
+    }
+
+    /// Refresh the labels of tray → Compare Frames
+    fn refresh_diff_menu(&self) {
+        if let Some(menu) = &self.menu_diff_reference {
+            menu.set_text(self.diff_reference_label());
+        }
+        if let Some(menu) = &self.menu_diff_view {
+            menu.set_text(format!("View: {}", self.frame_diff.view().label()));
+        }
+        if let Some(menu) = &self.menu_diff_stop {
+            menu.set_enabled(self.frame_diff.is_active());
+        }
+    }
+
+    fn diff_reference_label(&self) -> &'static str {
+        if self.frame_diff.is_active() {
+            "Set New Reference Frame"
+        } else {
+            "Set Reference Frame"
+        }
+    }
+
+    /// Start the pre-capture countdown (or capture right away when it is disabled)
+    fn begin_countdown(&mut self) {
+        if self.settings.countdown_seconds == 0 {
+            self.start_capture();
+            return;
+        }
+
+        info!("Starting {}s countdown", self.settings.countdown_seconds);
+        self.countdown_started = Some(Instant::now());
+        self.countdown_shown = 0;
+    }
+
+    /// Abort a running countdown and stay in selection mode
+    fn cancel_countdown(&mut self) {
+        self.countdown_started = None;
+        self.capture_duration = None;
+        if let Some(popup) = &self.countdown_popup {
+            popup.hide();
+        }
+    }
+
+    /// Advance the countdown: redraw the number when it changes, start capture at zero
+    fn tick_countdown(&mut self, event_loop: &ActiveEventLoop) {
+        let Some(started) = self.countdown_started else {
+            return;
+        };
+
+        let total = Duration::from_secs(self.settings.countdown_seconds as u64);
+        let elapsed = started.elapsed();
+        if elapsed >= total {
+            info!("Countdown finished");
+            self.cancel_countdown();
+            self.start_capture();
+            return;
+        }
+
+        // Round up so a 3 second countdown shows 3, 2, 1
+        let remaining = (total - elapsed).as_secs() as u32 + 1;
+        if remaining != self.countdown_shown {
+            self.countdown_shown = remaining;
+            self.draw_countdown(remaining);
+        }
+
+        // Wake up again when the next number is due
+        let into_second = Duration::from_nanos((elapsed.as_nanos() % 1_000_000_000) as u64);
+        let next_tick = Instant::now() + (Duration::from_secs(1) - into_second);
+        event_loop.set_control_flow(ControlFlow::WaitUntil(next_tick));
+    }
+
+    /// Show the countdown number over the selected region (or the whole monitor)
+    fn draw_countdown(&self, number: u32) {
+        let (Some(overlay), Some(popup)) = (&self.overlay_window, &self.countdown_popup) else {
+            return;
+        };
+
+        let area = if self.settings.countdown_fullscreen {
+            overlay
+                .get_monitor_rect()
+                .unwrap_or_else(|| overlay.get_capture_rect())
+        } else {
+            overlay.get_capture_rect()
+        };
+
+        popup.update(
+            PhysicalPosition::new(area.x, area.y),
+            PhysicalSize::new(area.width, area.height),
+            |canvas| {
+                canvas.pixels.fill(constants::countdown::BACKGROUND);
+
+                // The number fills half of the area, whatever the monitor's DPI
+                let number = number.to_string();
+                let style = text::TextStyle {
+                    color: constants::countdown::TEXT,
+                    size: canvas.width.min(canvas.height) / 2,
+                    bold: true,
+                };
+                let (width, height) = text::text_size(&number, &style);
+                let x = (canvas.width - width) / 2;
+                let y = (canvas.height - height) / 2;
+                text::draw_text(canvas, x, y, &number, &style);
+            },
+        );
+    }
+
+    fn is_toolbar(&self, window_id: WindowId) -> bool {
+        self.toolbar_popup
+            .as_ref()
+            .is_some_and(|p| p.window_id() == window_id)
+    }
+
+    fn toolbar_scale(&self) -> f32 {
+        self.toolbar_popup.as_ref().map_or(1.0, |p| p.scale_factor())
+    }
+
+    /// Show, move or hide the control toolbar (see toolbar.rs)
+    /// Shown while capturing with settings.toolbar on, unless the windows are
+    /// hidden to the tray; only redrawn when something on it changed
+    fn update_toolbar(&mut self) {
+        let Some(popup) = &self.toolbar_popup else {
+            return;
+        };
+
+        let visible = !self.is_selecting
+            && self.settings.toolbar != ToolbarPlacement::Off
+            && self.hidden_to_tray.is_none();
+        let Some(overlay) = self.overlay_window.as_ref().filter(|_| visible) else {
+            if self.toolbar.hide() {
+                popup.hide();
+            }
+            return;
+        };
+
+        let region = overlay.get_capture_rect();
+        let monitor = overlay.get_monitor_rect().unwrap_or(region);
+        let scale = popup.scale_factor();
+        let view = ToolbarView {
+            elapsed_secs: self.capture_started.map_or(0, |t| t.elapsed().as_secs()),
+            paused: self.pause_screen.is_paused(),
+            annotating: self.is_drawing_mode,
+            muted: self.settings.hide_notifications,
+        };
+        let (x, y) = self
+            .toolbar
+            .position(region, monitor, self.settings.toolbar, scale);
+        if !self.toolbar.needs_redraw((x, y), view, scale) {
+            return;
+        }
+
+        let (width, height) = Toolbar::size(scale);
+        let toolbar = &self.toolbar;
+        popup.update(
+            PhysicalPosition::new(x, y),
+            PhysicalSize::new(width as u32, height as u32),
+            |canvas| toolbar.draw(canvas, &view, scale),
+        );
+    }
+
+    /// A toolbar button was clicked
+    fn handle_toolbar(&mut self, button: ToolbarButton) {
+        info!("Toolbar: {:?}", button);
+        match button {
+            ToolbarButton::Stop => self.confirm(ConfirmAction::StopCapture),
+            ToolbarButton::Pause => self.handle_hotkey(HotkeyAction::TogglePause),
+            ToolbarButton::Screenshot => self.take_screenshot(),
+            ToolbarButton::Annotate => self.handle_hotkey(HotkeyAction::ToggleDrawMode),
+            ToolbarButton::Mute => {
+                self.set_hide_notifications(!self.settings.hide_notifications)
+            }
+        }
+    }
+
+    /// Show, refresh or hide the local magnifier window
+    /// Only redraws when a new frame was presented or the magnifier changed
+    fn update_magnifier_window(&mut self) {
+        let Some(lens) = &self.magnifier_popup else {
+            return;
+        };
+
+        let visible = self.magnifier.is_enabled() && self.magnifier.mode() == MagnifierMode::Local;
+        let (renderer, capture) = match (&self.renderer, &self.capture_engine) {
+            (Some(renderer), Some(capture)) if visible => (renderer, capture),
+            _ => {
+                lens.hide();
+                self.magnifier_shown = (0, 0);
+                return;
+            }
+        };
+
+        let shown = (renderer.frame_count(), self.magnifier.revision());
+        if shown == self.magnifier_shown {
+            return;
+        }
+        self.magnifier_shown = shown;
+
+        let (frame, width, height) = renderer.last_frame();
+        if frame.is_empty() {
+            return;
+        }
+
+        // Keep the lens inside the capture region, then convert to screen coordinates
+        let region = capture.get_capture_region();
+        let (x, y) = self.magnifier.lens_position(width as i32, height as i32);
+        let position = PhysicalPosition::new(region.x + x, region.y + y);
+        let size = constants::magnifier::LENS_SIZE as u32;
+
+        lens.update(position, PhysicalSize::new(size, size), |canvas| {
+            self.magnifier
+                .render_lens(frame, width as i32, height as i32, canvas)
+        });
+    }
+
+    /// Enter or leave annotation draw mode
+    fn set_drawing_mode(&mut self, enabled: bool) {
+        if self.is_drawing_mode == enabled {
+            return;
+        }
+        if enabled {
+            self.leave_interactive_modes();
+        }
+        self.is_drawing_mode = enabled;
+        info!(
+            "Draw mode: {} (tool={}, width={})",
+            enabled,
+            self.annotations.tool().label(),
+            self.annotations.width()
+        );
+
+        if let Some(overlay) = &self.overlay_window {
+            let border = self.annotation_offset();
+            overlay.set_draw_mode(enabled, border);
+
+            if enabled {
+                // Without a border the overlay is hidden during capture - show it for drawing
+                overlay.show();
+                overlay.draw_annotations(border, &self.annotations);
+            } else if !self.settings.capture.show_border {
+                overlay.hide();
+            }
+        }
+    }
+
+    /// Enter or leave ruler measure mode
+    fn set_measure_mode(&mut self, enabled: bool) {
+        if self.is_measuring == enabled {
+            return;
+        }
+        if enabled {
+            self.leave_interactive_modes();
+        }
+        self.is_measuring = enabled;
+        self.measurement.clear();
+        info!("Measure mode: {}", enabled);
+
+        if let Some(overlay) = &self.overlay_window {
+            let border = self.annotation_offset();
+            overlay.set_draw_mode(enabled, border);
+
+            if enabled {
+                // Without a border the overlay is hidden during capture - show it for measuring
+                overlay.show();
+                overlay.draw_measurement(border, &self.measurement);
+            } else if !self.settings.capture.show_border {
+                overlay.hide();
+            }
+        }
+    }
+
+    /// Enter or leave color picker (eyedropper) mode
+    fn set_color_picker_mode(&mut self, enabled: bool) {
+        if self.is_picking_color == enabled {
+            return;
+        }
+        if enabled {
+            self.leave_interactive_modes();
+        }
+        self.is_picking_color = enabled;
+        self.color_picker.clear();
+        info!("Color picker mode: {}", enabled);
+
+        if let Some(overlay) = &self.overlay_window {
+            let border = self.annotation_offset();
+            overlay.set_draw_mode(enabled, border);
+
+            if enabled {
+                // Without a border the overlay is hidden during capture - show it for picking
+                overlay.show();
+                overlay.draw_color_picker(border, &self.color_picker);
+            } else if !self.settings.capture.show_border {
+                overlay.hide();
+            }
+        }
+    }
+
+    /// Read the captured pixel under the cursor and redraw the readout
+    fn sample_color_at_cursor(&mut self) {
+        let (x, y) = self.overlay_to_frame(self.overlay_cursor_pos);
+        if let Some(renderer) = &self.renderer {
+            let (frame, width, height) = renderer.last_frame();
+            self.color_picker
+                .sample(frame, width as i32, height as i32, x, y);
+        }
+        if let Some(overlay) = &self.overlay_window {
+            overlay.draw_color_picker(self.annotation_offset(), &self.color_picker);
+        }
+    }
+
+    /// Copy the picked color to the clipboard as "#RRGGBB"
+    fn copy_picked_color(&mut self) {
+        let Some(color) = self.color_picker.color() else {
+            return;
+        };
+        let hex = color_picker::hex(color);
+        match utils::set_clipboard_text(&hex) {
+            Ok(()) => {
+                info!("Copied color {}", hex);
+                self.color_picker.set_copied();
+                if let Some(overlay) = &self.overlay_window {
+                    overlay.draw_color_picker(self.annotation_offset(), &self.color_picker);
+                }
+            }
+            Err(e) => warn!("Failed to copy the color: {:#}", e),
+        }
+    }
+
+    /// Enter or leave privacy region redact mode
+    fn set_redact_mode(&mut self, enabled: bool) {
+        if self.is_redacting == enabled {
+            return;
+        }
+        if enabled {
+            self.leave_interactive_modes();
+        }
+        self.is_redacting = enabled;
+        info!(
+            "Redact mode: {} ({} privacy regions)",
+            enabled,
+            self.privacy.len()
+        );
+
+        if let Some(overlay) = &self.overlay_window {
+            let border = self.annotation_offset();
+            overlay.set_draw_mode(enabled, border);
+
+            if enabled {
+                // Without a border the overlay is hidden during capture - show it for editing
+                overlay.show();
+                overlay.draw_privacy_regions(border, &self.privacy);
+            } else if !self.settings.capture.show_border {
+                overlay.hide();
+            }
+        }
+    }
+
+    /// Open or close the layout editor (switches to the custom layout)
+    fn set_layout_editor(&mut self, enabled: bool) {
+        if self.is_editing_layout == enabled {
+            return;
+        }
+        if enabled {
+            self.leave_interactive_modes();
+            let sources = self.output_sources();
+            self.layout_editor
+                .load(composite::custom_rects(&self.custom_layout, &sources));
+            if self.composite_layout != CompositeLayout::Custom {
+                self.composite_layout = CompositeLayout::Custom;
+                self.apply_composite_layout();
+            }
+        }
+        self.is_editing_layout = enabled;
+        info!(
+            "Layout editor: {} ({} sources)",
+            enabled,
+            self.layout_editor.rects().len()
+        );
+
+        if let Some(overlay) = &self.overlay_window {
+            let border = self.annotation_offset();
+            overlay.set_draw_mode(enabled, border);
+
+            if enabled {
+                // Without a border the overlay is hidden during capture - show it for editing
+                overlay.show();
+                overlay.draw_layout_editor(border, &self.layout_editor);
+            } else if !self.settings.capture.show_border {
+                overlay.hide();
+            }
+        }
+    }
+
+    /// Take over the rectangles from the layout editor (if they changed) and
+    /// redraw it
+    fn apply_edited_layout(&mut self) {
+        if self.layout_editor.revision() != self.layout_revision {
+            self.layout_revision = self.layout_editor.revision();
+            for rect in self.layout_editor.rects() {
+                match self
+                    .custom_layout
+                    .iter_mut()
+                    .find(|r| r.source == rect.source)
+                {
+                    Some(saved) => *saved = *rect,
+                    None => self.custom_layout.push(*rect),
+                }
+            }
+            self.resize_output();
+        }
+        if let Some(overlay) = &self.overlay_window {
+            overlay.draw_layout_editor(self.annotation_offset(), &self.layout_editor);
+        }
+    }
+
+    /// Handle key presses in the layout editor (z-order, selection, nudging)
+    fn handle_layout_editor_key(&mut self, code: winit::keyboard::KeyCode) {
+        use winit::keyboard::KeyCode;
+
+        let step = if self.modifiers.shift_key() {
+            constants::layout_editor::NUDGE_STEP as i32
+        } else {
+            1
+        };
+        match code {
+            KeyCode::PageUp => self.layout_editor.bring_forward(),
+            KeyCode::PageDown => self.layout_editor.send_backward(),
+            KeyCode::Tab => self.layout_editor.select_next(),
+            KeyCode::ArrowLeft => self.layout_editor.nudge(-step, 0),
+            KeyCode::ArrowRight => self.layout_editor.nudge(step, 0),
+            KeyCode::ArrowUp => self.layout_editor.nudge(0, -step),
+            KeyCode::ArrowDown => self.layout_editor.nudge(0, step),
+            _ => return,
+        }
+        self.apply_edited_layout();
+    }
+
+    /// Exclude the window under the mouse cursor from the output (or include it again)
+    fn toggle_excluded_window_at_cursor(&mut self) {
+        let Some((hwnd, pid)) = window_mask::window_at_cursor() else {
+            return;
+        };
+        // Our own windows are handled by exclude_from_capture, never masked
+        if pid == std::process::id() {
+            info!("Window under cursor belongs to RustFrame, not excluding");
+            return;
+        }
+
+        let excluded = self.window_mask.toggle_window(hwnd);
+        info!(
+            "Window {:#x} {} ({} excluded)",
+            hwnd,
+            if excluded { "excluded" } else { "included again" },
+            self.window_mask.excluded_count()
+        );
+        if let Some(menu) = &self.menu_clear_excluded {
+            menu.set_enabled(self.window_mask.excluded_count() > 0);
+        }
+    }
+
+    /// Leave draw, measure, color picker and redact mode and the layout editor
+    /// They share the interactive overlay, so only one can be active at a time
+    fn leave_interactive_modes(&mut self) {
+        self.set_drawing_mode(false);
+        self.set_measure_mode(false);
+        self.set_color_picker_mode(false);
+        self.set_redact_mode(false);
+        self.set_layout_editor(false);
+    }
+
+    /// Handle key presses while in redact mode (style toggle, delete)
+    fn handle_redact_mode_key(&mut self, code: winit::keyboard::KeyCode) {
+        use winit::keyboard::KeyCode;
+
+        match code {
+            KeyCode::KeyP => self.privacy.toggle_selected_style(),
+            KeyCode::Delete | KeyCode::Backspace => self.privacy.remove_selected(),
+            KeyCode::KeyX => self.privacy.clear(),
+            KeyCode::KeyB | KeyCode::KeyE => {
+                let tool = if code == KeyCode::KeyB {
+                    RedactTool::Brush
+                } else {
+                    RedactTool::Eraser
+                };
+                self.privacy.toggle_tool(tool);
+                info!("Redact tool: {}", self.privacy.tool().label());
+            }
+            KeyCode::BracketLeft => self.privacy.adjust_brush(-1),
+            KeyCode::BracketRight => self.privacy.adjust_brush(1),
+            _ => return,
+        }
+        if let Some(overlay) = &self.overlay_window {
+            overlay.draw_privacy_regions(self.annotation_offset(), &self.privacy);
+        }
+    }
+
+    /// Handle key presses while in draw mode (tool, color and width selection)
+    fn handle_draw_mode_key(&mut self, code: winit::keyboard::KeyCode) {
+        use winit::keyboard::KeyCode;
+
+        match code {
+            KeyCode::KeyF => self.annotations.set_tool(AnnotationTool::Freehand),
+            KeyCode::KeyA => self.annotations.set_tool(AnnotationTool::Arrow),
+            KeyCode::KeyR => self.annotations.set_tool(AnnotationTool::Rectangle),
+            KeyCode::KeyO => self.annotations.set_tool(AnnotationTool::Ellipse),
+            KeyCode::Digit1 => self.annotations.set_color_index(0),
+            KeyCode::Digit2 => self.annotations.set_color_index(1),
+            KeyCode::Digit3 => self.annotations.set_color_index(2),
+            KeyCode::Digit4 => self.annotations.set_color_index(3),
+            KeyCode::Digit5 => self.annotations.set_color_index(4),
+            KeyCode::Digit6 => self.annotations.set_color_index(5),
+            KeyCode::BracketLeft => self.annotations.adjust_width(-1),
+            KeyCode::BracketRight => self.annotations.adjust_width(1),
+            KeyCode::KeyZ | KeyCode::Backspace => {
+                self.annotations.undo();
+                self.refresh_annotation_overlay();
+            }
+            _ => {}
+        }
+    }
+
+    /// Redraw the overlay's local copy of the annotations (draw mode only)
+    /// Touch and pen input on the overlay: draw in draw mode, otherwise move /
+    /// resize the region with gestures (see touch.rs)
+    fn handle_touch(&mut self, window_id: WindowId, touch: winit::event::Touch) {
+        use winit::event::TouchPhase;
+
+        let Some(overlay) = &self.overlay_window else {
+            return;
+        };
+        if overlay.window_id() != window_id {
+            return;
+        }
+
+        // Gestures work in screen coordinates (the window moves under the finger)
+        let position = overlay.get_outer_position();
+        let size = overlay.get_inner_size();
+        let bounds = ((position.x, position.y), (size.width, size.height));
+        let point = (
+            position.x as f64 + touch.location.x,
+            position.y as f64 + touch.location.y,
+        );
+
+        match touch.phase {
+            TouchPhase::Started => self.touch.start(touch.id, point, bounds),
+            TouchPhase::Moved => {}
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                // Finish the stroke before the contact is forgotten
+                if self.is_drawing_mode && self.touch.is_primary(touch.id) {
+                    self.annotations.end_stroke();
+                    self.refresh_annotation_overlay();
+                }
+                self.touch.end(touch.id, bounds);
+                return;
+            }
+        }
+
+        // Draw mode: the first finger or the pen draws; pen pressure sets the
+        // stroke width
+        if self.is_drawing_mode {
+            if !self.touch.is_primary(touch.id) {
+                return;
+            }
+            let (x, y) = self.overlay_to_frame((touch.location.x, touch.location.y));
+            let pressure = touch.force.map(|f| f.normalized() as f32);
+            if touch.phase == TouchPhase::Started {
+                self.annotations.begin_stroke(x, y, pressure);
+            } else {
+                self.annotations.extend_stroke(x, y, pressure);
+            }
+            self.refresh_annotation_overlay();
+            return;
+        }
+
+        if touch.phase != TouchPhase::Moved
+            || self.is_measuring
+            || self.is_redacting
+            || self.is_editing_layout
+            || self.is_picking_color
+            || self.countdown_started.is_some()
+        {
+            return;
+        }
+
+        // The resulting Moved / Resized events update the capture region
+        if let Some((position, size)) = self.touch.update(touch.id, point) {
+            overlay.set_bounds(position, size);
+        }
+    }
+
+    fn refresh_annotation_overlay(&self) {
+        if self.is_drawing_mode {
+            if let Some(overlay) = &self.overlay_window {
+                overlay.draw_annotations(self.annotation_offset(), &self.annotations);
+            }
+        }
+    }
+
+    /// Offset between the overlay window and the capture region (the border width)
+    fn annotation_offset(&self) -> u32 {
+        if self.settings.capture.show_border {
+            self.settings.capture.border_width
+        } else {
+            0
+        }
+    }
+
+    /// Convert overlay client coordinates to capture-region (frame) coordinates
+    fn overlay_to_frame(&self, pos: (f64, f64)) -> (i32, i32) {
+        let offset = self.annotation_offset() as i32;
+        (pos.0 as i32 - offset, pos.1 as i32 - offset)
+    }
+
+    /// Update overlay title and visual display to show current settings
+    fn update_overlay_title(&self) {
+        if let Some(overlay) = &self.overlay_window {
+            let cursor = if self.settings.capture.show_cursor {
+                "ON"
+            } else {
+                "OFF"
+            };
+            let border = if self.settings.capture.show_border {
+                "ON"
+            } else {
+                "OFF"
+            };
+            // E = Production mode: destination window behind overlay (single window view)
+            // OFF = Dev mode: two windows side by side
+            // ON = Prod mode: destination hidden behind overlay
+            let mode = if self.settings.capture.exclude_from_capture {
+                "PROD(single)"
+            } else {
+                "DEV(side-by-side)"
+            };
+
+            // The focused settings line is marked with '>' (the title is what
+            // screen readers announce for the overlay)
+            let focus = |option: usize| {
+                if self.focused_option == Some(option) {
+                    ">"
+                } else {
+                    ""
+                }
+            };
+
+            let title = format!(
+                "RustFrame | {}[C]ursor:{} {}[B]order:{} {}[E]mode:{} [S]ettings [G]rid | ENTER=Start ESC=Exit TAB=Focus ARROWS=Move",
+                focus(0),
+                cursor,
+                focus(1),
+                border,
+                focus(2),
+                mode
+            );
+            overlay.set_title(&title);
+            
+            // Update the visual display inside the overlay to reflect settings changes
+            if self.is_selecting {
+                if let Err(e) = overlay.update_settings_display(
+                    self.settings.capture.show_cursor,
+                    self.settings.capture.show_border,
+                    self.settings.capture.exclude_from_capture
+                ) {
+                    error!("Failed to update overlay settings display: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Advance to the next composition grid style and show it on the overlay
+    /// Toggle an overlay settings line (0 = cursor, 1 = border, 2 = mode)
+    fn toggle_overlay_option(&mut self, option: usize) {
+        match option {
+            0 => {
+                self.settings.capture.show_cursor = !self.settings.capture.show_cursor;
+                info!("Cursor visibility: {}", self.settings.capture.show_cursor);
+            }
+            1 => {
+                self.settings.capture.show_border = !self.settings.capture.show_border;
+                info!("Border visibility: {}", self.settings.capture.show_border);
+            }
+            _ => {
+                self.settings.capture.exclude_from_capture =
+                    !self.settings.capture.exclude_from_capture;
+                info!(
+                    "Exclude from capture: {}",
+                    self.settings.capture.exclude_from_capture
+                );
+            }
+        }
+        self.update_overlay_title();
+    }
+
+    /// Move the keyboard focus to the next (or previous) overlay settings line
+    /// The cycle includes "no line" so Space cannot toggle anything by accident
+    fn cycle_overlay_focus(&mut self, backwards: bool) {
+        let count = constants::keyboard::FOCUSABLE_OPTIONS;
+        self.focused_option = match (self.focused_option, backwards) {
+            (None, false) => Some(0),
+            (None, true) => Some(count - 1),
+            (Some(i), false) => (i + 1 < count).then_some(i + 1),
+            (Some(i), true) => i.checked_sub(1),
+        };
+        info!("Overlay focus: {:?}", self.focused_option);
+
+        if let Some(overlay) = &self.overlay_window {
+            overlay.set_focused_option(self.focused_option);
+        }
+        self.update_overlay_title();
+    }
+
+    /// Move (or with Shift resize) the capture region with an arrow key
+    fn nudge_overlay(&self, code: winit::keyboard::KeyCode) {
+        use winit::keyboard::KeyCode;
+
+        let Some(overlay) = &self.overlay_window else {
+            return;
+        };
+        let step = if self.modifiers.control_key() {
+            constants::keyboard::FINE_STEP
+        } else {
+            constants::keyboard::STEP
+        };
+        let (dx, dy) = match code {
+            KeyCode::ArrowLeft => (-step, 0),
+            KeyCode::ArrowRight => (step, 0),
+            KeyCode::ArrowUp => (0, -step),
+            KeyCode::ArrowDown => (0, step),
+            _ => return,
+        };
+
+        // The resulting Moved / Resized events update the capture region
+        if self.modifiers.shift_key() {
+            overlay.resize_by(dx, dy);
+        } else {
+            overlay.move_by(dx, dy);
+        }
+    }
+
+    fn cycle_grid_style(&mut self) {
+        self.grid.cycle_style();
+        info!("Composition grid: {}", self.grid.style().label());
+
+        if let Some(menu) = &self.menu_grid_style {
+            menu.set_text(format!("Grid: {}", self.grid.style().label()));
+        }
+        if let Some(overlay) = &self.overlay_window {
+            if let Err(e) = overlay.set_grid_style(self.grid.style()) {
+                error!("Failed to redraw overlay grid: {}", e);
+            }
+        }
+    }
+
+    /// Handle a gamepad button (see gamepad.rs)
+    fn handle_gamepad(&mut self, action: GamepadAction) {
+        info!("Gamepad action: {:?}", action);
+        match action {
+            GamepadAction::StartStop => self.toggle_capture(),
+            GamepadAction::TogglePause => self.handle_hotkey(HotkeyAction::TogglePause),
+            GamepadAction::NextScene => self.cycle_scene(true),
+            GamepadAction::PreviousScene => self.cycle_scene(false),
+            GamepadAction::DropMarker => self.drop_marker(),
+        }
+    }
+
+    /// Handle an action from a Stream Deck / remote (see stream_deck.rs) or OSC (see osc.rs)
+    fn handle_remote(&mut self, action: RemoteAction) {
+        match action {
+            RemoteAction::StartStop => self.toggle_capture(),
+            RemoteAction::Start => self.request_start(),
+            RemoteAction::Stop => self.request_stop(),
+            RemoteAction::TogglePause => self.handle_hotkey(HotkeyAction::TogglePause),
+            RemoteAction::NextScene => self.cycle_scene(true),
+            RemoteAction::PreviousScene => self.cycle_scene(false),
+            RemoteAction::SwitchScene(slot) => self.switch_scene(slot),
+            RemoteAction::DropMarker => self.drop_marker(),
+            RemoteAction::Screenshot => self.take_screenshot(),
+            RemoteAction::UploadScreenshot => self.upload_screenshot(),
+        }
+    }
+
+    /// State shown on the Stream Deck keys
+    fn remote_state(&self) -> RemoteState {
+        let scene_slot = self.scenes.active();
+        RemoteState {
+            capturing: !self.is_selecting,
+            countdown: self.countdown_started.is_some(),
+            paused: self.pause_screen.is_paused(),
+            scene: scene_slot
+                .and_then(|slot| self.scenes.get(slot))
+                .map(|s| s.name.clone()),
+            scene_slot: scene_slot.map(|slot| slot + 1),
+            elapsed_seconds: self.capture_started.map_or(0, |t| t.elapsed().as_secs()),
+            fps: self.renderer.as_ref().map_or(0, |r| r.fps()),
+            frames: self.renderer.as_ref().map_or(0, |r| r.frame_count()),
+        }
+    }
+
+    /// Install the mouse hook while capturing with click highlights or input
+    /// recording on, remove it otherwise
+    fn update_mouse_hook(&mut self) {
+        let wanted = (self.click_highlight.is_enabled() || self.input_recorder.is_some())
+            && !self.is_selecting;
+        if wanted == self.mouse_hook.is_some() {
+            return;
+        }
+        self.mouse_hook = None;
+        if wanted {
+            match MouseHook::install() {
+                Ok(hook) => self.mouse_hook = Some(hook),
+                Err(e) => error!("Click highlights and input recording unavailable: {:#}", e),
+            }
+        }
+    }
+
+    /// Start capture (with the countdown), cancel the countdown, or stop capture
+    fn toggle_capture(&mut self) {
+        if !self.is_selecting {
+            self.stop_capture();
+        } else if self.countdown_started.is_some() {
+            self.cancel_countdown();
+        } else {
+            self.begin_countdown();
+        }
+    }
+
+    /// Start capture (with the countdown) unless it is already starting or running
+    fn request_start(&mut self) {
+        if self.is_selecting && self.countdown_started.is_none() {
+            self.begin_countdown();
+        }
+    }
+
+    /// Stop capture or cancel the countdown
+    fn request_stop(&mut self) {
+        if !self.is_selecting {
+            self.stop_capture();
+        } else if self.countdown_started.is_some() {
+            self.cancel_countdown();
+        }
+    }
+
+    /// "Start Capture" / "Cancel Countdown" / "Stop Capture" for the tray
+    fn capture_action_label(&self) -> &'static str {
+        if !self.is_selecting {
+            "Stop Capture"
+        } else if self.countdown_started.is_some() {
+            "Cancel Countdown"
+        } else {
+            "Start Capture"
+        }
+    }
+
+    /// One-line state for the tray menu and tooltip, e.g.
+    /// "Capturing 1280x720 - Scene 2 - 00:12:34"
+    fn status_text(&self) -> String {
+        if self.countdown_started.is_some() {
+            return "Starting capture...".to_string();
+        }
+        if self.window_picker.is_some() {
+            return "Click a window to capture".to_string();
+        }
+        if self.is_selecting {
+            return "Selecting region".to_string();
+        }
+        let mut parts = vec![if self.pause_screen.is_paused() {
+            "Paused".to_string()
+        } else if self.is_output_frozen {
+            "Frozen".to_string()
+        } else {
+            "Capturing".to_string()
+        }];
+        if let Some(capture) = &self.capture_engine {
+            let region = capture.get_capture_region();
+            parts[0] = format!("{} {}x{}", parts[0], region.width, region.height);
+            if let Some(status) = capture.black_status() {
+                parts.push(status.to_string());
+            }
+        }
+        if let Some(scene) = self.scenes.active().and_then(|slot| self.scenes.get(slot)) {
+            parts.push(scene.name.clone());
+        }
+        if let Some(percent) = self.frame_diff.changed_percent() {
+            parts.push(format!("{:.1}% changed", percent));
+        }
+        let secs = self.capture_started.map_or(0, |t| t.elapsed().as_secs());
+        parts.push(format!(
+            "{:02}:{:02}:{:02}",
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60
+        ));
+        parts.join(" - ")
+    }
+
+    /// Running on battery with the low-power frame rate enabled (see power.rs)
+    fn low_power(&self) -> bool {
+        self.settings.low_power_on_battery
+            && self.settings.low_power_fps > 0
+            && self.power.on_battery()
+    }
+
+    /// Update the tray status line, tooltip and live action items when the state changed
+    fn refresh_tray_status(&mut self) {
+        let mut status = self.status_text();
+        if self.low_power() {
+            status = format!("{} - Low power", status);
+        }
+        if let Some(upload) = self.uploader.status_text() {
+            status = format!("{} - {}", status, upload);
+        }
+        if status == self.tray_status {
+            return;
+        }
+        if let Some(item) = &self.menu_status {
+            item.set_text(&status);
+        }
+        if let Some(item) = &self.menu_capture {
+            item.set_text(self.capture_action_label());
+        }
+        if let Some(item) = &self.menu_screenshot {
+            item.set_enabled(!self.is_selecting);
+        }
+        if let Some(item) = &self.menu_scan {
+            item.set_enabled(!self.is_selecting);
+        }
+        if let Some(item) = &self.menu_diff_reference {
+            item.set_enabled(!self.is_selecting);
+        }
+        if let Some(tray) = &self.tray_icon {
+            let _ = tray.set_tooltip(Some(format!("RustFrame - {}", status)));
+        }
+        self.tray_status = status;
+    }
+
+    /// Save the next composed output frame as a PNG (see save_screenshot)
+    fn take_screenshot(&mut self) {
+        if self.is_selecting {
+            warn!("Screenshots can only be taken while capturing");
+            self.screenshot_out = None;
+            return;
+        }
+        if let Some(renderer) = &mut self.renderer {
+            renderer.request_snapshot();
+        }
+    }
+
+    /// Write the frame requested by take_screenshot once the renderer has it
+    fn save_screenshot(&mut self) {
+        let Some((pixels, width, height)) =
+            self.renderer.as_mut().and_then(|r| r.take_snapshot())
+        else {
+            return;
+        };
+        let out = self.screenshot_out.take();
+        let keyer = self.chroma_key.keyer();
+        match screenshot::save(&pixels, width, height, keyer.as_ref(), out.as_deref()) {
+            Ok(path) => {
+                if let Some(stdio) = &self.stdio {
+                    stdio.screenshot_saved(&path);
+                }
+                // Files written to an explicit path are left where the caller wants them
+                if out.is_none() {
+                    self.last_screenshot =
+                        Some(post_actions::run(&self.settings.post_actions, &path));
+                } else {
+                    self.last_screenshot = Some(path);
+                }
+                self.refresh_recent_screenshots();
+            }
+            Err(e) => {
+                error!("Failed to save screenshot: {:#}", e);
+                self.send_webhook(
+                    WebhookEvent::Error,
+                    &format!("Failed to save screenshot: {:#}", e),
+                );
+            }
+        }
+    }
+
+    /// Send `event` to the webhooks configured in settings.json
+    fn send_webhook(&mut self, event: WebhookEvent, message: &str) {
+        if self.settings.webhooks.is_empty() {
+            return;
+        }
+        let state = self.remote_state();
+        self.webhooks.send(&self.settings.webhooks, event, message, &state);
+    }
+
+    /// Scan the captured frame for QR codes and links (see frame_scan.rs)
+    /// The scan runs on its own thread; the results arrive in about_to_wait.
+    fn scan_frame(&mut self) {
+        if self.is_selecting || self.pending_scan.is_some() {
+            return;
+        }
+        let Some(renderer) = &self.renderer else {
+            return;
+        };
+        let (frame, width, height) = renderer.last_frame();
+        if frame.is_empty() {
+            return;
+        }
+        let frame = frame.to_vec();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let waker = self.waker.clone();
+        std::thread::spawn(move || {
+            let _ = sender.send(frame_scan::scan(&frame, width, height));
+            let _ = waker.send_event(());
+        });
+        self.pending_scan = Some(receiver);
+        info!("Scanning the captured frame ({}x{})", width, height);
+    }
+
+    /// List the results of a finished scan in tray → Scan Results and sum them up
+    fn finish_scan(&mut self, found: Vec<frame_scan::Found>) {
+        let summary = if found.is_empty() {
+            "No QR codes or links found in the captured frame.".to_string()
+        } else {
+            let lines: Vec<String> = found
+                .iter()
+                .map(|f| format!("{}: {}", f.kind.label(), f.text))
+                .collect();
+            format!(
+                "Found in the captured frame:\n\n{}\n\nOpen or copy them from tray → Scan Results.",
+                lines.join("\n")
+            )
+        };
+        self.scan_results = found;
+        self.refresh_scan_results();
+        utils::show_info(&summary);
+    }
+
+    /// Fill tray → Scan Results with the results of the last scan
+    fn refresh_scan_results(&mut self) {
+        let Some(menu) = &self.menu_scan_results else {
+            return;
+        };
+        while menu.remove_at(0).is_some() {}
+
+        for (i, found) in self.scan_results.iter().enumerate() {
+            // Long QR contents would make the menu as wide as the screen
+            let mut text: String = found
+                .text
+                .chars()
+                .take(constants::frame_scan::MENU_TEXT_CHARS)
+                .collect();
+            if text.len() < found.text.len() {
+                text.push('…');
+            }
+            // A single & marks a menu accelerator
+            let label = format!("{}: {}", found.kind.label(), text.replace('&', "&&"));
+            let item = Submenu::new(label, true);
+            let _ = item.append(&MenuItem::with_id(
+                format!("{}{}", menu_ids::SCAN_OPEN, i),
+                "Open",
+                found.url().is_some(),
+                None,
+            ));
+            let _ = item.append(&MenuItem::with_id(
+                format!("{}{}", menu_ids::SCAN_COPY, i),
+                "Copy",
+                true,
+                None,
+            ));
+            let _ = menu.append(&item);
+        }
+        if self.scan_results.is_empty() {
+            let _ = menu.append(&MenuItem::new("Nothing found yet", false, None));
+        }
+    }
+
+    /// Fill tray → Recent Screenshots with the newest files
+    fn refresh_recent_screenshots(&mut self) {
+        let Some(menu) = &self.menu_recent else {
+            return;
+        };
+        while menu.remove_at(0).is_some() {}
+
+        self.recent_screenshots = screenshot::recent();
+        for (i, path) in self.recent_screenshots.iter().enumerate() {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let _ = menu.append(&MenuItem::with_id(
+                format!("{}{}", menu_ids::RECENT_SCREENSHOT, i),
+                name,
+                true,
+                None,
+            ));
+        }
+        if self.recent_screenshots.is_empty() {
+            let _ = menu.append(&MenuItem::new("No screenshots yet", false, None));
+        }
+        let _ = menu.append(&PredefinedMenuItem::separator());
+        let _ = menu.append(&MenuItem::with_id(
+            menu_ids::UPLOAD_SCREENSHOT,
+            "Upload Last Screenshot",
+            !self.settings.upload.target.is_empty(),
+            None,
+        ));
+        let _ = menu.append(&MenuItem::with_id(
+            menu_ids::OPEN_SCREENSHOTS,
+            "Open Screenshots Folder",
+            true,
+            None,
+        ));
+    }
+
+    /// Hide notification toasts from the capture (the toolbar's Mute button)
+    fn set_hide_notifications(&mut self, hide: bool) {
+        self.settings.hide_notifications = hide;
+        self.window_mask.set_hide_notifications(hide);
+        if let Err(e) = settings_file::save(&self.settings) {
+            error!("Failed to save settings: {:#}", e);
+        }
+        info!("Hide notifications: {}", hide);
+    }
+
+    /// Turn input recording on/off (saved; starts or ends the recording of a
+    /// running capture)
+    fn set_record_input(&mut self, enabled: bool) {
+        self.settings.record_input = enabled;
+        if let Some(menu) = &self.menu_record_input {
+            menu.set_checked(enabled);
+        }
+        if let Err(e) = settings_file::save(&self.settings) {
+            error!("Failed to save settings: {:#}", e);
+        }
+        info!("Input recording: {}", enabled);
+        self.update_input_recording();
+        self.update_mouse_hook();
+    }
+
+    /// Record the input while capturing with "Record Input" on, write the file
+    /// when either ends (see input_recording.rs)
+    fn update_input_recording(&mut self) {
+        let wanted = self.settings.record_input && self.capture_started.is_some();
+        if wanted == self.input_recorder.is_some() {
+            return;
+        }
+        if let Some(recorder) = self.input_recorder.take() {
+            if let Err(e) = recorder.finish() {
+                error!("Failed to save the input recording: {:#}", e);
+            }
+        } else if let Some(capture) = &self.capture_engine {
+            self.input_recorder = Some(InputRecorder::start(capture.get_capture_region()));
+        }
+    }
+
+    /// Replay the newest input recording, or stop the running replay
+    fn toggle_input_replay(&mut self) {
+        if self.input_replay.take().is_some_and(|r| r.is_running()) {
+            info!("Input replay stopped");
+        } else if let Some(path) = input_recording::latest() {
+            // Positions follow the current capture region
+            let origin = self.capture_engine.as_ref().map(|c| {
+                let region = c.get_capture_region();
+                (region.x, region.y)
+            });
+            match InputReplay::start(&path, origin) {
+                Ok(replay) => self.input_replay = Some(replay),
+                Err(e) => error!("{:#}", e),
+            }
+        } else {
+            warn!("There is no input recording to replay");
+        }
+        self.refresh_replay_label();
+    }
+
+    fn refresh_replay_label(&self) {
+        if let Some(menu) = &self.menu_replay_input {
+            menu.set_text(if self.input_replay.is_some() {
+                "Stop Input Replay"
+            } else {
+                "Replay Last Input Recording"
+            });
+        }
+    }
+
+    /// Upload the last screenshot and copy the link (see upload.rs)
+    fn upload_screenshot(&mut self) {
+        // Fall back to the newest file in Pictures\RustFrame (e.g. after a restart)
+        let path = self
+            .last_screenshot
+            .clone()
+            .filter(|path| path.exists())
+            .or_else(|| screenshot::recent().into_iter().next());
+        match path {
+            Some(path) => self.uploader.start(&self.settings.upload, path),
+            None => warn!("There is no screenshot to upload"),
+        }
+    }
+
+    /// Create the overlay, destination and popup windows that do not exist yet
+    fn create_windows(&mut self, event_loop: &ActiveEventLoop) {
+        // Create the overlay window first (for region selection)
+        if self.overlay_window.is_none() {
+            match OverlayWindow::new(event_loop) {
+                Ok(overlay) => {
+                    info!("Overlay window created successfully");
+                    // Initialize the overlay with current settings state
+                    if let Err(e) = overlay.update_settings_display(
+                        self.settings.capture.show_cursor,
+                        self.settings.capture.show_border,
+                        self.settings.capture.exclude_from_capture
+                    ) {
+                        error!("Failed to initialize overlay settings display: {}", e);
+                    }
+                    // Keyboard users can start right away (arrows, Tab, Enter)
+                    overlay.focus();
+                    self.overlay_window = Some(overlay);
+                    // Set initial title with settings info
+                    self.update_overlay_title();
+                }
+                Err(e) => {
+                    error!("Failed to create overlay window: {}", e);
+                }
+            }
+        }
+
+        // Create the destination window
+        if self.destination_window.is_none() {
+            match DestinationWindow::new(event_loop, self.dev_mode) {
+                Ok(dest) => {
+                    info!("Destination window created successfully");
+                    dest.set_dark(theme::palette().dark);
+                    self.destination_window = Some(dest);
+                }
+                Err(e) => {
+                    error!("Failed to create destination window: {}", e);
+                }
+            }
+        }
+
+        // Create the (hidden) local-only popups
+        if self.magnifier_popup.is_none() {
+            match PopupWindow::new(event_loop, "RustFrame Magnifier") {
+                Ok(popup) => self.magnifier_popup = Some(popup),
+                Err(e) => error!("Failed to create magnifier window: {}", e),
+            }
+        }
+        if self.countdown_popup.is_none() {
+            match PopupWindow::new(event_loop, "RustFrame Countdown") {
+                Ok(popup) => self.countdown_popup = Some(popup),
+                Err(e) => error!("Failed to create countdown window: {}", e),
+            }
+        }
+        if self.toolbar_popup.is_none() {
+            match PopupWindow::new_clickable(event_loop, "RustFrame Toolbar") {
+                Ok(popup) => self.toolbar_popup = Some(popup),
+                Err(e) => error!("Failed to create toolbar window: {}", e),
+            }
+        }
+    }
+
+    /// Daemon mode: create the windows before an action that needs them
+    fn ensure_windows(&mut self, event_loop: &ActiveEventLoop) {
+        if !self.daemon || self.overlay_window.is_some() {
+            return;
+        }
+        info!("Creating the windows (daemon mode)");
+        self.create_windows(event_loop);
+        if let Some(rect) = self.released_region.take() {
+            self.set_capture_region(rect);
+        }
+    }
+
+    /// Daemon mode: drop the windows once they are hidden while nothing runs,
+    /// so an idle RustFrame holds no windows, swap chains or GPU memory
+    fn release_windows(&mut self) {
+        if !self.daemon
+            || !self.is_selecting
+            || self.countdown_started.is_some()
+            || !self.region_overlays.is_empty()
+        {
+            return;
+        }
+        let Some(overlay) = self.overlay_window.take() else {
+            return;
+        };
+        self.released_region = Some(if self.settings.capture.show_border {
+            overlay.get_capture_rect_inner(self.settings.capture.border_width)
+        } else {
+            overlay.get_capture_rect()
+        });
+        self.destination_window = None;
+        self.magnifier_popup = None;
+        self.countdown_popup = None;
+        self.toolbar_popup = None;
+        self.toolbar.hide();
+        self.hidden_to_tray = None;
+        info!("Windows released (daemon mode)");
+    }
+
+    /// ESC outside of the editing modes, as set in settings.esc_action
+    /// Stopping a running capture is always confirmed, so an accidental ESC
+    /// does not end a recording or stream.
+    fn handle_escape(&mut self, event_loop: &ActiveEventLoop, window_id: WindowId) {
+        match self.settings.esc_action {
+            EscAction::Minimize => {
+                info!("ESC pressed, hiding to tray");
+                self.hide_to_tray(window_id);
+            }
+            _ if !self.is_selecting => {
+                info!("ESC pressed in capture mode, asking to stop capture");
+                self.confirm(ConfirmAction::StopCapture);
+            }
+            EscAction::Ask => {
+                info!("ESC pressed in selection mode, asking to exit");
+                self.confirm(ConfirmAction::Exit);
+            }
+            EscAction::Stop => {
+                info!("ESC pressed in selection mode, exiting");
+                event_loop.exit();
+            }
+        }
+    }
+
+    /// Ask before `action`; it is carried out in about_to_wait once the user
+    /// answers yes. Only one box is shown at a time.
+    fn confirm(&mut self, action: ConfirmAction) {
+        if self.pending_confirm.is_some() {
+            return;
+        }
+        let question;
+        let text = match &action {
+            ConfirmAction::RestartElevated => {
+                question = elevation::restart_question(
+                    self.elevated_app.as_deref().unwrap_or("An application"),
+                );
+                question.as_str()
+            }
+            ConfirmAction::StopCapture => {
+                "Stop the capture?\n\nApps recording or streaming the RustFrame window will lose the picture."
+            }
+            ConfirmAction::Exit if self.is_selecting => "Exit RustFrame?",
+            ConfirmAction::Exit => {
+                "The capture is still running. Exit RustFrame?\n\nApps recording or streaming the RustFrame window will lose the picture."
+            }
+            ConfirmAction::RunLink(_) => {
+                "A rustframe:// link wants to start capturing the screen.\n\nStart the capture?"
+            }
+        };
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let waker = self.waker.clone();
+        utils::confirm(text, move |answer| {
+            let _ = sender.send(answer);
+            let _ = waker.send_event(());
+        });
+        self.pending_confirm = Some((action, receiver));
+    }
+
+    /// Hide the overlay to the tray (close / minimize with the tray options on)
+    /// The capture keeps running and the output stays shared. The destination
+    /// window is only hidden if it was the window closed or minimized itself
+    /// (development mode, where it has a title bar).
+    fn hide_to_tray(&mut self, window_id: WindowId) {
+        let destination = self
+            .destination_window
+            .as_ref()
+            .filter(|d| d.window_id() == window_id);
+        if let Some(dest) = destination {
+            dest.hide();
+        }
+        let destination_hidden = destination.is_some() || self.hidden_to_tray == Some(true);
+        if let Some(overlay) = &self.overlay_window {
+            overlay.hide();
+        }
+        self.hidden_to_tray = Some(destination_hidden);
+        info!(
+            "Hidden to tray ({})",
+            if self.is_selecting { "selecting" } else { "capture continues" }
+        );
+        self.release_windows();
+    }
+
+    /// Bring back the windows hidden with hide_to_tray (tray icon left click)
+    fn restore_from_tray(&mut self) {
+        let Some(destination_hidden) = self.hidden_to_tray.take() else {
+            return;
+        };
+        info!("Restored from tray");
+        // Without a border the overlay stays hidden during capture
+        let overlay_visible = self.is_selecting
+            || self.settings.capture.show_border
+            || self.is_drawing_mode
+            || self.is_measuring
+            || self.is_picking_color
+            || self.is_redacting
+            || self.is_editing_layout;
+        if let Some(overlay) = self.overlay_window.as_ref().filter(|_| overlay_visible) {
+            overlay.restore();
+        }
+        if let Some(dest) = self.destination_window.as_ref().filter(|_| destination_hidden) {
+            dest.restore();
+        }
+    }
+
+    /// Switch to the next / previous saved scene, skipping empty slots
+    fn cycle_scene(&mut self, forward: bool) {
+        let count = self.scenes.len();
+        let step = if forward { 1 } else { count - 1 };
+        let mut slot = self
+            .scenes
+            .active()
+            .unwrap_or(if forward { count - 1 } else { 0 });
+        for _ in 0..count {
+            slot = (slot + step) % count;
+            if self.scenes.get(slot).is_some() {
+                self.switch_scene(slot);
+                return;
+            }
+        }
+        info!("No saved scenes to switch to");
+    }
+
+    /// Handle a MIDI note / CC (see midi.rs)
+    fn handle_midi(&mut self, event: MidiEvent) {
+        let Some(action) = midi::resolve(&self.settings.midi_mappings)
+            .into_iter()
+            .find(|&(_, trigger)| trigger == Some(event.trigger))
+            .map(|(action, _)| action)
+        else {
+            return;
+        };
+
+        // The fader follows every value; the other actions fire when the pad goes down
+        if action == MidiAction::OverlayOpacity {
+            self.overlay_opacity.set(event.value as u32 * 100 / 127);
+            return;
+        }
+        if !event.pressed {
+            return;
+        }
+        info!("MIDI action: {:?} ({})", action, event.trigger.label());
+        match action {
+            MidiAction::SwitchScene(slot) => self.switch_scene(slot),
+            MidiAction::TogglePause => self.handle_hotkey(HotkeyAction::TogglePause),
+            MidiAction::DropMarker => self.drop_marker(),
+            MidiAction::OverlayOpacity => {}
+        }
+    }
+
+    /// Run a rustframe:// link (see deep_link.rs); a launched link that
+    /// starts a capture asks first
+    fn handle_deep_link(&mut self, url: &str, source: LinkSource) {
+        let link = match DeepLink::parse(url) {
+            Ok(link) => link,
+            Err(e) => {
+                warn!("{}", e);
+                return;
+            }
+        };
+        info!("Link ({:?}): {:?}", source, link);
+        // A web page must not pick where a file is written
+        let link = match link {
+            DeepLink::Screenshot { out: Some(out) } if source == LinkSource::Launch => {
+                warn!(
+                    "Ignoring out={:?} - only rustframe-ctl can choose the screenshot file",
+                    out
+                );
+                DeepLink::Screenshot { out: None }
+            }
+            link => link,
+        };
+        let starts_capture = match link {
+            // Toggling a running capture stops it
+            DeepLink::Toggle => self.is_selecting && self.countdown_started.is_none(),
+            _ => link.starts_capture(),
+        };
+        if source == LinkSource::Launch && starts_capture {
+            self.confirm(ConfirmAction::RunLink(link));
+            return;
+        }
+        self.run_deep_link(link);
+    }
+
+    fn run_deep_link(&mut self, link: DeepLink) {
+        match link {
+            DeepLink::Start { scene, duration } => {
+                self.restore_from_tray();
+                if let Some(slot) = scene {
+                    self.switch_scene(slot);
+                }
+                // Counted from the start of the capture
+                self.capture_duration = duration;
+                self.request_start();
+            }
+            DeepLink::Stop => self.request_stop(),
+            DeepLink::Toggle => self.toggle_capture(),
+            DeepLink::TogglePause => self.handle_hotkey(HotkeyAction::TogglePause),
+            DeepLink::SwitchScene(slot) => self.switch_scene(slot),
+            DeepLink::DropMarker => self.drop_marker(),
+            DeepLink::Screenshot { out } => {
+                self.screenshot_out = out;
+                self.take_screenshot();
+            }
+            DeepLink::Show => self.restore_from_tray(),
+            DeepLink::PickWindow => self.start_window_picker(),
+            DeepLink::Frame {
+                x,
+                y,
+                width,
+                height,
+                start,
+            } => {
+                let rect = capture::CaptureRect {
+                    x,
+                    y,
+                    width,
+                    height,
+                };
+                if start {
+                    self.capture_target(rect);
+                } else {
+                    self.place_frame(rect);
+                }
+            }
+            DeepLink::CaptureMonitor => {
+                let monitor = utils::get_cursor_pos().and_then(|(x, y)| self.monitor_at(x, y));
+                match monitor {
+                    Some(rect) => self.capture_target(rect),
+                    None => warn!("No monitor under the mouse"),
+                }
+            }
+        }
+    }
+
+    /// Carry out a command of the parent program (see control_stdio.rs)
+    fn handle_stdio(&mut self, event_loop: &ActiveEventLoop, input: StdioInput) {
+        let request = match input {
+            StdioInput::Request(request) => request,
+            StdioInput::Closed => {
+                info!("The controlling program closed stdin, exiting");
+                event_loop.exit();
+                return;
+            }
+        };
+        info!("stdin command: {:?}", request.command);
+        let mut error = None;
+        match &request.command {
+            StdioCommand::Start => self.request_start(),
+            StdioCommand::Stop => self.request_stop(),
+            StdioCommand::SetRegion(rect) => self.set_capture_region(*rect),
+            StdioCommand::Screenshot { out } => {
+                if self.is_selecting {
+                    error = Some("Screenshots can only be taken while capturing");
+                } else {
+                    self.screenshot_out = out.clone();
+                    self.take_screenshot();
+                }
+            }
+            StdioCommand::Action(action) => self.handle_remote(*action),
+            StdioCommand::Status => {}
+            StdioCommand::Quit => {}
+        }
+        let state = self.remote_state();
+        if let Some(stdio) = &mut self.stdio {
+            match error {
+                Some(error) => stdio.reply_error(&request, error),
+                None => stdio.reply_ok(&request),
+            }
+            if matches!(request.command, StdioCommand::Status) {
+                stdio.publish(&state, true);
+            }
+        }
+        if matches!(request.command, StdioCommand::Quit) {
+            info!("Exit requested on stdin");
+            event_loop.exit();
+        }
+    }
+
+    /// Wait for the click on the window to capture
+    fn start_window_picker(&mut self) {
+        if !self.is_selecting {
+            info!("Stop the capture before picking a window");
+            return;
+        }
+        match MouseHook::install() {
+            Ok(hook) => {
+                info!("Click the window to capture");
+                self.window_picker = Some((hook, Instant::now()));
+            }
+            Err(e) => error!("Window picker unavailable: {:#}", e),
+        }
+    }
+
+    /// Capture the window clicked since the picker started (gives up after PICK_TIMEOUT_MS)
+    fn tick_window_picker(&mut self) {
+        let Some((hook, started)) = &self.window_picker else {
+            return;
+        };
+        let click = hook.take_finished().into_iter().find(|g| !g.is_drag());
+        let timed_out = started.elapsed()
+            >= Duration::from_millis(constants::shell_menu::PICK_TIMEOUT_MS);
+        if click.is_none() && !timed_out {
+            return;
+        }
+        self.window_picker = None;
+
+        let Some(click) = click else {
+            info!("No window picked");
+            return;
+        };
+        let point = click.start();
+        match window_mask::window_at(point.x, point.y) {
+            Some((_, pid)) if pid == std::process::id() => {
+                info!("Picked a RustFrame window - nothing to capture");
+            }
+            Some((hwnd, pid)) => match window_mask::window_rect(hwnd) {
+                Some(rect) => {
+                    self.tracked_window = TrackedWindow::new(hwnd);
+                    self.capture_target(rect);
+                    if let Some(name) = self.elevation.check(hwnd, pid) {
+                        self.offer_elevated_restart(name);
                     }
                 }
+                None => warn!("Window {:#x} has no visible bounds", hwnd),
+            },
+            None => info!("No window at {}, {}", point.x, point.y),
+        }
+    }
+
+    /// Bounds of the monitor containing a screen position
+    fn monitor_at(&self, x: i32, y: i32) -> Option<capture::CaptureRect> {
+        let overlay = self.overlay_window.as_ref()?;
+        overlay
+            .get_window()
+            .available_monitors()
+            .map(|m| capture::CaptureRect {
+                x: m.position().x,
+                y: m.position().y,
+                width: m.size().width,
+                height: m.size().height,
+            })
+            .find(|r| {
+                x >= r.x && y >= r.y && x < r.x + r.width as i32 && y < r.y + r.height as i32
+            })
+    }
+
+    /// Explain that an elevated application blocks the input hooks and offer
+    /// to restart as administrator (see elevation.rs)
+    fn offer_elevated_restart(&mut self, name: String) {
+        let code = self.code_run_elevated();
+        if !code.is_empty() {
+            warn!("Restart as administrator not offered: {}", code.join(", "));
+            utils::show_warning(&elevation::restart_refused(&name, &code));
+            return;
+        }
+        self.elevated_app = Some(name);
+        self.confirm(ConfirmAction::RestartElevated);
+    }
+
+    /// What the restarted instance would run as administrator: plugins,
+    /// scripts and the post-capture command keep the restart from happening
+    fn code_run_elevated(&self) -> Vec<&'static str> {
+        let mut code = Vec::new();
+        if !self.settings.enabled_plugins.is_empty() {
+            code.push("plugins");
+        }
+        if self.scripts.is_some() {
+            code.push("scripts");
+        }
+        if !self.settings.post_actions.command.trim().is_empty() {
+            code.push("the command after each capture");
+        }
+        code
+    }
+
+    /// Start RustFrame again as administrator and exit; the new instance puts
+    /// the frame back and starts capturing again if a capture is running
+    fn restart_elevated(&mut self, event_loop: &ActiveEventLoop) {
+        // Set up while the question was open
+        let code = self.code_run_elevated();
+        if !code.is_empty() {
+            warn!("Restart as administrator cancelled: {}", code.join(", "));
+            return;
+        }
+        let Some(rect) = self.current_region() else {
+            warn!("No frame to restore, restart as administrator cancelled");
+            return;
+        };
+        let link = format!(
+            "{}://frame?x={}&y={}&width={}&height={}&start={}",
+            deep_link::SCHEME,
+            rect.x,
+            rect.y,
+            rect.width,
+            rect.height,
+            u8::from(!self.is_selecting)
+        );
+        match elevation::restart_elevated(&link) {
+            Ok(()) => event_loop.exit(),
+            Err(e) => warn!("{:#}", e),
+        }
+    }
+
+    /// The captured region, or the region inside the frame while selecting
+    fn current_region(&self) -> Option<capture::CaptureRect> {
+        match (&self.capture_engine, &self.overlay_window) {
+            (Some(capture), _) => Some(capture.get_capture_region()),
+            (None, Some(overlay)) if self.settings.capture.show_border => {
+                Some(overlay.get_capture_rect_inner(self.settings.capture.border_width))
+            }
+            (None, Some(overlay)) => Some(overlay.get_capture_rect()),
+            (None, None) => None,
+        }
+    }
+
+    /// True if a pan can start: a path with two points or more
+    fn pan_available(&self) -> bool {
+        self.pan.is_some() || self.settings.pan_path.len() >= 2
+    }
 
-                // Handle border width change
-                if border_width_changed && self.settings.show_border {
-                    if let Some(overlay) = &self.overlay_window {
-                        overlay.update_hollow_frame(self.settings.border_width);
-                    }
+    fn pan_action_label(&self) -> &'static str {
+        if self.pan.is_some() {
+            "Stop Pan"
+        } else {
+            "Start Pan"
+        }
+    }
 
-                    // Update capture region
-                    if let (Some(overlay), Some(capture)) =
-                        (&self.overlay_window, &mut self.capture_engine)
-                    {
-                        let rect = overlay.get_capture_rect_inner(self.settings.border_width);
-                        if let Err(e) = capture.update_region(rect) {
-                            error!("Failed to update capture region: {}", e);
-                        }
-                    }
-                }
+    fn pan_add_label(&self) -> String {
+        format!(
+            "Add Current Position ({} of {})",
+            self.settings.pan_path.len(),
+            constants::pan::MAX_POINTS
+        )
+    }
 
-                // Handle production mode change
-                if mode_changed {
-                    if let (Some(overlay), Some(dest)) =
-                        (&self.overlay_window, &self.destination_window)
-                    {
-                        let overlay_pos = overlay.get_outer_position();
-                        let size = overlay.get_inner_size();
+    /// Start the auto pan along the saved path, or stop the running one
+    fn toggle_pan(&mut self) {
+        if self.pan.is_some() {
+            info!("Pan stopped");
+            self.stop_pan();
+            return;
+        }
+        if self.is_selecting {
+            info!("Start the capture before panning");
+            return;
+        }
+        match Pan::start(&self.settings.pan_path, self.settings.pan_seconds) {
+            Some(pan) => {
+                info!(
+                    "Panning through {} points in {} s",
+                    self.settings.pan_path.len(),
+                    self.settings.pan_seconds
+                );
+                self.pan = Some(pan);
+            }
+            None => warn!("The pan path needs two different positions"),
+        }
+        self.refresh_pan_menu();
+    }
 
-                        if self.settings.exclude_from_capture {
-                            dest.position_offscreen(size);
-                        } else {
-                            dest.position_beside_overlay(overlay_pos, size);
-                        }
+    fn stop_pan(&mut self) {
+        if self.pan.take().is_some() {
+            self.refresh_pan_menu();
+        }
+    }
+
+    /// Move the capture region along the running pan
+    fn update_pan(&mut self) {
+        let Some(pan) = &mut self.pan else {
+            return;
+        };
+        let step = pan.step();
+        let finished = pan.is_finished();
+        if let (Some((x, y)), Some(region)) = (step, self.current_region()) {
+            self.set_capture_region(capture::CaptureRect { x, y, ..region });
+        }
+        if finished {
+            info!("Pan finished");
+            self.stop_pan();
+        }
+    }
+
+    /// Add the position of the capture region to the pan path
+    fn add_pan_point(&mut self) {
+        if self.settings.pan_path.len() >= constants::pan::MAX_POINTS {
+            return;
+        }
+        let Some(region) = self.current_region() else {
+            return;
+        };
+        self.settings.pan_path.push((region.x, region.y));
+        info!(
+            "Pan point {} at {}, {}",
+            self.settings.pan_path.len(),
+            region.x,
+            region.y
+        );
+        self.save_pan_settings();
+    }
+
+    /// Store the timer options changed in the tray menu
+    fn save_timer_settings(&mut self) {
+        self.settings.timer_seconds = self.timer.mode().seconds();
+        self.settings.timer_corner = self.timer.corner();
+        self.settings.timer_size = self.timer.size();
+        if let Err(e) = settings_file::save(&self.settings) {
+            error!("Failed to save settings: {:#}", e);
+        }
+        self.refresh_timer_menu();
+    }
+
+    /// Show the pause screen style in its tray item
+    fn refresh_pause_style_menu(&self) {
+        if let Some(menu) = &self.menu_pause_style {
+            menu.set_text(format!("Pause Screen: {}", self.pause_screen.style().label()));
+        }
+    }
+
+    /// Show the timer options in the tray items
+    fn refresh_timer_menu(&self) {
+        if let Some(menu) = &self.menu_timer_mode {
+            menu.set_text(format!("Mode: {}", self.timer.mode().label()));
+        }
+        if let Some(menu) = &self.menu_timer_position {
+            menu.set_text(format!("Position: {}", self.timer.corner().label()));
+        }
+        if let Some(menu) = &self.menu_timer_size {
+            menu.set_text(format!("Size: {}", self.timer.size().label()));
+        }
+    }
+
+    /// Save the pan path and duration and update the tray items
+    fn save_pan_settings(&mut self) {
+        if let Err(e) = settings_file::save(&self.settings) {
+            error!("Failed to save settings: {:#}", e);
+        }
+        self.refresh_pan_menu();
+    }
+
+    fn refresh_pan_menu(&self) {
+        if let Some(menu) = &self.menu_pan_start {
+            menu.set_text(self.pan_action_label());
+            menu.set_enabled(self.pan_available());
+        }
+        if let Some(menu) = &self.menu_pan_add {
+            menu.set_text(self.pan_add_label());
+            menu.set_enabled(self.settings.pan_path.len() < constants::pan::MAX_POINTS);
+        }
+        if let Some(menu) = &self.menu_pan_clear {
+            menu.set_enabled(!self.settings.pan_path.is_empty());
+        }
+        if let Some(menu) = &self.menu_pan_duration {
+            menu.set_text(format!("Duration: {} s", self.settings.pan_seconds));
+        }
+    }
+
+    /// Put the frame around a window or monitor and start capturing it (with the countdown)
+    fn capture_target(&mut self, rect: capture::CaptureRect) {
+        if !self.is_selecting {
+            return;
+        }
+        self.place_frame(rect);
+        self.request_start();
+    }
+
+    /// Put the frame around a screen region (selection mode only)
+    fn place_frame(&mut self, rect: capture::CaptureRect) {
+        if !self.is_selecting {
+            return;
+        }
+        self.restore_from_tray();
+        // The border is drawn around the captured area, not on top of it
+        let border = if self.settings.capture.show_border {
+            self.settings.capture.border_width
+        } else {
+            0
+        };
+        if let Some(overlay) = &self.overlay_window {
+            info!(
+                "Capture target: {}x{} at {}, {}",
+                rect.width, rect.height, rect.x, rect.y
+            );
+            overlay.set_bounds(
+                (rect.x - border as i32, rect.y - border as i32),
+                (rect.width + 2 * border, rect.height + 2 * border),
+            );
+        }
+    }
+
+    /// Append a marker with the live scene name to the marker file
+    fn drop_marker(&mut self) {
+        let scene = self
+            .scenes
+            .active()
+            .and_then(|slot| self.scenes.get(slot))
+            .map(|s| s.name.clone());
+        if let Err(e) = self.markers.drop_marker(scene.as_deref()) {
+            warn!("{:#}", e);
+        }
+    }
+
+    /// (Re-)register the global hotkeys with the current shortcut settings
+    fn register_hotkeys(&mut self) {
+        // The old manager has to unregister its hotkeys before they can be
+        // registered again for another action
+        self.hotkeys = None;
+        match HotkeyManager::new(&hotkeys::resolve(&self.settings.hotkeys)) {
+            Ok(hotkeys) => self.hotkeys = Some(hotkeys),
+            Err(e) => error!("Failed to register global hotkeys: {}", e),
+        }
+    }
+
+    /// Show the settings dialog and apply changes
+    fn show_settings_dialog(&mut self) {
+        info!("Opening settings dialog...");
+
+        // Release the MIDI device so the learn mode in the dialog can open it
+        self.midi = None;
+
+        let original = self.settings.clone();
+        let plugins = self.plugins.infos();
+        let result = settings_dialog::show_settings_dialog(
+            &original,
+            self.dev_mode,
+            &plugins,
+            &mut |preview| self.preview_settings(preview),
+        );
+        if let Some(new_settings) = result {
+            info!("Settings changed, applying...");
+
+            self.apply_settings(new_settings);
+            if let Err(e) = settings_file::save(&self.settings) {
+                error!("Failed to save settings: {:#}", e);
+            }
+        } else {
+            info!("Settings dialog cancelled");
+            self.preview_settings(&original);
+        }
+
+        self.midi = open_midi(&self.settings);
+    }
+
+    /// Show the border, cursor and filter values of the open settings dialog
+    /// on a running capture and keep the output going (the dialog blocks the
+    /// event loop); called again with the old values on Cancel
+    fn preview_settings(&mut self, preview: &AppSettings) {
+        let cursor_changed = self.settings.capture.show_cursor != preview.capture.show_cursor;
+        let border_changed = self.settings.capture.show_border != preview.capture.show_border;
+        let border_width_changed = self.settings.capture.border_width != preview.capture.border_width;
+        self.settings.capture.show_cursor = preview.capture.show_cursor;
+        self.settings.capture.show_border = preview.capture.show_border;
+        self.settings.capture.border_width = preview.capture.border_width;
+        self.apply_theme(preview.theme, &preview.accent_color);
+        if self.settings.filters != preview.filters {
+            self.settings.filters = preview.filters.clone();
+            self.filters.set_order(&self.settings.filters);
+        }
+        self.apply_capture_changes(cursor_changed, border_changed, border_width_changed);
+        if !self.is_selecting {
+            self.render_output();
+        }
+    }
+
+    /// Switch the theme (see theme.rs) and redraw what uses it
+    fn apply_theme(&mut self, mode: theme::ThemeMode, accent: &str) {
+        if !theme::apply(mode, accent) {
+            return;
+        }
+        let dark = theme::palette().dark;
+        for dest in [&self.destination_window, &self.second_destination]
+            .into_iter()
+            .flatten()
+        {
+            dest.set_dark(dark);
+        }
+        // While capturing, the frame shows the border of the same drawing;
+        // the draw / measure / color picker / redact modes and the layout
+        // editor paint their own
+        let interactive = self.is_drawing_mode
+            || self.is_measuring
+            || self.is_picking_color
+            || self.is_redacting
+            || self.is_editing_layout;
+        if let (Some(overlay), false) = (&self.overlay_window, interactive) {
+            if let Err(e) = overlay.redraw_selection_overlay() {
+                error!("Failed to redraw overlay: {}", e);
+            }
+        }
+    }
+
+    /// Apply changed settings (from the dialog or an edited settings.json)
+    fn apply_settings(&mut self, new_settings: AppSettings) {
+        // Update cursor menu checkbox
+        if let Some(menu) = &self.menu_cursor {
+            menu.set_checked(new_settings.capture.show_cursor);
+        }
+
+        // Update border menu checkbox
+        if let Some(menu) = &self.menu_border {
+            menu.set_checked(new_settings.capture.show_border);
+        }
+
+        // Update exclude/production mode menu checkbox
+        if let Some(menu) = &self.menu_exclude {
+            menu.set_checked(new_settings.capture.exclude_from_capture);
+        }
+
+        // Compare with the old settings to detect changes
+        let cursor_changed = self.settings.capture.show_cursor != new_settings.capture.show_cursor;
+        let border_changed = self.settings.capture.show_border != new_settings.capture.show_border;
+        let mode_changed =
+            self.settings.capture.exclude_from_capture != new_settings.capture.exclude_from_capture;
+        let border_width_changed =
+            self.settings.capture.border_width != new_settings.capture.border_width;
+        let hotkeys_changed = self.settings.hotkeys != new_settings.hotkeys;
+        let stream_deck_changed = self.settings.stream_deck != new_settings.stream_deck
+            || self.settings.stream_deck_port != new_settings.stream_deck_port
+            || self.settings.stream_deck_token != new_settings.stream_deck_token;
+        let osc_changed = self.settings.osc != new_settings.osc
+            || self.settings.osc_port != new_settings.osc_port
+            || self.settings.osc_bind != new_settings.osc_bind
+            || self.settings.osc_allowed_sources != new_settings.osc_allowed_sources;
+        let auto_start_changed = self.settings.auto_start != new_settings.auto_start;
+        let shell_menu_changed = self.settings.shell_menu != new_settings.shell_menu;
+        let thread_changed = self.settings.thread_priority != new_settings.thread_priority
+            || self.settings.cpu_cores != new_settings.cpu_cores;
+        let scenes_changed = self.settings.scenes != new_settings.scenes;
+
+        // Apply the new settings
+        self.settings = new_settings;
+        if hotkeys_changed {
+            self.register_hotkeys();
+        }
+        if self.settings.gamepad != self.gamepad.is_some() {
+            self.gamepad = self.settings.gamepad.then(Gamepad::new);
+        }
+        if auto_start_changed {
+            if let Err(e) = autostart::set_enabled(self.settings.auto_start) {
+                error!("{:#}", e);
+            }
+        }
+        if shell_menu_changed {
+            if let Err(e) = shell_menu::set_enabled(self.settings.shell_menu) {
+                error!("{:#}", e);
+            }
+        }
+        if thread_changed {
+            thread_priority::apply_to_current_thread(
+                self.settings.thread_priority,
+                &self.settings.cpu_cores,
+            );
+        }
+        if stream_deck_changed {
+            // The old server has to release the port first
+            self.stream_deck = None;
+            self.stream_deck = open_stream_deck(&self.settings);
+        }
+        if osc_changed {
+            // The old listener has to release the port first
+            self.osc = None;
+            self.osc = open_osc(&self.settings);
+        }
+        if scenes_changed {
+            self.scenes.set_slots(self.settings.scenes.clone());
+            self.update_scene_menu();
+        }
+        self.window_mask.set_apps(&self.settings.redacted_apps);
+        self.window_mask.set_hide_notifications(self.settings.hide_notifications);
+        self.filters.set_order(&self.settings.filters);
+        self.frame_hold.set_grace_seconds(self.settings.hold_seconds);
+        self.timer.set_mode(TimerMode::from_seconds(self.settings.timer_seconds));
+        self.timer.set_corner(self.settings.timer_corner);
+        self.timer.set_size(self.settings.timer_size);
+        self.refresh_timer_menu();
+        self.pause_screen.set_style(self.settings.pause_style);
+        self.refresh_pause_style_menu();
+        self.refresh_transition_menu();
+        let (mode, accent) = (self.settings.theme, self.settings.accent_color.clone());
+        self.apply_theme(mode, &accent);
+
+        // Reload the image source with the new path / slide interval
+        if self.image_source.take().is_some() {
+            self.update_image_source();
+        }
+
+        // Update overlay title
+        self.update_overlay_title();
+
+        // If capture is active, apply runtime changes
+        self.apply_capture_changes(cursor_changed, border_changed, border_width_changed);
+        if !self.is_selecting {
+            // Handle production mode change
+            if mode_changed {
+                if let (Some(overlay), Some(dest)) =
+                    (&self.overlay_window, &self.destination_window)
+                {
+                    let overlay_pos = overlay.get_outer_position();
+                    let size = overlay.get_inner_size();
+
+                    if self.settings.capture.exclude_from_capture {
+                        dest.position_offscreen(size);
+                    } else {
+                        dest.position_beside_overlay(overlay_pos, size);
                     }
                 }
             }
+        }
+    }
+
+    /// Apply settings.json when it was edited in a text editor; a file that
+    /// cannot be used is shown in a notification and the current settings stay
+    fn reload_settings_file(&mut self) {
+        match settings_file::reload() {
+            Ok(loaded) => {
+                let loaded = AppSettings {
+                    capture: CaptureSettings {
+                        exclude_from_capture: self.settings.capture.exclude_from_capture,
+                        ..loaded.capture
+                    },
+                    ..loaded
+                };
+                // Our own saves are noticed too - nothing to do for those
+                if serde_json::to_value(&loaded).ok() == serde_json::to_value(&self.settings).ok() {
+                    return;
+                }
+                info!("settings.json changed, applying...");
+                let midi_changed = loaded.midi_enabled != self.settings.midi_enabled
+                    || loaded.midi_device != self.settings.midi_device;
+                self.apply_settings(loaded);
+                if midi_changed {
+                    self.midi = None;
+                    self.midi = open_midi(&self.settings);
+                }
+            }
+            Err(e) => {
+                warn!("{:#}", e);
+                utils::show_warning(&format!(
+                    "settings.json was not applied:\n\n{:#}\n\nThe current settings stay in use until the file is fixed.",
+                    e
+                ));
+            }
+        }
+    }
+
+    /// Tray → Export Configuration: save the setup as a bundle
+    fn export_config(&mut self) {
+        let Some(path) = config_bundle::pick_export_path() else {
+            return;
+        };
+        if let Err(e) = config_bundle::export(&self.settings, &path) {
+            error!("Configuration export failed: {:#}", e);
+            utils::show_warning(&format!("The configuration was not exported:\n\n{:#}", e));
+        }
+    }
+
+    /// Tray → Import Configuration: merge or replace the setup from a bundle
+    fn import_config(&mut self) {
+        let Some(path) = config_bundle::pick_import_path() else {
+            return;
+        };
+        let active = match config_bundle::inspect(&path) {
+            Ok(active) => active,
+            Err(e) => {
+                error!("Configuration import failed: {:#}", e);
+                utils::show_warning(&format!("The configuration was not imported:\n\n{:#}", e));
+                return;
+            }
+        };
+        let Some(mode) = config_bundle::ask_import_mode() else {
+            return;
+        };
+        // Scripts, the post-capture command, webhooks and uploads only with consent
+        let include_active = if active.is_empty() {
+            false
         } else {
-            info!("Settings dialog cancelled");
+            match config_bundle::ask_include_active(&active) {
+                Some(include) => include,
+                None => return,
+            }
+        };
+        match config_bundle::import(&self.settings, &path, mode, include_active) {
+            Ok(_) => {
+                // Applied right away instead of waiting for the file watcher
+                self.reload_settings_file();
+                self.scripts = ScriptHost::load();
+            }
+            Err(e) => {
+                error!("Configuration import failed: {:#}", e);
+                utils::show_warning(&format!(
+                    "The configuration was not imported:\n\n{:#}\n\nNothing was changed.",
+                    e
+                ));
+            }
+        }
+    }
+}
+
+/// Wake the event loop whenever `engine` has a new frame (see about_to_wait)
+fn wake_on_frames(waker: &EventLoopProxy<()>, engine: &CaptureEngine) {
+    let waker = waker.clone();
+    engine.set_frame_callback(move || {
+        let _ = waker.send_event(());
+    });
+}
+
+/// Offer to repair recordings an earlier run did not finish (see recovery.rs)
+/// The files are repaired on the dialog's thread; the app starts meanwhile.
+fn offer_recovery() {
+    let videos = rustframe::recovery::unfinished();
+    if videos.is_empty() {
+        return;
+    }
+    info!("Unfinished recordings: {:?}", videos);
+    let list: Vec<String> = videos.iter().map(|v| v.display().to_string()).collect();
+    let text = format!(
+        "These recordings were not finished (RustFrame or Windows stopped while recording):\n\n{}\n\n\
+         Repair them now? The repaired copies are saved next to them as .recovered.mp4.\n\
+         No keeps the files as they are.",
+        list.join("\n")
+    );
+    utils::confirm(&text, move |repair| {
+        if !repair {
+            videos.iter().for_each(|v| rustframe::recovery::forget(v));
+            return;
+        }
+        let mut report = Vec::new();
+        for video in &videos {
+            match rustframe::recovery::recover(video) {
+                Ok(out) => report.push(format!("Saved {}", out.display())),
+                Err(e) => {
+                    error!("Failed to recover {:?}: {}", video, e);
+                    report.push(format!("Could not repair {}: {}", video.display(), e));
+                }
+            }
+        }
+        utils::show_info(&report.join("\n"));
+    });
+}
+
+/// Start the Stream Deck server if it is enabled (errors are logged)
+fn open_stream_deck(settings: &AppSettings) -> Option<StreamDeckServer> {
+    if !settings.stream_deck {
+        return None;
+    }
+    match StreamDeckServer::start(settings.stream_deck_port, &settings.stream_deck_token) {
+        Ok(server) => Some(server),
+        Err(e) => {
+            error!("Stream Deck control unavailable: {:#}", e);
+            None
+        }
+    }
+}
+
+/// Start the OSC listener if OSC control is enabled (errors are logged)
+fn open_osc(settings: &AppSettings) -> Option<OscListener> {
+    if !settings.osc {
+        return None;
+    }
+    match OscListener::start(
+        &settings.osc_bind,
+        settings.osc_port,
+        &settings.osc_allowed_sources,
+    ) {
+        Ok(listener) => Some(listener),
+        Err(e) => {
+            error!("OSC control unavailable: {:#}", e);
+            None
+        }
+    }
+}
+
+/// Open the MIDI input if MIDI control is enabled (errors are logged)
+fn open_midi(settings: &AppSettings) -> Option<MidiInput> {
+    if !settings.midi_enabled {
+        return None;
+    }
+    match MidiInput::open(&settings.midi_device) {
+        Ok(midi) => Some(midi),
+        Err(e) => {
+            error!("MIDI control unavailable: {:#}", e);
+            None
+        }
+    }
+}
+
+/// `RustFrame record --region x,y,w,h --duration s --out file.mp4`
+/// Records like `rustframe-ctl record` (see record.rs); runs next to a
+/// RustFrame that is already running and never starts the app.
+fn record_headless(options: &[String]) -> Result<(), CliError> {
+    let args = rustframe::record::RecordArgs::parse(options)
+        .map_err(|e| CliError::usage(e, constants::record::USAGE))?;
+    // The capture thread settings of the app apply to the recording thread too
+    let settings = settings_file::load(false);
+    thread_priority::apply_to_current_thread(settings.thread_priority, &settings.cpu_cores);
+    let summary = rustframe::record(args.region, args.duration, &args.out, &args.options)
+        .map_err(|e| CliError::capture("Recording failed", &e))?;
+    // Post-capture actions (see post_actions.rs) - the process exits right after
+    let out = post_actions::run_and_wait(&settings.post_actions, &args.out);
+    println!(
+        "{} ({}x{}, {} frames{})",
+        out.display(),
+        summary.width,
+        summary.height,
+        summary.frames,
+        if summary.interrupted { ", stopped early" } else { "" }
+    );
+    if let Some(quality) = summary.quality {
+        println!("Quality: {}", quality);
+    }
+    Ok(())
+}
+
+/// `RustFrame shot --monitor 1 --out screen.png` (see shot.rs)
+fn shot_headless(options: &[String]) -> Result<(), CliError> {
+    let args = shot::ShotArgs::parse(options)
+        .map_err(|e| CliError::usage(e, constants::shot::USAGE))?;
+    let path = shot::take(&args)?;
+    println!("{}", path.display());
+    Ok(())
+}
+
+/// `RustFrame bench --region 0,0,1920,1080 --seconds 10` (see bench.rs)
+fn bench_headless(options: &[String]) -> Result<(), CliError> {
+    let args = bench::BenchArgs::parse(options)
+        .map_err(|e| CliError::usage(e, constants::bench::USAGE))?;
+    let report = bench::run(args.region, args.duration, args.cursor)
+        .map_err(|e| CliError::capture("Benchmark failed", &e))?;
+    if args.json {
+        let json = serde_json::to_string_pretty(&report)
+            .map_err(|e| CliError::new(FailureKind::Other, e.to_string()))?;
+        println!("{}", json);
+    } else {
+        println!("{}", report);
+    }
+    Ok(())
+}
+
+/// `RustFrame latency --samples 50` (see latency.rs)
+fn latency_headless(options: &[String]) -> Result<(), CliError> {
+    let args = latency::LatencyArgs::parse(options)
+        .map_err(|e| CliError::usage(e, constants::latency::USAGE))?;
+    let report =
+        latency::run(args.samples).map_err(|e| CliError::capture("Latency test failed", &e))?;
+    if args.json {
+        let json = serde_json::to_string_pretty(&report)
+            .map_err(|e| CliError::new(FailureKind::Other, e.to_string()))?;
+        println!("{}", json);
+    } else {
+        println!("{}", report);
+    }
+    Ok(())
+}
+
+/// `RustFrame diagnose [--out report.zip]` (see diagnose.rs)
+fn diagnose_headless(options: &[String]) -> Result<(), CliError> {
+    let out = match options {
+        [] => None,
+        [option, path] if option == "--out" => Some(std::path::PathBuf::from(path)),
+        _ => return Err(CliError::usage("Invalid options", constants::diagnose::USAGE)),
+    };
+    let report = diagnose::collect();
+    diagnose::write(&report, out.as_deref())
+        .map_err(|e| CliError::new(FailureKind::Output, e.to_string()))?;
+    if let Some(out) = out {
+        println!("{}", out.display());
+    }
+    Ok(())
+}
+
+/// `RustFrame check-config [path] [--json]` (see check_config.rs)
+fn check_config_headless(options: &[String]) -> Result<(), CliError> {
+    let mut json = false;
+    let mut path = None;
+    for option in options {
+        match option.as_str() {
+            "--json" => json = true,
+            other if !other.starts_with("--") && path.is_none() => {
+                path = Some(std::path::PathBuf::from(other))
+            }
+            other => {
+                return Err(CliError::usage(
+                    format!("Unknown option '{}'", other),
+                    constants::check_config::USAGE,
+                ))
+            }
+        }
+    }
+    let path = path
+        .or_else(settings_file::path)
+        .ok_or_else(|| CliError::new(FailureKind::Other, "%APPDATA% is not set"))?;
+    let problems = check_config::check_file(&path).map_err(|e| {
+        CliError::new(
+            FailureKind::Other,
+            format!("Failed to read {}: {}", path.display(), e),
+        )
+    })?;
+
+    if json {
+        let report = serde_json::json!({ "path": path, "problems": problems });
+        println!("{}", report);
+    } else if problems.is_empty() {
+        println!("{}: OK", path.display());
+    } else {
+        println!("{}: {} problem(s)", path.display(), problems.len());
+        for problem in &problems {
+            if problem.field.is_empty() {
+                println!("  {}", problem.message);
+            } else {
+                println!("  {}: {}", problem.field, problem.message);
+            }
+        }
+    }
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(CliError::new(
+            FailureKind::InvalidConfig,
+            format!("{} has {} problem(s)", path.display(), problems.len()),
+        ))
+    }
+}
+
+/// `RustFrame list-monitors [--json]` / `list-windows [--json]` (see list.rs)
+fn list_headless(options: &[String], print: fn(bool) -> Result<()>) -> Result<(), CliError> {
+    let json = match options {
+        [] => false,
+        [option] if option == "--json" => true,
+        _ => {
+            return Err(CliError::usage(
+                "Invalid options",
+                "Usage: RustFrame list-monitors|list-windows [--json] [--json-errors]",
+            ))
         }
+    };
+    print(json).map_err(|e| CliError::new(FailureKind::Other, e.to_string()))
+}
+
+/// Run a command line command and exit with its exit code (see cli.rs)
+fn run_headless(args: &[String], command: fn(&[String]) -> Result<(), CliError>) -> Result<()> {
+    utils::attach_console();
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+
+    let mut options = args[2..].to_vec();
+    let json_errors = cli::take_json_errors(&mut options);
+    if let Err(e) = command(&options) {
+        std::process::exit(e.report(json_errors).into());
     }
+    Ok(())
+}
+
+/// Tray label of the test pattern ("Off" while the screen is captured)
+fn pattern_label(pattern: Option<&TestPattern>) -> &'static str {
+    pattern.map_or("Off", |p| p.kind().label())
 }
 
 fn main() -> Result<()> {
-    // Initialize logging
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let args: Vec<String> = std::env::args().collect();
+
+    // RustFrame record / shot ...: capture to a file with no window, tray or GPU
+    match args.get(1).map(String::as_str) {
+        Some("record") => return run_headless(&args, record_headless),
+        Some("shot") => return run_headless(&args, shot_headless),
+        Some("bench") => return run_headless(&args, bench_headless),
+        Some("latency") => return run_headless(&args, latency_headless),
+        Some("diagnose") => return run_headless(&args, diagnose_headless),
+        Some("check-config") => return run_headless(&args, check_config_headless),
+        Some("list-monitors") => {
+            return run_headless(&args, |o| list_headless(o, list::print_monitors))
+        }
+        Some("list-windows") => {
+            return run_headless(&args, |o| list_headless(o, list::print_windows))
+        }
+        _ => {}
+    }
+
+    // Initialize logging (to config\rustframe.log in portable mode)
+    let mut logger =
+        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"));
+    let portable_dir = settings_file::portable_dir();
+    let log_file = portable_dir.as_ref().and_then(|dir| {
+        std::fs::create_dir_all(dir).ok()?;
+        std::fs::File::create(dir.join(constants::settings_file::PORTABLE_LOG_NAME)).ok()
+    });
+    if let Some(file) = log_file {
+        logger.target(env_logger::Target::Pipe(Box::new(file)));
+    }
+    logger.init();
 
     info!("RustFrame starting...");
+    if let Some(dir) = &portable_dir {
+        info!("Portable mode: settings and logs in {:?}", dir);
+    }
     info!("Using Windows.Graphics.Capture API (not GDI/BitBlt)");
 
     // Determine if we should run in development mode:
     // 1. Debug builds always run in DEV mode
     // 2. Release builds with --dev argument run in DEV mode
     // 3. Otherwise, run in PRODUCTION mode
-    let args: Vec<String> = std::env::args().collect();
     let has_dev_flag = args.iter().any(|arg| arg == "--dev" || arg == "-d");
+    // --minimized: start hidden in the tray (used by Start with Windows)
+    let minimized_flag = args.iter().any(|arg| arg == "--minimized");
+    // --control-stdio: commands on stdin, events on stdout (see control_stdio.rs)
+    let control_stdio = args.iter().any(|arg| arg == "--control-stdio");
+    // --daemon: tray only, windows created when needed (see ensure_windows)
+    let daemon = args.iter().any(|arg| arg == "--daemon");
+    if daemon {
+        info!("Daemon mode: the windows are created when needed");
+    }
+
+    // Started by a rustframe:// link: hand it to the running instance if there is one
+    // (not after a restart as administrator: that instance is the one exiting)
+    let link = deep_link::from_args(&args);
+    let restarted = args
+        .iter()
+        .any(|arg| arg == constants::elevation::RESTARTED_FLAG);
+    if let Some(url) = link.as_ref().filter(|_| !restarted) {
+        match instance::send_to_running(&[format!("{}{}", deep_link::LAUNCH_PREFIX, url)]) {
+            Ok(true) => {
+                info!("Passed {} to the running instance", url);
+                return Ok(());
+            }
+            Ok(false) => info!("No running instance - starting with {}", url),
+            Err(e) => warn!("{:#}", e),
+        }
+    }
 
     #[cfg(debug_assertions)]
     let dev_mode = true; // Always DEV mode in debug builds
@@ -900,16 +6435,33 @@ fn main() -> Result<()> {
         info!("--dev flag detected, forcing development mode");
     }
 
+    // Recordings cut off by a crash (a parent program gets no dialog)
+    if !control_stdio {
+        offer_recovery();
+    }
+
     // Create the winit event loop
     let event_loop = EventLoop::new()?;
     event_loop.set_control_flow(ControlFlow::Poll);
 
     // Create application state
-    let mut app = RustFrameApp::new(dev_mode);
+    let mut app = RustFrameApp::new(
+        dev_mode,
+        minimized_flag,
+        daemon,
+        control_stdio,
+        link,
+        event_loop.create_proxy(),
+    );
+
+    // Logging off / shutting down Windows exits like tray → Exit
+    session_end::start(event_loop.create_proxy());
 
     // Run the event loop
     event_loop.run_app(&mut app)?;
+    drop(app);
+    session_end::done();
 
-    info!("RustFrame shutting down");
+    info!("RustFrame shut down");
     Ok(())
 }
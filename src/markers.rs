@@ -0,0 +1,92 @@
+// markers.rs - Capture Markers
+//
+// A marker notes a moment of the capture session ("the demo starts here") so
+// it can be found again in a recording of the meeting. Markers are appended to
+// %APPDATA%\RustFrame\markers.txt, one line each, with the time since the
+// capture started:
+//
+//   # Capture started (Unix time 1760600000)
+//   00:03:12  Marker 1
+//   00:17:45  Marker 2  (Scene 2)
+
+use anyhow::{anyhow, Context, Result};
+use log::info;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::constants::markers;
+
+/// Markers of the current capture session
+pub struct MarkerLog {
+    /// When the capture started (None while not capturing)
+    started: Option<Instant>,
+    /// Markers dropped in this session
+    count: u32,
+}
+
+impl MarkerLog {
+    pub fn new() -> Self {
+        Self {
+            started: None,
+            count: 0,
+        }
+    }
+
+    /// A capture started - marker times count from now
+    pub fn start_session(&mut self) {
+        self.started = Some(Instant::now());
+        self.count = 0;
+    }
+
+    pub fn end_session(&mut self) {
+        self.started = None;
+    }
+
+    /// Append a marker to markers.txt (`note` is added after the number, e.g. the scene)
+    pub fn drop_marker(&mut self, note: Option<&str>) -> Result<()> {
+        let started = self
+            .started
+            .ok_or_else(|| anyhow!("Markers can only be dropped while capturing"))?;
+        let path = path().ok_or_else(|| anyhow!("%APPDATA% is not set"))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+        }
+
+        let mut text = String::new();
+        if self.count == 0 {
+            let unix_time = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            text.push_str(&format!("# Capture started (Unix time {})\n", unix_time));
+        }
+        self.count += 1;
+        let secs = started.elapsed().as_secs();
+        text.push_str(&format!(
+            "{:02}:{:02}:{:02}  Marker {}",
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60,
+            self.count
+        ));
+        if let Some(note) = note {
+            text.push_str(&format!("  ({})", note));
+        }
+        text.push('\n');
+
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(text.as_bytes()))
+            .with_context(|| format!("Failed to write {:?}", path))?;
+        info!("{}", text.trim_end().lines().last().unwrap_or_default());
+        Ok(())
+    }
+}
+
+/// Location of markers.txt (next to settings.json)
+fn path() -> Option<PathBuf> {
+    crate::settings_file::dir().map(|dir| dir.join(markers::FILE_NAME))
+}
@@ -0,0 +1,130 @@
+// measure.rs - On-Screen Ruler / Measurement Tool
+//
+// In measure mode a mouse drag over the overlay measures the captured content:
+// the bounding rectangle of the drag (width x height) and the straight-line
+// distance between its end points. Values are in device pixels, which are the
+// same pixels the capture produces - so a button measured here is exactly as
+// large in the recording.
+//
+// Measurements are local feedback only: they are drawn on the overlay window
+// (excluded from capture while measuring) and never composited into the output.
+
+use crate::bitmap_font::Canvas;
+use crate::constants::measure;
+use crate::drawing;
+use crate::text::{self, TextStyle};
+
+/// Current measurement (coordinates relative to the capture region)
+pub struct Measurement {
+    start: Option<(i32, i32)>,
+    end: (i32, i32),
+    dragging: bool,
+}
+
+impl Measurement {
+    pub fn new() -> Self {
+        Self {
+            start: None,
+            end: (0, 0),
+            dragging: false,
+        }
+    }
+
+    /// Start a new measurement at the given point (replaces the previous one)
+    pub fn begin(&mut self, x: i32, y: i32) {
+        self.start = Some((x, y));
+        self.end = (x, y);
+        self.dragging = true;
+    }
+
+    /// Move the end point while the mouse button is held
+    pub fn update(&mut self, x: i32, y: i32) {
+        if self.dragging {
+            self.end = (x, y);
+        }
+    }
+
+    /// Finish the drag; the measurement stays visible until the next one
+    pub fn end(&mut self) {
+        self.dragging = false;
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.dragging
+    }
+
+    pub fn clear(&mut self) {
+        self.start = None;
+        self.dragging = false;
+    }
+
+    /// Width and height of the measured rectangle, plus the diagonal distance
+    /// End points are inclusive, so a single click measures 1 x 1
+    pub fn size(&self) -> Option<(i32, i32, f64)> {
+        let (x0, y0) = self.start?;
+        let (x1, y1) = self.end;
+        let dx = (x1 - x0).abs();
+        let dy = (y1 - y0).abs();
+        let distance = ((dx * dx + dy * dy) as f64).sqrt();
+        Some((dx + 1, dy + 1, distance))
+    }
+
+    /// Human-readable readout, e.g. "320 x 180 px  |  367.1 px"
+    pub fn readout(&self) -> Option<String> {
+        let (w, h, distance) = self.size()?;
+        Some(format!("{} x {} px  |  {:.1} px", w, h, distance))
+    }
+
+    /// Draw the measurement at the given offset (the overlay border width)
+    /// `scale`: scale factor of the overlay window, for the readout text
+    pub fn draw(&self, canvas: &mut Canvas, offset_x: i32, offset_y: i32, scale: f32) {
+        let Some((x0, y0)) = self.start else {
+            return;
+        };
+        let (x0, y0) = (x0 + offset_x, y0 + offset_y);
+        let (x1, y1) = (self.end.0 + offset_x, self.end.1 + offset_y);
+
+        // STEP 1: Bounding rectangle and the distance line
+        drawing::draw_rect(canvas, x0, y0, x1, y1, 1, measure::BOX);
+        drawing::draw_line(canvas, x0, y0, x1, y1, 1, measure::LINE);
+
+        // STEP 2: End point markers
+        for (x, y) in [(x0, y0), (x1, y1)] {
+            drawing::fill_rect(
+                canvas,
+                x - measure::MARKER_SIZE / 2,
+                y - measure::MARKER_SIZE / 2,
+                measure::MARKER_SIZE,
+                measure::MARKER_SIZE,
+                measure::LINE,
+            );
+        }
+
+        // STEP 3: Readout label next to the end point, kept inside the canvas
+        if let Some(readout) = self.readout() {
+            let style = TextStyle {
+                color: measure::LABEL_TEXT,
+                size: text::scaled(measure::TEXT_SIZE, scale),
+                bold: false,
+            };
+            let padding = text::scaled(measure::LABEL_PADDING, scale);
+            let (text_w, text_h) = text::text_size(&readout, &style);
+            let label_w = text_w + padding * 2;
+            let label_h = text_h + padding * 2;
+
+            let mut lx = x1 + measure::LABEL_OFFSET;
+            let mut ly = y1 + measure::LABEL_OFFSET;
+            if lx + label_w > canvas.width {
+                lx = x1 - measure::LABEL_OFFSET - label_w;
+            }
+            if ly + label_h > canvas.height {
+                ly = y1 - measure::LABEL_OFFSET - label_h;
+            }
+            let lx = lx.max(0);
+            let ly = ly.max(0);
+
+            drawing::fill_rect(canvas, lx, ly, label_w, label_h, measure::LABEL_BACKGROUND);
+            text::draw_text(canvas, lx + padding, ly + padding, &readout, &style);
+        }
+    }
+}
@@ -0,0 +1,302 @@
+// midi.rs - MIDI Controller Input
+//
+// Lets MIDI pads and control surfaces (stream-deck-style controllers) drive
+// RustFrame. Notes and control changes (CCs) are mapped to actions:
+// - Switch to scene 1-4
+// - Pause (blank the output with the pause screen)
+// - Drop a marker (see markers.rs)
+// - Overlay opacity: a fader / knob sets how strongly drawn overlays
+//   (annotations, spotlight, grid, timer, ...) cover the frame
+//
+// The mapping is set in Settings → MIDI with a learn mode: select an action,
+// click Learn and press the pad or move the control. It is stored in
+// settings.json as action id → trigger ("scene_1": "note 36", "overlay_opacity":
+// "cc 7"); the MIDI channel is ignored.
+//
+// Messages arrive on a winmm callback thread and are passed to the app through
+// a channel, polled from about_to_wait like the hotkeys and the gamepad.
+
+use anyhow::Result;
+use log::info;
+use std::collections::BTreeMap;
+use std::sync::mpsc::{Receiver, Sender};
+
+use crate::constants::scene;
+
+/// A note or a control change (channel ignored)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MidiTrigger {
+    Note(u8),
+    Control(u8),
+}
+
+impl MidiTrigger {
+    /// Form stored in settings.json ("note 36", "cc 7")
+    pub fn id(self) -> String {
+        match self {
+            MidiTrigger::Note(note) => format!("note {}", note),
+            MidiTrigger::Control(cc) => format!("cc {}", cc),
+        }
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        let (kind, number) = text.trim().split_once(' ')?;
+        let number: u8 = number.trim().parse().ok().filter(|n| *n < 128)?;
+        match kind.to_lowercase().as_str() {
+            "note" => Some(MidiTrigger::Note(number)),
+            "cc" => Some(MidiTrigger::Control(number)),
+            _ => None,
+        }
+    }
+
+    /// Label for the settings list ("Note 36", "CC 7")
+    pub fn label(self) -> String {
+        match self {
+            MidiTrigger::Note(note) => format!("Note {}", note),
+            MidiTrigger::Control(cc) => format!("CC {}", cc),
+        }
+    }
+}
+
+/// A message from the controller
+#[derive(Debug, Clone, Copy)]
+pub struct MidiEvent {
+    pub trigger: MidiTrigger,
+    /// Note velocity or CC value (0-127)
+    pub value: u8,
+    /// The pad went down: a note on, or a CC crossing the middle upwards
+    /// (pads that send CCs send 127 / 0)
+    pub pressed: bool,
+}
+
+/// Actions a MIDI trigger can be mapped to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MidiAction {
+    SwitchScene(usize),
+    TogglePause,
+    DropMarker,
+    /// Continuous: the value sets the overlay opacity
+    OverlayOpacity,
+}
+
+impl MidiAction {
+    /// All actions, in the order shown in Settings
+    pub fn all() -> Vec<MidiAction> {
+        let mut actions: Vec<MidiAction> = (0..scene::SLOTS).map(MidiAction::SwitchScene).collect();
+        actions.extend([
+            MidiAction::TogglePause,
+            MidiAction::DropMarker,
+            MidiAction::OverlayOpacity,
+        ]);
+        actions
+    }
+
+    /// Stable identifier used in settings.json
+    pub fn id(self) -> String {
+        match self {
+            MidiAction::SwitchScene(slot) => format!("scene_{}", slot + 1),
+            MidiAction::TogglePause => "pause".to_string(),
+            MidiAction::DropMarker => "marker".to_string(),
+            MidiAction::OverlayOpacity => "overlay_opacity".to_string(),
+        }
+    }
+
+    /// Name shown in Settings → MIDI
+    pub fn label(self) -> String {
+        match self {
+            MidiAction::SwitchScene(slot) => format!("Switch to scene {}", slot + 1),
+            MidiAction::TogglePause => "Pause / mute output".to_string(),
+            MidiAction::DropMarker => "Drop marker".to_string(),
+            MidiAction::OverlayOpacity => "Overlay opacity (fader)".to_string(),
+        }
+    }
+}
+
+/// Trigger of every action (None = not mapped), from the stored mapping
+pub fn resolve(mappings: &BTreeMap<String, String>) -> Vec<(MidiAction, Option<MidiTrigger>)> {
+    MidiAction::all()
+        .into_iter()
+        .map(|action| {
+            let trigger = mappings
+                .get(&action.id())
+                .and_then(|text| MidiTrigger::parse(text));
+            (action, trigger)
+        })
+        .collect()
+}
+
+/// Stored form of a mapping (unmapped actions are left out)
+pub fn mappings(bindings: &[(MidiAction, Option<MidiTrigger>)]) -> BTreeMap<String, String> {
+    bindings
+        .iter()
+        .filter_map(|(action, trigger)| trigger.map(|t| (action.id(), t.id())))
+        .collect()
+}
+
+/// Names of the MIDI input devices
+#[cfg(windows)]
+pub fn device_names() -> Vec<String> {
+    use windows::Win32::Media::Audio::{midiInGetDevCapsW, midiInGetNumDevs, MIDIINCAPSW};
+
+    unsafe {
+        (0..midiInGetNumDevs())
+            .filter_map(|id| {
+                let mut caps = MIDIINCAPSW::default();
+                let size = std::mem::size_of::<MIDIINCAPSW>() as u32;
+                if midiInGetDevCapsW(id as usize, &mut caps, size) != 0 {
+                    return None;
+                }
+                // Copied out - the struct is packed
+                let name = caps.szPname;
+                let len = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+                Some(String::from_utf16_lossy(&name[..len]))
+            })
+            .collect()
+    }
+}
+
+#[cfg(not(windows))]
+pub fn device_names() -> Vec<String> {
+    Vec::new()
+}
+
+/// An open MIDI input device
+pub struct MidiInput {
+    #[cfg(windows)]
+    handle: windows::Win32::Media::Audio::HMIDIIN,
+    /// Sender handed to the winmm callback (freed after the device is closed)
+    #[cfg(windows)]
+    sender: *mut Sender<MidiEvent>,
+    receiver: Receiver<MidiEvent>,
+}
+
+impl MidiInput {
+    /// Open a device by name (empty = the first device)
+    #[cfg(windows)]
+    pub fn open(device: &str) -> Result<Self> {
+        use anyhow::anyhow;
+        use windows::Win32::Media::Audio::{midiInOpen, midiInStart, CALLBACK_FUNCTION, HMIDIIN};
+
+        let names = device_names();
+        let index = if device.trim().is_empty() {
+            (!names.is_empty()).then_some(0)
+        } else {
+            names
+                .iter()
+                .position(|name| name.eq_ignore_ascii_case(device.trim()))
+        }
+        .ok_or_else(|| match device.trim() {
+            "" => anyhow!("No MIDI input device found"),
+            name => anyhow!("MIDI input device {:?} not found", name),
+        })?;
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let sender = Box::into_raw(Box::new(sender));
+        let mut handle = HMIDIIN::default();
+        unsafe {
+            let result = midiInOpen(
+                &mut handle,
+                index as u32,
+                Some(midi_callback as *const () as usize),
+                Some(sender as usize),
+                CALLBACK_FUNCTION,
+            );
+            if result != 0 {
+                drop(Box::from_raw(sender));
+                return Err(anyhow!(
+                    "Failed to open MIDI device {:?} (error {}) - is another application using it?",
+                    names[index],
+                    result
+                ));
+            }
+            midiInStart(handle);
+        }
+        info!("MIDI input opened: {}", names[index]);
+        Ok(Self {
+            handle,
+            sender,
+            receiver,
+        })
+    }
+
+    #[cfg(not(windows))]
+    pub fn open(_device: &str) -> Result<Self> {
+        Err(anyhow::anyhow!("MIDI input is only supported on Windows"))
+    }
+
+    /// Next message from the controller, if any
+    pub fn poll(&self) -> Option<MidiEvent> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+impl Drop for MidiInput {
+    fn drop(&mut self) {
+        #[cfg(windows)]
+        unsafe {
+            use windows::Win32::Media::Audio::{midiInClose, midiInReset, midiInStop};
+
+            // After midiInClose returns the callback is no longer called, so
+            // the sender can be freed
+            midiInStop(self.handle);
+            midiInReset(self.handle);
+            midiInClose(self.handle);
+            drop(Box::from_raw(self.sender));
+            info!("MIDI input closed");
+        }
+    }
+}
+
+/// winmm callback (runs on a system thread): decode short messages and send
+/// notes and CCs to the app
+#[cfg(windows)]
+unsafe extern "system" fn midi_callback(
+    _handle: windows::Win32::Media::Audio::HMIDIIN,
+    message: u32,
+    instance: usize,
+    param1: usize,
+    _param2: usize,
+) {
+    use std::cell::Cell;
+    use windows::Win32::Media::MM_MIM_DATA;
+
+    // Last value of every CC, to detect pads crossing the middle (this
+    // callback thread only serves one device at a time)
+    thread_local! {
+        static CC_VALUES: Cell<[u8; 128]> = const { Cell::new([0; 128]) };
+    }
+
+    if message != MM_MIM_DATA || instance == 0 {
+        return;
+    }
+    let status = (param1 & 0xF0) as u8;
+    let data1 = ((param1 >> 8) & 0x7F) as u8;
+    let data2 = ((param1 >> 16) & 0x7F) as u8;
+
+    let event = match status {
+        // Note on (velocity 0 is a note off)
+        0x90 => MidiEvent {
+            trigger: MidiTrigger::Note(data1),
+            value: data2,
+            pressed: data2 > 0,
+        },
+        0xB0 => {
+            let previous = CC_VALUES.with(|values| {
+                let mut all = values.get();
+                let previous = all[data1 as usize];
+                all[data1 as usize] = data2;
+                values.set(all);
+                previous
+            });
+            MidiEvent {
+                trigger: MidiTrigger::Control(data1),
+                value: data2,
+                pressed: data2 >= 64 && previous < 64,
+            }
+        }
+        _ => return,
+    };
+
+    let sender = &*(instance as *const Sender<MidiEvent>);
+    let _ = sender.send(event);
+}
@@ -0,0 +1,359 @@
+// mouse_hook.rs - Low-Level Mouse Hook
+//
+// Records what the mouse buttons do anywhere on the desktop (the captured
+// application has the focus, not RustFrame), as gestures: a button press, the
+// moves while it is held and the release, each point with a timestamp. A
+// gesture that stays within a few pixels is a click, anything longer a drag.
+// Every gesture also knows the modifier keys held when the button went down
+// and whether it was a double click (same button again within the system
+// double-click time and distance).
+//
+// Consumers:
+// - ClickHighlight draws click rings and drag paths into the output
+//   (see click_highlight.rs)
+// - InputRecorder stores presses and releases with their timestamps, so
+//   they can be replayed later (see input_recording.rs)
+//
+// The WH_MOUSE_LL hook runs on its own thread with its own message loop, so a
+// busy render loop never delays the system-wide mouse. The gesture in
+// progress is shared through a mutex; finished gestures are sent through a
+// channel.
+
+use anyhow::Result;
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use crate::constants::mouse_hook;
+
+/// Mouse button of a gesture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+/// Modifier keys held when the button went down
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyModifiers {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub win: bool,
+}
+
+impl KeyModifiers {
+    pub fn any(self) -> bool {
+        self.ctrl || self.shift || self.alt || self.win
+    }
+
+    /// "Ctrl+Shift" (empty without modifiers)
+    pub fn label(self) -> String {
+        [
+            (self.ctrl, "Ctrl"),
+            (self.shift, "Shift"),
+            (self.alt, "Alt"),
+            (self.win, "Win"),
+        ]
+        .iter()
+        .filter(|(held, _)| *held)
+        .map(|(_, name)| *name)
+        .collect::<Vec<_>>()
+        .join("+")
+    }
+}
+
+/// One recorded position (screen coordinates)
+#[derive(Debug, Clone, Copy)]
+pub struct GesturePoint {
+    pub x: i32,
+    pub y: i32,
+    pub at: Instant,
+}
+
+/// Press, moves while held, release
+#[derive(Debug, Clone)]
+pub struct MouseGesture {
+    pub button: MouseButton,
+    pub modifiers: KeyModifiers,
+    /// 1 = single click, 2 = double click, 3 = triple click, ...
+    pub clicks: u32,
+    /// First point = press, last point = release (once released)
+    pub points: Vec<GesturePoint>,
+    pub released: bool,
+}
+
+impl MouseGesture {
+    fn new(button: MouseButton, point: GesturePoint, modifiers: KeyModifiers, clicks: u32) -> Self {
+        Self {
+            button,
+            modifiers,
+            clicks,
+            points: vec![point],
+            released: false,
+        }
+    }
+
+    pub fn start(&self) -> GesturePoint {
+        self.points[0]
+    }
+
+    pub fn end(&self) -> GesturePoint {
+        self.points[self.points.len() - 1]
+    }
+
+    /// Whether the mouse moved far enough while pressed to count as a drag
+    pub fn is_drag(&self) -> bool {
+        let start = self.start();
+        self.points.iter().any(|p| {
+            (p.x - start.x).abs() > mouse_hook::DRAG_THRESHOLD
+                || (p.y - start.y).abs() > mouse_hook::DRAG_THRESHOLD
+        })
+    }
+
+    /// Add a move, skipping points closer than MIN_POINT_DISTANCE to the last one
+    fn extend(&mut self, point: GesturePoint) {
+        let last = self.end();
+        let near = (point.x - last.x).abs() < mouse_hook::MIN_POINT_DISTANCE
+            && (point.y - last.y).abs() < mouse_hook::MIN_POINT_DISTANCE;
+        if !near && self.points.len() < mouse_hook::MAX_POINTS {
+            self.points.push(point);
+        }
+    }
+
+    fn release(&mut self, point: GesturePoint) {
+        self.points.push(point);
+        self.released = true;
+    }
+}
+
+/// The installed hook (removed when dropped)
+pub struct MouseHook {
+    /// Gesture while a button is held
+    current: Arc<Mutex<Option<MouseGesture>>>,
+    finished: Receiver<MouseGesture>,
+    #[cfg(windows)]
+    thread_id: u32,
+    #[cfg(windows)]
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl MouseHook {
+    /// Install the hook on its own thread
+    #[cfg(windows)]
+    pub fn install() -> Result<Self> {
+        let current = Arc::new(Mutex::new(None));
+        let (sender, finished) = std::sync::mpsc::channel();
+        let (ready_sender, ready) = std::sync::mpsc::channel();
+
+        let thread = {
+            let current = current.clone();
+            std::thread::spawn(move || hook_thread(current, sender, ready_sender))
+        };
+        let thread_id = ready
+            .recv()
+            .map_err(|_| anyhow::anyhow!("Mouse hook thread exited"))?
+            .map_err(|e| anyhow::anyhow!("Failed to install mouse hook: {}", e))?;
+        log::info!("Mouse hook installed");
+
+        Ok(Self {
+            current,
+            finished,
+            thread_id,
+            thread: Some(thread),
+        })
+    }
+
+    #[cfg(not(windows))]
+    pub fn install() -> Result<Self> {
+        Err(anyhow::anyhow!("Mouse hook is only supported on Windows"))
+    }
+
+    /// Gestures released since the last call, oldest first
+    pub fn take_finished(&self) -> Vec<MouseGesture> {
+        self.finished.try_iter().collect()
+    }
+
+    /// The gesture while a button is held
+    pub fn current(&self) -> Option<MouseGesture> {
+        self.current.lock().ok().and_then(|g| g.clone())
+    }
+}
+
+impl Drop for MouseHook {
+    fn drop(&mut self) {
+        #[cfg(windows)]
+        unsafe {
+            use windows::Win32::Foundation::{LPARAM, WPARAM};
+            use windows::Win32::UI::WindowsAndMessaging::{PostThreadMessageW, WM_QUIT};
+
+            let _ = PostThreadMessageW(self.thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+            log::info!("Mouse hook removed");
+        }
+    }
+}
+
+/// Hook state on the hook thread
+#[cfg(windows)]
+struct HookState {
+    current: Arc<Mutex<Option<MouseGesture>>>,
+    sender: std::sync::mpsc::Sender<MouseGesture>,
+    /// Button, press point and click count of the last click (double click detection)
+    last_click: Option<(MouseButton, GesturePoint, u32)>,
+}
+
+#[cfg(windows)]
+thread_local! {
+    static HOOK_STATE: std::cell::RefCell<Option<HookState>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Install the hook and pump messages until WM_QUIT (the hook is called from
+/// this thread's message loop)
+#[cfg(windows)]
+fn hook_thread(
+    current: Arc<Mutex<Option<MouseGesture>>>,
+    sender: std::sync::mpsc::Sender<MouseGesture>,
+    ready: std::sync::mpsc::Sender<windows::core::Result<u32>>,
+) {
+    use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+    use windows::Win32::System::Threading::GetCurrentThreadId;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetMessageW, SetWindowsHookExW, UnhookWindowsHookEx, MSG, WH_MOUSE_LL,
+    };
+
+    unsafe {
+        HOOK_STATE.with(|s| {
+            *s.borrow_mut() = Some(HookState {
+                current,
+                sender,
+                last_click: None,
+            })
+        });
+
+        let module = match GetModuleHandleW(None) {
+            Ok(module) => module,
+            Err(e) => {
+                let _ = ready.send(Err(e));
+                return;
+            }
+        };
+        let hook = match SetWindowsHookExW(WH_MOUSE_LL, Some(hook_proc), Some(module.into()), 0) {
+            Ok(hook) => hook,
+            Err(e) => {
+                let _ = ready.send(Err(e));
+                return;
+            }
+        };
+        let _ = ready.send(Ok(GetCurrentThreadId()));
+
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, None, 0, 0).as_bool() {}
+
+        let _ = UnhookWindowsHookEx(hook);
+    }
+}
+
+/// WH_MOUSE_LL callback: turn button and move messages into gestures
+#[cfg(windows)]
+unsafe extern "system" fn hook_proc(
+    code: i32,
+    wparam: windows::Win32::Foundation::WPARAM,
+    lparam: windows::Win32::Foundation::LPARAM,
+) -> windows::Win32::Foundation::LRESULT {
+    use windows::Win32::UI::WindowsAndMessaging::*;
+
+    if code == HC_ACTION as i32 {
+        let info = &*(lparam.0 as *const MSLLHOOKSTRUCT);
+        let point = GesturePoint {
+            x: info.pt.x,
+            y: info.pt.y,
+            at: Instant::now(),
+        };
+        let (button, down) = match wparam.0 as u32 {
+            WM_LBUTTONDOWN => (Some(MouseButton::Left), true),
+            WM_LBUTTONUP => (Some(MouseButton::Left), false),
+            WM_RBUTTONDOWN => (Some(MouseButton::Right), true),
+            WM_RBUTTONUP => (Some(MouseButton::Right), false),
+            WM_MBUTTONDOWN => (Some(MouseButton::Middle), true),
+            WM_MBUTTONUP => (Some(MouseButton::Middle), false),
+            _ => (None, false),
+        };
+
+        HOOK_STATE.with(|s| {
+            let mut state = s.borrow_mut();
+            let Some(state) = state.as_mut() else {
+                return;
+            };
+            let Ok(mut current) = state.current.lock() else {
+                return;
+            };
+            match (button, current.as_mut()) {
+                // Press: start a gesture (a second button while one is held is ignored)
+                (Some(button), None) if down => {
+                    let clicks = match state.last_click {
+                        Some((last, press, count))
+                            if last == button && is_double_click(press, point) =>
+                        {
+                            count + 1
+                        }
+                        _ => 1,
+                    };
+                    state.last_click = Some((button, point, clicks));
+                    *current = Some(MouseGesture::new(button, point, held_modifiers(), clicks));
+                }
+                // Release of the held button: the gesture is finished
+                (Some(button), Some(gesture)) if !down && gesture.button == button => {
+                    gesture.release(point);
+                    if gesture.is_drag() {
+                        // A drag is never the first half of a double click
+                        state.last_click = None;
+                    }
+                    if let Some(gesture) = current.take() {
+                        let _ = state.sender.send(gesture);
+                    }
+                }
+                (None, Some(gesture)) if wparam.0 as u32 == WM_MOUSEMOVE => gesture.extend(point),
+                _ => {}
+            }
+        });
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
+
+/// Modifier keys held right now
+#[cfg(windows)]
+fn held_modifiers() -> KeyModifiers {
+    use windows::Win32::UI::Input::KeyboardAndMouse::{
+        GetAsyncKeyState, VIRTUAL_KEY, VK_CONTROL, VK_LWIN, VK_MENU, VK_RWIN, VK_SHIFT,
+    };
+
+    let held = |key: VIRTUAL_KEY| unsafe { GetAsyncKeyState(key.0 as i32) as u16 & 0x8000 != 0 };
+    KeyModifiers {
+        ctrl: held(VK_CONTROL),
+        shift: held(VK_SHIFT),
+        alt: held(VK_MENU),
+        win: held(VK_LWIN) || held(VK_RWIN),
+    }
+}
+
+/// Whether a press continues the click at `previous` (within the system
+/// double-click time and distance)
+#[cfg(windows)]
+fn is_double_click(previous: GesturePoint, press: GesturePoint) -> bool {
+    use windows::Win32::UI::Input::KeyboardAndMouse::GetDoubleClickTime;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetSystemMetrics, SM_CXDOUBLECLK, SM_CYDOUBLECLK,
+    };
+
+    unsafe {
+        let within_time =
+            press.at.duration_since(previous.at).as_millis() <= GetDoubleClickTime() as u128;
+        let within_x = (press.x - previous.x).abs() <= GetSystemMetrics(SM_CXDOUBLECLK) / 2;
+        let within_y = (press.y - previous.y).abs() <= GetSystemMetrics(SM_CYDOUBLECLK) / 2;
+        within_time && within_x && within_y
+    }
+}
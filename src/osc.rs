@@ -0,0 +1,239 @@
+// osc.rs - OSC Control
+//
+// Listens for OSC messages (Open Sound Control over UDP) from lighting desks,
+// AV control surfaces, QLab, TouchOSC, Companion, ... and triggers the same
+// actions as the Stream Deck server (see stream_deck.rs):
+//
+//   /rustframe/record/start    start capture (with the countdown)
+//   /rustframe/record/stop     stop capture (or cancel the countdown)
+//   /rustframe/record/toggle   start / stop capture
+//   /rustframe/pause           pause screen on / off
+//   /rustframe/scene/2         switch to scene 2
+//   /rustframe/scene/next      next / previous saved scene
+//   /rustframe/marker          drop a marker
+//   /rustframe/action/<id>     any remote action id, e.g. /rustframe/action/scene_3
+//
+// Screenshots and uploads are not available over OSC: UDP has no
+// authentication, so a spoofed packet must not save or publish the screen.
+//
+// Arguments are ignored, except that a message whose first argument is the
+// number 0 does nothing - control surfaces send 1 when a button goes down
+// and 0 when it comes back up. Bundles are unpacked, their time tags ignored.
+//
+// The listener runs on its own thread; actions reach the app through a
+// channel polled from about_to_wait (like the hotkeys). It binds to 127.0.0.1
+// unless osc_bind says otherwise (e.g. 0.0.0.0 for controllers on other
+// devices), and only accepts packets from this computer and the addresses in
+// osc_allowed_sources.
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use std::collections::HashSet;
+use std::net::{IpAddr, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::constants::{osc, scene};
+use crate::stream_deck::RemoteAction;
+
+/// The OSC listener (stops when dropped)
+pub struct OscListener {
+    receiver: Receiver<RemoteAction>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl OscListener {
+    /// Listen on UDP `bind`:`port`, accepting packets from this computer and
+    /// the `allowed` IP addresses
+    pub fn start(bind: &str, port: u16, allowed: &[String]) -> Result<Self> {
+        let bind: IpAddr = bind
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid OSC address to listen on: {:?}", bind))?;
+        let allowed: Vec<IpAddr> = allowed
+            .iter()
+            .filter_map(|source| match source.trim().parse() {
+                Ok(ip) => Some(ip),
+                Err(_) => {
+                    warn!("Ignoring invalid OSC source address {:?}", source);
+                    None
+                }
+            })
+            .collect();
+        let socket = UdpSocket::bind((bind, port))
+            .with_context(|| format!("Failed to listen on UDP port {} - is it in use?", port))?;
+        // Reads time out so the thread notices when the listener stops
+        socket.set_read_timeout(Some(Duration::from_millis(osc::RECEIVE_TIMEOUT_MS)))?;
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let stop = stop.clone();
+            std::thread::spawn(move || receive_loop(socket, allowed, sender, stop))
+        };
+        info!("OSC listening on UDP {}:{}", bind, port);
+
+        Ok(Self {
+            receiver,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    /// Next action received, if any
+    pub fn poll(&self) -> Option<RemoteAction> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+impl Drop for OscListener {
+    fn drop(&mut self) {
+        // Wait for the socket to close, so the port can be opened again right away
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        info!("OSC listener stopped");
+    }
+}
+
+fn receive_loop(
+    socket: UdpSocket,
+    allowed: Vec<IpAddr>,
+    sender: Sender<RemoteAction>,
+    stop: Arc<AtomicBool>,
+) {
+    let mut buffer = vec![0u8; osc::MAX_PACKET];
+    // Refused senders are only logged once
+    let mut refused = HashSet::new();
+    while !stop.load(Ordering::Relaxed) {
+        let (len, from) = match socket.recv_from(&mut buffer) {
+            Ok(received) => received,
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                ) =>
+            {
+                continue
+            }
+            Err(e) => {
+                warn!("OSC: {}", e);
+                continue;
+            }
+        };
+        if !from.ip().is_loopback() && !allowed.contains(&from.ip()) {
+            if refused.insert(from.ip()) {
+                warn!(
+                    "OSC from {} ignored - add it to osc_allowed_sources to accept it",
+                    from.ip()
+                );
+            }
+            continue;
+        }
+
+        let mut messages = Vec::new();
+        collect_messages(&buffer[..len], &mut messages);
+        for (address, pressed) in messages {
+            match parse_address(&address) {
+                Some(action) if !allowed_over_osc(action) => {
+                    warn!("OSC from {}: {} is not available over OSC", from, address)
+                }
+                Some(action) if pressed => {
+                    info!("OSC action from {}: {} → {:?}", from, address, action);
+                    let _ = sender.send(action);
+                }
+                Some(_) => {}
+                None => info!("OSC from {}: unknown address {}", from, address),
+            }
+        }
+    }
+}
+
+/// Action for an address (None = not a RustFrame address)
+fn parse_address(address: &str) -> Option<RemoteAction> {
+    let path = address.strip_prefix(osc::ADDRESS_PREFIX)?;
+    let path = path.trim_matches('/').to_ascii_lowercase();
+    match path.as_str() {
+        "record/start" => Some(RemoteAction::Start),
+        "record/stop" => Some(RemoteAction::Stop),
+        "record/toggle" => Some(RemoteAction::StartStop),
+        "pause" => Some(RemoteAction::TogglePause),
+        "scene/next" => Some(RemoteAction::NextScene),
+        "scene/previous" => Some(RemoteAction::PreviousScene),
+        "marker" => Some(RemoteAction::DropMarker),
+        _ => {
+            if let Some(slot) = path.strip_prefix("scene/") {
+                let slot: usize = slot.parse().ok()?;
+                return (1..=scene::SLOTS)
+                    .contains(&slot)
+                    .then_some(RemoteAction::SwitchScene(slot - 1));
+            }
+            RemoteAction::from_id(path.strip_prefix("action/")?)
+        }
+    }
+}
+
+/// Whether an action may be triggered over OSC (no screenshots or uploads)
+fn allowed_over_osc(action: RemoteAction) -> bool {
+    !matches!(
+        action,
+        RemoteAction::Screenshot | RemoteAction::UploadScreenshot
+    )
+}
+
+/// Messages in a packet as (address, pressed) - bundles are unpacked,
+/// malformed parts skipped
+fn collect_messages(packet: &[u8], messages: &mut Vec<(String, bool)>) {
+    if let Some(mut rest) = packet.strip_prefix(b"#bundle\0") {
+        // 8-byte time tag, then elements prefixed with their size
+        rest = rest.get(8..).unwrap_or_default();
+        while rest.len() >= 4 {
+            let size = i32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]).max(0) as usize;
+            let Some(element) = rest.get(4..4 + size) else {
+                return;
+            };
+            collect_messages(element, messages);
+            rest = &rest[4 + size..];
+        }
+        return;
+    }
+
+    let Some((address, rest)) = read_string(packet) else {
+        return;
+    };
+    if !address.starts_with('/') {
+        return;
+    }
+    // Type tags are optional in old OSC senders - no tags = no arguments
+    let pressed = match read_string(rest) {
+        Some((tags, args)) => first_argument_pressed(tags.strip_prefix(',').unwrap_or(""), args),
+        None => true,
+    };
+    messages.push((address, pressed));
+}
+
+/// False only if the first argument is a number equal to 0 (button released)
+fn first_argument_pressed(tags: &str, args: &[u8]) -> bool {
+    let Some(word) = args.get(..4).map(|b| [b[0], b[1], b[2], b[3]]) else {
+        return true;
+    };
+    match tags.chars().next() {
+        Some('i') => i32::from_be_bytes(word) != 0,
+        Some('f') => f32::from_be_bytes(word) != 0.0,
+        Some('F') => false,
+        _ => true,
+    }
+}
+
+/// A null-terminated string padded to 4 bytes, and the bytes after it
+fn read_string(data: &[u8]) -> Option<(String, &[u8])> {
+    let end = data.iter().position(|&b| b == 0)?;
+    let text = std::str::from_utf8(&data[..end]).ok()?.to_string();
+    let padded = (end + 4) & !3;
+    Some((text, data.get(padded..).unwrap_or_default()))
+}
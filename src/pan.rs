@@ -0,0 +1,99 @@
+// pan.rs - Automated Pan Along a Path
+//
+// A wide dashboard or a long page is easier to show by moving the capture
+// region across it than by dragging the frame by hand, which jitters. The
+// path is a list of region positions (the top-left corner of the capture
+// region in screen coordinates), added with tray → Auto Pan → Add Current
+// Position. Starting the pan during a capture moves the region through the
+// points in order over the configured duration; the region keeps its size.
+//
+// HOW IT WORKS:
+// 1. The speed along the path is constant (long segments take longer than
+//    short ones), eased in at the start and out at the end
+// 2. Every event loop pass asks for the position at the current time; the
+//    region is only moved when the position changed by a whole pixel
+// 3. The pan ends at the last point, when the capture stops or with
+//    tray → Auto Pan → Stop Pan
+//
+// The points and the duration are saved in settings.json (`pan_path`,
+// `pan_seconds`).
+
+use std::time::{Duration, Instant};
+
+/// A running pan
+pub struct Pan {
+    points: Vec<(i32, i32)>,
+    /// Length of the path up to each point (pixels)
+    distances: Vec<f64>,
+    duration: Duration,
+    started: Instant,
+    /// Last position handed out by `step`
+    last: Option<(i32, i32)>,
+}
+
+impl Pan {
+    /// Start moving along `points` for `seconds`
+    /// None if the path has no length (fewer than two different points).
+    pub fn start(points: &[(i32, i32)], seconds: u32) -> Option<Self> {
+        let mut distances = Vec::with_capacity(points.len());
+        let mut total = 0.0;
+        for (i, point) in points.iter().enumerate() {
+            if i > 0 {
+                let (px, py) = points[i - 1];
+                total += ((point.0 - px) as f64).hypot((point.1 - py) as f64);
+            }
+            distances.push(total);
+        }
+        if total < 1.0 || seconds == 0 {
+            return None;
+        }
+        Some(Self {
+            points: points.to_vec(),
+            distances,
+            duration: Duration::from_secs(seconds as u64),
+            started: Instant::now(),
+            last: None,
+        })
+    }
+
+    /// The region position to move to now, if it changed since the last call
+    pub fn step(&mut self) -> Option<(i32, i32)> {
+        let position = self.position();
+        if self.last == Some(position) {
+            return None;
+        }
+        self.last = Some(position);
+        Some(position)
+    }
+
+    /// True once the last point is reached
+    pub fn is_finished(&self) -> bool {
+        self.started.elapsed() >= self.duration
+    }
+
+    /// Position on the path at the current time
+    fn position(&self) -> (i32, i32) {
+        let t = (self.started.elapsed().as_secs_f64() / self.duration.as_secs_f64()).min(1.0);
+        // Smoothstep: no jump in speed at the start and the end
+        let eased = t * t * (3.0 - 2.0 * t);
+        let total = self.distances[self.distances.len() - 1];
+        let distance = eased * total;
+
+        let segment = self
+            .distances
+            .windows(2)
+            .position(|d| distance <= d[1])
+            .unwrap_or(self.points.len() - 2);
+        let (start, end) = (self.points[segment], self.points[segment + 1]);
+        let length = self.distances[segment + 1] - self.distances[segment];
+        let along = if length > 0.0 {
+            (distance - self.distances[segment]) / length
+        } else {
+            1.0
+        };
+        (
+            start.0 + ((end.0 - start.0) as f64 * along).round() as i32,
+            start.1 + ((end.1 - start.1) as f64 * along).round() as i32,
+        )
+    }
+}
@@ -0,0 +1,220 @@
+// pause_screen.rs - Pause / "Be Right Back" Screen
+//
+// While output is paused, viewers get a placeholder instead of a frozen frame,
+// so it is obvious the interruption is intentional. The placeholder is one of:
+// 1. A "BE RIGHT BACK" card
+// 2. A solid color
+// 3. A custom image (pause.png next to the executable, like icon.ico)
+// The style is cycled from the tray menu and stored in settings.json.
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::path::PathBuf;
+
+use crate::bitmap_font::{self, Canvas, TextStyle};
+use crate::constants::pause_screen;
+use crate::drawing;
+use crate::image_source;
+
+/// What is shown while paused
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseStyle {
+    #[default]
+    Card,
+    SolidColor,
+    Image,
+}
+
+impl PauseStyle {
+    pub fn label(self) -> &'static str {
+        match self {
+            PauseStyle::Card => "Be Right Back Card",
+            PauseStyle::SolidColor => "Solid Color",
+            PauseStyle::Image => "Custom Image",
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            PauseStyle::Card => PauseStyle::SolidColor,
+            PauseStyle::SolidColor => PauseStyle::Image,
+            PauseStyle::Image => PauseStyle::Card,
+        }
+    }
+}
+
+/// Source image decoded to ARGB, plus a copy scaled to the last frame size
+struct PauseImage {
+    pixels: Vec<u32>,
+    width: u32,
+    height: u32,
+    /// (frame width, frame height, letterboxed pixels) - rebuilt when the frame size changes
+    scaled: RefCell<Option<(i32, i32, Vec<u32>)>>,
+}
+
+/// Pause state and placeholder configuration
+pub struct PauseScreen {
+    paused: bool,
+    style: PauseStyle,
+    image: Option<PauseImage>,
+    /// Bumped on every visible change so the renderer knows when to recomposite
+    revision: u64,
+}
+
+impl PauseScreen {
+    pub fn new(style: PauseStyle) -> Self {
+        Self {
+            paused: false,
+            style,
+            image: match style {
+                PauseStyle::Image => load_pause_image(),
+                _ => None,
+            },
+            revision: 0,
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        if self.paused != paused {
+            self.paused = paused;
+            self.revision += 1;
+        }
+    }
+
+    pub fn style(&self) -> PauseStyle {
+        self.style
+    }
+
+    /// Cycle Card -> Solid Color -> Custom Image
+    /// The custom image is (re)loaded from disk every time it is selected
+    pub fn cycle_style(&mut self) {
+        self.set_style(self.style.next());
+    }
+
+    pub fn set_style(&mut self, style: PauseStyle) {
+        if self.style == style {
+            return;
+        }
+        self.style = style;
+        if self.style == PauseStyle::Image {
+            self.image = load_pause_image();
+        }
+        self.revision += 1;
+    }
+
+    /// Revision counter - changes whenever the rendered result would change
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Replace the frame with the placeholder (no-op when not paused)
+    pub fn draw(&self, canvas: &mut Canvas) {
+        if !self.paused {
+            return;
+        }
+
+        match (self.style, &self.image) {
+            (PauseStyle::SolidColor, _) => canvas.pixels.fill(pause_screen::SOLID_COLOR),
+            (PauseStyle::Image, Some(image)) => image.draw(canvas),
+            // Missing image falls back to the card
+            (PauseStyle::Card, _) | (PauseStyle::Image, None) => draw_card(canvas),
+        }
+    }
+}
+
+impl PauseImage {
+    /// Draw the image letterboxed (aspect ratio kept) onto a black canvas
+    fn draw(&self, canvas: &mut Canvas) {
+        let mut scaled = self.scaled.borrow_mut();
+        let stale =
+            !matches!(&*scaled, Some((w, h, _)) if *w == canvas.width && *h == canvas.height);
+        if stale {
+            *scaled = Some((
+                canvas.width,
+                canvas.height,
+                self.letterbox(canvas.width, canvas.height),
+            ));
+        }
+        if let Some((_, _, pixels)) = &*scaled {
+            canvas.pixels.copy_from_slice(pixels);
+        }
+    }
+
+    fn letterbox(&self, width: i32, height: i32) -> Vec<u32> {
+        image_source::letterbox(
+            &self.pixels,
+            (self.width, self.height),
+            width,
+            height,
+            pause_screen::SOLID_COLOR,
+        )
+    }
+}
+
+/// Dark card with an accent bar and centered headline
+fn draw_card(canvas: &mut Canvas) {
+    canvas.pixels.fill(pause_screen::CARD_BACKGROUND);
+
+    let title = pause_screen::CARD_TITLE;
+    let scale = (canvas.width / (bitmap_font::text_width(title, 1) * 2)).max(1);
+    let text_w = bitmap_font::text_width(title, scale);
+    let text_h = bitmap_font::text_height(scale);
+    let x = (canvas.width - text_w) / 2;
+    let y = (canvas.height - text_h) / 2;
+
+    let bar_height = scale.max(2);
+    drawing::fill_rect(
+        canvas,
+        x,
+        y + text_h + text_h / 2,
+        text_w,
+        bar_height,
+        pause_screen::CARD_ACCENT,
+    );
+
+    let style = TextStyle {
+        color: pause_screen::CARD_TEXT,
+        scale,
+    };
+    bitmap_font::draw_text(canvas, x, y, title, &style);
+}
+
+/// Load pause.png from the executable directory, then the current directory
+fn load_pause_image() -> Option<PauseImage> {
+    let paths = [
+        std::env::current_exe()
+            .ok()
+            .and_then(|p| p.parent().map(|d| d.join(pause_screen::IMAGE_FILE))),
+        Some(PathBuf::from(pause_screen::IMAGE_FILE)),
+    ];
+
+    for path in paths.iter().flatten() {
+        if !path.exists() {
+            continue;
+        }
+        match image_source::load_argb(path) {
+            Ok((pixels, width, height)) => {
+                info!("Loaded pause image {:?} ({}x{})", path, width, height);
+                return Some(PauseImage {
+                    pixels,
+                    width,
+                    height,
+                    scaled: RefCell::new(None),
+                });
+            }
+            Err(e) => warn!("{:#}", e),
+        }
+    }
+
+    warn!(
+        "{} not found, the pause screen falls back to the card",
+        pause_screen::IMAGE_FILE
+    );
+    None
+}
@@ -0,0 +1,405 @@
+// plugin.rs - Plugins
+//
+// DLLs in %APPDATA%\RustFrame\plugins are loaded at startup, so features
+// like exotic streaming protocols can live outside this repository. Loading
+// is opt-in: only the DLLs turned on in Settings → Plugins (enabled_plugins)
+// are loaded, a DLL that was just copied into the folder stays off. A plugin
+// exports one C function:
+//
+//   const RustFramePlugin* rustframe_plugin_entry(uint32_t host_abi_version);
+//
+// and returns a static descriptor (see PluginDescriptor and
+// sdk/rustframe_plugin.h) with any of these hooks:
+//
+// - filter_frame  modifies every output frame in place, after the frame
+//                 filters (see filter.rs) - skipped while paused
+// - sink_frame    receives every output frame, e.g. to stream it somewhere
+// - panel_text    status text for the plugin's panel in Settings → Plugins
+// - shutdown      called before the DLL is unloaded
+//
+// Frames are one u32 per pixel, 0xAARRGGBB (BGRA in memory), rows without
+// padding. Hooks run on the UI thread - sinks that send frames over the
+// network should copy them and do the work on their own thread.
+//
+// The ABI is versioned: RustFrame passes ABI_VERSION to the entry point, a
+// plugin that does not support it returns null, and the descriptor states
+// the version it was built for. Only plugins built for the same version are
+// loaded. A hook that returns an error code is not called again; the error
+// is shown in the plugin manager.
+//
+// Every call into a plugin runs inside catch_unwind, so a panic that makes it
+// out of a hook counts as an error of that plugin. Plugins still run inside
+// RustFrame - a plugin that crashes takes RustFrame with it. Only install
+// plugins you trust.
+
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use std::cell::RefCell;
+use std::ffi::{c_char, c_void, CStr};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use crate::bitmap_font::Canvas;
+use crate::constants::plugin;
+
+/// Version of the plugin interface (bumped on incompatible changes)
+pub const ABI_VERSION: u32 = 1;
+
+/// What a plugin's entry point returns (must stay valid until shutdown)
+/// Hooks return 0 on success; the user pointer is passed back unchanged
+#[repr(C)]
+pub struct PluginDescriptor {
+    /// ABI_VERSION the plugin was built for
+    pub abi_version: u32,
+    /// Display name and version (UTF-8, null-terminated)
+    pub name: *const c_char,
+    pub version: *const c_char,
+    pub user: *mut c_void,
+    pub filter_frame: Option<
+        unsafe extern "C" fn(user: *mut c_void, pixels: *mut u32, width: u32, height: u32) -> i32,
+    >,
+    pub sink_frame: Option<
+        unsafe extern "C" fn(user: *mut c_void, pixels: *const u32, width: u32, height: u32) -> i32,
+    >,
+    /// Text shown in the plugin's panel (UTF-8, null-terminated, valid
+    /// until the next call)
+    pub panel_text: Option<unsafe extern "C" fn(user: *mut c_void) -> *const c_char>,
+    pub shutdown: Option<unsafe extern "C" fn(user: *mut c_void)>,
+}
+
+type EntryFn = unsafe extern "C" fn(host_abi_version: u32) -> *const PluginDescriptor;
+
+/// A plugin as listed in Settings → Plugins
+#[derive(Debug, Clone)]
+pub struct PluginInfo {
+    /// DLL file name (how the plugin is turned off in the settings)
+    pub file: String,
+    pub name: String,
+    pub version: String,
+    /// Hooks the plugin provides, e.g. "filter, sink"
+    pub hooks: String,
+    pub enabled: bool,
+    /// "Loaded", "Off" or the error
+    pub status: String,
+    /// Text of the plugin's panel
+    pub panel: String,
+}
+
+struct Plugin {
+    file: String,
+    name: String,
+    version: String,
+    library: *mut c_void,
+    descriptor: *const PluginDescriptor,
+    /// First error returned by a hook (the hooks are not called again)
+    error: RefCell<Option<String>>,
+}
+
+/// The loaded plugins (unloaded when dropped)
+pub struct PluginHost {
+    plugins: Vec<Plugin>,
+    /// Plugins that are turned off or failed to load
+    not_loaded: Vec<PluginInfo>,
+}
+
+impl PluginHost {
+    /// Load the DLLs of the plugins folder that are in `enabled`
+    pub fn load(enabled: &[String]) -> Self {
+        let mut host = Self {
+            plugins: Vec::new(),
+            not_loaded: Vec::new(),
+        };
+        let Some(dir) = dir() else {
+            return host;
+        };
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            return host;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("dll"))
+            })
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let file = path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            if !enabled.iter().any(|e| e.eq_ignore_ascii_case(&file)) {
+                host.not_loaded
+                    .push(PluginInfo::not_loaded(file, false, "Off"));
+                continue;
+            }
+            match Plugin::load(&path, file.clone()) {
+                Ok(plugin) => {
+                    info!(
+                        "Plugin loaded: {} {} ({})",
+                        plugin.name, plugin.version, plugin.file
+                    );
+                    host.plugins.push(plugin);
+                }
+                Err(e) => {
+                    warn!("Plugin {}: {:#}", file, e);
+                    let status = format!("Error: {:#}", e);
+                    host.not_loaded
+                        .push(PluginInfo::not_loaded(file, true, &status));
+                }
+            }
+        }
+        host
+    }
+
+    /// True if a plugin modifies the output frames
+    pub fn has_filters(&self) -> bool {
+        self.plugins
+            .iter()
+            .any(|p| p.is_ok() && p.descriptor().filter_frame.is_some())
+    }
+
+    /// Run the plugin frame filters
+    pub fn filter(&self, canvas: &mut Canvas) {
+        for plugin in self.plugins.iter().filter(|p| p.is_ok()) {
+            let descriptor = plugin.descriptor();
+            if let Some(filter_frame) = descriptor.filter_frame {
+                let result = plugin.call("filter_frame", || unsafe {
+                    filter_frame(
+                        descriptor.user,
+                        canvas.pixels.as_mut_ptr(),
+                        canvas.width as u32,
+                        canvas.height as u32,
+                    )
+                });
+                plugin.check("filter_frame", result);
+            }
+        }
+    }
+
+    /// Pass an output frame to the plugin sinks
+    pub fn sink(&self, pixels: &[u32], width: u32, height: u32) {
+        for plugin in self.plugins.iter().filter(|p| p.is_ok()) {
+            let descriptor = plugin.descriptor();
+            if let Some(sink_frame) = descriptor.sink_frame {
+                let result = plugin.call("sink_frame", || unsafe {
+                    sink_frame(descriptor.user, pixels.as_ptr(), width, height)
+                });
+                plugin.check("sink_frame", result);
+            }
+        }
+    }
+
+    /// Every plugin in the folder, loaded or not
+    pub fn infos(&self) -> Vec<PluginInfo> {
+        let mut infos: Vec<PluginInfo> = self.plugins.iter().map(Plugin::info).collect();
+        infos.extend(self.not_loaded.iter().cloned());
+        infos.sort_by_key(|info| info.file.to_lowercase());
+        infos
+    }
+}
+
+impl Drop for PluginHost {
+    fn drop(&mut self) {
+        for plugin in self.plugins.drain(..) {
+            let descriptor = plugin.descriptor();
+            if let Some(shutdown) = descriptor.shutdown {
+                plugin.call("shutdown", || unsafe { shutdown(descriptor.user) });
+            }
+            free_library(plugin.library);
+        }
+    }
+}
+
+impl Plugin {
+    fn load(path: &Path, file: String) -> Result<Self> {
+        let library = load_library(path)?;
+        let descriptor = match entry_point(library) {
+            Ok(entry) => match catch_unwind(|| unsafe { entry(ABI_VERSION) }) {
+                Ok(descriptor) => descriptor,
+                Err(_) => {
+                    free_library(library);
+                    return Err(anyhow!("rustframe_plugin_entry panicked"));
+                }
+            },
+            Err(e) => {
+                free_library(library);
+                return Err(e);
+            }
+        };
+        if descriptor.is_null() {
+            free_library(library);
+            return Err(anyhow!(
+                "Does not support plugin interface version {}",
+                ABI_VERSION
+            ));
+        }
+        let d = unsafe { &*descriptor };
+        if d.abi_version != ABI_VERSION {
+            free_library(library);
+            return Err(anyhow!(
+                "Built for plugin interface version {}, RustFrame has version {}",
+                d.abi_version,
+                ABI_VERSION
+            ));
+        }
+
+        let name = c_string(d.name).unwrap_or_else(|| file.clone());
+        let version = c_string(d.version).unwrap_or_default();
+        Ok(Self {
+            file,
+            name,
+            version,
+            library,
+            descriptor,
+            error: RefCell::new(None),
+        })
+    }
+
+    fn descriptor(&self) -> &PluginDescriptor {
+        // Valid until shutdown (checked for null in load)
+        unsafe { &*self.descriptor }
+    }
+
+    fn is_ok(&self) -> bool {
+        self.error.borrow().is_none()
+    }
+
+    /// Call into the plugin; a panic is remembered as an error (None)
+    fn call<T>(&self, hook: &str, f: impl FnOnce() -> T) -> Option<T> {
+        match catch_unwind(AssertUnwindSafe(f)) {
+            Ok(value) => Some(value),
+            Err(_) => {
+                self.fail(format!("{} panicked", hook));
+                None
+            }
+        }
+    }
+
+    /// Remember a failing hook (None = it panicked, already remembered)
+    fn check(&self, hook: &str, result: Option<i32>) {
+        if let Some(result) = result.filter(|&r| r != 0) {
+            self.fail(format!("{} returned error {}", hook, result));
+        }
+    }
+
+    /// Remember the first error; the hooks are not called again
+    fn fail(&self, message: String) {
+        if !self.is_ok() {
+            return;
+        }
+        warn!(
+            "Plugin {}: {} - its hooks are no longer called",
+            self.name, message
+        );
+        *self.error.borrow_mut() = Some(message);
+    }
+
+    fn info(&self) -> PluginInfo {
+        let d = self.descriptor();
+        let hooks: Vec<&str> = [
+            ("filter", d.filter_frame.is_some()),
+            ("sink", d.sink_frame.is_some()),
+            ("panel", d.panel_text.is_some()),
+        ]
+        .into_iter()
+        .filter(|&(_, present)| present)
+        .map(|(hook, _)| hook)
+        .collect();
+        let panel = match d.panel_text {
+            Some(panel_text) if self.is_ok() => self
+                .call("panel_text", || unsafe { panel_text(d.user) })
+                .and_then(c_string)
+                .unwrap_or_default(),
+            _ => String::new(),
+        };
+        PluginInfo {
+            file: self.file.clone(),
+            name: self.name.clone(),
+            version: self.version.clone(),
+            hooks: hooks.join(", "),
+            enabled: true,
+            status: match &*self.error.borrow() {
+                Some(error) => format!("Error: {}", error),
+                None => "Loaded".to_string(),
+            },
+            panel,
+        }
+    }
+}
+
+impl PluginInfo {
+    /// A plugin that is turned off (enabled = false) or failed to load
+    /// (stays on, so it is tried again at the next start)
+    fn not_loaded(file: String, enabled: bool, status: &str) -> Self {
+        Self {
+            name: file.clone(),
+            file,
+            version: String::new(),
+            hooks: String::new(),
+            enabled,
+            status: status.to_string(),
+            panel: String::new(),
+        }
+    }
+}
+
+/// %APPDATA%\RustFrame\plugins (None if %APPDATA% is not set)
+pub fn dir() -> Option<PathBuf> {
+    crate::settings_file::dir().map(|dir| dir.join(plugin::DIR_NAME))
+}
+
+/// Copy of a null-terminated UTF-8 string from a plugin
+fn c_string(text: *const c_char) -> Option<String> {
+    if text.is_null() {
+        return None;
+    }
+    Some(
+        unsafe { CStr::from_ptr(text) }
+            .to_string_lossy()
+            .into_owned(),
+    )
+}
+
+#[cfg(windows)]
+fn load_library(path: &Path) -> Result<*mut c_void> {
+    use crate::utils::wide_string;
+    use windows::core::PCWSTR;
+    use windows::Win32::System::LibraryLoader::LoadLibraryW;
+
+    let wide = wide_string(&path.to_string_lossy());
+    let module = unsafe { LoadLibraryW(PCWSTR(wide.as_ptr())) }
+        .map_err(|e| anyhow!("Failed to load the DLL: {}", e))?;
+    Ok(module.0)
+}
+
+#[cfg(not(windows))]
+fn load_library(_path: &Path) -> Result<*mut c_void> {
+    Err(anyhow!("Plugins are only supported on Windows"))
+}
+
+#[cfg(windows)]
+fn entry_point(library: *mut c_void) -> Result<EntryFn> {
+    use windows::core::PCSTR;
+    use windows::Win32::Foundation::HMODULE;
+    use windows::Win32::System::LibraryLoader::GetProcAddress;
+
+    let symbol = unsafe { GetProcAddress(HMODULE(library), PCSTR(plugin::ENTRY_SYMBOL.as_ptr())) }
+        .ok_or_else(|| anyhow!("Not a RustFrame plugin (no rustframe_plugin_entry export)"))?;
+    Ok(unsafe { std::mem::transmute::<unsafe extern "system" fn() -> isize, EntryFn>(symbol) })
+}
+
+#[cfg(not(windows))]
+fn entry_point(_library: *mut c_void) -> Result<EntryFn> {
+    Err(anyhow!("Plugins are only supported on Windows"))
+}
+
+#[cfg(windows)]
+fn free_library(library: *mut c_void) {
+    use windows::Win32::Foundation::{FreeLibrary, HMODULE};
+    let _ = unsafe { FreeLibrary(HMODULE(library)) };
+}
+
+#[cfg(not(windows))]
+fn free_library(_library: *mut c_void) {}
@@ -0,0 +1,247 @@
+// post_actions.rs - Actions After a Capture
+//
+// RustFrame's window is usually recorded by OBS, Teams or a screen recorder,
+// so the files the app writes are screenshots; `RustFrame record` writes MP4
+// files. After each screenshot and each `record` run these optional steps
+// run, configured in settings.json:
+//
+//   "post_actions": {
+//     "move_to": "D:\\Captures",
+//     "copy_path": true,
+//     "open_folder": false,
+//     "command": "\"C:\\Tools\\upload.exe\" --public {path}"
+//   }
+//
+// In this order:
+// - move_to      move the file into this folder (created if needed); a file
+//                of the same name there is never replaced - the moved file
+//                gets a free name like "shot (2).png"
+// - copy_path    put the file's path on the clipboard
+// - open_folder  open the folder in Explorer with the file selected
+// - command      run a command; {path} is replaced by the quoted file path
+//                (without {path}, the path is added at the end). The program
+//                is started directly, not through cmd.exe, so a "%" in a
+//                file name is never expanded as a variable
+//
+// Screenshots written to an explicit file (rustframe-ctl screenshot --out,
+// rustframe://screenshot?out=...) are left alone - the caller chose where
+// they go. For screenshots the steps run on a background thread, `record`
+// waits for them before it exits; failures are logged.
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// What to do with a file after it was written (settings.json)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PostActions {
+    /// Folder the file is moved to (empty = leave it)
+    pub move_to: String,
+    /// Copy the file path to the clipboard
+    pub copy_path: bool,
+    /// Open the containing folder with the file selected
+    pub open_folder: bool,
+    /// Command run with the file path (empty = none)
+    pub command: String,
+}
+
+impl PostActions {
+    /// Whether any action is configured
+    pub fn is_empty(&self) -> bool {
+        self.move_to.trim().is_empty()
+            && !self.copy_path
+            && !self.open_folder
+            && self.command.trim().is_empty()
+    }
+}
+
+/// Run the configured actions for `path` in the background
+/// Returns where the file ends up once they ran
+pub fn run(actions: &PostActions, path: &Path) -> PathBuf {
+    let target = destination(actions, path);
+    if !actions.is_empty() {
+        let actions = actions.clone();
+        let path = path.to_path_buf();
+        let target = target.clone();
+        std::thread::spawn(move || apply(&actions, path, target));
+    }
+    target
+}
+
+/// Run the configured actions for `path` and wait for them
+/// Returns where the file ended up
+pub fn run_and_wait(actions: &PostActions, path: &Path) -> PathBuf {
+    if actions.is_empty() {
+        return path.to_path_buf();
+    }
+    apply(actions, path.to_path_buf(), destination(actions, path))
+}
+
+fn apply(actions: &PostActions, path: PathBuf, target: PathBuf) -> PathBuf {
+    let path = if target == path {
+        path
+    } else {
+        match move_file(&path, &target) {
+            Ok(()) => target,
+            Err(e) => {
+                warn!("{:#}", e);
+                path
+            }
+        }
+    };
+    if actions.copy_path {
+        match copy_path(&path) {
+            Ok(()) => info!("Copied {:?} to the clipboard", path),
+            Err(e) => warn!("{:#}", e),
+        }
+    }
+    if actions.open_folder {
+        if let Err(e) = open_folder(&path) {
+            warn!("{:#}", e);
+        }
+    }
+    if !actions.command.trim().is_empty() {
+        if let Err(e) = run_command(actions.command.trim(), &path) {
+            warn!("{:#}", e);
+        }
+    }
+    path
+}
+
+/// Where the file written to `path` is moved: move_to with the same file
+/// name, or "name (2).ext", ... if that is taken
+fn destination(actions: &PostActions, path: &Path) -> PathBuf {
+    let dir = match actions.move_to.trim() {
+        "" => return path.to_path_buf(),
+        dir => Path::new(dir),
+    };
+    let target = dir.join(path.file_name().unwrap_or_default());
+    if target == path || !target.exists() {
+        return target;
+    }
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    (2..)
+        .map(|n| dir.join(format!("{} ({}){}", stem, n, extension)))
+        .find(|candidate| !candidate.exists())
+        .unwrap_or(target)
+}
+
+/// Move a file; never replaces an existing file at `target`
+fn move_file(path: &Path, target: &Path) -> Result<()> {
+    if let Some(dir) = target.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    }
+    // rename replaces an existing file on Windows - check first
+    if target.exists() {
+        return Err(anyhow::anyhow!(
+            "Not moving {:?}: {:?} already exists",
+            path,
+            target
+        ));
+    }
+    // rename fails across drives - copy and delete instead (create_new keeps
+    // a file that appeared in the meantime)
+    if std::fs::rename(path, target).is_err() {
+        let mut source =
+            std::fs::File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+        let mut copy = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(target)
+            .with_context(|| format!("Failed to move {:?} to {:?}", path, target))?;
+        std::io::copy(&mut source, &mut copy)
+            .with_context(|| format!("Failed to move {:?} to {:?}", path, target))?;
+        drop(source);
+        std::fs::remove_file(path).with_context(|| format!("Failed to delete {:?}", path))?;
+    }
+    info!("Moved {:?} to {:?}", path, target);
+    Ok(())
+}
+
+#[cfg(windows)]
+fn copy_path(path: &Path) -> Result<()> {
+    crate::utils::set_clipboard_text(&path.to_string_lossy())
+}
+
+#[cfg(not(windows))]
+fn copy_path(_path: &Path) -> Result<()> {
+    Err(anyhow::anyhow!(
+        "Copying to the clipboard is only supported on Windows"
+    ))
+}
+
+/// Show the file in Explorer
+#[cfg(windows)]
+fn open_folder(path: &Path) -> Result<()> {
+    use std::os::windows::process::CommandExt;
+
+    std::process::Command::new("explorer.exe")
+        .raw_arg(format!("/select,\"{}\"", path.display()))
+        .spawn()
+        .context("Failed to start Explorer")?;
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn open_folder(path: &Path) -> Result<()> {
+    Err(anyhow::anyhow!(
+        "Opening the folder of {:?} is only supported on Windows",
+        path
+    ))
+}
+
+/// Start the user's command line (without a console window)
+/// The program is started directly - through cmd.exe, %...% in the file path
+/// would be expanded as environment variables.
+#[cfg(windows)]
+fn run_command(command: &str, path: &Path) -> Result<()> {
+    use std::os::windows::process::CommandExt;
+    use windows::Win32::System::Threading::CREATE_NO_WINDOW;
+
+    let (program, arguments) = split_program(command);
+    let quoted = format!("\"{}\"", path.display());
+    let arguments = if command.contains("{path}") {
+        arguments.replace("{path}", &quoted)
+    } else {
+        format!("{} {}", arguments, quoted)
+    };
+    std::process::Command::new(program)
+        .raw_arg(arguments.trim())
+        .creation_flags(CREATE_NO_WINDOW.0)
+        .spawn()
+        .with_context(|| format!("Failed to run {}", program))?;
+    info!("Ran {} {}", program, arguments.trim());
+    Ok(())
+}
+
+/// Program (quotes removed) and the rest of a command line
+#[cfg(windows)]
+fn split_program(command: &str) -> (&str, &str) {
+    let command = command.trim();
+    match command.strip_prefix('"') {
+        Some(rest) => match rest.find('"') {
+            Some(end) => (&rest[..end], &rest[end + 1..]),
+            None => (rest, ""),
+        },
+        None => command
+            .split_once(char::is_whitespace)
+            .unwrap_or((command, "")),
+    }
+}
+
+#[cfg(not(windows))]
+fn run_command(command: &str, _path: &Path) -> Result<()> {
+    Err(anyhow::anyhow!(
+        "Running {} is only supported on Windows",
+        command
+    ))
+}
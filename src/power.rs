@@ -0,0 +1,77 @@
+// power.rs - Low-Power Mode on Battery
+//
+// A two-hour capture on a laptop should not drain the battery at the full
+// frame rate. While the computer runs on battery, the output frame rate is
+// capped at `low_power_fps` (settings.json, default 30) and the idle frame
+// rate of static content (see adaptive frame rate in main.rs) starts sooner.
+// Plugging the charger in brings the normal settings back.
+//
+// "low_power_on_battery": false turns it off. The tray tooltip shows
+// "Low power" while it is active.
+//
+// The power source is read with GetSystemPowerStatus every few seconds;
+// desktops without a battery always report AC power.
+
+use log::info;
+use std::time::Instant;
+
+use crate::constants::power;
+
+/// Watches whether the computer runs on battery
+pub struct PowerWatcher {
+    on_battery: bool,
+    last_check: Option<Instant>,
+}
+
+impl PowerWatcher {
+    pub fn new() -> Self {
+        Self {
+            on_battery: false,
+            last_check: None,
+        }
+    }
+
+    /// Whether the computer ran on battery at the last check
+    pub fn on_battery(&self) -> bool {
+        self.on_battery
+    }
+
+    /// Check the power source at the poll interval
+    /// Returns true when it changed since the last check.
+    pub fn update(&mut self) -> bool {
+        if self
+            .last_check
+            .is_some_and(|t| t.elapsed().as_millis() < power::POLL_MS as u128)
+        {
+            return false;
+        }
+        self.last_check = Some(Instant::now());
+
+        let on_battery = on_battery();
+        if on_battery == self.on_battery {
+            return false;
+        }
+        self.on_battery = on_battery;
+        if on_battery {
+            info!("Running on battery");
+        } else {
+            info!("Running on AC power");
+        }
+        true
+    }
+}
+
+/// Whether the computer runs on battery right now (false if unknown)
+#[cfg(windows)]
+fn on_battery() -> bool {
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status = SYSTEM_POWER_STATUS::default();
+    // ACLineStatus: 0 = offline (battery), 1 = online, 255 = unknown
+    unsafe { GetSystemPowerStatus(&mut status).is_ok() && status.ACLineStatus == 0 }
+}
+
+#[cfg(not(windows))]
+fn on_battery() -> bool {
+    false
+}
@@ -0,0 +1,551 @@
+// privacy.rs - Privacy Blur / Pixelate Regions
+//
+// Sub-rectangles of the capture region whose content is hidden in every output
+// frame - for e-mail addresses, tokens and other things that must not leak while
+// screen sharing. Each region is either blurred or pixelated.
+//
+// Regions are edited in redact mode on the overlay window: drag on empty space
+// to create a region, drag an existing region to move it, right-click to delete.
+// Coordinates are relative to the top-left corner of the capture region, so
+// regions stay attached to the region when the overlay is moved.
+//
+// For things that pop up unexpectedly during a live session there is also a
+// censor brush: painted areas are pixelated in every following frame, and the
+// eraser removes them again. Brush strokes are stored as BLOCK_SIZE cells
+// aligned to the pixelate grid, so each cell becomes exactly one block.
+//
+// Redaction is applied first in the overlay chain, so nothing drawn later
+// (spotlight, magnifier) can reveal the original pixels. It deliberately runs
+// on the CPU in the compositor rather than as a GPU pass: the composed frame is
+// what screenshots, recordings and every output read, and a shader-only blur
+// would leave the original pixels in all of them.
+//
+// The rectangles are part of a scene (the saved region presets, see scene.rs),
+// so switching scenes brings back the regions drawn for that setup. Brush
+// strokes are for the current session only.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::bitmap_font::Canvas;
+use crate::constants::privacy;
+use crate::drawing;
+use crate::text::{self, TextStyle};
+
+/// How a region hides its content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RedactStyle {
+    Blur,
+    Pixelate,
+}
+
+impl RedactStyle {
+    /// Short label for logs and the overlay
+    pub fn label(self) -> &'static str {
+        match self {
+            RedactStyle::Blur => "Blur",
+            RedactStyle::Pixelate => "Pixelate",
+        }
+    }
+}
+
+/// What a mouse drag does in redact mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedactTool {
+    /// Create and move rectangular regions
+    Regions,
+    /// Paint pixelated areas
+    Brush,
+    /// Remove painted areas
+    Eraser,
+}
+
+impl RedactTool {
+    /// Short label for logs and the overlay
+    pub fn label(self) -> &'static str {
+        match self {
+            RedactTool::Regions => "Regions",
+            RedactTool::Brush => "Brush",
+            RedactTool::Eraser => "Eraser",
+        }
+    }
+}
+
+/// A redacted rectangle (capture-region coordinates)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedactRegion {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub style: RedactStyle,
+}
+
+impl RedactRegion {
+    fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+}
+
+/// Mouse drag in progress
+#[derive(Debug, Clone, Copy)]
+enum Drag {
+    /// Creating a new region from `start` to the cursor
+    Create { start: (i32, i32) },
+    /// Moving region `index`; `grab` is the cursor offset inside the region
+    Move { index: usize, grab: (i32, i32) },
+    /// Painting with the brush or eraser; `last` is the previous cursor position
+    Paint { last: (i32, i32) },
+}
+
+/// All privacy regions for the session
+pub struct PrivacyRegions {
+    regions: Vec<RedactRegion>,
+    /// Style given to newly created regions
+    default_style: RedactStyle,
+    drag: Option<Drag>,
+    /// Region the next style toggle / delete applies to
+    selected: Option<usize>,
+    /// Current mouse tool
+    tool: RedactTool,
+    /// Painted cells (cell coordinates, i.e. pixels / BLOCK_SIZE)
+    brush: HashSet<(i32, i32)>,
+    /// Brush / eraser radius in pixels
+    brush_radius: i32,
+    /// Bumped on every visible change so the renderer knows when to recomposite
+    revision: u64,
+}
+
+impl PrivacyRegions {
+    pub fn new() -> Self {
+        Self {
+            regions: Vec::new(),
+            default_style: RedactStyle::Pixelate,
+            drag: None,
+            selected: None,
+            tool: RedactTool::Regions,
+            brush: HashSet::new(),
+            brush_radius: privacy::BRUSH_RADIUS,
+            revision: 0,
+        }
+    }
+
+    /// True if nothing is redacted (no regions and no painted areas)
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty() && self.brush.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    /// The rectangles (for scenes)
+    pub fn regions(&self) -> &[RedactRegion] {
+        &self.regions
+    }
+
+    /// Replace the rectangles (scene switch); painted areas stay
+    pub fn set_regions(&mut self, regions: Vec<RedactRegion>) {
+        if self.regions != regions {
+            self.regions = regions;
+            self.selected = None;
+            self.drag = None;
+            self.revision += 1;
+        }
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Topmost region under the point (regions created later are on top)
+    fn region_at(&self, x: i32, y: i32) -> Option<usize> {
+        self.regions.iter().rposition(|r| r.contains(x, y))
+    }
+
+    pub fn tool(&self) -> RedactTool {
+        self.tool
+    }
+
+    /// Switch to the given tool, or back to regions if it is already active
+    pub fn toggle_tool(&mut self, tool: RedactTool) {
+        self.tool = if self.tool == tool {
+            RedactTool::Regions
+        } else {
+            tool
+        };
+    }
+
+    /// Grow (positive) or shrink (negative) the brush
+    pub fn adjust_brush(&mut self, steps: i32) {
+        self.brush_radius = (self.brush_radius + steps * privacy::BRUSH_STEP)
+            .clamp(privacy::BRUSH_MIN_RADIUS, privacy::BRUSH_MAX_RADIUS);
+    }
+
+    /// Mouse button pressed: paint / erase, or grab the region under the cursor
+    /// or start a new one
+    pub fn begin_drag(&mut self, x: i32, y: i32) {
+        if self.tool != RedactTool::Regions {
+            self.paint(x, y);
+            self.drag = Some(Drag::Paint { last: (x, y) });
+            return;
+        }
+
+        self.drag = Some(match self.region_at(x, y) {
+            Some(index) => {
+                let r = self.regions[index];
+                self.selected = Some(index);
+                Drag::Move {
+                    index,
+                    grab: (x - r.x, y - r.y),
+                }
+            }
+            None => {
+                self.regions.push(RedactRegion {
+                    x,
+                    y,
+                    width: 0,
+                    height: 0,
+                    style: self.default_style,
+                });
+                self.selected = Some(self.regions.len() - 1);
+                Drag::Create { start: (x, y) }
+            }
+        });
+        self.revision += 1;
+    }
+
+    /// Mouse moved with the button held
+    pub fn update_drag(&mut self, x: i32, y: i32) {
+        match self.drag {
+            Some(Drag::Create { start }) => {
+                if let Some(r) = self.regions.last_mut() {
+                    r.x = start.0.min(x);
+                    r.y = start.1.min(y);
+                    r.width = (x - start.0).abs();
+                    r.height = (y - start.1).abs();
+                    self.revision += 1;
+                }
+            }
+            Some(Drag::Move { index, grab }) => {
+                if let Some(r) = self.regions.get_mut(index) {
+                    r.x = x - grab.0;
+                    r.y = y - grab.1;
+                    self.revision += 1;
+                }
+            }
+            Some(Drag::Paint { last }) => {
+                // Fill the gap between mouse events so fast strokes stay continuous
+                let (dx, dy) = (x - last.0, y - last.1);
+                let spacing = (self.brush_radius / 2).max(1);
+                let steps = (dx.abs().max(dy.abs()) / spacing).max(1);
+                for i in 1..=steps {
+                    self.paint(last.0 + dx * i / steps, last.1 + dy * i / steps);
+                }
+                self.drag = Some(Drag::Paint { last: (x, y) });
+            }
+            None => {}
+        }
+    }
+
+    /// Mouse button released; regions too small to matter are discarded
+    pub fn end_drag(&mut self) {
+        if let Some(Drag::Create { .. }) = self.drag.take() {
+            if let Some(r) = self.regions.last() {
+                if r.width < privacy::MIN_SIZE || r.height < privacy::MIN_SIZE {
+                    self.regions.pop();
+                    self.selected = None;
+                    self.revision += 1;
+                }
+            }
+        }
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    /// Paint (or erase) every cell whose center lies within the brush radius
+    fn paint(&mut self, x: i32, y: i32) {
+        let cell = privacy::BLOCK_SIZE;
+        let radius = self.brush_radius;
+        let erase = self.tool == RedactTool::Eraser;
+        let mut changed = false;
+
+        for cy in (y - radius).div_euclid(cell)..=(y + radius).div_euclid(cell) {
+            for cx in (x - radius).div_euclid(cell)..=(x + radius).div_euclid(cell) {
+                let dx = cx * cell + cell / 2 - x;
+                let dy = cy * cell + cell / 2 - y;
+                if dx * dx + dy * dy > radius * radius {
+                    continue;
+                }
+                changed |= if erase {
+                    self.brush.remove(&(cx, cy))
+                } else {
+                    self.brush.insert((cx, cy))
+                };
+            }
+        }
+        if changed {
+            self.revision += 1;
+        }
+    }
+
+    /// Delete the region under the point (right-click)
+    pub fn remove_at(&mut self, x: i32, y: i32) {
+        if let Some(index) = self.region_at(x, y) {
+            self.regions.remove(index);
+            self.selected = None;
+            self.revision += 1;
+        }
+    }
+
+    /// Delete the selected region
+    pub fn remove_selected(&mut self) {
+        if let Some(index) = self.selected.take() {
+            if index < self.regions.len() {
+                self.regions.remove(index);
+                self.revision += 1;
+            }
+        }
+    }
+
+    /// Switch the selected region between blur and pixelate
+    /// The new style also becomes the default for new regions
+    pub fn toggle_selected_style(&mut self) {
+        let style = match self.default_style {
+            RedactStyle::Blur => RedactStyle::Pixelate,
+            RedactStyle::Pixelate => RedactStyle::Blur,
+        };
+        self.default_style = style;
+        if let Some(r) = self.selected.and_then(|i| self.regions.get_mut(i)) {
+            r.style = style;
+        }
+        self.revision += 1;
+    }
+
+    /// Remove all regions and painted areas
+    pub fn clear(&mut self) {
+        if !self.is_empty() {
+            self.regions.clear();
+            self.brush.clear();
+            self.selected = None;
+            self.drag = None;
+            self.revision += 1;
+        }
+    }
+
+    /// Redact every region and painted area in the captured frame
+    pub fn apply(&self, canvas: &mut Canvas) {
+        let cell = privacy::BLOCK_SIZE;
+        for &(cx, cy) in &self.brush {
+            let x0 = (cx * cell).max(0);
+            let y0 = (cy * cell).max(0);
+            let x1 = (cx * cell + cell).min(canvas.width);
+            let y1 = (cy * cell + cell).min(canvas.height);
+            if x1 > x0 && y1 > y0 {
+                pixelate(canvas, x0, y0, x1, y1);
+            }
+        }
+
+        for r in &self.regions {
+            // Clip to the frame
+            let x0 = r.x.max(0);
+            let y0 = r.y.max(0);
+            let x1 = (r.x + r.width).min(canvas.width);
+            let y1 = (r.y + r.height).min(canvas.height);
+            if x1 <= x0 || y1 <= y0 {
+                continue;
+            }
+
+            match r.style {
+                RedactStyle::Pixelate => pixelate(canvas, x0, y0, x1, y1),
+                RedactStyle::Blur => {
+                    // Three box blur passes approximate a gaussian blur
+                    for _ in 0..3 {
+                        box_blur(canvas, x0, y0, x1, y1);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Draw region outlines, painted areas and the active tool on the overlay
+    /// (redact mode feedback); `scale`: scale factor of the overlay window
+    pub fn draw_outlines(&self, canvas: &mut Canvas, offset_x: i32, offset_y: i32, scale: f32) {
+        let style = TextStyle {
+            color: privacy::OUTLINE,
+            size: text::scaled(privacy::LABEL_SIZE, scale),
+            bold: false,
+        };
+        let padding = text::scaled(4, scale);
+
+        let cell = privacy::BLOCK_SIZE;
+        for &(cx, cy) in &self.brush {
+            drawing::fill_rect(
+                canvas,
+                cx * cell + offset_x,
+                cy * cell + offset_y,
+                cell,
+                cell,
+                privacy::BRUSH_TINT,
+            );
+        }
+
+        if self.tool != RedactTool::Regions {
+            let label = format!("{} ({} px)", self.tool.label(), self.brush_radius * 2);
+            text::draw_text(
+                canvas,
+                offset_x + padding,
+                offset_y + padding,
+                &label,
+                &style,
+            );
+        }
+
+        for (i, r) in self.regions.iter().enumerate() {
+            let color = if self.selected == Some(i) {
+                privacy::SELECTED
+            } else {
+                privacy::OUTLINE
+            };
+            let x0 = r.x + offset_x;
+            let y0 = r.y + offset_y;
+            drawing::draw_rect(canvas, x0, y0, x0 + r.width, y0 + r.height, 2, color);
+            text::draw_text(canvas, x0 + padding, y0 + padding, r.style.label(), &style);
+        }
+    }
+}
+
+/// Replace each BLOCK_SIZE x BLOCK_SIZE block with its average color
+fn pixelate(canvas: &mut Canvas, x0: i32, y0: i32, x1: i32, y1: i32) {
+    let block = privacy::BLOCK_SIZE;
+    let mut by = y0;
+    while by < y1 {
+        let bh = block.min(y1 - by);
+        let mut bx = x0;
+        while bx < x1 {
+            let bw = block.min(x1 - bx);
+
+            // STEP 1: Average the block
+            let (mut r, mut g, mut b) = (0u32, 0u32, 0u32);
+            for y in by..by + bh {
+                for x in bx..bx + bw {
+                    let p = canvas.pixels[(y * canvas.width + x) as usize];
+                    r += (p >> 16) & 0xFF;
+                    g += (p >> 8) & 0xFF;
+                    b += p & 0xFF;
+                }
+            }
+            let n = (bw * bh) as u32;
+            let avg = 0xFF000000 | ((r / n) << 16) | ((g / n) << 8) | (b / n);
+
+            // STEP 2: Fill it
+            for y in by..by + bh {
+                let row = (y * canvas.width) as usize;
+                canvas.pixels[row + bx as usize..row + (bx + bw) as usize].fill(avg);
+            }
+            bx += block;
+        }
+        by += block;
+    }
+}
+
+/// One separable box blur pass (horizontal, then vertical) inside the rectangle
+/// Samples outside the rectangle are clamped to its edge, so unredacted pixels
+/// around the region never bleed in and redacted pixels never bleed out.
+fn box_blur(canvas: &mut Canvas, x0: i32, y0: i32, x1: i32, y1: i32) {
+    let w = (x1 - x0) as usize;
+    let h = (y1 - y0) as usize;
+    let radius = privacy::BLUR_RADIUS as usize;
+
+    // Copy the region out so both passes read unmodified input
+    let mut buf: Vec<u32> = Vec::with_capacity(w * h);
+    for y in y0..y1 {
+        let row = (y * canvas.width) as usize;
+        buf.extend_from_slice(&canvas.pixels[row + x0 as usize..row + x1 as usize]);
+    }
+
+    let mut line = Vec::new();
+    // Horizontal pass
+    for y in 0..h {
+        line.clear();
+        line.extend_from_slice(&buf[y * w..(y + 1) * w]);
+        for x in 0..w {
+            buf[y * w + x] = average(|i| line[i], x, w, radius);
+        }
+    }
+    // Vertical pass
+    for x in 0..w {
+        line.clear();
+        line.extend((0..h).map(|y| buf[y * w + x]));
+        for y in 0..h {
+            buf[y * w + x] = average(|i| line[i], y, h, radius);
+        }
+    }
+
+    for y in 0..h {
+        let row = ((y0 as usize) + y) * canvas.width as usize;
+        canvas.pixels[row + x0 as usize..row + x1 as usize]
+            .copy_from_slice(&buf[y * w..(y + 1) * w]);
+    }
+}
+
+/// Average of the samples within `radius` of `center` (edge samples clamped)
+fn average(sample: impl Fn(usize) -> u32, center: usize, len: usize, radius: usize) -> u32 {
+    let (mut r, mut g, mut b) = (0u32, 0u32, 0u32);
+    let n = (radius * 2 + 1) as u32;
+    for k in 0..=radius * 2 {
+        let i = (center + k).saturating_sub(radius).min(len - 1);
+        let p = sample(i);
+        r += (p >> 16) & 0xFF;
+        g += (p >> 8) & 0xFF;
+        b += p & 0xFF;
+    }
+    0xFF000000 | ((r / n) << 16) | ((g / n) << 8) | (b / n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn brush_paints_the_cells_within_its_radius() {
+        let mut regions = PrivacyRegions::new();
+        regions.toggle_tool(RedactTool::Brush);
+        let cell = privacy::BLOCK_SIZE;
+        regions.paint(cell / 2, cell / 2);
+        assert!(regions.brush.contains(&(0, 0)));
+        // Every cell whose center is within the radius of the cursor
+        let radius = privacy::BRUSH_RADIUS;
+        let span = (radius / cell + 1) * 2 + 1;
+        let expected = (0..span * span)
+            .map(|i| (i % span - span / 2, i / span - span / 2))
+            .filter(|&(cx, cy)| (cx * cell).pow(2) + (cy * cell).pow(2) <= radius * radius)
+            .count();
+        assert_eq!(regions.brush.len(), expected);
+        assert!(!regions.is_empty());
+        assert_eq!(regions.len(), 0);
+    }
+
+    #[test]
+    fn painting_again_changes_nothing() {
+        let mut regions = PrivacyRegions::new();
+        regions.toggle_tool(RedactTool::Brush);
+        regions.paint(100, 100);
+        let revision = regions.revision();
+        regions.paint(100, 100);
+        assert_eq!(regions.revision(), revision);
+    }
+
+    #[test]
+    fn eraser_removes_painted_cells() {
+        let mut regions = PrivacyRegions::new();
+        regions.toggle_tool(RedactTool::Brush);
+        regions.paint(100, 100);
+        regions.toggle_tool(RedactTool::Eraser);
+        regions.paint(100, 100);
+        assert!(regions.is_empty());
+    }
+}
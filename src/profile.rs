@@ -0,0 +1,189 @@
+// profile.rs - Per-Monitor and Per-Application Defaults
+//
+// Profiles in settings.json override a few capture settings when a capture
+// starts, depending on where the region is and what is under it:
+//
+//   "profiles": [
+//     { "name": "4K", "monitor": "3840x2160", "target_fps": 30 },
+//     { "name": "IDE", "app": "Code.exe", "show_cursor": true, "filters": ["sharpen"] }
+//   ]
+//
+// - monitor: Windows device name ("\\.\DISPLAY2"), a resolution ("3840x2160")
+//   or "primary" - matched against the monitor with the center of the region
+// - app: process file name of the topmost window under the center of the
+//   region (RustFrame's own windows are skipped)
+// - An empty condition matches everything; a profile needs all of its
+//   conditions to match
+//
+// Every matching profile is applied in order, so a later one wins. The values
+// stay in use after the capture, like the values of a scene.
+
+use serde::{Deserialize, Serialize};
+
+use crate::app_settings::AppSettings;
+use crate::capture::CaptureRect;
+
+/// Settings that apply in one context (settings.json)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SettingsProfile {
+    /// Shown in the log when the profile is applied
+    pub name: String,
+    /// Device name, "WIDTHxHEIGHT" or "primary" (empty = any monitor)
+    pub monitor: String,
+    /// Process file name, e.g. "Code.exe" (empty = any application)
+    pub app: String,
+    pub target_fps: Option<u32>,
+    pub show_cursor: Option<bool>,
+    pub show_border: Option<bool>,
+    pub border_width: Option<u32>,
+    /// Enabled frame filters in order (see filter.rs)
+    pub filters: Option<Vec<String>>,
+}
+
+impl SettingsProfile {
+    fn matches(&self, context: &Context) -> bool {
+        let monitor = self.monitor.trim();
+        let monitor_matches =
+            monitor.is_empty() || context.monitor.as_ref().is_some_and(|m| m.matches(monitor));
+        let app = self.app.trim();
+        let app_matches = app.is_empty()
+            || context
+                .app
+                .as_ref()
+                .is_some_and(|a| a.eq_ignore_ascii_case(app));
+        monitor_matches && app_matches
+    }
+
+    /// Write the values this profile sets into `settings`
+    fn apply_to(&self, settings: &mut AppSettings) {
+        if let Some(fps) = self.target_fps {
+            settings.capture.target_fps = fps;
+        }
+        if let Some(show) = self.show_cursor {
+            settings.capture.show_cursor = show;
+        }
+        if let Some(show) = self.show_border {
+            settings.capture.show_border = show;
+        }
+        if let Some(width) = self.border_width {
+            settings.capture.border_width = width;
+        }
+        if let Some(filters) = &self.filters {
+            settings.filters = filters.clone();
+        }
+    }
+}
+
+/// The monitor a region is on
+#[derive(Debug, Clone)]
+pub struct MonitorInfo {
+    /// Windows device name, e.g. "\\.\DISPLAY1"
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub primary: bool,
+}
+
+impl MonitorInfo {
+    fn matches(&self, condition: &str) -> bool {
+        if condition.eq_ignore_ascii_case("primary") {
+            return self.primary;
+        }
+        if let Some((w, h)) = condition.split_once(['x', 'X']) {
+            if let (Ok(w), Ok(h)) = (w.trim().parse::<u32>(), h.trim().parse::<u32>()) {
+                return w == self.width && h == self.height;
+            }
+        }
+        self.name.eq_ignore_ascii_case(condition)
+    }
+}
+
+/// Where a capture starts
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+    pub monitor: Option<MonitorInfo>,
+    /// Process file name of the application under the region
+    pub app: Option<String>,
+}
+
+impl Context {
+    /// Look up the monitor and the application for a capture region
+    pub fn of_region(region: CaptureRect) -> Self {
+        let x = region.x + region.width as i32 / 2;
+        let y = region.y + region.height as i32 / 2;
+        Self {
+            monitor: monitor_at(x, y),
+            app: app_at(x, y),
+        }
+    }
+}
+
+/// Apply every profile that matches the context, in order
+/// Returns the names of the applied profiles.
+pub fn apply(settings: &mut AppSettings, context: &Context) -> Vec<String> {
+    let profiles = settings.profiles.clone();
+    profiles
+        .iter()
+        .filter(|profile| profile.matches(context))
+        .map(|profile| {
+            profile.apply_to(settings);
+            profile.name.clone()
+        })
+        .collect()
+}
+
+/// Monitor containing a screen position
+#[cfg(windows)]
+fn monitor_at(x: i32, y: i32) -> Option<MonitorInfo> {
+    use windows::Win32::Foundation::POINT;
+    use windows::Win32::Graphics::Gdi::{
+        GetMonitorInfoW, MonitorFromPoint, MONITORINFO, MONITORINFOEXW, MONITOR_DEFAULTTONULL,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::MONITORINFOF_PRIMARY;
+
+    unsafe {
+        let monitor = MonitorFromPoint(POINT { x, y }, MONITOR_DEFAULTTONULL);
+        if monitor.is_invalid() {
+            return None;
+        }
+        let mut info = MONITORINFOEXW::default();
+        info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+        if !GetMonitorInfoW(
+            monitor,
+            &mut info as *mut MONITORINFOEXW as *mut MONITORINFO,
+        )
+        .as_bool()
+        {
+            return None;
+        }
+        let len = info
+            .szDevice
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(info.szDevice.len());
+        let rect = info.monitorInfo.rcMonitor;
+        Some(MonitorInfo {
+            name: String::from_utf16_lossy(&info.szDevice[..len]),
+            width: (rect.right - rect.left) as u32,
+            height: (rect.bottom - rect.top) as u32,
+            primary: info.monitorInfo.dwFlags & MONITORINFOF_PRIMARY != 0,
+        })
+    }
+}
+
+#[cfg(not(windows))]
+fn monitor_at(_x: i32, _y: i32) -> Option<MonitorInfo> {
+    None
+}
+
+/// Process file name of the topmost window at a screen position that is not
+/// one of RustFrame's own
+fn app_at(x: i32, y: i32) -> Option<String> {
+    let own_pid = std::process::id();
+    crate::window_mask::visible_windows()
+        .into_iter()
+        .filter(|w| w.pid != own_pid)
+        .find(|w| x >= w.left && x < w.right && y >= w.top && y < w.bottom)
+        .and_then(|w| crate::window_mask::process_name(w.pid))
+}
@@ -0,0 +1,150 @@
+// quality.rs - Video Quality Metrics (PSNR / SSIM)
+//
+// How close an encoded video is to the captured frames, as numbers - so
+// encoder settings (bitrate, frame rate) can be compared objectively instead
+// of by eye:
+// - PSNR (peak signal-to-noise ratio, in dB): the mean squared error of the
+//   pixels on a log scale. Higher is better; above about 40 dB differences
+//   are hard to see, identical frames are reported as MAX_PSNR_DB.
+// - SSIM (structural similarity, 0 to 1): compares brightness, contrast and
+//   structure of 8x8 blocks, closer to how the eye judges. 1 is identical.
+//
+// Both are computed on the luma (brightness) of the frames, as is usual for
+// video - H.264 stores the colors at half resolution anyway.
+//
+// HOW IT WORKS (`record --quality`, see record.rs):
+// 1. While recording, the Sampler keeps the luma of one source frame per
+//    sampling interval, within a memory budget: when it is full, every other
+//    sample is dropped and the interval doubles
+// 2. After the MP4 file is finished, it is decoded again and each decoded
+//    frame with the timestamp of a sample is compared with it
+// 3. The recording reports the averages (and the lowest SSIM, which points at
+//    the worst moment)
+
+use crate::constants::quality;
+
+/// Luma (BT.601, 0-255) of the top-left `width` x `height` pixels of a
+/// 0xAARRGGBB frame whose rows are `stride` pixels apart
+pub fn luma(pixels: &[u32], stride: usize, width: u32, height: u32) -> Vec<u8> {
+    let mut luma = Vec::with_capacity(width as usize * height as usize);
+    for y in 0..height as usize {
+        let row = &pixels[y * stride..y * stride + width as usize];
+        luma.extend(row.iter().map(|&pixel| pixel_luma(pixel)));
+    }
+    luma
+}
+
+/// Luma of one 0xAARRGGBB pixel
+pub fn pixel_luma(pixel: u32) -> u8 {
+    let (r, g, b) = ((pixel >> 16) & 0xFF, (pixel >> 8) & 0xFF, pixel & 0xFF);
+    ((r * 77 + g * 150 + b * 29) >> 8) as u8
+}
+
+/// PSNR of two luma planes of the same size (dB, at most MAX_PSNR_DB)
+pub fn psnr(a: &[u8], b: &[u8]) -> f64 {
+    let count = a.len().min(b.len()).max(1);
+    let squared: u64 = a
+        .iter()
+        .zip(b)
+        .map(|(&x, &y)| {
+            let d = x as i64 - y as i64;
+            (d * d) as u64
+        })
+        .sum();
+    if squared == 0 {
+        return quality::MAX_PSNR_DB;
+    }
+    let mse = squared as f64 / count as f64;
+    (10.0 * (255.0 * 255.0 / mse).log10()).min(quality::MAX_PSNR_DB)
+}
+
+/// SSIM of two luma planes of `width` x `height`: the mean over 8x8 blocks
+/// (a frame smaller than a block is one block)
+pub fn ssim(a: &[u8], b: &[u8], width: u32, height: u32) -> f64 {
+    let (width, height) = (width as usize, height as usize);
+    let block = quality::SSIM_BLOCK;
+    let (block_w, block_h) = (block.min(width), block.min(height));
+    if block_w == 0 || block_h == 0 {
+        return 1.0;
+    }
+    // Stabilizing constants for the usual K1 = 0.01, K2 = 0.03 and 8-bit values
+    let c1 = (0.01 * 255.0_f64).powi(2);
+    let c2 = (0.03 * 255.0_f64).powi(2);
+
+    let mut total = 0.0;
+    let mut blocks = 0;
+    for by in (0..=height - block_h).step_by(block_h) {
+        for bx in (0..=width - block_w).step_by(block_w) {
+            let (mut sum_a, mut sum_b) = (0.0, 0.0);
+            let (mut sum_aa, mut sum_bb, mut sum_ab) = (0.0, 0.0, 0.0);
+            for y in by..by + block_h {
+                for x in bx..bx + block_w {
+                    let (pa, pb) = (a[y * width + x] as f64, b[y * width + x] as f64);
+                    sum_a += pa;
+                    sum_b += pb;
+                    sum_aa += pa * pa;
+                    sum_bb += pb * pb;
+                    sum_ab += pa * pb;
+                }
+            }
+            let n = (block_w * block_h) as f64;
+            let (mean_a, mean_b) = (sum_a / n, sum_b / n);
+            let var_a = sum_aa / n - mean_a * mean_a;
+            let var_b = sum_bb / n - mean_b * mean_b;
+            let covariance = sum_ab / n - mean_a * mean_b;
+            total += ((2.0 * mean_a * mean_b + c1) * (2.0 * covariance + c2))
+                / ((mean_a * mean_a + mean_b * mean_b + c1) * (var_a + var_b + c2));
+            blocks += 1;
+        }
+    }
+    total / blocks.max(1) as f64
+}
+
+/// Luma of sampled source frames, by frame number
+pub struct Sampler {
+    /// Frames between two samples
+    step: u64,
+    /// Frame number of the next sample
+    next: u64,
+    /// Most samples kept (the memory budget)
+    max: usize,
+    samples: Vec<(u64, Vec<u8>)>,
+}
+
+impl Sampler {
+    /// Sample every `step` frames of `width` x `height`
+    pub fn new(step: u64, width: u32, height: u32) -> Self {
+        let frame_bytes = (width as usize * height as usize).max(1);
+        Self {
+            step: step.max(1),
+            next: 0,
+            max: (quality::SAMPLE_MEMORY_BYTES / frame_bytes).max(2),
+            samples: Vec::new(),
+        }
+    }
+
+    /// True if the frame written as frame number `index` should be kept
+    pub fn wants(&self, index: u64) -> bool {
+        index >= self.next
+    }
+
+    /// Keep the luma of frame number `index`
+    pub fn add(&mut self, index: u64, luma: Vec<u8>) {
+        self.samples.push((index, luma));
+        if self.samples.len() > self.max {
+            // Full - keep every other sample and sample half as often
+            let mut position = 0;
+            self.samples.retain(|_| {
+                position += 1;
+                position % 2 == 1
+            });
+            self.step *= 2;
+        }
+        self.next = index + self.step;
+    }
+
+    /// The samples, ordered by frame number
+    pub fn into_samples(self) -> Vec<(u64, Vec<u8>)> {
+        self.samples
+    }
+}
@@ -0,0 +1,855 @@
+// record.rs - Headless Recording to a Video File
+//
+// `rustframe::record` captures a screen region for a fixed time and encodes
+// it to an H.264 MP4 file with the Media Foundation sink writer - no window,
+// tray or running RustFrame needed. `rustframe-ctl record` uses it, and other
+// Rust programs can call it for automated or server-side recordings.
+//
+// The video has a constant frame rate: on every frame tick the newest
+// captured frame is written, or the previous one again while the screen does
+// not change (Windows.Graphics.Capture only delivers frames on changes).
+// H.264 needs even dimensions, so an odd last column or row is cut off.
+//
+// When the encoder cannot keep up (the frames fall further and further
+// behind their time, or samples pile up in the sink writer), the recording
+// steps down one level per second - the encoder's fastest preset, then half
+// and a quarter of the frame rate (longer frames, the video length stays
+// right) - with a warning on the console. After five seconds with headroom it
+// steps back up. The frame size of an MP4 stream cannot change, so the
+// resolution is not reduced.
+//
+// The file is a fragmented MP4 with a keyframe (and a new fragment) every two
+// seconds, so a crash of RustFrame, the graphics driver or Windows keeps
+// everything up to the last fragment; the next start of RustFrame offers to
+// repair such files (see recovery.rs).
+//
+// Ctrl+C, Ctrl+Break and closing the console window (also logging off or
+// shutting down) end the recording early instead of killing it mid-write: the
+// frames so far are written and the MP4 file is finalized, so it stays
+// playable. Windows gives a closing console a few seconds, which is enough
+// for the sink writer to finish. Programs using the library call `stop()`.
+//
+// With `--quality` the recording also measures how close the video is to the
+// captured frames: it keeps sampled source frames, decodes the finished file
+// again and reports the average PSNR and SSIM (see quality.rs) - for tuning
+// the bitrate and frame rate with numbers.
+//
+// `RecordArgs` parses the command line shared by `RustFrame record` and
+// `rustframe-ctl record`:
+//
+//   --region <x,y,width,height> --duration <seconds> --out <file.mp4>
+//   [--fps <n>] [--bitrate <bits per second>] [--no-cursor] [--quality]
+
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::capture::{CaptureError, CaptureFrame, CaptureRect, CaptureSession, Result};
+use crate::constants::{self, record};
+use crate::quality::{self, Sampler};
+use crate::recovery::{self, Journal};
+
+/// Options of `record`
+#[derive(Debug, Clone)]
+pub struct RecordOptions {
+    /// Frames per second of the video
+    pub fps: u32,
+    /// Include the mouse cursor
+    pub cursor: bool,
+    /// Target bitrate of the H.264 stream (bits per second)
+    pub bitrate: u32,
+    /// Measure PSNR / SSIM of the video against the captured frames
+    pub quality: bool,
+}
+
+impl Default for RecordOptions {
+    fn default() -> Self {
+        Self {
+            fps: record::DEFAULT_FPS,
+            cursor: true,
+            bitrate: record::DEFAULT_BITRATE,
+            quality: false,
+        }
+    }
+}
+
+/// A parsed `record` command line
+#[derive(Debug, Clone)]
+pub struct RecordArgs {
+    pub region: CaptureRect,
+    pub duration: Duration,
+    pub out: PathBuf,
+    pub options: RecordOptions,
+}
+
+impl RecordArgs {
+    /// Parse the options after `record`; the error is a message for the user
+    pub fn parse(args: &[String]) -> std::result::Result<Self, String> {
+        let mut region = None;
+        let mut duration = None;
+        let mut out = None;
+        let mut options = RecordOptions::default();
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .map(|v| v.trim())
+                    .ok_or_else(|| format!("Missing value for {}", arg))
+            };
+            match arg.as_str() {
+                "--region" => {
+                    let text = value()?;
+                    region = Some(
+                        parse_region(text).ok_or_else(|| format!("Invalid region '{}'", text))?,
+                    );
+                }
+                "--duration" => {
+                    let text = value()?;
+                    let seconds = text
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|s| s.is_finite() && *s > 0.0)
+                        .ok_or_else(|| format!("Invalid --duration '{}'", text))?;
+                    duration = Some(Duration::from_secs_f64(seconds));
+                }
+                "--out" => out = Some(PathBuf::from(value()?)),
+                "--fps" => {
+                    let text = value()?;
+                    options.fps = text
+                        .parse()
+                        .ok()
+                        .filter(|fps| *fps > 0)
+                        .ok_or_else(|| format!("Invalid --fps '{}'", text))?;
+                }
+                "--bitrate" => {
+                    let text = value()?;
+                    options.bitrate = text
+                        .parse()
+                        .ok()
+                        .filter(|bitrate| *bitrate > 0)
+                        .ok_or_else(|| format!("Invalid --bitrate '{}'", text))?;
+                }
+                "--no-cursor" => options.cursor = false,
+                "--quality" => options.quality = true,
+                other => return Err(format!("Unknown option '{}'", other)),
+            }
+        }
+
+        Ok(Self {
+            region: region.ok_or("Missing --region")?,
+            duration: duration.ok_or("Missing --duration")?,
+            out: out.ok_or("Missing --out")?,
+            options,
+        })
+    }
+}
+
+/// "x,y,width,height" (virtual screen coordinates)
+pub fn parse_region(text: &str) -> Option<CaptureRect> {
+    let parts: Vec<&str> = text.split(',').map(str::trim).collect();
+    match parts.as_slice() {
+        [x, y, width, height] => Some(CaptureRect {
+            x: x.parse().ok()?,
+            y: y.parse().ok()?,
+            width: width.parse().ok()?,
+            height: height.parse().ok()?,
+        }),
+        _ => None,
+    }
+}
+
+/// What `record` wrote
+#[derive(Debug, Clone, Copy)]
+pub struct RecordSummary {
+    pub frames: u64,
+    pub width: u32,
+    pub height: u32,
+    /// Stopped before the end of the duration (Ctrl+C, `stop()`, ...)
+    pub interrupted: bool,
+    /// Quality of the video against the captured frames (`RecordOptions::quality`;
+    /// None if it was not measured or the measurement failed)
+    pub quality: Option<QualityReport>,
+}
+
+/// Video quality of a recording, averaged over the sampled frames
+#[derive(Debug, Clone, Copy)]
+pub struct QualityReport {
+    /// Average PSNR (dB)
+    pub psnr: f64,
+    /// Average SSIM (0-1)
+    pub ssim: f64,
+    /// Lowest SSIM of a sampled frame
+    pub min_ssim: f64,
+    /// Frames compared
+    pub samples: u32,
+}
+
+impl std::fmt::Display for QualityReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "PSNR {:.2} dB, SSIM {:.4} (lowest {:.4}), {} frames compared",
+            self.psnr, self.ssim, self.min_ssim, self.samples
+        )
+    }
+}
+
+/// Asks the running recording to end early
+static STOP: AtomicBool = AtomicBool::new(false);
+/// A recording is writing its file (the console handler waits for it)
+static RECORDING: AtomicBool = AtomicBool::new(false);
+
+/// End the running recording early; it finalizes its file and returns
+pub fn stop() {
+    STOP.store(true, Ordering::SeqCst);
+}
+
+/// Record `region` for `duration` into the MP4 file `path` (replaced if it
+/// exists); blocks until the file is complete
+pub fn record(
+    region: CaptureRect,
+    duration: Duration,
+    path: &Path,
+    options: &RecordOptions,
+) -> Result<RecordSummary> {
+    let fps = options
+        .fps
+        .clamp(1, crate::constants::capture::MAX_TARGET_FPS);
+    let mut session = CaptureSession::builder()
+        .region(region)
+        .cursor(options.cursor)
+        .build()?;
+
+    // The first frame fixes the video size (the region may be cut off at the
+    // edge of its monitor)
+    let mut latest = CaptureFrame::default();
+    match session.next_frame(Duration::from_millis(record::FIRST_FRAME_TIMEOUT_MS))? {
+        Some(frame) => latest.clone_from(frame),
+        None => {
+            return Err(CaptureError::Backend(
+                "Waiting for the first frame",
+                windows::Win32::Foundation::ERROR_TIMEOUT.into(),
+            ))
+        }
+    }
+    let width = latest.width & !1;
+    let height = latest.height & !1;
+    if width == 0 || height == 0 {
+        return Err(CaptureError::InvalidRegion(
+            "the region is too small to record".to_string(),
+        ));
+    }
+
+    for video in recovery::unfinished() {
+        warn!(
+            "{:?} was not finished by an earlier recording - start RustFrame to repair it",
+            video
+        );
+    }
+    let writer =
+        Mp4Writer::create(path, width, height, fps, options.bitrate).map_err(encoder_error)?;
+    let journal = Journal::start(path);
+    let interval = Duration::from_secs_f64(1.0 / fps as f64);
+    let frames = ((duration.as_secs_f64() * fps as f64).round() as u64).max(1);
+    info!(
+        "Recording {:?} for {:?} to {:?} ({}x{}, {} fps)",
+        region, duration, path, width, height, fps
+    );
+
+    let mut sampler = options.quality.then(|| {
+        let step = fps as u64 * constants::quality::SAMPLE_SECONDS;
+        Sampler::new(step, width, height)
+    });
+
+    STOP.store(false, Ordering::SeqCst);
+    RECORDING.store(true, Ordering::SeqCst);
+    let _interrupts = ConsoleInterrupts::catch();
+
+    let started = Instant::now();
+    let mut load = LoadControl::new(interval);
+    let mut index = 0;
+    let mut written = || -> Result<()> {
+        while index < frames && !STOP.load(Ordering::SeqCst) {
+            let due = started + interval.mul_f64(index as f64);
+            let now = Instant::now();
+            if let Some(wait) = due.checked_duration_since(now) {
+                std::thread::sleep(wait);
+            }
+            if let Some(frame) = session.try_next_frame()? {
+                if frame.width >= width && frame.height >= height {
+                    latest.clone_from(frame);
+                }
+            }
+            let length = load.level.frame_step().min(frames - index);
+            writer
+                .write(&latest, index, length)
+                .map_err(encoder_error)?;
+            if let Some(sampler) = sampler.as_mut().filter(|s| s.wants(index)) {
+                let luma = quality::luma(&latest.pixels, latest.width as usize, width, height);
+                sampler.add(index, luma);
+            }
+            index += length;
+
+            let late = now.saturating_duration_since(due);
+            if let Some(level) = load.update(late, writer.queued_samples()) {
+                writer.set_fast_preset(level >= Load::FastPreset);
+            }
+        }
+        Ok(())
+    };
+    // The file is finalized after a failed frame too, so what was written
+    // before stays playable
+    let written = written();
+    // Stop capturing before the encoder drains
+    drop(session);
+    let finished = writer.finish().map_err(encoder_error);
+    RECORDING.store(false, Ordering::SeqCst);
+    // A file that could not be finished stays in the journal for recovery
+    if let (Ok(()), Some(journal)) = (&finished, journal) {
+        journal.finish();
+    }
+    written?;
+    finished?;
+
+    let quality = match sampler {
+        Some(sampler) if index > 0 => {
+            info!("Measuring the video quality of {:?}", path);
+            match measure_quality(path, width, height, fps, &sampler.into_samples()) {
+                Ok(report) => {
+                    info!("Video quality: {}", report);
+                    Some(report)
+                }
+                Err(e) => {
+                    warn!("Failed to measure the video quality: {}", e);
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+
+    let interrupted = index < frames;
+    if interrupted {
+        warn!(
+            "Recording stopped early: {:?} ({} of {} frames)",
+            path, index, frames
+        );
+    } else {
+        info!("Recording saved: {:?} ({} frames)", path, frames);
+    }
+
+    Ok(RecordSummary {
+        frames: index,
+        width,
+        height,
+        interrupted,
+        quality,
+    })
+}
+
+/// Decode the finished `path` again and compare the frames with the sampled
+/// frame numbers to the source frames (see quality.rs)
+fn measure_quality(
+    path: &Path,
+    width: u32,
+    height: u32,
+    fps: u32,
+    samples: &[(u64, Vec<u8>)],
+) -> Result<QualityReport> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Media::MediaFoundation::*;
+
+    let at = CaptureError::encoder;
+    let url = crate::utils::wide_string(&path.to_string_lossy());
+    let frame_duration = 10_000_000 / fps as i64;
+
+    let _media_foundation = MediaFoundation::start()?;
+    let (mut psnr, mut ssim, mut min_ssim, mut compared) = (0.0, 0.0, 1.0_f64, 0);
+    unsafe {
+        // Let the reader convert the decoded frames to RGB32, like the source
+        let mut attributes = None;
+        MFCreateAttributes(&mut attributes, 1)?;
+        if let Some(attributes) = &attributes {
+            attributes.SetUINT32(&MF_SOURCE_READER_ENABLE_VIDEO_PROCESSING, 1)?;
+        }
+        let reader = MFCreateSourceReaderFromURL(PCWSTR(url.as_ptr()), attributes.as_ref())
+            .map_err(at("Opening the recording"))?;
+        let stream = MF_SOURCE_READER_FIRST_VIDEO_STREAM.0 as u32;
+        let media_type = MFCreateMediaType()?;
+        media_type.SetGUID(&MF_MT_MAJOR_TYPE, &MFMediaType_Video)?;
+        media_type.SetGUID(&MF_MT_SUBTYPE, &MFVideoFormat_RGB32)?;
+        reader
+            .SetCurrentMediaType(stream, None, &media_type)
+            .map_err(at("Setting the decoder output"))?;
+        // The decoded frames can be padded (1088 rows for 1080)
+        let packed = reader
+            .GetCurrentMediaType(stream)?
+            .GetUINT64(&MF_MT_FRAME_SIZE)?;
+        let decoded_width = (packed >> 32) as u32;
+        if decoded_width < width || (packed as u32) < height {
+            return Err(CaptureError::Encoder(
+                "The decoded frames are smaller than the video",
+                windows::Win32::Foundation::E_FAIL.into(),
+            ));
+        }
+
+        let mut pending = samples.iter().peekable();
+        while pending.peek().is_some() {
+            let mut flags = 0u32;
+            let mut timestamp = 0i64;
+            let mut sample = None;
+            reader
+                .ReadSample(
+                    stream,
+                    0,
+                    None,
+                    Some(&mut flags),
+                    Some(&mut timestamp),
+                    Some(&mut sample),
+                )
+                .map_err(at("Decoding a frame"))?;
+            if flags & MF_SOURCE_READERF_ENDOFSTREAM.0 as u32 != 0 {
+                break;
+            }
+            let Some(sample) = sample else {
+                continue;
+            };
+            let index = ((timestamp + frame_duration / 2) / frame_duration) as u64;
+            // Sampled frames the decoder did not deliver are left out
+            while pending.next_if(|(i, _)| *i < index).is_some() {}
+            let Some((_, source)) = pending.next_if(|(i, _)| *i == index) else {
+                continue;
+            };
+            let decoded = decoded_luma(&sample, decoded_width, width, height)?;
+            let frame_ssim = quality::ssim(source, &decoded, width, height);
+            psnr += quality::psnr(source, &decoded);
+            ssim += frame_ssim;
+            min_ssim = min_ssim.min(frame_ssim);
+            compared += 1;
+        }
+    }
+    if compared == 0 {
+        return Err(CaptureError::Encoder(
+            "No sampled frame was decoded",
+            windows::Win32::Foundation::E_FAIL.into(),
+        ));
+    }
+    Ok(QualityReport {
+        psnr: psnr / compared as f64,
+        ssim: ssim / compared as f64,
+        min_ssim,
+        samples: compared,
+    })
+}
+
+/// Luma of the top-left `width` x `height` pixels of a decoded RGB32 sample
+/// whose rows are `decoded_width` pixels wide
+unsafe fn decoded_luma(
+    sample: &windows::Win32::Media::MediaFoundation::IMFSample,
+    decoded_width: u32,
+    width: u32,
+    height: u32,
+) -> Result<Vec<u8>> {
+    use windows::core::Interface;
+    use windows::Win32::Media::MediaFoundation::IMF2DBuffer;
+
+    let buffer = sample.ConvertToContiguousBuffer()?;
+    // IMF2DBuffer reports the real pitch (negative for bottom-up images);
+    // plain buffers are tightly packed top-down rows
+    let buffer_2d = buffer.cast::<IMF2DBuffer>().ok();
+    let mut scanline0 = std::ptr::null_mut();
+    let mut pitch = (decoded_width * 4) as i32;
+    match &buffer_2d {
+        Some(buffer_2d) => buffer_2d.Lock2D(&mut scanline0, &mut pitch)?,
+        None => buffer.Lock(&mut scanline0, None, None)?,
+    }
+
+    let mut luma = Vec::with_capacity(width as usize * height as usize);
+    for y in 0..height as usize {
+        let row = scanline0.offset(y as isize * pitch as isize) as *const u32;
+        let row = std::slice::from_raw_parts(row, width as usize);
+        luma.extend(row.iter().map(|&pixel| quality::pixel_luma(pixel)));
+    }
+
+    match &buffer_2d {
+        Some(buffer_2d) => buffer_2d.Unlock2D()?,
+        None => buffer.Unlock()?,
+    }
+    Ok(luma)
+}
+
+/// Console events end the recording instead of the process while it lives
+struct ConsoleInterrupts;
+
+impl ConsoleInterrupts {
+    fn catch() -> Self {
+        use windows::Win32::System::Console::SetConsoleCtrlHandler;
+
+        if let Err(e) = unsafe { SetConsoleCtrlHandler(Some(console_handler), true) } {
+            warn!("Ctrl+C will not finish the recording: {}", e);
+        }
+        Self
+    }
+}
+
+impl Drop for ConsoleInterrupts {
+    fn drop(&mut self) {
+        use windows::Win32::System::Console::SetConsoleCtrlHandler;
+
+        let _ = unsafe { SetConsoleCtrlHandler(Some(console_handler), false) };
+    }
+}
+
+/// Called on its own thread for Ctrl+C, Ctrl+Break, closing the console,
+/// logging off and shutting down
+unsafe extern "system" fn console_handler(event: u32) -> windows::core::BOOL {
+    use windows::Win32::System::Console::{
+        CTRL_CLOSE_EVENT, CTRL_LOGOFF_EVENT, CTRL_SHUTDOWN_EVENT,
+    };
+
+    stop();
+    // The process ends when these return - wait for the file to be finished
+    if [CTRL_CLOSE_EVENT, CTRL_LOGOFF_EVENT, CTRL_SHUTDOWN_EVENT].contains(&event) {
+        let started = Instant::now();
+        while RECORDING.load(Ordering::SeqCst)
+            && started.elapsed() < Duration::from_millis(record::CLOSE_WAIT_MS)
+        {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+    true.into()
+}
+
+/// How far a recording stepped down to keep up with its frame rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Load {
+    Normal,
+    /// The encoder's fastest quality / speed setting
+    FastPreset,
+    HalfFps,
+    QuarterFps,
+}
+
+impl Load {
+    /// Video frames each encoded frame lasts
+    fn frame_step(self) -> u64 {
+        match self {
+            Load::Normal | Load::FastPreset => 1,
+            Load::HalfFps => 2,
+            Load::QuarterFps => 4,
+        }
+    }
+
+    fn lower(self) -> Option<Self> {
+        match self {
+            Load::Normal => Some(Load::FastPreset),
+            Load::FastPreset => Some(Load::HalfFps),
+            Load::HalfFps => Some(Load::QuarterFps),
+            Load::QuarterFps => None,
+        }
+    }
+
+    fn higher(self) -> Option<Self> {
+        match self {
+            Load::Normal => None,
+            Load::FastPreset => Some(Load::Normal),
+            Load::HalfFps => Some(Load::FastPreset),
+            Load::QuarterFps => Some(Load::HalfFps),
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Load::Normal => "full quality and frame rate",
+            Load::FastPreset => "fastest encoder preset",
+            Load::HalfFps => "half the frame rate",
+            Load::QuarterFps => "a quarter of the frame rate",
+        }
+    }
+}
+
+/// Watches how far the recording is behind and picks the load level
+struct LoadControl {
+    level: Load,
+    /// Length of one video frame
+    interval: Duration,
+    /// Start of the current check window and how late the frames were then
+    window: (Instant, Duration),
+    /// Since when the recording kept up (stepping back up after RESTORE_MS)
+    headroom_since: Option<Instant>,
+}
+
+impl LoadControl {
+    fn new(interval: Duration) -> Self {
+        Self {
+            level: Load::Normal,
+            interval,
+            window: (Instant::now(), Duration::ZERO),
+            headroom_since: None,
+        }
+    }
+
+    /// Check once per window whether the encoder falls behind (the frames
+    /// are late and getting later, or samples pile up in the encoder) or has
+    /// room again; returns the new level when it changed
+    fn update(&mut self, late: Duration, queued: u64) -> Option<Load> {
+        let (window_start, window_late) = self.window;
+        if window_start.elapsed() < Duration::from_millis(record::OVERLOAD_CHECK_MS) {
+            return None;
+        }
+        self.window = (Instant::now(), late);
+
+        let falling_behind = (late > Duration::from_millis(record::OVERLOAD_LATE_MS)
+            && late >= window_late)
+            || queued > record::OVERLOAD_QUEUED_SAMPLES;
+        if falling_behind {
+            self.headroom_since = None;
+            let lower = self.level.lower()?;
+            self.level = lower;
+            warn!(
+                "The encoder falls behind ({} ms late, {} samples queued) - recording with {}",
+                late.as_millis(),
+                queued,
+                lower.describe()
+            );
+            return Some(lower);
+        }
+
+        if late >= self.interval || queued > 1 {
+            self.headroom_since = None;
+            return None;
+        }
+        let since = *self.headroom_since.get_or_insert_with(Instant::now);
+        if since.elapsed() < Duration::from_millis(record::RESTORE_MS) {
+            return None;
+        }
+        let higher = self.level.higher()?;
+        self.level = higher;
+        self.headroom_since = None;
+        info!("The encoder keeps up again - recording with {}", higher.describe());
+        Some(higher)
+    }
+}
+
+/// H.264 / MP4 encoder (Media Foundation sink writer)
+struct Mp4Writer {
+    writer: windows::Win32::Media::MediaFoundation::IMFSinkWriter,
+    stream: u32,
+    width: u32,
+    height: u32,
+    /// Duration of one frame in 100 ns units
+    frame_duration: i64,
+    /// Quality / speed setting of the encoder (None if it has none) and its
+    /// value at the start
+    codec: Option<(windows::Win32::Media::MediaFoundation::ICodecAPI, u32)>,
+    /// Dropped after the writer
+    _media_foundation: MediaFoundation,
+}
+
+/// Errors of the MP4 writer are encoder errors, also those of the calls that
+/// are not named (setting media type attributes, sample buffers)
+fn encoder_error(error: CaptureError) -> CaptureError {
+    match error {
+        CaptureError::Backend(call, error) => CaptureError::Encoder(call, error),
+        other => other,
+    }
+}
+
+/// Media Foundation started (shut down when dropped)
+pub(crate) struct MediaFoundation;
+
+impl MediaFoundation {
+    pub(crate) fn start() -> Result<Self> {
+        use windows::Win32::Media::MediaFoundation::{MFStartup, MFSTARTUP_NOSOCKET, MF_VERSION};
+
+        unsafe { MFStartup(MF_VERSION, MFSTARTUP_NOSOCKET) }
+            .map_err(CaptureError::encoder("MFStartup"))?;
+        Ok(Self)
+    }
+}
+
+impl Drop for MediaFoundation {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = windows::Win32::Media::MediaFoundation::MFShutdown();
+        }
+    }
+}
+
+impl Mp4Writer {
+    fn create(path: &Path, width: u32, height: u32, fps: u32, bitrate: u32) -> Result<Self> {
+        use windows::core::PCWSTR;
+        use windows::Win32::Media::MediaFoundation::*;
+
+        let at = CaptureError::encoder;
+        let frame_size = ((width as u64) << 32) | height as u64;
+        let frame_rate = ((fps as u64) << 32) | 1;
+        let square_pixels = (1u64 << 32) | 1;
+        let url = crate::utils::wide_string(&path.to_string_lossy());
+
+        let media_foundation = MediaFoundation::start()?;
+        unsafe {
+            let mut attributes = None;
+            MFCreateAttributes(&mut attributes, 2).map_err(at("MFCreateAttributes"))?;
+            if let Some(attributes) = &attributes {
+                attributes.SetUINT32(&MF_READWRITE_ENABLE_HARDWARE_TRANSFORMS, 1)?;
+                // Fragmented MP4 - readable up to the last fragment after a crash
+                attributes.SetGUID(
+                    &MF_TRANSCODE_CONTAINERTYPE,
+                    &MFTranscodeContainerType_FMPEG4,
+                )?;
+            }
+            let writer = MFCreateSinkWriterFromURL(PCWSTR(url.as_ptr()), None, attributes.as_ref())
+                .map_err(at("MFCreateSinkWriterFromURL"))?;
+
+            let output = MFCreateMediaType()?;
+            output.SetGUID(&MF_MT_MAJOR_TYPE, &MFMediaType_Video)?;
+            output.SetGUID(&MF_MT_SUBTYPE, &MFVideoFormat_H264)?;
+            output.SetUINT32(&MF_MT_AVG_BITRATE, bitrate)?;
+            output.SetUINT32(&MF_MT_INTERLACE_MODE, MFVideoInterlace_Progressive.0 as u32)?;
+            output.SetUINT64(&MF_MT_FRAME_SIZE, frame_size)?;
+            output.SetUINT64(&MF_MT_FRAME_RATE, frame_rate)?;
+            output.SetUINT64(&MF_MT_PIXEL_ASPECT_RATIO, square_pixels)?;
+            output.SetUINT32(&MF_MT_MAX_KEYFRAME_SPACING, fps * record::FRAGMENT_SECONDS)?;
+            let stream = writer
+                .AddStream(&output)
+                .map_err(at("Adding the H.264 stream"))?;
+
+            // Captured frames are BGRA (RGB32), top row first
+            let input = MFCreateMediaType()?;
+            input.SetGUID(&MF_MT_MAJOR_TYPE, &MFMediaType_Video)?;
+            input.SetGUID(&MF_MT_SUBTYPE, &MFVideoFormat_RGB32)?;
+            input.SetUINT32(&MF_MT_INTERLACE_MODE, MFVideoInterlace_Progressive.0 as u32)?;
+            input.SetUINT32(&MF_MT_DEFAULT_STRIDE, width * 4)?;
+            input.SetUINT64(&MF_MT_FRAME_SIZE, frame_size)?;
+            input.SetUINT64(&MF_MT_FRAME_RATE, frame_rate)?;
+            input.SetUINT64(&MF_MT_PIXEL_ASPECT_RATIO, square_pixels)?;
+            writer
+                .SetInputMediaType(stream, &input, None)
+                .map_err(at("Setting the encoder input"))?;
+
+            writer.BeginWriting().map_err(at("Starting the MP4 file"))?;
+            let codec = codec_api(&writer, stream);
+
+            Ok(Self {
+                writer,
+                stream,
+                width,
+                height,
+                frame_duration: 10_000_000 / fps as i64,
+                codec,
+                _media_foundation: media_foundation,
+            })
+        }
+    }
+
+    /// Encode `frame` as frame number `index`, lasting `frames` frames
+    /// (cropped to the video size)
+    fn write(&self, frame: &CaptureFrame, index: u64, frames: u64) -> Result<()> {
+        use windows::Win32::Media::MediaFoundation::{MFCreateMemoryBuffer, MFCreateSample};
+
+        let row = self.width as usize;
+        let length = self.width * self.height * 4;
+        unsafe {
+            let buffer = MFCreateMemoryBuffer(length)?;
+            let mut data = std::ptr::null_mut();
+            buffer.Lock(&mut data, None, None)?;
+            let target =
+                std::slice::from_raw_parts_mut(data as *mut u32, row * self.height as usize);
+            for (y, line) in target.chunks_exact_mut(row).enumerate() {
+                let start = y * frame.width as usize;
+                line.copy_from_slice(&frame.pixels[start..start + row]);
+            }
+            buffer.Unlock()?;
+            buffer.SetCurrentLength(length)?;
+
+            let sample = MFCreateSample()?;
+            sample.AddBuffer(&buffer)?;
+            sample.SetSampleTime(index as i64 * self.frame_duration)?;
+            sample.SetSampleDuration(frames as i64 * self.frame_duration)?;
+            self.writer
+                .WriteSample(self.stream, &sample)
+                .map_err(CaptureError::encoder("Encoding a frame"))
+        }
+    }
+
+    /// Samples handed to the sink writer that were not encoded yet
+    fn queued_samples(&self) -> u64 {
+        use windows::Win32::Media::MediaFoundation::MF_SINK_WRITER_STATISTICS;
+
+        let mut stats = MF_SINK_WRITER_STATISTICS {
+            cb: std::mem::size_of::<MF_SINK_WRITER_STATISTICS>() as u32,
+            ..Default::default()
+        };
+        match unsafe { self.writer.GetStatistics(self.stream, &mut stats) } {
+            Ok(()) => stats
+                .qwNumSamplesReceived
+                .saturating_sub(stats.qwNumSamplesEncoded),
+            Err(_) => 0,
+        }
+    }
+
+    /// Switch the encoder to its fastest preset, or back to the original one
+    fn set_fast_preset(&self, fast: bool) {
+        use windows::Win32::Media::MediaFoundation::CODECAPI_AVEncCommonQualityVsSpeed;
+
+        let Some((codec, original)) = &self.codec else {
+            return;
+        };
+        let value = ui4_variant(if fast { 0 } else { *original });
+        if let Err(e) = unsafe { codec.SetValue(&CODECAPI_AVEncCommonQualityVsSpeed, &value) } {
+            warn!("Failed to change the encoder preset: {}", e);
+        }
+    }
+
+    /// Write the end of the file
+    fn finish(self) -> Result<()> {
+        unsafe {
+            self.writer
+                .Finalize()
+                .map_err(CaptureError::encoder("Finishing the MP4 file"))
+        }
+    }
+}
+
+/// The encoder's quality / speed setting and its current value, if it has one
+fn codec_api(
+    writer: &windows::Win32::Media::MediaFoundation::IMFSinkWriter,
+    stream: u32,
+) -> Option<(windows::Win32::Media::MediaFoundation::ICodecAPI, u32)> {
+    use windows::core::{Interface, GUID};
+    use windows::Win32::Media::MediaFoundation::{ICodecAPI, CODECAPI_AVEncCommonQualityVsSpeed};
+    use windows::Win32::System::Variant::VT_UI4;
+
+    unsafe {
+        let mut codec: Option<ICodecAPI> = None;
+        writer
+            .GetServiceForStream(
+                stream,
+                &GUID::zeroed(),
+                &ICodecAPI::IID,
+                &mut codec as *mut _ as *mut *mut std::ffi::c_void,
+            )
+            .ok()?;
+        let codec = codec?;
+        let value = codec.GetValue(&CODECAPI_AVEncCommonQualityVsSpeed).ok()?;
+        let value = &value.Anonymous.Anonymous;
+        (value.vt == VT_UI4).then(|| (codec.clone(), value.Anonymous.ulVal))
+    }
+}
+
+fn ui4_variant(value: u32) -> windows::Win32::System::Variant::VARIANT {
+    use windows::Win32::System::Variant::{VARIANT, VARIANT_0, VARIANT_0_0, VARIANT_0_0_0, VT_UI4};
+
+    VARIANT {
+        Anonymous: VARIANT_0 {
+            Anonymous: std::mem::ManuallyDrop::new(VARIANT_0_0 {
+                vt: VT_UI4,
+                Anonymous: VARIANT_0_0_0 { ulVal: value },
+                ..Default::default()
+            }),
+        },
+    }
+}
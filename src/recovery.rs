@@ -0,0 +1,224 @@
+// recovery.rs - Recovering Unfinished Recordings
+//
+// Recordings (see record.rs) are written as fragmented MP4: the encoder
+// starts a new fragment at every keyframe (every two seconds), and each
+// fragment is complete on disk once written. If RustFrame, the graphics
+// driver or Windows crashes 40 minutes into a recording, everything up to
+// the last fragment is still there - only the index at the end of the file
+// (written when the recording finishes) is missing, which some players need.
+//
+// While a recording runs, a journal entry in %APPDATA%\RustFrame\recordings
+// names its file; it is removed once the file is finished. The entry is held
+// open without sharing, so a recording that is still running (another
+// `RustFrame record`) is never mistaken for an unfinished one.
+//
+// On the next start RustFrame finds the entries left behind and offers to
+// repair the files: `recover` copies the complete fragments into a new,
+// finished MP4 next to the original ("demo.recovered.mp4") without encoding
+// them again. The original file is kept.
+
+use log::{info, warn};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::capture::{CaptureError, Result};
+use crate::constants::{record, settings_file};
+
+/// Journal entry of the running recording (removed by `finish`)
+pub struct Journal {
+    /// Held open without sharing while the recording runs
+    file: Option<File>,
+    path: PathBuf,
+}
+
+impl Journal {
+    /// Note that `video` is being written (None if the journal is unavailable)
+    pub fn start(video: &Path) -> Option<Self> {
+        let video = std::env::current_dir()
+            .map(|dir| dir.join(video))
+            .unwrap_or_else(|_| video.to_path_buf());
+        let result = (|| -> std::io::Result<Self> {
+            let dir = dir().ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::NotFound, "%APPDATA% is not set")
+            })?;
+            std::fs::create_dir_all(&dir)?;
+            let millis = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis();
+            let path = dir.join(format!("{}-{}.txt", millis, std::process::id()));
+            let mut file = open_exclusive(&path)?;
+            file.write_all(video.to_string_lossy().as_bytes())?;
+            file.flush()?;
+            Ok(Self {
+                file: Some(file),
+                path,
+            })
+        })();
+        match result {
+            Ok(journal) => Some(journal),
+            Err(e) => {
+                warn!(
+                    "Recording without a journal, a crash cannot be recovered: {}",
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// The file was finished - nothing to recover
+    pub fn finish(mut self) {
+        self.file = None;
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            warn!("Failed to remove the journal entry {:?}: {}", self.path, e);
+        }
+    }
+}
+
+/// Recordings that were not finished (RustFrame or Windows stopped mid-way)
+pub fn unfinished() -> Vec<PathBuf> {
+    entries().into_iter().map(|(video, _)| video).collect()
+}
+
+/// Copy the complete part of the unfinished recording `video` into a
+/// finished MP4 next to it; returns the new file
+pub fn recover(video: &Path) -> Result<PathBuf> {
+    let out = recovered_path(video);
+    info!("Recovering {:?} into {:?}", video, out);
+    let samples = remux(video, &out)?;
+    info!("Recovered {:?} ({} frames)", out, samples);
+    forget(video);
+    Ok(out)
+}
+
+/// Stop offering to recover `video` (the file itself is kept)
+pub fn forget(video: &Path) {
+    for (entry_video, entry) in entries() {
+        if entry_video == video {
+            let _ = std::fs::remove_file(entry);
+        }
+    }
+}
+
+/// %APPDATA%\RustFrame\recordings (None if %APPDATA% is not set)
+fn dir() -> Option<PathBuf> {
+    std::env::var_os("APPDATA").map(|dir| {
+        PathBuf::from(dir)
+            .join(settings_file::DIR_NAME)
+            .join(record::JOURNAL_DIR_NAME)
+    })
+}
+
+/// Video and journal entry of every unfinished recording; entries whose video
+/// is gone are removed, entries of running recordings skipped
+fn entries() -> Vec<(PathBuf, PathBuf)> {
+    let Some(Ok(dir)) = dir().map(std::fs::read_dir) else {
+        return Vec::new();
+    };
+    let mut entries = Vec::new();
+    for entry in dir.flatten() {
+        let path = entry.path();
+        // Fails while the recording holds the entry open
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let video = PathBuf::from(text.trim());
+        if video.exists() {
+            entries.push((video, path));
+        } else {
+            let _ = std::fs::remove_file(&path);
+        }
+    }
+    entries
+}
+
+/// "demo.mp4" -> "demo.recovered.mp4"
+fn recovered_path(video: &Path) -> PathBuf {
+    let stem = video
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    video.with_file_name(format!("{}.recovered.mp4", stem))
+}
+
+#[cfg(windows)]
+fn open_exclusive(path: &Path) -> std::io::Result<File> {
+    use std::os::windows::fs::OpenOptionsExt;
+
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .share_mode(0)
+        .open(path)
+}
+
+#[cfg(not(windows))]
+fn open_exclusive(path: &Path) -> std::io::Result<File> {
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+}
+
+/// Copy the H.264 samples of `video` into `out` until the data ends or
+/// breaks off; returns the number of samples
+fn remux(video: &Path, out: &Path) -> Result<u64> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Media::MediaFoundation::*;
+
+    let at = CaptureError::encoder;
+    let video_url = crate::utils::wide_string(&video.to_string_lossy());
+    let out_url = crate::utils::wide_string(&out.to_string_lossy());
+
+    let _media_foundation = crate::record::MediaFoundation::start()?;
+    unsafe {
+        let reader = MFCreateSourceReaderFromURL(PCWSTR(video_url.as_ptr()), None)
+            .map_err(at("Opening the unfinished recording"))?;
+        let stream = MF_SOURCE_READER_FIRST_VIDEO_STREAM.0 as u32;
+        // The stored H.264 type - the samples are copied, not encoded again
+        let media_type = reader
+            .GetNativeMediaType(stream, 0)
+            .map_err(at("Reading the video format"))?;
+        let writer = MFCreateSinkWriterFromURL(PCWSTR(out_url.as_ptr()), None, None)
+            .map_err(at("MFCreateSinkWriterFromURL"))?;
+        let out_stream = writer
+            .AddStream(&media_type)
+            .map_err(at("Adding the H.264 stream"))?;
+        writer.SetInputMediaType(out_stream, &media_type, None)?;
+        writer.BeginWriting().map_err(at("Starting the MP4 file"))?;
+
+        let mut samples = 0;
+        loop {
+            let mut flags = 0u32;
+            let mut sample = None;
+            if let Err(e) =
+                reader.ReadSample(stream, 0, None, Some(&mut flags), None, Some(&mut sample))
+            {
+                // The last fragment was cut off
+                warn!("The recording breaks off after {} frames: {}", samples, e);
+                break;
+            }
+            if flags & MF_SOURCE_READERF_ENDOFSTREAM.0 as u32 != 0 {
+                break;
+            }
+            if let Some(sample) = sample {
+                writer
+                    .WriteSample(out_stream, &sample)
+                    .map_err(at("Copying a frame"))?;
+                samples += 1;
+            }
+        }
+        if samples == 0 {
+            drop(writer);
+            let _ = std::fs::remove_file(out);
+            return Err(CaptureError::Encoder(
+                "The recording has no complete frames",
+                windows::Win32::Foundation::E_FAIL.into(),
+            ));
+        }
+        writer.Finalize().map_err(at("Finishing the MP4 file"))?;
+        Ok(samples)
+    }
+}
@@ -19,10 +19,168 @@ use anyhow::{anyhow, Context, Result};
 use log::{info, warn};
 use std::sync::Arc;
 use wgpu::util::DeviceExt;
-use windows::Win32::Graphics::Direct3D11::*;
 use winit::window::Window;
 
-use crate::capture::{CaptureEngine, CaptureRect};
+use crate::annotation::AnnotationLayer;
+use crate::bitmap_font::Canvas;
+use crate::chroma_key::ChromaKey;
+use crate::click_highlight::ClickHighlight;
+use crate::composite::{CompositeLayout, SourceRect};
+use crate::constants::renderer;
+use crate::enhance::OutputEnhance;
+use crate::filter::{FilterChain, FilterKind, FrameFilter, OverlayOpacity};
+use crate::frame_diff::FrameDiff;
+use crate::grid::CompositionGrid;
+use crate::magnifier::Magnifier;
+use crate::plugin::PluginHost;
+use crate::pause_screen::PauseScreen;
+use crate::privacy::PrivacyRegions;
+use crate::capture::{CaptureRect, GpuAdapter};
+use crate::source::FrameSource;
+use crate::spotlight::Spotlight;
+use crate::timer::OverlayTimer;
+use crate::transition::{Transition, TransitionKind, TransitionUniform};
+use crate::window_mask::WindowMasker;
+
+/// Everything that gets composited on top of the captured pixels
+/// Each overlay keeps a revision counter that only ever increases, so the sum
+/// of all revisions changes whenever any overlay changes
+/// The filters run in the order configured in the filter chain
+pub struct FrameOverlays<'a> {
+    pub filters: &'a FilterChain,
+    pub annotations: &'a AnnotationLayer,
+    pub clicks: &'a ClickHighlight,
+    pub spotlight: &'a Spotlight,
+    pub magnifier: &'a Magnifier,
+    pub timer: &'a OverlayTimer,
+    pub pause: &'a PauseScreen,
+    pub diff: &'a FrameDiff,
+    pub grid: &'a CompositionGrid,
+    pub opacity: &'a OverlayOpacity,
+    pub plugins: &'a PluginHost,
+}
+
+impl FrameOverlays<'_> {
+    /// The overlay behind a filter chain entry (None for the redaction
+    /// filters, which run before compositing - see Redaction)
+    fn filter(&self, kind: FilterKind) -> Option<&dyn FrameFilter> {
+        match kind {
+            FilterKind::WindowMask | FilterKind::Privacy => None,
+            FilterKind::Spotlight => Some(self.spotlight),
+            FilterKind::Annotations => Some(self.annotations),
+            FilterKind::Clicks => Some(self.clicks),
+            FilterKind::Magnifier => Some(self.magnifier),
+            FilterKind::Grid => Some(self.grid),
+            FilterKind::Timer => Some(self.timer),
+        }
+    }
+
+    fn revision(&self) -> u64 {
+        FilterKind::ALL
+            .into_iter()
+            .filter_map(|kind| self.filter(kind))
+            .map(|filter| filter.revision())
+            .sum::<u64>()
+            + self.filters.revision()
+            + self.pause.revision()
+            + self.diff.revision()
+            + self.opacity.revision()
+    }
+
+    /// True when no overlay would modify the frame
+    fn is_passthrough(&self) -> bool {
+        !self.pause.is_paused()
+            && !self.diff.is_active()
+            && self
+                .filters
+                .enabled()
+                .filter_map(|k| self.filter(k))
+                .all(|filter| !filter.is_active())
+            && !self.plugins.has_filters()
+    }
+
+    /// Composite all enabled filters in chain order (default order: spotlight
+    /// before annotations so they stay bright,
+    /// magnifier after annotations so its lens also zooms into them, grid after
+    /// the magnifier so guide lines are not zoomed, timer last)
+    /// While paused the frame is replaced by the pause screen (the timer stays visible)
+    /// While comparing frames the captured pixels are replaced by the
+    /// comparison view first, so the overlays stay on top (see frame_diff.rs)
+    /// Below full overlay opacity, drawn overlays are blended with the frame
+    /// Plugin filters run after the chain (see plugin.rs)
+    fn apply(&self, canvas: &mut Canvas) {
+        if self.pause.is_paused() {
+            self.pause.draw(canvas);
+            if self.filters.enabled().any(|k| k == FilterKind::Timer) {
+                self.timer.draw(canvas);
+            }
+            return;
+        }
+
+        self.diff.apply(canvas);
+        for kind in self.filters.enabled() {
+            let Some(filter) = self.filter(kind) else {
+                continue;
+            };
+            if kind.fades() && self.opacity.percent() < 100 && filter.is_active() {
+                let before = canvas.pixels.to_vec();
+                filter.apply(canvas);
+                self.opacity.blend(canvas.pixels, &before);
+            } else {
+                filter.apply(canvas);
+            }
+        }
+        self.plugins.filter(canvas);
+    }
+}
+
+/// Redaction of the captured screen pixels (redacted application windows,
+/// privacy regions), applied to each frame before the frames are composited
+/// so it lands on the right pixels wherever the layout puts the frame
+pub struct Redaction<'a> {
+    pub filters: &'a FilterChain,
+    pub window_mask: &'a WindowMasker,
+    pub privacy: &'a PrivacyRegions,
+    /// Capture region shown by the main frame and by each additional source
+    /// (0 = main region, i + 1 = additional region i, None = no screen
+    /// capture, e.g. the test pattern, webcam or image)
+    pub regions: &'a [Option<usize>],
+}
+
+impl Redaction<'_> {
+    fn revision(&self) -> u64 {
+        self.window_mask.revision() + self.privacy.revision() + self.filters.revision()
+    }
+
+    /// Redacted copy of frame `index` (0 = main frame, i + 1 = additional
+    /// source i), None if nothing covers it
+    fn apply(&self, index: usize, pixels: &[u32], (width, height): (u32, u32)) -> Option<Vec<u32>> {
+        let region = self.regions.get(index).copied().flatten()?;
+        let mask =
+            self.filters.is_enabled(FilterKind::WindowMask) && self.window_mask.covers(region);
+        // Privacy regions are drawn over the main region
+        let privacy =
+            region == 0 && self.filters.is_enabled(FilterKind::Privacy) && !self.privacy.is_empty();
+        if !(mask || privacy) || pixels.len() < (width * height) as usize {
+            return None;
+        }
+
+        let mut redacted = pixels.to_vec();
+        let mut canvas = Canvas {
+            pixels: &mut redacted,
+            width: width as i32,
+            height: height as i32,
+        };
+        for kind in self.filters.enabled() {
+            match kind {
+                FilterKind::WindowMask if mask => self.window_mask.apply(region, &mut canvas),
+                FilterKind::Privacy if privacy => self.privacy.apply(&mut canvas),
+                _ => {}
+            }
+        }
+        Some(redacted)
+    }
+}
 
 /// The renderer that displays captured frames in the destination window
 pub struct Renderer {
@@ -41,7 +199,7 @@ pub struct Renderer {
     /// Render pipeline (vertex/fragment shaders and state)
     render_pipeline: wgpu::RenderPipeline,
 
-    /// Bind group layout (describes texture bindings)
+    /// Bind group layout (describes texture, sampler and shader parameter bindings)
     bind_group_layout: wgpu::BindGroupLayout,
 
     /// Sampler for texture sampling
@@ -55,11 +213,90 @@ pub struct Renderer {
 
     /// Frame counter for debugging
     frame_count: u32,
+
+    /// Start of the current one-second window and the frame count at that time
+    fps_window: (std::time::Instant, u32),
+
+    /// Frames presented during the last full second
+    fps: u32,
+
+    /// Reading the main source failed on the last frame
+    source_lost: bool,
+
+    /// Last captured frame (cropped, ARGB) - kept so overlays can be
+    /// recomposited even when WGC has no new frame for us
+    last_frame: Vec<u32>,
+
+    /// Size of last_frame in pixels
+    last_frame_size: (u32, u32),
+
+    /// Buffer a new frame is read into - swapped with last_frame when its
+    /// content differs
+    incoming_frame: Vec<u32>,
+
+    /// When the presented content last changed (new pixels, overlays, regions)
+    changed_at: std::time::Instant,
+
+    /// Parts of last_frame that changed since it was last uploaded as it is
+    /// (None = unknown, upload the whole frame)
+    dirty: Option<Vec<CaptureRect>>,
+
+    /// Overlay revision that was composited into the last presented frame
+    composed_revision: u64,
+
+    /// Freeze-frame: new captured frames are dropped and last_frame keeps being shown
+    frozen: bool,
+
+    /// Uniform buffer with the chroma key parameters (bind group binding 2)
+    chroma_key_buffer: wgpu::Buffer,
+
+    /// Uniform buffer with the sharpen / denoise parameters (bind group binding 3)
+    enhance_buffer: wgpu::Buffer,
+
+    /// Previously presented frame texture (temporal denoise, bind group binding 4)
+    previous_frame: Option<(wgpu::Texture, wgpu::TextureView)>,
+
+    /// Shader settings changed - present again even without a new frame
+    settings_changed: bool,
+
+    /// Uniform buffer with the transition parameters (bind group binding 6)
+    transition_buffer: wgpu::Buffer,
+
+    /// Running transition and the frame it fades out (bind group binding 5)
+    transition: Option<((wgpu::Texture, wgpu::TextureView), Transition)>,
+
+    /// Last frames of the additional sources (regions, webcam; ARGB) and their sizes
+    region_frames: Vec<(Vec<u32>, (u32, u32))>,
+
+    /// How the region frames are arranged around the main frame
+    composite_layout: CompositeLayout,
+
+    /// Rectangles of the custom layout, in the order of the frames
+    custom_layout: Vec<SourceRect>,
+
+    /// Size each frame is given in the output (main frame first), so a source
+    /// without a frame yet keeps its place in the layout
+    source_sizes: Vec<(u32, u32)>,
+
+    /// Whether the main capture region is part of the output (false when a
+    /// fullscreen webcam replaces it)
+    screen_visible: bool,
+
+    /// A copy of the next composed frame was requested (screenshot)
+    snapshot_requested: bool,
+
+    /// Composed frame (ARGB) and its size, waiting to be taken
+    snapshot: Option<(Vec<u32>, u32, u32)>,
+
+    /// Staging buffers and frame textures the frames are uploaded with
+    uploader: FrameUploader,
 }
 
 impl Renderer {
     /// Create a new renderer for the destination window
-    pub fn new(window: &Arc<Window>) -> Result<Self> {
+    /// `capture_gpu`: the GPU the frames are captured on - used for rendering
+    /// too when it can present to the window
+    pub fn new(window: &Arc<Window>, capture_gpu: Option<&GpuAdapter>) -> Result<Self> {
         info!("Initializing wgpu renderer");
 
         // STEP 1: Create wgpu instance
@@ -79,18 +316,45 @@ impl Renderer {
         info!("Surface created");
 
         // STEP 3: Request adapter
-        // The adapter represents a physical GPU
-        let adapter =
-            match pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::HighPerformance,
-                compatible_surface: Some(&surface),
-                force_fallback_adapter: false,
-            })) {
+        // The adapter represents a physical GPU - preferably the one the capture
+        // runs on: on hybrid graphics laptops the high-performance GPU is often
+        // not the one driving the captured monitor
+        let same_gpu = |adapter: &wgpu::Adapter, gpu: &GpuAdapter| {
+            let info = adapter.get_info();
+            info.vendor == gpu.vendor_id && info.device == gpu.device_id
+        };
+        let shared = match capture_gpu {
+            Some(gpu) => pollster::block_on(instance.enumerate_adapters(wgpu::Backends::DX12))
+                .into_iter()
+                .find(|adapter| same_gpu(adapter, gpu) && adapter.is_surface_supported(&surface)),
+            None => None,
+        };
+        let adapter = match shared {
+            Some(adapter) => adapter,
+            None => match pollster::block_on(instance.request_adapter(
+                &wgpu::RequestAdapterOptions {
+                    power_preference: wgpu::PowerPreference::HighPerformance,
+                    compatible_surface: Some(&surface),
+                    force_fallback_adapter: false,
+                },
+            )) {
                 Ok(adapter) => adapter,
                 Err(e) => return Err(anyhow!("Failed to find suitable GPU adapter: {:?}", e)),
-            };
+            },
+        };
 
         info!("Adapter acquired: {:?}", adapter.get_info());
+        if let Some(gpu) = capture_gpu.filter(|gpu| !same_gpu(&adapter, gpu)) {
+            // Frames already reach the renderer through system memory, so this
+            // costs bandwidth rather than correctness
+            warn!(
+                "Capturing on {} but rendering on {} - every frame is copied between the GPUs. \
+                 Set RustFrame to the GPU of the captured monitor in Windows Settings → System → \
+                 Display → Graphics",
+                gpu.name,
+                adapter.get_info().name
+            );
+        }
 
         // STEP 4: Request device and queue
         // The device is our interface to the GPU, the queue submits commands
@@ -152,6 +416,61 @@ impl Renderer {
                     ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
                     count: None,
                 },
+                // Chroma key parameters
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // Sharpen / denoise parameters
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // Previous frame (temporal denoise)
+                wgpu::BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // Frame faded out by a transition
+                wgpu::BindGroupLayoutEntry {
+                    binding: 5,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // Transition parameters
+                wgpu::BindGroupLayoutEntry {
+                    binding: 6,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
             ],
         });
 
@@ -226,6 +545,28 @@ impl Renderer {
         });
         info!("Vertex buffer created");
 
+        // STEP 12: Create the chroma key uniform buffer (keying disabled by default)
+        let chroma_key_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Chroma Key Buffer"),
+            contents: bytemuck::bytes_of(&ChromaKey::new().uniform()),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        // STEP 13: Create the sharpen / denoise uniform buffer (both off by default)
+        let enhance_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Enhance Buffer"),
+            contents: bytemuck::bytes_of(&OutputEnhance::default().uniform()),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        // STEP 14: Create the transition uniform buffer (no transition running)
+        let transition_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Transition Buffer"),
+            contents: bytemuck::bytes_of(&TransitionUniform::done()),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let uploader = FrameUploader::new(&device);
+
         Ok(Self {
             surface,
             device,
@@ -237,6 +578,30 @@ impl Renderer {
             vertex_buffer,
             window_size: (window_size.width, window_size.height),
             frame_count: 0,
+            fps_window: (std::time::Instant::now(), 0),
+            fps: 0,
+            source_lost: false,
+            last_frame: Vec::new(),
+            last_frame_size: (0, 0),
+            incoming_frame: Vec::new(),
+            changed_at: std::time::Instant::now(),
+            dirty: None,
+            composed_revision: 0,
+            frozen: false,
+            chroma_key_buffer,
+            enhance_buffer,
+            previous_frame: None,
+            settings_changed: false,
+            transition_buffer,
+            transition: None,
+            region_frames: Vec::new(),
+            composite_layout: CompositeLayout::SideBySide,
+            custom_layout: Vec::new(),
+            source_sizes: Vec::new(),
+            screen_visible: true,
+            snapshot_requested: false,
+            snapshot: None,
+            uploader,
         })
     }
 
@@ -251,33 +616,282 @@ impl Renderer {
         }
     }
 
-    /// Render a frame from the capture engine
-    pub fn render(&mut self, capture: &mut CaptureEngine) -> Result<()> {
-        // STEP 1: Get the latest captured frame surface from WGC
-        let frame_surface = match capture.get_latest_frame_surface() {
-            Some(surf) => surf,
-            None => {
-                // No new frame available - don't clear to black!
-                // Just skip this render cycle and keep the previous frame displayed
-                // This prevents the rapid about_to_wait loop from overwriting good frames
+    /// Number of frames presented so far (changes whenever a new frame was shown)
+    pub fn frame_count(&self) -> u32 {
+        self.frame_count
+    }
+
+    /// Whether the main source failed to deliver the last frame
+    pub fn source_lost(&self) -> bool {
+        self.source_lost
+    }
+
+    /// Time since the presented content last changed (see adaptive frame rate
+    /// in main.rs)
+    pub fn unchanged_for(&self) -> std::time::Duration {
+        self.changed_at.elapsed()
+    }
+
+    /// Frames presented during the last second (0 if nothing was presented lately)
+    pub fn fps(&self) -> u32 {
+        if self.fps_window.0.elapsed().as_secs() >= 2 {
+            0
+        } else {
+            self.fps
+        }
+    }
+
+    /// Last captured frame (cropped, without overlays) and its size
+    pub fn last_frame(&self) -> (&[u32], u32, u32) {
+        (&self.last_frame, self.last_frame_size.0, self.last_frame_size.1)
+    }
+
+    /// Keep a copy of the next composed frame (see take_snapshot)
+    /// The output is recomposited even if no new frame arrives
+    pub fn request_snapshot(&mut self) {
+        self.snapshot_requested = true;
+        self.settings_changed = true;
+    }
+
+    /// The frame requested with request_snapshot, once it was composed
+    /// (capture with overlays, before the shader stages) and its size
+    pub fn take_snapshot(&mut self) -> Option<(Vec<u32>, u32, u32)> {
+        self.snapshot.take()
+    }
+
+    /// Freeze or unfreeze the captured content
+    /// While frozen the output keeps showing the last frame (overlays stay live)
+    pub fn set_frozen(&mut self, frozen: bool) {
+        self.frozen = frozen;
+    }
+
+    /// Update the chroma key stage of the output shader
+    pub fn set_chroma_key(&mut self, chroma_key: &ChromaKey) {
+        self.queue.write_buffer(
+            &self.chroma_key_buffer,
+            0,
+            bytemuck::bytes_of(&chroma_key.uniform()),
+        );
+        self.settings_changed = true;
+    }
+
+    /// Update the sharpen / denoise stages of the output shader
+    pub fn set_enhance(&mut self, enhance: &OutputEnhance) {
+        self.queue
+            .write_buffer(&self.enhance_buffer, 0, bytemuck::bytes_of(&enhance.uniform()));
+        self.settings_changed = true;
+    }
+
+    /// Change how additional capture regions are arranged in the output
+    pub fn set_composite_layout(&mut self, layout: CompositeLayout) {
+        self.composite_layout = layout;
+        self.settings_changed = true;
+    }
+
+    /// Change the rectangles of the custom layout (main frame first, then the
+    /// additional sources in order - see composite::custom_rects)
+    pub fn set_custom_layout(&mut self, rects: Vec<SourceRect>) {
+        if self.custom_layout != rects {
+            self.custom_layout = rects;
+            self.settings_changed = true;
+        }
+    }
+
+    /// Change the sizes the output was arranged for (main frame first, then
+    /// the additional sources in order)
+    pub fn set_source_sizes(&mut self, sizes: Vec<(u32, u32)>) {
+        self.source_sizes = sizes;
+    }
+
+    /// Show or leave out the main capture region (the other sources stay)
+    pub fn set_screen_visible(&mut self, visible: bool) {
+        self.screen_visible = visible;
+        self.settings_changed = true;
+    }
+
+    /// Transition from the frame currently shown to whatever is rendered next
+    /// (scene switch, freeze / pause toggled) - see transition.rs
+    pub fn start_transition(&mut self, kind: TransitionKind, duration_ms: u32, color: u32) {
+        let transition = Transition::start(kind, duration_ms, color);
+        self.transition = match (self.previous_frame.clone(), transition) {
+            (Some(previous), Some(transition)) => Some((previous, transition)),
+            _ => None,
+        };
+    }
+
+    /// Render a frame from the main source (capture region or test pattern)
+    /// Each frame is redacted first (see Redaction), then the frames of the
+    /// additional `sources` (capture regions, webcam) are placed next to the
+    /// main frame (see composite.rs) and overlays are composited on top of the
+    /// captured pixels so they end up in everything that consumes the
+    /// destination window (None = show the captured pixels untouched)
+    pub fn render(
+        &mut self,
+        main: &mut dyn FrameSource,
+        sources: &mut [&mut dyn FrameSource],
+        redaction: &Redaction,
+        overlays: Option<&FrameOverlays>,
+    ) -> Result<()> {
+        let revision = overlays.map_or(0, |o| o.revision()) + redaction.revision();
+        // A running transition has to be presented on every pass
+        let overlays_changed = revision != self.composed_revision
+            || self.settings_changed
+            || self.transition.is_some();
+
+        // Pick up new frames of the additional sources (dropped while frozen)
+        let regions_changed = self.read_region_frames(sources);
+        if overlays_changed || regions_changed {
+            self.changed_at = std::time::Instant::now();
+        }
+
+        // STEP 1: Get the latest frame of the main source (the capture region
+        // via WGC, or the test pattern)
+        if self.frozen && !self.last_frame.is_empty() {
+            // Frozen - new frames are left unread so the output keeps the old content
+            if !overlays_changed && !regions_changed {
                 return Ok(());
             }
-        };
+        } else {
+            // STEP 2: Copy the new frame into incoming_frame (for WGC: the cropped
+            // region of the D3D11 texture, via a CPU-readable staging texture)
+            let read = main.read_frame(&mut self.incoming_frame);
+            self.source_lost = read.is_err();
+            match read {
+                Ok(Some(size))
+                    if size == self.last_frame_size && self.incoming_frame == self.last_frame =>
+                {
+                    // WGC delivers a frame when anything on the monitor changed -
+                    // the region itself is the same, so there is nothing new to
+                    // present unless an overlay or another region changed
+                    if !overlays_changed && !regions_changed {
+                        return Ok(());
+                    }
+                }
+                Ok(Some(size)) => {
+                    std::mem::swap(&mut self.last_frame, &mut self.incoming_frame);
+                    let dirty = main.dirty_rects().filter(|_| size == self.last_frame_size);
+                    self.dirty = match (self.dirty.take(), dirty) {
+                        (Some(mut pending), Some(dirty)) => {
+                            pending.extend(dirty);
+                            Some(pending)
+                        }
+                        _ => None,
+                    };
+                    self.last_frame_size = size;
+                    self.changed_at = std::time::Instant::now();
+                }
+                Ok(None)
+                    if (overlays_changed || regions_changed) && !self.last_frame.is_empty() =>
+                {
+                    // No new capture frame, but an overlay or another region changed -
+                    // recomposite on top of the previous frame
+                }
+                Ok(None) => {
+                    // No new frame available - don't clear to black!
+                    // Just skip this render cycle and keep the previous frame displayed
+                    // This prevents the rapid about_to_wait loop from overwriting good frames
+                    return Ok(());
+                }
+                Err(e) => {
+                    warn!("Failed to read the captured frame: {:?}. Rendering clear color.", e);
+                    return self.render_clear();
+                }
+            }
+        }
 
-        // STEP 2: Convert the WinRT IDirect3DSurface to COM ID3D11Texture2D
-        // Use DXGI as the bridge between WinRT and COM interfaces
-        let d3d11_texture: ID3D11Texture2D = match self.cast_surface_to_texture(&frame_surface) {
-            Ok(tex) => tex,
-            Err(e) => {
-                warn!(
-                    "Failed to cast surface to D3D11 texture: {:?}. Rendering clear color.",
-                    e
-                );
-                return self.render_clear();
+        // STEP 3: Redact each frame in its own coordinates
+        let main_redacted = redaction.apply(0, &self.last_frame, self.last_frame_size);
+        let main_frame = main_redacted.as_deref().unwrap_or(&self.last_frame);
+        let region_frames: Vec<Option<Vec<u32>>> = self
+            .region_frames
+            .iter()
+            .enumerate()
+            .map(|(i, (pixels, size))| redaction.apply(i + 1, pixels, *size))
+            .collect();
+
+        // STEP 4: Place the region frames, composite the overlays and upload
+        // the result to a wgpu texture
+        let arranged = if self.region_frames.is_empty()
+            && self.screen_visible
+            && self.composite_layout.passes_single_frame()
+        {
+            None
+        } else {
+            let mut frames = Vec::new();
+            if self.screen_visible {
+                frames.push((main_frame, self.last_frame_size.0, self.last_frame_size.1));
+            }
+            // Frames are placed by their position in the list, so a source
+            // without a frame yet keeps its place (left blank) at the size the
+            // output was arranged for
+            let first = frames.len();
+            for (i, (pixels, (w, h))) in self.region_frames.iter().enumerate() {
+                frames.push(match &region_frames[i] {
+                    Some(redacted) => (redacted.as_slice(), *w, *h),
+                    None if pixels.is_empty() => {
+                        let size = self.source_sizes.get(first + i).copied();
+                        let (w, h) = size.unwrap_or((0, 0));
+                        (pixels.as_slice(), w, h)
+                    }
+                    None => (pixels.as_slice(), *w, *h),
+                });
             }
+            if first == 0 && self.region_frames.iter().all(|(p, _)| p.is_empty()) {
+                // Only the hidden screen region so far - wait for the other sources
+                return Ok(());
+            }
+            Some(self.composite_layout.compose(&frames, &self.custom_layout))
         };
+        let (base, width, height) = match &arranged {
+            Some((pixels, w, h)) => (pixels.as_slice(), *w, *h),
+            None => (main_frame, self.last_frame_size.0, self.last_frame_size.1),
+        };
+        let composed = match overlays {
+            Some(overlays) if !overlays.is_passthrough() => {
+                let mut composed = base.to_vec();
+                let mut canvas = Canvas {
+                    pixels: &mut composed,
+                    width: width as i32,
+                    height: height as i32,
+                };
+                overlays.apply(&mut canvas);
+                Some(composed)
+            }
+            _ => None,
+        };
+        let pixels = composed.as_deref().unwrap_or(base);
+        // Only the captured frame as it is can be updated in parts - overlays
+        // and layouts may change any pixel
+        let raw = arranged.is_none() && composed.is_none() && main_redacted.is_none();
+        let dirty = self.dirty.replace(Vec::new()).filter(|_| raw);
+        if self.snapshot_requested {
+            self.snapshot_requested = false;
+            self.snapshot = Some((pixels.to_vec(), width, height));
+        }
+        if let Some(overlays) = overlays {
+            overlays.plugins.sink(pixels, width, height);
+        }
 
-        // STEP 3: Get the current surface texture (what we're rendering to)
+        // One command encoder takes the upload and the render pass; the frame
+        // goes into a texture the denoise and transition inputs do not use
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Render Encoder"),
+            });
+        let previous = self.previous_frame.as_ref().map(|(texture, _)| texture);
+        let faded_out = self.transition.as_ref().map(|((texture, _), _)| texture);
+        let (texture, texture_view) = self.uploader.upload(
+            &self.device,
+            &mut encoder,
+            (pixels, width, height),
+            dirty.as_deref(),
+            |texture| previous == Some(texture) || faded_out == Some(texture),
+        );
+        self.composed_revision = revision;
+        self.settings_changed = false;
+
+        // Get the current surface texture (what we're rendering to)
         let output = self
             .surface
             .get_current_texture()
@@ -287,17 +901,18 @@ impl Renderer {
             .texture
             .create_view(&wgpu::TextureViewDescriptor::default());
 
-        // STEP 4: Copy D3D11 texture to wgpu texture
-        // This uses CPU-side copying via staging texture
-        let (_texture, texture_view) = self.copy_d3d11_texture_to_wgpu(
-            &d3d11_texture,
-            capture.get_d3d_device(),
-            capture.get_d3d_context(),
-            capture.get_capture_region(),
-            capture.get_monitor_origin(),
-        )?;
-
         // STEP 5: Create bind group for this frame
+        // (the first frame has no predecessor - denoise then compares it with itself)
+        let previous_view = match &self.previous_frame {
+            Some((_, view)) => view,
+            None => &texture_view,
+        };
+        let (faded_out_view, transition) = match &self.transition {
+            Some(((_, view), transition)) => (view, transition.uniform()),
+            None => (&texture_view, TransitionUniform::done()),
+        };
+        self.queue
+            .write_buffer(&self.transition_buffer, 0, bytemuck::bytes_of(&transition));
         let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Texture Bind Group"),
             layout: &self.bind_group_layout,
@@ -310,16 +925,30 @@ impl Renderer {
                     binding: 1,
                     resource: wgpu::BindingResource::Sampler(&self.sampler),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.chroma_key_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: self.enhance_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: wgpu::BindingResource::TextureView(previous_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 5,
+                    resource: wgpu::BindingResource::TextureView(faded_out_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 6,
+                    resource: self.transition_buffer.as_entire_binding(),
+                },
             ],
         });
 
-        // STEP 6: Create command encoder and render pass
-        let mut encoder = self
-            .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                label: Some("Render Encoder"),
-            });
-
+        // STEP 6: Render pass
         {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
@@ -352,41 +981,49 @@ impl Renderer {
 
         // STEP 7: Submit commands and present
         self.queue.submit(std::iter::once(encoder.finish()));
+        self.uploader.submitted();
         output.present();
 
+        // Keep this frame for the next frame's temporal denoise
+        self.previous_frame = Some((texture, texture_view));
+        if transition.is_done() {
+            self.transition = None;
+        }
+
         // Log every 60 frames to confirm rendering is working
         self.frame_count += 1;
         if self.frame_count.is_multiple_of(60) {
             info!("Rendered frame #{}", self.frame_count);
         }
+        if self.fps_window.0.elapsed().as_secs() >= 1 {
+            self.fps = self.frame_count - self.fps_window.1;
+            self.fps_window = (std::time::Instant::now(), self.frame_count);
+        }
 
         Ok(())
     }
 
-    /// Cast WinRT IDirect3DSurface to COM ID3D11Texture2D using DXGI as bridge
-    /// This properly handles the WinRT↔COM interface conversion
-    fn cast_surface_to_texture(
-        &self,
-        surface: &windows::Graphics::DirectX::Direct3D11::IDirect3DSurface,
-    ) -> Result<ID3D11Texture2D> {
-        use windows::core::Interface;
-        use windows::Win32::System::WinRT::Direct3D11::IDirect3DDxgiInterfaceAccess;
-
-        // The correct way to get the underlying DXGI/D3D11 interface from a WinRT IDirect3DSurface
-        // is through IDirect3DDxgiInterfaceAccess::GetInterface()
-        unsafe {
-            // Cast the WinRT surface to the interop interface
-            let interop: IDirect3DDxgiInterfaceAccess = surface
-                .cast()
-                .context("Failed to cast IDirect3DSurface to IDirect3DDxgiInterfaceAccess")?;
-
-            // Get the underlying D3D11 texture
-            let texture: ID3D11Texture2D = interop
-                .GetInterface()
-                .context("Failed to get ID3D11Texture2D from IDirect3DDxgiInterfaceAccess")?;
-
-            Ok(texture)
+    /// Read the latest frames of the additional sources (regions, webcam)
+    /// Returns true if any of them changed
+    fn read_region_frames(&mut self, sources: &mut [&mut dyn FrameSource]) -> bool {
+        let mut changed = self.region_frames.len() != sources.len();
+        self.region_frames.resize_with(sources.len(), Default::default);
+
+        for (source, (pixels, size)) in sources.iter_mut().zip(self.region_frames.iter_mut()) {
+            if self.frozen && !pixels.is_empty() {
+                continue;
+            }
+            match source.read_frame(pixels) {
+                Ok(Some(new_size)) => {
+                    *size = new_size;
+                    changed = true;
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Failed to read source frame: {}", e),
+            }
         }
+
+        changed
     }
 
     /// Render a clear frame (black screen)
@@ -430,157 +1067,195 @@ impl Renderer {
 
         Ok(())
     }
+}
 
-    /// Copy a D3D11 texture to a wgpu texture
-    ///
-    /// This is the bridge between Windows.Graphics.Capture (D3D11) and wgpu (DX12/Vulkan).
-    /// The process:
-    /// 1. Create a staging texture in D3D11 (CPU-readable)
-    /// 2. Copy the captured texture to the staging texture
-    /// 3. Map the staging texture and read pixel data to CPU
-    /// 4. Create a wgpu texture and upload the pixel data
-    ///
-    /// WHY: wgpu and D3D11 don't share memory directly without using HAL (Hardware Abstraction Layer)
-    /// This is the simplest approach but involves a CPU roundtrip.
-    ///
-    /// PERFORMANCE: This is not ideal for real-time capture (adds latency and CPU overhead)
-    /// For production, you'd want to use:
-    /// - Direct3D12 interop with wgpu's DX12 backend
-    /// - wgpu HAL for zero-copy texture sharing
-    fn copy_d3d11_texture_to_wgpu(
-        &self,
-        d3d11_texture: &ID3D11Texture2D,
-        d3d_device: &ID3D11Device,
-        d3d_context: &ID3D11DeviceContext,
-        crop_region: CaptureRect,
-        monitor_origin: (i32, i32),
-    ) -> Result<(wgpu::Texture, wgpu::TextureView)> {
-        // STEP 1: Get the texture description
-        let mut desc = D3D11_TEXTURE2D_DESC::default();
-        unsafe {
-            d3d11_texture.GetDesc(&mut desc);
-        }
+/// Uploads the composed frames to the GPU
+///
+/// The pixels go through a staging belt (a ring of mapped staging buffers that
+/// are reused once the GPU has copied them) into the render pass's command
+/// encoder, and the frame textures are kept while the frame size stays the
+/// same - no texture or staging buffer is created per frame.
+struct FrameUploader {
+    belt: wgpu::util::StagingBelt,
+    /// Frame textures of the current frame size (the shown frame, the previous
+    /// one for temporal denoise and the one a transition fades out)
+    textures: Vec<PooledTexture>,
+}
 
-        // STEP 2: Create a staging texture (CPU-readable)
-        // This is necessary because the captured texture is on the GPU
-        let staging_desc = D3D11_TEXTURE2D_DESC {
-            Width: desc.Width,
-            Height: desc.Height,
-            MipLevels: 1,
-            ArraySize: 1,
-            Format: desc.Format, // Keep same format (should be BGRA8)
-            SampleDesc: windows::Win32::Graphics::Dxgi::Common::DXGI_SAMPLE_DESC {
-                Count: 1,
-                Quality: 0,
-            },
-            Usage: D3D11_USAGE_STAGING, // Staging = CPU-readable
-            BindFlags: D3D11_BIND_FLAG(0).0 as u32,
-            CPUAccessFlags: D3D11_CPU_ACCESS_READ.0 as u32, // Allow CPU reads
-            MiscFlags: D3D11_RESOURCE_MISC_FLAG(0).0 as u32,
-        };
+/// A reusable frame texture
+struct PooledTexture {
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    /// Parts that differ from the newest frame (None = all of it)
+    stale: Option<Vec<CaptureRect>>,
+}
 
-        let mut staging_texture: Option<ID3D11Texture2D> = None;
-        unsafe {
-            d3d_device
-                .CreateTexture2D(&staging_desc, None, Some(&mut staging_texture))
-                .context("Failed to create staging texture")?;
+impl FrameUploader {
+    fn new(device: &wgpu::Device) -> Self {
+        Self {
+            belt: wgpu::util::StagingBelt::new(device.clone(), renderer::UPLOAD_CHUNK_BYTES),
+            textures: Vec::new(),
         }
+    }
 
-        let staging_texture =
-            staging_texture.ok_or_else(|| anyhow!("Staging texture creation returned null"))?;
-
-        // STEP 3: Copy from captured texture to staging texture (GPU -> GPU)
-        unsafe {
-            d3d_context.CopyResource(&staging_texture, d3d11_texture);
+    /// Record the upload of `pixels` into `encoder`; returns the texture it ends
+    /// up in - one that `in_use` does not claim (call `submitted` after the
+    /// encoder was submitted)
+    ///
+    /// `dirty` lists the parts that changed since the previous upload (None =
+    /// unknown). A texture that held the previous frame only gets those parts,
+    /// so mostly static content (slides, a code editor) uploads a fraction of
+    /// the frame.
+    fn upload(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        frame: (&[u32], u32, u32),
+        dirty: Option<&[CaptureRect]>,
+        in_use: impl Fn(&wgpu::Texture) -> bool,
+    ) -> (wgpu::Texture, wgpu::TextureView) {
+        let (pixels, width, height) = frame;
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        if self.textures.first().is_some_and(|t| t.texture.size() != size) {
+            self.textures.clear();
         }
 
-        // STEP 4: Map the staging texture (GPU -> CPU)
-        let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
-        unsafe {
-            d3d_context
-                .Map(
-                    &staging_texture,
-                    0,              // Subresource index
-                    D3D11_MAP_READ, // Read-only access
-                    0,              // No flags
-                    Some(&mut mapped),
-                )
-                .context("Failed to map staging texture")?;
+        // Every pooled texture now lags behind in the dirty parts too
+        for pooled in &mut self.textures {
+            pooled.stale = match (pooled.stale.take(), dirty) {
+                (Some(mut stale), Some(dirty))
+                    if stale.len() + dirty.len() <= renderer::MAX_DIRTY_RECTS =>
+                {
+                    stale.extend_from_slice(dirty);
+                    Some(stale)
+                }
+                _ => None,
+            };
         }
 
-        // STEP 5: Read pixel data from CPU memory
-        let row_pitch = mapped.RowPitch as usize;
-
-        // Compute crop window relative to captured surface (monitor origin aware)
-        let origin_x = (crop_region.x - monitor_origin.0).max(0) as usize;
-        let origin_y = (crop_region.y - monitor_origin.1).max(0) as usize;
-
-        let crop_width = crop_region
-            .width
-            .min(desc.Width.saturating_sub(origin_x as u32)) as usize;
-        let crop_height = crop_region
-            .height
-            .min(desc.Height.saturating_sub(origin_y as u32)) as usize;
-
-        // Log crop calculation for debugging (only first frame)
-        if self.frame_count == 0 {
-            info!(
-                "Crop calculation: monitor_origin=({},{}), crop_region=({},{} {}x{})",
-                monitor_origin.0,
-                monitor_origin.1,
-                crop_region.x,
-                crop_region.y,
-                crop_region.width,
-                crop_region.height
-            );
-            info!(
-                "Computed: origin=({},{}) size={}x{}, texture_size={}x{}",
-                origin_x, origin_y, crop_width, crop_height, desc.Width, desc.Height
-            );
-        }
+        let (texture, texture_view, stale) =
+            match self.textures.iter_mut().find(|t| !in_use(&t.texture)) {
+                Some(free) => (
+                    free.texture.clone(),
+                    free.view.clone(),
+                    free.stale.replace(Vec::new()),
+                ),
+                None => {
+                let (texture, view) = Self::create_texture(device, size);
+                if self.textures.len() < renderer::FRAME_TEXTURE_POOL {
+                    self.textures.push(PooledTexture {
+                        texture: texture.clone(),
+                        view: view.clone(),
+                        stale: Some(Vec::new()),
+                    });
+                }
+                (texture, view, None)
+            }
+        };
 
-        if crop_width == 0 || crop_height == 0 {
-            unsafe {
-                d3d_context.Unmap(&staging_texture, 0);
+        // Past a share of the frame one copy of everything is cheaper
+        let full = [CaptureRect {
+            x: 0,
+            y: 0,
+            width,
+            height,
+        }];
+        let rects = match &stale {
+            Some(rects)
+                if rects.iter().map(|r| r.width as u64 * r.height as u64).sum::<u64>() * 100
+                    < width as u64 * height as u64 * renderer::DIRTY_AREA_PERCENT =>
+            {
+                rects.as_slice()
             }
-            return Err(anyhow!(
-                "Computed zero-sized crop region; check overlay position"
-            ));
+            _ => &full,
+        };
+        for rect in rects {
+            self.upload_rect(encoder, &texture, (pixels, width, height), rect);
         }
+        self.belt.finish();
 
-        // Allocate buffer for pixel data
-        // Assuming BGRA8 format (4 bytes per pixel)
-        let mut pixel_data = vec![0u8; crop_width * crop_height * 4];
-
-        unsafe {
-            let src_ptr = mapped.pData as *const u8;
-
-            // Copy only the cropped region row by row (texture rows may have padding)
-            for y in 0..crop_height {
-                let src_offset = (origin_y + y) * row_pitch + origin_x * 4;
-                let dst_offset = y * crop_width * 4;
+        (texture, texture_view)
+    }
 
-                std::ptr::copy_nonoverlapping(
-                    src_ptr.add(src_offset),
-                    pixel_data.as_mut_ptr().add(dst_offset),
-                    crop_width * 4,
-                );
+    /// Record the copy of one rectangle of the frame into `texture`
+    fn upload_rect(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        texture: &wgpu::Texture,
+        frame: (&[u32], u32, u32),
+        rect: &CaptureRect,
+    ) {
+        let (pixels, width, height) = frame;
+        // Clipped to the frame
+        let x = rect.x.clamp(0, width as i32) as u32;
+        let y = rect.y.clamp(0, height as i32) as u32;
+        let rect_width = (rect.width.min(width - x)) as usize;
+        let rect_height = rect.height.min(height - y);
+
+        // Rows in a buffer → texture copy start at multiples of 256 bytes
+        let row = rect_width * 4;
+        let padded_row = wgpu::util::align_to(row as u32, wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+        let Some(length) = wgpu::BufferSize::new(padded_row as u64 * rect_height as u64) else {
+            return;
+        };
+        let slice = self.belt.allocate(
+            length,
+            wgpu::BufferSize::new(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as u64).unwrap(),
+        );
+        {
+            let mut mapped = slice.get_mapped_range_mut();
+            let source: &[u8] = bytemuck::cast_slice(pixels);
+            let stride = width as usize * 4;
+            let start = y as usize * stride + x as usize * 4;
+            for (target, line) in mapped
+                .chunks_exact_mut(padded_row as usize)
+                .zip(source[start..].chunks(stride))
+            {
+                target[..row].copy_from_slice(&line[..row]);
             }
-
-            // STEP 6: Unmap the staging texture
-            d3d_context.Unmap(&staging_texture, 0);
         }
+        encoder.copy_buffer_to_texture(
+            wgpu::TexelCopyBufferInfo {
+                buffer: slice.buffer(),
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: slice.offset(),
+                    bytes_per_row: Some(padded_row),
+                    rows_per_image: Some(rect_height),
+                },
+            },
+            wgpu::TexelCopyTextureInfo {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d { x, y, z: 0 },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::Extent3d {
+                width: rect_width as u32,
+                height: rect_height,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+
+    /// The encoder with the upload was submitted - its staging buffers are
+    /// reused once the GPU is done with them
+    fn submitted(&mut self) {
+        self.belt.recall();
+    }
 
-        // STEP 7: Create wgpu texture and upload data
+    fn create_texture(
+        device: &wgpu::Device,
+        size: wgpu::Extent3d,
+    ) -> (wgpu::Texture, wgpu::TextureView) {
         // Use Bgra8UnormSrgb to match the surface format and get correct colors
         // The captured data is already in sRGB color space from the desktop
-        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
             label: Some("Captured Frame Texture"),
-            size: wgpu::Extent3d {
-                width: crop_width as u32,
-                height: crop_height as u32,
-                depth_or_array_layers: 1,
-            },
+            size,
             mip_level_count: 1,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
@@ -588,31 +1263,8 @@ impl Renderer {
             usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
             view_formats: &[],
         });
-
-        // Upload pixel data to GPU
-        self.queue.write_texture(
-            wgpu::TexelCopyTextureInfo {
-                texture: &texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
-            &pixel_data,
-            wgpu::TexelCopyBufferLayout {
-                offset: 0,
-                bytes_per_row: Some(crop_width as u32 * 4),
-                rows_per_image: Some(crop_height as u32),
-            },
-            wgpu::Extent3d {
-                width: crop_width as u32,
-                height: crop_height as u32,
-                depth_or_array_layers: 1,
-            },
-        );
-
         let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-
-        Ok((texture, texture_view))
+        (texture, texture_view)
     }
 }
 
@@ -0,0 +1,131 @@
+// scene.rs - Scenes (Saved Region + Output Setups)
+//
+// A scene is a named snapshot of everything that defines what the audience
+// sees: the capture region (overlay position and size), the composition grid,
+// the spotlight, the chroma key, the sharpen / denoise flags, the filter
+// chain, how the sources are laid out (including the custom layout's
+// rectangles), the webcam, the image, the privacy regions and the capture
+// settings (target frame rate, cursor, border). Switching scenes moves the
+// overlay to the saved region and restores the rest, with a transition in the
+// output (see transition.rs).
+//
+// Scenes live in numbered slots (Scene 1 = IDE, Scene 2 = browser, ...):
+// - Ctrl+Alt+Shift+<n> (or tray → Scenes → Save) stores the current setup
+// - Ctrl+Alt+<n> (or tray → Scenes → <name>) switches to it
+//
+// The slots are saved in settings.json ("scenes", null for an empty slot), so
+// the scenes are still there after a restart and travel in configuration
+// bundles.
+
+use serde::{Deserialize, Serialize};
+
+use crate::chroma_key::ChromaKey;
+use crate::composite::{CompositeLayout, SourceRect};
+use crate::constants::scene;
+use crate::enhance::OutputEnhance;
+use crate::grid::GridStyle;
+use crate::privacy::RedactRegion;
+use crate::source::Placement;
+
+/// One saved setup
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scene {
+    pub name: String,
+    /// Overlay window outer position (screen coordinates)
+    pub position: (i32, i32),
+    /// Overlay window inner size
+    pub size: (u32, u32),
+    pub grid_style: GridStyle,
+    pub grid_in_output: bool,
+    pub spotlight: bool,
+    pub chroma_key: ChromaKey,
+    pub enhance: OutputEnhance,
+    /// Sharpen / denoise flags of the second output
+    #[serde(default)]
+    pub second_enhance: OutputEnhance,
+    /// Enabled frame filters in order (see filter.rs)
+    pub filters: Vec<String>,
+    /// Arrangement of additional capture regions (see composite.rs)
+    pub composite_layout: CompositeLayout,
+    /// Place of each source in the custom layout (see layout_editor.rs)
+    #[serde(default)]
+    pub custom_layout: Vec<SourceRect>,
+    /// Output frame rate limit (0 = every captured frame)
+    pub target_fps: u32,
+    pub show_cursor: bool,
+    pub show_border: bool,
+    pub border_width: u32,
+    /// Where the webcam is shown (see webcam.rs)
+    pub webcam: Placement,
+    /// Where the image / slideshow is shown (see image_source.rs)
+    pub image: Placement,
+    /// Blurred / pixelated rectangles of the region (see privacy.rs)
+    #[serde(default)]
+    pub privacy_regions: Vec<RedactRegion>,
+}
+
+/// The scene slots and which one is live
+pub struct SceneList {
+    slots: Vec<Option<Scene>>,
+    active: Option<usize>,
+}
+
+impl SceneList {
+    /// The scenes saved in settings.json (missing slots are empty, extra
+    /// ones are dropped)
+    pub fn from_slots(mut slots: Vec<Option<Scene>>) -> Self {
+        slots.resize(scene::SLOTS, None);
+        Self {
+            slots,
+            active: None,
+        }
+    }
+
+    /// Every slot, for settings.json
+    pub fn slots(&self) -> &[Option<Scene>] {
+        &self.slots
+    }
+
+    /// Replace the scenes (settings.json was edited or imported); the live
+    /// scene is forgotten if its slot changed
+    pub fn set_slots(&mut self, slots: Vec<Option<Scene>>) {
+        let previous = self.active.and_then(|slot| self.slots[slot].clone());
+        let active = self.active;
+        *self = Self::from_slots(slots);
+        self.active = active.filter(|&slot| self.slots[slot] == previous);
+    }
+
+    /// Number of scene slots
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn get(&self, slot: usize) -> Option<&Scene> {
+        self.slots.get(slot).and_then(|s| s.as_ref())
+    }
+
+    /// Store a scene in the slot (replacing what was there)
+    pub fn save(&mut self, slot: usize, scene: Scene) {
+        if let Some(s) = self.slots.get_mut(slot) {
+            *s = Some(scene);
+            self.active = Some(slot);
+        }
+    }
+
+    /// Remember which scene is live (after switching to it)
+    pub fn set_active(&mut self, slot: usize) {
+        self.active = Some(slot);
+    }
+
+    pub fn active(&self) -> Option<usize> {
+        self.active
+    }
+
+    /// Tray menu label for a slot, e.g. "Scene 2 (1280x720)" or "Scene 3 (empty)"
+    pub fn label(&self, slot: usize) -> String {
+        match self.get(slot) {
+            Some(s) => format!("{} ({}x{})", s.name, s.size.0, s.size.1),
+            None => format!("Scene {} (empty)", slot + 1),
+        }
+    }
+}
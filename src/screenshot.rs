@@ -0,0 +1,161 @@
+// screenshot.rs - Output Screenshots
+//
+// Saves the composed output (capture plus overlays and filters, before the
+// sharpen / denoise shader stages) as a PNG in
+// Pictures\RustFrame, named after the local time:
+//
+//   Pictures\RustFrame\RustFrame 2026-10-16 14-03-27.png
+//
+// While the chroma key is on, keyed areas are written as PNG alpha instead of
+// the checkerboard the destination window shows.
+//
+// The renderer hands out a copy of the next composed frame on request (see
+// Renderer::request_snapshot), so taking a screenshot never stalls the output.
+// The newest files are listed in tray → Recent Screenshots.
+//
+// A script can ask for another file instead (rustframe-ctl screenshot --out,
+// rustframe://screenshot?out=...).
+
+use anyhow::{anyhow, Context, Result};
+use log::info;
+use std::path::{Path, PathBuf};
+
+use crate::chroma_key::Keyer;
+use crate::constants::screenshot;
+
+/// Write an ARGB frame to a PNG file and return its path
+/// `keyer`: chroma key that produces the alpha channel (None = opaque)
+/// `out`: the file to write (None = a new file in Pictures\RustFrame)
+pub fn save(
+    pixels: &[u32],
+    width: u32,
+    height: u32,
+    keyer: Option<&Keyer>,
+    out: Option<&Path>,
+) -> Result<PathBuf> {
+    let path = match out {
+        Some(path) => path.to_path_buf(),
+        None => new_path()?,
+    };
+    let rgba: Vec<u8> = pixels
+        .iter()
+        .flat_map(|&p| {
+            let alpha = keyer.map_or(0xFF, |k| k.alpha(p));
+            [(p >> 16) as u8, (p >> 8) as u8, p as u8, alpha]
+        })
+        .collect();
+    image::save_buffer(&path, &rgba, width, height, image::ExtendedColorType::Rgba8)
+        .with_context(|| format!("Failed to write {:?}", path))?;
+    info!("Screenshot saved: {:?} ({}x{})", path, width, height);
+    Ok(path)
+}
+
+/// A file name in Pictures\RustFrame that is not taken yet
+fn new_path() -> Result<PathBuf> {
+    let dir = folder().ok_or_else(|| anyhow!("The Pictures folder was not found"))?;
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {:?}", dir))?;
+
+    // A second screenshot within the same second gets a number
+    let stem = format!("RustFrame {}", timestamp());
+    let mut path = dir.join(format!("{}.png", stem));
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(format!("{} ({}).png", stem, n));
+        n += 1;
+    }
+    Ok(path)
+}
+
+/// The newest screenshots, newest first (at most RECENT_COUNT)
+pub fn recent() -> Vec<PathBuf> {
+    let Some(entries) = folder().and_then(|dir| std::fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(std::time::SystemTime, PathBuf)> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("png"))
+        })
+        .filter_map(|path| {
+            let modified = path.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, path))
+        })
+        .collect();
+    files.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    files
+        .into_iter()
+        .take(screenshot::RECENT_COUNT)
+        .map(|(_, path)| path)
+        .collect()
+}
+
+/// Open a file or folder with its default application
+#[cfg(windows)]
+pub fn open(path: &Path) {
+    use windows::core::PCWSTR;
+    use windows::Win32::UI::Shell::ShellExecuteW;
+    use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+    let operation = crate::utils::wide_string("open");
+    let file = crate::utils::wide_string(&path.to_string_lossy());
+    // Values above 32 mean success
+    let result = unsafe {
+        ShellExecuteW(
+            None,
+            PCWSTR(operation.as_ptr()),
+            PCWSTR(file.as_ptr()),
+            None,
+            None,
+            SW_SHOWNORMAL,
+        )
+    };
+    if result.0 as usize <= 32 {
+        log::warn!("Failed to open {:?}", path);
+    }
+}
+
+#[cfg(not(windows))]
+pub fn open(path: &Path) {
+    log::warn!("Opening {:?} is only supported on Windows", path);
+}
+
+/// Pictures\RustFrame (None if the Pictures folder is unknown)
+#[cfg(windows)]
+pub fn folder() -> Option<PathBuf> {
+    use windows::Win32::System::Com::CoTaskMemFree;
+    use windows::Win32::UI::Shell::{FOLDERID_Pictures, SHGetKnownFolderPath, KF_FLAG_DEFAULT};
+
+    unsafe {
+        let path = SHGetKnownFolderPath(&FOLDERID_Pictures, KF_FLAG_DEFAULT, None).ok()?;
+        let pictures = path.to_string().ok();
+        CoTaskMemFree(Some(path.0 as *const _));
+        pictures.map(|p| PathBuf::from(p).join(screenshot::DIR_NAME))
+    }
+}
+
+#[cfg(not(windows))]
+pub fn folder() -> Option<PathBuf> {
+    None
+}
+
+/// Local time as "2026-10-16 14-03-27" (no colons - they are not allowed in file names)
+#[cfg(windows)]
+pub fn timestamp() -> String {
+    use windows::Win32::System::SystemInformation::GetLocalTime;
+
+    let t = unsafe { GetLocalTime() };
+    format!(
+        "{:04}-{:02}-{:02} {:02}-{:02}-{:02}",
+        t.wYear, t.wMonth, t.wDay, t.wHour, t.wMinute, t.wSecond
+    )
+}
+
+#[cfg(not(windows))]
+pub fn timestamp() -> String {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs().to_string())
+        .unwrap_or_default()
+}
@@ -0,0 +1,294 @@
+// scripting.rs - Rhai Scripts
+//
+// Every *.rhai file in %APPDATA%\RustFrame\scripts is loaded at startup.
+// Scripts can drive RustFrame and react to what it does:
+//
+//   fn on_recording_start() {
+//       switch_scene(2);
+//       action("toggle_timer");
+//   }
+//
+//   fn on_frame_stats(fps, frames) {
+//       if fps < 20 { print(`Only ${fps} fps`); }
+//   }
+//
+// Functions scripts can call:
+// - start_capture(), stop_capture(), toggle_capture()
+// - toggle_pause(), drop_marker(), screenshot(), upload_screenshot()
+// - switch_scene(slot), next_scene(), previous_scene()
+// - action(id)   any remote action id ("scene_3", ...) or shortcut id
+//                ("toggle_spotlight", "toggle_magnifier", ...) - the overlays
+// - state()      a map with capturing, countdown, paused, scene, scene_slot,
+//                elapsed_seconds, fps and frames
+// - print(text)  writes to the log
+//
+// Hooks (define the ones needed):
+// - on_start()                     after the scripts were loaded
+// - on_recording_start()           the capture is running
+// - on_recording_stop()            the capture stopped
+// - on_scene_change(slot, name)    a scene was switched to (slot starts at 1)
+// - on_frame_stats(fps, frames)    once a second while capturing
+//
+// Calls are queued and run by the app after the hook returns (like a
+// Stream Deck key press), so state() inside a hook still shows the state
+// before those calls. A hook that fails or runs too long is logged and
+// skipped - it does not stop the other scripts.
+
+use log::{info, warn};
+use rhai::{Dynamic, Engine, EvalAltResult, FuncArgs, Map, Scope, AST};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::constants::{scene, scripting};
+use crate::hotkeys::HotkeyAction;
+use crate::stream_deck::{RemoteAction, RemoteState};
+
+/// Something a script asked RustFrame to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptCommand {
+    Remote(RemoteAction),
+    Hotkey(HotkeyAction),
+}
+
+struct Script {
+    name: String,
+    ast: AST,
+    scope: Scope<'static>,
+}
+
+/// The loaded scripts
+pub struct ScriptHost {
+    engine: Engine,
+    scripts: Vec<Script>,
+    commands: Rc<RefCell<VecDeque<ScriptCommand>>>,
+    /// State returned by state()
+    state: Rc<RefCell<RemoteState>>,
+    /// State at the last update (None = on_start not called yet)
+    last: Option<RemoteState>,
+    last_frame_stats: Option<Instant>,
+}
+
+impl ScriptHost {
+    /// Load the scripts folder (None if it holds no scripts)
+    pub fn load() -> Option<Self> {
+        let dir = dir()?;
+        // Created so users find where scripts go
+        if let Err(e) = std::fs::create_dir_all(&dir) {
+            warn!("Failed to create {}: {}", dir.display(), e);
+            return None;
+        }
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(&dir)
+            .ok()?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("rhai"))
+            })
+            .collect();
+        if paths.is_empty() {
+            return None;
+        }
+        paths.sort();
+
+        let commands = Rc::new(RefCell::new(VecDeque::new()));
+        let state = Rc::new(RefCell::new(RemoteState::default()));
+        let engine = new_engine(&commands, &state);
+
+        let mut scripts = Vec::new();
+        for path in paths {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let ast = match engine.compile_file(path.clone()) {
+                Ok(ast) => ast,
+                Err(e) => {
+                    warn!("Script {}: {}", name, e);
+                    continue;
+                }
+            };
+            // Top-level statements run once, when the script is loaded
+            let mut scope = Scope::new();
+            if let Err(e) = engine.run_ast_with_scope(&mut scope, &ast) {
+                warn!("Script {}: {}", name, e);
+                continue;
+            }
+            info!("Script loaded: {}", name);
+            scripts.push(Script { name, ast, scope });
+        }
+        if scripts.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            engine,
+            scripts,
+            commands,
+            state,
+            last: None,
+            last_frame_stats: None,
+        })
+    }
+
+    /// Call the hooks for what changed since the last update
+    pub fn update(&mut self, state: &RemoteState) {
+        *self.state.borrow_mut() = state.clone();
+        let Some(last) = self.last.replace(state.clone()) else {
+            self.call("on_start", ());
+            return;
+        };
+
+        if state.capturing && !last.capturing {
+            self.last_frame_stats = Some(Instant::now());
+            self.call("on_recording_start", ());
+        } else if !state.capturing && last.capturing {
+            self.last_frame_stats = None;
+            self.call("on_recording_stop", ());
+        }
+
+        if state.scene_slot != last.scene_slot {
+            if let Some(slot) = state.scene_slot {
+                let name = state.scene.clone().unwrap_or_default();
+                self.call("on_scene_change", (slot as i64, name));
+            }
+        }
+
+        let interval = Duration::from_millis(scripting::FRAME_STATS_INTERVAL_MS);
+        if self
+            .last_frame_stats
+            .is_some_and(|t| t.elapsed() >= interval)
+        {
+            self.last_frame_stats = Some(Instant::now());
+            self.call("on_frame_stats", (state.fps as i64, state.frames as i64));
+        }
+    }
+
+    /// Next command queued by a script, if any
+    pub fn poll(&self) -> Option<ScriptCommand> {
+        self.commands.borrow_mut().pop_front()
+    }
+
+    /// Call a hook in every script that defines it
+    fn call(&mut self, hook: &str, args: impl FuncArgs + Clone) {
+        for script in &mut self.scripts {
+            let defined = script.ast.iter_functions().any(|f| f.name == hook);
+            if !defined {
+                continue;
+            }
+            if let Err(e) =
+                self.engine
+                    .call_fn::<Dynamic>(&mut script.scope, &script.ast, hook, args.clone())
+            {
+                warn!("Script {}: {} failed: {}", script.name, hook, e);
+            }
+        }
+    }
+}
+
+/// %APPDATA%\RustFrame\scripts (None if %APPDATA% is not set)
+pub fn dir() -> Option<PathBuf> {
+    crate::settings_file::dir().map(|dir| dir.join(scripting::DIR_NAME))
+}
+
+fn new_engine(
+    commands: &Rc<RefCell<VecDeque<ScriptCommand>>>,
+    state: &Rc<RefCell<RemoteState>>,
+) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(scripting::MAX_OPERATIONS);
+    engine.on_print(|text| info!("Script: {}", text));
+    engine.on_debug(|text, source, pos| {
+        info!("Script {} {}: {}", source.unwrap_or_default(), pos, text)
+    });
+
+    let simple = [
+        ("start_capture", RemoteAction::Start),
+        ("stop_capture", RemoteAction::Stop),
+        ("toggle_capture", RemoteAction::StartStop),
+        ("toggle_pause", RemoteAction::TogglePause),
+        ("next_scene", RemoteAction::NextScene),
+        ("previous_scene", RemoteAction::PreviousScene),
+        ("drop_marker", RemoteAction::DropMarker),
+        ("screenshot", RemoteAction::Screenshot),
+        ("upload_screenshot", RemoteAction::UploadScreenshot),
+    ];
+    for (name, action) in simple {
+        let commands = commands.clone();
+        engine.register_fn(name, move || {
+            commands
+                .borrow_mut()
+                .push_back(ScriptCommand::Remote(action))
+        });
+    }
+
+    {
+        let commands = commands.clone();
+        engine.register_fn(
+            "switch_scene",
+            move |slot: i64| -> Result<(), Box<EvalAltResult>> {
+                if slot < 1 || slot > scene::SLOTS as i64 {
+                    return Err(format!("No scene {} (1 to {})", slot, scene::SLOTS).into());
+                }
+                let action = RemoteAction::SwitchScene(slot as usize - 1);
+                commands
+                    .borrow_mut()
+                    .push_back(ScriptCommand::Remote(action));
+                Ok(())
+            },
+        );
+    }
+
+    {
+        let commands = commands.clone();
+        engine.register_fn(
+            "action",
+            move |id: &str| -> Result<(), Box<EvalAltResult>> {
+                let command = RemoteAction::from_id(id)
+                    .map(ScriptCommand::Remote)
+                    .or_else(|| {
+                        // Holding a key has no meaning for a script
+                        HotkeyAction::from_id(id)
+                            .filter(|a| *a != HotkeyAction::HoldToShowCursor)
+                            .map(ScriptCommand::Hotkey)
+                    })
+                    .ok_or_else(|| format!("Unknown action '{}'", id))?;
+                commands.borrow_mut().push_back(command);
+                Ok(())
+            },
+        );
+    }
+
+    {
+        let state = state.clone();
+        engine.register_fn("state", move || state_map(&state.borrow()));
+    }
+
+    engine
+}
+
+fn state_map(state: &RemoteState) -> Map {
+    let mut map = Map::new();
+    map.insert("capturing".into(), state.capturing.into());
+    map.insert("countdown".into(), state.countdown.into());
+    map.insert("paused".into(), state.paused.into());
+    map.insert(
+        "scene".into(),
+        state.scene.clone().map_or(Dynamic::UNIT, Dynamic::from),
+    );
+    map.insert(
+        "scene_slot".into(),
+        state
+            .scene_slot
+            .map_or(Dynamic::UNIT, |slot| Dynamic::from(slot as i64)),
+    );
+    map.insert(
+        "elapsed_seconds".into(),
+        Dynamic::from(state.elapsed_seconds as i64),
+    );
+    map.insert("fps".into(), Dynamic::from(state.fps as i64));
+    map.insert("frames".into(), Dynamic::from(state.frames as i64));
+    map
+}
@@ -0,0 +1,143 @@
+// session_end.rs - Shutting Down with Windows
+//
+// When the user logs off or Windows shuts down or restarts, every program
+// gets WM_QUERYENDSESSION and then WM_ENDSESSION, and may be terminated as
+// soon as it returns from WM_ENDSESSION. winit does not pass these messages
+// on, so RustFrame would be killed in the middle of a capture - without
+// stopping it, saving the input recording or removing the tray icon.
+//
+// A hidden window on its own thread receives the messages instead. Once the
+// session really ends (WM_ENDSESSION - a shutdown can still be cancelled
+// after WM_QUERYENDSESSION), it asks the event loop to exit, the same
+// orderly shutdown as tray → Exit (see RustFrameApp::exiting), and holds
+// WM_ENDSESSION until the shutdown is done, at most SHUTDOWN_WAIT_MS.
+
+use log::{info, warn};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use winit::event_loop::EventLoopProxy;
+
+/// Windows is ending the session
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+/// The app finished its shutdown
+static DONE: AtomicBool = AtomicBool::new(false);
+/// Wakes the event loop to start the shutdown
+static WAKER: OnceLock<EventLoopProxy<()>> = OnceLock::new();
+
+/// Start the hidden window; `waker` wakes the event loop when the session ends
+pub fn start(waker: EventLoopProxy<()>) {
+    let _ = WAKER.set(waker);
+    std::thread::spawn(run_window);
+}
+
+/// Whether Windows is ending the session (checked in about_to_wait)
+pub fn requested() -> bool {
+    REQUESTED.load(Ordering::SeqCst)
+}
+
+/// The shutdown is complete - Windows may end the process
+pub fn done() {
+    DONE.store(true, Ordering::SeqCst);
+}
+
+/// Ask the event loop to shut down
+fn request() {
+    if !REQUESTED.swap(true, Ordering::SeqCst) {
+        info!("Windows is ending the session, shutting down");
+    }
+    if let Some(waker) = WAKER.get() {
+        let _ = waker.send_event(());
+    }
+}
+
+/// Create the hidden window and handle its messages until the process ends
+#[cfg(windows)]
+fn run_window() {
+    use windows::core::PCWSTR;
+    use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CreateWindowExW, DispatchMessageW, GetMessageW, RegisterClassExW, TranslateMessage, MSG,
+        WINDOW_EX_STYLE, WNDCLASSEXW, WS_POPUP,
+    };
+
+    unsafe {
+        let instance = match GetModuleHandleW(None) {
+            Ok(module) => module.into(),
+            Err(e) => {
+                warn!("Shutting down with Windows unavailable: {}", e);
+                return;
+            }
+        };
+        let class_name = crate::utils::wide_string("RustFrameSessionEnd");
+        let class = WNDCLASSEXW {
+            cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
+            lpfnWndProc: Some(session_window_proc),
+            hInstance: instance,
+            lpszClassName: PCWSTR(class_name.as_ptr()),
+            ..Default::default()
+        };
+        RegisterClassExW(&class);
+
+        // A top-level window (never shown) - message-only windows do not get
+        // the session messages
+        let title = crate::utils::wide_string("RustFrame");
+        if let Err(e) = CreateWindowExW(
+            WINDOW_EX_STYLE(0),
+            PCWSTR(class_name.as_ptr()),
+            PCWSTR(title.as_ptr()),
+            WS_POPUP,
+            0,
+            0,
+            0,
+            0,
+            None,
+            None,
+            Some(instance),
+            None,
+        ) {
+            warn!("Shutting down with Windows unavailable: {}", e);
+            return;
+        }
+
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+            let _ = TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn run_window() {}
+
+#[cfg(windows)]
+unsafe extern "system" fn session_window_proc(
+    hwnd: windows::Win32::Foundation::HWND,
+    msg: u32,
+    wparam: windows::Win32::Foundation::WPARAM,
+    lparam: windows::Win32::Foundation::LPARAM,
+) -> windows::Win32::Foundation::LRESULT {
+    use windows::Win32::Foundation::LRESULT;
+    use windows::Win32::UI::WindowsAndMessaging::{DefWindowProcW, WM_ENDSESSION};
+
+    use crate::constants::session_end;
+
+    match msg {
+        // wparam is 0 when the shutdown was cancelled
+        WM_ENDSESSION if wparam.0 != 0 => {
+            request();
+            let started = std::time::Instant::now();
+            while !DONE.load(Ordering::SeqCst)
+                && started.elapsed().as_millis() < session_end::SHUTDOWN_WAIT_MS as u128
+            {
+                std::thread::sleep(std::time::Duration::from_millis(10));
+            }
+            if !DONE.load(Ordering::SeqCst) {
+                warn!("Windows ends the session before the shutdown finished");
+            }
+            LRESULT(0)
+        }
+        // WM_QUERYENDSESSION included: RustFrame never stands in the way
+        _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
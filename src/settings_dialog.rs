@@ -3,26 +3,40 @@
 // A Win32 dialog for adjusting capture settings.
 // Uses modern Windows controls with proper DPI scaling and Segoe UI font.
 
+use crate::accessibility;
+use crate::app_settings::{AppSettings, EscAction, ToolbarPlacement};
 use crate::capture::CaptureSettings;
-use crate::constants::{capture as capture_const, dialog};
+use crate::constants::{capture as capture_const, dialog, midi as midi_const};
+use crate::hotkeys::{self, HotkeyAction};
+use crate::midi::{self, MidiAction, MidiInput, MidiTrigger};
+use crate::plugin::{self, PluginInfo};
+use crate::stream_deck;
+use crate::theme::{self, ThemeMode};
+use crate::thread_priority::{self, ThreadPriority};
 use crate::utils::wide_string;
-use log::info;
+use global_hotkey::hotkey::{HotKey, Modifiers};
+use log::{info, warn};
 use std::cell::RefCell;
 
 #[cfg(windows)]
 use windows::Win32::{
-    Foundation::{HINSTANCE, HWND, LPARAM, LRESULT, WPARAM},
+    Foundation::{COLORREF, HINSTANCE, HWND, LPARAM, LRESULT, TRUE, WPARAM},
     Graphics::Dwm::{
         DwmSetWindowAttribute, DWMSBT_MAINWINDOW, DWMWA_SYSTEMBACKDROP_TYPE,
         DWMWA_USE_IMMERSIVE_DARK_MODE, DWM_SYSTEMBACKDROP_TYPE,
     },
     Graphics::Gdi::{
-        CreateFontW, DeleteObject, GetDC, GetSysColorBrush, ReleaseDC, CLEARTYPE_QUALITY,
-        CLIP_DEFAULT_PRECIS, COLOR_3DFACE, DEFAULT_CHARSET, FF_SWISS, FW_NORMAL, HFONT, HGDIOBJ,
-        OUT_TT_PRECIS,
+        CreateFontW, CreateSolidBrush, DeleteObject, GetDC, GetSysColorBrush, ReleaseDC,
+        SetBkColor, SetTextColor, CLEARTYPE_QUALITY, CLIP_DEFAULT_PRECIS, COLOR_3DFACE,
+        DEFAULT_CHARSET, FF_SWISS, FW_NORMAL, HBRUSH, HDC, HFONT, HGDIOBJ, OUT_TT_PRECIS,
     },
     System::LibraryLoader::GetModuleHandleW,
+    UI::Accessibility::{CLSID_AccPropServices, IAccPropServices, PROPID_ACC_NAME},
     UI::Controls::*,
+    UI::Input::KeyboardAndMouse::{
+        GetFocus, GetKeyState, RegisterHotKey, UnregisterHotKey, HOT_KEY_MODIFIERS, MOD_ALT,
+        MOD_CONTROL, MOD_NOREPEAT, MOD_SHIFT, MOD_WIN, VK_CONTROL, VK_SHIFT, VK_TAB,
+    },
     UI::WindowsAndMessaging::*,
 };
 
@@ -35,32 +49,160 @@ const ID_CHECK_PROD_MODE: i32 = 103;
 const ID_EDIT_BORDER_WIDTH: i32 = 105;
 const ID_BTN_SAVE: i32 = 106;
 const ID_BTN_CANCEL: i32 = 107;
+const ID_EDIT_COUNTDOWN: i32 = 108;
+const ID_CHECK_COUNTDOWN_FULLSCREEN: i32 = 109;
+const ID_EDIT_REDACTED_APPS: i32 = 110;
+const ID_CHECK_HIDE_NOTIFICATIONS: i32 = 111;
+const ID_EDIT_FILTERS: i32 = 112;
+const ID_EDIT_HOLD: i32 = 113;
+const ID_EDIT_TRANSITION: i32 = 114;
+const ID_EDIT_TARGET_FPS: i32 = 115;
+const ID_EDIT_IMAGE_PATH: i32 = 116;
+const ID_EDIT_SLIDE_SECONDS: i32 = 117;
+const ID_TAB: i32 = 118;
+const ID_LIST_SHORTCUTS: i32 = 119;
+const ID_HOTKEY_SHORTCUT: i32 = 120;
+const ID_BTN_SHORTCUT_DEFAULT: i32 = 121;
+const ID_BTN_SHORTCUT_CLEAR: i32 = 122;
+const ID_CHECK_GAMEPAD: i32 = 123;
+const ID_CHECK_MIDI: i32 = 124;
+const ID_COMBO_MIDI_DEVICE: i32 = 125;
+const ID_LIST_MIDI: i32 = 126;
+const ID_BTN_MIDI_LEARN: i32 = 127;
+const ID_BTN_MIDI_CLEAR: i32 = 128;
+const ID_CHECK_STREAM_DECK: i32 = 129;
+const ID_CHECK_MINIMIZE_TO_TRAY: i32 = 130;
+const ID_CHECK_CLOSE_TO_TRAY: i32 = 131;
+const ID_CHECK_AUTO_START: i32 = 132;
+const ID_CHECK_START_MINIMIZED: i32 = 133;
+const ID_CHECK_SHELL_MENU: i32 = 134;
+const ID_EDIT_REMOTE_TOKEN: i32 = 135;
+const ID_CHECK_OSC: i32 = 136;
+const ID_LIST_PLUGINS: i32 = 137;
+const ID_BTN_PLUGINS_FOLDER: i32 = 138;
+const ID_BTN_UNDO: i32 = 139;
+const ID_BTN_REDO: i32 = 140;
+const ID_BTN_RESET_PAGE: i32 = 141;
+const ID_COMBO_THREAD_PRIORITY: i32 = 142;
+const ID_EDIT_CPU_CORES: i32 = 143;
+const ID_COMBO_ESC_ACTION: i32 = 144;
+const ID_COMBO_THEME: i32 = 145;
+const ID_EDIT_ACCENT: i32 = 146;
+const ID_COMBO_TOOLBAR: i32 = 147;
+const ID_CHECK_GAME_CAPTURE: i32 = 148;
+/// Temporary hotkey id for checking whether a shortcut is free
+const ID_SHORTCUT_PROBE: i32 = 0xBFFF;
+
+/// Tab pages (index in the tab control)
+const PAGE_GENERAL: usize = 0;
+const PAGE_SHORTCUTS: usize = 1;
+const PAGE_MIDI: usize = 2;
+const PAGE_PLUGINS: usize = 3;
+const PAGE_ADVANCED: usize = 4;
+
+/// Timer that polls the MIDI device in learn mode
+const ID_MIDI_LEARN_TIMER: usize = 1;
+/// Timer that passes the edited values to the live preview
+const ID_PREVIEW_TIMER: usize = 2;
 
 // Static text style for center alignment
 const SS_CENTER: u32 = 0x01;
 
 // Thread-local state for dialog
 thread_local! {
-    static DIALOG_SETTINGS: RefCell<Option<CaptureSettings>> = const { RefCell::new(None) };
+    static DIALOG_SETTINGS: RefCell<Option<AppSettings>> = const { RefCell::new(None) };
     static SETTINGS_CHANGED: RefCell<bool> = const { RefCell::new(false) };
     static DIALOG_HWND: RefCell<Option<HWND>> = const { RefCell::new(None) };
     static DIALOG_FONT: RefCell<Option<HFONT>> = const { RefCell::new(None) };
+    /// Background of the dark theme (None in the light theme)
+    static DIALOG_BRUSH: RefCell<Option<HBRUSH>> = const { RefCell::new(None) };
+    /// Controls given a screen reader name (cleared when the dialog closes)
+    static DLG_NAMED: RefCell<Option<(IAccPropServices, Vec<HWND>)>> = const { RefCell::new(None) };
     static DIALOG_DEV_MODE: RefCell<bool> = const { RefCell::new(false) };
 
     static DLG_CHECK_CURSOR: RefCell<Option<HWND>> = const { RefCell::new(None) };
     static DLG_CHECK_BORDER: RefCell<Option<HWND>> = const { RefCell::new(None) };
     static DLG_CHECK_PROD: RefCell<Option<HWND>> = const { RefCell::new(None) };
     static DLG_EDIT_BORDER_WIDTH: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_EDIT_COUNTDOWN: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_CHECK_COUNTDOWN_FULLSCREEN: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_EDIT_REDACTED_APPS: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_CHECK_HIDE_NOTIFICATIONS: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_CHECK_GAMEPAD: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_CHECK_STREAM_DECK: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_EDIT_REMOTE_TOKEN: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_CHECK_OSC: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_CHECK_MINIMIZE_TO_TRAY: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_CHECK_CLOSE_TO_TRAY: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_CHECK_AUTO_START: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_CHECK_START_MINIMIZED: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_CHECK_SHELL_MENU: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_EDIT_FILTERS: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_EDIT_HOLD: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_EDIT_TRANSITION: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_EDIT_TARGET_FPS: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_EDIT_IMAGE_PATH: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_EDIT_SLIDE_SECONDS: RefCell<Option<HWND>> = const { RefCell::new(None) };
+
+    static DLG_TAB: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_PAGES: RefCell<Vec<HWND>> = const { RefCell::new(Vec::new()) };
+    static DLG_LIST_SHORTCUTS: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_HOTKEY_SHORTCUT: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_SHORTCUT_STATUS: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    /// Shortcut of every action as edited in the dialog (None = unbound)
+    static DLG_SHORTCUTS: RefCell<Vec<(HotkeyAction, Option<HotKey>)>> = const { RefCell::new(Vec::new()) };
+    /// Shortcuts registered by RustFrame itself when the dialog was opened
+    /// (not reported as taken by another application)
+    static DLG_REGISTERED_SHORTCUTS: RefCell<Vec<HotKey>> = const { RefCell::new(Vec::new()) };
+
+    static DLG_CHECK_MIDI: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_COMBO_MIDI_DEVICE: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_COMBO_THREAD_PRIORITY: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_EDIT_CPU_CORES: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_COMBO_ESC_ACTION: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_COMBO_THEME: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_EDIT_ACCENT: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_COMBO_TOOLBAR: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_CHECK_GAME_CAPTURE: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_LIST_MIDI: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_BTN_MIDI_LEARN: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_MIDI_STATUS: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    /// Trigger of every MIDI action as edited in the dialog (None = not mapped)
+    static DLG_MIDI_BINDINGS: RefCell<Vec<(MidiAction, Option<MidiTrigger>)>> = const { RefCell::new(Vec::new()) };
+    /// Device opened for learn mode and the list row waiting for a trigger
+    static DLG_MIDI_LEARN: RefCell<Option<(MidiInput, usize)>> = const { RefCell::new(None) };
+
+    /// Plugins in the plugins folder when the dialog was opened
+    static DLG_PLUGINS: RefCell<Vec<PluginInfo>> = const { RefCell::new(Vec::new()) };
+    static DLG_LIST_PLUGINS: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_PLUGIN_PANEL: RefCell<Option<HWND>> = const { RefCell::new(None) };
+
+    /// Undo history: earlier states of the controls (newest last), undone
+    /// states, and the state after the last recorded edit
+    static DLG_UNDO: RefCell<Vec<AppSettings>> = const { RefCell::new(Vec::new()) };
+    static DLG_REDO: RefCell<Vec<AppSettings>> = const { RefCell::new(Vec::new()) };
+    static DLG_RECORDED: RefCell<Option<AppSettings>> = const { RefCell::new(None) };
+    /// Set while undo / redo / reset fill in the controls
+    static DLG_HISTORY_PAUSED: RefCell<bool> = const { RefCell::new(false) };
+    static DLG_BTN_UNDO: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    static DLG_BTN_REDO: RefCell<Option<HWND>> = const { RefCell::new(None) };
+    /// A control changed since the live preview last read them
+    static DLG_PREVIEW_DIRTY: RefCell<bool> = const { RefCell::new(false) };
 }
 
 /// Show the settings dialog
-/// Returns Some(CaptureSettings) if user clicked Save, None if cancelled
+/// Returns Some(AppSettings) if user clicked Save, None if cancelled
 /// dev_mode: if true, shows production mode option
+/// plugins: listed on the Plugins tab (see plugin.rs)
+/// preview: called every PREVIEW_TICK_MS with the values as edited so far,
+/// so a running capture can show them before Save
 #[cfg(windows)]
 pub fn show_settings_dialog(
-    current_settings: &CaptureSettings,
+    current_settings: &AppSettings,
     dev_mode: bool,
-) -> Option<CaptureSettings> {
+    plugins: &[PluginInfo],
+    preview: &mut dyn FnMut(&AppSettings),
+) -> Option<AppSettings> {
     use windows::core::PCWSTR;
 
     unsafe {
@@ -77,6 +219,15 @@ pub fn show_settings_dialog(
         // Store settings in thread-local state
         DIALOG_SETTINGS.with(|s| *s.borrow_mut() = Some(current_settings.clone()));
         SETTINGS_CHANGED.with(|c| *c.borrow_mut() = false);
+        // No undo history until all controls exist (see start_history)
+        DLG_RECORDED.with(|r| *r.borrow_mut() = None);
+        let shortcuts = hotkeys::resolve(&current_settings.hotkeys);
+        DLG_REGISTERED_SHORTCUTS
+            .with(|r| *r.borrow_mut() = shortcuts.iter().filter_map(|(_, h)| *h).collect());
+        DLG_SHORTCUTS.with(|s| *s.borrow_mut() = shortcuts);
+        DLG_MIDI_BINDINGS
+            .with(|b| *b.borrow_mut() = midi::resolve(&current_settings.midi_mappings));
+        DLG_PLUGINS.with(|p| *p.borrow_mut() = plugins.to_vec());
 
         // Create modern font (Segoe UI, 10pt)
         let font_name = wide_string("Segoe UI");
@@ -104,6 +255,7 @@ pub fn show_settings_dialog(
             PCWSTR(font_name.as_ptr()),
         );
         DIALOG_FONT.with(|f| *f.borrow_mut() = Some(hfont));
+        let background = create_theme_brush().unwrap_or_else(|| GetSysColorBrush(COLOR_3DFACE));
 
         let module = GetModuleHandleW(None).unwrap();
         let hinstance: HINSTANCE = module.into();
@@ -119,7 +271,7 @@ pub fn show_settings_dialog(
             hInstance: hinstance,
             hIcon: HICON::default(),
             hCursor: LoadCursorW(None, IDC_ARROW).unwrap_or_default(),
-            hbrBackground: GetSysColorBrush(COLOR_3DFACE),
+            hbrBackground: background,
             lpszMenuName: PCWSTR::null(),
             lpszClassName: PCWSTR(class_name.as_ptr()),
             hIconSm: HICON::default(),
@@ -127,6 +279,19 @@ pub fn show_settings_dialog(
 
         RegisterClassExW(&wc);
 
+        // Tab pages forward control notifications to the dialog
+        let page_class_name = wide_string(&format!("RustFrameSettingsPage_{}", std::process::id()));
+        let page_wc = WNDCLASSEXW {
+            cbSize: size_of::<WNDCLASSEXW>() as u32,
+            lpfnWndProc: Some(settings_page_proc),
+            hInstance: hinstance,
+            hCursor: LoadCursorW(None, IDC_ARROW).unwrap_or_default(),
+            hbrBackground: background,
+            lpszClassName: PCWSTR(page_class_name.as_ptr()),
+            ..Default::default()
+        };
+        RegisterClassExW(&page_wc);
+
         // Get screen dimensions for centering
         let screen_width = GetSystemMetrics(SM_CXSCREEN);
         let screen_height = GetSystemMetrics(SM_CYSCREEN);
@@ -166,8 +331,8 @@ pub fn show_settings_dialog(
             size_of::<DWM_SYSTEMBACKDROP_TYPE>() as u32,
         );
 
-        // Enable dark mode
-        let use_dark: i32 = 1;
+        // Dark title bar in the dark theme
+        let use_dark = theme::palette().dark as i32;
         let _ = DwmSetWindowAttribute(
             hwnd,
             DWMWA_USE_IMMERSIVE_DARK_MODE,
@@ -178,8 +343,22 @@ pub fn show_settings_dialog(
         // Store hwnd for reference
         DIALOG_HWND.with(|h| *h.borrow_mut() = Some(hwnd));
 
-        // Create controls
-        create_controls(hwnd, current_settings, hfont, dev_mode);
+        // Create the tabs and their pages, then the controls on them
+        let (general_page, shortcuts_page, midi_page, plugins_page, advanced_page) =
+            create_tabs(hwnd, PCWSTR(page_class_name.as_ptr()), hfont);
+        create_controls(hwnd, general_page, current_settings, hfont, dev_mode);
+        create_shortcut_controls(shortcuts_page, hfont);
+        create_midi_controls(midi_page, current_settings, hfont);
+        create_plugin_controls(plugins_page, hfont);
+        create_advanced_controls(advanced_page, current_settings, hfont);
+        theme_controls(hwnd);
+        name_controls();
+        start_history();
+
+        // Live preview: re-read the controls after a change, pass them on every tick
+        let mut preview_state = Some(current_settings.clone());
+        DLG_PREVIEW_DIRTY.with(|d| *d.borrow_mut() = false);
+        SetTimer(Some(hwnd), ID_PREVIEW_TIMER, dialog::PREVIEW_TICK_MS, None);
 
         // Message loop - run until window is closed
         let mut msg = MSG::default();
@@ -194,6 +373,28 @@ pub fn show_settings_dialog(
                 break;
             }
 
+            if msg.message == WM_TIMER && msg.hwnd == hwnd && msg.wParam.0 == ID_PREVIEW_TIMER {
+                if DLG_PREVIEW_DIRTY.with(|d| d.replace(false)) {
+                    preview_state = controls_state();
+                }
+                if let Some(state) = &preview_state {
+                    preview(state);
+                }
+                continue;
+            }
+
+            // Ctrl+Tab / Ctrl+Shift+Tab switch pages, as in other Windows dialogs
+            if msg.message == WM_KEYDOWN
+                && msg.wParam.0 == VK_TAB.0 as usize
+                && GetKeyState(VK_CONTROL.0 as i32) < 0
+            {
+                let count = DLG_PAGES.with(|p| p.borrow().len()).max(1);
+                let back = GetKeyState(VK_SHIFT.0 as i32) < 0;
+                let step = if back { count - 1 } else { 1 };
+                show_page((current_page() + step) % count);
+                continue;
+            }
+
             if !IsDialogMessageW(hwnd, &msg).as_bool() {
                 let _ = TranslateMessage(&msg);
                 DispatchMessageW(&msg);
@@ -204,7 +405,12 @@ pub fn show_settings_dialog(
         if let Some(font) = DIALOG_FONT.with(|f| *f.borrow()) {
             let _ = DeleteObject(HGDIOBJ(font.0));
         }
+        if let Some(brush) = DIALOG_BRUSH.with(|b| b.borrow_mut().take()) {
+            let _ = DeleteObject(HGDIOBJ(brush.0));
+        }
         let _ = UnregisterClassW(PCWSTR(class_name.as_ptr()), Some(hinstance));
+        let _ = UnregisterClassW(PCWSTR(page_class_name.as_ptr()), Some(hinstance));
+        DLG_PAGES.with(|p| p.borrow_mut().clear());
 
         // Return settings if changed
         let changed = SETTINGS_CHANGED.with(|c| *c.borrow());
@@ -219,8 +425,162 @@ pub fn show_settings_dialog(
 #[cfg(windows)]
 use windows::Win32::Graphics::Gdi::GetDeviceCaps;
 
+/// ARGB -> COLORREF (0x00BBGGRR)
+#[cfg(windows)]
+fn colorref(argb: u32) -> COLORREF {
+    COLORREF(((argb & 0xFF) << 16) | (argb & 0xFF00) | ((argb >> 16) & 0xFF))
+}
+
+/// Background brush of the dark theme; the light theme and Windows contrast
+/// themes keep the system colors
+#[cfg(windows)]
+unsafe fn create_theme_brush() -> Option<HBRUSH> {
+    let palette = theme::palette();
+    if !palette.dark || accessibility::high_contrast() {
+        return None;
+    }
+    let brush = CreateSolidBrush(colorref(palette.window_bg));
+    DIALOG_BRUSH.with(|b| *b.borrow_mut() = Some(brush));
+    Some(brush)
+}
+
+/// Text and background colors of labels, checkboxes and text fields in the
+/// dark theme (WM_CTLCOLOR*); None lets Windows use its own
+#[cfg(windows)]
+unsafe fn control_colors(msg: u32, wparam: WPARAM) -> Option<LRESULT> {
+    if !matches!(
+        msg,
+        WM_CTLCOLORSTATIC | WM_CTLCOLORBTN | WM_CTLCOLOREDIT | WM_CTLCOLORLISTBOX
+    ) {
+        return None;
+    }
+    let brush = DIALOG_BRUSH.with(|b| *b.borrow())?;
+    let palette = theme::palette();
+    let hdc = HDC(wparam.0 as *mut c_void);
+    SetTextColor(hdc, colorref(palette.window_text));
+    SetBkColor(hdc, colorref(palette.window_bg));
+    Some(LRESULT(brush.0 as isize))
+}
+
+/// Dark buttons, lists, text fields and scroll bars in the dark theme.
+/// Checkboxes, radio buttons and group boxes drop their visual style
+/// instead - themed, they ignore the text color of WM_CTLCOLORSTATIC.
+#[cfg(windows)]
+unsafe fn theme_controls(dialog: HWND) {
+    use windows::core::{w, BOOL, PCWSTR};
+
+    if DIALOG_BRUSH.with(|b| b.borrow().is_none()) {
+        return;
+    }
+
+    unsafe extern "system" fn each(hwnd: HWND, _: LPARAM) -> BOOL {
+        let class = class_name(hwnd);
+        let button_type = GetWindowLongW(hwnd, GWL_STYLE) & BS_TYPEMASK;
+        let (app_name, id_list) = match class.as_str() {
+            "Button" if button_type == BS_PUSHBUTTON || button_type == BS_DEFPUSHBUTTON => {
+                (w!("DarkMode_Explorer"), PCWSTR::null())
+            }
+            "Button" => (w!(""), w!("")),
+            "Edit" | "ComboBox" => (w!("DarkMode_CFD"), PCWSTR::null()),
+            "SysListView32" => {
+                let palette = theme::palette();
+                let bg = LPARAM(colorref(palette.window_bg).0 as isize);
+                let text = LPARAM(colorref(palette.window_text).0 as isize);
+                let _ = SendMessageW(hwnd, LVM_SETBKCOLOR, None, Some(bg));
+                let _ = SendMessageW(hwnd, LVM_SETTEXTBKCOLOR, None, Some(bg));
+                let _ = SendMessageW(hwnd, LVM_SETTEXTCOLOR, None, Some(text));
+                (w!("DarkMode_Explorer"), PCWSTR::null())
+            }
+            _ => return TRUE,
+        };
+        let _ = SetWindowTheme(hwnd, app_name, id_list);
+        TRUE
+    }
+
+    let _ = EnumChildWindows(Some(dialog), Some(each), LPARAM(0));
+}
+
+/// Window class of a control ("Edit", "Button", ...)
+#[cfg(windows)]
+unsafe fn class_name(hwnd: HWND) -> String {
+    let mut buffer = [0u16; 64];
+    let len = GetClassNameW(hwnd, &mut buffer);
+    String::from_utf16_lossy(&buffer[..len.max(0) as usize])
+}
+
+/// Screen reader names for the text fields, lists and shortcut fields: the
+/// label in front of them, without the colon. Checkboxes and buttons are
+/// named by their own text.
+#[cfg(windows)]
+unsafe fn name_controls() {
+    use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_INPROC_SERVER};
+
+    let services: IAccPropServices =
+        match CoCreateInstance(&CLSID_AccPropServices, None, CLSCTX_INPROC_SERVER) {
+            Ok(services) => services,
+            Err(e) => {
+                warn!("Screen reader names unavailable: {}", e);
+                return;
+            }
+        };
+    let mut named = Vec::new();
+    for page in DLG_PAGES.with(|p| p.borrow().clone()) {
+        let mut label = String::new();
+        let mut child = GetWindow(page, GW_CHILD).ok();
+        while let Some(hwnd) = child {
+            match class_name(hwnd).as_str() {
+                "Static" => {
+                    let mut buffer = [0u16; 256];
+                    let len = GetWindowTextW(hwnd, &mut buffer);
+                    let text = String::from_utf16_lossy(&buffer[..len.max(0) as usize]);
+                    label = text.trim().trim_end_matches(':').to_string();
+                }
+                "Edit" | "ComboBox" | "SysListView32" | "msctls_hotkey32" if !label.is_empty() => {
+                    let name = wide_string(&label);
+                    if services
+                        .SetHwndPropStr(
+                            hwnd,
+                            OBJID_CLIENT.0 as u32,
+                            CHILDID_SELF,
+                            PROPID_ACC_NAME,
+                            windows::core::PCWSTR(name.as_ptr()),
+                        )
+                        .is_ok()
+                    {
+                        named.push(hwnd);
+                    }
+                }
+                _ => {}
+            }
+            child = GetWindow(hwnd, GW_HWNDNEXT).ok();
+        }
+    }
+    DLG_NAMED.with(|n| *n.borrow_mut() = Some((services, named)));
+}
+
+/// Remove the names again (while the controls still exist)
+#[cfg(windows)]
+unsafe fn clear_control_names() {
+    if let Some((services, named)) = DLG_NAMED.with(|n| n.borrow_mut().take()) {
+        for hwnd in named {
+            let _ = services.ClearHwndProps(
+                hwnd,
+                OBJID_CLIENT.0 as u32,
+                CHILDID_SELF,
+                &[PROPID_ACC_NAME],
+            );
+        }
+    }
+}
+
 #[cfg(windows)]
-unsafe fn create_controls(hwnd: HWND, settings: &CaptureSettings, hfont: HFONT, dev_mode: bool) {
+unsafe fn create_controls(
+    hwnd: HWND,
+    page: HWND,
+    settings: &AppSettings,
+    hfont: HFONT,
+    dev_mode: bool,
+) {
     use windows::core::PCWSTR;
 
     let module = GetModuleHandleW(None).unwrap();
@@ -246,7 +606,7 @@ unsafe fn create_controls(hwnd: HWND, settings: &CaptureSettings, hfont: HFONT,
         y_pos - 10,
         control_width,
         28,
-        Some(hwnd),
+        Some(page),
         None,
         Some(hinstance),
         None,
@@ -271,7 +631,7 @@ unsafe fn create_controls(hwnd: HWND, settings: &CaptureSettings, hfont: HFONT,
         y_pos,
         control_width,
         control_height,
-        Some(hwnd),
+        Some(page),
         Some(HMENU(ID_CHECK_CURSOR as isize as *mut c_void)),
         Some(hinstance),
         None,
@@ -284,7 +644,7 @@ unsafe fn create_controls(hwnd: HWND, settings: &CaptureSettings, hfont: HFONT,
         Some(WPARAM(hfont.0 as usize)),
         Some(LPARAM(1)),
     );
-    if settings.show_cursor {
+    if settings.capture.show_cursor {
         let _ = SendMessageW(
             check_cursor,
             BM_SETCHECK,
@@ -305,7 +665,7 @@ unsafe fn create_controls(hwnd: HWND, settings: &CaptureSettings, hfont: HFONT,
         y_pos,
         control_width,
         control_height,
-        Some(hwnd),
+        Some(page),
         Some(HMENU(ID_CHECK_BORDER as isize as *mut c_void)),
         Some(hinstance),
         None,
@@ -318,7 +678,7 @@ unsafe fn create_controls(hwnd: HWND, settings: &CaptureSettings, hfont: HFONT,
         Some(WPARAM(hfont.0 as usize)),
         Some(LPARAM(1)),
     );
-    if settings.show_border {
+    if settings.capture.show_border {
         let _ = SendMessageW(
             check_border,
             BM_SETCHECK,
@@ -339,7 +699,7 @@ unsafe fn create_controls(hwnd: HWND, settings: &CaptureSettings, hfont: HFONT,
         y_pos + 2,
         120,
         control_height,
-        Some(hwnd),
+        Some(page),
         None,
         Some(hinstance),
         None,
@@ -352,7 +712,7 @@ unsafe fn create_controls(hwnd: HWND, settings: &CaptureSettings, hfont: HFONT,
         Some(LPARAM(1)),
     );
 
-    let text = wide_string(&settings.border_width.to_string());
+    let text = wide_string(&settings.capture.border_width.to_string());
     let edit_hwnd = CreateWindowExW(
         WS_EX_CLIENTEDGE,
         PCWSTR(edit_class.as_ptr()),
@@ -366,7 +726,7 @@ unsafe fn create_controls(hwnd: HWND, settings: &CaptureSettings, hfont: HFONT,
         y_pos,
         50,
         control_height,
-        Some(hwnd),
+        Some(page),
         Some(HMENU(ID_EDIT_BORDER_WIDTH as isize as *mut c_void)),
         Some(hinstance),
         None,
@@ -390,7 +750,7 @@ unsafe fn create_controls(hwnd: HWND, settings: &CaptureSettings, hfont: HFONT,
         y_pos + 2,
         25,
         control_height,
-        Some(hwnd),
+        Some(page),
         None,
         Some(hinstance),
         None,
@@ -404,156 +764,2779 @@ unsafe fn create_controls(hwnd: HWND, settings: &CaptureSettings, hfont: HFONT,
     );
     y_pos += spacing;
 
-    // Checkbox: Production Mode (only in dev mode)
-    if dev_mode {
-        let text = wide_string("  Production mode (hide destination window)");
-        let check_prod = CreateWindowExW(
-            WINDOW_EX_STYLE(0),
-            PCWSTR(button_class.as_ptr()),
-            PCWSTR(text.as_ptr()),
-            WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(BS_AUTOCHECKBOX as u32),
-            left_margin,
-            y_pos,
-            control_width,
-            control_height,
-            Some(hwnd),
-            Some(HMENU(ID_CHECK_PROD_MODE as isize as *mut c_void)),
-            Some(hinstance),
-            None,
-        )
-        .unwrap();
-        DLG_CHECK_PROD.with(|c| *c.borrow_mut() = Some(check_prod));
-        let _ = SendMessageW(
-            check_prod,
-            WM_SETFONT,
-            Some(WPARAM(hfont.0 as usize)),
-            Some(LPARAM(1)),
-        );
-        if settings.exclude_from_capture {
-            let _ = SendMessageW(
-                check_prod,
-                BM_SETCHECK,
-                Some(WPARAM(BST_CHECKED.0 as usize)),
-                Some(LPARAM(0)),
-            );
-        }
-        y_pos += spacing;
-    }
-    y_pos += 20;
+    // Countdown label and edit (on same line)
+    let text = wide_string("  Countdown:");
+    let label_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos + 2,
+        120,
+        control_height,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    let _ = SendMessageW(
+        label_hwnd,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
 
-    // Buttons - Save and Cancel
-    let btn_width = 100;
-    let btn_height = 32;
-    let btn_spacing = 20;
-    let total_btn_width = btn_width * 2 + btn_spacing;
-    let btn_start_x = (dialog::WIDTH - total_btn_width) / 2;
+    let text = wide_string(&settings.countdown_seconds.to_string());
+    let countdown_hwnd = CreateWindowExW(
+        WS_EX_CLIENTEDGE,
+        PCWSTR(edit_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD
+            | WS_VISIBLE
+            | WS_TABSTOP
+            | WINDOW_STYLE(ES_NUMBER as u32)
+            | WINDOW_STYLE(ES_CENTER as u32),
+        left_margin + 125,
+        y_pos,
+        50,
+        control_height,
+        Some(page),
+        Some(HMENU(ID_EDIT_COUNTDOWN as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    DLG_EDIT_COUNTDOWN.with(|c| *c.borrow_mut() = Some(countdown_hwnd));
+    let _ = SendMessageW(
+        countdown_hwnd,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
 
-    let text = wide_string("Save");
-    let save_btn = CreateWindowExW(
+    let text = wide_string("seconds (0 = off)");
+    let seconds_hwnd = CreateWindowExW(
         WINDOW_EX_STYLE(0),
-        PCWSTR(button_class.as_ptr()),
+        PCWSTR(static_class.as_ptr()),
         PCWSTR(text.as_ptr()),
-        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(BS_DEFPUSHBUTTON as u32),
-        btn_start_x,
-        y_pos,
-        btn_width,
-        btn_height,
-        Some(hwnd),
-        Some(HMENU(ID_BTN_SAVE as isize as *mut c_void)),
+        WS_CHILD | WS_VISIBLE,
+        left_margin + 180,
+        y_pos + 2,
+        160,
+        control_height,
+        Some(page),
+        None,
         Some(hinstance),
         None,
     )
     .unwrap();
     let _ = SendMessageW(
-        save_btn,
+        seconds_hwnd,
         WM_SETFONT,
         Some(WPARAM(hfont.0 as usize)),
         Some(LPARAM(1)),
     );
+    y_pos += spacing;
 
-    let text = wide_string("Cancel");
-    let cancel_btn = CreateWindowExW(
+    // Checkbox: Countdown fullscreen
+    let text = wide_string("  Show countdown over the whole screen");
+    let check_countdown_fullscreen = CreateWindowExW(
         WINDOW_EX_STYLE(0),
         PCWSTR(button_class.as_ptr()),
         PCWSTR(text.as_ptr()),
-        WS_CHILD | WS_VISIBLE | WS_TABSTOP,
-        btn_start_x + btn_width + btn_spacing,
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(BS_AUTOCHECKBOX as u32),
+        left_margin,
         y_pos,
-        btn_width,
-        btn_height,
-        Some(hwnd),
-        Some(HMENU(ID_BTN_CANCEL as isize as *mut c_void)),
+        control_width,
+        control_height,
+        Some(page),
+        Some(HMENU(ID_CHECK_COUNTDOWN_FULLSCREEN as isize as *mut c_void)),
         Some(hinstance),
         None,
     )
     .unwrap();
+    DLG_CHECK_COUNTDOWN_FULLSCREEN.with(|c| *c.borrow_mut() = Some(check_countdown_fullscreen));
     let _ = SendMessageW(
-        cancel_btn,
+        check_countdown_fullscreen,
         WM_SETFONT,
         Some(WPARAM(hfont.0 as usize)),
         Some(LPARAM(1)),
     );
+    if settings.countdown_fullscreen {
+        let _ = SendMessageW(
+            check_countdown_fullscreen,
+            BM_SETCHECK,
+            Some(WPARAM(BST_CHECKED.0 as usize)),
+            Some(LPARAM(0)),
+        );
+    }
+    y_pos += spacing;
 
-    // Credit label at bottom
-    let dialog_height = if dev_mode {
-        dialog::HEIGHT_DEV
-    } else {
-        dialog::HEIGHT_PROD
-    };
-    let text = wide_string("by Salih Cantekin");
-    let credit_hwnd = CreateWindowExW(
+    // Hold last frame label and edit (on same line)
+    let text = wide_string("  Hold on interrupt:");
+    let label_hwnd = CreateWindowExW(
         WINDOW_EX_STYLE(0),
         PCWSTR(static_class.as_ptr()),
         PCWSTR(text.as_ptr()),
-        WS_CHILD | WS_VISIBLE | WINDOW_STYLE(SS_CENTER),
-        0,
-        dialog_height - 55,
-        dialog::WIDTH,
-        18,
-        Some(hwnd),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos + 2,
+        120,
+        control_height,
+        Some(page),
         None,
         Some(hinstance),
         None,
     )
     .unwrap();
-    // Use smaller font for credit
-    let small_font = CreateFontW(
-        14,
-        0,
-        0,
-        0,
-        FW_NORMAL.0 as i32,
-        0,
-        0,
-        0,
-        DEFAULT_CHARSET,
-        OUT_TT_PRECIS,
-        CLIP_DEFAULT_PRECIS,
-        CLEARTYPE_QUALITY,
-        FF_SWISS.0 as u32,
-        PCWSTR(wide_string("Segoe UI").as_ptr()),
+    let _ = SendMessageW(
+        label_hwnd,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
     );
+
+    let text = wide_string(&settings.hold_seconds.to_string());
+    let hold_hwnd = CreateWindowExW(
+        WS_EX_CLIENTEDGE,
+        PCWSTR(edit_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD
+            | WS_VISIBLE
+            | WS_TABSTOP
+            | WINDOW_STYLE(ES_NUMBER as u32)
+            | WINDOW_STYLE(ES_CENTER as u32),
+        left_margin + 125,
+        y_pos,
+        50,
+        control_height,
+        Some(page),
+        Some(HMENU(ID_EDIT_HOLD as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    DLG_EDIT_HOLD.with(|c| *c.borrow_mut() = Some(hold_hwnd));
     let _ = SendMessageW(
-        credit_hwnd,
+        hold_hwnd,
         WM_SETFONT,
-        Some(WPARAM(small_font.0 as usize)),
+        Some(WPARAM(hfont.0 as usize)),
         Some(LPARAM(1)),
     );
-}
 
-#[cfg(windows)]
+    let text = wide_string("seconds (0 = off)");
+    let seconds_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin + 180,
+        y_pos + 2,
+        160,
+        control_height,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    let _ = SendMessageW(
+        seconds_hwnd,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+    y_pos += spacing;
+
+    // Transition duration label and edit (on same line)
+    let text = wide_string("  Transition:");
+    let label_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos + 2,
+        120,
+        control_height,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    let _ = SendMessageW(
+        label_hwnd,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+
+    let text = wide_string(&settings.transition_ms.to_string());
+    let transition_hwnd = CreateWindowExW(
+        WS_EX_CLIENTEDGE,
+        PCWSTR(edit_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD
+            | WS_VISIBLE
+            | WS_TABSTOP
+            | WINDOW_STYLE(ES_NUMBER as u32)
+            | WINDOW_STYLE(ES_CENTER as u32),
+        left_margin + 125,
+        y_pos,
+        50,
+        control_height,
+        Some(page),
+        Some(HMENU(ID_EDIT_TRANSITION as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    DLG_EDIT_TRANSITION.with(|c| *c.borrow_mut() = Some(transition_hwnd));
+    let _ = SendMessageW(
+        transition_hwnd,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+
+    let text = wide_string("ms (0 = cut)");
+    let ms_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin + 180,
+        y_pos + 2,
+        160,
+        control_height,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    let _ = SendMessageW(
+        ms_hwnd,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+    y_pos += spacing;
+
+    // Target frame rate label and edit (on same line)
+    let text = wide_string("  Target FPS:");
+    let label_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos + 2,
+        120,
+        control_height,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    let _ = SendMessageW(
+        label_hwnd,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+
+    let text = wide_string(&settings.capture.target_fps.to_string());
+    let fps_hwnd = CreateWindowExW(
+        WS_EX_CLIENTEDGE,
+        PCWSTR(edit_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD
+            | WS_VISIBLE
+            | WS_TABSTOP
+            | WINDOW_STYLE(ES_NUMBER as u32)
+            | WINDOW_STYLE(ES_CENTER as u32),
+        left_margin + 125,
+        y_pos,
+        50,
+        control_height,
+        Some(page),
+        Some(HMENU(ID_EDIT_TARGET_FPS as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    DLG_EDIT_TARGET_FPS.with(|c| *c.borrow_mut() = Some(fps_hwnd));
+    let _ = SendMessageW(
+        fps_hwnd,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+
+    let text = wide_string("fps (0 = unlimited)");
+    let fps_unit_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin + 180,
+        y_pos + 2,
+        160,
+        control_height,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    let _ = SendMessageW(
+        fps_unit_hwnd,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+    y_pos += spacing;
+
+    // Image source label and edit (image file or slideshow folder)
+    let text = wide_string("  Image:");
+    let label_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos + 2,
+        120,
+        control_height,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    let _ = SendMessageW(
+        label_hwnd,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+
+    let text = wide_string(&settings.image_path);
+    let image_hwnd = CreateWindowExW(
+        WS_EX_CLIENTEDGE,
+        PCWSTR(edit_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(ES_AUTOHSCROLL as u32),
+        left_margin + 125,
+        y_pos,
+        control_width - 125,
+        control_height,
+        Some(page),
+        Some(HMENU(ID_EDIT_IMAGE_PATH as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    DLG_EDIT_IMAGE_PATH.with(|c| *c.borrow_mut() = Some(image_hwnd));
+    let _ = SendMessageW(
+        image_hwnd,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+    y_pos += spacing;
+
+    // Slide interval label and edit (on same line)
+    let text = wide_string("  Slide every:");
+    let label_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos + 2,
+        120,
+        control_height,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    let _ = SendMessageW(
+        label_hwnd,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+
+    let text = wide_string(&settings.slide_seconds.to_string());
+    let slide_hwnd = CreateWindowExW(
+        WS_EX_CLIENTEDGE,
+        PCWSTR(edit_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD
+            | WS_VISIBLE
+            | WS_TABSTOP
+            | WINDOW_STYLE(ES_NUMBER as u32)
+            | WINDOW_STYLE(ES_CENTER as u32),
+        left_margin + 125,
+        y_pos,
+        50,
+        control_height,
+        Some(page),
+        Some(HMENU(ID_EDIT_SLIDE_SECONDS as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    DLG_EDIT_SLIDE_SECONDS.with(|c| *c.borrow_mut() = Some(slide_hwnd));
+    let _ = SendMessageW(
+        slide_hwnd,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+
+    let text = wide_string("s (0 = manual)");
+    let slide_unit_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin + 180,
+        y_pos + 2,
+        160,
+        control_height,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    let _ = SendMessageW(
+        slide_unit_hwnd,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+    y_pos += spacing;
+
+    // Redacted applications label and edit (comma-separated process names)
+    let text = wide_string("  Redact apps:");
+    let label_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos + 2,
+        120,
+        control_height,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    let _ = SendMessageW(
+        label_hwnd,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+
+    let text = wide_string(&settings.redacted_apps.join(", "));
+    let apps_hwnd = CreateWindowExW(
+        WS_EX_CLIENTEDGE,
+        PCWSTR(edit_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(ES_AUTOHSCROLL as u32),
+        left_margin + 125,
+        y_pos,
+        control_width - 125,
+        control_height,
+        Some(page),
+        Some(HMENU(ID_EDIT_REDACTED_APPS as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    DLG_EDIT_REDACTED_APPS.with(|c| *c.borrow_mut() = Some(apps_hwnd));
+    let _ = SendMessageW(
+        apps_hwnd,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+    y_pos += spacing;
+
+    // Checkbox: Hide notifications
+    let text = wide_string("  Hide notification popups in the output");
+    let check_hide_notifications = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(button_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(BS_AUTOCHECKBOX as u32),
+        left_margin,
+        y_pos,
+        control_width,
+        control_height,
+        Some(page),
+        Some(HMENU(ID_CHECK_HIDE_NOTIFICATIONS as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    DLG_CHECK_HIDE_NOTIFICATIONS.with(|c| *c.borrow_mut() = Some(check_hide_notifications));
+    let _ = SendMessageW(
+        check_hide_notifications,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+    if settings.hide_notifications {
+        let _ = SendMessageW(
+            check_hide_notifications,
+            BM_SETCHECK,
+            Some(WPARAM(BST_CHECKED.0 as usize)),
+            Some(LPARAM(0)),
+        );
+    }
+    y_pos += spacing;
+
+    // Checkbox: Gamepad control
+    let text = wide_string("  Gamepad control (XInput controller)");
+    let check_gamepad = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(button_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(BS_AUTOCHECKBOX as u32),
+        left_margin,
+        y_pos,
+        control_width,
+        control_height,
+        Some(page),
+        Some(HMENU(ID_CHECK_GAMEPAD as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    DLG_CHECK_GAMEPAD.with(|c| *c.borrow_mut() = Some(check_gamepad));
+    let _ = SendMessageW(
+        check_gamepad,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+    if settings.gamepad {
+        let _ = SendMessageW(
+            check_gamepad,
+            BM_SETCHECK,
+            Some(WPARAM(BST_CHECKED.0 as usize)),
+            Some(LPARAM(0)),
+        );
+    }
+    y_pos += spacing;
+
+    // Checkbox: Stream Deck / remote control server
+    let text = wide_string(&format!(
+        "  Stream Deck / remote control (port {})",
+        settings.stream_deck_port
+    ));
+    let check_stream_deck = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(button_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(BS_AUTOCHECKBOX as u32),
+        left_margin,
+        y_pos,
+        control_width,
+        control_height,
+        Some(page),
+        Some(HMENU(ID_CHECK_STREAM_DECK as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    DLG_CHECK_STREAM_DECK.with(|c| *c.borrow_mut() = Some(check_stream_deck));
+    let _ = SendMessageW(
+        check_stream_deck,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+    if settings.stream_deck {
+        let _ = SendMessageW(
+            check_stream_deck,
+            BM_SETCHECK,
+            Some(WPARAM(BST_CHECKED.0 as usize)),
+            Some(LPARAM(0)),
+        );
+    }
+    y_pos += spacing;
+
+    // Remote control token label and edit (clients identify with it)
+    let text = wide_string("  Remote token:");
+    let label_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos + 2,
+        120,
+        control_height,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    let _ = SendMessageW(
+        label_hwnd,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+
+    let text = wide_string(&settings.stream_deck_token);
+    let token_hwnd = CreateWindowExW(
+        WS_EX_CLIENTEDGE,
+        PCWSTR(edit_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(ES_AUTOHSCROLL as u32),
+        left_margin + 125,
+        y_pos,
+        control_width - 125,
+        control_height,
+        Some(page),
+        Some(HMENU(ID_EDIT_REMOTE_TOKEN as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    DLG_EDIT_REMOTE_TOKEN.with(|c| *c.borrow_mut() = Some(token_hwnd));
+    let _ = SendMessageW(
+        token_hwnd,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+    y_pos += spacing;
+
+    // Checkbox: OSC listener
+    let text = wide_string(&format!("  OSC control (UDP port {})", settings.osc_port));
+    let check_osc = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(button_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(BS_AUTOCHECKBOX as u32),
+        left_margin,
+        y_pos,
+        control_width,
+        control_height,
+        Some(page),
+        Some(HMENU(ID_CHECK_OSC as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    DLG_CHECK_OSC.with(|c| *c.borrow_mut() = Some(check_osc));
+    let _ = SendMessageW(
+        check_osc,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+    if settings.osc {
+        let _ = SendMessageW(
+            check_osc,
+            BM_SETCHECK,
+            Some(WPARAM(BST_CHECKED.0 as usize)),
+            Some(LPARAM(0)),
+        );
+    }
+    y_pos += spacing;
+
+    // Checkbox: Minimize to tray
+    let text = wide_string("  Minimize to tray (capture keeps running)");
+    let check_minimize_to_tray = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(button_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(BS_AUTOCHECKBOX as u32),
+        left_margin,
+        y_pos,
+        control_width,
+        control_height,
+        Some(page),
+        Some(HMENU(ID_CHECK_MINIMIZE_TO_TRAY as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    DLG_CHECK_MINIMIZE_TO_TRAY.with(|c| *c.borrow_mut() = Some(check_minimize_to_tray));
+    let _ = SendMessageW(
+        check_minimize_to_tray,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+    if settings.minimize_to_tray {
+        let _ = SendMessageW(
+            check_minimize_to_tray,
+            BM_SETCHECK,
+            Some(WPARAM(BST_CHECKED.0 as usize)),
+            Some(LPARAM(0)),
+        );
+    }
+    y_pos += spacing;
+
+    // Checkbox: Close to tray
+    let text = wide_string("  Close to tray (exit from the tray menu)");
+    let check_close_to_tray = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(button_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(BS_AUTOCHECKBOX as u32),
+        left_margin,
+        y_pos,
+        control_width,
+        control_height,
+        Some(page),
+        Some(HMENU(ID_CHECK_CLOSE_TO_TRAY as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    DLG_CHECK_CLOSE_TO_TRAY.with(|c| *c.borrow_mut() = Some(check_close_to_tray));
+    let _ = SendMessageW(
+        check_close_to_tray,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+    if settings.close_to_tray {
+        let _ = SendMessageW(
+            check_close_to_tray,
+            BM_SETCHECK,
+            Some(WPARAM(BST_CHECKED.0 as usize)),
+            Some(LPARAM(0)),
+        );
+    }
+    y_pos += spacing;
+
+    // Checkbox: Start with Windows
+    let text = wide_string("  Start with Windows (minimized to tray)");
+    let check_auto_start = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(button_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(BS_AUTOCHECKBOX as u32),
+        left_margin,
+        y_pos,
+        control_width,
+        control_height,
+        Some(page),
+        Some(HMENU(ID_CHECK_AUTO_START as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    DLG_CHECK_AUTO_START.with(|c| *c.borrow_mut() = Some(check_auto_start));
+    let _ = SendMessageW(
+        check_auto_start,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+    if settings.auto_start {
+        let _ = SendMessageW(
+            check_auto_start,
+            BM_SETCHECK,
+            Some(WPARAM(BST_CHECKED.0 as usize)),
+            Some(LPARAM(0)),
+        );
+    }
+    y_pos += spacing;
+
+    // Checkbox: Start minimized
+    let text = wide_string("  Start minimized to tray");
+    let check_start_minimized = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(button_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(BS_AUTOCHECKBOX as u32),
+        left_margin,
+        y_pos,
+        control_width,
+        control_height,
+        Some(page),
+        Some(HMENU(ID_CHECK_START_MINIMIZED as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    DLG_CHECK_START_MINIMIZED.with(|c| *c.borrow_mut() = Some(check_start_minimized));
+    let _ = SendMessageW(
+        check_start_minimized,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+    if settings.start_minimized {
+        let _ = SendMessageW(
+            check_start_minimized,
+            BM_SETCHECK,
+            Some(WPARAM(BST_CHECKED.0 as usize)),
+            Some(LPARAM(0)),
+        );
+    }
+    y_pos += spacing;
+
+    // Checkbox: Desktop right-click menu
+    let text = wide_string("  Add \"Capture with RustFrame\" to the desktop menu");
+    let check_shell_menu = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(button_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(BS_AUTOCHECKBOX as u32),
+        left_margin,
+        y_pos,
+        control_width,
+        control_height,
+        Some(page),
+        Some(HMENU(ID_CHECK_SHELL_MENU as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    DLG_CHECK_SHELL_MENU.with(|c| *c.borrow_mut() = Some(check_shell_menu));
+    let _ = SendMessageW(
+        check_shell_menu,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+    if settings.shell_menu {
+        let _ = SendMessageW(
+            check_shell_menu,
+            BM_SETCHECK,
+            Some(WPARAM(BST_CHECKED.0 as usize)),
+            Some(LPARAM(0)),
+        );
+    }
+    y_pos += spacing;
+
+    // Filter chain label and edit (comma-separated filter names in the order they
+    // run; filters left out are disabled)
+    let text = wide_string("  Filters:");
+    let label_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos + 2,
+        120,
+        control_height,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    let _ = SendMessageW(
+        label_hwnd,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+
+    let text = wide_string(&settings.filters.join(", "));
+    let filters_hwnd = CreateWindowExW(
+        WS_EX_CLIENTEDGE,
+        PCWSTR(edit_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(ES_AUTOHSCROLL as u32),
+        left_margin + 125,
+        y_pos,
+        control_width - 125,
+        control_height,
+        Some(page),
+        Some(HMENU(ID_EDIT_FILTERS as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    DLG_EDIT_FILTERS.with(|c| *c.borrow_mut() = Some(filters_hwnd));
+    let _ = SendMessageW(
+        filters_hwnd,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+    y_pos += spacing;
+
+    // Checkbox: Production Mode (only in dev mode)
+    if dev_mode {
+        let text = wide_string("  Production mode (hide destination window)");
+        let check_prod = CreateWindowExW(
+            WINDOW_EX_STYLE(0),
+            PCWSTR(button_class.as_ptr()),
+            PCWSTR(text.as_ptr()),
+            WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(BS_AUTOCHECKBOX as u32),
+            left_margin,
+            y_pos,
+            control_width,
+            control_height,
+            Some(page),
+            Some(HMENU(ID_CHECK_PROD_MODE as isize as *mut c_void)),
+            Some(hinstance),
+            None,
+        )
+        .unwrap();
+        DLG_CHECK_PROD.with(|c| *c.borrow_mut() = Some(check_prod));
+        let _ = SendMessageW(
+            check_prod,
+            WM_SETFONT,
+            Some(WPARAM(hfont.0 as usize)),
+            Some(LPARAM(1)),
+        );
+        if settings.capture.exclude_from_capture {
+            let _ = SendMessageW(
+                check_prod,
+                BM_SETCHECK,
+                Some(WPARAM(BST_CHECKED.0 as usize)),
+                Some(LPARAM(0)),
+            );
+        }
+        y_pos += spacing;
+    }
+    y_pos += 20;
+
+    // The pages are as tall as the general settings, the buttons go below them
+    DLG_PAGES.with(|pages| {
+        for &page in pages.borrow().iter() {
+            let _ = SetWindowPos(
+                page,
+                None,
+                0,
+                0,
+                dialog::WIDTH,
+                y_pos,
+                SWP_NOMOVE | SWP_NOZORDER,
+            );
+        }
+    });
+    y_pos += dialog::PAGE_TOP;
+
+    let btn_width = 100;
+    let btn_height = 32;
+    let btn_spacing = 20;
+
+    // Buttons - Undo, Redo and Reset Tab (disabled until there is an edit)
+    let total_btn_width = btn_width * 3 + btn_spacing * 2;
+    let btn_start_x = (dialog::WIDTH - total_btn_width) / 2;
+    for (index, (id, label)) in [
+        (ID_BTN_UNDO, "Undo"),
+        (ID_BTN_REDO, "Redo"),
+        (ID_BTN_RESET_PAGE, "Reset Tab"),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let text = wide_string(label);
+        let button = CreateWindowExW(
+            WINDOW_EX_STYLE(0),
+            PCWSTR(button_class.as_ptr()),
+            PCWSTR(text.as_ptr()),
+            WS_CHILD | WS_VISIBLE | WS_TABSTOP,
+            btn_start_x + (btn_width + btn_spacing) * index as i32,
+            y_pos,
+            btn_width,
+            btn_height,
+            Some(hwnd),
+            Some(HMENU(id as isize as *mut c_void)),
+            Some(hinstance),
+            None,
+        )
+        .unwrap();
+        let _ = SendMessageW(
+            button,
+            WM_SETFONT,
+            Some(WPARAM(hfont.0 as usize)),
+            Some(LPARAM(1)),
+        );
+        match id {
+            ID_BTN_UNDO => DLG_BTN_UNDO.with(|b| *b.borrow_mut() = Some(button)),
+            ID_BTN_REDO => DLG_BTN_REDO.with(|b| *b.borrow_mut() = Some(button)),
+            _ => {}
+        }
+    }
+    y_pos += btn_height + 12;
+
+    // Buttons - Save and Cancel
+    let total_btn_width = btn_width * 2 + btn_spacing;
+    let btn_start_x = (dialog::WIDTH - total_btn_width) / 2;
+
+    let text = wide_string("Save");
+    let save_btn = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(button_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(BS_DEFPUSHBUTTON as u32),
+        btn_start_x,
+        y_pos,
+        btn_width,
+        btn_height,
+        Some(hwnd),
+        Some(HMENU(ID_BTN_SAVE as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    let _ = SendMessageW(
+        save_btn,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+
+    let text = wide_string("Cancel");
+    let cancel_btn = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(button_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP,
+        btn_start_x + btn_width + btn_spacing,
+        y_pos,
+        btn_width,
+        btn_height,
+        Some(hwnd),
+        Some(HMENU(ID_BTN_CANCEL as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    let _ = SendMessageW(
+        cancel_btn,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+
+    // Credit label at bottom
+    let dialog_height = if dev_mode {
+        dialog::HEIGHT_DEV
+    } else {
+        dialog::HEIGHT_PROD
+    };
+    let text = wide_string("by Salih Cantekin");
+    let credit_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE | WINDOW_STYLE(SS_CENTER),
+        0,
+        dialog_height - 55,
+        dialog::WIDTH,
+        18,
+        Some(hwnd),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    // Use smaller font for credit
+    let small_font = CreateFontW(
+        14,
+        0,
+        0,
+        0,
+        FW_NORMAL.0 as i32,
+        0,
+        0,
+        0,
+        DEFAULT_CHARSET,
+        OUT_TT_PRECIS,
+        CLIP_DEFAULT_PRECIS,
+        CLEARTYPE_QUALITY,
+        FF_SWISS.0 as u32,
+        PCWSTR(wide_string("Segoe UI").as_ptr()),
+    );
+    let _ = SendMessageW(
+        credit_hwnd,
+        WM_SETFONT,
+        Some(WPARAM(small_font.0 as usize)),
+        Some(LPARAM(1)),
+    );
+}
+
+/// Create the tab control with the General, Shortcuts, MIDI, Plugins and
+/// Advanced tabs and a page window for each (the pages are sized in create_controls)
+#[cfg(windows)]
+unsafe fn create_tabs(
+    hwnd: HWND,
+    page_class: windows::core::PCWSTR,
+    hfont: HFONT,
+) -> (HWND, HWND, HWND, HWND, HWND) {
+    use windows::core::PWSTR;
+
+    let module = GetModuleHandleW(None).unwrap();
+    let hinstance: HINSTANCE = module.into();
+
+    let tab = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        WC_TABCONTROLW,
+        windows::core::PCWSTR::null(),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WS_CLIPSIBLINGS,
+        dialog::TAB_MARGIN,
+        dialog::TAB_MARGIN,
+        dialog::WIDTH - 4 * dialog::TAB_MARGIN,
+        dialog::TAB_HEIGHT,
+        Some(hwnd),
+        Some(HMENU(ID_TAB as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    let _ = SendMessageW(
+        tab,
+        WM_SETFONT,
+        Some(WPARAM(hfont.0 as usize)),
+        Some(LPARAM(1)),
+    );
+    for (index, title) in ["General", "Shortcuts", "MIDI", "Plugins", "Advanced"]
+        .iter()
+        .enumerate()
+    {
+        let mut text = wide_string(title);
+        let item = TCITEMW {
+            mask: TCIF_TEXT,
+            pszText: PWSTR(text.as_mut_ptr()),
+            ..Default::default()
+        };
+        let _ = SendMessageW(
+            tab,
+            TCM_INSERTITEMW,
+            Some(WPARAM(index)),
+            Some(LPARAM(&item as *const _ as isize)),
+        );
+    }
+    DLG_TAB.with(|t| *t.borrow_mut() = Some(tab));
+
+    let mut pages = Vec::new();
+    for visible in [true, false, false, false, false] {
+        let style = if visible {
+            WS_CHILD | WS_VISIBLE
+        } else {
+            WS_CHILD
+        };
+        let page = CreateWindowExW(
+            WS_EX_CONTROLPARENT,
+            page_class,
+            windows::core::PCWSTR::null(),
+            style,
+            0,
+            dialog::PAGE_TOP,
+            dialog::WIDTH,
+            0,
+            Some(hwnd),
+            None,
+            Some(hinstance),
+            None,
+        )
+        .unwrap();
+        pages.push(page);
+    }
+    DLG_PAGES.with(|p| *p.borrow_mut() = pages.clone());
+    (
+        pages[PAGE_GENERAL],
+        pages[PAGE_SHORTCUTS],
+        pages[PAGE_MIDI],
+        pages[PAGE_PLUGINS],
+        pages[PAGE_ADVANCED],
+    )
+}
+
+/// Create the Shortcuts page: every action with its key combination, and a
+/// hotkey control that takes the new combination for the selected action
+#[cfg(windows)]
+unsafe fn create_shortcut_controls(page: HWND, hfont: HFONT) {
+    use windows::core::{PCWSTR, PWSTR};
+
+    let module = GetModuleHandleW(None).unwrap();
+    let hinstance: HINSTANCE = module.into();
+    let button_class = wide_string("BUTTON");
+    let static_class = wide_string("STATIC");
+
+    let mut y_pos = 20;
+    let left_margin = 30;
+    let control_width = 340;
+    let control_height = 24;
+    let spacing = 32;
+
+    let set_font = |control: HWND| {
+        let _ = SendMessageW(
+            control,
+            WM_SETFONT,
+            Some(WPARAM(hfont.0 as usize)),
+            Some(LPARAM(1)),
+        );
+    };
+
+    // Title and hint
+    let text = wide_string("Shortcuts");
+    let title_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos - 10,
+        control_width,
+        28,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(title_hwnd);
+    y_pos += spacing - 8;
+
+    let text = wide_string("Select an action, then press the new key combination.");
+    let hint_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos,
+        control_width,
+        control_height,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(hint_hwnd);
+    y_pos += spacing;
+
+    // Action list (action, shortcut)
+    let list = CreateWindowExW(
+        WS_EX_CLIENTEDGE,
+        WC_LISTVIEWW,
+        PCWSTR::null(),
+        WS_CHILD
+            | WS_VISIBLE
+            | WS_TABSTOP
+            | WINDOW_STYLE(LVS_REPORT | LVS_SINGLESEL | LVS_SHOWSELALWAYS | LVS_NOSORTHEADER),
+        left_margin,
+        y_pos,
+        control_width,
+        dialog::SHORTCUT_LIST_HEIGHT,
+        Some(page),
+        Some(HMENU(ID_LIST_SHORTCUTS as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(list);
+    let _ = SendMessageW(
+        list,
+        LVM_SETEXTENDEDLISTVIEWSTYLE,
+        Some(WPARAM(LVS_EX_FULLROWSELECT as usize)),
+        Some(LPARAM(LVS_EX_FULLROWSELECT as isize)),
+    );
+    for (index, (title, width)) in [("Action", 190), ("Shortcut", 125)].iter().enumerate() {
+        let mut text = wide_string(title);
+        let column = LVCOLUMNW {
+            mask: LVCF_TEXT | LVCF_WIDTH,
+            cx: *width,
+            pszText: PWSTR(text.as_mut_ptr()),
+            ..Default::default()
+        };
+        let _ = SendMessageW(
+            list,
+            LVM_INSERTCOLUMNW,
+            Some(WPARAM(index)),
+            Some(LPARAM(&column as *const _ as isize)),
+        );
+    }
+    let shortcuts = DLG_SHORTCUTS.with(|s| s.borrow().clone());
+    for (row, (action, hotkey)) in shortcuts.iter().enumerate() {
+        let mut text = wide_string(&action.label());
+        let item = LVITEMW {
+            mask: LVIF_TEXT,
+            iItem: row as i32,
+            pszText: PWSTR(text.as_mut_ptr()),
+            ..Default::default()
+        };
+        let _ = SendMessageW(
+            list,
+            LVM_INSERTITEMW,
+            Some(WPARAM(0)),
+            Some(LPARAM(&item as *const _ as isize)),
+        );
+        set_list_text(list, row, 1, &shortcut_label(*hotkey));
+    }
+    DLG_LIST_SHORTCUTS.with(|l| *l.borrow_mut() = Some(list));
+    y_pos += dialog::SHORTCUT_LIST_HEIGHT + 12;
+
+    // Shortcut label and hotkey control (on same line)
+    let text = wide_string("  Shortcut:");
+    let label_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos + 2,
+        120,
+        control_height,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(label_hwnd);
+
+    let hotkey_hwnd = CreateWindowExW(
+        WS_EX_CLIENTEDGE,
+        HOTKEY_CLASSW,
+        PCWSTR::null(),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP,
+        left_margin + 125,
+        y_pos,
+        control_width - 125,
+        control_height,
+        Some(page),
+        Some(HMENU(ID_HOTKEY_SHORTCUT as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(hotkey_hwnd);
+    // Global shortcuts need a modifier - plain keys and Shift+key become Ctrl+Alt+key
+    let _ = SendMessageW(
+        hotkey_hwnd,
+        HKM_SETRULES,
+        Some(WPARAM((HKCOMB_NONE | HKCOMB_S) as usize)),
+        Some(LPARAM((HOTKEYF_CONTROL | HOTKEYF_ALT) as isize)),
+    );
+    DLG_HOTKEY_SHORTCUT.with(|h| *h.borrow_mut() = Some(hotkey_hwnd));
+    y_pos += spacing + 4;
+
+    // Default and Unbind buttons
+    let btn_width = (control_width - 125 - 10) / 2;
+    for (index, (title, id)) in [
+        ("Default", ID_BTN_SHORTCUT_DEFAULT),
+        ("Unbind", ID_BTN_SHORTCUT_CLEAR),
+    ]
+    .iter()
+    .enumerate()
+    {
+        let text = wide_string(title);
+        let button = CreateWindowExW(
+            WINDOW_EX_STYLE(0),
+            PCWSTR(button_class.as_ptr()),
+            PCWSTR(text.as_ptr()),
+            WS_CHILD | WS_VISIBLE | WS_TABSTOP,
+            left_margin + 125 + index as i32 * (btn_width + 10),
+            y_pos,
+            btn_width,
+            28,
+            Some(page),
+            Some(HMENU(*id as isize as *mut c_void)),
+            Some(hinstance),
+            None,
+        )
+        .unwrap();
+        set_font(button);
+    }
+    y_pos += spacing + 8;
+
+    // Conflicts and warnings for the selected shortcut
+    let status_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR::null(),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos,
+        control_width,
+        dialog::SHORTCUT_STATUS_HEIGHT,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(status_hwnd);
+    DLG_SHORTCUT_STATUS.with(|s| *s.borrow_mut() = Some(status_hwnd));
+
+    // Start with the first action selected
+    select_list_row(list, 0);
+}
+
+/// Create the MIDI page: enable checkbox, input device, every action with its
+/// note / CC, and Learn / Clear buttons for the selected action
+#[cfg(windows)]
+unsafe fn create_midi_controls(page: HWND, settings: &AppSettings, hfont: HFONT) {
+    use windows::core::{PCWSTR, PWSTR};
+
+    let module = GetModuleHandleW(None).unwrap();
+    let hinstance: HINSTANCE = module.into();
+    let button_class = wide_string("BUTTON");
+    let static_class = wide_string("STATIC");
+    let combo_class = wide_string("COMBOBOX");
+
+    let mut y_pos = 20;
+    let left_margin = 30;
+    let control_width = 340;
+    let control_height = 24;
+    let spacing = 32;
+
+    let set_font = |control: HWND| {
+        let _ = SendMessageW(
+            control,
+            WM_SETFONT,
+            Some(WPARAM(hfont.0 as usize)),
+            Some(LPARAM(1)),
+        );
+    };
+
+    // Title
+    let text = wide_string("MIDI Controller");
+    let title_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos - 10,
+        control_width,
+        28,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(title_hwnd);
+    y_pos += spacing - 8;
+
+    // Checkbox: MIDI control
+    let text = wide_string("  Enable MIDI control");
+    let check_midi = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(button_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(BS_AUTOCHECKBOX as u32),
+        left_margin,
+        y_pos,
+        control_width,
+        control_height,
+        Some(page),
+        Some(HMENU(ID_CHECK_MIDI as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(check_midi);
+    if settings.midi_enabled {
+        let _ = SendMessageW(
+            check_midi,
+            BM_SETCHECK,
+            Some(WPARAM(BST_CHECKED.0 as usize)),
+            Some(LPARAM(0)),
+        );
+    }
+    DLG_CHECK_MIDI.with(|c| *c.borrow_mut() = Some(check_midi));
+    y_pos += spacing;
+
+    // Device label and dropdown (on same line)
+    let text = wide_string("  Device:");
+    let label_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos + 2,
+        120,
+        control_height,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(label_hwnd);
+
+    let combo = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(combo_class.as_ptr()),
+        PCWSTR::null(),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WS_VSCROLL | WINDOW_STYLE(CBS_DROPDOWNLIST as u32),
+        left_margin + 125,
+        y_pos,
+        control_width - 125,
+        200,
+        Some(page),
+        Some(HMENU(ID_COMBO_MIDI_DEVICE as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(combo);
+    // "(first device)", the connected devices, and the stored device even if
+    // it is not connected right now (so saving does not forget it)
+    let mut devices = midi::device_names();
+    let stored = settings.midi_device.trim();
+    if !stored.is_empty() && !devices.iter().any(|d| d.eq_ignore_ascii_case(stored)) {
+        devices.push(stored.to_string());
+    }
+    let mut selected = 0;
+    for (index, name) in std::iter::once("(first device)")
+        .chain(devices.iter().map(|d| d.as_str()))
+        .enumerate()
+    {
+        let text = wide_string(name);
+        let _ = SendMessageW(
+            combo,
+            CB_ADDSTRING,
+            None,
+            Some(LPARAM(text.as_ptr() as isize)),
+        );
+        if index > 0 && name.eq_ignore_ascii_case(stored) {
+            selected = index;
+        }
+    }
+    let _ = SendMessageW(combo, CB_SETCURSEL, Some(WPARAM(selected)), None);
+    DLG_COMBO_MIDI_DEVICE.with(|c| *c.borrow_mut() = Some(combo));
+    y_pos += spacing + 4;
+
+    let text =
+        wide_string("Select an action, click Learn, then press the pad or move the control.");
+    let hint_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos,
+        control_width,
+        control_height * 2,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(hint_hwnd);
+    y_pos += spacing + 16;
+
+    // Action list (action, note / CC)
+    let list = CreateWindowExW(
+        WS_EX_CLIENTEDGE,
+        WC_LISTVIEWW,
+        PCWSTR::null(),
+        WS_CHILD
+            | WS_VISIBLE
+            | WS_TABSTOP
+            | WINDOW_STYLE(LVS_REPORT | LVS_SINGLESEL | LVS_SHOWSELALWAYS | LVS_NOSORTHEADER),
+        left_margin,
+        y_pos,
+        control_width,
+        dialog::MIDI_LIST_HEIGHT,
+        Some(page),
+        Some(HMENU(ID_LIST_MIDI as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(list);
+    let _ = SendMessageW(
+        list,
+        LVM_SETEXTENDEDLISTVIEWSTYLE,
+        Some(WPARAM(LVS_EX_FULLROWSELECT as usize)),
+        Some(LPARAM(LVS_EX_FULLROWSELECT as isize)),
+    );
+    for (index, (title, width)) in [("Action", 210), ("MIDI", 105)].iter().enumerate() {
+        let mut text = wide_string(title);
+        let column = LVCOLUMNW {
+            mask: LVCF_TEXT | LVCF_WIDTH,
+            cx: *width,
+            pszText: PWSTR(text.as_mut_ptr()),
+            ..Default::default()
+        };
+        let _ = SendMessageW(
+            list,
+            LVM_INSERTCOLUMNW,
+            Some(WPARAM(index)),
+            Some(LPARAM(&column as *const _ as isize)),
+        );
+    }
+    let bindings = DLG_MIDI_BINDINGS.with(|b| b.borrow().clone());
+    for (row, (action, trigger)) in bindings.iter().enumerate() {
+        let mut text = wide_string(&action.label());
+        let item = LVITEMW {
+            mask: LVIF_TEXT,
+            iItem: row as i32,
+            pszText: PWSTR(text.as_mut_ptr()),
+            ..Default::default()
+        };
+        let _ = SendMessageW(
+            list,
+            LVM_INSERTITEMW,
+            Some(WPARAM(0)),
+            Some(LPARAM(&item as *const _ as isize)),
+        );
+        set_list_text(list, row, 1, &trigger_label(*trigger));
+    }
+    DLG_LIST_MIDI.with(|l| *l.borrow_mut() = Some(list));
+    y_pos += dialog::MIDI_LIST_HEIGHT + 12;
+
+    // Learn and Clear buttons
+    let btn_width = (control_width - 125 - 10) / 2;
+    for (index, (title, id)) in [("Learn", ID_BTN_MIDI_LEARN), ("Clear", ID_BTN_MIDI_CLEAR)]
+        .iter()
+        .enumerate()
+    {
+        let text = wide_string(title);
+        let button = CreateWindowExW(
+            WINDOW_EX_STYLE(0),
+            PCWSTR(button_class.as_ptr()),
+            PCWSTR(text.as_ptr()),
+            WS_CHILD | WS_VISIBLE | WS_TABSTOP,
+            left_margin + 125 + index as i32 * (btn_width + 10),
+            y_pos,
+            btn_width,
+            28,
+            Some(page),
+            Some(HMENU(*id as isize as *mut c_void)),
+            Some(hinstance),
+            None,
+        )
+        .unwrap();
+        set_font(button);
+        if *id == ID_BTN_MIDI_LEARN {
+            DLG_BTN_MIDI_LEARN.with(|b| *b.borrow_mut() = Some(button));
+        }
+    }
+    y_pos += spacing + 8;
+
+    // Learn mode and device status
+    let status_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR::null(),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos,
+        control_width,
+        dialog::MIDI_STATUS_HEIGHT,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(status_hwnd);
+    DLG_MIDI_STATUS.with(|s| *s.borrow_mut() = Some(status_hwnd));
+    if midi::device_names().is_empty() {
+        set_midi_status("No MIDI input device found - connect the controller and reopen Settings.");
+    }
+
+    // Start with the first action selected
+    select_list_row(list, 0);
+}
+
+/// Create the Advanced page: priority and cores of the capture thread
+#[cfg(windows)]
+unsafe fn create_advanced_controls(page: HWND, settings: &AppSettings, hfont: HFONT) {
+    use windows::core::PCWSTR;
+
+    let module = GetModuleHandleW(None).unwrap();
+    let hinstance: HINSTANCE = module.into();
+    let button_class = wide_string("BUTTON");
+    let static_class = wide_string("STATIC");
+    let combo_class = wide_string("COMBOBOX");
+    let edit_class = wide_string("EDIT");
+
+    let mut y_pos = 20;
+    let left_margin = 30;
+    let control_width = 340;
+    let control_height = 24;
+    let spacing = 32;
+
+    let set_font = |control: HWND| {
+        let _ = SendMessageW(
+            control,
+            WM_SETFONT,
+            Some(WPARAM(hfont.0 as usize)),
+            Some(LPARAM(1)),
+        );
+    };
+    let label = |text: &str, y: i32| {
+        let text = wide_string(text);
+        let hwnd = CreateWindowExW(
+            WINDOW_EX_STYLE(0),
+            PCWSTR(static_class.as_ptr()),
+            PCWSTR(text.as_ptr()),
+            WS_CHILD | WS_VISIBLE,
+            left_margin,
+            y,
+            120,
+            control_height,
+            Some(page),
+            None,
+            Some(hinstance),
+            None,
+        )
+        .unwrap();
+        set_font(hwnd);
+    };
+
+    // Title and hint
+    let text = wide_string("Capture Thread");
+    let title_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos - 10,
+        control_width,
+        28,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(title_hwnd);
+    y_pos += spacing - 8;
+
+    let text = wide_string(
+        "Helps frame pacing when the captured application keeps the CPU busy. \
+         Cores are numbers from 0, e.g. \"2, 3\" (empty = any core).",
+    );
+    let hint_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos,
+        control_width,
+        control_height * 2,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(hint_hwnd);
+    y_pos += spacing + 16;
+
+    // Priority label and dropdown (on same line)
+    label("  Priority:", y_pos + 2);
+    let combo = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(combo_class.as_ptr()),
+        PCWSTR::null(),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WS_VSCROLL | WINDOW_STYLE(CBS_DROPDOWNLIST as u32),
+        left_margin + 125,
+        y_pos,
+        control_width - 125,
+        200,
+        Some(page),
+        Some(HMENU(ID_COMBO_THREAD_PRIORITY as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(combo);
+    for priority in ThreadPriority::ALL {
+        let text = wide_string(priority.label());
+        let _ = SendMessageW(
+            combo,
+            CB_ADDSTRING,
+            None,
+            Some(LPARAM(text.as_ptr() as isize)),
+        );
+    }
+    let selected = ThreadPriority::ALL
+        .iter()
+        .position(|&p| p == settings.thread_priority)
+        .unwrap_or(0);
+    let _ = SendMessageW(combo, CB_SETCURSEL, Some(WPARAM(selected)), None);
+    DLG_COMBO_THREAD_PRIORITY.with(|c| *c.borrow_mut() = Some(combo));
+    y_pos += spacing + 4;
+
+    // Cores label and edit (on same line)
+    label("  CPU cores:", y_pos + 2);
+    let text = wide_string(&cores_text(&settings.cpu_cores));
+    let cores_hwnd = CreateWindowExW(
+        WS_EX_CLIENTEDGE,
+        PCWSTR(edit_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(ES_AUTOHSCROLL as u32),
+        left_margin + 125,
+        y_pos,
+        control_width - 125,
+        control_height,
+        Some(page),
+        Some(HMENU(ID_EDIT_CPU_CORES as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(cores_hwnd);
+    DLG_EDIT_CPU_CORES.with(|c| *c.borrow_mut() = Some(cores_hwnd));
+    y_pos += spacing;
+
+    // Checkbox: Desktop Duplication for exclusive-fullscreen games
+    let text = wide_string("  Capture fullscreen games with Desktop Duplication");
+    let check_game_capture = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(button_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(BS_AUTOCHECKBOX as u32),
+        left_margin,
+        y_pos,
+        control_width,
+        control_height,
+        Some(page),
+        Some(HMENU(ID_CHECK_GAME_CAPTURE as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(check_game_capture);
+    if settings.capture.game_capture {
+        let _ = SendMessageW(
+            check_game_capture,
+            BM_SETCHECK,
+            Some(WPARAM(BST_CHECKED.0 as usize)),
+            Some(LPARAM(0)),
+        );
+    }
+    DLG_CHECK_GAME_CAPTURE.with(|c| *c.borrow_mut() = Some(check_game_capture));
+    y_pos += spacing + 16;
+
+    // Keyboard: what ESC does
+    let text = wide_string("Keyboard");
+    let keyboard_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos - 10,
+        control_width,
+        28,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(keyboard_hwnd);
+    y_pos += spacing - 8;
+
+    let text = wide_string("Stopping a running capture is always confirmed first.");
+    let hint_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos,
+        control_width,
+        control_height,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(hint_hwnd);
+    y_pos += spacing;
+
+    label("  ESC key:", y_pos + 2);
+    let combo = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(combo_class.as_ptr()),
+        PCWSTR::null(),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WS_VSCROLL | WINDOW_STYLE(CBS_DROPDOWNLIST as u32),
+        left_margin + 125,
+        y_pos,
+        control_width - 125,
+        200,
+        Some(page),
+        Some(HMENU(ID_COMBO_ESC_ACTION as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(combo);
+    for action in EscAction::ALL {
+        let text = wide_string(action.label());
+        let _ = SendMessageW(
+            combo,
+            CB_ADDSTRING,
+            None,
+            Some(LPARAM(text.as_ptr() as isize)),
+        );
+    }
+    let selected = EscAction::ALL
+        .iter()
+        .position(|&a| a == settings.esc_action)
+        .unwrap_or(0);
+    let _ = SendMessageW(combo, CB_SETCURSEL, Some(WPARAM(selected)), None);
+    DLG_COMBO_ESC_ACTION.with(|c| *c.borrow_mut() = Some(combo));
+    y_pos += spacing + 16;
+
+    // Appearance: light / dark and the accent color
+    let text = wide_string("Appearance");
+    let appearance_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos - 10,
+        control_width,
+        28,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(appearance_hwnd);
+    y_pos += spacing - 8;
+
+    label("  Theme:", y_pos + 2);
+    let combo = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(combo_class.as_ptr()),
+        PCWSTR::null(),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WS_VSCROLL | WINDOW_STYLE(CBS_DROPDOWNLIST as u32),
+        left_margin + 125,
+        y_pos,
+        control_width - 125,
+        200,
+        Some(page),
+        Some(HMENU(ID_COMBO_THEME as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(combo);
+    for mode in ThemeMode::ALL {
+        let text = wide_string(mode.label());
+        let _ = SendMessageW(
+            combo,
+            CB_ADDSTRING,
+            None,
+            Some(LPARAM(text.as_ptr() as isize)),
+        );
+    }
+    let selected = ThemeMode::ALL
+        .iter()
+        .position(|&m| m == settings.theme)
+        .unwrap_or(0);
+    let _ = SendMessageW(combo, CB_SETCURSEL, Some(WPARAM(selected)), None);
+    DLG_COMBO_THEME.with(|c| *c.borrow_mut() = Some(combo));
+    y_pos += spacing + 4;
+
+    label("  Accent color:", y_pos + 2);
+    let text = wide_string(&settings.accent_color);
+    let accent_hwnd = CreateWindowExW(
+        WS_EX_CLIENTEDGE,
+        PCWSTR(edit_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WINDOW_STYLE(ES_AUTOHSCROLL as u32),
+        left_margin + 125,
+        y_pos,
+        control_width - 125,
+        control_height,
+        Some(page),
+        Some(HMENU(ID_EDIT_ACCENT as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(accent_hwnd);
+    DLG_EDIT_ACCENT.with(|c| *c.borrow_mut() = Some(accent_hwnd));
+    y_pos += spacing;
+
+    let text = wide_string("#RRGGBB, empty for RustFrame blue");
+    let accent_hint = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin + 125,
+        y_pos - 4,
+        control_width - 125,
+        control_height,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(accent_hint);
+    y_pos += spacing;
+
+    label("  Toolbar:", y_pos + 2);
+    let combo = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(combo_class.as_ptr()),
+        PCWSTR::null(),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP | WS_VSCROLL | WINDOW_STYLE(CBS_DROPDOWNLIST as u32),
+        left_margin + 125,
+        y_pos,
+        control_width - 125,
+        200,
+        Some(page),
+        Some(HMENU(ID_COMBO_TOOLBAR as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(combo);
+    for placement in ToolbarPlacement::ALL {
+        let text = wide_string(placement.label());
+        let _ = SendMessageW(
+            combo,
+            CB_ADDSTRING,
+            None,
+            Some(LPARAM(text.as_ptr() as isize)),
+        );
+    }
+    let selected = ToolbarPlacement::ALL
+        .iter()
+        .position(|&p| p == settings.toolbar)
+        .unwrap_or(0);
+    let _ = SendMessageW(combo, CB_SETCURSEL, Some(WPARAM(selected)), None);
+    DLG_COMBO_TOOLBAR.with(|c| *c.borrow_mut() = Some(combo));
+}
+
+/// "2, 3" for the cores edit
+fn cores_text(cores: &[u32]) -> String {
+    cores
+        .iter()
+        .map(|core| core.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Create the Plugins page: every plugin DLL with an on/off checkbox, its
+/// hooks and status, and the panel of the selected plugin
+#[cfg(windows)]
+unsafe fn create_plugin_controls(page: HWND, hfont: HFONT) {
+    use windows::core::{PCWSTR, PWSTR};
+
+    let module = GetModuleHandleW(None).unwrap();
+    let hinstance: HINSTANCE = module.into();
+    let button_class = wide_string("BUTTON");
+    let static_class = wide_string("STATIC");
+
+    let mut y_pos = 20;
+    let left_margin = 30;
+    let control_width = 340;
+    let control_height = 24;
+    let spacing = 32;
+
+    let set_font = |control: HWND| {
+        let _ = SendMessageW(
+            control,
+            WM_SETFONT,
+            Some(WPARAM(hfont.0 as usize)),
+            Some(LPARAM(1)),
+        );
+    };
+
+    // Title and hint
+    let text = wide_string("Plugins");
+    let title_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos - 10,
+        control_width,
+        28,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(title_hwnd);
+    y_pos += spacing - 8;
+
+    let text = wide_string(
+        "Plugin DLLs in the plugins folder. Turning a plugin on or off takes effect at the next start.",
+    );
+    let hint_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos,
+        control_width,
+        control_height * 2,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(hint_hwnd);
+    y_pos += spacing + 16;
+
+    // Plugin list (checkbox + name, hooks, status)
+    let list = CreateWindowExW(
+        WS_EX_CLIENTEDGE,
+        WC_LISTVIEWW,
+        PCWSTR::null(),
+        WS_CHILD
+            | WS_VISIBLE
+            | WS_TABSTOP
+            | WINDOW_STYLE(LVS_REPORT | LVS_SINGLESEL | LVS_SHOWSELALWAYS | LVS_NOSORTHEADER),
+        left_margin,
+        y_pos,
+        control_width,
+        dialog::PLUGIN_LIST_HEIGHT,
+        Some(page),
+        Some(HMENU(ID_LIST_PLUGINS as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(list);
+    let extended = LVS_EX_FULLROWSELECT | LVS_EX_CHECKBOXES;
+    let _ = SendMessageW(
+        list,
+        LVM_SETEXTENDEDLISTVIEWSTYLE,
+        Some(WPARAM(extended as usize)),
+        Some(LPARAM(extended as isize)),
+    );
+    for (index, (title, width)) in [("Plugin", 150), ("Hooks", 80), ("Status", 85)]
+        .iter()
+        .enumerate()
+    {
+        let mut text = wide_string(title);
+        let column = LVCOLUMNW {
+            mask: LVCF_TEXT | LVCF_WIDTH,
+            cx: *width,
+            pszText: PWSTR(text.as_mut_ptr()),
+            ..Default::default()
+        };
+        let _ = SendMessageW(
+            list,
+            LVM_INSERTCOLUMNW,
+            Some(WPARAM(index)),
+            Some(LPARAM(&column as *const _ as isize)),
+        );
+    }
+    let plugins = DLG_PLUGINS.with(|p| p.borrow().clone());
+    for (row, plugin) in plugins.iter().enumerate() {
+        let mut text = wide_string(&format!("{} {}", plugin.name, plugin.version));
+        let item = LVITEMW {
+            mask: LVIF_TEXT,
+            iItem: row as i32,
+            pszText: PWSTR(text.as_mut_ptr()),
+            ..Default::default()
+        };
+        let _ = SendMessageW(
+            list,
+            LVM_INSERTITEMW,
+            Some(WPARAM(0)),
+            Some(LPARAM(&item as *const _ as isize)),
+        );
+        set_list_text(list, row, 1, &plugin.hooks);
+        set_list_text(list, row, 2, &plugin.status);
+        set_list_check(list, row, plugin.enabled);
+    }
+    DLG_LIST_PLUGINS.with(|l| *l.borrow_mut() = Some(list));
+    y_pos += dialog::PLUGIN_LIST_HEIGHT + 12;
+
+    // Open Plugins Folder button
+    let text = wide_string("Open Plugins Folder");
+    let button = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(button_class.as_ptr()),
+        PCWSTR(text.as_ptr()),
+        WS_CHILD | WS_VISIBLE | WS_TABSTOP,
+        left_margin + 125,
+        y_pos,
+        control_width - 125,
+        28,
+        Some(page),
+        Some(HMENU(ID_BTN_PLUGINS_FOLDER as isize as *mut c_void)),
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(button);
+    y_pos += spacing + 8;
+
+    // Panel of the selected plugin
+    let panel_hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE(0),
+        PCWSTR(static_class.as_ptr()),
+        PCWSTR::null(),
+        WS_CHILD | WS_VISIBLE,
+        left_margin,
+        y_pos,
+        control_width,
+        dialog::PLUGIN_PANEL_HEIGHT,
+        Some(page),
+        None,
+        Some(hinstance),
+        None,
+    )
+    .unwrap();
+    set_font(panel_hwnd);
+    DLG_PLUGIN_PANEL.with(|p| *p.borrow_mut() = Some(panel_hwnd));
+
+    if plugins.is_empty() {
+        set_plugin_panel(
+            "No plugins installed - copy plugin DLLs to the plugins folder and restart RustFrame.",
+        );
+    } else {
+        select_list_row(list, 0);
+    }
+}
+
+/// Show the panel of the newly selected plugin
+#[cfg(windows)]
+unsafe fn show_selected_plugin() {
+    let Some(list) = DLG_LIST_PLUGINS.with(|l| *l.borrow()) else {
+        return;
+    };
+    let row = SendMessageW(
+        list,
+        LVM_GETNEXTITEM,
+        Some(WPARAM(usize::MAX)),
+        Some(LPARAM(LVNI_SELECTED as isize)),
+    )
+    .0;
+    let Ok(row) = usize::try_from(row) else {
+        return;
+    };
+    let Some(plugin) = DLG_PLUGINS.with(|p| p.borrow().get(row).cloned()) else {
+        return;
+    };
+    let mut text = format!("{}\n{}", plugin.file, plugin.status);
+    if !plugin.panel.is_empty() {
+        text.push_str("\n\n");
+        text.push_str(&plugin.panel);
+    }
+    set_plugin_panel(&text);
+}
+
+/// Text below the plugin list
+#[cfg(windows)]
+unsafe fn set_plugin_panel(text: &str) {
+    if let Some(panel) = DLG_PLUGIN_PANEL.with(|p| *p.borrow()) {
+        let text = wide_string(text);
+        let _ = SetWindowTextW(panel, windows::core::PCWSTR(text.as_ptr()));
+    }
+}
+
+/// Check or uncheck a row of a list with checkboxes
+#[cfg(windows)]
+unsafe fn set_list_check(list: HWND, row: usize, checked: bool) {
+    // State image 1 = unchecked, 2 = checked
+    let image = if checked { 2 } else { 1 };
+    let item = LVITEMW {
+        state: LIST_VIEW_ITEM_STATE_FLAGS(image << 12),
+        stateMask: LVIS_STATEIMAGEMASK,
+        ..Default::default()
+    };
+    let _ = SendMessageW(
+        list,
+        LVM_SETITEMSTATE,
+        Some(WPARAM(row)),
+        Some(LPARAM(&item as *const _ as isize)),
+    );
+}
+
+/// Whether a row of a list with checkboxes is checked
+#[cfg(windows)]
+unsafe fn list_check(list: HWND, row: usize) -> bool {
+    let state = SendMessageW(
+        list,
+        LVM_GETITEMSTATE,
+        Some(WPARAM(row)),
+        Some(LPARAM(LVIS_STATEIMAGEMASK.0 as isize)),
+    )
+    .0 as u32;
+    (state >> 12) == 2
+}
+
+/// MIDI column text
+#[cfg(windows)]
+fn trigger_label(trigger: Option<MidiTrigger>) -> String {
+    trigger.map_or_else(|| "(none)".to_string(), |t| t.label())
+}
+
+#[cfg(windows)]
+unsafe fn set_midi_status(text: &str) {
+    if let Some(status) = DLG_MIDI_STATUS.with(|s| *s.borrow()) {
+        let text = wide_string(text);
+        let _ = SetWindowTextW(status, windows::core::PCWSTR(text.as_ptr()));
+    }
+}
+
+/// Row of the selected action in the MIDI list
+#[cfg(windows)]
+unsafe fn selected_midi_action() -> Option<usize> {
+    let list = DLG_LIST_MIDI.with(|l| *l.borrow())?;
+    let row = SendMessageW(
+        list,
+        LVM_GETNEXTITEM,
+        Some(WPARAM(usize::MAX)),
+        Some(LPARAM(LVNI_SELECTED as isize)),
+    )
+    .0;
+    usize::try_from(row).ok()
+}
+
+/// Device chosen in the dropdown (empty = the first device)
+#[cfg(windows)]
+unsafe fn selected_midi_device() -> String {
+    let Some(combo) = DLG_COMBO_MIDI_DEVICE.with(|c| *c.borrow()) else {
+        return String::new();
+    };
+    let index = SendMessageW(combo, CB_GETCURSEL, None, None).0;
+    if index <= 0 {
+        return String::new();
+    }
+    let mut buffer = [0u16; 256];
+    let len = SendMessageW(
+        combo,
+        CB_GETLBTEXT,
+        Some(WPARAM(index as usize)),
+        Some(LPARAM(buffer.as_mut_ptr() as isize)),
+    )
+    .0;
+    String::from_utf16_lossy(&buffer[..len.clamp(0, 255) as usize])
+}
+
+/// Bind a list row to a trigger (None = not mapped); an action already on
+/// that trigger loses it, so one pad never fires two actions
+#[cfg(windows)]
+unsafe fn set_midi_binding(row: usize, trigger: Option<MidiTrigger>) -> Vec<MidiAction> {
+    let mut unbound = Vec::new();
+    DLG_MIDI_BINDINGS.with(|b| {
+        let mut bindings = b.borrow_mut();
+        for (index, (action, other)) in bindings.iter_mut().enumerate() {
+            if index != row && trigger.is_some() && *other == trigger {
+                *other = None;
+                unbound.push(*action);
+            }
+        }
+        if let Some(binding) = bindings.get_mut(row) {
+            binding.1 = trigger;
+        }
+    });
+    if let Some(list) = DLG_LIST_MIDI.with(|l| *l.borrow()) {
+        let bindings = DLG_MIDI_BINDINGS.with(|b| b.borrow().clone());
+        for (index, (_, trigger)) in bindings.iter().enumerate() {
+            set_list_text(list, index, 1, &trigger_label(*trigger));
+        }
+    }
+    unbound
+}
+
+/// Open the chosen device and wait for a note / CC for the selected action
+#[cfg(windows)]
+unsafe fn start_midi_learn(dialog: HWND) {
+    let Some(row) = selected_midi_action() else {
+        return;
+    };
+    let action = DLG_MIDI_BINDINGS.with(|b| b.borrow()[row].0);
+    match MidiInput::open(&selected_midi_device()) {
+        Ok(input) => {
+            DLG_MIDI_LEARN.with(|l| *l.borrow_mut() = Some((input, row)));
+            SetTimer(
+                Some(dialog),
+                ID_MIDI_LEARN_TIMER,
+                midi_const::LEARN_POLL_MS,
+                None,
+            );
+            if let Some(button) = DLG_BTN_MIDI_LEARN.with(|b| *b.borrow()) {
+                let text = wide_string("Stop");
+                let _ = SetWindowTextW(button, windows::core::PCWSTR(text.as_ptr()));
+            }
+            set_midi_status(&format!(
+                "Waiting for MIDI input for \"{}\" - press a pad or move a control.",
+                action.label()
+            ));
+        }
+        Err(e) => set_midi_status(&format!("⚠ {:#}", e)),
+    }
+}
+
+/// Leave learn mode and release the device
+#[cfg(windows)]
+unsafe fn stop_midi_learn(dialog: HWND) {
+    if DLG_MIDI_LEARN.with(|l| l.borrow_mut().take()).is_none() {
+        return;
+    }
+    let _ = KillTimer(Some(dialog), ID_MIDI_LEARN_TIMER);
+    if let Some(button) = DLG_BTN_MIDI_LEARN.with(|b| *b.borrow()) {
+        let text = wide_string("Learn");
+        let _ = SetWindowTextW(button, windows::core::PCWSTR(text.as_ptr()));
+    }
+}
+
+/// Learn mode timer: bind the first pad pressed / control moved
+#[cfg(windows)]
+unsafe fn poll_midi_learn(dialog: HWND) {
+    let learned = DLG_MIDI_LEARN.with(|l| {
+        let learn = l.borrow();
+        let (input, row) = learn.as_ref()?;
+        // Note offs are not a new press; any CC movement counts
+        std::iter::from_fn(|| input.poll())
+            .find(|e| e.pressed || matches!(e.trigger, MidiTrigger::Control(_)))
+            .map(|e| (e.trigger, *row))
+    });
+    let Some((trigger, row)) = learned else {
+        return;
+    };
+    stop_midi_learn(dialog);
+
+    let unbound = set_midi_binding(row, Some(trigger));
+    let action = DLG_MIDI_BINDINGS.with(|b| b.borrow()[row].0);
+    let mut text = format!("{} → {}", trigger.label(), action.label());
+    if !unbound.is_empty() {
+        let labels: Vec<String> = unbound.iter().map(|a| a.label()).collect();
+        text += &format!(" (removed from: {})", labels.join(", "));
+    }
+    if action == MidiAction::OverlayOpacity && matches!(trigger, MidiTrigger::Note(_)) {
+        text += "\n⚠ Overlay opacity follows a fader or knob - a note only sets it by velocity.";
+    }
+    set_midi_status(&text);
+    record_edit();
+}
+
+/// Forwards control notifications of a tab page to the dialog
+#[cfg(windows)]
+unsafe extern "system" fn settings_page_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if let Some(result) = control_colors(msg, wparam) {
+        return result;
+    }
+    match msg {
+        WM_COMMAND | WM_NOTIFY => match GetParent(hwnd) {
+            Ok(dialog) => SendMessageW(dialog, msg, Some(wparam), Some(lparam)),
+            Err(_) => LRESULT(0),
+        },
+        _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
+
+/// Show one tab page and hide the others
+#[cfg(windows)]
+unsafe fn show_page(index: usize) {
+    DLG_PAGES.with(|pages| {
+        for (i, &page) in pages.borrow().iter().enumerate() {
+            let _ = ShowWindow(page, if i == index { SW_SHOW } else { SW_HIDE });
+        }
+    });
+    if let Some(tab) = DLG_TAB.with(|t| *t.borrow()) {
+        let _ = SendMessageW(tab, TCM_SETCURSEL, Some(WPARAM(index)), None);
+    }
+}
+
+/// Text of a list cell
+#[cfg(windows)]
+unsafe fn set_list_text(list: HWND, row: usize, column: i32, text: &str) {
+    let mut text = wide_string(text);
+    let item = LVITEMW {
+        iSubItem: column,
+        pszText: windows::core::PWSTR(text.as_mut_ptr()),
+        ..Default::default()
+    };
+    let _ = SendMessageW(
+        list,
+        LVM_SETITEMTEXTW,
+        Some(WPARAM(row)),
+        Some(LPARAM(&item as *const _ as isize)),
+    );
+}
+
+#[cfg(windows)]
+unsafe fn select_list_row(list: HWND, row: usize) {
+    let item = LVITEMW {
+        state: LVIS_SELECTED,
+        stateMask: LVIS_SELECTED,
+        ..Default::default()
+    };
+    let _ = SendMessageW(
+        list,
+        LVM_SETITEMSTATE,
+        Some(WPARAM(row)),
+        Some(LPARAM(&item as *const _ as isize)),
+    );
+}
+
+/// Row of the selected action in the shortcut list
+#[cfg(windows)]
+unsafe fn selected_shortcut() -> Option<usize> {
+    let list = DLG_LIST_SHORTCUTS.with(|l| *l.borrow())?;
+    let row = SendMessageW(
+        list,
+        LVM_GETNEXTITEM,
+        Some(WPARAM(usize::MAX)),
+        Some(LPARAM(LVNI_SELECTED as isize)),
+    )
+    .0;
+    usize::try_from(row).ok()
+}
+
+/// Fill the hotkey control with the shortcut of the newly selected action
+#[cfg(windows)]
+unsafe fn show_selected_shortcut() {
+    let Some(index) = selected_shortcut() else {
+        return;
+    };
+    let hotkey = DLG_SHORTCUTS.with(|s| s.borrow()[index].1);
+    if let Some(control) = DLG_HOTKEY_SHORTCUT.with(|h| *h.borrow()) {
+        let _ = SendMessageW(
+            control,
+            HKM_SETHOTKEY,
+            Some(WPARAM(to_hotkey_control(hotkey))),
+            None,
+        );
+    }
+    update_shortcut_status();
+}
+
+/// Bind the selected action to a new shortcut (None = unbound)
+/// `fill_control`: also show it in the hotkey control (Default / Unbind buttons)
+#[cfg(windows)]
+unsafe fn set_selected_shortcut(hotkey: Option<HotKey>, fill_control: bool) {
+    let Some(index) = selected_shortcut() else {
+        return;
+    };
+    DLG_SHORTCUTS.with(|s| s.borrow_mut()[index].1 = hotkey);
+    if let Some(list) = DLG_LIST_SHORTCUTS.with(|l| *l.borrow()) {
+        set_list_text(list, index, 1, &shortcut_label(hotkey));
+    }
+    if fill_control {
+        show_selected_shortcut();
+    } else {
+        update_shortcut_status();
+    }
+}
+
+/// Show conflicts with other actions and combinations taken by Windows or
+/// another application for the selected action
+#[cfg(windows)]
+unsafe fn update_shortcut_status() {
+    let Some(status) = DLG_SHORTCUT_STATUS.with(|s| *s.borrow()) else {
+        return;
+    };
+    let shortcuts = DLG_SHORTCUTS.with(|s| s.borrow().clone());
+    let text = match selected_shortcut().and_then(|i| shortcuts.get(i)) {
+        Some(&(action, Some(hotkey))) => {
+            let others: Vec<String> = shortcuts
+                .iter()
+                .filter(|(other, h)| *other != action && *h == Some(hotkey))
+                .map(|(other, _)| other.label())
+                .collect();
+            if !others.is_empty() {
+                format!(
+                    "⚠ {} is also used for: {}",
+                    hotkeys::format_hotkey(hotkey),
+                    others.join(", ")
+                )
+            } else if taken_by_other_app(hotkey) {
+                format!(
+                    "⚠ {} is already taken by Windows or another application - it will not work while that one holds it",
+                    hotkeys::format_hotkey(hotkey)
+                )
+            } else {
+                String::new()
+            }
+        }
+        Some((_, None)) => "This action has no shortcut.".to_string(),
+        None => String::new(),
+    };
+    let text = wide_string(&text);
+    let _ = SetWindowTextW(status, windows::core::PCWSTR(text.as_ptr()));
+}
+
+/// Warning text if two actions share a shortcut
+#[cfg(windows)]
+fn shortcut_conflict_message() -> Option<String> {
+    let conflicts = DLG_SHORTCUTS.with(|s| hotkeys::conflicts(&s.borrow()));
+    let lines: Vec<String> = DLG_SHORTCUTS.with(|s| {
+        let shortcuts = s.borrow();
+        conflicts
+            .iter()
+            .map(|(a, b)| {
+                let hotkey = shortcuts.iter().find(|(action, _)| action == a)?.1?;
+                Some(format!(
+                    "{}: {} and {}",
+                    hotkeys::format_hotkey(hotkey),
+                    a.label(),
+                    b.label()
+                ))
+            })
+            .collect::<Option<Vec<_>>>()
+            .unwrap_or_default()
+    });
+    if lines.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "These shortcuts are used for more than one action:\n\n{}\n\nChange or unbind them before saving.",
+        lines.join("\n")
+    ))
+}
+
+/// Whether another application (or Windows itself) has registered the combination
+/// Probed by registering it for a moment - RustFrame's own shortcuts are skipped
+#[cfg(windows)]
+unsafe fn taken_by_other_app(hotkey: HotKey) -> bool {
+    if DLG_REGISTERED_SHORTCUTS.with(|r| r.borrow().contains(&hotkey)) {
+        return false;
+    }
+    let Some(vk) = hotkeys::vk_from_code(hotkey.key) else {
+        return false;
+    };
+    let mut modifiers = MOD_NOREPEAT;
+    for (modifier, flag) in [
+        (Modifiers::CONTROL, MOD_CONTROL),
+        (Modifiers::ALT, MOD_ALT),
+        (Modifiers::SHIFT, MOD_SHIFT),
+        (Modifiers::SUPER, MOD_WIN),
+    ] {
+        if hotkey.mods.contains(modifier) {
+            modifiers |= flag;
+        }
+    }
+    let dialog = DIALOG_HWND.with(|h| *h.borrow());
+    match RegisterHotKey(
+        dialog,
+        ID_SHORTCUT_PROBE,
+        HOT_KEY_MODIFIERS(modifiers.0),
+        vk as u32,
+    ) {
+        Ok(()) => {
+            let _ = UnregisterHotKey(dialog, ID_SHORTCUT_PROBE);
+            false
+        }
+        Err(_) => true,
+    }
+}
+
+/// Shortcut column text
+#[cfg(windows)]
+fn shortcut_label(hotkey: Option<HotKey>) -> String {
+    hotkey.map_or_else(|| "(none)".to_string(), hotkeys::format_hotkey)
+}
+
+/// HKM_SETHOTKEY value: virtual-key code in the low byte, HOTKEYF_* flags above
+#[cfg(windows)]
+fn to_hotkey_control(hotkey: Option<HotKey>) -> usize {
+    let Some(hotkey) = hotkey else {
+        return 0;
+    };
+    let Some(vk) = hotkeys::vk_from_code(hotkey.key) else {
+        return 0;
+    };
+    let mut flags = 0;
+    for (modifier, flag) in [
+        (Modifiers::CONTROL, HOTKEYF_CONTROL),
+        (Modifiers::ALT, HOTKEYF_ALT),
+        (Modifiers::SHIFT, HOTKEYF_SHIFT),
+    ] {
+        if hotkey.mods.contains(modifier) {
+            flags |= flag;
+        }
+    }
+    vk as usize | (flags as usize) << 8
+}
+
+/// Shortcut from an HKM_GETHOTKEY value (None if empty or an unsupported key)
+#[cfg(windows)]
+fn from_hotkey_control(value: usize) -> Option<HotKey> {
+    let code = hotkeys::code_from_vk((value & 0xFF) as u16)?;
+    let flags = ((value >> 8) & 0xFF) as u32;
+    let mut modifiers = Modifiers::empty();
+    for (flag, modifier) in [
+        (HOTKEYF_CONTROL, Modifiers::CONTROL),
+        (HOTKEYF_ALT, Modifiers::ALT),
+        (HOTKEYF_SHIFT, Modifiers::SHIFT),
+    ] {
+        if flags & flag != 0 {
+            modifiers |= modifier;
+        }
+    }
+    Some(HotKey::new(Some(modifiers), code))
+}
+
+#[cfg(windows)]
 unsafe extern "system" fn settings_dialog_proc(
     hwnd: HWND,
     msg: u32,
     wparam: WPARAM,
     lparam: LPARAM,
 ) -> LRESULT {
+    if let Some(result) = control_colors(msg, wparam) {
+        return result;
+    }
     match msg {
         WM_COMMAND => {
             let control_id = (wparam.0 & 0xFFFF) as i32;
+            let notification = ((wparam.0 >> 16) & 0xFFFF) as u32;
+            DLG_PREVIEW_DIRTY.with(|d| *d.borrow_mut() = true);
 
             match control_id {
                 ID_BTN_SAVE => {
+                    // Two actions on the same shortcut - resolve that first
+                    if let Some(message) = shortcut_conflict_message() {
+                        show_page(PAGE_SHORTCUTS);
+                        let text = wide_string(&message);
+                        let caption = wide_string("RustFrame Settings");
+                        let _ = MessageBoxW(
+                            Some(hwnd),
+                            windows::core::PCWSTR(text.as_ptr()),
+                            windows::core::PCWSTR(caption.as_ptr()),
+                            MB_OK | MB_ICONWARNING,
+                        );
+                        return LRESULT(0);
+                    }
                     save_settings_from_controls();
                     SETTINGS_CHANGED.with(|c| *c.borrow_mut() = true);
                     let _ = DestroyWindow(hwnd);
@@ -562,8 +3545,95 @@ unsafe extern "system" fn settings_dialog_proc(
                     SETTINGS_CHANGED.with(|c| *c.borrow_mut() = false);
                     let _ = DestroyWindow(hwnd);
                 }
+                ID_BTN_UNDO => step_history(true),
+                ID_BTN_REDO => step_history(false),
+                ID_BTN_RESET_PAGE => reset_page(current_page()),
+                // A new combination was pressed in the hotkey control (ignore
+                // the control being filled in when an action is selected)
+                ID_HOTKEY_SHORTCUT if notification == EN_CHANGE => {
+                    let control = DLG_HOTKEY_SHORTCUT.with(|h| *h.borrow());
+                    if control.is_some() && control == Some(GetFocus()) {
+                        let value = SendMessageW(GetFocus(), HKM_GETHOTKEY, None, None).0;
+                        set_selected_shortcut(from_hotkey_control(value as usize), false);
+                    }
+                }
+                ID_BTN_SHORTCUT_DEFAULT => {
+                    if let Some(index) = selected_shortcut() {
+                        let action = DLG_SHORTCUTS.with(|s| s.borrow()[index].0);
+                        set_selected_shortcut(Some(action.default_hotkey()), true);
+                    }
+                }
+                ID_BTN_SHORTCUT_CLEAR => set_selected_shortcut(None, true),
+                ID_BTN_MIDI_LEARN => {
+                    if DLG_MIDI_LEARN.with(|l| l.borrow().is_some()) {
+                        stop_midi_learn(hwnd);
+                        set_midi_status("");
+                    } else {
+                        start_midi_learn(hwnd);
+                    }
+                }
+                ID_BTN_MIDI_CLEAR => {
+                    stop_midi_learn(hwnd);
+                    if let Some(row) = selected_midi_action() {
+                        set_midi_binding(row, None);
+                    }
+                    set_midi_status("");
+                }
+                ID_BTN_PLUGINS_FOLDER => {
+                    if let Some(dir) = plugin::dir() {
+                        let _ = std::fs::create_dir_all(&dir);
+                        crate::screenshot::open(&dir);
+                    }
+                }
+                // Another device is learned from (it is opened when Learn is clicked)
+                ID_COMBO_MIDI_DEVICE if notification == CBN_SELCHANGE => {
+                    stop_midi_learn(hwnd);
+                    set_midi_status("");
+                }
                 _ => {}
             }
+
+            // Anything that may have changed a value becomes an undo step
+            // (text fields when they lose the focus, not on every key)
+            let history_button = matches!(
+                control_id,
+                ID_BTN_SAVE | ID_BTN_CANCEL | ID_BTN_UNDO | ID_BTN_REDO | ID_BTN_RESET_PAGE
+            );
+            let edit = matches!(notification, BN_CLICKED | EN_KILLFOCUS | CBN_SELCHANGE)
+                || (control_id == ID_HOTKEY_SHORTCUT && notification == EN_CHANGE);
+            if edit && !history_button {
+                record_edit();
+            }
+            LRESULT(0)
+        }
+        WM_NOTIFY => {
+            let header = &*(lparam.0 as *const NMHDR);
+            if header.code == TCN_SELCHANGE {
+                let tab = SendMessageW(header.hwndFrom, TCM_GETCURSEL, None, None).0;
+                show_page(tab.max(0) as usize);
+            } else if header.code == LVN_ITEMCHANGED && header.idFrom == ID_LIST_SHORTCUTS as usize
+            {
+                let change = &*(lparam.0 as *const NMLISTVIEW);
+                let selected = LVIS_SELECTED.0;
+                if change.uNewState & selected != 0 && change.uOldState & selected == 0 {
+                    show_selected_shortcut();
+                }
+            } else if header.code == LVN_ITEMCHANGED && header.idFrom == ID_LIST_PLUGINS as usize {
+                let change = &*(lparam.0 as *const NMLISTVIEW);
+                let selected = LVIS_SELECTED.0;
+                if change.uNewState & selected != 0 && change.uOldState & selected == 0 {
+                    show_selected_plugin();
+                }
+                // A plugin was checked or unchecked
+                let check = LVIS_STATEIMAGEMASK.0;
+                if (change.uNewState ^ change.uOldState) & check != 0 {
+                    record_edit();
+                }
+            }
+            LRESULT(0)
+        }
+        WM_TIMER if wparam.0 == ID_MIDI_LEARN_TIMER => {
+            poll_midi_learn(hwnd);
             LRESULT(0)
         }
         WM_CLOSE => {
@@ -572,6 +3642,9 @@ unsafe extern "system" fn settings_dialog_proc(
             LRESULT(0)
         }
         WM_DESTROY => {
+            // Release the device so the app can open it again
+            stop_midi_learn(hwnd);
+            clear_control_names();
             PostQuitMessage(0);
             LRESULT(0)
         }
@@ -581,70 +3654,682 @@ unsafe extern "system" fn settings_dialog_proc(
 
 #[cfg(windows)]
 unsafe fn save_settings_from_controls() {
-    let dev_mode = DIALOG_DEV_MODE.with(|d| *d.borrow());
-
     DIALOG_SETTINGS.with(|settings_cell| {
         let mut settings_opt = settings_cell.borrow_mut();
         if let Some(ref mut settings) = *settings_opt {
-            // Read checkbox states
-            DLG_CHECK_CURSOR.with(|c| {
-                if let Some(h) = *c.borrow() {
-                    let state = SendMessageW(h, BM_GETCHECK, Some(WPARAM(0)), Some(LPARAM(0))).0;
-                    settings.show_cursor = state == BST_CHECKED.0 as isize;
+            read_controls(settings);
+            // An emptied token is replaced, so the server never accepts everyone
+            if settings.stream_deck_token.is_empty() {
+                settings.stream_deck_token = stream_deck::new_token();
+            }
+
+            info!(
+                "Settings saved: cursor={}, border={}, width={}, prod_mode={}, countdown={}s, hold={}s, transition={}ms, fps={}, image={:?}, slide={}s, redacted_apps={:?}, hide_notifications={}, gamepad={}, stream_deck={}, osc={}, minimize_to_tray={}, close_to_tray={}, auto_start={}, start_minimized={}, shell_menu={}, midi={} ({:?}, {:?}), filters={:?}, hotkeys={:?}, enabled_plugins={:?}",
+                settings.capture.show_cursor,
+                settings.capture.show_border,
+                settings.capture.border_width,
+                settings.capture.exclude_from_capture,
+                settings.countdown_seconds,
+                settings.hold_seconds,
+                settings.transition_ms,
+                settings.capture.target_fps,
+                settings.image_path,
+                settings.slide_seconds,
+                settings.redacted_apps,
+                settings.hide_notifications,
+                settings.gamepad,
+                settings.stream_deck,
+                settings.osc,
+                settings.minimize_to_tray,
+                settings.close_to_tray,
+                settings.auto_start,
+                settings.start_minimized,
+                settings.shell_menu,
+                settings.midi_enabled,
+                settings.midi_device,
+                settings.midi_mappings,
+                settings.filters,
+                settings.hotkeys,
+                settings.enabled_plugins
+            );
+        }
+    });
+}
+
+/// Read the values of the controls into `settings`
+/// (fields without a control keep their value)
+#[cfg(windows)]
+unsafe fn read_controls(settings: &mut AppSettings) {
+    let dev_mode = DIALOG_DEV_MODE.with(|d| *d.borrow());
+
+    // Read checkbox states
+    DLG_CHECK_CURSOR.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let state = SendMessageW(h, BM_GETCHECK, Some(WPARAM(0)), Some(LPARAM(0))).0;
+            settings.capture.show_cursor = state == BST_CHECKED.0 as isize;
+        }
+    });
+
+    DLG_CHECK_BORDER.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let state = SendMessageW(h, BM_GETCHECK, Some(WPARAM(0)), Some(LPARAM(0))).0;
+            settings.capture.show_border = state == BST_CHECKED.0 as isize;
+        }
+    });
+
+    // Production mode checkbox only exists in dev mode
+    if dev_mode {
+        DLG_CHECK_PROD.with(|c| {
+            if let Some(h) = *c.borrow() {
+                let state = SendMessageW(h, BM_GETCHECK, Some(WPARAM(0)), Some(LPARAM(0))).0;
+                settings.capture.exclude_from_capture = state == BST_CHECKED.0 as isize;
+            }
+        });
+    }
+
+    // Read border width
+    DLG_EDIT_BORDER_WIDTH.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let mut buffer = [0u16; 16];
+            let len = GetWindowTextW(h, &mut buffer);
+            if len > 0 {
+                let text: String = String::from_utf16_lossy(&buffer[..len as usize]);
+                if let Ok(width) = text.parse::<u32>() {
+                    settings.capture.border_width = width.clamp(
+                        capture_const::MIN_BORDER_WIDTH,
+                        capture_const::MAX_BORDER_WIDTH,
+                    );
                 }
-            });
+            }
+        }
+    });
 
-            DLG_CHECK_BORDER.with(|c| {
-                if let Some(h) = *c.borrow() {
-                    let state = SendMessageW(h, BM_GETCHECK, Some(WPARAM(0)), Some(LPARAM(0))).0;
-                    settings.show_border = state == BST_CHECKED.0 as isize;
+    // Read countdown
+    DLG_EDIT_COUNTDOWN.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let mut buffer = [0u16; 16];
+            let len = GetWindowTextW(h, &mut buffer);
+            if len > 0 {
+                let text: String = String::from_utf16_lossy(&buffer[..len as usize]);
+                if let Ok(seconds) = text.parse::<u32>() {
+                    settings.countdown_seconds = seconds.min(capture_const::MAX_COUNTDOWN_SECONDS);
                 }
-            });
-
-            // Production mode checkbox only exists in dev mode
-            if dev_mode {
-                DLG_CHECK_PROD.with(|c| {
-                    if let Some(h) = *c.borrow() {
-                        let state =
-                            SendMessageW(h, BM_GETCHECK, Some(WPARAM(0)), Some(LPARAM(0))).0;
-                        settings.exclude_from_capture = state == BST_CHECKED.0 as isize;
-                    }
-                });
             }
+        }
+    });
 
-            // Read border width
-            DLG_EDIT_BORDER_WIDTH.with(|c| {
-                if let Some(h) = *c.borrow() {
-                    let mut buffer = [0u16; 16];
-                    let len = GetWindowTextW(h, &mut buffer);
-                    if len > 0 {
-                        let text: String = String::from_utf16_lossy(&buffer[..len as usize]);
-                        if let Ok(width) = text.parse::<u32>() {
-                            settings.border_width = width.clamp(
-                                capture_const::MIN_BORDER_WIDTH,
-                                capture_const::MAX_BORDER_WIDTH,
-                            );
-                        }
-                    }
+    DLG_CHECK_COUNTDOWN_FULLSCREEN.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let state = SendMessageW(h, BM_GETCHECK, Some(WPARAM(0)), Some(LPARAM(0))).0;
+            settings.countdown_fullscreen = state == BST_CHECKED.0 as isize;
+        }
+    });
+
+    // Read hold grace period
+    DLG_EDIT_HOLD.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let mut buffer = [0u16; 16];
+            let len = GetWindowTextW(h, &mut buffer);
+            if len > 0 {
+                let text: String = String::from_utf16_lossy(&buffer[..len as usize]);
+                if let Ok(seconds) = text.parse::<u32>() {
+                    settings.hold_seconds = seconds.min(capture_const::MAX_HOLD_SECONDS);
+                }
+            }
+        }
+    });
+
+    // Read transition duration
+    DLG_EDIT_TRANSITION.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let mut buffer = [0u16; 16];
+            let len = GetWindowTextW(h, &mut buffer);
+            if len > 0 {
+                let text: String = String::from_utf16_lossy(&buffer[..len as usize]);
+                if let Ok(ms) = text.parse::<u32>() {
+                    settings.transition_ms = ms.min(capture_const::MAX_TRANSITION_MS);
                 }
-            });
+            }
+        }
+    });
 
-            info!(
-                "Settings saved: cursor={}, border={}, width={}, prod_mode={}",
-                settings.show_cursor,
-                settings.show_border,
-                settings.border_width,
-                settings.exclude_from_capture
-            );
+    // Read target frame rate
+    DLG_EDIT_TARGET_FPS.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let mut buffer = [0u16; 16];
+            let len = GetWindowTextW(h, &mut buffer);
+            if len > 0 {
+                let text: String = String::from_utf16_lossy(&buffer[..len as usize]);
+                if let Ok(fps) = text.parse::<u32>() {
+                    settings.capture.target_fps = fps.min(capture_const::MAX_TARGET_FPS);
+                }
+            }
+        }
+    });
+
+    // Read the image source path (an empty field means no image)
+    DLG_EDIT_IMAGE_PATH.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let mut buffer = [0u16; 1024];
+            let len = GetWindowTextW(h, &mut buffer);
+            let text: String = String::from_utf16_lossy(&buffer[..len.max(0) as usize]);
+            settings.image_path = text.trim().to_string();
+        }
+    });
+
+    // Read slide interval
+    DLG_EDIT_SLIDE_SECONDS.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let mut buffer = [0u16; 16];
+            let len = GetWindowTextW(h, &mut buffer);
+            if len > 0 {
+                let text: String = String::from_utf16_lossy(&buffer[..len as usize]);
+                if let Ok(seconds) = text.parse::<u32>() {
+                    settings.slide_seconds = seconds.min(capture_const::MAX_SLIDE_SECONDS);
+                }
+            }
+        }
+    });
+
+    // Read redacted applications (comma-separated; an empty field disables redaction)
+    DLG_EDIT_REDACTED_APPS.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let mut buffer = [0u16; 1024];
+            let len = GetWindowTextW(h, &mut buffer);
+            let text: String = String::from_utf16_lossy(&buffer[..len.max(0) as usize]);
+            settings.redacted_apps = text
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+        }
+    });
+
+    DLG_CHECK_HIDE_NOTIFICATIONS.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let state = SendMessageW(h, BM_GETCHECK, Some(WPARAM(0)), Some(LPARAM(0))).0;
+            settings.hide_notifications = state == BST_CHECKED.0 as isize;
+        }
+    });
+
+    DLG_CHECK_GAMEPAD.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let state = SendMessageW(h, BM_GETCHECK, Some(WPARAM(0)), Some(LPARAM(0))).0;
+            settings.gamepad = state == BST_CHECKED.0 as isize;
+        }
+    });
+
+    DLG_CHECK_STREAM_DECK.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let state = SendMessageW(h, BM_GETCHECK, Some(WPARAM(0)), Some(LPARAM(0))).0;
+            settings.stream_deck = state == BST_CHECKED.0 as isize;
+        }
+    });
+
+    DLG_CHECK_OSC.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let state = SendMessageW(h, BM_GETCHECK, Some(WPARAM(0)), Some(LPARAM(0))).0;
+            settings.osc = state == BST_CHECKED.0 as isize;
+        }
+    });
+
+    // An emptied token is replaced when saving (see save_settings_from_controls)
+    DLG_EDIT_REMOTE_TOKEN.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let mut buffer = [0u16; 256];
+            let len = GetWindowTextW(h, &mut buffer);
+            let text = String::from_utf16_lossy(&buffer[..len.max(0) as usize]);
+            settings.stream_deck_token = text.trim().to_string();
+        }
+    });
+
+    DLG_CHECK_MINIMIZE_TO_TRAY.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let state = SendMessageW(h, BM_GETCHECK, Some(WPARAM(0)), Some(LPARAM(0))).0;
+            settings.minimize_to_tray = state == BST_CHECKED.0 as isize;
+        }
+    });
+
+    DLG_CHECK_CLOSE_TO_TRAY.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let state = SendMessageW(h, BM_GETCHECK, Some(WPARAM(0)), Some(LPARAM(0))).0;
+            settings.close_to_tray = state == BST_CHECKED.0 as isize;
+        }
+    });
+
+    DLG_CHECK_AUTO_START.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let state = SendMessageW(h, BM_GETCHECK, Some(WPARAM(0)), Some(LPARAM(0))).0;
+            settings.auto_start = state == BST_CHECKED.0 as isize;
+        }
+    });
+
+    DLG_CHECK_START_MINIMIZED.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let state = SendMessageW(h, BM_GETCHECK, Some(WPARAM(0)), Some(LPARAM(0))).0;
+            settings.start_minimized = state == BST_CHECKED.0 as isize;
+        }
+    });
+
+    DLG_CHECK_SHELL_MENU.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let state = SendMessageW(h, BM_GETCHECK, Some(WPARAM(0)), Some(LPARAM(0))).0;
+            settings.shell_menu = state == BST_CHECKED.0 as isize;
+        }
+    });
+
+    // Read the filter chain (comma-separated names; unknown names are
+    // ignored by FilterChain, left-out filters are disabled)
+    DLG_EDIT_FILTERS.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let mut buffer = [0u16; 1024];
+            let len = GetWindowTextW(h, &mut buffer);
+            let text: String = String::from_utf16_lossy(&buffer[..len.max(0) as usize]);
+            settings.filters = text
+                .split(',')
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| !s.is_empty())
+                .collect();
+        }
+    });
+
+    // Shortcuts (only the ones that differ from the defaults are stored)
+    settings.hotkeys = DLG_SHORTCUTS.with(|s| hotkeys::overrides(&s.borrow()));
+
+    // MIDI control (only mapped actions are stored)
+    DLG_CHECK_MIDI.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let state = SendMessageW(h, BM_GETCHECK, Some(WPARAM(0)), Some(LPARAM(0))).0;
+            settings.midi_enabled = state == BST_CHECKED.0 as isize;
+        }
+    });
+    settings.midi_device = selected_midi_device();
+    settings.midi_mappings = DLG_MIDI_BINDINGS.with(|b| midi::mappings(&b.borrow()));
+
+    // Capture thread (Advanced)
+    if let Some(combo) = DLG_COMBO_THREAD_PRIORITY.with(|c| *c.borrow()) {
+        let index = SendMessageW(combo, CB_GETCURSEL, None, None).0;
+        settings.thread_priority = ThreadPriority::ALL
+            .get(index.max(0) as usize)
+            .copied()
+            .unwrap_or_default();
+    }
+    DLG_EDIT_CPU_CORES.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let mut buffer = [0u16; 256];
+            let len = GetWindowTextW(h, &mut buffer);
+            let text = String::from_utf16_lossy(&buffer[..len.max(0) as usize]);
+            settings.cpu_cores = thread_priority::parse_cores(&text);
+        }
+    });
+    if let Some(combo) = DLG_COMBO_ESC_ACTION.with(|c| *c.borrow()) {
+        let index = SendMessageW(combo, CB_GETCURSEL, None, None).0;
+        settings.esc_action = EscAction::ALL
+            .get(index.max(0) as usize)
+            .copied()
+            .unwrap_or_default();
+    }
+    if let Some(combo) = DLG_COMBO_THEME.with(|c| *c.borrow()) {
+        let index = SendMessageW(combo, CB_GETCURSEL, None, None).0;
+        settings.theme = ThemeMode::ALL
+            .get(index.max(0) as usize)
+            .copied()
+            .unwrap_or_default();
+    }
+    DLG_EDIT_ACCENT.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let mut buffer = [0u16; 64];
+            let len = GetWindowTextW(h, &mut buffer);
+            let text = String::from_utf16_lossy(&buffer[..len.max(0) as usize]);
+            // Invalid colors are kept out of settings.json
+            settings.accent_color = match theme::parse_accent(&text) {
+                Some(rgb) => format!("#{:06X}", rgb),
+                None => String::new(),
+            };
+        }
+    });
+    DLG_CHECK_GAME_CAPTURE.with(|c| {
+        if let Some(h) = *c.borrow() {
+            let state = SendMessageW(h, BM_GETCHECK, Some(WPARAM(0)), Some(LPARAM(0))).0;
+            settings.capture.game_capture = state == BST_CHECKED.0 as isize;
+        }
+    });
+    if let Some(combo) = DLG_COMBO_TOOLBAR.with(|c| *c.borrow()) {
+        let index = SendMessageW(combo, CB_GETCURSEL, None, None).0;
+        settings.toolbar = ToolbarPlacement::ALL
+            .get(index.max(0) as usize)
+            .copied()
+            .unwrap_or_default();
+    }
+
+    // Plugins turned on (by DLL file name)
+    if let Some(list) = DLG_LIST_PLUGINS.with(|l| *l.borrow()) {
+        settings.enabled_plugins = DLG_PLUGINS.with(|p| {
+            p.borrow()
+                .iter()
+                .enumerate()
+                .filter(|&(row, _)| list_check(list, row))
+                .map(|(_, plugin)| plugin.file.clone())
+                .collect()
+        });
+    }
+}
+
+/// Put the values of `settings` into the controls (the reverse of read_controls)
+#[cfg(windows)]
+unsafe fn write_controls(settings: &AppSettings) {
+    unsafe fn set_check(cell: &'static std::thread::LocalKey<RefCell<Option<HWND>>>, on: bool) {
+        if let Some(h) = cell.with(|c| *c.borrow()) {
+            let state = if on { BST_CHECKED } else { BST_UNCHECKED };
+            let _ = SendMessageW(h, BM_SETCHECK, Some(WPARAM(state.0 as usize)), None);
+        }
+    }
+    unsafe fn set_text(cell: &'static std::thread::LocalKey<RefCell<Option<HWND>>>, text: &str) {
+        if let Some(h) = cell.with(|c| *c.borrow()) {
+            let text = wide_string(text);
+            let _ = SetWindowTextW(h, windows::core::PCWSTR(text.as_ptr()));
+        }
+    }
+
+    // The controls changing below must not be recorded as edits of their own
+    DLG_HISTORY_PAUSED.with(|p| *p.borrow_mut() = true);
+
+    set_check(&DLG_CHECK_CURSOR, settings.capture.show_cursor);
+    set_check(&DLG_CHECK_BORDER, settings.capture.show_border);
+    set_check(&DLG_CHECK_PROD, settings.capture.exclude_from_capture);
+    set_text(
+        &DLG_EDIT_BORDER_WIDTH,
+        &settings.capture.border_width.to_string(),
+    );
+    set_text(&DLG_EDIT_COUNTDOWN, &settings.countdown_seconds.to_string());
+    set_check(
+        &DLG_CHECK_COUNTDOWN_FULLSCREEN,
+        settings.countdown_fullscreen,
+    );
+    set_text(&DLG_EDIT_HOLD, &settings.hold_seconds.to_string());
+    set_text(&DLG_EDIT_TRANSITION, &settings.transition_ms.to_string());
+    set_text(
+        &DLG_EDIT_TARGET_FPS,
+        &settings.capture.target_fps.to_string(),
+    );
+    set_text(&DLG_EDIT_IMAGE_PATH, &settings.image_path);
+    set_text(&DLG_EDIT_SLIDE_SECONDS, &settings.slide_seconds.to_string());
+    set_text(&DLG_EDIT_REDACTED_APPS, &settings.redacted_apps.join(", "));
+    set_check(&DLG_CHECK_HIDE_NOTIFICATIONS, settings.hide_notifications);
+    set_check(&DLG_CHECK_GAMEPAD, settings.gamepad);
+    set_check(&DLG_CHECK_STREAM_DECK, settings.stream_deck);
+    set_check(&DLG_CHECK_OSC, settings.osc);
+    set_text(&DLG_EDIT_REMOTE_TOKEN, &settings.stream_deck_token);
+    set_check(&DLG_CHECK_MINIMIZE_TO_TRAY, settings.minimize_to_tray);
+    set_check(&DLG_CHECK_CLOSE_TO_TRAY, settings.close_to_tray);
+    set_check(&DLG_CHECK_AUTO_START, settings.auto_start);
+    set_check(&DLG_CHECK_START_MINIMIZED, settings.start_minimized);
+    set_check(&DLG_CHECK_SHELL_MENU, settings.shell_menu);
+    set_text(&DLG_EDIT_FILTERS, &settings.filters.join(", "));
+    if let Some(combo) = DLG_COMBO_THREAD_PRIORITY.with(|c| *c.borrow()) {
+        let index = ThreadPriority::ALL
+            .iter()
+            .position(|&p| p == settings.thread_priority)
+            .unwrap_or(0);
+        let _ = SendMessageW(combo, CB_SETCURSEL, Some(WPARAM(index)), None);
+    }
+    set_check(&DLG_CHECK_GAME_CAPTURE, settings.capture.game_capture);
+    set_text(&DLG_EDIT_CPU_CORES, &cores_text(&settings.cpu_cores));
+    if let Some(combo) = DLG_COMBO_ESC_ACTION.with(|c| *c.borrow()) {
+        let index = EscAction::ALL
+            .iter()
+            .position(|&a| a == settings.esc_action)
+            .unwrap_or(0);
+        let _ = SendMessageW(combo, CB_SETCURSEL, Some(WPARAM(index)), None);
+    }
+    if let Some(combo) = DLG_COMBO_THEME.with(|c| *c.borrow()) {
+        let index = ThemeMode::ALL
+            .iter()
+            .position(|&m| m == settings.theme)
+            .unwrap_or(0);
+        let _ = SendMessageW(combo, CB_SETCURSEL, Some(WPARAM(index)), None);
+    }
+    set_text(&DLG_EDIT_ACCENT, &settings.accent_color);
+    if let Some(combo) = DLG_COMBO_TOOLBAR.with(|c| *c.borrow()) {
+        let index = ToolbarPlacement::ALL
+            .iter()
+            .position(|&p| p == settings.toolbar)
+            .unwrap_or(0);
+        let _ = SendMessageW(combo, CB_SETCURSEL, Some(WPARAM(index)), None);
+    }
+
+    // Shortcuts
+    let shortcuts = hotkeys::resolve(&settings.hotkeys);
+    if let Some(list) = DLG_LIST_SHORTCUTS.with(|l| *l.borrow()) {
+        for (row, (_, hotkey)) in shortcuts.iter().enumerate() {
+            set_list_text(list, row, 1, &shortcut_label(*hotkey));
+        }
+    }
+    DLG_SHORTCUTS.with(|s| *s.borrow_mut() = shortcuts);
+    show_selected_shortcut();
+
+    // MIDI (a running learn mode would bind into the replaced mapping)
+    if let Some(dialog) = DIALOG_HWND.with(|h| *h.borrow()) {
+        stop_midi_learn(dialog);
+        set_midi_status("");
+    }
+    set_check(&DLG_CHECK_MIDI, settings.midi_enabled);
+    if let Some(combo) = DLG_COMBO_MIDI_DEVICE.with(|c| *c.borrow()) {
+        let device = settings.midi_device.trim();
+        let mut index = 0;
+        if !device.is_empty() {
+            let text = wide_string(device);
+            index = SendMessageW(
+                combo,
+                CB_FINDSTRINGEXACT,
+                Some(WPARAM(usize::MAX)),
+                Some(LPARAM(text.as_ptr() as isize)),
+            )
+            .0;
+            if index < 0 {
+                index = SendMessageW(
+                    combo,
+                    CB_ADDSTRING,
+                    None,
+                    Some(LPARAM(text.as_ptr() as isize)),
+                )
+                .0;
+            }
+        }
+        let _ = SendMessageW(
+            combo,
+            CB_SETCURSEL,
+            Some(WPARAM(index.max(0) as usize)),
+            None,
+        );
+    }
+    let bindings = midi::resolve(&settings.midi_mappings);
+    if let Some(list) = DLG_LIST_MIDI.with(|l| *l.borrow()) {
+        for (row, (_, trigger)) in bindings.iter().enumerate() {
+            set_list_text(list, row, 1, &trigger_label(*trigger));
+        }
+    }
+    DLG_MIDI_BINDINGS.with(|b| *b.borrow_mut() = bindings);
+
+    // Plugins
+    if let Some(list) = DLG_LIST_PLUGINS.with(|l| *l.borrow()) {
+        let plugins = DLG_PLUGINS.with(|p| p.borrow().clone());
+        for (row, plugin) in plugins.iter().enumerate() {
+            set_list_check(list, row, settings.enabled_plugins.contains(&plugin.file));
+        }
+    }
+
+    DLG_HISTORY_PAUSED.with(|p| *p.borrow_mut() = false);
+    DLG_PREVIEW_DIRTY.with(|d| *d.borrow_mut() = true);
+}
+
+/// The settings as currently shown in the controls
+#[cfg(windows)]
+unsafe fn controls_state() -> Option<AppSettings> {
+    let mut settings = DIALOG_SETTINGS.with(|s| s.borrow().clone())?;
+    read_controls(&mut settings);
+    Some(settings)
+}
+
+#[cfg(windows)]
+fn same_settings(a: &AppSettings, b: &AppSettings) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+/// Start the undo history with the state the dialog opened with
+#[cfg(windows)]
+unsafe fn start_history() {
+    DLG_UNDO.with(|u| u.borrow_mut().clear());
+    DLG_REDO.with(|r| r.borrow_mut().clear());
+    let state = controls_state();
+    DLG_RECORDED.with(|r| *r.borrow_mut() = state);
+    update_history_buttons();
+}
+
+/// If the controls changed since the last recorded state, make that state an
+/// undo step (called after every control notification that may be an edit)
+#[cfg(windows)]
+unsafe fn record_edit() {
+    if DLG_HISTORY_PAUSED.with(|p| *p.borrow()) {
+        return;
+    }
+    let Some(previous) = DLG_RECORDED.with(|r| r.borrow().clone()) else {
+        return;
+    };
+    let Some(current) = controls_state() else {
+        return;
+    };
+    if same_settings(&previous, &current) {
+        return;
+    }
+    DLG_UNDO.with(|u| {
+        let mut undo = u.borrow_mut();
+        undo.push(previous);
+        if undo.len() > dialog::UNDO_LIMIT {
+            undo.remove(0);
         }
     });
+    DLG_REDO.with(|r| r.borrow_mut().clear());
+    DLG_RECORDED.with(|r| *r.borrow_mut() = Some(current));
+    update_history_buttons();
+}
+
+/// Undo (`back`) or redo the last edit
+#[cfg(windows)]
+unsafe fn step_history(back: bool) {
+    record_edit();
+    let (from, to) = if back {
+        (&DLG_UNDO, &DLG_REDO)
+    } else {
+        (&DLG_REDO, &DLG_UNDO)
+    };
+    let Some(state) = from.with(|f| f.borrow_mut().pop()) else {
+        return;
+    };
+    if let Some(current) = DLG_RECORDED.with(|r| r.borrow_mut().take()) {
+        to.with(|t| t.borrow_mut().push(current));
+    }
+    write_controls(&state);
+    DLG_RECORDED.with(|r| *r.borrow_mut() = Some(state));
+    update_history_buttons();
+}
+
+/// Put the settings of one tab back to their defaults (an undoable edit)
+#[cfg(windows)]
+unsafe fn reset_page(page: usize) {
+    record_edit();
+    let Some(current) = controls_state() else {
+        return;
+    };
+    let defaults = if DIALOG_DEV_MODE.with(|d| *d.borrow()) {
+        AppSettings::for_development()
+    } else {
+        AppSettings::default()
+    };
+    let reset = match page {
+        PAGE_SHORTCUTS => AppSettings {
+            hotkeys: defaults.hotkeys,
+            ..current
+        },
+        PAGE_MIDI => AppSettings {
+            midi_enabled: defaults.midi_enabled,
+            midi_device: defaults.midi_device,
+            midi_mappings: defaults.midi_mappings,
+            ..current
+        },
+        PAGE_PLUGINS => AppSettings {
+            enabled_plugins: defaults.enabled_plugins,
+            ..current
+        },
+        PAGE_ADVANCED => AppSettings {
+            thread_priority: defaults.thread_priority,
+            cpu_cores: defaults.cpu_cores,
+            capture: CaptureSettings {
+                game_capture: defaults.capture.game_capture,
+                ..current.capture
+            },
+            esc_action: defaults.esc_action,
+            theme: defaults.theme,
+            accent_color: defaults.accent_color,
+            toolbar: defaults.toolbar,
+            ..current
+        },
+        // The remote control token is kept - a new one would unpair the
+        // clients - and so are the saved scenes
+        _ => AppSettings {
+            stream_deck_token: current.stream_deck_token,
+            scenes: current.scenes,
+            hotkeys: current.hotkeys,
+            midi_enabled: current.midi_enabled,
+            midi_device: current.midi_device,
+            midi_mappings: current.midi_mappings,
+            enabled_plugins: current.enabled_plugins,
+            thread_priority: current.thread_priority,
+            cpu_cores: current.cpu_cores,
+            capture: CaptureSettings {
+                game_capture: current.capture.game_capture,
+                ..defaults.capture
+            },
+            esc_action: current.esc_action,
+            theme: current.theme,
+            accent_color: current.accent_color,
+            toolbar: current.toolbar,
+            ..defaults
+        },
+    };
+    write_controls(&reset);
+    record_edit();
+    info!("Settings tab {} reset to defaults", page);
+}
+
+/// Enable Undo / Redo only when there is something to undo / redo
+#[cfg(windows)]
+unsafe fn update_history_buttons() {
+    use windows::Win32::UI::Input::KeyboardAndMouse::EnableWindow;
+
+    for (button, stack) in [(&DLG_BTN_UNDO, &DLG_UNDO), (&DLG_BTN_REDO, &DLG_REDO)] {
+        if let Some(h) = button.with(|b| *b.borrow()) {
+            let _ = EnableWindow(h, !stack.with(|s| s.borrow().is_empty()));
+        }
+    }
+}
+
+/// Tab shown right now
+#[cfg(windows)]
+unsafe fn current_page() -> usize {
+    DLG_TAB
+        .with(|t| *t.borrow())
+        .map(|tab| SendMessageW(tab, TCM_GETCURSEL, None, None).0.max(0) as usize)
+        .unwrap_or(PAGE_GENERAL)
 }
 
 #[cfg(not(windows))]
 pub fn show_settings_dialog(
-    _current_settings: &CaptureSettings,
+    _current_settings: &AppSettings,
     _dev_mode: bool,
-) -> Option<CaptureSettings> {
+    _plugins: &[PluginInfo],
+    _preview: &mut dyn FnMut(&AppSettings),
+) -> Option<AppSettings> {
     // Settings dialog not supported on non-Windows platforms
     None
 }
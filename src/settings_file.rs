@@ -0,0 +1,184 @@
+// settings_file.rs - Persisted Settings (settings.json)
+//
+// The settings from the Settings dialog (including the shortcuts) are stored
+// as JSON in %APPDATA%\RustFrame\settings.json and loaded at startup:
+// - Fields missing from the file get their defaults, so files written by an
+//   older version keep working
+// - A file that cannot be read is ignored with a warning (defaults are used)
+// - Production / development mode is not stored - it follows the build and
+//   the --dev flag
+//
+// Edits made in a text editor while RustFrame runs are picked up by the
+// Watcher and applied live; a file that does not parse or has conflicting
+// shortcuts is reported instead of being ignored (see reload).
+//
+// Portable mode: with a portable.txt next to RustFrame.exe (or the --portable
+// flag), settings.json and the other files of dir() go to a config folder next
+// to the executable instead, together with a log file.
+
+use anyhow::{anyhow, Context, Result};
+use log::{info, warn};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::app_settings::AppSettings;
+use crate::capture::CaptureSettings;
+use crate::constants::settings_file;
+use crate::hotkeys;
+
+/// Folder for settings.json, markers, scripts and plugins:
+/// %APPDATA%\RustFrame, or the config folder in portable mode
+/// (None if %APPDATA% is not set)
+pub fn dir() -> Option<PathBuf> {
+    portable_dir().or_else(|| {
+        std::env::var_os("APPDATA").map(|dir| PathBuf::from(dir).join(settings_file::DIR_NAME))
+    })
+}
+
+/// The config folder next to the executable, if RustFrame runs in portable mode
+pub fn portable_dir() -> Option<PathBuf> {
+    static PORTABLE_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+    PORTABLE_DIR
+        .get_or_init(|| {
+            let exe = std::env::current_exe().ok()?;
+            let exe_dir = exe.parent()?;
+            let portable = std::env::args().any(|arg| arg == "--portable")
+                || exe_dir.join(settings_file::PORTABLE_MARKER).exists();
+            portable.then(|| exe_dir.join(settings_file::PORTABLE_DIR_NAME))
+        })
+        .clone()
+}
+
+/// Location of settings.json (None if %APPDATA% is not set)
+pub fn path() -> Option<PathBuf> {
+    dir().map(|dir| dir.join(settings_file::FILE_NAME))
+}
+
+/// Load the saved settings on top of the defaults of the current mode
+pub fn load(dev_mode: bool) -> AppSettings {
+    let defaults = if dev_mode {
+        AppSettings::for_development()
+    } else {
+        AppSettings::default()
+    };
+    let Some(path) = path() else {
+        return defaults;
+    };
+    if !path.exists() {
+        info!("No settings file at {:?} - using defaults", path);
+        return defaults;
+    }
+
+    let loaded = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {:?}", path))
+        .and_then(|text| {
+            serde_json::from_str::<AppSettings>(&text)
+                .with_context(|| format!("Failed to parse {:?}", path))
+        });
+    match loaded {
+        Ok(settings) => {
+            info!("Loaded settings from {:?}", path);
+            AppSettings {
+                capture: CaptureSettings {
+                    exclude_from_capture: defaults.capture.exclude_from_capture,
+                    ..settings.capture
+                },
+                ..settings
+            }
+        }
+        Err(e) => {
+            warn!("{:#} - using defaults", e);
+            defaults
+        }
+    }
+}
+
+/// Read settings.json again after it was edited outside RustFrame
+/// Unlike load(), a file that cannot be used is an error, so it can be shown.
+pub fn reload() -> Result<AppSettings> {
+    let path = path().ok_or_else(|| anyhow!("%APPDATA% is not set"))?;
+    let text =
+        std::fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+    let settings: AppSettings =
+        serde_json::from_str(&text).with_context(|| format!("{:?} is not valid", path))?;
+    validate(&settings).with_context(|| format!("{:?} is not valid", path))?;
+    info!("Reloaded settings from {:?}", path);
+    Ok(settings)
+}
+
+/// Problems serde cannot see: shortcuts that do not parse or are used twice
+pub fn validate(settings: &AppSettings) -> Result<()> {
+    for (id, text) in &settings.hotkeys {
+        if !text.trim().is_empty() {
+            hotkeys::parse_hotkey(text).with_context(|| format!("Shortcut for {}", id))?;
+        }
+    }
+    let bindings = hotkeys::resolve(&settings.hotkeys);
+    if let Some((a, b)) = hotkeys::conflicts(&bindings).first() {
+        return Err(anyhow!(
+            "{} and {} use the same shortcut",
+            a.label(),
+            b.label()
+        ));
+    }
+    Ok(())
+}
+
+/// Last change of settings.json (None if it does not exist)
+fn modified() -> Option<SystemTime> {
+    path()?.metadata().and_then(|m| m.modified()).ok()
+}
+
+/// Notices when settings.json changes on disk
+/// A change is reported once the file has stayed the same for one poll, so
+/// an editor that is still writing it is not read halfway.
+pub struct Watcher {
+    known: Option<SystemTime>,
+    pending: Option<SystemTime>,
+    checked: Instant,
+}
+
+impl Watcher {
+    pub fn new() -> Self {
+        Self {
+            known: modified(),
+            pending: None,
+            checked: Instant::now(),
+        }
+    }
+
+    /// Whether settings.json was changed since the last call that returned
+    /// true (checked every WATCH_POLL_MS)
+    pub fn poll(&mut self) -> bool {
+        if self.checked.elapsed() < Duration::from_millis(settings_file::WATCH_POLL_MS) {
+            return false;
+        }
+        self.checked = Instant::now();
+
+        let current = modified();
+        if current == self.known {
+            self.pending = None;
+            return false;
+        }
+        if current != self.pending {
+            self.pending = current;
+            return false;
+        }
+        self.known = current;
+        self.pending = None;
+        current.is_some()
+    }
+}
+
+/// Write the settings to settings.json
+pub fn save(settings: &AppSettings) -> Result<()> {
+    let path = path().ok_or_else(|| anyhow!("%APPDATA% is not set"))?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    }
+    let text = serde_json::to_string_pretty(settings).context("Failed to serialize settings")?;
+    std::fs::write(&path, text).with_context(|| format!("Failed to write {:?}", path))?;
+    info!("Saved settings to {:?}", path);
+    Ok(())
+}
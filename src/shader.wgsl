@@ -3,6 +3,9 @@
 // This is a simple passthrough shader that:
 // 1. Vertex shader: Transforms vertices from NDC to screen space
 // 2. Fragment shader: Samples the captured texture and outputs the color
+//    (optionally denoised and sharpened - see enhance.rs - blended with the
+//    previous picture during a transition - see transition.rs - and with a
+//    chroma key color keyed out - see chroma_key.rs)
 //
 // WGSL is the WebGPU Shading Language, similar to GLSL or HLSL
 
@@ -25,6 +28,61 @@ var t_texture: texture_2d<f32>;
 @group(0) @binding(1)
 var t_sampler: sampler;
 
+// Chroma key parameters (must match ChromaKeyUniform in chroma_key.rs)
+struct ChromaKey {
+    key_color: vec4<f32>, // Key color in linear RGB
+    similarity: f32,      // CbCr distance that is fully transparent
+    smoothness: f32,      // Width of the partially transparent edge
+    enabled: u32,         // 1 = keying on, 0 = passthrough
+    checker_size: f32,    // Checkerboard cell size in pixels
+};
+
+@group(0) @binding(2)
+var<uniform> chroma: ChromaKey;
+
+// Sharpen / denoise parameters (must match EnhanceUniform in enhance.rs)
+struct Enhance {
+    sharpen_amount: f32,    // Unsharp mask strength, 0 = off
+    denoise_threshold: f32, // Largest change treated as noise, 0 = off
+    denoise_strength: f32,  // Share of the previous frame blended in
+    _padding: f32,
+};
+
+@group(0) @binding(3)
+var<uniform> enhance: Enhance;
+
+// The previously presented frame (temporal denoise)
+@group(0) @binding(4)
+var t_previous: texture_2d<f32>;
+
+// The frame being faded out by a transition
+@group(0) @binding(5)
+var t_faded_out: texture_2d<f32>;
+
+// Transition parameters (must match TransitionUniform in transition.rs)
+struct Transition {
+    color: vec4<f32>, // Fade to color target in linear RGB
+    progress: f32,    // 0 = old frame only, 1 = new frame only
+    kind: u32,        // 0 = cut, 1 = crossfade, 2 = slide, 3 = fade to color
+    _padding0: f32,
+    _padding1: f32,
+};
+
+@group(0) @binding(6)
+var<uniform> transition: Transition;
+
+// Blue-difference and red-difference chroma (BT.709) of an RGB color
+fn rgb_to_cbcr(rgb: vec3<f32>) -> vec2<f32> {
+    let y = dot(rgb, vec3<f32>(0.2126, 0.7152, 0.0722));
+    return vec2<f32>((rgb.b - y) * 0.5389, (rgb.r - y) * 0.6350);
+}
+
+// Alpha for a pixel: 0 = matches the key color, 1 = keep
+fn chroma_alpha(rgb: vec3<f32>) -> f32 {
+    let distance = length(rgb_to_cbcr(rgb) - rgb_to_cbcr(chroma.key_color.rgb));
+    return smoothstep(chroma.similarity, chroma.similarity + chroma.smoothness, distance);
+}
+
 // Vertex shader
 // Transforms 2D positions to 4D clip space positions
 @vertex
@@ -44,9 +102,63 @@ fn vs_main(input: VertexInput) -> VertexOutput {
 // Samples the texture and outputs the color
 @fragment
 fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    // Slide transition: the new frame enters from the right edge, the old one
+    // leaves to the left (edge = where the new frame starts on screen)
+    let edge = select(0.0, 1.0 - transition.progress, transition.kind == 2u);
+    let uv = input.tex_coords - vec2<f32>(edge, 0.0);
+    let old_uv = input.tex_coords + vec2<f32>(select(0.0, transition.progress, transition.kind == 2u), 0.0);
+
     // Sample the texture at the given coordinates
-    let color = textureSample(t_texture, t_sampler, input.tex_coords);
+    var color = textureSample(t_texture, t_sampler, uv);
+
+    // Neighbors one output pixel away (for the unsharp mask) and the previous
+    // frame - sampled unconditionally because textureSample needs uniform
+    // control flow
+    let texel = fwidth(input.tex_coords);
+    let blur = (textureSample(t_texture, t_sampler, uv + vec2<f32>(texel.x, 0.0))
+        + textureSample(t_texture, t_sampler, uv - vec2<f32>(texel.x, 0.0))
+        + textureSample(t_texture, t_sampler, uv + vec2<f32>(0.0, texel.y))
+        + textureSample(t_texture, t_sampler, uv - vec2<f32>(0.0, texel.y))) * 0.25;
+    let previous = textureSample(t_previous, t_sampler, uv);
+    let faded_out = textureSample(t_faded_out, t_sampler, old_uv);
+
+    // Temporal denoise: small changes since the previous frame are noise,
+    // blend them away (the less it changed, the more of the previous frame)
+    let detail = color.rgb - blur.rgb;
+    if (enhance.denoise_threshold > 0.0) {
+        let change = length(color.rgb - previous.rgb);
+        let weight = enhance.denoise_strength * (1.0 - smoothstep(0.0, enhance.denoise_threshold, change));
+        color = vec4<f32>(mix(color.rgb, previous.rgb, weight), color.a);
+    }
+
+    // Unsharp mask: add back the difference to the blurred neighborhood
+    color = vec4<f32>(clamp(color.rgb + detail * enhance.sharpen_amount, vec3<f32>(0.0), vec3<f32>(1.0)), color.a);
+
+    // Transition from the old picture to the new one
+    let p = transition.progress;
+    if (transition.kind == 1u) {
+        color = mix(faded_out, color, p);
+    } else if (transition.kind == 2u) {
+        color = select(faded_out, color, input.tex_coords.x >= edge);
+    } else if (transition.kind == 3u) {
+        // First half: old frame -> color, second half: color -> new frame
+        if (p < 0.5) {
+            color = mix(faded_out, transition.color, p * 2.0);
+        } else {
+            color = mix(transition.color, color, (p - 0.5) * 2.0);
+        }
+    }
+
+    if (chroma.enabled == 0u) {
+        // Return the sampled color
+        return color;
+    }
 
-    // Return the sampled color
-    return color;
+    // Chroma key: the window surface is opaque, so show keyed areas over a
+    // checkerboard (like image editors do); screenshots get the alpha from
+    // the CPU keyer in chroma_key.rs
+    let alpha = chroma_alpha(color.rgb);
+    let cell = floor(input.clip_position.xy / chroma.checker_size);
+    let checker = select(0.2, 0.4, (i32(cell.x) + i32(cell.y)) % 2 == 0);
+    return vec4<f32>(mix(vec3<f32>(checker), color.rgb, alpha), alpha);
 }
@@ -0,0 +1,74 @@
+// shell_menu.rs - Desktop Right-Click Menu
+//
+// With the setting on, right-clicking the desktop shows a "Capture with
+// RustFrame" submenu:
+//
+//   Capture a window...    the next window clicked is captured
+//   Capture this monitor   the monitor that was right-clicked is captured
+//
+// Both start the capture (with the countdown) with the frame already around
+// the target - no dragging and resizing the overlay. The entries are
+// rustframe:// links (see deep_link.rs), so they also work while RustFrame is
+// not running yet.
+//
+// Windows does not let other applications add items to a window's title bar
+// menu, so the desktop menu is the entry point for picking a window.
+//
+// The entries live in the current user's registry (no administrator rights
+// needed):
+//
+//   HKCU\Software\Classes\DesktopBackground\Shell\RustFrame
+//
+// They are rewritten at every start while the setting is on, so they follow
+// the executable if it was moved, and removed when it is turned off.
+
+use anyhow::Result;
+
+#[cfg(windows)]
+const MENU_KEY: &str = r"Software\Classes\DesktopBackground\Shell\RustFrame";
+
+/// Submenu entries: (key name, text, link)
+/// Explorer sorts the entries by key name
+#[cfg(windows)]
+const ENTRIES: [(&str, &str, &str); 2] = [
+    ("1window", "Capture a window...", "rustframe://window"),
+    ("2monitor", "Capture this monitor", "rustframe://monitor"),
+];
+
+/// Add or remove the desktop menu entries
+#[cfg(windows)]
+pub fn set_enabled(enabled: bool) -> Result<()> {
+    use crate::utils::{delete_user_registry_key, set_user_registry_string};
+    use anyhow::Context;
+
+    // Entries from an older version are replaced as a whole
+    delete_user_registry_key(MENU_KEY)?;
+    if !enabled {
+        log::info!("Desktop menu entries removed");
+        return Ok(());
+    }
+
+    let exe = std::env::current_exe().context("Failed to locate RustFrame.exe")?;
+    set_user_registry_string(MENU_KEY, Some("MUIVerb"), "Capture with RustFrame")?;
+    set_user_registry_string(MENU_KEY, Some("Icon"), &format!("\"{}\",0", exe.display()))?;
+    // An empty SubCommands value turns the entry into a submenu of its "shell" key
+    set_user_registry_string(MENU_KEY, Some("SubCommands"), "")?;
+    for (name, text, link) in ENTRIES {
+        let key = format!(r"{}\shell\{}", MENU_KEY, name);
+        set_user_registry_string(&key, Some("MUIVerb"), text)?;
+        set_user_registry_string(
+            &format!(r"{}\command", key),
+            None,
+            &format!("\"{}\" \"{}\"", exe.display(), link),
+        )?;
+    }
+    log::info!("Desktop menu entries added");
+    Ok(())
+}
+
+#[cfg(not(windows))]
+pub fn set_enabled(_enabled: bool) -> Result<()> {
+    Err(anyhow::anyhow!(
+        "The desktop menu is only supported on Windows"
+    ))
+}
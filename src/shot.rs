@@ -0,0 +1,210 @@
+// shot.rs - One-Shot Screenshots from the Command Line
+//
+// `RustFrame shot` grabs a single frame with the capture engine, writes it as
+// a PNG and exits - no window, tray or GPU device, and a running RustFrame is
+// not involved:
+//
+//   RustFrame shot --monitor 1 --out screen.png
+//   RustFrame shot --window "Visual Studio Code" --out code.png
+//   RustFrame shot --region 100,100,1280,720
+//
+// - --monitor: 1, 2, ... in the order Windows lists the monitors, or "primary"
+// - --window: the topmost visible window whose title contains the text (case
+//   does not matter); it is brought to the front first, so windows above it
+//   do not cover it
+// - --region: x,y,width,height in virtual screen coordinates
+//
+// Without --out the file goes to Pictures\RustFrame like other screenshots.
+
+use log::info;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use rustframe::capture::{CaptureRect, CaptureSession};
+use rustframe::cli::{CliError, FailureKind};
+use rustframe::constants::shot;
+use rustframe::record::parse_region;
+
+use crate::{list, screenshot};
+
+/// What to capture
+#[derive(Debug, Clone)]
+pub enum ShotTarget {
+    /// 1-based monitor number (0 = the primary monitor)
+    Monitor(usize),
+    /// Part of a window title
+    Window(String),
+    Region(CaptureRect),
+}
+
+/// A parsed `shot` command line
+#[derive(Debug, Clone)]
+pub struct ShotArgs {
+    pub target: ShotTarget,
+    /// None = a new file in Pictures\RustFrame
+    pub out: Option<PathBuf>,
+    pub cursor: bool,
+}
+
+impl ShotArgs {
+    /// Parse the options after `shot`; the error is a message for the user
+    pub fn parse(args: &[String]) -> std::result::Result<Self, String> {
+        let mut target = None;
+        let mut out = None;
+        let mut cursor = false;
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .map(|v| v.trim())
+                    .ok_or_else(|| format!("Missing value for {}", arg))
+            };
+            let next = match arg.as_str() {
+                "--monitor" => {
+                    let text = value()?;
+                    let number = if text.eq_ignore_ascii_case("primary") {
+                        0
+                    } else {
+                        text.parse()
+                            .ok()
+                            .filter(|n| *n > 0)
+                            .ok_or_else(|| format!("Invalid monitor '{}'", text))?
+                    };
+                    Some(ShotTarget::Monitor(number))
+                }
+                "--window" => {
+                    let text = value()?;
+                    if text.is_empty() {
+                        return Err("Empty window title".to_string());
+                    }
+                    Some(ShotTarget::Window(text.to_string()))
+                }
+                "--region" => {
+                    let text = value()?;
+                    let region =
+                        parse_region(text).ok_or_else(|| format!("Invalid region '{}'", text))?;
+                    Some(ShotTarget::Region(region))
+                }
+                "--out" => {
+                    out = Some(PathBuf::from(value()?));
+                    None
+                }
+                "--cursor" => {
+                    cursor = true;
+                    None
+                }
+                other => return Err(format!("Unknown option '{}'", other)),
+            };
+            if next.is_some() {
+                if target.is_some() {
+                    return Err("Use only one of --monitor, --window and --region".to_string());
+                }
+                target = next;
+            }
+        }
+
+        Ok(Self {
+            // The whole primary monitor by default
+            target: target.unwrap_or(ShotTarget::Monitor(0)),
+            out,
+            cursor,
+        })
+    }
+}
+
+type Result<T> = std::result::Result<T, CliError>;
+
+/// Capture one frame of the target and save it; returns the file
+pub fn take(args: &ShotArgs) -> Result<PathBuf> {
+    let region = match &args.target {
+        ShotTarget::Monitor(number) => monitor_rect(*number)?,
+        ShotTarget::Window(title) => window_rect(title)?,
+        ShotTarget::Region(region) => *region,
+    };
+    info!("Taking a screenshot of {:?} ({:?})", args.target, region);
+
+    let mut session = CaptureSession::builder()
+        .region(region)
+        .cursor(args.cursor)
+        .build()
+        .map_err(|e| CliError::capture("Screenshot failed", &e))?;
+    let frame = session
+        .next_frame(Duration::from_millis(shot::FRAME_TIMEOUT_MS))
+        .map_err(|e| CliError::capture("Screenshot failed", &e))?
+        .ok_or_else(|| CliError::new(FailureKind::Other, "No frame was captured"))?;
+    screenshot::save(
+        &frame.pixels,
+        frame.width,
+        frame.height,
+        None,
+        args.out.as_deref(),
+    )
+    .map_err(|e| CliError::new(FailureKind::Output, e.to_string()))
+}
+
+/// Bounds of monitor `number` (1-based, 0 = primary)
+fn monitor_rect(number: usize) -> Result<CaptureRect> {
+    let monitors = list::monitors();
+    let monitor = if number == 0 {
+        monitors.iter().find(|m| m.primary)
+    } else {
+        monitors.get(number - 1)
+    }
+    .ok_or_else(|| {
+        CliError::new(
+            FailureKind::InvalidRegion,
+            format!(
+                "There is no monitor {} ({} connected, see RustFrame list-monitors)",
+                number,
+                monitors.len()
+            ),
+        )
+    })?;
+    Ok(CaptureRect {
+        x: monitor.x,
+        y: monitor.y,
+        width: monitor.width,
+        height: monitor.height,
+    })
+}
+
+/// Bounds of the topmost window with `title` in its title, brought to the front
+fn window_rect(title: &str) -> Result<CaptureRect> {
+    let wanted = title.to_lowercase();
+    // Listed in z-order, topmost first
+    let window = list::windows()
+        .into_iter()
+        .find(|w| w.title.to_lowercase().contains(&wanted))
+        .ok_or_else(|| {
+            CliError::new(
+                FailureKind::InvalidRegion,
+                format!("No visible window has '{}' in its title", title),
+            )
+        })?;
+
+    bring_to_front(window.hwnd);
+    // Give the window time to draw itself on top
+    std::thread::sleep(Duration::from_millis(shot::WINDOW_SETTLE_MS));
+    crate::window_mask::window_rect(window.hwnd)
+        .filter(|r| r.width > 0 && r.height > 0)
+        .ok_or_else(|| {
+            CliError::new(
+                FailureKind::InvalidRegion,
+                format!("The window '{}' has no size", title),
+            )
+        })
+}
+
+#[cfg(windows)]
+fn bring_to_front(hwnd: isize) {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::SetForegroundWindow;
+
+    unsafe {
+        let _ = SetForegroundWindow(HWND(hwnd as *mut std::ffi::c_void));
+    }
+}
+
+#[cfg(not(windows))]
+fn bring_to_front(_hwnd: isize) {}
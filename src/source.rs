@@ -0,0 +1,59 @@
+// source.rs - Frame Sources for the Compositor
+//
+// Everything the renderer can place on the output canvas is a frame source:
+// - Screen regions, the main one and additional ones (CaptureEngine, see
+//   capture.rs and composite.rs) - the library's CaptureSession reads its
+//   frames the same way
+// - Webcam devices (see webcam.rs)
+// - Images and slideshows (see image_source.rs)
+//
+// The renderer keeps the last frame of every source and composites them with
+// the current CompositeLayout, so all sources share the same layouts, freeze /
+// pause handling and second output window.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::capture::CaptureRect;
+
+/// Where an optional source (webcam, image) is shown in the output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Placement {
+    Off,
+    /// Composited next to the captured region
+    Beside,
+    /// Replaces the captured region
+    Fullscreen,
+}
+
+impl Placement {
+    pub fn label(self) -> &'static str {
+        match self {
+            Placement::Off => "Off",
+            Placement::Beside => "Beside Screen",
+            Placement::Fullscreen => "Fullscreen",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Placement::Off => Placement::Beside,
+            Placement::Beside => Placement::Fullscreen,
+            Placement::Fullscreen => Placement::Off,
+        }
+    }
+}
+
+/// Something that delivers ARGB frames to the compositor
+pub trait FrameSource {
+    /// Copy the newest frame into `frame` and return its size, or None when
+    /// there is no new frame since the last call (the previous one stays shown)
+    fn read_frame(&mut self, frame: &mut Vec<u32>) -> Result<Option<(u32, u32)>>;
+
+    /// Parts of the frame last read that changed since the frame before it
+    /// (frame coordinates); None = unknown, the whole frame may have changed
+    fn dirty_rects(&mut self) -> Option<Vec<CaptureRect>> {
+        None
+    }
+}
@@ -0,0 +1,165 @@
+// spotlight.rs - Presentation Spotlight
+//
+// Darkens the captured frame except for a focus area (ellipse or rectangle),
+// so viewers' attention goes where the presenter wants it.
+//
+// The focus area either follows the mouse cursor or stays pinned where it was
+// placed. Coordinates are relative to the top-left corner of the capture
+// region, just like the annotation layer.
+
+use crate::bitmap_font::Canvas;
+use crate::constants::spotlight;
+
+/// Shape of the undimmed focus area
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpotlightShape {
+    Ellipse,
+    Rectangle,
+}
+
+impl SpotlightShape {
+    /// Short label for logs and titles
+    pub fn label(self) -> &'static str {
+        match self {
+            SpotlightShape::Ellipse => "Ellipse",
+            SpotlightShape::Rectangle => "Rectangle",
+        }
+    }
+}
+
+/// Spotlight state for the current capture session
+pub struct Spotlight {
+    enabled: bool,
+    shape: SpotlightShape,
+    /// Follow the cursor (true) or stay pinned at `center` (false)
+    follow_cursor: bool,
+    center: (i32, i32),
+    radius: (i32, i32),
+    /// Bumped on every visible change so the renderer knows when to recomposite
+    revision: u64,
+}
+
+impl Spotlight {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            shape: SpotlightShape::Ellipse,
+            follow_cursor: true,
+            center: (0, 0),
+            radius: (spotlight::DEFAULT_RADIUS_X, spotlight::DEFAULT_RADIUS_Y),
+            revision: 0,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if self.enabled != enabled {
+            self.enabled = enabled;
+            self.revision += 1;
+        }
+    }
+
+    pub fn shape(&self) -> SpotlightShape {
+        self.shape
+    }
+
+    /// Switch between ellipse and rectangle
+    pub fn toggle_shape(&mut self) {
+        self.shape = match self.shape {
+            SpotlightShape::Ellipse => SpotlightShape::Rectangle,
+            SpotlightShape::Rectangle => SpotlightShape::Ellipse,
+        };
+        self.revision += 1;
+    }
+
+    pub fn follows_cursor(&self) -> bool {
+        self.follow_cursor
+    }
+
+    /// Pin the focus area where it currently is, or let it follow the cursor again
+    pub fn toggle_follow_cursor(&mut self) {
+        self.follow_cursor = !self.follow_cursor;
+    }
+
+    /// Feed the current cursor position (capture-region coordinates)
+    /// Ignored while the focus area is pinned
+    pub fn update_cursor(&mut self, x: i32, y: i32) {
+        if self.follow_cursor {
+            self.move_to(x, y);
+        }
+    }
+
+    /// Place the focus area at the given position (capture-region coordinates)
+    pub fn move_to(&mut self, x: i32, y: i32) {
+        if self.center != (x, y) {
+            self.center = (x, y);
+            self.revision += 1;
+        }
+    }
+
+    /// Grow (positive) or shrink (negative) the focus area, keeping its aspect ratio
+    pub fn resize(&mut self, steps: i32) {
+        let (rx, ry) = self.radius;
+        let dx = steps * spotlight::RESIZE_STEP;
+        let dy = dx * ry / rx.max(1);
+        self.radius = (
+            (rx + dx).clamp(spotlight::MIN_RADIUS, spotlight::MAX_RADIUS),
+            (ry + dy).clamp(spotlight::MIN_RADIUS, spotlight::MAX_RADIUS),
+        );
+        self.revision += 1;
+    }
+
+    /// Revision counter - changes whenever the rendered result would change
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Dim every pixel outside the focus area (no-op when disabled)
+    pub fn apply(&self, canvas: &mut Canvas) {
+        if !self.enabled {
+            return;
+        }
+
+        let (cx, cy) = self.center;
+        let (rx, ry) = (self.radius.0.max(1) as f32, self.radius.1.max(1) as f32);
+        let feather = spotlight::FEATHER as f32;
+
+        for y in 0..canvas.height {
+            let row = (y * canvas.width) as usize;
+            let dy = (y - cy) as f32;
+            for x in 0..canvas.width {
+                let dx = (x - cx) as f32;
+
+                // Distance (in pixels) outside the focus area's edge
+                let outside = match self.shape {
+                    SpotlightShape::Ellipse => {
+                        let norm = ((dx / rx).powi(2) + (dy / ry).powi(2)).sqrt();
+                        (norm - 1.0) * rx.min(ry)
+                    }
+                    SpotlightShape::Rectangle => {
+                        let ox = (dx.abs() - rx).max(0.0);
+                        let oy = (dy.abs() - ry).max(0.0);
+                        (ox * ox + oy * oy).sqrt()
+                    }
+                };
+                if outside <= 0.0 {
+                    continue;
+                }
+
+                // Blend from full brightness to DIM_PERCENT across the feather band
+                let t = (outside / feather).min(1.0);
+                let keep = 1.0 - t * (1.0 - spotlight::DIM_PERCENT as f32 / 100.0);
+                let keep = (keep * 256.0) as u32;
+
+                let px = &mut canvas.pixels[row + x as usize];
+                let r = (((*px >> 16) & 0xFF) * keep) >> 8;
+                let g = (((*px >> 8) & 0xFF) * keep) >> 8;
+                let b = ((*px & 0xFF) * keep) >> 8;
+                *px = (*px & 0xFF00_0000) | (r << 16) | (g << 8) | b;
+            }
+        }
+    }
+}
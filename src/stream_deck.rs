@@ -0,0 +1,470 @@
+// stream_deck.rs - Stream Deck / Remote Control Server
+//
+// A WebSocket server on localhost that lets an Elgato Stream Deck plugin (or
+// Bitfocus Companion, Touch Portal, a script, ...) control RustFrame and show
+// its live status on the keys. Messages are JSON text frames, the protocol is
+// documented in docs/stream-deck.md:
+// - On connect RustFrame sends {"event": "hello", ...} with the available
+//   actions
+// - The client identifies with {"identify": "<token>"} - the token is in
+//   Settings → General and settings.json; RustFrame answers with
+//   {"event": "identified"} and the current state, or an error and closes
+// - The client sends {"action": "start_stop"} (ids from RemoteAction)
+// - RustFrame sends {"event": "state", ...} whenever the state changes
+//   (capture on/off, paused, scene, elapsed seconds, frame stats)
+// - Plain HTTP requests on the same port go to the REST endpoint
+//   (GET /status, POST /record/start, ... - see http_api.rs)
+//
+// The token keeps other local programs and web pages (browsers may open
+// WebSockets to localhost) from driving RustFrame without the user's consent.
+//
+// The server only listens on 127.0.0.1. Every connection runs on its own
+// thread; actions reach the app through a channel polled from about_to_wait
+// (like the hotkeys), the state is shared through a mutex. At most
+// MAX_CLIENTS connections are served at once, reads and writes time out, and
+// a WebSocket client that does not identify within IDENTIFY_TIMEOUT_MS is
+// closed - a stuck or hostile client can't pile up threads.
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+use serde::Serialize;
+use std::io::ErrorKind;
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tungstenite::{Message, WebSocket};
+
+use crate::constants::{scene, stream_deck};
+use crate::http_api;
+
+/// Actions a remote can trigger
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemoteAction {
+    /// Start capture (with the countdown) / stop capture
+    StartStop,
+    /// Start capture (with the countdown) unless it is already running
+    Start,
+    /// Stop capture (or cancel the countdown)
+    Stop,
+    TogglePause,
+    NextScene,
+    PreviousScene,
+    SwitchScene(usize),
+    DropMarker,
+    /// Save a screenshot of the output (see screenshot.rs)
+    Screenshot,
+    /// Upload the last screenshot (see upload.rs)
+    UploadScreenshot,
+}
+
+impl RemoteAction {
+    /// All actions, in the order they are announced to the client
+    pub fn all() -> Vec<RemoteAction> {
+        let mut actions = vec![
+            RemoteAction::StartStop,
+            RemoteAction::Start,
+            RemoteAction::Stop,
+            RemoteAction::TogglePause,
+            RemoteAction::NextScene,
+            RemoteAction::PreviousScene,
+        ];
+        actions.extend((0..scene::SLOTS).map(RemoteAction::SwitchScene));
+        actions.push(RemoteAction::DropMarker);
+        actions.push(RemoteAction::Screenshot);
+        actions.push(RemoteAction::UploadScreenshot);
+        actions
+    }
+
+    /// Identifier used in the protocol
+    pub fn id(self) -> String {
+        match self {
+            RemoteAction::StartStop => "start_stop".to_string(),
+            RemoteAction::Start => "start".to_string(),
+            RemoteAction::Stop => "stop".to_string(),
+            RemoteAction::TogglePause => "toggle_pause".to_string(),
+            RemoteAction::NextScene => "next_scene".to_string(),
+            RemoteAction::PreviousScene => "previous_scene".to_string(),
+            RemoteAction::SwitchScene(slot) => format!("scene_{}", slot + 1),
+            RemoteAction::DropMarker => "drop_marker".to_string(),
+            RemoteAction::Screenshot => "screenshot".to_string(),
+            RemoteAction::UploadScreenshot => "upload_screenshot".to_string(),
+        }
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            RemoteAction::StartStop => "Start / Stop Capture".to_string(),
+            RemoteAction::Start => "Start Capture".to_string(),
+            RemoteAction::Stop => "Stop Capture".to_string(),
+            RemoteAction::TogglePause => "Pause Screen".to_string(),
+            RemoteAction::NextScene => "Next Scene".to_string(),
+            RemoteAction::PreviousScene => "Previous Scene".to_string(),
+            RemoteAction::SwitchScene(slot) => format!("Scene {}", slot + 1),
+            RemoteAction::DropMarker => "Drop Marker".to_string(),
+            RemoteAction::Screenshot => "Screenshot".to_string(),
+            RemoteAction::UploadScreenshot => "Upload Screenshot".to_string(),
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::all()
+            .into_iter()
+            .find(|a| a.id().eq_ignore_ascii_case(id.trim()))
+    }
+}
+
+/// What the Stream Deck keys show
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RemoteState {
+    /// Capture is running (the output is live)
+    pub capturing: bool,
+    /// The countdown before the capture is running
+    pub countdown: bool,
+    /// The pause screen is shown instead of the capture
+    pub paused: bool,
+    /// Live scene (slot starts at 1)
+    pub scene: Option<String>,
+    pub scene_slot: Option<usize>,
+    /// Seconds since the capture started (0 while not capturing)
+    pub elapsed_seconds: u64,
+    /// Output frames presented during the last second
+    pub fps: u32,
+    /// Output frames presented since the capture started
+    pub frames: u32,
+}
+
+#[derive(Serialize)]
+struct ActionInfo {
+    id: String,
+    label: String,
+}
+
+/// Messages sent to the client
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum ServerMessage<'a> {
+    Hello {
+        app: &'static str,
+        version: &'static str,
+        /// Always true - actions are only accepted after {"identify": "<token>"}
+        authentication: bool,
+        actions: Vec<ActionInfo>,
+    },
+    Identified,
+    State(&'a RemoteState),
+    Error {
+        message: String,
+    },
+}
+
+/// State shared with the connection threads
+struct Shared {
+    state: RemoteState,
+    /// Bumped whenever the state changes
+    revision: u64,
+}
+
+/// The WebSocket server (stops when dropped)
+pub struct StreamDeckServer {
+    receiver: Receiver<RemoteAction>,
+    shared: Arc<Mutex<Shared>>,
+    stop: Arc<AtomicBool>,
+    accept_thread: Option<JoinHandle<()>>,
+}
+
+impl StreamDeckServer {
+    /// Listen on 127.0.0.1:<port>, accepting clients that identify with `token`
+    pub fn start(port: u16, token: &str) -> Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .with_context(|| format!("Failed to listen on port {} - is it in use?", port))?;
+        // Non-blocking so the accept thread notices when the server stops
+        listener.set_nonblocking(true)?;
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let shared = Arc::new(Mutex::new(Shared {
+            state: RemoteState::default(),
+            revision: 0,
+        }));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let accept_thread = {
+            let shared = shared.clone();
+            let stop = stop.clone();
+            let token = token.to_string();
+            std::thread::spawn(move || accept_loop(listener, sender, shared, stop, token))
+        };
+        info!(
+            "Remote control listening on ws://127.0.0.1:{0} and http://127.0.0.1:{0}",
+            port
+        );
+
+        Ok(Self {
+            receiver,
+            shared,
+            stop,
+            accept_thread: Some(accept_thread),
+        })
+    }
+
+    /// Next action sent by a client, if any
+    pub fn poll(&self) -> Option<RemoteAction> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Update the state shown on the keys (clients are only sent changes)
+    pub fn publish(&self, state: RemoteState) {
+        if let Ok(mut shared) = self.shared.lock() {
+            if shared.state != state {
+                shared.state = state;
+                shared.revision += 1;
+            }
+        }
+    }
+}
+
+impl Drop for StreamDeckServer {
+    fn drop(&mut self) {
+        // Wait for the listener to close, so the port can be opened again right away
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.accept_thread.take() {
+            let _ = thread.join();
+        }
+        info!("Stream Deck server stopped");
+    }
+}
+
+/// Counts a connection as served until dropped
+struct ClientSlot(Arc<AtomicUsize>);
+
+impl Drop for ClientSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+fn accept_loop(
+    listener: TcpListener,
+    sender: Sender<RemoteAction>,
+    shared: Arc<Mutex<Shared>>,
+    stop: Arc<AtomicBool>,
+    token: String,
+) {
+    let clients = Arc::new(AtomicUsize::new(0));
+    while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, address)) => {
+                if clients.load(Ordering::Relaxed) >= stream_deck::MAX_CLIENTS {
+                    warn!(
+                        "Stream Deck client {} refused - too many connections",
+                        address
+                    );
+                    continue;
+                }
+                clients.fetch_add(1, Ordering::Relaxed);
+                let slot = ClientSlot(clients.clone());
+                info!("Stream Deck client connected: {}", address);
+                let (sender, shared, stop) = (sender.clone(), shared.clone(), stop.clone());
+                let token = token.clone();
+                std::thread::spawn(move || {
+                    let _slot = slot;
+                    if let Err(e) = serve_client(stream, &sender, &shared, &stop, &token) {
+                        warn!("Stream Deck client {}: {:#}", address, e);
+                    }
+                    info!("Stream Deck client disconnected: {}", address);
+                });
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                std::thread::sleep(Duration::from_millis(stream_deck::ACCEPT_POLL_MS));
+            }
+            Err(e) => {
+                warn!("Stream Deck server: {}", e);
+                std::thread::sleep(Duration::from_millis(stream_deck::ACCEPT_POLL_MS));
+            }
+        }
+    }
+}
+
+/// Handle one connection: check the token, then send the state when it
+/// changes and pass actions to the app
+fn serve_client(
+    stream: TcpStream,
+    sender: &Sender<RemoteAction>,
+    shared: &Mutex<Shared>,
+    stop: &AtomicBool,
+    token: &str,
+) -> Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_millis(
+        stream_deck::HTTP_HEAD_TIMEOUT_MS,
+    )))?;
+    stream.set_write_timeout(Some(Duration::from_millis(stream_deck::WRITE_TIMEOUT_MS)))?;
+    // Plain HTTP requests are answered by the REST endpoint (see http_api.rs)
+    if !http_api::is_websocket(&stream)? {
+        let state = match shared.lock() {
+            Ok(shared) => shared.state.clone(),
+            Err(_) => return Ok(()),
+        };
+        return http_api::serve(stream, sender, &state, token);
+    }
+    let mut socket = tungstenite::accept(stream)
+        .map_err(|e| anyhow::anyhow!("WebSocket handshake failed: {}", e))?;
+    // Reads time out so state changes are sent while the client is quiet
+    socket
+        .get_mut()
+        .set_read_timeout(Some(Duration::from_millis(stream_deck::CLIENT_POLL_MS)))?;
+
+    send(
+        &mut socket,
+        &ServerMessage::Hello {
+            app: "RustFrame",
+            version: env!("CARGO_PKG_VERSION"),
+            authentication: true,
+            actions: RemoteAction::all()
+                .into_iter()
+                .map(|a| ActionInfo {
+                    id: a.id(),
+                    label: a.label(),
+                })
+                .collect(),
+        },
+    )?;
+
+    let mut identified = false;
+    let mut sent_revision = None;
+    let identify_deadline =
+        Instant::now() + Duration::from_millis(stream_deck::IDENTIFY_TIMEOUT_MS);
+    while !stop.load(Ordering::Relaxed) {
+        if !identified && Instant::now() >= identify_deadline {
+            let message = "Identify timed out".to_string();
+            warn!("Stream Deck client rejected: {}", message);
+            send(&mut socket, &ServerMessage::Error { message })?;
+            let _ = socket.close(None);
+            break;
+        }
+
+        let (state, revision) = match shared.lock() {
+            Ok(shared) => (shared.state.clone(), shared.revision),
+            Err(_) => break,
+        };
+        if identified && sent_revision != Some(revision) {
+            send(&mut socket, &ServerMessage::State(&state))?;
+            sent_revision = Some(revision);
+        }
+
+        match socket.read() {
+            Ok(Message::Text(text)) if !identified => match parse_identify(&text, token) {
+                Ok(()) => {
+                    identified = true;
+                    send(&mut socket, &ServerMessage::Identified)?;
+                }
+                Err(message) => {
+                    warn!("Stream Deck client rejected: {}", message);
+                    send(&mut socket, &ServerMessage::Error { message })?;
+                    let _ = socket.close(None);
+                    break;
+                }
+            },
+            Ok(Message::Text(text)) => match parse_action(&text) {
+                Ok(action) => {
+                    info!("Stream Deck action: {:?}", action);
+                    let _ = sender.send(action);
+                }
+                Err(message) => send(&mut socket, &ServerMessage::Error { message })?,
+            },
+            Ok(Message::Close(_)) => break,
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => break,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(())
+}
+
+fn send(socket: &mut WebSocket<TcpStream>, message: &ServerMessage) -> Result<()> {
+    let text = serde_json::to_string(message)?;
+    socket.send(Message::text(text))?;
+    Ok(())
+}
+
+/// Check the first client message ({"identify": "<token>"})
+fn parse_identify(text: &str, token: &str) -> Result<(), String> {
+    let value: serde_json::Value =
+        serde_json::from_str(text).map_err(|e| format!("Invalid JSON: {}", e))?;
+    match value.get("identify").and_then(|t| t.as_str()) {
+        Some(sent) if token_matches(sent, token) => Ok(()),
+        Some(_) => Err("Wrong token".to_string()),
+        None => Err("Identify first: {\"identify\": \"<token>\"}".to_string()),
+    }
+}
+
+/// Random token for new installations, from the system's cryptographic
+/// random number generator (empty if it fails - then no client is accepted
+/// and the next start tries again)
+#[cfg(windows)]
+pub fn new_token() -> String {
+    use windows::Win32::Security::Cryptography::{
+        BCryptGenRandom, BCRYPT_USE_SYSTEM_PREFERRED_RNG,
+    };
+
+    let mut bytes = [0u8; stream_deck::TOKEN_BYTES];
+    let status = unsafe { BCryptGenRandom(None, &mut bytes, BCRYPT_USE_SYSTEM_PREFERRED_RNG) };
+    if status.is_err() {
+        warn!("Failed to generate a remote token: {:?}", status);
+        return String::new();
+    }
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(not(windows))]
+pub fn new_token() -> String {
+    warn!("Generating a remote token is only supported on Windows");
+    String::new()
+}
+
+/// Whether a client sent the remote token (an empty token accepts nobody)
+/// Compares in constant time, so the answer time gives no hint how much of
+/// a guess was right.
+pub fn token_matches(sent: &str, token: &str) -> bool {
+    if token.is_empty() || sent.len() != token.len() {
+        return false;
+    }
+    sent.bytes()
+        .zip(token.bytes())
+        .fold(0u8, |difference, (a, b)| difference | (a ^ b))
+        == 0
+}
+
+/// Action from a client message ({"action": "scene_2"}), or the error to send back
+fn parse_action(text: &str) -> Result<RemoteAction, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(text).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let id = value
+        .get("action")
+        .and_then(|a| a.as_str())
+        .ok_or_else(|| "Expected {\"action\": \"<id>\"}".to_string())?;
+    RemoteAction::from_id(id).ok_or_else(|| format!("Unknown action '{}'", id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_must_match_exactly() {
+        assert!(token_matches("s3cret", "s3cret"));
+        assert!(!token_matches("s3creT", "s3cret"));
+        assert!(!token_matches("s3cre", "s3cret"));
+        assert!(!token_matches("s3cret!", "s3cret"));
+    }
+
+    #[test]
+    fn empty_token_never_matches() {
+        assert!(!token_matches("", ""));
+        assert!(!token_matches("anything", ""));
+    }
+}
@@ -0,0 +1,218 @@
+// test_pattern.rs - Synthetic Test Pattern Source
+//
+// A generated frame source that replaces the screen capture in the output:
+// - Color Bars:   SMPTE color bars (75% bars, castellations, -I / white / +Q
+//                 and the PLUGE bars) for checking color and levels
+// - Gradient:     a gray ramp over red, green and blue ramps (banding, gamma)
+// - Checkerboard: a checkerboard that moves every frame (motion, dropped frames)
+//
+// Every pattern carries a frame counter and the elapsed time, so dropped frames
+// and end-to-end latency can be read off the picture on the receiving side.
+//
+// The pattern is rendered at the size of the capture region. If
+// Windows.Graphics.Capture is unavailable (e.g. in some VMs), capture still
+// starts with the test pattern as the only source.
+
+use anyhow::Result;
+use std::time::{Duration, Instant};
+
+use crate::bitmap_font::{self, Canvas, TextStyle};
+use crate::constants::test_pattern;
+use crate::drawing;
+use crate::source::FrameSource;
+
+/// Which pattern is generated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatternKind {
+    ColorBars,
+    Gradient,
+    Checkerboard,
+}
+
+impl PatternKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            PatternKind::ColorBars => "Color Bars",
+            PatternKind::Gradient => "Gradient",
+            PatternKind::Checkerboard => "Checkerboard",
+        }
+    }
+
+    /// Next pattern, None after the last one (back to the screen capture)
+    pub fn next(kind: Option<Self>) -> Option<Self> {
+        match kind {
+            None => Some(PatternKind::ColorBars),
+            Some(PatternKind::ColorBars) => Some(PatternKind::Gradient),
+            Some(PatternKind::Gradient) => Some(PatternKind::Checkerboard),
+            Some(PatternKind::Checkerboard) => None,
+        }
+    }
+}
+
+/// Generates test pattern frames at a fixed rate
+pub struct TestPattern {
+    kind: PatternKind,
+    size: (u32, u32),
+    /// Frames generated so far (shown in the picture)
+    frame_count: u64,
+    started: Instant,
+    last_frame: Option<Instant>,
+}
+
+impl TestPattern {
+    pub fn new(kind: PatternKind) -> Self {
+        Self {
+            kind,
+            size: (0, 0),
+            frame_count: 0,
+            started: Instant::now(),
+            last_frame: None,
+        }
+    }
+
+    pub fn kind(&self) -> PatternKind {
+        self.kind
+    }
+
+    /// Render at this size from the next frame on (the capture region size)
+    pub fn set_size(&mut self, width: u32, height: u32) {
+        self.size = (width, height);
+    }
+
+    /// Fill the frame with the pattern (frame counter excluded)
+    fn draw_pattern(&self, canvas: &mut Canvas) {
+        let (width, height) = (canvas.width, canvas.height);
+        match self.kind {
+            PatternKind::ColorBars => {
+                // Heights: bars 67%, castellations 8%, bottom row 25%
+                let bars_bottom = height * 67 / 100;
+                let castellation_bottom = height * 75 / 100;
+                let bar = |i: i32| (i * width / 7, (i + 1) * width / 7);
+                for (i, &color) in test_pattern::BARS.iter().enumerate() {
+                    let (x0, x1) = bar(i as i32);
+                    drawing::fill_rect(canvas, x0, 0, x1 - x0, bars_bottom, color);
+                }
+                for (i, &color) in test_pattern::CASTELLATIONS.iter().enumerate() {
+                    let (x0, x1) = bar(i as i32);
+                    let h = castellation_bottom - bars_bottom;
+                    drawing::fill_rect(canvas, x0, bars_bottom, x1 - x0, h, color);
+                }
+
+                // Bottom row: -I, white, +Q and black over 5 bar widths,
+                // then the PLUGE bars (below black, black, above black)
+                let h = height - castellation_bottom;
+                let block = 5 * width / 7 / 4;
+                for (i, &color) in test_pattern::BOTTOM_ROW.iter().enumerate() {
+                    let x0 = i as i32 * block;
+                    let x1 = if i == 3 { bar(4).1 } else { x0 + block };
+                    drawing::fill_rect(canvas, x0, castellation_bottom, x1 - x0, h, color);
+                }
+                let (pluge_x0, pluge_x1) = bar(5);
+                for (i, &color) in test_pattern::PLUGE.iter().enumerate() {
+                    let x0 = pluge_x0 + i as i32 * (pluge_x1 - pluge_x0) / 3;
+                    let x1 = pluge_x0 + (i as i32 + 1) * (pluge_x1 - pluge_x0) / 3;
+                    drawing::fill_rect(canvas, x0, castellation_bottom, x1 - x0, h, color);
+                }
+                let (x0, x1) = bar(6);
+                let color = test_pattern::BOTTOM_ROW[3];
+                drawing::fill_rect(canvas, x0, castellation_bottom, x1 - x0, h, color);
+            }
+            PatternKind::Gradient => {
+                // Top half gray, bottom half red / green / blue ramps
+                let band = (height / 2 / 3).max(1);
+                for y in 0..height {
+                    let shift = if y < height / 2 {
+                        None
+                    } else {
+                        Some(16 - 8 * ((y - height / 2) / band).min(2) as u32)
+                    };
+                    let row = (y * width) as usize;
+                    for x in 0..width {
+                        let level = (x * 255 / (width - 1).max(1)) as u32;
+                        canvas.pixels[row + x as usize] = match shift {
+                            None => 0xFF000000 | level << 16 | level << 8 | level,
+                            Some(shift) => 0xFF000000 | level << shift,
+                        };
+                    }
+                }
+            }
+            PatternKind::Checkerboard => {
+                let square = test_pattern::SQUARE_SIZE;
+                let offset = (self.frame_count as i32 * test_pattern::CHECKER_SPEED) % (2 * square);
+                for y in 0..height {
+                    let row = (y * width) as usize;
+                    for x in 0..width {
+                        let dark = ((x + offset) / square + y / square) % 2 == 0;
+                        canvas.pixels[row + x as usize] = if dark {
+                            test_pattern::CHECKER_DARK
+                        } else {
+                            test_pattern::CHECKER_LIGHT
+                        };
+                    }
+                }
+            }
+        }
+    }
+
+    /// Frame counter and elapsed time in the top-left corner
+    fn draw_counter(&self, canvas: &mut Canvas) {
+        let elapsed = self.started.elapsed();
+        let millis = elapsed.as_millis();
+        let text = format!(
+            "FRAME {:06}  {:02}:{:02}:{:02}.{:03}",
+            self.frame_count,
+            millis / 3_600_000,
+            millis / 60_000 % 60,
+            millis / 1000 % 60,
+            millis % 1000
+        );
+
+        let scale = test_pattern::TEXT_SCALE;
+        let padding = test_pattern::TEXT_PADDING;
+        let margin = test_pattern::TEXT_MARGIN;
+        let box_w = bitmap_font::text_width(&text, scale) + padding * 2;
+        let box_h = bitmap_font::text_height(scale) + padding * 2;
+        drawing::fill_rect(
+            canvas,
+            margin,
+            margin,
+            box_w,
+            box_h,
+            test_pattern::TEXT_BACKGROUND,
+        );
+        let style = TextStyle {
+            color: test_pattern::TEXT,
+            scale,
+        };
+        bitmap_font::draw_text(canvas, margin + padding, margin + padding, &text, &style);
+    }
+}
+
+impl FrameSource for TestPattern {
+    fn read_frame(&mut self, frame: &mut Vec<u32>) -> Result<Option<(u32, u32)>> {
+        let (width, height) = self.size;
+        if width == 0 || height == 0 {
+            return Ok(None);
+        }
+
+        // Generate at the pattern frame rate (a new size is drawn right away)
+        let interval = Duration::from_secs_f64(1.0 / test_pattern::FPS as f64);
+        let resized = frame.len() != (width * height) as usize;
+        if !resized && self.last_frame.is_some_and(|t| t.elapsed() < interval) {
+            return Ok(None);
+        }
+        self.last_frame = Some(Instant::now());
+
+        frame.resize((width * height) as usize, 0);
+        let mut canvas = Canvas {
+            pixels: frame,
+            width: width as i32,
+            height: height as i32,
+        };
+        self.draw_pattern(&mut canvas);
+        self.draw_counter(&mut canvas);
+        self.frame_count += 1;
+
+        Ok(Some((width, height)))
+    }
+}
@@ -0,0 +1,420 @@
+// text.rs - Scalable Text for the Overlay Windows
+//
+// The layered windows (selection overlay help text, countdown, measure and
+// redact labels) used to draw with the 5x7 bitmap font, scaled by whole
+// pixels: blocky on a 4K monitor and limited to ASCII. Their text is now
+// rasterized by GDI with an outline font (Segoe UI), in any size and for any
+// Unicode text:
+//
+// 1. The text is drawn white on black into a 32-bit DIB with grayscale
+//    antialiasing (ClearType needs an opaque background, which a layered
+//    window does not have)
+// 2. The brightness of each pixel is the coverage of the glyphs there; it
+//    blends the text color into the premultiplied canvas
+//
+// Sizes are font heights in pixels; callers multiply them by the scale
+// factor of the window (`scaled`), so text has the same physical size on
+// every monitor.
+//
+// Text drawn into the captured frames (timer, pause screen, test pattern,
+// click labels) still uses bitmap_font.rs - it runs on every frame, where a
+// GDI round trip per string is not worth it.
+
+use crate::bitmap_font::Canvas;
+use crate::theme;
+
+/// Text drawing parameters
+pub struct TextStyle {
+    /// Premultiplied ARGB
+    pub color: u32,
+    /// Font height in pixels (already scaled for the window)
+    pub size: i32,
+    pub bold: bool,
+}
+
+/// `size` at 100 % scaling -> pixels at `scale` (1.5 at 150 %)
+pub fn scaled(size: i32, scale: f32) -> i32 {
+    ((size as f32 * scale).round() as i32).max(1)
+}
+
+/// Width and line height of `text` in pixels
+pub fn text_size(text: &str, style: &TextStyle) -> (i32, i32) {
+    raster::measure(text, style)
+}
+
+/// Draw one line of text with its top-left corner at (x, y)
+pub fn draw_text(canvas: &mut Canvas, x: i32, y: i32, text: &str, style: &TextStyle) {
+    raster::draw(canvas, x, y, text, style);
+}
+
+/// Blend `coverage` (0-255) of the premultiplied `color` over `dst`
+fn blend(dst: u32, color: u32, coverage: u32) -> u32 {
+    let channel = |shift: u32| ((color >> shift) & 0xFF) * coverage / 255;
+    let alpha = channel(24);
+    let over = |shift: u32| {
+        let d = (dst >> shift) & 0xFF;
+        (channel(shift) + d * (255 - alpha) / 255).min(255) << shift
+    };
+    over(24) | over(16) | over(8) | over(0)
+}
+
+/// Draw centered help text for the selection overlay
+/// Shows current settings state (cursor, border, mode)
+/// `scale`: scale factor of the overlay window (1.0 = 96 DPI)
+/// `focused` is the settings line (0 = cursor, 1 = border, 2 = mode) that has
+/// keyboard focus (Tab / Space), outlined so it can be found without a mouse
+#[allow(clippy::too_many_arguments)]
+pub fn draw_help_text(
+    pixels: &mut [u32],
+    width: i32,
+    height: i32,
+    scale: f32,
+    show_cursor: bool,
+    show_border: bool,
+    exclude_from_capture: bool,
+    focused: Option<usize>,
+) {
+    use crate::constants::text_box;
+
+    let mut canvas = Canvas {
+        pixels,
+        width,
+        height,
+    };
+    let colors = theme::palette();
+
+    // Format settings status text
+    let cursor_status = if show_cursor { "ON" } else { "OFF" };
+    let border_status = if show_border { "ON" } else { "OFF" };
+    let mode_status = if exclude_from_capture { "PROD" } else { "DEV" };
+
+    let cursor_line = format!("[C] Cursor: {}", cursor_status);
+    let border_line = format!("[B] Border: {}", border_status);
+    let mode_line = format!("[E] Mode: {}", mode_status);
+
+    // (text, color, title); the settings lines follow "ESC - Stop / Exit"
+    let lines: [(&str, u32, bool); 12] = [
+        ("RustFrame", colors.title, true),
+        ("", colors.text, false),
+        ("Drag borders / Shift+Arrows", colors.secondary, false),
+        ("Drag center / Arrows", colors.secondary, false),
+        ("", colors.text, false),
+        ("ENTER - Start capture", colors.text, false),
+        ("ESC - Stop / Exit", colors.text, false),
+        ("", colors.text, false),
+        ("[S] Settings", colors.secondary, false),
+        ("TAB - Focus   SPACE - Toggle", colors.secondary, false),
+        ("", colors.text, false),
+        ("by Salih Cantekin", colors.secondary, false),
+    ];
+    const SETTINGS_AFTER: usize = 6;
+
+    let title_style = |color| TextStyle {
+        color,
+        size: scaled(text_box::TITLE_SIZE, scale),
+        bold: true,
+    };
+    let line_style = |color| TextStyle {
+        color,
+        size: scaled(text_box::LINE_SIZE, scale),
+        bold: false,
+    };
+    let line_height = text_size("", &line_style(0)).1;
+    let title_height = text_size("", &title_style(0)).1;
+    let empty_height = scaled(text_box::EMPTY_LINE_HEIGHT, scale);
+    let focus_padding = scaled(text_box::FOCUS_PADDING, scale);
+
+    let total_height: i32 = lines
+        .iter()
+        .map(|(text, _, title)| match (text.is_empty(), title) {
+            (true, _) => empty_height,
+            (false, true) => title_height,
+            (false, false) => line_height,
+        })
+        .sum::<i32>()
+        + empty_height
+        + line_height * 3;
+
+    let mut y = (height - total_height) / 2;
+    for (i, &(text, color, title)) in lines.iter().enumerate() {
+        if text.is_empty() {
+            y += empty_height;
+            continue;
+        }
+
+        let style = if title {
+            title_style(color)
+        } else {
+            line_style(color)
+        };
+        let (text_w, text_h) = text_size(text, &style);
+        draw_text(&mut canvas, (width - text_w) / 2, y, text, &style);
+        y += text_h;
+
+        if i == SETTINGS_AFTER {
+            y += empty_height;
+
+            // Cursor and border are green if ON, red if OFF; mode is blue / yellow
+            let settings_lines = [
+                (
+                    &cursor_line,
+                    if show_cursor {
+                        colors.enabled
+                    } else {
+                        colors.disabled
+                    },
+                ),
+                (
+                    &border_line,
+                    if show_border {
+                        colors.enabled
+                    } else {
+                        colors.disabled
+                    },
+                ),
+                (
+                    &mode_line,
+                    if exclude_from_capture {
+                        colors.title
+                    } else {
+                        colors.warning
+                    },
+                ),
+            ];
+            for (index, (line, color)) in settings_lines.into_iter().enumerate() {
+                let style = line_style(color);
+                let (text_w, text_h) = text_size(line, &style);
+                let x = (width - text_w) / 2;
+                draw_text(&mut canvas, x, y, line, &style);
+
+                // Focus indicator: outline around the focused line
+                if focused == Some(index) {
+                    crate::drawing::draw_rect(
+                        &mut canvas,
+                        x - focus_padding,
+                        y - focus_padding / 2,
+                        x + text_w + focus_padding,
+                        y + text_h + focus_padding / 2,
+                        scaled(1, scale) as u32,
+                        colors.focus,
+                    );
+                }
+                y += text_h;
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+mod raster {
+    use std::cell::RefCell;
+
+    use windows::Win32::Foundation::{COLORREF, SIZE};
+    use windows::Win32::Graphics::Gdi::{
+        CreateCompatibleDC, CreateDIBSection, CreateFontW, DeleteDC, DeleteObject, GdiFlush,
+        GetTextExtentPoint32W, SelectObject, SetBkMode, SetTextColor, TextOutW,
+        ANTIALIASED_QUALITY, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, CLIP_DEFAULT_PRECIS,
+        DEFAULT_CHARSET, DIB_RGB_COLORS, FF_SWISS, FW_BOLD, FW_NORMAL, HDC, HFONT, HGDIOBJ,
+        OUT_TT_PRECIS, TRANSPARENT,
+    };
+
+    use super::{blend, TextStyle};
+    use crate::bitmap_font::Canvas;
+    use crate::constants::text;
+    use crate::utils::wide_string;
+
+    /// Memory DC and the fonts created so far (per thread, GDI objects are
+    /// not shared between threads)
+    struct Rasterizer {
+        dc: HDC,
+        fonts: Vec<((i32, bool), HFONT)>,
+    }
+
+    impl Rasterizer {
+        fn new() -> Option<Self> {
+            let dc = unsafe { CreateCompatibleDC(None) };
+            if dc.is_invalid() {
+                return None;
+            }
+            Some(Self {
+                dc,
+                fonts: Vec::new(),
+            })
+        }
+
+        /// Select the font of `style` into the DC
+        fn select_font(&mut self, style: &TextStyle) {
+            let key = (style.size.max(1), style.bold);
+            let font = match self.fonts.iter().find(|(k, _)| *k == key) {
+                Some(&(_, font)) => font,
+                None => {
+                    // The countdown asks for a new size with every region size
+                    if self.fonts.len() >= text::MAX_FONTS {
+                        let (_, oldest) = self.fonts.remove(0);
+                        unsafe {
+                            let _ = DeleteObject(HGDIOBJ(oldest.0));
+                        }
+                    }
+                    let name = wide_string(text::FONT_NAME);
+                    let font = unsafe {
+                        CreateFontW(
+                            -key.0,
+                            0,
+                            0,
+                            0,
+                            if key.1 { FW_BOLD.0 } else { FW_NORMAL.0 } as i32,
+                            0,
+                            0,
+                            0,
+                            DEFAULT_CHARSET,
+                            OUT_TT_PRECIS,
+                            CLIP_DEFAULT_PRECIS,
+                            ANTIALIASED_QUALITY,
+                            FF_SWISS.0 as u32,
+                            windows::core::PCWSTR(name.as_ptr()),
+                        )
+                    };
+                    self.fonts.push((key, font));
+                    font
+                }
+            };
+            unsafe {
+                SelectObject(self.dc, HGDIOBJ(font.0));
+            }
+        }
+
+        fn measure(&mut self, text: &[u16], style: &TextStyle) -> (i32, i32) {
+            self.select_font(style);
+            let mut size = SIZE::default();
+            unsafe {
+                let _ = GetTextExtentPoint32W(self.dc, text, &mut size);
+            }
+            (size.cx, size.cy)
+        }
+    }
+
+    impl Drop for Rasterizer {
+        fn drop(&mut self) {
+            unsafe {
+                for (_, font) in self.fonts.drain(..) {
+                    let _ = DeleteObject(HGDIOBJ(font.0));
+                }
+                let _ = DeleteDC(self.dc);
+            }
+        }
+    }
+
+    thread_local! {
+        static RASTERIZER: RefCell<Option<Rasterizer>> = const { RefCell::new(None) };
+    }
+
+    fn with<R>(f: impl FnOnce(&mut Rasterizer) -> R) -> Option<R> {
+        RASTERIZER.with(|r| {
+            let mut r = r.borrow_mut();
+            if r.is_none() {
+                *r = Rasterizer::new();
+            }
+            r.as_mut().map(f)
+        })
+    }
+
+    /// Text without the terminating zero of wide_string
+    fn utf16(text: &str) -> Vec<u16> {
+        text.encode_utf16().collect()
+    }
+
+    pub fn measure(text: &str, style: &TextStyle) -> (i32, i32) {
+        let wide = utf16(text);
+        with(|r| r.measure(&wide, style)).unwrap_or((0, style.size))
+    }
+
+    pub fn draw(canvas: &mut Canvas, x: i32, y: i32, text: &str, style: &TextStyle) {
+        let wide = utf16(text);
+        with(|r| {
+            let (width, height) = r.measure(&wide, style);
+            if width <= 0 || height <= 0 {
+                return;
+            }
+            unsafe {
+                let bmi = BITMAPINFO {
+                    bmiHeader: BITMAPINFOHEADER {
+                        biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                        biWidth: width,
+                        biHeight: -height, // Top-down
+                        biPlanes: 1,
+                        biBitCount: 32,
+                        biCompression: BI_RGB.0,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                };
+                // Zero-filled: black background
+                let mut bits: *mut std::ffi::c_void = std::ptr::null_mut();
+                let Ok(bitmap) =
+                    CreateDIBSection(Some(r.dc), &bmi, DIB_RGB_COLORS, &mut bits, None, 0)
+                else {
+                    return;
+                };
+                let old_bitmap = SelectObject(r.dc, bitmap.into());
+                SetBkMode(r.dc, TRANSPARENT);
+                SetTextColor(r.dc, COLORREF(0x00FF_FFFF));
+                let _ = TextOutW(r.dc, 0, 0, &wide);
+                let _ = GdiFlush();
+
+                let coverage =
+                    std::slice::from_raw_parts(bits as *const u32, (width * height) as usize);
+                for row in 0..height {
+                    let py = y + row;
+                    if py < 0 || py >= canvas.height {
+                        continue;
+                    }
+                    for col in 0..width {
+                        let px = x + col;
+                        if px < 0 || px >= canvas.width {
+                            continue;
+                        }
+                        // Grayscale antialiasing: all channels carry the coverage
+                        let c = coverage[(row * width + col) as usize] & 0xFF;
+                        if c == 0 {
+                            continue;
+                        }
+                        let idx = (py * canvas.width + px) as usize;
+                        if let Some(pixel) = canvas.pixels.get_mut(idx) {
+                            *pixel = blend(*pixel, style.color, c);
+                        }
+                    }
+                }
+
+                SelectObject(r.dc, old_bitmap);
+                let _ = DeleteObject(bitmap.into());
+            }
+        });
+    }
+}
+
+/// Without GDI the bitmap font stands in, scaled to roughly the same height
+#[cfg(not(windows))]
+mod raster {
+    use super::TextStyle;
+    use crate::bitmap_font::{self, Canvas};
+
+    fn bitmap_scale(style: &TextStyle) -> i32 {
+        (style.size / bitmap_font::text_height(1)).max(1)
+    }
+
+    pub fn measure(text: &str, style: &TextStyle) -> (i32, i32) {
+        let scale = bitmap_scale(style);
+        (
+            bitmap_font::text_width(text, scale),
+            bitmap_font::text_height(scale),
+        )
+    }
+
+    pub fn draw(canvas: &mut Canvas, x: i32, y: i32, text: &str, style: &TextStyle) {
+        let style = bitmap_font::TextStyle {
+            color: style.color,
+            scale: bitmap_scale(style),
+        };
+        bitmap_font::draw_text(canvas, x, y, text, &style);
+    }
+}
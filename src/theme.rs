@@ -0,0 +1,295 @@
+// theme.rs - Light / Dark Theme and Accent Color
+//
+// The colors of the selection overlay (border, corners, help text), the
+// click highlight labels and the settings dialog follow one palette:
+//
+//   "theme": "system",         // "light", "dark" or "high_contrast";
+//                              // system = the Windows app mode
+//   "accent_color": "#E81123"  // empty = RustFrame blue
+//
+// The accent color is used for the region border, the corner markers, the
+// help text frame and the title. Settings → Advanced sets both; the overlay
+// picks up the change right away, the settings dialog the next time it opens.
+//
+// "high_contrast" is white and yellow on black and ignores the accent color.
+// While a Windows contrast theme is on, every mode uses the colors of that
+// theme instead (see accessibility.rs).
+//
+// The palette is global (like the constants it replaces), so drawing code
+// reads it with `palette()` instead of having it passed through.
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+use crate::accessibility;
+use crate::constants::colors;
+
+/// Light or dark UI
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeMode {
+    /// Follow "Choose your app mode" in the Windows settings
+    #[default]
+    System,
+    Light,
+    Dark,
+    /// White and yellow on black
+    HighContrast,
+}
+
+impl ThemeMode {
+    pub const ALL: [ThemeMode; 4] = [
+        ThemeMode::System,
+        ThemeMode::Light,
+        ThemeMode::Dark,
+        ThemeMode::HighContrast,
+    ];
+
+    /// Name shown in the settings dialog
+    pub fn label(self) -> &'static str {
+        match self {
+            ThemeMode::System => "Same as Windows",
+            ThemeMode::Light => "Light",
+            ThemeMode::Dark => "Dark",
+            ThemeMode::HighContrast => "High contrast",
+        }
+    }
+}
+
+/// UI colors (ARGB)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    pub dark: bool,
+    /// Region border
+    pub border: u32,
+    /// Inside of the region while selecting
+    pub fill: u32,
+    /// Corner markers
+    pub corner: u32,
+    /// Help text box background and frame
+    pub text_bg: u32,
+    pub text_border: u32,
+    /// Main text, title, secondary text
+    pub text: u32,
+    pub title: u32,
+    pub secondary: u32,
+    /// Settings that are on / off, development mode
+    pub enabled: u32,
+    pub disabled: u32,
+    pub warning: u32,
+    /// Keyboard focus outline
+    pub focus: u32,
+    /// Settings dialog background and text
+    pub window_bg: u32,
+    pub window_text: u32,
+}
+
+impl Palette {
+    pub const DARK: Palette = Palette {
+        dark: true,
+        border: colors::BORDER,
+        fill: colors::FILL,
+        corner: colors::CORNER,
+        text_bg: colors::TEXT_BG,
+        text_border: colors::TEXT_BORDER,
+        text: colors::TEXT_WHITE,
+        title: colors::TEXT_BLUE,
+        secondary: colors::TEXT_GRAY,
+        enabled: colors::TEXT_GREEN,
+        disabled: colors::TEXT_RED,
+        warning: colors::TEXT_YELLOW,
+        focus: colors::FOCUS,
+        window_bg: colors::WINDOW_BG,
+        window_text: colors::TEXT_WHITE,
+    };
+
+    pub const LIGHT: Palette = Palette {
+        dark: false,
+        border: colors::BORDER,
+        fill: colors::FILL,
+        corner: colors::CORNER,
+        text_bg: colors::light::TEXT_BG,
+        text_border: colors::TEXT_BORDER,
+        text: colors::light::TEXT,
+        title: colors::light::TITLE,
+        secondary: colors::light::SECONDARY,
+        enabled: colors::light::ENABLED,
+        disabled: colors::light::DISABLED,
+        warning: colors::light::WARNING,
+        focus: colors::light::TEXT,
+        window_bg: colors::light::WINDOW_BG,
+        window_text: colors::light::TEXT,
+    };
+
+    pub const HIGH_CONTRAST: Palette = Palette {
+        dark: true,
+        border: colors::high_contrast::ACCENT,
+        fill: colors::FILL,
+        corner: colors::high_contrast::ACCENT,
+        text_bg: colors::high_contrast::BACKGROUND,
+        text_border: colors::high_contrast::ACCENT,
+        text: colors::high_contrast::TEXT,
+        title: colors::high_contrast::ACCENT,
+        secondary: colors::high_contrast::SECONDARY,
+        enabled: colors::high_contrast::ENABLED,
+        disabled: colors::high_contrast::DISABLED,
+        warning: colors::high_contrast::ACCENT,
+        focus: colors::high_contrast::TEXT,
+        window_bg: colors::high_contrast::BACKGROUND,
+        window_text: colors::high_contrast::TEXT,
+    };
+
+    /// The palette of `mode` with `accent` (0xRRGGBB) instead of the blue
+    fn new(mode: ThemeMode, accent: Option<u32>) -> Self {
+        if accessibility::high_contrast() {
+            return system_contrast();
+        }
+        let dark = match mode {
+            ThemeMode::System => system_dark(),
+            ThemeMode::Light => false,
+            ThemeMode::Dark => true,
+            ThemeMode::HighContrast => return Self::HIGH_CONTRAST,
+        };
+        let mut palette = if dark { Self::DARK } else { Self::LIGHT };
+        if let Some(accent) = accent {
+            let accent = 0xFF00_0000 | accent;
+            palette.border = accent;
+            palette.text_border = accent;
+            palette.corner = mix(accent, 0xFFFF_FFFF, 30);
+            // Titles stay readable on the light background
+            palette.title = if dark {
+                palette.corner
+            } else {
+                mix(accent, 0xFF00_0000, 20)
+            };
+        }
+        palette
+    }
+}
+
+static PALETTE: RwLock<Palette> = RwLock::new(Palette::DARK);
+
+/// The current palette
+pub fn palette() -> Palette {
+    *PALETTE.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Switch to `mode` and `accent` ("#RRGGBB", empty = RustFrame blue);
+/// returns true when the palette changed
+pub fn apply(mode: ThemeMode, accent: &str) -> bool {
+    let accent_rgb = if accent.trim().is_empty() {
+        None
+    } else {
+        let parsed = parse_accent(accent);
+        if parsed.is_none() {
+            warn!("Invalid accent color '{}' - using the default", accent);
+        }
+        parsed
+    };
+    let palette = Palette::new(mode, accent_rgb);
+    let mut current = PALETTE.write().unwrap_or_else(|e| e.into_inner());
+    if *current == palette {
+        return false;
+    }
+    *current = palette;
+    info!(
+        "Theme: {} ({}), accent {}",
+        mode.label(),
+        if palette.dark { "dark" } else { "light" },
+        if accent_rgb.is_some() {
+            accent.trim()
+        } else {
+            "default"
+        }
+    );
+    true
+}
+
+/// Parse "#RRGGBB" (the # is optional) into 0xRRGGBB
+pub fn parse_accent(text: &str) -> Option<u32> {
+    let hex = text.trim().trim_start_matches('#');
+    if hex.len() != 6 {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok()
+}
+
+/// Blend `percent` % of `other` into `color` (ARGB, alpha of `color` kept)
+fn mix(color: u32, other: u32, percent: u32) -> u32 {
+    let channel = |shift: u32| {
+        let a = (color >> shift) & 0xFF;
+        let b = (other >> shift) & 0xFF;
+        ((a * (100 - percent) + b * percent) / 100) << shift
+    };
+    (color & 0xFF00_0000) | channel(16) | channel(8) | channel(0)
+}
+
+/// Whether Windows apps use the dark mode (dark if unknown)
+#[cfg(windows)]
+fn system_dark() -> bool {
+    use windows::core::w;
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD};
+
+    let mut value = 0u32;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    let result = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            w!("Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize"),
+            w!("AppsUseLightTheme"),
+            RRF_RT_REG_DWORD,
+            None,
+            Some(&mut value as *mut u32 as *mut std::ffi::c_void),
+            Some(&mut size),
+        )
+    };
+    result.is_err() || value == 0
+}
+
+#[cfg(not(windows))]
+fn system_dark() -> bool {
+    true
+}
+
+/// The colors of the Windows contrast theme
+#[cfg(windows)]
+fn system_contrast() -> Palette {
+    use windows::Win32::Graphics::Gdi::{
+        GetSysColor, COLOR_GRAYTEXT, COLOR_HIGHLIGHT, COLOR_HOTLIGHT, COLOR_WINDOW,
+        COLOR_WINDOWTEXT, SYS_COLOR_INDEX,
+    };
+
+    // COLORREF (0x00BBGGRR) -> opaque ARGB
+    let color = |index: SYS_COLOR_INDEX| {
+        let bgr = unsafe { GetSysColor(index) };
+        0xFF00_0000 | ((bgr & 0xFF) << 16) | (bgr & 0xFF00) | ((bgr >> 16) & 0xFF)
+    };
+    let background = color(COLOR_WINDOW);
+    let text = color(COLOR_WINDOWTEXT);
+    let highlight = color(COLOR_HIGHLIGHT);
+    let brightness = ((background >> 16) & 0xFF) + ((background >> 8) & 0xFF) + (background & 0xFF);
+    Palette {
+        dark: brightness < 3 * 128,
+        border: highlight,
+        fill: colors::FILL,
+        corner: highlight,
+        text_bg: background,
+        text_border: highlight,
+        text,
+        title: color(COLOR_HOTLIGHT),
+        secondary: color(COLOR_GRAYTEXT),
+        // Contrast themes have no green and red - the help text says ON / OFF
+        enabled: text,
+        disabled: color(COLOR_GRAYTEXT),
+        warning: color(COLOR_HOTLIGHT),
+        focus: highlight,
+        window_bg: background,
+        window_text: text,
+    }
+}
+
+#[cfg(not(windows))]
+fn system_contrast() -> Palette {
+    Palette::HIGH_CONTRAST
+}
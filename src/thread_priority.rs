@@ -0,0 +1,120 @@
+// thread_priority.rs - Thread Priority and CPU Affinity
+//
+// When the captured application saturates the CPU, the thread that copies
+// the captured frames and presents them has to compete with it, and frame
+// pacing suffers. Settings → Advanced can raise the priority of that thread
+// and pin it to some cores, leaving the others to the application being
+// demoed:
+//
+//   "thread_priority": "above_normal",
+//   "cpu_cores": [2, 3]
+//
+// In the app this is the event loop thread (capture copy, compositing,
+// presenting); `RustFrame record` applies the same settings to its recording
+// thread. Media Foundation runs its encoder on its own worker threads, which
+// are not affected.
+//
+// Cores are logical processor numbers starting at 0; an empty list (or only
+// cores this computer does not have) lets Windows pick.
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+/// Scheduling priority of the capture thread
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThreadPriority {
+    #[default]
+    Normal,
+    AboveNormal,
+    Highest,
+}
+
+impl ThreadPriority {
+    pub const ALL: [ThreadPriority; 3] = [
+        ThreadPriority::Normal,
+        ThreadPriority::AboveNormal,
+        ThreadPriority::Highest,
+    ];
+
+    /// Name shown in the settings dialog
+    pub fn label(self) -> &'static str {
+        match self {
+            ThreadPriority::Normal => "Normal",
+            ThreadPriority::AboveNormal => "Above normal",
+            ThreadPriority::Highest => "Highest",
+        }
+    }
+}
+
+/// Parse a core list like "2, 3" (invalid entries are skipped)
+pub fn parse_cores(text: &str) -> Vec<u32> {
+    let mut cores: Vec<u32> = text
+        .split(',')
+        .filter_map(|core| core.trim().parse().ok())
+        .collect();
+    cores.sort_unstable();
+    cores.dedup();
+    cores
+}
+
+/// Set the priority and the cores of the calling thread
+#[cfg(windows)]
+pub fn apply_to_current_thread(priority: ThreadPriority, cores: &[u32]) {
+    use windows::Win32::System::Threading::{
+        GetCurrentProcess, GetCurrentThread, GetProcessAffinityMask, SetThreadAffinityMask,
+        SetThreadPriority, THREAD_PRIORITY, THREAD_PRIORITY_ABOVE_NORMAL,
+        THREAD_PRIORITY_HIGHEST, THREAD_PRIORITY_NORMAL,
+    };
+
+    let level: THREAD_PRIORITY = match priority {
+        ThreadPriority::Normal => THREAD_PRIORITY_NORMAL,
+        ThreadPriority::AboveNormal => THREAD_PRIORITY_ABOVE_NORMAL,
+        ThreadPriority::Highest => THREAD_PRIORITY_HIGHEST,
+    };
+
+    unsafe {
+        let thread = GetCurrentThread();
+        if let Err(e) = SetThreadPriority(thread, level) {
+            warn!("Failed to set the thread priority: {}", e);
+        }
+
+        // Only cores the process may run on (all of them unless restricted)
+        let (mut process_mask, mut system_mask) = (0usize, 0usize);
+        if GetProcessAffinityMask(GetCurrentProcess(), &mut process_mask, &mut system_mask)
+            .is_err()
+        {
+            warn!("Failed to read the process affinity mask");
+            return;
+        }
+        let wanted = cores
+            .iter()
+            .filter(|&&core| core < usize::BITS)
+            .fold(0usize, |mask, &core| mask | (1 << core));
+        let mask = match wanted & process_mask {
+            0 => {
+                if !cores.is_empty() {
+                    warn!("None of the cores {:?} is available - using all cores", cores);
+                }
+                process_mask
+            }
+            mask => mask,
+        };
+        if SetThreadAffinityMask(thread, mask) == 0 {
+            warn!("Failed to set the thread affinity");
+            return;
+        }
+    }
+    info!(
+        "Capture thread priority: {}, cores: {}",
+        priority.label(),
+        if cores.is_empty() {
+            "all".to_string()
+        } else {
+            format!("{:?}", cores)
+        }
+    );
+}
+
+#[cfg(not(windows))]
+pub fn apply_to_current_thread(_priority: ThreadPriority, _cores: &[u32]) {}
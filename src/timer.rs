@@ -0,0 +1,304 @@
+// timer.rs - On-Screen Timer / Stopwatch
+//
+// A count-up (stopwatch) or count-down timer composited into the output
+// frames, for timed exercises and exam-style demos.
+//
+// Start/pause and reset are global hotkeys; mode, position and size are
+// cycled from the tray menu and stored in settings.json.
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use crate::bitmap_font::{self, Canvas, TextStyle};
+use crate::constants::timer;
+use crate::drawing;
+
+/// Corner of the frame the timer is drawn in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimerCorner {
+    TopLeft,
+    #[default]
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl TimerCorner {
+    pub fn label(self) -> &'static str {
+        match self {
+            TimerCorner::TopLeft => "Top Left",
+            TimerCorner::TopRight => "Top Right",
+            TimerCorner::BottomLeft => "Bottom Left",
+            TimerCorner::BottomRight => "Bottom Right",
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            TimerCorner::TopLeft => TimerCorner::TopRight,
+            TimerCorner::TopRight => TimerCorner::BottomRight,
+            TimerCorner::BottomRight => TimerCorner::BottomLeft,
+            TimerCorner::BottomLeft => TimerCorner::TopLeft,
+        }
+    }
+}
+
+/// Text size of the timer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimerSize {
+    Small,
+    #[default]
+    Medium,
+    Large,
+}
+
+impl TimerSize {
+    pub fn label(self) -> &'static str {
+        match self {
+            TimerSize::Small => "Small",
+            TimerSize::Medium => "Medium",
+            TimerSize::Large => "Large",
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            TimerSize::Small => TimerSize::Medium,
+            TimerSize::Medium => TimerSize::Large,
+            TimerSize::Large => TimerSize::Small,
+        }
+    }
+
+    /// Bitmap font scale for this size
+    fn scale(self) -> i32 {
+        match self {
+            TimerSize::Small => 2,
+            TimerSize::Medium => 4,
+            TimerSize::Large => 7,
+        }
+    }
+}
+
+/// Count up from zero, or down from a preset duration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerMode {
+    CountUp,
+    CountDown(Duration),
+}
+
+impl TimerMode {
+    pub fn label(self) -> String {
+        match self {
+            TimerMode::CountUp => "Stopwatch".to_string(),
+            TimerMode::CountDown(d) => format!("Countdown {} min", d.as_secs() / 60),
+        }
+    }
+
+    /// Mode stored in settings.json as a countdown length (0 = stopwatch)
+    pub fn from_seconds(seconds: u64) -> Self {
+        match seconds {
+            0 => TimerMode::CountUp,
+            s => TimerMode::CountDown(Duration::from_secs(s)),
+        }
+    }
+
+    /// Countdown length in seconds (0 = stopwatch)
+    pub fn seconds(self) -> u64 {
+        match self {
+            TimerMode::CountUp => 0,
+            TimerMode::CountDown(d) => d.as_secs(),
+        }
+    }
+}
+
+/// Timer state for the current session
+pub struct OverlayTimer {
+    visible: bool,
+    mode: TimerMode,
+    corner: TimerCorner,
+    size: TimerSize,
+    /// When the current run started (None while paused)
+    running_since: Option<Instant>,
+    /// Time accumulated by previous runs (before the last pause)
+    elapsed_before: Duration,
+    /// Whole seconds currently shown - a change means the frame must be recomposited
+    shown_seconds: u64,
+    /// Bumped on every visible change so the renderer knows when to recomposite
+    revision: u64,
+}
+
+impl OverlayTimer {
+    pub fn new(mode: TimerMode, corner: TimerCorner, size: TimerSize) -> Self {
+        Self {
+            visible: false,
+            mode,
+            corner,
+            size,
+            running_since: None,
+            elapsed_before: Duration::ZERO,
+            shown_seconds: 0,
+            revision: 0,
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running_since.is_some()
+    }
+
+    /// Start or pause the timer (starting also makes it visible)
+    pub fn toggle_running(&mut self) {
+        match self.running_since.take() {
+            Some(since) => self.elapsed_before += since.elapsed(),
+            None => {
+                self.visible = true;
+                self.running_since = Some(Instant::now());
+            }
+        }
+        self.revision += 1;
+    }
+
+    /// Stop the timer, reset it to its initial value and hide it
+    pub fn reset(&mut self) {
+        self.visible = false;
+        self.running_since = None;
+        self.elapsed_before = Duration::ZERO;
+        self.shown_seconds = 0;
+        self.revision += 1;
+    }
+
+    pub fn mode(&self) -> TimerMode {
+        self.mode
+    }
+
+    /// Cycle Stopwatch -> Countdown presets -> Stopwatch (resets the timer)
+    pub fn cycle_mode(&mut self) {
+        let presets = timer::COUNTDOWN_PRESETS.map(Duration::from_secs);
+        self.set_mode(match self.mode {
+            TimerMode::CountUp => TimerMode::CountDown(presets[0]),
+            TimerMode::CountDown(d) => match presets.iter().position(|&p| p == d) {
+                Some(i) if i + 1 < presets.len() => TimerMode::CountDown(presets[i + 1]),
+                _ => TimerMode::CountUp,
+            },
+        });
+    }
+
+    /// Switch the mode (resets the timer if it changes)
+    pub fn set_mode(&mut self, mode: TimerMode) {
+        if self.mode != mode {
+            self.mode = mode;
+            self.reset();
+        }
+    }
+
+    pub fn corner(&self) -> TimerCorner {
+        self.corner
+    }
+
+    pub fn cycle_corner(&mut self) {
+        self.set_corner(self.corner.next());
+    }
+
+    pub fn set_corner(&mut self, corner: TimerCorner) {
+        if self.corner != corner {
+            self.corner = corner;
+            self.revision += 1;
+        }
+    }
+
+    pub fn size(&self) -> TimerSize {
+        self.size
+    }
+
+    pub fn cycle_size(&mut self) {
+        self.set_size(self.size.next());
+    }
+
+    pub fn set_size(&mut self, size: TimerSize) {
+        if self.size != size {
+            self.size = size;
+            self.revision += 1;
+        }
+    }
+
+    /// Total running time
+    fn elapsed(&self) -> Duration {
+        self.elapsed_before + self.running_since.map_or(Duration::ZERO, |s| s.elapsed())
+    }
+
+    /// Seconds to display (counts down to zero in countdown mode)
+    fn display_seconds(&self) -> u64 {
+        let elapsed = self.elapsed().as_secs();
+        match self.mode {
+            TimerMode::CountUp => elapsed,
+            TimerMode::CountDown(total) => total.as_secs().saturating_sub(elapsed),
+        }
+    }
+
+    /// Call once per loop iteration - bumps the revision when the shown value changes
+    pub fn tick(&mut self) {
+        if !self.visible {
+            return;
+        }
+        let seconds = self.display_seconds();
+        if seconds != self.shown_seconds {
+            self.shown_seconds = seconds;
+            self.revision += 1;
+        }
+    }
+
+    /// Revision counter - changes whenever the rendered result would change
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Draw the timer box into a captured frame (no-op when hidden)
+    pub fn draw(&self, canvas: &mut Canvas) {
+        if !self.visible {
+            return;
+        }
+
+        let seconds = self.display_seconds();
+        let text = if seconds >= 3600 {
+            format!(
+                "{}:{:02}:{:02}",
+                seconds / 3600,
+                (seconds / 60) % 60,
+                seconds % 60
+            )
+        } else {
+            format!("{:02}:{:02}", seconds / 60, seconds % 60)
+        };
+        let expired = matches!(self.mode, TimerMode::CountDown(_)) && seconds == 0;
+
+        let scale = self.size.scale();
+        let padding = timer::PADDING * scale / 2;
+        let box_w = bitmap_font::text_width(&text, scale) + padding * 2;
+        let box_h = bitmap_font::text_height(scale) + padding * 2;
+
+        let x = match self.corner {
+            TimerCorner::TopLeft | TimerCorner::BottomLeft => timer::MARGIN,
+            TimerCorner::TopRight | TimerCorner::BottomRight => {
+                canvas.width - box_w - timer::MARGIN
+            }
+        };
+        let y = match self.corner {
+            TimerCorner::TopLeft | TimerCorner::TopRight => timer::MARGIN,
+            TimerCorner::BottomLeft | TimerCorner::BottomRight => {
+                canvas.height - box_h - timer::MARGIN
+            }
+        };
+
+        drawing::fill_rect(canvas, x, y, box_w, box_h, timer::BACKGROUND);
+        let style = TextStyle {
+            color: if expired { timer::EXPIRED } else { timer::TEXT },
+            scale,
+        };
+        bitmap_font::draw_text(canvas, x + padding, y + padding, &text, &style);
+    }
+}
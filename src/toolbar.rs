@@ -0,0 +1,433 @@
+// toolbar.rs - Control Toolbar During a Capture
+//
+// A small bar on the presenter's screen for the actions otherwise reached
+// with hotkeys or the tray menu:
+//
+//   [grip] [Stop] [Pause] [Screenshot] [Annotate] [Mute]  ● 12:34
+//
+// - Stop asks before ending the capture (like ESC)
+// - Pause shows the pause screen in the output, or resumes
+// - Screenshot saves the current output frame
+// - Annotate switches draw mode on and off
+// - Mute hides notification toasts from the capture (RustFrame captures no
+//   audio, notifications are what could "make noise" in front of viewers)
+// The timer shows how long the capture has been running.
+//
+// Where it is shown follows settings.toolbar: attached to the capture border
+// (centered above the region, below it when the monitor has no room above)
+// or floating at the top of the monitor. Dragging the grip or the timer
+// moves the toolbar freely for the rest of the capture.
+//
+// The toolbar is a layered popup (see PopupWindow in window_manager.rs):
+// excluded from capture and never activated, so clicking it does not take the
+// focus away from the presented application.
+
+use crate::app_settings::ToolbarPlacement;
+use crate::bitmap_font::Canvas;
+use crate::capture::CaptureRect;
+use crate::constants::toolbar;
+use crate::drawing;
+use crate::text::{self, scaled, TextStyle};
+use crate::theme;
+
+/// Toolbar buttons from left to right
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolbarButton {
+    Stop,
+    Pause,
+    Screenshot,
+    Annotate,
+    Mute,
+}
+
+impl ToolbarButton {
+    pub const ALL: [ToolbarButton; 5] = [
+        ToolbarButton::Stop,
+        ToolbarButton::Pause,
+        ToolbarButton::Screenshot,
+        ToolbarButton::Annotate,
+        ToolbarButton::Mute,
+    ];
+}
+
+/// State shown on the toolbar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToolbarView {
+    /// Seconds since the capture started
+    pub elapsed_secs: u64,
+    pub paused: bool,
+    pub annotating: bool,
+    /// Notifications are hidden from the capture
+    pub muted: bool,
+}
+
+/// What is on screen, to skip redrawing an unchanged toolbar
+#[derive(Clone, Copy, PartialEq)]
+struct Shown {
+    position: (i32, i32),
+    scale: f32,
+    view: ToolbarView,
+    hovered: Option<ToolbarButton>,
+    pressed: Option<ToolbarButton>,
+}
+
+/// Position, drag and hover state of the toolbar
+pub struct Toolbar {
+    /// Top-left corner after the toolbar was dragged (screen coordinates)
+    moved: Option<(i32, i32)>,
+    /// Last cursor position inside the toolbar
+    cursor: (f64, f64),
+    /// Cursor position inside the toolbar where a drag started
+    drag: Option<(i32, i32)>,
+    hovered: Option<ToolbarButton>,
+    pressed: Option<ToolbarButton>,
+    /// None while hidden
+    shown: Option<Shown>,
+}
+
+impl Toolbar {
+    pub fn new() -> Self {
+        Self {
+            moved: None,
+            cursor: (0.0, 0.0),
+            drag: None,
+            hovered: None,
+            pressed: None,
+            shown: None,
+        }
+    }
+
+    /// Forget where the toolbar was dragged (a new capture starts)
+    pub fn reset(&mut self) {
+        self.moved = None;
+        self.drag = None;
+        self.pressed = None;
+    }
+
+    /// Width and height in pixels at `scale`
+    pub fn size(scale: f32) -> (i32, i32) {
+        let width = toolbar::PADDING * 3
+            + toolbar::GRIP_WIDTH
+            + Self::buttons_width()
+            + toolbar::TIMER_WIDTH;
+        (scaled(width, scale), scaled(toolbar::HEIGHT, scale))
+    }
+
+    /// Screen position of the top-left corner
+    /// `region` is the capture region with its border, `monitor` its monitor.
+    /// The placed toolbar stays on the monitor; a dragged one stays where it
+    /// was put.
+    pub fn position(
+        &self,
+        region: CaptureRect,
+        monitor: CaptureRect,
+        placement: ToolbarPlacement,
+        scale: f32,
+    ) -> (i32, i32) {
+        if let Some(moved) = self.moved {
+            return moved;
+        }
+
+        let (width, height) = Self::size(scale);
+        let gap = scaled(toolbar::GAP, scale);
+        let monitor_bottom = monitor.y + monitor.height as i32;
+        let (x, y) = match placement {
+            ToolbarPlacement::Border => {
+                let x = region.x + (region.width as i32 - width) / 2;
+                let above = region.y - height - gap;
+                let below = region.y + region.height as i32 + gap;
+                let y = if above >= monitor.y {
+                    above
+                } else if below + height <= monitor_bottom {
+                    below
+                } else {
+                    // Fullscreen region: inside its top edge
+                    region.y + gap
+                };
+                (x, y)
+            }
+            ToolbarPlacement::Floating | ToolbarPlacement::Off => (
+                monitor.x + (monitor.width as i32 - width) / 2,
+                monitor.y + gap,
+            ),
+        };
+
+        let max_x = monitor.x + monitor.width as i32 - width;
+        let max_y = monitor_bottom - height;
+        (x.min(max_x).max(monitor.x), y.min(max_y).max(monitor.y))
+    }
+
+    /// Left button pressed: presses the button under the cursor or starts
+    /// dragging the toolbar
+    pub fn press(&mut self, scale: f32) {
+        let (x, y) = self.cursor;
+        self.pressed = Self::button_at(x, y, scale);
+        if self.pressed.is_none() {
+            self.drag = Some((x as i32, y as i32));
+        }
+    }
+
+    /// Left button released: the clicked button, if the press started on the
+    /// button under the cursor
+    pub fn release(&mut self, scale: f32) -> Option<ToolbarButton> {
+        let (x, y) = self.cursor;
+        self.drag = None;
+        self.pressed
+            .take()
+            .filter(|&button| Self::button_at(x, y, scale) == Some(button))
+    }
+
+    /// Cursor moved to (x, y) inside the toolbar; `screen` is the cursor's
+    /// screen position, used to move the toolbar while dragging
+    pub fn move_cursor(&mut self, x: f64, y: f64, screen: Option<(i32, i32)>, scale: f32) {
+        self.cursor = (x, y);
+        self.hovered = Self::button_at(x, y, scale);
+        if let (Some((dx, dy)), Some((sx, sy))) = (self.drag, screen) {
+            self.moved = Some((sx - dx, sy - dy));
+        }
+    }
+
+    /// Cursor left the toolbar
+    pub fn leave(&mut self) {
+        self.hovered = None;
+    }
+
+    /// Whether the toolbar has to be drawn (again) at `position`
+    /// Remembers the state as shown.
+    pub fn needs_redraw(&mut self, position: (i32, i32), view: ToolbarView, scale: f32) -> bool {
+        let shown = Shown {
+            position,
+            scale,
+            view,
+            hovered: self.hovered,
+            pressed: self.pressed,
+        };
+        if self.shown == Some(shown) {
+            return false;
+        }
+        self.shown = Some(shown);
+        true
+    }
+
+    /// The toolbar is being hidden; returns true if it was visible
+    pub fn hide(&mut self) -> bool {
+        self.drag = None;
+        self.hovered = None;
+        self.pressed = None;
+        self.shown.take().is_some()
+    }
+
+    /// Draw the toolbar into the popup's canvas (premultiplied ARGB)
+    pub fn draw(&self, canvas: &mut Canvas, view: &ToolbarView, scale: f32) {
+        let palette = theme::palette();
+        let background = palette.text_bg | 0xFF00_0000;
+        let line = scaled(1, scale) as u32;
+
+        // Opaque everywhere, so every pixel takes clicks
+        canvas.pixels.fill(background);
+        drawing::draw_rect(
+            canvas,
+            0,
+            0,
+            canvas.width - 1,
+            canvas.height - 1,
+            line,
+            palette.text_border,
+        );
+
+        // Grip: two columns of three dots
+        let dot = scaled(2, scale);
+        let grip_x =
+            scaled(toolbar::PADDING, scale) + (scaled(toolbar::GRIP_WIDTH, scale) - dot * 3) / 2;
+        for column in 0..2 {
+            for row in -1..=1 {
+                drawing::fill_rect(
+                    canvas,
+                    grip_x + column * dot * 2,
+                    canvas.height / 2 + row * dot * 2 - dot / 2,
+                    dot,
+                    dot,
+                    palette.secondary,
+                );
+            }
+        }
+
+        for button in ToolbarButton::ALL {
+            let (x, y, size) = Self::button_rect(button, scale);
+            if self.pressed == Some(button) {
+                drawing::fill_rect(canvas, x, y, size, size, palette.secondary);
+            } else if self.hovered == Some(button) {
+                drawing::draw_rect(
+                    canvas,
+                    x,
+                    y,
+                    x + size - 1,
+                    y + size - 1,
+                    line,
+                    palette.secondary,
+                );
+            }
+            let active = match button {
+                ToolbarButton::Pause => view.paused,
+                ToolbarButton::Annotate => view.annotating,
+                ToolbarButton::Mute => view.muted,
+                ToolbarButton::Stop | ToolbarButton::Screenshot => false,
+            };
+            let color = if active {
+                palette.enabled
+            } else {
+                palette.text
+            };
+            draw_icon(canvas, button, (x, y, size), view, color, background, scale);
+        }
+
+        // Recording dot (gray while paused) and elapsed time
+        let timer_x = scaled(
+            toolbar::PADDING * 2 + toolbar::GRIP_WIDTH + Self::buttons_width(),
+            scale,
+        );
+        let radius = scaled(4, scale);
+        let dot_color = if view.paused {
+            palette.secondary
+        } else {
+            toolbar::RECORDING_COLOR
+        };
+        drawing::fill_circle(
+            canvas,
+            timer_x + radius,
+            canvas.height / 2,
+            radius,
+            dot_color,
+        );
+
+        let style = TextStyle {
+            color: palette.text,
+            size: scaled(toolbar::TEXT_SIZE, scale),
+            bold: false,
+        };
+        let elapsed = format_elapsed(view.elapsed_secs);
+        let (_, text_height) = text::text_size(&elapsed, &style);
+        text::draw_text(
+            canvas,
+            timer_x + radius * 3,
+            (canvas.height - text_height) / 2,
+            &elapsed,
+            &style,
+        );
+    }
+
+    /// Width of the buttons with the space after each (at 100 %)
+    fn buttons_width() -> i32 {
+        (toolbar::BUTTON_SIZE + toolbar::PADDING) * ToolbarButton::ALL.len() as i32
+    }
+
+    /// Left, top and size of a button in pixels at `scale`
+    fn button_rect(button: ToolbarButton, scale: f32) -> (i32, i32, i32) {
+        let index = ToolbarButton::ALL
+            .iter()
+            .position(|&b| b == button)
+            .unwrap_or(0) as i32;
+        let x = toolbar::PADDING * 2
+            + toolbar::GRIP_WIDTH
+            + index * (toolbar::BUTTON_SIZE + toolbar::PADDING);
+        let y = (toolbar::HEIGHT - toolbar::BUTTON_SIZE) / 2;
+        (
+            scaled(x, scale),
+            scaled(y, scale),
+            scaled(toolbar::BUTTON_SIZE, scale),
+        )
+    }
+
+    /// The button at (x, y) inside the toolbar, None over the grip and timer
+    fn button_at(x: f64, y: f64, scale: f32) -> Option<ToolbarButton> {
+        let (x, y) = (x as i32, y as i32);
+        ToolbarButton::ALL.into_iter().find(|&button| {
+            let (left, top, size) = Self::button_rect(button, scale);
+            x >= left && x < left + size && y >= top && y < top + size
+        })
+    }
+}
+
+/// "12:34", or "1:02:03" after an hour
+fn format_elapsed(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+/// Draw the icon of `button` into its square (left, top, size)
+fn draw_icon(
+    canvas: &mut Canvas,
+    button: ToolbarButton,
+    (x, y, size): (i32, i32, i32),
+    view: &ToolbarView,
+    color: u32,
+    background: u32,
+    scale: f32,
+) {
+    let margin = size / 4;
+    let (ix, iy, is) = (x + margin, y + margin, size - margin * 2);
+    let stroke = scaled(2, scale) as u32;
+
+    match button {
+        ToolbarButton::Stop => {
+            drawing::fill_rect(canvas, ix, iy, is, is, toolbar::RECORDING_COLOR);
+        }
+        // Paused: a play triangle to resume
+        ToolbarButton::Pause if view.paused => {
+            for row in 0..is {
+                let width = is - 2 * (row - is / 2).abs();
+                drawing::fill_rect(canvas, ix + is / 8, iy + row, width, 1, color);
+            }
+        }
+        ToolbarButton::Pause => {
+            let bar = is / 3;
+            drawing::fill_rect(canvas, ix, iy, bar, is, color);
+            drawing::fill_rect(canvas, ix + is - bar, iy, bar, is, color);
+        }
+        // Camera: body, viewfinder bump and lens
+        ToolbarButton::Screenshot => {
+            let top = iy + is / 5;
+            drawing::draw_rect(canvas, ix, top, ix + is, iy + is, stroke, color);
+            drawing::fill_rect(canvas, ix + is / 3, iy, is / 3, is / 5, color);
+            let (cx, cy, r) = (ix + is / 2, top + (iy + is - top) / 2, is / 5);
+            drawing::draw_ellipse(canvas, cx - r, cy - r, cx + r, cy + r, stroke, color);
+        }
+        // Pen: shaft and nib
+        ToolbarButton::Annotate => {
+            let nib = is / 4;
+            drawing::draw_line(
+                canvas,
+                ix + nib,
+                iy + is - nib,
+                ix + is,
+                iy,
+                stroke + 1,
+                color,
+            );
+            drawing::draw_line(canvas, ix, iy + is, ix + nib, iy + is - nib, stroke, color);
+        }
+        // Bell, crossed out while notifications are hidden
+        ToolbarButton::Mute => {
+            let cx = ix + is / 2;
+            let body = iy + is * 2 / 5;
+            drawing::fill_circle(canvas, cx, body, is * 3 / 10, color);
+            drawing::fill_rect(
+                canvas,
+                cx - is * 3 / 10,
+                body,
+                is * 3 / 5,
+                is * 3 / 10,
+                color,
+            );
+            drawing::fill_rect(canvas, ix, body + is * 3 / 10, is, stroke as i32, color);
+            drawing::fill_circle(canvas, cx, iy + is * 17 / 20, is / 10, color);
+            if view.muted {
+                drawing::draw_line(canvas, ix, iy, ix + is, iy + is, stroke + 2, background);
+                drawing::draw_line(canvas, ix, iy, ix + is, iy + is, stroke, color);
+            }
+        }
+    }
+}
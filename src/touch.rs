@@ -0,0 +1,212 @@
+// touch.rs - Touch Gestures for the Overlay and Border
+//
+// On touch screens (Surface-style devices) the selection overlay and the
+// hollow capture border are moved and resized with fingers:
+// - One finger on an edge drags that edge (corners drag two edges)
+// - One finger anywhere else moves the region
+// - Two fingers pinch to resize the region around its center
+//
+// Touch contacts arrive as winit Touch events in window coordinates; they are
+// converted to screen coordinates first, because the window moves under the
+// finger while it is dragged. Pen input in draw mode does not go through the
+// gestures - it draws annotations with pressure-based stroke width (see
+// annotation.rs).
+
+use std::collections::BTreeMap;
+
+use crate::constants::{overlay, touch};
+
+/// Screen rectangle of the overlay: outer position and inner size
+pub type Bounds = ((i32, i32), (u32, u32));
+
+/// What a single finger drags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Grab {
+    /// The whole region
+    Move,
+    /// One or two edges (left, right, top, bottom)
+    Edges {
+        left: bool,
+        right: bool,
+        top: bool,
+        bottom: bool,
+    },
+}
+
+/// State of the current touch gesture
+#[derive(Debug, Clone, Copy)]
+enum Gesture {
+    Idle,
+    /// One finger: grab, finger start point and bounds at the start
+    Drag {
+        grab: Grab,
+        start: (f64, f64),
+        bounds: Bounds,
+    },
+    /// Two fingers: finger distance and bounds at the start
+    Pinch {
+        distance: f64,
+        bounds: Bounds,
+    },
+}
+
+/// Turns touch contacts into new overlay bounds
+pub struct TouchGestures {
+    /// Active contacts by touch id (screen coordinates)
+    contacts: BTreeMap<u64, (f64, f64)>,
+    /// The contact that went down first (draws in draw mode)
+    primary: Option<u64>,
+    gesture: Gesture,
+}
+
+impl TouchGestures {
+    pub fn new() -> Self {
+        Self {
+            contacts: BTreeMap::new(),
+            primary: None,
+            gesture: Gesture::Idle,
+        }
+    }
+
+    /// Whether a finger or pen is down (promoted mouse input is ignored then)
+    pub fn is_active(&self) -> bool {
+        !self.contacts.is_empty()
+    }
+
+    /// Whether this contact went down first (later fingers only pinch)
+    pub fn is_primary(&self, id: u64) -> bool {
+        self.primary == Some(id)
+    }
+
+    /// A finger touched down at a screen point; `bounds` are the current overlay bounds
+    pub fn start(&mut self, id: u64, point: (f64, f64), bounds: Bounds) {
+        if self.contacts.is_empty() {
+            self.primary = Some(id);
+        }
+        self.contacts.insert(id, point);
+        self.restart(bounds);
+    }
+
+    /// A finger moved - returns the new overlay bounds (None while idle)
+    pub fn update(&mut self, id: u64, point: (f64, f64)) -> Option<Bounds> {
+        let contact = self.contacts.get_mut(&id)?;
+        *contact = point;
+
+        let new_bounds = match self.gesture {
+            Gesture::Idle => return None,
+            Gesture::Drag {
+                grab,
+                start,
+                bounds,
+            } => drag(grab, bounds, point.0 - start.0, point.1 - start.1),
+            Gesture::Pinch { distance, bounds } => {
+                let scale = self.finger_distance()? / distance;
+                pinch(bounds, scale)
+            }
+        };
+        Some(new_bounds)
+    }
+
+    /// A finger lifted (or the touch was cancelled); the remaining fingers
+    /// continue from the current overlay bounds
+    pub fn end(&mut self, id: u64, bounds: Bounds) {
+        self.contacts.remove(&id);
+        if self.primary == Some(id) {
+            self.primary = None;
+        }
+        self.restart(bounds);
+    }
+
+    /// Pick the gesture for the contacts that are down now
+    fn restart(&mut self, bounds: Bounds) {
+        self.gesture = match self.contacts.len() {
+            0 => Gesture::Idle,
+            1 => {
+                let start = *self.contacts.values().next().unwrap_or(&(0.0, 0.0));
+                Gesture::Drag {
+                    grab: grab_at(bounds, start),
+                    start,
+                    bounds,
+                }
+            }
+            _ => match self.finger_distance() {
+                Some(distance) => Gesture::Pinch { distance, bounds },
+                None => Gesture::Idle,
+            },
+        };
+    }
+
+    /// Distance between the first two fingers (None if they are on top of each other)
+    fn finger_distance(&self) -> Option<f64> {
+        let mut points = self.contacts.values();
+        let (a, b) = (points.next()?, points.next()?);
+        let distance = (a.0 - b.0).hypot(a.1 - b.1);
+        (distance >= 1.0).then_some(distance)
+    }
+}
+
+/// Edges within reach of the finger, or Move when none is
+fn grab_at(((x, y), (width, height)): Bounds, point: (f64, f64)) -> Grab {
+    let reach = touch::EDGE_REACH as f64;
+    let (px, py) = (point.0 - x as f64, point.1 - y as f64);
+    let left = px < reach;
+    let right = px >= width as f64 - reach;
+    let top = py < reach;
+    let bottom = py >= height as f64 - reach;
+    if left || right || top || bottom {
+        Grab::Edges {
+            left,
+            right: right && !left,
+            top,
+            bottom: bottom && !top,
+        }
+    } else {
+        Grab::Move
+    }
+}
+
+/// Bounds after dragging by (dx, dy) with the given grab
+fn drag(grab: Grab, ((x, y), (width, height)): Bounds, dx: f64, dy: f64) -> Bounds {
+    let (dx, dy) = (dx.round() as i32, dy.round() as i32);
+    match grab {
+        Grab::Move => ((x + dx, y + dy), (width, height)),
+        Grab::Edges {
+            left,
+            right,
+            top,
+            bottom,
+        } => {
+            let (mut x0, mut y0) = (x, y);
+            let (mut x1, mut y1) = (x + width as i32, y + height as i32);
+            let (min_w, min_h) = (overlay::MIN_WIDTH as i32, overlay::MIN_HEIGHT as i32);
+            if left {
+                x0 = (x0 + dx).min(x1 - min_w);
+            }
+            if right {
+                x1 = (x1 + dx).max(x0 + min_w);
+            }
+            if top {
+                y0 = (y0 + dy).min(y1 - min_h);
+            }
+            if bottom {
+                y1 = (y1 + dy).max(y0 + min_h);
+            }
+            ((x0, y0), ((x1 - x0) as u32, (y1 - y0) as u32))
+        }
+    }
+}
+
+/// Bounds scaled around their center
+fn pinch(((x, y), (width, height)): Bounds, scale: f64) -> Bounds {
+    let new_width = ((width as f64 * scale).round() as u32).max(overlay::MIN_WIDTH);
+    let new_height = ((height as f64 * scale).round() as u32).max(overlay::MIN_HEIGHT);
+    let center_x = x + width as i32 / 2;
+    let center_y = y + height as i32 / 2;
+    (
+        (
+            center_x - new_width as i32 / 2,
+            center_y - new_height as i32 / 2,
+        ),
+        (new_width, new_height),
+    )
+}
@@ -0,0 +1,167 @@
+// transition.rs - Output Transitions
+//
+// Live switches (scene changes, freeze / unfreeze, pause / resume) would
+// otherwise jump from one picture to the next in a single frame. A transition
+// blends from the last presented frame to the new output instead:
+// - Cut:           no transition (the old behavior)
+// - Crossfade:     old and new frame are blended
+// - Slide:         the new frame slides in from the right, pushing the old one out
+// - Fade to color: the old frame fades to a solid color, then the new frame fades in
+//
+// The blending itself runs in the fragment shader (shader.wgsl); the renderer
+// keeps the frame being faded out and this module computes the progress.
+// The duration is configured in Settings (Transition), the kind in the tray;
+// both are saved in settings.json together with the fade color
+// (transition_color, "#RRGGBB"). Every output window runs the same transition.
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use crate::chroma_key::srgb_to_linear;
+
+/// How the output changes from the old to the new picture
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransitionKind {
+    Cut,
+    #[default]
+    Crossfade,
+    Slide,
+    FadeToColor,
+}
+
+impl TransitionKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            TransitionKind::Cut => "Cut",
+            TransitionKind::Crossfade => "Crossfade",
+            TransitionKind::Slide => "Slide",
+            TransitionKind::FadeToColor => "Fade to Color",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            TransitionKind::Cut => TransitionKind::Crossfade,
+            TransitionKind::Crossfade => TransitionKind::Slide,
+            TransitionKind::Slide => TransitionKind::FadeToColor,
+            TransitionKind::FadeToColor => TransitionKind::Cut,
+        }
+    }
+
+    /// Kind number understood by the shader
+    fn shader_id(self) -> u32 {
+        match self {
+            TransitionKind::Cut => 0,
+            TransitionKind::Crossfade => 1,
+            TransitionKind::Slide => 2,
+            TransitionKind::FadeToColor => 3,
+        }
+    }
+}
+
+/// Uniform block consumed by shader.wgsl (must match `struct Transition` there)
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct TransitionUniform {
+    /// Fade to color target in linear RGB
+    color: [f32; 4],
+    /// 0 = only the old frame, 1 = only the new frame
+    progress: f32,
+    /// TransitionKind::shader_id
+    kind: u32,
+    _padding: [f32; 2],
+}
+
+impl TransitionUniform {
+    /// No transition running (shows the new frame only)
+    pub fn done() -> Self {
+        Self {
+            color: [0.0, 0.0, 0.0, 1.0],
+            progress: 1.0,
+            kind: TransitionKind::Cut.shader_id(),
+            _padding: [0.0; 2],
+        }
+    }
+
+    /// True once the new frame is fully shown
+    pub fn is_done(&self) -> bool {
+        self.progress >= 1.0
+    }
+}
+
+/// A running transition
+pub struct Transition {
+    kind: TransitionKind,
+    /// Fade to color target (0xRRGGBB)
+    color: u32,
+    duration: Duration,
+    started: Instant,
+}
+
+impl Transition {
+    /// Start a transition now (None for a cut or a zero duration)
+    pub fn start(kind: TransitionKind, duration_ms: u32, color: u32) -> Option<Self> {
+        if kind == TransitionKind::Cut || duration_ms == 0 {
+            return None;
+        }
+        Some(Self {
+            kind,
+            color,
+            duration: Duration::from_millis(duration_ms as u64),
+            started: Instant::now(),
+        })
+    }
+
+    /// 0.0 at the start, 1.0 once the transition is over
+    fn progress(&self) -> f32 {
+        (self.started.elapsed().as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+
+    /// Shader parameters for the current moment
+    pub fn uniform(&self) -> TransitionUniform {
+        let rgb = self.color;
+        let channel = |shift: u32| srgb_to_linear(((rgb >> shift) & 0xFF) as f32 / 255.0);
+        TransitionUniform {
+            color: [channel(16), channel(8), channel(0), 1.0],
+            progress: self.progress(),
+            kind: self.kind.shader_id(),
+            _padding: [0.0; 2],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cut_and_zero_duration_start_nothing() {
+        assert!(Transition::start(TransitionKind::Cut, 500, 0).is_none());
+        assert!(Transition::start(TransitionKind::Crossfade, 0, 0).is_none());
+    }
+
+    #[test]
+    fn progress_runs_from_zero_to_one() {
+        let transition = Transition::start(TransitionKind::Slide, 60_000, 0).unwrap();
+        assert!(transition.progress() < 0.01);
+        assert!(!transition.uniform().is_done());
+
+        let finished = Transition {
+            started: Instant::now() - Duration::from_secs(2),
+            ..Transition::start(TransitionKind::Slide, 1000, 0).unwrap()
+        };
+        assert_eq!(finished.progress(), 1.0);
+        assert!(finished.uniform().is_done());
+    }
+
+    #[test]
+    fn fade_color_is_converted_to_linear() {
+        let transition = Transition::start(TransitionKind::FadeToColor, 1000, 0xFF0000).unwrap();
+        let uniform = transition.uniform();
+        let [red, green, blue, alpha] = uniform.color;
+        assert!((red - 1.0).abs() < 1e-6);
+        assert_eq!((green, blue, alpha), (0.0, 0.0, 1.0));
+        assert_eq!(uniform.kind, TransitionKind::FadeToColor.shader_id());
+    }
+}
@@ -0,0 +1,641 @@
+// upload.rs - Uploads
+//
+// Uploads the last screenshot to a configured target and puts a shareable
+// link on the clipboard (tray → Recent Screenshots → Upload Last Screenshot,
+// the upload_screenshot remote action, or upload_screenshot() in scripts).
+// The target is configured in settings.json:
+//
+//   "upload": {
+//     "target": "s3",
+//     "s3": { "bucket": "captures", "region": "eu-central-1",
+//             "access_key_id": "AKIA...", "secret_access_key": "..." }
+//   }
+//
+// Targets:
+// - s3            Amazon S3 or a compatible store (endpoint), signed with
+//                 AWS Signature V4; the link is public_url + key or the
+//                 object URL
+// - google_drive  Google Drive via an OAuth refresh token; the file is shared
+//                 with anyone who has the link
+// - imgur         anonymous Imgur upload with an app's client ID
+// - http_put      PUT to any URL ({name} = file name), e.g. a WebDAV folder
+//
+// Secrets (S3 secret key, Google client secret and refresh token, the PUT
+// Authorization header) are moved from settings.json into the Windows
+// Credential Manager on start (see credentials.rs).
+//
+// One upload runs at a time, on a background thread; the tray status shows
+// its progress and the result.
+
+use anyhow::{anyhow, Context, Result};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::constants::upload;
+use crate::credentials;
+use crate::http;
+
+/// Upload settings (settings.json)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UploadSettings {
+    /// "s3", "google_drive", "imgur" or "http_put" (empty = uploads are off)
+    pub target: String,
+    pub s3: S3Settings,
+    pub google_drive: GoogleDriveSettings,
+    pub imgur: ImgurSettings,
+    pub http_put: HttpPutSettings,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct S3Settings {
+    pub bucket: String,
+    /// e.g. "eu-central-1" (empty = us-east-1)
+    pub region: String,
+    /// S3-compatible store, e.g. "https://minio.local:9000" (empty = AWS)
+    pub endpoint: String,
+    /// Prepended to the file name, e.g. "screenshots/"
+    pub prefix: String,
+    /// Base of the shared link, e.g. a CDN in front of the bucket (empty = object URL)
+    pub public_url: String,
+    pub access_key_id: String,
+    /// Moved to the Credential Manager
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub secret_access_key: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GoogleDriveSettings {
+    /// Folder the files go to (empty = My Drive)
+    pub folder_id: String,
+    pub client_id: String,
+    /// Moved to the Credential Manager
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub client_secret: String,
+    /// Moved to the Credential Manager
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImgurSettings {
+    /// Client ID of a registered Imgur application
+    pub client_id: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpPutSettings {
+    /// Where the file is PUT ({name} = file name)
+    pub url: String,
+    /// Shared link ({name} = file name, empty = the PUT URL)
+    pub public_url: String,
+    /// Authorization header value, e.g. "Basic ..." (moved to the Credential Manager)
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub authorization: String,
+}
+
+/// Credential Manager names of the secrets
+const S3_SECRET: &str = "s3_secret_access_key";
+const DRIVE_SECRET: &str = "google_drive_client_secret";
+const DRIVE_TOKEN: &str = "google_drive_refresh_token";
+const PUT_AUTHORIZATION: &str = "http_put_authorization";
+
+/// Move secrets entered in settings.json into the Credential Manager
+/// Returns true if the settings changed (and should be saved).
+pub fn store_secrets(settings: &mut UploadSettings) -> bool {
+    let secrets = [
+        (S3_SECRET, &mut settings.s3.secret_access_key),
+        (DRIVE_SECRET, &mut settings.google_drive.client_secret),
+        (DRIVE_TOKEN, &mut settings.google_drive.refresh_token),
+        (PUT_AUTHORIZATION, &mut settings.http_put.authorization),
+    ];
+    let mut changed = false;
+    for (name, value) in secrets {
+        if value.is_empty() {
+            continue;
+        }
+        match credentials::set(name, value.trim()) {
+            Ok(()) => {
+                info!(
+                    "Moved {} from settings.json to the Credential Manager",
+                    name
+                );
+                value.clear();
+                changed = true;
+            }
+            Err(e) => warn!("{:#}", e),
+        }
+    }
+    changed
+}
+
+/// A secret from the Credential Manager
+fn secret(name: &str) -> Result<String> {
+    credentials::get(name).ok_or_else(|| {
+        anyhow!(
+            "{} is not set - add it to the upload settings in settings.json",
+            name
+        )
+    })
+}
+
+/// Somewhere files can be uploaded to
+trait UploadTarget: Send {
+    /// Upload `data` as `name` and return the link to share
+    fn upload(
+        &self,
+        name: &str,
+        content_type: &str,
+        data: &[u8],
+        progress: &mut dyn FnMut(u64),
+    ) -> Result<String>;
+}
+
+/// The configured target
+fn target(settings: &UploadSettings) -> Result<Box<dyn UploadTarget>> {
+    match settings.target.trim() {
+        "s3" => Ok(Box::new(S3 {
+            settings: settings.s3.clone(),
+            secret_access_key: secret(S3_SECRET)?,
+        })),
+        "google_drive" => Ok(Box::new(GoogleDrive {
+            settings: settings.google_drive.clone(),
+            client_secret: secret(DRIVE_SECRET)?,
+            refresh_token: secret(DRIVE_TOKEN)?,
+        })),
+        "imgur" => Ok(Box::new(Imgur {
+            client_id: settings.imgur.client_id.clone(),
+        })),
+        "http_put" => Ok(Box::new(HttpPut {
+            settings: settings.http_put.clone(),
+            authorization: credentials::get(PUT_AUTHORIZATION),
+        })),
+        "" => Err(anyhow!(
+            "No upload target is configured (settings.json → upload)"
+        )),
+        other => Err(anyhow!("Unknown upload target '{}'", other)),
+    }
+}
+
+/// State of the current or last upload
+enum UploadStatus {
+    Idle,
+    Uploading { sent: u64, total: u64 },
+    Done { at: Instant },
+    Failed { at: Instant },
+}
+
+/// Runs uploads in the background
+pub struct Uploader {
+    status: Arc<Mutex<UploadStatus>>,
+}
+
+impl Uploader {
+    pub fn new() -> Self {
+        Self {
+            status: Arc::new(Mutex::new(UploadStatus::Idle)),
+        }
+    }
+
+    /// Upload `path`, then copy the link to the clipboard
+    pub fn start(&self, settings: &UploadSettings, path: PathBuf) {
+        if matches!(*self.status.lock().unwrap(), UploadStatus::Uploading { .. }) {
+            warn!("An upload is already running");
+            return;
+        }
+        let target = match target(settings) {
+            Ok(target) => target,
+            Err(e) => {
+                warn!("Upload failed: {:#}", e);
+                *self.status.lock().unwrap() = UploadStatus::Failed { at: Instant::now() };
+                return;
+            }
+        };
+        *self.status.lock().unwrap() = UploadStatus::Uploading { sent: 0, total: 0 };
+
+        let status = self.status.clone();
+        std::thread::spawn(move || {
+            let result = upload_file(target.as_ref(), &path, &status);
+            let mut status = status.lock().unwrap();
+            match result {
+                Ok(link) => {
+                    info!("Uploaded {:?}: {}", path, link);
+                    copy_link(&link);
+                    *status = UploadStatus::Done { at: Instant::now() };
+                }
+                Err(e) => {
+                    warn!("Upload of {:?} failed: {:#}", path, e);
+                    *status = UploadStatus::Failed { at: Instant::now() };
+                }
+            }
+        });
+    }
+
+    /// Progress or recent result for the tray status (None when there is nothing to show)
+    pub fn status_text(&self) -> Option<String> {
+        let shown = Duration::from_millis(upload::RESULT_SHOWN_MS);
+        match *self.status.lock().unwrap() {
+            UploadStatus::Uploading { sent, total } if total > 0 => {
+                Some(format!("Uploading {}%", sent * 100 / total))
+            }
+            UploadStatus::Uploading { .. } => Some("Uploading".to_string()),
+            UploadStatus::Done { at } if at.elapsed() < shown => {
+                Some("Upload done, link copied".to_string())
+            }
+            UploadStatus::Failed { at } if at.elapsed() < shown => {
+                Some("Upload failed".to_string())
+            }
+            _ => None,
+        }
+    }
+}
+
+fn upload_file(
+    target: &dyn UploadTarget,
+    path: &Path,
+    status: &Mutex<UploadStatus>,
+) -> Result<String> {
+    let data = std::fs::read(path).with_context(|| format!("Failed to read {:?}", path))?;
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let total = data.len() as u64;
+    *status.lock().unwrap() = UploadStatus::Uploading { sent: 0, total };
+    let mut progress = |sent: u64| {
+        *status.lock().unwrap() = UploadStatus::Uploading { sent, total };
+    };
+    target.upload(&name, content_type(path), &data, &mut progress)
+}
+
+#[cfg(windows)]
+fn copy_link(link: &str) {
+    if let Err(e) = crate::utils::set_clipboard_text(link) {
+        warn!("{:#}", e);
+    }
+}
+
+#[cfg(not(windows))]
+fn copy_link(_link: &str) {}
+
+fn content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "bmp" => "image/bmp",
+        "mp4" => "video/mp4",
+        _ => "application/octet-stream",
+    }
+}
+
+/// A value from a JSON answer, e.g. json_field(&body, &["data", "link"])
+fn json_field(response: &http::Response, path: &[&str]) -> Result<String> {
+    let json: serde_json::Value =
+        serde_json::from_slice(&response.body).context("The answer is not JSON")?;
+    path.iter()
+        .try_fold(&json, |value, key| value.get(key))
+        .and_then(|value| value.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("The answer has no {}", path.join(".")))
+}
+
+/// A multipart body with one JSON or text part per field and the file last
+fn multipart(boundary: &str, fields: &[(&str, &str, &str)], file: (&str, &str, &[u8])) -> Vec<u8> {
+    let mut body = Vec::new();
+    for (name, content_type, value) in fields {
+        body.extend_from_slice(
+            format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\nContent-Type: {}\r\n\r\n{}\r\n",
+                boundary, name, content_type, value
+            )
+            .as_bytes(),
+        );
+    }
+    let (name, content_type, data) = file;
+    body.extend_from_slice(
+        format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"image\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+            boundary, name, content_type
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(data);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+    body
+}
+
+/// Amazon S3 (or compatible), AWS Signature V4
+struct S3 {
+    settings: S3Settings,
+    secret_access_key: String,
+}
+
+impl UploadTarget for S3 {
+    fn upload(
+        &self,
+        name: &str,
+        content_type: &str,
+        data: &[u8],
+        progress: &mut dyn FnMut(u64),
+    ) -> Result<String> {
+        let s = &self.settings;
+        if s.bucket.is_empty() || s.access_key_id.is_empty() {
+            return Err(anyhow!("S3 needs bucket and access_key_id"));
+        }
+        let region = if s.region.is_empty() {
+            upload::S3_DEFAULT_REGION
+        } else {
+            s.region.as_str()
+        };
+        let key = http::encode(&format!("{}{}", s.prefix, name), true);
+        // AWS: virtual-hosted style; other stores: path style
+        let url = if s.endpoint.is_empty() {
+            format!("https://{}.s3.{}.amazonaws.com/{}", s.bucket, region, key)
+        } else {
+            format!("{}/{}/{}", s.endpoint.trim_end_matches('/'), s.bucket, key)
+        };
+        let parsed = http::Url::parse(&url)?;
+
+        let (date, time) = utc_now();
+        let amz_date = format!("{}T{}Z", date, time);
+        let payload_hash = hex(&sha256(data)?);
+        let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+        let canonical_request = format!(
+            "PUT\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+            parsed.path,
+            parsed.host_header(),
+            payload_hash,
+            amz_date,
+            signed_headers,
+            payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex(&sha256(canonical_request.as_bytes())?)
+        );
+        let mut key_bytes = hmac_sha256(
+            format!("AWS4{}", self.secret_access_key).as_bytes(),
+            date.as_bytes(),
+        )?;
+        for part in [region, "s3", "aws4_request"] {
+            key_bytes = hmac_sha256(&key_bytes, part.as_bytes())?;
+        }
+        let signature = hex(&hmac_sha256(&key_bytes, string_to_sign.as_bytes())?);
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            s.access_key_id, scope, signed_headers, signature
+        );
+
+        let headers = [
+            ("Authorization", authorization),
+            ("Content-Type", content_type.to_string()),
+            ("x-amz-content-sha256", payload_hash),
+            ("x-amz-date", amz_date),
+        ];
+        http::request("PUT", &url, &headers, data, upload::TIMEOUT_MS, progress)?
+            .error_for_status()?;
+
+        Ok(if s.public_url.is_empty() {
+            url
+        } else {
+            format!("{}/{}", s.public_url.trim_end_matches('/'), key)
+        })
+    }
+}
+
+/// Google Drive (files.create with uploadType=multipart)
+struct GoogleDrive {
+    settings: GoogleDriveSettings,
+    client_secret: String,
+    refresh_token: String,
+}
+
+impl UploadTarget for GoogleDrive {
+    fn upload(
+        &self,
+        name: &str,
+        content_type: &str,
+        data: &[u8],
+        progress: &mut dyn FnMut(u64),
+    ) -> Result<String> {
+        let s = &self.settings;
+        let form = format!(
+            "client_id={}&client_secret={}&refresh_token={}&grant_type=refresh_token",
+            http::encode(&s.client_id, false),
+            http::encode(&self.client_secret, false),
+            http::encode(&self.refresh_token, false)
+        );
+        let headers = [(
+            "Content-Type",
+            "application/x-www-form-urlencoded".to_string(),
+        )];
+        let response = http::request(
+            "POST",
+            upload::GOOGLE_TOKEN_URL,
+            &headers,
+            form.as_bytes(),
+            upload::TIMEOUT_MS,
+            &mut |_| {},
+        )?
+        .error_for_status()
+        .context("Google sign-in failed")?;
+        let bearer = format!("Bearer {}", json_field(&response, &["access_token"])?);
+
+        let mut metadata = serde_json::json!({ "name": name });
+        if !s.folder_id.is_empty() {
+            metadata["parents"] = serde_json::json!([s.folder_id]);
+        }
+        let boundary = upload::MULTIPART_BOUNDARY;
+        let mut body = format!(
+            "--{}\r\nContent-Type: application/json; charset=UTF-8\r\n\r\n{}\r\n--{}\r\nContent-Type: {}\r\n\r\n",
+            boundary, metadata, boundary, content_type
+        )
+        .into_bytes();
+        body.extend_from_slice(data);
+        body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+        let headers = [
+            ("Authorization", bearer.clone()),
+            (
+                "Content-Type",
+                format!("multipart/related; boundary={}", boundary),
+            ),
+        ];
+        let response = http::request(
+            "POST",
+            upload::GOOGLE_UPLOAD_URL,
+            &headers,
+            &body,
+            upload::TIMEOUT_MS,
+            progress,
+        )?
+        .error_for_status()?;
+        let id = json_field(&response, &["id"])?;
+        let link = json_field(&response, &["webViewLink"])?;
+
+        // Anyone with the link can view the file
+        let headers = [
+            ("Authorization", bearer),
+            ("Content-Type", "application/json".to_string()),
+        ];
+        http::request(
+            "POST",
+            &format!("{}/{}/permissions", upload::GOOGLE_FILES_URL, id),
+            &headers,
+            br#"{"role":"reader","type":"anyone"}"#,
+            upload::TIMEOUT_MS,
+            &mut |_| {},
+        )?
+        .error_for_status()
+        .context("Sharing the file failed")?;
+        Ok(link)
+    }
+}
+
+/// Imgur (anonymous upload)
+struct Imgur {
+    client_id: String,
+}
+
+impl UploadTarget for Imgur {
+    fn upload(
+        &self,
+        name: &str,
+        content_type: &str,
+        data: &[u8],
+        progress: &mut dyn FnMut(u64),
+    ) -> Result<String> {
+        if self.client_id.is_empty() {
+            return Err(anyhow!("Imgur needs client_id"));
+        }
+        let boundary = upload::MULTIPART_BOUNDARY;
+        let body = multipart(
+            boundary,
+            &[("type", "text/plain", "file")],
+            (name, content_type, data),
+        );
+        let headers = [
+            ("Authorization", format!("Client-ID {}", self.client_id)),
+            (
+                "Content-Type",
+                format!("multipart/form-data; boundary={}", boundary),
+            ),
+        ];
+        let response = http::request(
+            "POST",
+            upload::IMGUR_UPLOAD_URL,
+            &headers,
+            &body,
+            upload::TIMEOUT_MS,
+            progress,
+        )?
+        .error_for_status()?;
+        json_field(&response, &["data", "link"])
+    }
+}
+
+/// PUT to a URL (WebDAV, presigned storage, own server)
+struct HttpPut {
+    settings: HttpPutSettings,
+    authorization: Option<String>,
+}
+
+impl UploadTarget for HttpPut {
+    fn upload(
+        &self,
+        name: &str,
+        content_type: &str,
+        data: &[u8],
+        progress: &mut dyn FnMut(u64),
+    ) -> Result<String> {
+        let s = &self.settings;
+        if s.url.is_empty() {
+            return Err(anyhow!("http_put needs url"));
+        }
+        let encoded = http::encode(name, false);
+        let url = s.url.replace("{name}", &encoded);
+        let mut headers = vec![("Content-Type", content_type.to_string())];
+        if let Some(authorization) = &self.authorization {
+            headers.push(("Authorization", authorization.clone()));
+        }
+        http::request("PUT", &url, &headers, data, upload::TIMEOUT_MS, progress)?
+            .error_for_status()?;
+        Ok(if s.public_url.is_empty() {
+            url
+        } else {
+            s.public_url.replace("{name}", &encoded)
+        })
+    }
+}
+
+/// Current UTC date and time as ("YYYYMMDD", "HHMMSS")
+fn utc_now() -> (String, String) {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let days = (secs / 86400) as i64;
+    let rest = secs % 86400;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    (
+        format!("{:04}{:02}{:02}", year, month, day),
+        format!("{:02}{:02}{:02}", rest / 3600, rest / 60 % 60, rest % 60),
+    )
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(windows)]
+fn sha256(data: &[u8]) -> Result<[u8; 32]> {
+    use windows::Win32::Security::Cryptography::{BCryptHash, BCRYPT_SHA256_ALG_HANDLE};
+
+    let mut out = [0u8; 32];
+    unsafe { BCryptHash(BCRYPT_SHA256_ALG_HANDLE, None, data, &mut out) }.ok()?;
+    Ok(out)
+}
+
+#[cfg(windows)]
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Result<[u8; 32]> {
+    use windows::Win32::Security::Cryptography::{BCryptHash, BCRYPT_HMAC_SHA256_ALG_HANDLE};
+
+    let mut out = [0u8; 32];
+    unsafe { BCryptHash(BCRYPT_HMAC_SHA256_ALG_HANDLE, Some(key), data, &mut out) }.ok()?;
+    Ok(out)
+}
+
+#[cfg(not(windows))]
+fn sha256(_data: &[u8]) -> Result<[u8; 32]> {
+    Err(anyhow!("Hashing is only supported on Windows"))
+}
+
+#[cfg(not(windows))]
+fn hmac_sha256(_key: &[u8], _data: &[u8]) -> Result<[u8; 32]> {
+    Err(anyhow!("Hashing is only supported on Windows"))
+}
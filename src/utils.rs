@@ -14,7 +14,7 @@ pub fn wide_string(s: &str) -> Vec<u16> {
 }
 
 /// Get HWND from a winit window handle
-#[cfg(windows)]
+#[cfg(all(windows, feature = "app"))]
 #[allow(dead_code)]
 pub fn get_hwnd(window: &winit::window::Window) -> Option<windows::Win32::Foundation::HWND> {
     use windows::Win32::Foundation::HWND;
@@ -29,10 +29,213 @@ pub fn get_hwnd(window: &winit::window::Window) -> Option<windows::Win32::Founda
 }
 
 /// Get HWND from a winit Arc<Window>
-#[cfg(windows)]
+#[cfg(all(windows, feature = "app"))]
 #[allow(dead_code)]
 pub fn get_hwnd_arc(
     window: &std::sync::Arc<winit::window::Window>,
 ) -> Option<windows::Win32::Foundation::HWND> {
     get_hwnd(window.as_ref())
 }
+
+/// Get the current mouse cursor position in virtual screen coordinates
+#[cfg(windows)]
+pub fn get_cursor_pos() -> Option<(i32, i32)> {
+    use windows::Win32::Foundation::POINT;
+    use windows::Win32::UI::WindowsAndMessaging::GetCursorPos;
+
+    let mut point = POINT::default();
+    unsafe { GetCursorPos(&mut point).ok()? };
+    Some((point.x, point.y))
+}
+
+#[cfg(not(windows))]
+pub fn get_cursor_pos() -> Option<(i32, i32)> {
+    None
+}
+
+/// Write a string value under HKEY_CURRENT_USER, creating the key if needed
+/// (`name` None = the key's default value)
+#[cfg(windows)]
+pub fn set_user_registry_string(subkey: &str, name: Option<&str>, value: &str) -> anyhow::Result<()> {
+    use windows::core::PCWSTR;
+    use windows::Win32::System::Registry::{
+        RegCloseKey, RegCreateKeyExW, RegSetValueExW, HKEY, HKEY_CURRENT_USER, KEY_SET_VALUE,
+        REG_OPTION_NON_VOLATILE, REG_SZ,
+    };
+
+    let subkey_wide = wide_string(subkey);
+    let name_wide = name.map(wide_string);
+    let data = wide_string(value);
+    unsafe {
+        let mut key = HKEY::default();
+        RegCreateKeyExW(
+            HKEY_CURRENT_USER,
+            PCWSTR(subkey_wide.as_ptr()),
+            None,
+            None,
+            REG_OPTION_NON_VOLATILE,
+            KEY_SET_VALUE,
+            None,
+            &mut key,
+            None,
+        )
+        .ok()
+        .map_err(|e| anyhow::anyhow!("Failed to open HKCU\\{}: {}", subkey, e))?;
+        let bytes = std::slice::from_raw_parts(data.as_ptr() as *const u8, data.len() * 2);
+        let name_ptr = name_wide
+            .as_ref()
+            .map_or(PCWSTR::null(), |n| PCWSTR(n.as_ptr()));
+        let result = RegSetValueExW(key, name_ptr, None, REG_SZ, Some(bytes)).ok();
+        let _ = RegCloseKey(key);
+        result.map_err(|e| anyhow::anyhow!("Failed to write HKCU\\{}: {}", subkey, e))
+    }
+}
+
+/// Delete a key and everything below it under HKEY_CURRENT_USER (a missing key is fine)
+#[cfg(windows)]
+pub fn delete_user_registry_key(subkey: &str) -> anyhow::Result<()> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::ERROR_FILE_NOT_FOUND;
+    use windows::Win32::System::Registry::{RegDeleteTreeW, HKEY_CURRENT_USER};
+
+    let subkey_wide = wide_string(subkey);
+    let result = unsafe { RegDeleteTreeW(HKEY_CURRENT_USER, PCWSTR(subkey_wide.as_ptr())) };
+    if result.is_ok() || result == ERROR_FILE_NOT_FOUND {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!("Failed to delete HKCU\\{}: {:?}", subkey, result))
+    }
+}
+
+/// Put text on the clipboard (replacing what is there)
+#[cfg(windows)]
+pub fn set_clipboard_text(text: &str) -> anyhow::Result<()> {
+    use windows::Win32::Foundation::{GlobalFree, HANDLE};
+    use windows::Win32::System::DataExchange::{
+        CloseClipboard, EmptyClipboard, OpenClipboard, SetClipboardData,
+    };
+    use windows::Win32::System::Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
+    use windows::Win32::System::Ole::CF_UNICODETEXT;
+
+    let data = wide_string(text);
+    unsafe {
+        OpenClipboard(None).map_err(|e| anyhow::anyhow!("Failed to open the clipboard: {}", e))?;
+        let result = (|| -> anyhow::Result<()> {
+            EmptyClipboard()?;
+            let memory = GlobalAlloc(GMEM_MOVEABLE, data.len() * 2)?;
+            let target = GlobalLock(memory) as *mut u16;
+            if target.is_null() {
+                let _ = GlobalFree(Some(memory));
+                return Err(anyhow::anyhow!("Failed to lock clipboard memory"));
+            }
+            std::ptr::copy_nonoverlapping(data.as_ptr(), target, data.len());
+            let _ = GlobalUnlock(memory);
+            // The clipboard owns the memory once this succeeds
+            if let Err(e) = SetClipboardData(CF_UNICODETEXT.0 as u32, Some(HANDLE(memory.0))) {
+                let _ = GlobalFree(Some(memory));
+                return Err(e.into());
+            }
+            Ok(())
+        })();
+        let _ = CloseClipboard();
+        result.map_err(|e| anyhow::anyhow!("Failed to set the clipboard: {}", e))
+    }
+}
+
+/// Show a warning box without blocking the caller (it runs on its own thread)
+#[cfg(windows)]
+pub fn show_warning(text: &str) {
+    use windows::core::PCWSTR;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        MessageBoxW, MB_ICONWARNING, MB_OK, MB_SETFOREGROUND,
+    };
+
+    let text = wide_string(text);
+    std::thread::spawn(move || {
+        let caption = wide_string("RustFrame");
+        unsafe {
+            MessageBoxW(
+                None,
+                PCWSTR(text.as_ptr()),
+                PCWSTR(caption.as_ptr()),
+                MB_OK | MB_ICONWARNING | MB_SETFOREGROUND,
+            );
+        }
+    });
+}
+
+#[cfg(not(windows))]
+pub fn show_warning(text: &str) {
+    log::warn!("{}", text);
+}
+
+/// Show an information box without blocking the caller
+#[cfg(windows)]
+pub fn show_info(text: &str) {
+    use windows::core::PCWSTR;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        MessageBoxW, MB_ICONINFORMATION, MB_OK, MB_SETFOREGROUND,
+    };
+
+    let text = wide_string(text);
+    std::thread::spawn(move || {
+        let caption = wide_string("RustFrame");
+        unsafe {
+            MessageBoxW(
+                None,
+                PCWSTR(text.as_ptr()),
+                PCWSTR(caption.as_ptr()),
+                MB_OK | MB_ICONINFORMATION | MB_SETFOREGROUND,
+            );
+        }
+    });
+}
+
+#[cfg(not(windows))]
+pub fn show_info(text: &str) {
+    log::info!("{}", text);
+}
+
+/// Ask a yes/no question without blocking the caller; `on_answer` runs on
+/// the box's own thread with true for Yes (No is the default button)
+#[cfg(windows)]
+pub fn confirm(text: &str, on_answer: impl FnOnce(bool) + Send + 'static) {
+    use windows::core::PCWSTR;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        MessageBoxW, IDYES, MB_DEFBUTTON2, MB_ICONQUESTION, MB_SETFOREGROUND, MB_TOPMOST,
+        MB_YESNO,
+    };
+
+    let text = wide_string(text);
+    std::thread::spawn(move || {
+        let caption = wide_string("RustFrame");
+        let answer = unsafe {
+            MessageBoxW(
+                None,
+                PCWSTR(text.as_ptr()),
+                PCWSTR(caption.as_ptr()),
+                MB_YESNO | MB_ICONQUESTION | MB_DEFBUTTON2 | MB_SETFOREGROUND | MB_TOPMOST,
+            )
+        };
+        on_answer(answer == IDYES);
+    });
+}
+
+#[cfg(not(windows))]
+pub fn confirm(text: &str, on_answer: impl FnOnce(bool) + Send + 'static) {
+    log::warn!("{} (no dialog on this platform, assuming yes)", text);
+    on_answer(true);
+}
+
+/// Write stdout / stderr to the console RustFrame was started from
+/// Release builds are GUI programs and have no console of their own.
+#[cfg(windows)]
+pub fn attach_console() {
+    use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+    // Fails when there is no parent console or we already have one
+    let _ = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+#[cfg(not(windows))]
+pub fn attach_console() {}
@@ -0,0 +1,473 @@
+// webcam.rs - Webcam Source
+//
+// A webcam device is a frame source just like an additional screen region
+// (see source.rs), so it can be placed in the output with the region layouts
+// (Placement):
+// - Beside Screen: the camera is composited next to the captured region
+// - Fullscreen:    the camera replaces the captured region in the output
+//                  (a "camera scene"; other regions are still composited)
+//
+// HOW IT WORKS:
+// 1. The video capture devices are enumerated with Media Foundation
+// 2. A background thread opens the selected device with a source reader that
+//    converts every frame to RGB32 (MF_SOURCE_READER_ENABLE_VIDEO_PROCESSING)
+// 3. ReadSample blocks until the camera delivers the next frame, which is
+//    copied into a shared buffer the renderer picks up (FrameSource). The
+//    buffer length is checked against the frame size before copying, and a
+//    format change (the camera switching resolution) updates the frame size
+// 4. Closing flushes the reader, which wakes up a pending ReadSample, so a
+//    camera that stopped delivering frames can't hang the app; a thread that
+//    still doesn't stop in time is left to exit on its own
+//
+// The camera is only open while a capture is running and the mode is not Off,
+// so the camera light is never on while RustFrame is idle. Redaction (window
+// masks, privacy regions) never applies to the camera picture - it belongs to
+// the captured screen regions only.
+
+use anyhow::{anyhow, Result};
+use log::{error, info, warn};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::constants::webcam;
+use crate::source::FrameSource;
+
+/// Lets another thread interrupt the capture thread's blocking ReadSample
+#[cfg(windows)]
+type ReaderHandle =
+    windows::core::AgileReference<windows::Win32::Media::MediaFoundation::IMFSourceReader>;
+
+#[cfg(not(windows))]
+type ReaderHandle = ();
+
+/// What the capture thread reports once the device is open
+type Opened = (String, (u32, u32), Option<ReaderHandle>);
+
+/// Latest camera frame, handed from the capture thread to the renderer
+#[derive(Default)]
+struct SharedFrame {
+    pixels: Vec<u32>,
+    size: (u32, u32),
+    /// Set by the capture thread, cleared when the renderer takes the frame
+    fresh: bool,
+}
+
+/// An open webcam device
+pub struct Webcam {
+    name: String,
+    size: (u32, u32),
+    frame: Arc<Mutex<SharedFrame>>,
+    stop: Arc<AtomicBool>,
+    reader: Option<ReaderHandle>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Webcam {
+    /// Open the video capture device with the given index (see list_devices)
+    pub fn open(index: usize) -> Result<Self> {
+        let frame = Arc::new(Mutex::new(SharedFrame::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (ready_tx, ready_rx) = mpsc::channel();
+
+        let thread_frame = Arc::clone(&frame);
+        let thread_stop = Arc::clone(&stop);
+        let thread = std::thread::Builder::new()
+            .name("webcam".to_string())
+            .spawn(move || run_capture(index, ready_tx, thread_frame, thread_stop))?;
+
+        // The thread reports the device name and frame size once the device is open
+        let (name, size, reader) = ready_rx
+            .recv()
+            .map_err(|_| anyhow!("Webcam thread exited before opening the device"))??;
+        info!("Webcam opened: {} ({}x{})", name, size.0, size.1);
+
+        Ok(Self {
+            name,
+            size,
+            frame,
+            stop,
+            reader,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Frame size delivered by the device (follows format changes)
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+}
+
+impl FrameSource for Webcam {
+    fn read_frame(&mut self, frame: &mut Vec<u32>) -> Result<Option<(u32, u32)>> {
+        let mut shared = self
+            .frame
+            .lock()
+            .map_err(|_| anyhow!("Webcam frame lock poisoned"))?;
+        if !shared.fresh {
+            return Ok(None);
+        }
+        shared.fresh = false;
+        // Swap instead of copying - the capture thread overwrites the old buffer
+        std::mem::swap(frame, &mut shared.pixels);
+        self.size = shared.size;
+        Ok(Some(shared.size))
+    }
+}
+
+impl Drop for Webcam {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(reader) = &self.reader {
+            flush(reader);
+        }
+        if let Some(thread) = self.thread.take() {
+            let deadline = Instant::now() + Duration::from_millis(webcam::STOP_TIMEOUT_MS);
+            while !thread.is_finished() && Instant::now() < deadline {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            if thread.is_finished() {
+                let _ = thread.join();
+            } else {
+                warn!("Webcam thread did not stop in time - it exits after the next frame");
+            }
+        }
+        info!("Webcam closed: {}", self.name);
+    }
+}
+
+/// Names of the video capture devices (index = device index for Webcam::open)
+#[cfg(windows)]
+pub fn list_devices() -> Vec<String> {
+    use windows::Win32::Media::MediaFoundation::{
+        MFShutdown, MFStartup, MFSTARTUP_NOSOCKET, MF_VERSION,
+    };
+
+    unsafe {
+        if let Err(e) = MFStartup(MF_VERSION, MFSTARTUP_NOSOCKET) {
+            error!("Failed to start Media Foundation: {}", e);
+            return Vec::new();
+        }
+        let names = match enum_devices() {
+            Ok(devices) => devices.iter().map(|d| friendly_name(d)).collect(),
+            Err(e) => {
+                error!("Failed to enumerate webcams: {}", e);
+                Vec::new()
+            }
+        };
+        let _ = MFShutdown();
+        names
+    }
+}
+
+#[cfg(not(windows))]
+pub fn list_devices() -> Vec<String> {
+    Vec::new()
+}
+
+/// Wake up a ReadSample pending on the capture thread (it returns without a
+/// sample)
+#[cfg(windows)]
+fn flush(reader: &ReaderHandle) {
+    use windows::Win32::Media::MediaFoundation::MF_SOURCE_READER_ALL_STREAMS;
+
+    match reader.resolve() {
+        Ok(reader) => unsafe {
+            if let Err(e) = reader.Flush(MF_SOURCE_READER_ALL_STREAMS.0 as u32) {
+                warn!("Failed to flush the webcam reader: {}", e);
+            }
+        },
+        Err(e) => warn!("Failed to reach the webcam reader: {}", e),
+    }
+}
+
+#[cfg(not(windows))]
+fn flush(_reader: &ReaderHandle) {}
+
+/// Capture thread: open the device, then copy frames until stopped
+#[cfg(windows)]
+fn run_capture(
+    index: usize,
+    ready: mpsc::Sender<Result<Opened>>,
+    frame: Arc<Mutex<SharedFrame>>,
+    stop: Arc<AtomicBool>,
+) {
+    use windows::core::AgileReference;
+    use windows::Win32::Media::MediaFoundation::{
+        MFShutdown, MFStartup, MFSTARTUP_NOSOCKET, MF_SOURCE_READERF_CURRENTMEDIATYPECHANGED,
+        MF_SOURCE_READERF_ENDOFSTREAM, MF_SOURCE_READER_FIRST_VIDEO_STREAM, MF_VERSION,
+    };
+    use windows::Win32::System::Com::{CoInitializeEx, COINIT_MULTITHREADED};
+
+    unsafe {
+        // STEP 1: COM and Media Foundation for this thread
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        if let Err(e) = MFStartup(MF_VERSION, MFSTARTUP_NOSOCKET) {
+            let _ = ready.send(Err(anyhow!("Failed to start Media Foundation: {}", e)));
+            return;
+        }
+
+        // STEP 2: Open the device
+        let (source, reader, mut size) = match open_reader(index) {
+            Ok((source, reader, name, size)) => {
+                let handle = match AgileReference::new(&reader) {
+                    Ok(handle) => Some(handle),
+                    Err(e) => {
+                        warn!("Webcam reader can't be flushed from other threads: {}", e);
+                        None
+                    }
+                };
+                let _ = ready.send(Ok((name, size, handle)));
+                (source, reader, size)
+            }
+            Err(e) => {
+                let _ = ready.send(Err(e));
+                let _ = MFShutdown();
+                return;
+            }
+        };
+
+        // STEP 3: Copy frames until the Webcam is dropped
+        let stream = MF_SOURCE_READER_FIRST_VIDEO_STREAM.0 as u32;
+        while !stop.load(Ordering::Relaxed) {
+            let mut flags = 0u32;
+            let mut sample = None;
+            if let Err(e) =
+                reader.ReadSample(stream, 0, None, Some(&mut flags), None, Some(&mut sample))
+            {
+                error!("Webcam read failed: {}", e);
+                break;
+            }
+            if flags & MF_SOURCE_READERF_ENDOFSTREAM.0 as u32 != 0 {
+                info!("Webcam stream ended");
+                break;
+            }
+            if flags & MF_SOURCE_READERF_CURRENTMEDIATYPECHANGED.0 as u32 != 0 {
+                match frame_size(&reader) {
+                    Ok(new_size) => {
+                        info!("Webcam format changed: {}x{}", new_size.0, new_size.1);
+                        size = new_size;
+                    }
+                    Err(e) => {
+                        error!("Failed to read the new webcam format: {}", e);
+                        break;
+                    }
+                }
+            }
+            let Some(sample) = sample else {
+                continue;
+            };
+            if let Err(e) = copy_sample(&sample, size, &frame) {
+                error!("Failed to copy webcam frame: {}", e);
+            }
+        }
+
+        let _ = source.Shutdown();
+        let _ = MFShutdown();
+    }
+}
+
+#[cfg(not(windows))]
+fn run_capture(
+    _index: usize,
+    ready: mpsc::Sender<Result<Opened>>,
+    _frame: Arc<Mutex<SharedFrame>>,
+    _stop: Arc<AtomicBool>,
+) {
+    let _ = ready.send(Err(anyhow!("Webcams are only supported on Windows")));
+}
+
+/// Video capture devices (Media Foundation must be started)
+#[cfg(windows)]
+unsafe fn enum_devices() -> Result<Vec<windows::Win32::Media::MediaFoundation::IMFActivate>> {
+    use windows::Win32::Media::MediaFoundation::{
+        MFCreateAttributes, MFEnumDeviceSources, MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE,
+        MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_GUID,
+    };
+    use windows::Win32::System::Com::CoTaskMemFree;
+
+    let mut attributes = None;
+    MFCreateAttributes(&mut attributes, 1)?;
+    let attributes = attributes.ok_or_else(|| anyhow!("MFCreateAttributes returned nothing"))?;
+    attributes.SetGUID(
+        &MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE,
+        &MF_DEVSOURCE_ATTRIBUTE_SOURCE_TYPE_VIDCAP_GUID,
+    )?;
+
+    let mut list = std::ptr::null_mut();
+    let mut count = 0u32;
+    MFEnumDeviceSources(&attributes, &mut list, &mut count)?;
+    if list.is_null() {
+        return Ok(Vec::new());
+    }
+    let devices = std::slice::from_raw_parts_mut(list, count as usize)
+        .iter_mut()
+        .filter_map(|device| device.take())
+        .collect();
+    CoTaskMemFree(Some(list as *const std::ffi::c_void));
+    Ok(devices)
+}
+
+/// Display name of a device (e.g. "Integrated Camera")
+#[cfg(windows)]
+unsafe fn friendly_name(device: &windows::Win32::Media::MediaFoundation::IMFActivate) -> String {
+    use windows::core::PWSTR;
+    use windows::Win32::Media::MediaFoundation::MF_DEVSOURCE_ATTRIBUTE_FRIENDLY_NAME;
+    use windows::Win32::System::Com::CoTaskMemFree;
+
+    let mut name = PWSTR::null();
+    let mut length = 0u32;
+    if device
+        .GetAllocatedString(
+            &MF_DEVSOURCE_ATTRIBUTE_FRIENDLY_NAME,
+            &mut name,
+            &mut length,
+        )
+        .is_err()
+    {
+        return "Unknown camera".to_string();
+    }
+    let text = name.to_string().unwrap_or_default();
+    CoTaskMemFree(Some(name.0 as *const std::ffi::c_void));
+    text
+}
+
+/// Activate the device and create a source reader delivering RGB32 frames
+#[cfg(windows)]
+unsafe fn open_reader(
+    index: usize,
+) -> Result<(
+    windows::Win32::Media::MediaFoundation::IMFMediaSource,
+    windows::Win32::Media::MediaFoundation::IMFSourceReader,
+    String,
+    (u32, u32),
+)> {
+    use windows::Win32::Media::MediaFoundation::{
+        IMFMediaSource, MFCreateAttributes, MFCreateMediaType, MFCreateSourceReaderFromMediaSource,
+        MFMediaType_Video, MFVideoFormat_RGB32, MF_MT_MAJOR_TYPE, MF_MT_SUBTYPE,
+        MF_SOURCE_READER_ENABLE_VIDEO_PROCESSING, MF_SOURCE_READER_FIRST_VIDEO_STREAM,
+    };
+
+    let devices = enum_devices()?;
+    let device = devices
+        .get(index)
+        .ok_or_else(|| anyhow!("No webcam with index {} ({} found)", index, devices.len()))?;
+    let name = friendly_name(device);
+    let source: IMFMediaSource = device.ActivateObject()?;
+
+    // Let the reader convert the camera format (usually NV12 / YUY2 / MJPG) to RGB32
+    let mut attributes = None;
+    MFCreateAttributes(&mut attributes, 1)?;
+    let attributes = attributes.ok_or_else(|| anyhow!("MFCreateAttributes returned nothing"))?;
+    attributes.SetUINT32(&MF_SOURCE_READER_ENABLE_VIDEO_PROCESSING, 1)?;
+    let reader = MFCreateSourceReaderFromMediaSource(&source, &attributes)?;
+
+    let stream = MF_SOURCE_READER_FIRST_VIDEO_STREAM.0 as u32;
+    let media_type = MFCreateMediaType()?;
+    media_type.SetGUID(&MF_MT_MAJOR_TYPE, &MFMediaType_Video)?;
+    media_type.SetGUID(&MF_MT_SUBTYPE, &MFVideoFormat_RGB32)?;
+    reader.SetCurrentMediaType(stream, None, &media_type)?;
+    let size = frame_size(&reader)?;
+
+    Ok((source, reader, name, size))
+}
+
+/// Frame size of the reader's current media type
+#[cfg(windows)]
+unsafe fn frame_size(
+    reader: &windows::Win32::Media::MediaFoundation::IMFSourceReader,
+) -> Result<(u32, u32)> {
+    use windows::Win32::Media::MediaFoundation::{
+        MF_MT_FRAME_SIZE, MF_SOURCE_READER_FIRST_VIDEO_STREAM,
+    };
+
+    // MF_MT_FRAME_SIZE packs width (high 32 bits) and height (low 32 bits)
+    let packed = reader
+        .GetCurrentMediaType(MF_SOURCE_READER_FIRST_VIDEO_STREAM.0 as u32)?
+        .GetUINT64(&MF_MT_FRAME_SIZE)?;
+    Ok(((packed >> 32) as u32, packed as u32))
+}
+
+/// Copy one RGB32 sample into the shared frame (top-down, opaque ARGB)
+#[cfg(windows)]
+unsafe fn copy_sample(
+    sample: &windows::Win32::Media::MediaFoundation::IMFSample,
+    (width, height): (u32, u32),
+    frame: &Mutex<SharedFrame>,
+) -> Result<()> {
+    use windows::core::Interface;
+    use windows::Win32::Media::MediaFoundation::{IMF2DBuffer2, MF2DBuffer_LockFlags_Read};
+
+    let buffer = sample.ConvertToContiguousBuffer()?;
+
+    // IMF2DBuffer2 reports the real pitch (negative for bottom-up images) and
+    // the locked memory; plain buffers are tightly packed top-down rows
+    let buffer_2d = buffer.cast::<IMF2DBuffer2>().ok();
+    let mut scanline0 = std::ptr::null_mut();
+    let mut pitch = (width * 4) as i32;
+    let mut start = std::ptr::null_mut();
+    let mut length = 0u32;
+    match &buffer_2d {
+        Some(buffer_2d) => buffer_2d.Lock2DSize(
+            MF2DBuffer_LockFlags_Read,
+            &mut scanline0,
+            &mut pitch,
+            &mut start,
+            &mut length,
+        )?,
+        None => {
+            buffer.Lock(&mut scanline0, None, Some(&mut length))?;
+            start = scanline0;
+        }
+    }
+
+    // Every row has to lie inside the locked memory (a driver may deliver a
+    // short buffer, or a frame of the previous size during a format change)
+    let row_bytes = width as isize * 4;
+    let first = scanline0 as isize - start as isize;
+    let last = first + (height as isize - 1) * pitch as isize;
+    let fits = height > 0
+        && (pitch.unsigned_abs() as isize) >= row_bytes
+        && first.min(last) >= 0
+        && first.max(last) + row_bytes <= length as isize;
+    if !fits {
+        match &buffer_2d {
+            Some(buffer_2d) => buffer_2d.Unlock2D()?,
+            None => buffer.Unlock()?,
+        }
+        return Err(anyhow!(
+            "Webcam buffer of {} bytes (pitch {}) does not hold a {}x{} frame",
+            length,
+            pitch,
+            width,
+            height
+        ));
+    }
+
+    if let Ok(mut shared) = frame.lock() {
+        shared.pixels.resize((width * height) as usize, 0);
+        for y in 0..height as usize {
+            let row = scanline0.offset(y as isize * pitch as isize) as *const u32;
+            let src = std::slice::from_raw_parts(row, width as usize);
+            let dst = &mut shared.pixels[y * width as usize..(y + 1) * width as usize];
+            // RGB32 is BGRX in memory - force the unused byte to opaque alpha
+            for (d, s) in dst.iter_mut().zip(src) {
+                *d = s | 0xFF000000;
+            }
+        }
+        shared.size = (width, height);
+        shared.fresh = true;
+    }
+
+    match &buffer_2d {
+        Some(buffer_2d) => buffer_2d.Unlock2D()?,
+        None => buffer.Unlock()?,
+    }
+    Ok(())
+}
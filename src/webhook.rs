@@ -0,0 +1,310 @@
+// webhook.rs - Webhook Notifications
+//
+// POSTs a JSON message to configured URLs when something happens, so a Slack
+// channel or a home-automation system can react to captures:
+//
+//   "webhooks": [
+//     {
+//       "url": "https://hooks.slack.com/services/...",
+//       "events": ["capture_start", "capture_stop"],
+//       "template": "{\"text\": \"RustFrame: {{message}}\"}"
+//     }
+//   ]
+//
+// Events:
+// - capture_start  the capture is running (after the countdown)
+// - capture_stop   the capture stopped
+// - stream_drop    the captured screen stopped delivering frames
+//                  (e.g. the monitor was disconnected)
+// - error          capture or rendering failed
+//
+// RustFrame does not write video files, so the capture events are about the
+// live output; the old ids recording_start / recording_stop are still
+// accepted in settings.json.
+//
+// An empty event list means every event. The template is the request body
+// with {{event}}, {{message}}, {{timestamp}} (Unix seconds), {{scene}} and
+// {{elapsed_seconds}} replaced in one pass - values are JSON-escaped and
+// never searched for placeholders themselves, so placeholders go inside
+// quotes. Without a template a default JSON object is sent.
+//
+// Requests go out on a background thread (see http.rs); failures are only
+// logged. Repeated stream drops and errors are sent at most once per
+// cooldown.
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::constants::webhook;
+use crate::stream_deck::RemoteState;
+
+/// A configured webhook (settings.json)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Webhook {
+    /// http:// or https:// URL the message is POSTed to
+    pub url: String,
+    /// Event ids that trigger it (empty = every event)
+    pub events: Vec<String>,
+    /// Request body with {{placeholders}} (empty = default JSON)
+    pub template: String,
+}
+
+/// Something a webhook can be sent for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WebhookEvent {
+    CaptureStart,
+    CaptureStop,
+    StreamDrop,
+    Error,
+}
+
+impl WebhookEvent {
+    pub const ALL: [WebhookEvent; 4] = [
+        WebhookEvent::CaptureStart,
+        WebhookEvent::CaptureStop,
+        WebhookEvent::StreamDrop,
+        WebhookEvent::Error,
+    ];
+
+    /// Identifier used in settings.json and in {{event}}
+    pub fn id(self) -> &'static str {
+        match self {
+            WebhookEvent::CaptureStart => "capture_start",
+            WebhookEvent::CaptureStop => "capture_stop",
+            WebhookEvent::StreamDrop => "stream_drop",
+            WebhookEvent::Error => "error",
+        }
+    }
+
+    /// Event for an id of settings.json (also the old recording_* ids)
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "recording_start" => Some(WebhookEvent::CaptureStart),
+            "recording_stop" => Some(WebhookEvent::CaptureStop),
+            _ => Self::ALL.into_iter().find(|e| e.id() == id),
+        }
+    }
+
+    /// Whether repeats are limited to one per cooldown
+    fn throttled(self) -> bool {
+        matches!(self, WebhookEvent::StreamDrop | WebhookEvent::Error)
+    }
+}
+
+/// A request for the sender thread
+struct Delivery {
+    url: String,
+    body: String,
+}
+
+/// Sends webhooks on a background thread
+pub struct WebhookSender {
+    sender: Sender<Delivery>,
+    /// None once drained
+    thread: Option<std::thread::JoinHandle<()>>,
+    /// When each throttled event was last sent
+    last_sent: HashMap<WebhookEvent, Instant>,
+}
+
+impl WebhookSender {
+    pub fn new() -> Self {
+        let (sender, receiver) = std::sync::mpsc::channel::<Delivery>();
+        // Ends when the sender is dropped
+        let thread = std::thread::spawn(move || {
+            for delivery in receiver {
+                match post(&delivery.url, &delivery.body) {
+                    Ok(status) if (200..300).contains(&status) => {
+                        info!("Webhook sent to {}", delivery.url)
+                    }
+                    Ok(status) => warn!("Webhook {} answered HTTP {}", delivery.url, status),
+                    Err(e) => warn!("Webhook {} failed: {:#}", delivery.url, e),
+                }
+            }
+        });
+        Self {
+            sender,
+            thread: Some(thread),
+            last_sent: HashMap::new(),
+        }
+    }
+
+    /// Wait up to `timeout` for the webhooks already queued (at exit)
+    pub fn drain(&mut self, timeout: Duration) {
+        // The thread ends once the old sender is gone; later events go nowhere
+        self.sender = std::sync::mpsc::channel().0;
+        let Some(thread) = self.thread.take() else {
+            return;
+        };
+        let started = Instant::now();
+        while !thread.is_finished() {
+            if started.elapsed() >= timeout {
+                warn!("Exiting before all webhooks were sent");
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    /// Send `event` to every webhook that wants it
+    pub fn send(
+        &mut self,
+        webhooks: &[Webhook],
+        event: WebhookEvent,
+        message: &str,
+        state: &RemoteState,
+    ) {
+        let wanted: Vec<&Webhook> = webhooks
+            .iter()
+            .filter(|w| !w.url.trim().is_empty())
+            .filter(|w| {
+                w.events.is_empty()
+                    || w.events
+                        .iter()
+                        .any(|e| WebhookEvent::from_id(e) == Some(event))
+            })
+            .collect();
+        if wanted.is_empty() {
+            return;
+        }
+
+        if event.throttled() {
+            let cooldown = Duration::from_millis(webhook::COOLDOWN_MS);
+            if self
+                .last_sent
+                .get(&event)
+                .is_some_and(|t| t.elapsed() < cooldown)
+            {
+                return;
+            }
+            self.last_sent.insert(event, Instant::now());
+        }
+
+        for hook in wanted {
+            let body = render(&hook.template, event, message, state);
+            let _ = self.sender.send(Delivery {
+                url: hook.url.trim().to_string(),
+                body,
+            });
+        }
+    }
+}
+
+/// Request body for an event
+fn render(template: &str, event: WebhookEvent, message: &str, state: &RemoteState) -> String {
+    let template = if template.trim().is_empty() {
+        webhook::DEFAULT_TEMPLATE
+    } else {
+        template
+    };
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let value = |name: &str| match name {
+        "event" => Some(escape(event.id())),
+        "message" => Some(escape(message)),
+        "timestamp" => Some(timestamp.to_string()),
+        "scene" => Some(escape(state.scene.as_deref().unwrap_or(""))),
+        "elapsed_seconds" => Some(state.elapsed_seconds.to_string()),
+        _ => None,
+    };
+
+    // One pass over the template: a value that contains "{{...}}" (a scene
+    // name, an error message) is copied as it is
+    let mut body = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        body.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after
+            .find("}}")
+            .and_then(|end| Some((value(&after[..end])?, end)))
+        {
+            Some((text, end)) => {
+                body.push_str(&text);
+                rest = &after[end + 2..];
+            }
+            // Unknown placeholders stay in the body
+            None => {
+                body.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    body.push_str(rest);
+    body
+}
+
+/// A string escaped for use inside JSON quotes
+fn escape(text: &str) -> String {
+    let quoted = serde_json::to_string(text).unwrap_or_default();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+/// POST a JSON body, returning the HTTP status
+fn post(url: &str, body: &str) -> anyhow::Result<u32> {
+    let headers = [("Content-Type", "application/json".to_string())];
+    let response = crate::http::request(
+        "POST",
+        url,
+        &headers,
+        body.as_bytes(),
+        webhook::TIMEOUT_MS,
+        &mut |_| {},
+    )?;
+    Ok(response.status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_are_inserted_once_and_escaped() {
+        let state = RemoteState {
+            scene: Some("Demo {{event}}".to_string()),
+            elapsed_seconds: 42,
+            ..Default::default()
+        };
+        let body = render(
+            "{{event}}|{{scene}}|{{elapsed_seconds}}|{{message}}",
+            WebhookEvent::CaptureStart,
+            "say \"hi\" {{scene}}",
+            &state,
+        );
+        assert_eq!(
+            body,
+            "capture_start|Demo {{event}}|42|say \\\"hi\\\" {{scene}}"
+        );
+    }
+
+    #[test]
+    fn unknown_and_unterminated_placeholders_stay() {
+        let state = RemoteState::default();
+        let body = render(
+            "{{unknown}} {{event}} {{event",
+            WebhookEvent::Error,
+            "",
+            &state,
+        );
+        assert_eq!(body, "{{unknown}} error {{event");
+    }
+
+    #[test]
+    fn empty_template_sends_the_default_json() {
+        let body = render(
+            " ",
+            WebhookEvent::CaptureStop,
+            "Done",
+            &RemoteState::default(),
+        );
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["event"], "capture_stop");
+        assert_eq!(json["message"], "Done");
+        assert!(json["timestamp"].is_u64());
+    }
+}
@@ -1,8 +1,9 @@
 // window_manager.rs - Window Management for Overlay and Destination Windows
 //
-// This module manages two types of windows:
+// This module manages three types of windows:
 // 1. OverlayWindow: A transparent, frameless window for region selection
 // 2. DestinationWindow: A normal window that displays the captured content
+// 3. PopupWindow: Local-only popups (magnifier lens, countdown, toolbar)
 //
 // OVERLAY WINDOW REQUIREMENTS:
 // - Transparent background
@@ -24,12 +25,22 @@ use winit::{
     dpi::{LogicalSize, PhysicalPosition, PhysicalSize},
     event_loop::ActiveEventLoop,
     raw_window_handle::{HasWindowHandle, RawWindowHandle},
-    window::{Window, WindowAttributes, WindowId, WindowLevel},
+    window::{Theme, Window, WindowAttributes, WindowId, WindowLevel},
 };
 
+use crate::annotation::AnnotationLayer;
 use crate::bitmap_font;
 use crate::capture::CaptureRect;
-use crate::constants::{colors, overlay, text_box};
+use crate::color_picker::ColorPicker;
+use crate::constants::{
+    annotation, color_picker, grid, layout_editor, magnifier, measure, overlay, privacy, text_box,
+};
+use crate::drawing;
+use crate::grid::GridStyle;
+use crate::layout_editor::LayoutEditor;
+use crate::measure::Measurement;
+use crate::privacy::PrivacyRegions;
+use crate::text;
 
 #[cfg(windows)]
 use windows::Win32::{
@@ -44,6 +55,22 @@ thread_local! {
     static OVERLAY_HWND: Cell<isize> = const { Cell::new(0) };
     // Settings state for display in overlay (show_cursor, show_border, exclude_from_capture)
     static SETTINGS_STATE: Cell<(bool, bool, bool)> = const { Cell::new((true, true, true)) };
+    // When true, the hollow frame interior receives mouse input (draw mode)
+    static INTERACTIVE_INTERIOR: Cell<bool> = const { Cell::new(false) };
+    // Composition grid drawn inside the selection overlay
+    static GRID_STYLE: Cell<GridStyle> = const { Cell::new(GridStyle::Off) };
+    // Settings line with keyboard focus (Tab), outlined in the help text
+    static FOCUSED_OPTION: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Whether the input message being processed was promoted from touch or pen
+/// (mouse messages synthesized by Windows carry the MI_WP_SIGNATURE extra info)
+#[cfg(windows)]
+fn is_touch_or_pen_input() -> bool {
+    const MI_WP_SIGNATURE: u32 = 0xFF51_5700;
+    const SIGNATURE_MASK: u32 = 0xFFFF_FF00;
+    let extra_info = unsafe { GetMessageExtraInfo() };
+    (extra_info.0 as u32) & SIGNATURE_MASK == MI_WP_SIGNATURE
 }
 
 /// Wrapper for the overlay (selector) window
@@ -184,6 +211,12 @@ impl OverlayWindow {
 
         // Custom hit testing for frameless window
         if msg == WM_NCHITTEST {
+            // Touch and pen are handled by the app (touch.rs), not by the
+            // system move / size loop
+            if is_touch_or_pen_input() {
+                return LRESULT(HTCLIENT as isize);
+            }
+
             let x = (lparam.0 & 0xFFFF) as i16 as i32;
             let y = ((lparam.0 >> 16) & 0xFFFF) as i16 as i32;
 
@@ -233,6 +266,23 @@ impl OverlayWindow {
     /// Draw the selection overlay directly from HWND and size (used by subclass on resize)
     #[cfg(windows)]
     fn draw_selection_overlay_hwnd(hwnd: HWND, width: i32, height: i32) {
+        // Help text and its box follow the DPI of the overlay's monitor
+        let dpi = unsafe { windows::Win32::UI::HiDpi::GetDpiForWindow(hwnd) };
+        let scale = if dpi == 0 { 1.0 } else { dpi as f32 / 96.0 };
+        Self::update_layered_pixels(hwnd, width, height, |pixels, width, height| {
+            Self::render_overlay_pixels(pixels, width, height, scale)
+        });
+    }
+
+    /// Render pixels with the given function and push them to the layered window
+    /// The pixel buffer is premultiplied ARGB, top-down
+    #[cfg(windows)]
+    fn update_layered_pixels(
+        hwnd: HWND,
+        width: i32,
+        height: i32,
+        render: impl FnOnce(&mut [u32], i32, i32),
+    ) {
         use windows::Win32::Foundation::POINT;
         use windows::Win32::Graphics::Gdi::{
             CreateCompatibleDC, CreateDIBSection, DeleteDC, SelectObject, BITMAPINFO,
@@ -278,7 +328,7 @@ impl OverlayWindow {
             // Draw the overlay content to the bitmap
             let pixels =
                 std::slice::from_raw_parts_mut(bits as *mut u32, (width * height) as usize);
-            Self::render_overlay_pixels(pixels, width, height);
+            render(pixels, width, height);
 
             // Update the layered window with our bitmap
             let blend = windows::Win32::Graphics::Gdi::BLENDFUNCTION {
@@ -316,18 +366,22 @@ impl OverlayWindow {
 
     /// Render the overlay content to a pixel buffer (shared by all overlay drawing methods)
     #[cfg(windows)]
-    fn render_overlay_pixels(pixels: &mut [u32], width: i32, height: i32) {
+    fn render_overlay_pixels(pixels: &mut [u32], width: i32, height: i32, scale: f32) {
         let border_width = overlay::BORDER_WIDTH;
         let corner_size = overlay::CORNER_SIZE;
 
         // Calculate text box dimensions (centered, clamped to window size)
-        let tb_width = text_box::WIDTH.min(width - 20);
-        let tb_height = text_box::HEIGHT.min(height - 20);
+        let tb_width = text::scaled(text_box::WIDTH, scale).min(width - 20);
+        let tb_height = text::scaled(text_box::HEIGHT, scale).min(height - 20);
         let tb_left = (width - tb_width) / 2;
         let tb_top = (height - tb_height) / 2;
         let tb_right = tb_left + tb_width;
         let tb_bottom = tb_top + tb_height;
-        let tb_border = text_box::BORDER_WIDTH;
+        let tb_border = text::scaled(text_box::BORDER_WIDTH, scale);
+        let colors = crate::theme::palette();
+
+        // Composition grid lines (all false when the grid is off)
+        let (grid_columns, grid_rows) = GRID_STYLE.with(|g| g.get()).line_masks(width, height);
 
         for y in 0..height {
             for x in 0..width {
@@ -361,24 +415,36 @@ impl OverlayWindow {
                         || y >= tb_bottom - tb_border);
 
                 pixels[idx] = if in_corner {
-                    colors::CORNER
+                    colors.corner
                 } else if on_border {
-                    colors::BORDER
+                    colors.border
                 } else if on_text_box_border {
-                    colors::TEXT_BORDER
+                    colors.text_border
                 } else if in_text_box {
-                    colors::TEXT_BG
+                    colors.text_bg
+                } else if grid_columns[x as usize] || grid_rows[y as usize] {
+                    grid::OVERLAY_COLOR
                 } else {
-                    colors::FILL
+                    colors.fill
                 };
             }
         }
 
         // Get settings state from thread-local storage
         let (show_cursor, show_border, exclude_from_capture) = SETTINGS_STATE.with(|s| s.get());
+        let focused = FOCUSED_OPTION.with(|f| f.get());
         
-        // Draw help text using the bitmap font module with settings state
-        bitmap_font::draw_help_text(pixels, width, height, show_cursor, show_border, exclude_from_capture);
+        // Draw help text with settings state
+        text::draw_help_text(
+            pixels,
+            width,
+            height,
+            scale,
+            show_cursor,
+            show_border,
+            exclude_from_capture,
+            focused,
+        );
     }
 
     /// Draw the selection overlay with semi-transparent background, border, and help text
@@ -436,6 +502,13 @@ impl OverlayWindow {
         self.window.set_visible(true);
     }
 
+    /// Show the window again after it was hidden to the tray (un-minimizes it)
+    pub fn restore(&self) {
+        self.window.set_minimized(false);
+        self.window.set_visible(true);
+        self.window.focus_window();
+    }
+
     /// Set the window title
     pub fn set_title(&self, title: &str) {
         self.window.set_title(title);
@@ -472,6 +545,20 @@ impl OverlayWindow {
         }
     }
 
+    /// Get the rectangle of the monitor the overlay is on (in screen coordinates)
+    pub fn get_monitor_rect(&self) -> Option<CaptureRect> {
+        let monitor = self.window.current_monitor()?;
+        let position = monitor.position();
+        let size = monitor.size();
+
+        Some(CaptureRect {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        })
+    }
+
     /// Get the capture rectangle INSIDE the border (excludes border from capture)
     /// This is used when border is visible to avoid capturing the border itself
     pub fn get_capture_rect_inner(&self, border_width: u32) -> CaptureRect {
@@ -509,6 +596,16 @@ impl OverlayWindow {
         }
     }
 
+    /// Move and resize the overlay (e.g. when switching scenes)
+    /// The resulting Moved / Resized events update the capture region
+    pub fn set_bounds(&self, position: (i32, i32), size: (u32, u32)) {
+        self.window
+            .set_outer_position(PhysicalPosition::new(position.0, position.1));
+        let _ = self
+            .window
+            .request_inner_size(PhysicalSize::new(size.0, size.1));
+    }
+
     /// Convert the overlay to a hollow frame (only border visible, interior click-through)
     /// Uses SetWindowRgn for the visual appearance and subclass for hit testing
     #[cfg(windows)]
@@ -644,6 +741,19 @@ impl OverlayWindow {
                 let border = BORDER_WIDTH.with(|b| b.get()) as i32;
                 let resize_margin = border.max(8); // At least 8px for resize
 
+                // Touch and pen on the border are handled by the app (touch.rs);
+                // the interior stays click-through unless draw mode owns it
+                if is_touch_or_pen_input() {
+                    let in_interior = x >= rect.left + resize_margin
+                        && x < rect.right - resize_margin
+                        && y >= rect.top + resize_margin
+                        && y < rect.bottom - resize_margin;
+                    if in_interior && !INTERACTIVE_INTERIOR.with(|i| i.get()) {
+                        return LRESULT(HTTRANSPARENT as isize);
+                    }
+                    return LRESULT(HTCLIENT as isize);
+                }
+
                 // Check if on edges for resize
                 let on_left = x >= rect.left - 4 && x < rect.left + resize_margin;
                 let on_right = x >= rect.right - resize_margin && x < rect.right + 4;
@@ -685,6 +795,11 @@ impl OverlayWindow {
                     return LRESULT(HTCAPTION as isize); // Allow dragging from border
                 }
 
+                // In draw mode the interior belongs to us (mouse strokes)
+                if INTERACTIVE_INTERIOR.with(|i| i.get()) {
+                    return LRESULT(HTCLIENT as isize);
+                }
+
                 // Interior is transparent (click-through)
                 return LRESULT(HTTRANSPARENT as isize);
             }
@@ -743,11 +858,213 @@ impl OverlayWindow {
     #[cfg(not(windows))]
     pub fn update_hollow_frame(&self, _border_width: u32) {}
 
+    /// Change the composition grid shown inside the selection overlay
+    #[cfg(windows)]
+    pub fn set_grid_style(&self, style: GridStyle) -> Result<()> {
+        GRID_STYLE.with(|g| g.set(style));
+        self.redraw_selection_overlay()
+    }
+
+    #[cfg(not(windows))]
+    pub fn set_grid_style(&self, style: GridStyle) -> Result<()> {
+        GRID_STYLE.with(|g| g.set(style));
+        Ok(())
+    }
+
+    /// Move the keyboard focus indicator to a settings line (None = no line)
+    /// Shown with the next redraw (see update_settings_display)
+    pub fn set_focused_option(&self, focused: Option<usize>) {
+        FOCUSED_OPTION.with(|f| f.set(focused));
+    }
+
+    /// Give the overlay keyboard focus (arrow keys, Tab, Enter)
+    pub fn focus(&self) {
+        self.window.focus_window();
+    }
+
+    /// Resize the window by a delta, keeping the top-left corner in place
+    pub fn resize_by(&self, delta_width: i32, delta_height: i32) {
+        let size = self.window.inner_size();
+        let width = (size.width as i32 + delta_width).max(overlay::MIN_WIDTH as i32);
+        let height = (size.height as i32 + delta_height).max(overlay::MIN_HEIGHT as i32);
+        let _ = self
+            .window
+            .request_inner_size(PhysicalSize::new(width as u32, height as u32));
+    }
+
     #[cfg(not(windows))]
     pub fn make_hollow_frame(&self, _border_width: u32) {
         info!("Hollow frame not supported on this platform");
     }
     
+    /// Enter or leave annotation draw mode (or measure mode) during capture
+    ///
+    /// In draw mode the whole window (not just the border) receives mouse input and
+    /// shows the strokes locally. The window is excluded from capture meanwhile, so
+    /// strokes reach the output only through the renderer's compositing - never twice.
+    /// `border_width` is the hollow frame border to restore when leaving (0 = no border).
+    #[cfg(windows)]
+    pub fn set_draw_mode(&self, enabled: bool, border_width: u32) {
+        use windows::Win32::Graphics::Gdi::SetWindowRgn;
+        use windows::Win32::UI::WindowsAndMessaging::{
+            SetWindowDisplayAffinity, WDA_EXCLUDEFROMCAPTURE, WDA_NONE,
+        };
+        use winit::window::CursorIcon;
+
+        INTERACTIVE_INTERIOR.with(|i| i.set(enabled));
+
+        let handle = match self.window.window_handle() {
+            Ok(h) => h,
+            Err(_) => return,
+        };
+
+        if let RawWindowHandle::Win32(win32_handle) = handle.as_raw() {
+            unsafe {
+                let hwnd = HWND(win32_handle.hwnd.get() as *mut std::ffi::c_void);
+
+                let affinity = if enabled {
+                    WDA_EXCLUDEFROMCAPTURE
+                } else {
+                    WDA_NONE
+                };
+                if let Err(e) = SetWindowDisplayAffinity(hwnd, affinity) {
+                    error!("Failed to set overlay display affinity: {}", e);
+                }
+
+                if enabled {
+                    // Drop the hollow region so the interior can receive clicks
+                    SetWindowRgn(hwnd, None, true);
+                }
+            }
+        }
+
+        if enabled {
+            self.window.set_cursor(CursorIcon::Crosshair);
+            info!("Overlay entered draw mode");
+        } else {
+            self.window.set_cursor(CursorIcon::Default);
+
+            // Restore the regular frame look and the hollow region
+            if let Err(e) = self.redraw_selection_overlay() {
+                error!("Failed to redraw overlay after draw mode: {}", e);
+            }
+            if border_width > 0 {
+                self.update_hollow_frame(border_width);
+            }
+            info!("Overlay left draw mode");
+        }
+    }
+
+    #[cfg(not(windows))]
+    pub fn set_draw_mode(&self, _enabled: bool, _border_width: u32) {
+        info!("Draw mode not supported on this platform");
+    }
+
+    /// Redraw the overlay in draw mode: highlighted border, input-catching interior
+    /// and the current annotation strokes (offset by the border width)
+    pub fn draw_annotations(&self, border_width: u32, layer: &AnnotationLayer) {
+        let border = border_width as i32;
+        self.draw_interactive(border_width, annotation::BORDER, |canvas| {
+            layer.draw(canvas, border, border);
+        });
+    }
+
+    /// Redraw the overlay in measure mode: measure-colored border, input-catching
+    /// interior and the current measurement with its readout
+    pub fn draw_measurement(&self, border_width: u32, measurement: &Measurement) {
+        let border = border_width as i32;
+        let scale = self.window.scale_factor() as f32;
+        self.draw_interactive(border_width, measure::BORDER, |canvas| {
+            measurement.draw(canvas, border, border, scale);
+        });
+    }
+
+    /// Redraw the overlay in color picker mode: pink border, input-catching
+    /// interior and the readout of the pixel under the cursor
+    pub fn draw_color_picker(&self, border_width: u32, picker: &ColorPicker) {
+        let border = border_width as i32;
+        let scale = self.window.scale_factor() as f32;
+        self.draw_interactive(border_width, color_picker::BORDER, |canvas| {
+            picker.draw(canvas, border, border, scale);
+        });
+    }
+
+    /// Redraw the overlay in redact mode: red border, input-catching interior and
+    /// the outlines of the privacy regions (the redaction itself is output-only)
+    pub fn draw_privacy_regions(&self, border_width: u32, regions: &PrivacyRegions) {
+        let border = border_width as i32;
+        let scale = self.window.scale_factor() as f32;
+        self.draw_interactive(border_width, privacy::BORDER, |canvas| {
+            regions.draw_outlines(canvas, border, border, scale);
+        });
+    }
+
+    /// Redraw the overlay in the layout editor: green border, input-catching
+    /// interior and the boxes of the output sources
+    pub fn draw_layout_editor(&self, border_width: u32, editor: &LayoutEditor) {
+        let border = border_width as i32;
+        let scale = self.window.scale_factor() as f32;
+        self.draw_interactive(border_width, layout_editor::BORDER, |canvas| {
+            editor.draw(canvas, border, border, scale);
+        });
+    }
+
+    /// Shared drawing for the interactive overlay modes (draw / measure / redact):
+    /// a colored border ring, an almost transparent clickable interior, then
+    /// the mode's own content drawn by `render`
+    #[cfg(windows)]
+    fn draw_interactive(
+        &self,
+        border_width: u32,
+        border_color: u32,
+        render: impl FnOnce(&mut bitmap_font::Canvas),
+    ) {
+        let handle = match self.window.window_handle() {
+            Ok(h) => h,
+            Err(_) => return,
+        };
+
+        if let RawWindowHandle::Win32(win32_handle) = handle.as_raw() {
+            let hwnd = HWND(win32_handle.hwnd.get() as *mut std::ffi::c_void);
+            let size = self.window.inner_size();
+            let border = border_width as i32;
+
+            Self::update_layered_pixels(
+                hwnd,
+                size.width as i32,
+                size.height as i32,
+                |pixels, width, height| {
+                    let mut canvas = bitmap_font::Canvas {
+                        pixels,
+                        width,
+                        height,
+                    };
+
+                    // Border ring, then the (almost transparent) clickable interior
+                    drawing::fill_rect(&mut canvas, 0, 0, width, height, border_color);
+                    drawing::fill_rect(
+                        &mut canvas,
+                        border,
+                        border,
+                        width - 2 * border,
+                        height - 2 * border,
+                        annotation::INTERIOR,
+                    );
+                    render(&mut canvas);
+                },
+            );
+        }
+    }
+
+    #[cfg(not(windows))]
+    fn draw_interactive(
+        &self,
+        _border_width: u32,
+        _border_color: u32,
+        _render: impl FnOnce(&mut bitmap_font::Canvas),
+    ) {
+    }
+
     /// Restore overlay window to selection mode (from hollow frame mode)
     /// This reverses the changes made by make_hollow_frame()
     #[cfg(windows)]
@@ -1022,6 +1339,12 @@ impl DestinationWindow {
     pub fn request_redraw(&self) {
         self.window.request_redraw();
     }
+
+    /// Light or dark title bar (development mode, see theme.rs)
+    pub fn set_dark(&self, dark: bool) {
+        self.window
+            .set_theme(Some(if dark { Theme::Dark } else { Theme::Light }));
+    }
     
     /// Hide the destination window
     pub fn hide(&self) {
@@ -1029,6 +1352,12 @@ impl DestinationWindow {
         info!("Destination window hidden");
     }
 
+    /// Show the window again after it was hidden to the tray (un-minimizes it)
+    pub fn restore(&self) {
+        self.window.set_minimized(false);
+        self.window.set_visible(true);
+    }
+
     /// Resize the destination window
     pub fn resize(&self, size: PhysicalSize<u32>) {
         let _ = self.window.request_inner_size(size);
@@ -1193,6 +1522,138 @@ impl DestinationWindow {
     }
 }
 
+/// Wrapper for local-only popup windows (magnifier lens, countdown, toolbar)
+/// Never activated and excluded from capture so the popup is only visible on
+/// the presenter's screen. Click-through unless created with new_clickable.
+pub struct PopupWindow {
+    window: Arc<Window>,
+}
+
+impl PopupWindow {
+    /// Create a (initially hidden) click-through popup window
+    pub fn new(event_loop: &ActiveEventLoop, title: &str) -> Result<Self> {
+        Self::create(event_loop, title, true)
+    }
+
+    /// Create a (initially hidden) popup window that receives mouse input
+    pub fn new_clickable(event_loop: &ActiveEventLoop, title: &str) -> Result<Self> {
+        Self::create(event_loop, title, false)
+    }
+
+    fn create(event_loop: &ActiveEventLoop, title: &str, click_through: bool) -> Result<Self> {
+        info!("Creating popup window: {}", title);
+
+        let size = magnifier::LENS_SIZE as u32;
+        let attributes = WindowAttributes::default()
+            .with_title(title)
+            .with_inner_size(PhysicalSize::new(size, size))
+            .with_resizable(false)
+            .with_decorations(false)
+            .with_transparent(true)
+            .with_active(false)
+            .with_visible(false)
+            .with_window_level(WindowLevel::AlwaysOnTop);
+
+        let window = event_loop
+            .create_window(attributes)
+            .context("Failed to create popup window")?;
+
+        #[cfg(windows)]
+        Self::apply_popup_style(&window, click_through)?;
+        #[cfg(not(windows))]
+        let _ = click_through;
+
+        Ok(Self {
+            window: Arc::new(window),
+        })
+    }
+
+    /// Make the window layered, non-activating, excluded from capture and
+    /// optionally click-through
+    #[cfg(windows)]
+    fn apply_popup_style(window: &Window, click_through: bool) -> Result<()> {
+        let handle = window
+            .window_handle()
+            .context("Failed to get window handle")?;
+
+        if let RawWindowHandle::Win32(win32_handle) = handle.as_raw() {
+            unsafe {
+                let hwnd = HWND(win32_handle.hwnd.get() as *mut std::ffi::c_void);
+
+                let mut ex_style =
+                    WS_EX_LAYERED | WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE;
+                if click_through {
+                    ex_style |= WS_EX_TRANSPARENT;
+                }
+                SetWindowLongPtrW(hwnd, GWL_EXSTYLE, ex_style.0 as isize);
+
+                // A popup must never end up in the capture it is drawn over
+                SetWindowDisplayAffinity(hwnd, WDA_EXCLUDEFROMCAPTURE)
+                    .context("Failed to exclude popup from capture")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Render the popup with the given function and show it at a screen position
+    /// The pixel buffer is premultiplied ARGB
+    #[cfg(windows)]
+    pub fn update(
+        &self,
+        position: PhysicalPosition<i32>,
+        size: PhysicalSize<u32>,
+        render: impl FnOnce(&mut bitmap_font::Canvas),
+    ) {
+        let handle = match self.window.window_handle() {
+            Ok(h) => h,
+            Err(_) => return,
+        };
+
+        if let RawWindowHandle::Win32(win32_handle) = handle.as_raw() {
+            let hwnd = HWND(win32_handle.hwnd.get() as *mut std::ffi::c_void);
+
+            self.window.set_outer_position(position);
+            OverlayWindow::update_layered_pixels(
+                hwnd,
+                size.width as i32,
+                size.height as i32,
+                |pixels, width, height| {
+                    render(&mut bitmap_font::Canvas {
+                        pixels,
+                        width,
+                        height,
+                    });
+                },
+            );
+            self.window.set_visible(true);
+        }
+    }
+
+    #[cfg(not(windows))]
+    pub fn update(
+        &self,
+        _position: PhysicalPosition<i32>,
+        _size: PhysicalSize<u32>,
+        _render: impl FnOnce(&mut bitmap_font::Canvas),
+    ) {
+    }
+
+    /// Hide the popup
+    pub fn hide(&self) {
+        self.window.set_visible(false);
+    }
+
+    pub fn window_id(&self) -> WindowId {
+        self.window.id()
+    }
+
+    /// DPI scale of the monitor the popup is on (1.5 at 150 %)
+    pub fn scale_factor(&self) -> f32 {
+        self.window.scale_factor() as f32
+    }
+}
+
 // Note: For a production-quality overlay, you'd want to implement:
 // 1. True transparency using Win32 APIs (SetLayeredWindowAttributes)
 // 2. Frameless window with custom resize handles
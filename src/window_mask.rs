@@ -0,0 +1,455 @@
+// window_mask.rs - Redaction of Listed Applications and Excluded Windows
+//
+// Windows that must not appear in the output are blacked out:
+// - Any visible window that belongs to a listed process (password manager, chat,
+//   mail...) and overlaps the capture region
+// - Individual windows the user picked (e.g. a floating notes window)
+// - Windows toast notifications and other shell popups, if enabled
+//
+// SetWindowDisplayAffinity(WDA_EXCLUDEFROMCAPTURE) only works on windows owned
+// by the calling process, so other applications' windows are removed by masking.
+//
+// HOW IT WORKS:
+// 1. Top-level windows are enumerated (EnumWindows) at a short interval
+// 2. Each window's process image name is looked up (once per process ID and
+//    scan - IDs are reused after a process exits, so names are not kept)
+// 3. Listed and picked windows are intersected with every capture region (the
+//    main one and the additional ones)
+// 4. Each region's frame gets its rectangles filled with the mask color before
+//    the frames are composited (see Redaction in renderer.rs), so the masks
+//    follow the frame wherever the layout puts it
+//
+// Masking happens before any other overlay, so the spotlight or magnifier can
+// never reveal what is underneath. The whole window rectangle is masked even
+// when it is partly covered by other windows - better too much than too little.
+// For the same reason a window whose process can't be identified (access
+// denied, process just exiting) is masked while the application list is in use.
+//
+// A misspelled list entry silently hides nothing, so entries are normalized to
+// process file names ("C:\...\KeePassXC" -> "keepassxc.exe") and the first
+// scan after the list changes warns about entries no open window belongs to.
+
+use log::warn;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::bitmap_font::Canvas;
+use crate::capture::CaptureRect;
+use crate::constants::window_mask;
+use crate::drawing;
+
+/// A visible top-level window (virtual screen coordinates)
+#[derive(Debug, Clone, Copy)]
+pub struct WindowInfo {
+    pub hwnd: isize,
+    pub pid: u32,
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+/// Tracks the windows to mask and their rectangles in frame coordinates
+pub struct WindowMasker {
+    /// Lowercased process image names, e.g. "keepassxc.exe"
+    apps: Vec<String>,
+    /// Individually excluded top-level windows (HWND values)
+    excluded: Vec<isize>,
+    /// Also mask Windows notification popups (toasts)
+    hide_notifications: bool,
+    /// Rectangles to fill per capture region (0 = main region, i + 1 =
+    /// additional region i), relative to that region (x, y, width, height)
+    rects: Vec<Vec<(i32, i32, i32, i32)>>,
+    /// Process ID -> lowercased image name, for the current scan only
+    process_names: HashMap<u32, String>,
+    /// The list changed - report entries that match no window after the next scan
+    check_apps: bool,
+    last_refresh: Option<Instant>,
+    /// Bumped whenever the masked rectangles change
+    revision: u64,
+}
+
+impl WindowMasker {
+    pub fn new(apps: &[String]) -> Self {
+        let mut masker = Self {
+            apps: Vec::new(),
+            excluded: Vec::new(),
+            hide_notifications: false,
+            rects: Vec::new(),
+            process_names: HashMap::new(),
+            check_apps: false,
+            last_refresh: None,
+            revision: 0,
+        };
+        masker.set_apps(apps);
+        masker
+    }
+
+    /// Replace the list of redacted applications (process file names)
+    pub fn set_apps(&mut self, apps: &[String]) {
+        self.apps = apps.iter().filter_map(|a| normalize_app(a)).collect();
+        self.check_apps = !self.apps.is_empty();
+        // Force a refresh on the next frame
+        self.last_refresh = None;
+        self.clear_rects_if_idle();
+    }
+
+    /// Add the window to the excluded windows, or remove it if already excluded
+    /// Returns true if the window is excluded afterwards
+    pub fn toggle_window(&mut self, hwnd: isize) -> bool {
+        self.last_refresh = None;
+        if let Some(index) = self.excluded.iter().position(|&h| h == hwnd) {
+            self.excluded.remove(index);
+            self.clear_rects_if_idle();
+            false
+        } else {
+            self.excluded.push(hwnd);
+            true
+        }
+    }
+
+    /// Mask toast notifications (and other shell popups) while capturing
+    pub fn set_hide_notifications(&mut self, hide: bool) {
+        self.hide_notifications = hide;
+        self.last_refresh = None;
+        self.clear_rects_if_idle();
+    }
+
+    /// Number of individually excluded windows
+    pub fn excluded_count(&self) -> usize {
+        self.excluded.len()
+    }
+
+    /// Stop excluding all individually picked windows
+    pub fn clear_excluded(&mut self) {
+        self.excluded.clear();
+        self.last_refresh = None;
+        self.clear_rects_if_idle();
+    }
+
+    /// Nothing to mask any more - drop the rectangles right away
+    /// (refresh() is skipped entirely while idle)
+    fn clear_rects_if_idle(&mut self) {
+        if self.is_idle() && self.is_active() {
+            self.rects.clear();
+            self.revision += 1;
+        }
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// True if at least one window is currently masked
+    pub fn is_active(&self) -> bool {
+        self.rects.iter().any(|rects| !rects.is_empty())
+    }
+
+    /// True if a window is masked in the capture region (see `rects`)
+    pub fn covers(&self, region: usize) -> bool {
+        self.rects
+            .get(region)
+            .is_some_and(|rects| !rects.is_empty())
+    }
+
+    /// Re-scan windows if the refresh interval has passed
+    /// `regions`: the main capture region first, then the additional ones
+    pub fn refresh(&mut self, regions: &[CaptureRect]) {
+        if self.is_idle() {
+            return;
+        }
+        let interval = Duration::from_millis(window_mask::REFRESH_INTERVAL_MS);
+        if self.last_refresh.is_some_and(|t| t.elapsed() < interval) {
+            return;
+        }
+        self.last_refresh = Some(Instant::now());
+
+        // Forget excluded windows that have been closed
+        self.excluded.retain(|&hwnd| window_exists(hwnd));
+        self.process_names.clear();
+
+        let own_pid = std::process::id();
+        let mut rects = vec![Vec::new(); regions.len()];
+        for window in visible_windows() {
+            if window.pid == own_pid {
+                continue;
+            }
+            if !self.excluded.contains(&window.hwnd) && !self.is_listed(window.pid) {
+                continue;
+            }
+            for (region, region_rects) in regions.iter().zip(rects.iter_mut()) {
+                if let Some(rect) = intersect(&window, *region) {
+                    region_rects.push(rect);
+                }
+            }
+        }
+
+        if rects != self.rects {
+            self.rects = rects;
+            self.revision += 1;
+        }
+
+        if std::mem::take(&mut self.check_apps) {
+            for app in &self.apps {
+                if !self.process_names.values().any(|name| name == app) {
+                    warn!(
+                        "Redacted application '{}' has no open window right now - \
+                         check the name in Task Manager → Details",
+                        app
+                    );
+                }
+            }
+        }
+    }
+
+    /// True when there is nothing to look for
+    fn is_idle(&self) -> bool {
+        self.apps.is_empty() && self.excluded.is_empty() && !self.hide_notifications
+    }
+
+    /// True if the process belongs to the redaction list (or shows notifications
+    /// while those are hidden)
+    fn is_listed(&mut self, pid: u32) -> bool {
+        let name = match self.process_names.entry(pid) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => match process_name(pid) {
+                Some(name) => entry.insert(name.to_lowercase()),
+                // Unknown processes could be listed ones (failures are not
+                // cached, the next scan asks again)
+                None => return !self.apps.is_empty(),
+            },
+        };
+        self.apps.iter().any(|a| a == name)
+            || (self.hide_notifications
+                && window_mask::NOTIFICATION_PROCESSES
+                    .iter()
+                    .any(|p| p.eq_ignore_ascii_case(name)))
+    }
+
+    /// Black out every masked window in the frame of a capture region
+    pub fn apply(&self, region: usize, canvas: &mut Canvas) {
+        for &(x, y, w, h) in self.rects.get(region).into_iter().flatten() {
+            drawing::fill_rect(canvas, x, y, w, h, window_mask::COLOR);
+        }
+    }
+}
+
+/// Turn a redaction list entry into a lowercased process file name
+/// Paths are reduced to the file name and ".exe" is added when there is no
+/// extension; None for empty entries
+fn normalize_app(entry: &str) -> Option<String> {
+    let trimmed = entry.trim().trim_matches('"');
+    let file = trimmed.rsplit(['\\', '/']).next().unwrap_or(trimmed);
+    if file.is_empty() {
+        return None;
+    }
+    let mut name = file.to_lowercase();
+    if !name.contains('.') {
+        name.push_str(".exe");
+    }
+    if name != entry.trim().to_lowercase() {
+        warn!("Redacted application '{}' read as '{}'", entry.trim(), name);
+    }
+    Some(name)
+}
+
+/// Intersect a window with the capture region, padded by MARGIN so a window
+/// that moves between two refreshes stays covered
+/// Returns the rectangle relative to the capture region
+fn intersect(window: &WindowInfo, region: CaptureRect) -> Option<(i32, i32, i32, i32)> {
+    let margin = window_mask::MARGIN;
+    let left = (window.left - margin).max(region.x);
+    let top = (window.top - margin).max(region.y);
+    let right = (window.right + margin).min(region.x + region.width as i32);
+    let bottom = (window.bottom + margin).min(region.y + region.height as i32);
+    if right <= left || bottom <= top {
+        return None;
+    }
+    Some((left - region.x, top - region.y, right - left, bottom - top))
+}
+
+/// Enumerate visible, non-minimized, non-cloaked top-level windows
+#[cfg(windows)]
+pub fn visible_windows() -> Vec<WindowInfo> {
+    use windows::core::BOOL;
+    use windows::Win32::Foundation::{HWND, LPARAM, RECT};
+    use windows::Win32::Graphics::Dwm::{
+        DwmGetWindowAttribute, DWMWA_CLOAKED, DWMWA_EXTENDED_FRAME_BOUNDS,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        EnumWindows, GetWindowThreadProcessId, IsIconic, IsWindowVisible,
+    };
+
+    unsafe extern "system" fn enum_proc(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let windows = &mut *(lparam.0 as *mut Vec<WindowInfo>);
+
+        if !IsWindowVisible(hwnd).as_bool() || IsIconic(hwnd).as_bool() {
+            return BOOL(1);
+        }
+
+        // UWP / suspended windows can be "visible" but cloaked (not drawn)
+        let mut cloaked: u32 = 0;
+        if DwmGetWindowAttribute(
+            hwnd,
+            DWMWA_CLOAKED,
+            &mut cloaked as *mut u32 as *mut std::ffi::c_void,
+            std::mem::size_of::<u32>() as u32,
+        )
+        .is_ok()
+            && cloaked != 0
+        {
+            return BOOL(1);
+        }
+
+        // Extended frame bounds exclude the invisible resize borders
+        let mut rect = RECT::default();
+        if DwmGetWindowAttribute(
+            hwnd,
+            DWMWA_EXTENDED_FRAME_BOUNDS,
+            &mut rect as *mut RECT as *mut std::ffi::c_void,
+            std::mem::size_of::<RECT>() as u32,
+        )
+        .is_err()
+        {
+            return BOOL(1);
+        }
+
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+
+        windows.push(WindowInfo {
+            hwnd: hwnd.0 as isize,
+            pid,
+            left: rect.left,
+            top: rect.top,
+            right: rect.right,
+            bottom: rect.bottom,
+        });
+        BOOL(1)
+    }
+
+    let mut windows: Vec<WindowInfo> = Vec::new();
+    unsafe {
+        let _ = EnumWindows(
+            Some(enum_proc),
+            LPARAM(&mut windows as *mut Vec<WindowInfo> as isize),
+        );
+    }
+    windows
+}
+
+#[cfg(not(windows))]
+pub fn visible_windows() -> Vec<WindowInfo> {
+    Vec::new()
+}
+
+/// Top-level window under the mouse cursor as (HWND, process ID)
+pub fn window_at_cursor() -> Option<(isize, u32)> {
+    let (x, y) = crate::utils::get_cursor_pos()?;
+    window_at(x, y)
+}
+
+/// Top-level window at a screen position as (HWND, process ID)
+#[cfg(windows)]
+pub fn window_at(x: i32, y: i32) -> Option<(isize, u32)> {
+    use windows::Win32::Foundation::POINT;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetAncestor, GetWindowThreadProcessId, WindowFromPoint, GA_ROOT,
+    };
+
+    unsafe {
+        let child = WindowFromPoint(POINT { x, y });
+        if child.is_invalid() {
+            return None;
+        }
+        let root = GetAncestor(child, GA_ROOT);
+        if root.is_invalid() {
+            return None;
+        }
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(root, Some(&mut pid));
+        Some((root.0 as isize, pid))
+    }
+}
+
+#[cfg(not(windows))]
+pub fn window_at(_x: i32, _y: i32) -> Option<(isize, u32)> {
+    None
+}
+
+/// Visible bounds of a window (without the invisible resize borders)
+#[cfg(windows)]
+pub fn window_rect(hwnd: isize) -> Option<CaptureRect> {
+    use windows::Win32::Foundation::{HWND, RECT};
+    use windows::Win32::Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_EXTENDED_FRAME_BOUNDS};
+
+    let mut rect = RECT::default();
+    unsafe {
+        DwmGetWindowAttribute(
+            HWND(hwnd as *mut std::ffi::c_void),
+            DWMWA_EXTENDED_FRAME_BOUNDS,
+            &mut rect as *mut RECT as *mut std::ffi::c_void,
+            std::mem::size_of::<RECT>() as u32,
+        )
+        .ok()?;
+    }
+    Some(CaptureRect {
+        x: rect.left,
+        y: rect.top,
+        width: (rect.right - rect.left).max(0) as u32,
+        height: (rect.bottom - rect.top).max(0) as u32,
+    })
+}
+
+#[cfg(not(windows))]
+pub fn window_rect(_hwnd: isize) -> Option<CaptureRect> {
+    None
+}
+
+/// True if the HWND still refers to an existing window
+#[cfg(windows)]
+pub fn window_exists(hwnd: isize) -> bool {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::IsWindow;
+
+    unsafe { IsWindow(Some(HWND(hwnd as *mut std::ffi::c_void))).as_bool() }
+}
+
+#[cfg(not(windows))]
+pub fn window_exists(_hwnd: isize) -> bool {
+    false
+}
+
+/// File name of a process image, e.g. "Slack.exe"
+#[cfg(windows)]
+pub fn process_name(pid: u32) -> Option<String> {
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buffer = [0u16; 1024];
+        let mut size = buffer.len() as u32;
+        let result = QueryFullProcessImageNameW(
+            handle,
+            PROCESS_NAME_WIN32,
+            PWSTR(buffer.as_mut_ptr()),
+            &mut size,
+        );
+        let _ = CloseHandle(handle);
+        result.ok()?;
+
+        let path = String::from_utf16_lossy(&buffer[..size as usize]);
+        path.rsplit('\\').next().map(|s| s.to_string())
+    }
+}
+
+#[cfg(not(windows))]
+pub fn process_name(_pid: u32) -> Option<String> {
+    None
+}
@@ -0,0 +1,113 @@
+// window_tracker.rs - Re-Acquiring a Picked Window After It Restarts
+//
+// A window captured with the window picker (desktop menu → Capture a
+// window..., rustframe://window) is remembered by its handle, process name
+// and title.
+// When the application crashes or is restarted, its new window has a new
+// handle - for long monitoring sessions of flaky applications the capture
+// should move on to it instead of staying on the place the old one was.
+//
+// HOW IT WORKS:
+// 1. While capturing, the handle is checked every CHECK_INTERVAL_MS (IsWindow)
+// 2. Once it is gone, the visible windows of a process with the same name are
+//    searched for a title matching the old one: the same title, or one that
+//    contains the application part of it (after the last " - ", so
+//    "notes.txt - Notepad" finds "Untitled - Notepad")
+// 3. The topmost match becomes the tracked window and the capture region
+//    moves to its bounds; until one appears the search repeats
+//
+// Picking another window or stopping the capture ends the tracking.
+
+use std::time::{Duration, Instant};
+
+use log::info;
+
+use crate::capture::CaptureRect;
+use crate::constants::window_tracker;
+use crate::list;
+use crate::window_mask;
+
+/// The picked window and how to recognize it again
+pub struct TrackedWindow {
+    hwnd: isize,
+    /// Lowercased process image name, e.g. "notepad.exe"
+    process: String,
+    title: String,
+    /// Lowercased part of the title that a new window has to contain
+    pattern: String,
+    last_check: Instant,
+    /// The window is gone and a new one is being searched for
+    lost: bool,
+}
+
+impl TrackedWindow {
+    /// Remember a picked window (None if it has no title to recognize it by)
+    pub fn new(hwnd: isize) -> Option<Self> {
+        let window = list::windows().into_iter().find(|w| w.hwnd == hwnd)?;
+        let pattern = title_pattern(&window.title);
+        info!(
+            "Tracking \"{}\" ({}) - it is found again if it restarts",
+            window.title, window.process
+        );
+        Some(Self {
+            hwnd,
+            process: window.process.to_lowercase(),
+            title: window.title,
+            pattern,
+            last_check: Instant::now(),
+            lost: false,
+        })
+    }
+
+    /// Call regularly while capturing - the bounds of the new window once the
+    /// tracked one was replaced
+    pub fn poll(&mut self) -> Option<CaptureRect> {
+        if self.last_check.elapsed() < Duration::from_millis(window_tracker::CHECK_INTERVAL_MS) {
+            return None;
+        }
+        self.last_check = Instant::now();
+        if window_mask::window_exists(self.hwnd) {
+            return None;
+        }
+        if !self.lost {
+            self.lost = true;
+            info!(
+                "Tracked window \"{}\" is gone - waiting for {} to open it again",
+                self.title, self.process
+            );
+        }
+
+        // STEP 1: Find a window of the same application (topmost first)
+        let window = list::windows().into_iter().find(|w| {
+            w.process.to_lowercase() == self.process
+                && (w.title == self.title || w.title.to_lowercase().contains(&self.pattern))
+        })?;
+
+        // STEP 2: Track it from now on
+        info!(
+            "Found \"{}\" again as \"{}\" ({:#x})",
+            self.title, window.title, window.hwnd
+        );
+        self.hwnd = window.hwnd;
+        self.title = window.title;
+        self.lost = false;
+        Some(CaptureRect {
+            x: window.x,
+            y: window.y,
+            width: window.width,
+            height: window.height,
+        })
+    }
+}
+
+/// The part of a title that stays when the document changes: what follows the
+/// last " - " (the application name), or the whole title
+fn title_pattern(title: &str) -> String {
+    title
+        .rsplit(" - ")
+        .next()
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .unwrap_or(title)
+        .to_lowercase()
+}